@@ -8,7 +8,10 @@ MODEL="${1:-base}"
 MODELS_DIR="${MINTUES_MODELS_DIR:-$HOME/.local/share/minutes/models}"
 
 # Available models
-MODELS=("tiny" "tiny.en" "base" "base.en" "small" "small.en" "medium" "medium.en" "large-v1" "large-v2" "large-v3")
+MODELS=("tiny" "tiny.en" "base" "base.en" "small" "small.en" "medium" "medium.en" "large-v1" "large-v2" "large-v3"
+        "tiny-q5_1" "tiny.en-q5_1" "base-q5_1" "base.en-q5_1" "small-q5_1" "small.en-q5_1"
+        "medium-q5_0" "medium.en-q5_0" "large-v3-q5_0"
+        "tiny-q8_0" "base-q8_0" "small-q8_0" "medium-q8_0")
 
 # Check if model is valid
 valid_model=false
@@ -6,22 +6,22 @@
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{SampleFormat, Stream, StreamConfig};
-use hound::{WavSpec, WavWriter};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use crate::config::Settings;
 
-use super::AudioCapture;
+use super::encoder::CaptureSink;
+use super::{is_wav_path, AudioCapture, AudioDevice, LevelMeter, TrackLevels};
 
 /// Audio capture using cpal (cross-platform)
 ///
 /// This is the fallback backend that works on all platforms but only
 /// captures microphone input (not system audio).
 pub struct CpalCapture {
-    /// WAV writer
-    writer: Arc<Mutex<Option<WavWriter<std::io::BufWriter<std::fs::File>>>>>,
+    /// WAV or streaming OGG writer
+    writer: Arc<Mutex<Option<CaptureSink>>>,
 
     /// Audio stream
     stream: Option<Stream>,
@@ -35,8 +35,20 @@ pub struct CpalCapture {
     /// Number of channels
     channels: u16,
 
+    /// Preferred input device name (empty = default)
+    device: String,
+
+    /// Encode to OGG Opus during capture instead of writing WAV
+    stream_to_ogg: bool,
+
+    /// OGG Opus bitrate when streaming
+    ogg_bitrate: u32,
+
     /// Current output path
     output_path: Option<PathBuf>,
+
+    /// Level warnings for the last finished recording
+    level_warnings: Vec<String>,
 }
 
 impl CpalCapture {
@@ -48,7 +60,11 @@ impl CpalCapture {
             recording: Arc::new(AtomicBool::new(false)),
             sample_rate: settings.audio.sample_rate,
             channels: settings.audio.channels,
+            device: settings.audio.device.trim().to_string(),
+            stream_to_ogg: settings.audio.stream_to_ogg,
+            ogg_bitrate: settings.audio.ogg_bitrate,
             output_path: None,
+            level_warnings: Vec::new(),
         })
     }
 }
@@ -60,26 +76,12 @@ impl AudioCapture for CpalCapture {
             std::fs::create_dir_all(parent)?;
         }
 
-        // Create WAV writer
-        let spec = WavSpec {
-            channels: self.channels,
-            sample_rate: self.sample_rate,
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
-
-        let writer = WavWriter::create(output_path, spec)
-            .with_context(|| format!("Failed to create WAV file: {}", output_path.display()))?;
-
-        *self.writer.lock().unwrap() = Some(writer);
         self.output_path = Some(output_path.to_path_buf());
+        self.level_warnings.clear();
 
         let host = cpal::default_host();
 
-        // Get default input device
-        let device = host
-            .default_input_device()
-            .context("No input device available")?;
+        let device = select_input_device(&host, &self.device)?;
 
         tracing::info!(
             "cpal: Using audio device: {}",
@@ -111,6 +113,15 @@ impl AudioCapture for CpalCapture {
         self.sample_rate = config.sample_rate().0;
         self.channels = config.channels();
 
+        // Create the output writer now that the stream format is known
+        let sink = CaptureSink::create(
+            output_path,
+            self.sample_rate,
+            self.channels,
+            self.stream_to_ogg.then_some(self.ogg_bitrate),
+        )?;
+        *self.writer.lock().unwrap() = Some(sink);
+
         // Set up recording state
         self.recording.store(true, Ordering::SeqCst);
 
@@ -145,13 +156,28 @@ impl AudioCapture for CpalCapture {
         // Drop the stream to stop recording
         self.stream.take();
 
-        // Finalize the WAV file
+        // Finalize the output file
+        let mut finalized = false;
         if let Ok(mut guard) = self.writer.lock() {
             if let Some(writer) = guard.take() {
-                writer.finalize().context("Failed to finalize WAV file")?;
+                writer.finalize()?;
+                finalized = true;
             }
         }
 
+        // Streamed OGG files are not decoded again just for the level check
+        let measured = self
+            .output_path
+            .as_deref()
+            .filter(|path| finalized && is_wav_path(path));
+        if let Some(path) = measured {
+            let levels = TrackLevels {
+                system: None,
+                mic: LevelMeter::measure_wav(path).ok(),
+            };
+            self.level_warnings = levels.warnings();
+        }
+
         tracing::info!("cpal: Audio recording stopped");
         Ok(())
     }
@@ -163,6 +189,18 @@ impl AudioCapture for CpalCapture {
     fn backend_name(&self) -> &'static str {
         "cpal"
     }
+
+    fn level_warnings(&self) -> Vec<String> {
+        self.level_warnings.clone()
+    }
+
+    fn output_extension(&self) -> &'static str {
+        if self.stream_to_ogg {
+            "ogg"
+        } else {
+            "wav"
+        }
+    }
 }
 
 impl Drop for CpalCapture {
@@ -171,6 +209,46 @@ impl Drop for CpalCapture {
     }
 }
 
+/// List cpal input devices on the default host
+pub(crate) fn list_input_devices() -> Vec<AudioDevice> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+
+    let Ok(devices) = host.input_devices() else {
+        return Vec::new();
+    };
+
+    devices
+        .filter_map(|device| device.name().ok())
+        .map(|name| AudioDevice {
+            backend: "cpal",
+            kind: "microphone",
+            id: name.clone(),
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect()
+}
+
+/// Pick the configured input device, or the host default when none is set
+pub(super) fn select_input_device(host: &cpal::Host, preferred: &str) -> Result<cpal::Device> {
+    if preferred.is_empty() {
+        return host
+            .default_input_device()
+            .context("No input device available");
+    }
+
+    host.input_devices()
+        .context("Failed to enumerate input devices")?
+        .find(|device| device.name().map(|n| n == preferred).unwrap_or(false))
+        .with_context(|| {
+            format!(
+                "Configured audio device '{}' not found. Run `minutes devices` to list available devices.",
+                preferred
+            )
+        })
+}
+
 /// Find a suitable audio configuration
 fn find_suitable_config(
     configs: cpal::SupportedInputConfigs,
@@ -210,7 +288,7 @@ fn find_suitable_config(
 fn build_stream<T>(
     device: &cpal::Device,
     config: &StreamConfig,
-    writer: Arc<Mutex<Option<WavWriter<std::io::BufWriter<std::fs::File>>>>>,
+    writer: Arc<Mutex<Option<CaptureSink>>>,
     recording: Arc<AtomicBool>,
 ) -> Result<Stream>
 where
@@ -228,11 +306,12 @@ where
 
             if let Ok(mut guard) = writer.lock() {
                 if let Some(ref mut writer) = *guard {
-                    for &sample in data {
-                        let sample_i16: i16 = cpal::Sample::from_sample(sample);
-                        if writer.write_sample(sample_i16).is_err() {
-                            break;
-                        }
+                    let samples: Vec<i16> = data
+                        .iter()
+                        .map(|&sample| cpal::Sample::from_sample(sample))
+                        .collect();
+                    if let Err(e) = writer.write_samples(&samples) {
+                        tracing::error!("Failed to write audio samples: {}", e);
                     }
                 }
             }
@@ -0,0 +1,191 @@
+//! Microphone noise suppression
+//!
+//! Frames are denoised with RNNoise through `nnnoiseless`, its pure-Rust
+//! port: a small recurrent network estimates per-band gains for each 10 ms
+//! frame of 48 kHz audio, removing fans, hum, keyboard clatter and room tone
+//! while keeping speech. Streams at other rates are resampled to 48 kHz for
+//! the network and back.
+
+use std::collections::VecDeque;
+
+use nnnoiseless::DenoiseState;
+
+use super::mixer::resample;
+
+/// Sample rate RNNoise works at
+const RNNOISE_RATE: u32 = 48000;
+/// Samples per RNNoise frame (10 ms at 48 kHz)
+const FRAME_LEN: usize = DenoiseState::FRAME_SIZE;
+/// RNNoise reads samples in the range of 16-bit PCM
+const PCM_SCALE: f32 = 32768.0;
+
+/// Streaming noise suppressor for mono f32 samples
+pub struct NoiseSuppressor {
+    state: Box<DenoiseState<'static>>,
+    sample_rate: u32,
+    /// Input at the stream rate waiting to be resampled
+    input: Vec<f32>,
+    /// 48 kHz samples waiting for a full frame
+    pending: VecDeque<f32>,
+    /// Denoised 48 kHz samples waiting to be resampled back
+    denoised: Vec<f32>,
+    /// Leading 48 kHz samples to drop: the first frame only fades in
+    skip: usize,
+    samples_in: usize,
+    samples_out: usize,
+}
+
+impl NoiseSuppressor {
+    /// Create a suppressor for audio at `sample_rate`
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            state: DenoiseState::new(),
+            sample_rate,
+            input: Vec::new(),
+            pending: VecDeque::new(),
+            denoised: Vec::new(),
+            skip: FRAME_LEN,
+            samples_in: 0,
+            samples_out: 0,
+        }
+    }
+
+    /// Denoise a complete buffer in one go
+    pub fn process_all(sample_rate: u32, samples: &[f32]) -> Vec<f32> {
+        let mut suppressor = Self::new(sample_rate);
+        let mut output = suppressor.process(samples);
+        output.extend(suppressor.flush());
+        output
+    }
+
+    /// Feed samples and return whatever denoised output is ready
+    ///
+    /// Output lags input by about two frames; call [`flush`](Self::flush)
+    /// at the end of the stream to collect the rest.
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.samples_in += samples.len();
+        self.input.extend_from_slice(samples);
+        let output = self.run(false);
+        self.samples_out += output.len();
+        output
+    }
+
+    /// Drain the remaining samples so output length matches input length
+    pub fn flush(&mut self) -> Vec<f32> {
+        let remaining = self.samples_in - self.samples_out;
+        // Silence pushes the last real samples through the network
+        let padding = (FRAME_LEN * 2) as u64 * self.sample_rate as u64 / RNNOISE_RATE as u64;
+        self.input
+            .extend(std::iter::repeat_n(0.0, padding as usize + 1));
+
+        let mut output = self.run(true);
+        output.resize(remaining, 0.0);
+        self.samples_out += output.len();
+        self.input.clear();
+        self.pending.clear();
+        self.denoised.clear();
+        output
+    }
+
+    /// Denoise the whole frames available and resample them back
+    ///
+    /// Resampling works on whole blocks of input so the rates divide evenly;
+    /// `last` takes what is left as well.
+    fn run(&mut self, last: bool) -> Vec<f32> {
+        let block = self.block_len();
+        let take = if last {
+            self.input.len()
+        } else {
+            self.input.len() - self.input.len() % block
+        };
+        if take > 0 {
+            let block: Vec<f32> = self.input.drain(..take).collect();
+            self.pending
+                .extend(resample(&block, self.sample_rate, RNNOISE_RATE));
+        }
+
+        let mut frame = [0.0f32; FRAME_LEN];
+        let mut output = [0.0f32; FRAME_LEN];
+        while self.pending.len() >= FRAME_LEN {
+            for (slot, sample) in frame.iter_mut().zip(self.pending.drain(..FRAME_LEN)) {
+                *slot = sample * PCM_SCALE;
+            }
+            self.state.process_frame(&mut output, &frame);
+
+            let skipped = self.skip.min(FRAME_LEN);
+            self.skip -= skipped;
+            self.denoised
+                .extend(output[skipped..].iter().map(|sample| sample / PCM_SCALE));
+        }
+
+        let back = self.block_len() * RNNOISE_RATE as usize / self.sample_rate as usize;
+        let take = if last {
+            self.denoised.len()
+        } else {
+            self.denoised.len() - self.denoised.len() % back
+        };
+        let denoised: Vec<f32> = self.denoised.drain(..take).collect();
+        resample(&denoised, RNNOISE_RATE, self.sample_rate)
+    }
+
+    /// Input samples spanning a whole number of samples at both rates
+    fn block_len(&self) -> usize {
+        (self.sample_rate / gcd(self.sample_rate, RNNOISE_RATE)) as usize
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic white noise in [-amplitude, amplitude]
+    fn noise(len: usize, amplitude: f32) -> Vec<f32> {
+        let mut state: u32 = 0x1234_5678;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn output_length_matches_input_when_streaming() {
+        for rate in [16000, 44100, 48000] {
+            let input = noise(rate as usize + 123, 0.1);
+            let mut suppressor = NoiseSuppressor::new(rate);
+
+            let mut output = Vec::new();
+            for chunk in input.chunks(777) {
+                output.extend(suppressor.process(chunk));
+            }
+            output.extend(suppressor.flush());
+
+            assert_eq!(output.len(), input.len(), "at {} Hz", rate);
+        }
+    }
+
+    #[test]
+    fn attenuates_steady_background_noise() {
+        let input = noise(16000 * 3, 0.05);
+        let output = NoiseSuppressor::process_all(16000, &input);
+
+        // Skip the first second while the network settles
+        let tail = 16000..input.len();
+        assert!(rms(&output[tail.clone()]) < rms(&input[tail]) * 0.5);
+    }
+}
@@ -0,0 +1,42 @@
+//! Audio device enumeration
+//!
+//! Lists capture devices from every compiled-in backend so users can pick
+//! explicit targets for `audio.device` and `audio.system_device`.
+
+use serde::Serialize;
+
+/// A capture device reported by one of the audio backends
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDevice {
+    /// Backend that reported the device (pipewire, jack, wasapi, coreaudio, cpal)
+    pub backend: &'static str,
+    /// Device role (system, microphone)
+    pub kind: &'static str,
+    /// Identifier accepted by the backend's device setting
+    pub id: String,
+    /// Human-readable device name
+    pub name: String,
+    /// Whether this is the current default device for its role
+    pub is_default: bool,
+}
+
+/// Enumerate capture devices across all available backends
+pub fn list_devices() -> Vec<AudioDevice> {
+    let mut devices = Vec::new();
+
+    #[cfg(feature = "pipewire")]
+    {
+        if super::pipewire_available() {
+            devices.extend(super::pipewire_capture::list_pipewire_devices());
+        }
+    }
+
+    #[cfg(feature = "jack")]
+    devices.extend(super::jack_capture::list_jack_ports());
+
+    #[cfg(any(windows, target_os = "macos"))]
+    devices.extend(super::loopback_capture::list_system_devices());
+
+    devices.extend(super::cpal_capture::list_input_devices());
+    devices
+}
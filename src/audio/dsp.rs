@@ -0,0 +1,62 @@
+//! Small signal-processing helpers shared by the audio and transcription code
+
+/// In-place iterative radix-2 FFT
+///
+/// Both slices must have the same power-of-two length.
+pub(crate) fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -std::f32::consts::TAU / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fft_puts_a_cosine_in_its_bins() {
+        let mut re: Vec<f32> = (0..64)
+            .map(|i| (std::f32::consts::TAU * 4.0 * i as f32 / 64.0).cos())
+            .collect();
+        let mut im = vec![0.0; 64];
+
+        fft(&mut re, &mut im);
+
+        for k in 0..64 {
+            let magnitude = (re[k] * re[k] + im[k] * im[k]).sqrt();
+            let expected = if k == 4 || k == 60 { 32.0 } else { 0.0 };
+            assert!((magnitude - expected).abs() < 1e-3, "bin {}", k);
+        }
+    }
+}
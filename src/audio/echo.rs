@@ -0,0 +1,152 @@
+//! Acoustic echo cancellation for the microphone
+//!
+//! When meeting audio plays through speakers, the microphone picks it up
+//! again and the mix contains every remote voice twice, slightly delayed.
+//! A normalized LMS adaptive filter learns the path from the system track to
+//! the microphone and subtracts its estimate of the echo. Adaptation pauses
+//! while the local speaker talks (Geigel double-talk detection), so speech
+//! is not cancelled along with the echo.
+
+/// Longest echo path the filter models, in seconds
+const TAIL_SECS: f32 = 0.2;
+/// NLMS step size, between 0 and 2; smaller converges slower but steadier
+const STEP_SIZE: f32 = 0.5;
+/// Microphone level, relative to the recent reference peak, that signals local speech
+const DOUBLE_TALK_RATIO: f32 = 0.5;
+/// How long adaptation stays paused after local speech is detected, in seconds
+const DOUBLE_TALK_HOLD_SECS: f32 = 0.1;
+/// Reference energy below which the filter does not adapt (near-silent system audio)
+const MIN_REFERENCE_ENERGY: f64 = 1e-6;
+
+/// Streaming echo canceller for mono f32 samples
+pub struct EchoCanceller {
+    /// Filter taps, newest reference sample first
+    weights: Vec<f32>,
+    /// Reference history stored twice so the newest `taps` samples are contiguous
+    history: Vec<f32>,
+    /// Index of the newest reference sample in `history`
+    position: usize,
+    /// Sum of squares of the reference samples in the filter window
+    energy: f64,
+    hold_len: usize,
+    /// Samples left before adaptation resumes after double talk
+    hold: usize,
+}
+
+impl EchoCanceller {
+    /// Create a canceller for audio at `sample_rate`
+    pub fn new(sample_rate: u32) -> Self {
+        let taps = ((sample_rate as f32 * TAIL_SECS) as usize).max(1);
+        Self {
+            weights: vec![0.0; taps],
+            history: vec![0.0; taps * 2],
+            position: 0,
+            energy: 0.0,
+            hold_len: (sample_rate as f32 * DOUBLE_TALK_HOLD_SECS) as usize,
+            hold: 0,
+        }
+    }
+
+    /// Remove echo of `reference` (system audio) from `mic`
+    ///
+    /// Both slices must start at the same instant. Returns one sample per
+    /// microphone sample; a missing reference counts as silence. Consecutive
+    /// calls continue the same filter, so streams can be processed in chunks.
+    pub fn process(&mut self, reference: &[f32], mic: &[f32]) -> Vec<f32> {
+        mic.iter()
+            .enumerate()
+            .map(|(i, &sample)| {
+                self.push_reference(reference.get(i).copied().unwrap_or(0.0));
+                self.cancel(sample)
+            })
+            .collect()
+    }
+
+    fn push_reference(&mut self, sample: f32) {
+        let taps = self.weights.len();
+        self.position = (self.position + taps - 1) % taps;
+
+        let oldest = self.history[self.position];
+        self.energy = (self.energy + (sample as f64).powi(2) - (oldest as f64).powi(2)).max(0.0);
+        self.history[self.position] = sample;
+        self.history[self.position + taps] = sample;
+    }
+
+    fn cancel(&mut self, sample: f32) -> f32 {
+        let window = &self.history[self.position..self.position + self.weights.len()];
+        let (estimate, peak) = window
+            .iter()
+            .zip(&self.weights)
+            .fold((0.0f32, 0.0f32), |(estimate, peak), (&x, &w)| {
+                (estimate + x * w, peak.max(x.abs()))
+            });
+        let error = sample - estimate;
+
+        if sample.abs() > DOUBLE_TALK_RATIO * peak {
+            self.hold = self.hold_len;
+        }
+
+        if self.hold > 0 {
+            self.hold -= 1;
+        } else if self.energy > MIN_REFERENCE_ENERGY {
+            let step = (STEP_SIZE as f64 / self.energy) as f32 * error;
+            for (weight, &x) in self.weights.iter_mut().zip(window) {
+                *weight += step * x;
+            }
+        }
+
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic white noise in -0.5..0.5
+    fn noise(len: usize, mut seed: u32) -> Vec<f32> {
+        (0..len)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (seed >> 8) as f32 / (1u32 << 24) as f32 - 0.5
+            })
+            .collect()
+    }
+
+    fn energy(samples: &[f32]) -> f32 {
+        samples.iter().map(|s| s * s).sum()
+    }
+
+    /// Reference delayed by `delay` samples and attenuated, as a speaker echo
+    fn echo_of(reference: &[f32], delay: usize, gain: f32) -> Vec<f32> {
+        (0..reference.len())
+            .map(|i| i.checked_sub(delay).map_or(0.0, |j| reference[j] * gain))
+            .collect()
+    }
+
+    #[test]
+    fn removes_a_delayed_echo_of_the_reference() {
+        let rate = 8000;
+        let reference = noise(rate as usize * 2, 7);
+        let mic = echo_of(&reference, 120, 0.4);
+
+        let mut canceller = EchoCanceller::new(rate);
+        let mut output = Vec::new();
+        for (reference, mic) in reference.chunks(480).zip(mic.chunks(480)) {
+            output.extend(canceller.process(reference, mic));
+        }
+
+        // After a second of adaptation the echo is down by more than 20 dB
+        let tail = rate as usize..;
+        assert_eq!(output.len(), mic.len());
+        assert!(energy(&output[tail.clone()]) < energy(&mic[tail]) * 0.01);
+    }
+
+    #[test]
+    fn keeps_local_speech_without_system_audio() {
+        let mic = noise(4000, 3);
+        let mut canceller = EchoCanceller::new(8000);
+
+        assert_eq!(canceller.process(&[], &mic), mic);
+    }
+}
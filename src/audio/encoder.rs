@@ -1,13 +1,20 @@
-//! OGG Opus audio encoder
+//! OGG Opus and FLAC audio encoders
 //!
 //! Compresses WAV files to OGG Opus format for efficient storage.
 //! Speech audio compresses from ~115MB/hour (WAV) to ~7MB/hour (OGG Opus).
+//! FLAC is offered for lossless archival at roughly half the WAV size.
 
 use anyhow::{Context, Result};
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
+/// Sample rates accepted by the Opus codec
+const OPUS_SAMPLE_RATES: [u32; 5] = [8000, 12000, 16000, 24000, 48000];
+
+/// OGG Opus granule positions are always counted at 48kHz
+const OPUS_GRANULE_RATE: u64 = 48000;
+
 /// OGG Opus encoder for compressing audio files
 #[allow(dead_code)]
 pub struct OggEncoder {
@@ -53,59 +60,162 @@ impl OggEncoder {
     /// # Returns
     /// Ok(()) on success, error on failure
     pub fn encode(&self, wav_path: &Path, ogg_path: &Path) -> Result<()> {
-        use hound::WavReader;
-
         tracing::info!(
             "Encoding {} to OGG Opus ({}kbps)",
             wav_path.display(),
             self.bitrate / 1000
         );
 
-        // Read WAV file
-        let reader = WavReader::open(wav_path)
-            .with_context(|| format!("Failed to open WAV file: {}", wav_path.display()))?;
+        let (spec, samples) = read_wav_i16(wav_path)?;
+
+        // Encode through the same writer used for streaming capture
+        let mut writer =
+            StreamingOggWriter::create(ogg_path, spec.sample_rate, spec.channels, self.bitrate)?;
+        writer.write_samples(&samples)?;
+        writer.finalize()?;
+
+        let wav_size = std::fs::metadata(wav_path)?.len();
+        let ogg_size = std::fs::metadata(ogg_path)?.len();
+        let ratio = wav_size as f64 / ogg_size as f64;
 
-        let spec = reader.spec();
-        tracing::debug!(
-            "WAV spec: {} Hz, {} channels, {} bits",
-            spec.sample_rate,
-            spec.channels,
-            spec.bits_per_sample
+        tracing::info!(
+            "Encoded to OGG: {} -> {} ({:.1}x compression)",
+            format_size(wav_size),
+            format_size(ogg_size),
+            ratio
         );
 
-        // Collect samples
-        let samples: Vec<i16> = match spec.sample_format {
-            hound::SampleFormat::Int => {
-                if spec.bits_per_sample == 16 {
-                    reader
-                        .into_samples::<i16>()
-                        .filter_map(Result::ok)
-                        .collect()
-                } else if spec.bits_per_sample == 32 {
-                    reader
-                        .into_samples::<i32>()
-                        .filter_map(Result::ok)
-                        .map(|s| (s >> 16) as i16)
-                        .collect()
-                } else {
-                    anyhow::bail!("Unsupported bit depth: {}", spec.bits_per_sample);
-                }
+        Ok(())
+    }
+
+    /// Encode WAV to OGG and delete the original WAV file
+    ///
+    /// # Arguments
+    /// * `wav_path` - Path to input WAV file
+    ///
+    /// # Returns
+    /// Path to the created OGG file
+    pub fn encode_and_cleanup(&self, wav_path: &Path) -> Result<PathBuf> {
+        let ogg_path = wav_path.with_extension("ogg");
+
+        // Encode
+        self.encode(wav_path, &ogg_path)?;
+
+        replace_wav(wav_path, &ogg_path)?;
+
+        Ok(ogg_path)
+    }
+}
+
+/// Read a WAV file as interleaved 16-bit samples
+fn read_wav_i16(wav_path: &Path) -> Result<(hound::WavSpec, Vec<i16>)> {
+    use hound::WavReader;
+
+    let reader = WavReader::open(wav_path)
+        .with_context(|| format!("Failed to open WAV file: {}", wav_path.display()))?;
+
+    let spec = reader.spec();
+    tracing::debug!(
+        "WAV spec: {} Hz, {} channels, {} bits",
+        spec.sample_rate,
+        spec.channels,
+        spec.bits_per_sample
+    );
+
+    // Collect samples
+    let samples: Vec<i16> = match spec.sample_format {
+        hound::SampleFormat::Int => {
+            if spec.bits_per_sample == 16 {
+                reader
+                    .into_samples::<i16>()
+                    .filter_map(Result::ok)
+                    .collect()
+            } else if spec.bits_per_sample == 32 {
+                reader
+                    .into_samples::<i32>()
+                    .filter_map(Result::ok)
+                    .map(|s| (s >> 16) as i16)
+                    .collect()
+            } else {
+                anyhow::bail!("Unsupported bit depth: {}", spec.bits_per_sample);
             }
-            hound::SampleFormat::Float => reader
-                .into_samples::<f32>()
-                .filter_map(Result::ok)
-                .map(|s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
-                .collect(),
-        };
+        }
+        hound::SampleFormat::Float => reader
+            .into_samples::<f32>()
+            .filter_map(Result::ok)
+            .map(|s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
+            .collect(),
+    };
+
+    if samples.is_empty() {
+        anyhow::bail!("WAV file contains no samples");
+    }
+
+    Ok((spec, samples))
+}
+
+/// Verify an encoded file exists with content, then delete the source WAV
+fn replace_wav(wav_path: &Path, encoded_path: &Path) -> Result<()> {
+    let meta = std::fs::metadata(encoded_path).with_context(|| {
+        format!(
+            "Encoded file not found after encoding: {}",
+            encoded_path.display()
+        )
+    })?;
+
+    if meta.len() == 0 {
+        anyhow::bail!("Encoded file is empty: {}", encoded_path.display());
+    }
+
+    std::fs::remove_file(wav_path)
+        .with_context(|| format!("Failed to delete WAV file: {}", wav_path.display()))?;
+
+    tracing::info!("Deleted original WAV file: {}", wav_path.display());
+    Ok(())
+}
+
+impl Default for OggEncoder {
+    fn default() -> Self {
+        Self::for_speech()
+    }
+}
+
+/// Incremental OGG Opus writer
+///
+/// Accepts interleaved 16-bit PCM in arbitrary chunk sizes and writes each
+/// 20ms Opus frame to disk as soon as it is complete, so a recording never
+/// needs to exist as an uncompressed WAV.
+pub struct StreamingOggWriter {
+    file: BufWriter<File>,
+    encoder: opus::Encoder,
+    serial: u32,
+    page_no: u64,
+    granule_pos: u64,
+    /// Samples per channel in one 20ms frame
+    frame_size: usize,
+    channels: usize,
+    /// Granule increment per frame (in 48kHz units)
+    granule_step: u64,
+    /// Samples waiting for a full frame
+    pending: Vec<i16>,
+    /// Last encoded packet, held back so the final page can carry end-of-stream
+    held: Option<(Vec<u8>, u64)>,
+    encoded_buf: Vec<u8>,
+}
 
-        if samples.is_empty() {
-            anyhow::bail!("WAV file contains no samples");
+impl StreamingOggWriter {
+    /// Create the OGG file and write the Opus headers
+    pub fn create(path: &Path, sample_rate: u32, channels: u16, bitrate: u32) -> Result<Self> {
+        if !OPUS_SAMPLE_RATES.contains(&sample_rate) {
+            anyhow::bail!(
+                "Opus does not support {} Hz audio (supported: 8000, 12000, 16000, 24000, 48000)",
+                sample_rate
+            );
         }
 
-        // Create Opus encoder
         let mut encoder = opus::Encoder::new(
-            spec.sample_rate,
-            match spec.channels {
+            sample_rate,
+            match channels {
                 1 => opus::Channels::Mono,
                 2 => opus::Channels::Stereo,
                 n => anyhow::bail!("Unsupported channel count: {}", n),
@@ -115,126 +225,631 @@ impl OggEncoder {
         .context("Failed to create Opus encoder")?;
 
         encoder
-            .set_bitrate(opus::Bitrate::Bits(self.bitrate as i32))
+            .set_bitrate(opus::Bitrate::Bits(bitrate as i32))
             .context("Failed to set bitrate")?;
 
-        // Create OGG stream
-        let mut ogg_file = BufWriter::new(
-            File::create(ogg_path)
-                .with_context(|| format!("Failed to create OGG file: {}", ogg_path.display()))?,
+        let mut file = BufWriter::new(
+            File::create(path)
+                .with_context(|| format!("Failed to create OGG file: {}", path.display()))?,
         );
 
-        // Write Opus header and encode
         let serial = rand_serial();
-        let mut packet_no = 0u64;
-        let mut granule_pos = 0u64;
-
-        // Write Opus ID header
-        let id_header = create_opus_id_header(spec.channels as u8, spec.sample_rate);
-        write_ogg_page(&mut ogg_file, serial, 0, 2, packet_no, &id_header)?;
-        packet_no += 1;
 
-        // Write Opus comment header
+        // Write Opus ID and comment headers
+        let id_header = create_opus_id_header(channels as u8, sample_rate);
+        write_ogg_page(&mut file, serial, 0, 2, 0, &id_header)?;
         let comment_header = create_opus_comment_header();
-        write_ogg_page(&mut ogg_file, serial, 0, 0, packet_no, &comment_header)?;
-        packet_no += 1;
+        write_ogg_page(&mut file, serial, 0, 0, 1, &comment_header)?;
 
-        // Encode audio in frames
         // Opus typically uses 20ms frames = sample_rate * 0.02
-        let frame_size = (spec.sample_rate as usize) / 50; // 20ms
-        let channels = spec.channels as usize;
-        let samples_per_frame = frame_size * channels;
+        let frame_size = (sample_rate as usize) / 50;
+
+        Ok(Self {
+            file,
+            encoder,
+            serial,
+            page_no: 2,
+            granule_pos: 0,
+            frame_size,
+            channels: channels as usize,
+            granule_step: OPUS_GRANULE_RATE / 50,
+            pending: Vec::with_capacity(frame_size * channels as usize),
+            held: None,
+            encoded_buf: vec![0u8; 4000], // Max Opus packet size
+        })
+    }
+
+    /// Append interleaved samples, encoding every complete frame
+    pub fn write_samples(&mut self, samples: &[i16]) -> Result<()> {
+        let samples_per_frame = self.frame_size * self.channels;
+
+        for &sample in samples {
+            self.pending.push(sample);
+            if self.pending.len() == samples_per_frame {
+                let frame = std::mem::take(&mut self.pending);
+                self.encode_frame(&frame)?;
+                self.pending = frame;
+                self.pending.clear();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encode any remaining samples, mark end of stream, and flush to disk
+    pub fn finalize(mut self) -> Result<()> {
+        if !self.pending.is_empty() || self.held.is_none() {
+            let mut frame = std::mem::take(&mut self.pending);
+            frame.resize(self.frame_size * self.channels, 0);
+            self.encode_frame(&frame)?;
+        }
+
+        if let Some((packet, granule_pos)) = self.held.take() {
+            write_ogg_page(
+                &mut self.file,
+                self.serial,
+                granule_pos,
+                4,
+                self.page_no,
+                &packet,
+            )?;
+        }
+
+        self.file.flush()?;
+        Ok(())
+    }
+
+    fn encode_frame(&mut self, frame: &[i16]) -> Result<()> {
+        let encoded_len = self
+            .encoder
+            .encode(frame, &mut self.encoded_buf)
+            .context("Opus encoding failed")?;
+
+        if encoded_len == 0 {
+            return Ok(());
+        }
 
-        let mut encoded_buf = vec![0u8; 4000]; // Max Opus packet size
+        if let Some((packet, granule_pos)) = self.held.take() {
+            write_ogg_page(
+                &mut self.file,
+                self.serial,
+                granule_pos,
+                0,
+                self.page_no,
+                &packet,
+            )?;
+            self.page_no += 1;
+        }
+
+        self.granule_pos += self.granule_step;
+        self.held = Some((self.encoded_buf[..encoded_len].to_vec(), self.granule_pos));
+        Ok(())
+    }
+}
+
+/// Destination for captured PCM samples
+pub(crate) enum CaptureSink {
+    Wav(hound::WavWriter<BufWriter<File>>),
+    Ogg(StreamingOggWriter),
+}
+
+impl CaptureSink {
+    /// Create a 16-bit WAV file, or a streaming OGG Opus file when `ogg_bitrate` is set
+    pub(crate) fn create(
+        path: &Path,
+        sample_rate: u32,
+        channels: u16,
+        ogg_bitrate: Option<u32>,
+    ) -> Result<Self> {
+        if let Some(bitrate) = ogg_bitrate {
+            return Ok(CaptureSink::Ogg(StreamingOggWriter::create(
+                path,
+                sample_rate,
+                channels,
+                bitrate,
+            )?));
+        }
+
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer = hound::WavWriter::create(path, spec)
+            .with_context(|| format!("Failed to create WAV file: {}", path.display()))?;
+        Ok(CaptureSink::Wav(writer))
+    }
 
-        for chunk in samples.chunks(samples_per_frame) {
-            // Pad last chunk if needed
-            let mut frame = chunk.to_vec();
-            if frame.len() < samples_per_frame {
-                frame.resize(samples_per_frame, 0);
+    pub(crate) fn write_samples(&mut self, samples: &[i16]) -> Result<()> {
+        match self {
+            CaptureSink::Wav(writer) => {
+                for &sample in samples {
+                    writer.write_sample(sample)?;
+                }
+                Ok(())
             }
+            CaptureSink::Ogg(writer) => writer.write_samples(samples),
+        }
+    }
+
+    pub(crate) fn finalize(self) -> Result<()> {
+        match self {
+            CaptureSink::Wav(writer) => writer.finalize().context("Failed to finalize WAV file"),
+            CaptureSink::Ogg(writer) => writer.finalize().context("Failed to finalize OGG file"),
+        }
+    }
+}
+
+/// Decode an OGG Opus file to interleaved 16-bit PCM
+///
+/// Returns `(sample_rate, channels, samples)`. Audio is decoded at the input
+/// sample rate recorded in the Opus header when Opus supports it, otherwise
+/// at 48kHz.
+pub fn decode_ogg_opus(path: &Path) -> Result<(u32, u16, Vec<i16>)> {
+    let mut data = Vec::new();
+    File::open(path)
+        .with_context(|| format!("Failed to open OGG file: {}", path.display()))?
+        .read_to_end(&mut data)?;
+
+    let packets =
+        read_ogg_packets(&data).with_context(|| format!("Invalid OGG file: {}", path.display()))?;
+    let mut packets = packets.into_iter();
+
+    let head = packets.next().context("OGG file has no Opus header")?;
+    if head.len() < 19 || &head[..8] != b"OpusHead" {
+        anyhow::bail!("{} is not an OGG Opus file", path.display());
+    }
+    let channels = head[9] as u16;
+    let pre_skip = u16::from_le_bytes([head[10], head[11]]) as u64;
+    let input_rate = u32::from_le_bytes([head[12], head[13], head[14], head[15]]);
+    let sample_rate = if OPUS_SAMPLE_RATES.contains(&input_rate) {
+        input_rate
+    } else {
+        OPUS_GRANULE_RATE as u32
+    };
+
+    // Skip the comment header
+    packets.next();
+
+    let mut decoder = opus::Decoder::new(
+        sample_rate,
+        match channels {
+            1 => opus::Channels::Mono,
+            2 => opus::Channels::Stereo,
+            n => anyhow::bail!("Unsupported channel count: {}", n),
+        },
+    )
+    .context("Failed to create Opus decoder")?;
+
+    // 120ms is the longest Opus frame
+    let mut frame = vec![0i16; (sample_rate as usize / 1000) * 120 * channels as usize];
+    let mut samples = Vec::new();
+    for packet in packets {
+        let decoded = decoder
+            .decode(&packet, &mut frame, false)
+            .context("Opus decoding failed")?;
+        samples.extend_from_slice(&frame[..decoded * channels as usize]);
+    }
+
+    let skip = (pre_skip * sample_rate as u64 / OPUS_GRANULE_RATE) as usize * channels as usize;
+    samples.drain(..skip.min(samples.len()));
 
-            // Encode frame
-            let encoded_len = encoder
-                .encode(&frame, &mut encoded_buf)
-                .context("Opus encoding failed")?;
-
-            if encoded_len > 0 {
-                granule_pos += frame_size as u64;
-
-                // Determine if this is the last page
-                let header_type = if chunk.len() < samples_per_frame {
-                    4
-                } else {
-                    0
-                };
-
-                write_ogg_page(
-                    &mut ogg_file,
-                    serial,
-                    granule_pos,
-                    header_type,
-                    packet_no,
-                    &encoded_buf[..encoded_len],
-                )?;
-                packet_no += 1;
+    Ok((sample_rate, channels, samples))
+}
+
+/// Split an OGG bitstream into packets, following segment lacing across pages
+fn read_ogg_packets(data: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut packets = Vec::new();
+    let mut current = Vec::new();
+    let mut pos = 0;
+
+    while pos < data.len() {
+        if data.len() < pos + 27 || &data[pos..pos + 4] != b"OggS" {
+            anyhow::bail!("Missing OGG page header at byte {}", pos);
+        }
+
+        let segment_count = data[pos + 26] as usize;
+        let table_start = pos + 27;
+        let body_start = table_start + segment_count;
+        if data.len() < body_start {
+            anyhow::bail!("Truncated OGG page at byte {}", pos);
+        }
+
+        let mut offset = body_start;
+        for &lacing in &data[table_start..body_start] {
+            let end = offset + lacing as usize;
+            if data.len() < end {
+                anyhow::bail!("Truncated OGG page at byte {}", pos);
+            }
+            current.extend_from_slice(&data[offset..end]);
+            offset = end;
+            if lacing < 255 {
+                packets.push(std::mem::take(&mut current));
             }
         }
 
-        ogg_file.flush()?;
+        pos = offset;
+    }
+
+    Ok(packets)
+}
+
+/// FLAC encoder for lossless archival
+///
+/// A compact encoder using fixed linear predictors (orders 0-4) with Rice
+/// coded residuals, the same approach as `flac -0`. Speech typically lands
+/// at around half the size of the source WAV.
+pub struct FlacEncoder;
+
+impl FlacEncoder {
+    /// Encode a WAV file to FLAC
+    pub fn encode(&self, wav_path: &Path, flac_path: &Path) -> Result<()> {
+        tracing::info!("Encoding {} to FLAC", wav_path.display());
+
+        let (spec, samples) = read_wav_i16(wav_path)?;
+
+        let mut writer = FlacWriter::create(flac_path, spec.sample_rate, spec.channels)?;
+        writer.write_samples(&samples)?;
+        writer.finalize()?;
 
         let wav_size = std::fs::metadata(wav_path)?.len();
-        let ogg_size = std::fs::metadata(ogg_path)?.len();
-        let ratio = wav_size as f64 / ogg_size as f64;
+        let flac_size = std::fs::metadata(flac_path)?.len();
+        let ratio = wav_size as f64 / flac_size as f64;
 
         tracing::info!(
-            "Encoded to OGG: {} -> {} ({:.1}x compression)",
+            "Encoded to FLAC: {} -> {} ({:.1}x compression)",
             format_size(wav_size),
-            format_size(ogg_size),
+            format_size(flac_size),
             ratio
         );
 
         Ok(())
     }
 
-    /// Encode WAV to OGG and delete the original WAV file
-    ///
-    /// # Arguments
-    /// * `wav_path` - Path to input WAV file
-    ///
-    /// # Returns
-    /// Path to the created OGG file
+    /// Encode WAV to FLAC and delete the original WAV file
     pub fn encode_and_cleanup(&self, wav_path: &Path) -> Result<PathBuf> {
-        let ogg_path = wav_path.with_extension("ogg");
+        let flac_path = wav_path.with_extension("flac");
 
-        // Encode
-        self.encode(wav_path, &ogg_path)?;
+        self.encode(wav_path, &flac_path)?;
+        replace_wav(wav_path, &flac_path)?;
+
+        Ok(flac_path)
+    }
+}
+
+/// Samples per channel in each FLAC frame
+const FLAC_BLOCK_SIZE: usize = 4096;
+
+/// Largest Rice parameter before the escape code
+const FLAC_MAX_RICE_PARAM: u32 = 14;
 
-        // Verify OGG file exists and has content
-        let ogg_meta = std::fs::metadata(&ogg_path).with_context(|| {
-            format!("OGG file not found after encoding: {}", ogg_path.display())
-        })?;
+/// Incremental 16-bit FLAC writer
+pub struct FlacWriter {
+    file: BufWriter<File>,
+    sample_rate: u32,
+    channels: usize,
+    frame_no: u64,
+    total_samples: u64,
+    /// Interleaved samples waiting for a full block
+    pending: Vec<i16>,
+}
 
-        if ogg_meta.len() == 0 {
-            anyhow::bail!("OGG file is empty after encoding");
+impl FlacWriter {
+    /// Create the FLAC file and write the stream header
+    pub fn create(path: &Path, sample_rate: u32, channels: u16) -> Result<Self> {
+        if !(1..=8).contains(&channels) {
+            anyhow::bail!("Unsupported channel count: {}", channels);
+        }
+        if sample_rate == 0 || sample_rate >= 1 << 20 {
+            anyhow::bail!("Unsupported sample rate for FLAC: {}", sample_rate);
         }
 
-        // Delete original WAV
-        std::fs::remove_file(wav_path)
-            .with_context(|| format!("Failed to delete WAV file: {}", wav_path.display()))?;
+        let mut file = BufWriter::new(
+            File::create(path)
+                .with_context(|| format!("Failed to create FLAC file: {}", path.display()))?,
+        );
 
-        tracing::info!("Deleted original WAV file: {}", wav_path.display());
+        file.write_all(b"fLaC")?;
+        file.write_all(&create_flac_streaminfo(sample_rate, channels, 0))?;
 
-        Ok(ogg_path)
+        Ok(Self {
+            file,
+            sample_rate,
+            channels: channels as usize,
+            frame_no: 0,
+            total_samples: 0,
+            pending: Vec::with_capacity(FLAC_BLOCK_SIZE * channels as usize),
+        })
+    }
+
+    /// Append interleaved samples, encoding every complete block
+    pub fn write_samples(&mut self, samples: &[i16]) -> Result<()> {
+        let block_len = FLAC_BLOCK_SIZE * self.channels;
+
+        for &sample in samples {
+            self.pending.push(sample);
+            if self.pending.len() == block_len {
+                let block = std::mem::take(&mut self.pending);
+                self.write_frame(&block)?;
+                self.pending = block;
+                self.pending.clear();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encode the final partial block and record the total sample count
+    pub fn finalize(mut self) -> Result<()> {
+        if !self.pending.is_empty() {
+            let block = std::mem::take(&mut self.pending);
+            self.write_frame(&block)?;
+        }
+
+        self.file.flush()?;
+        let mut file = self.file.into_inner().map_err(|e| e.into_error())?;
+
+        // Patch the total sample count into STREAMINFO now that it is known
+        let streaminfo =
+            create_flac_streaminfo(self.sample_rate, self.channels as u16, self.total_samples);
+        file.seek(SeekFrom::Start(4))?;
+        file.write_all(&streaminfo)?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    fn write_frame(&mut self, interleaved: &[i16]) -> Result<()> {
+        let block_size = interleaved.len() / self.channels;
+        let mut bits = BitWriter::default();
+
+        // Frame header: sync code, fixed blocking, block size and sample rate
+        // taken from the end of the header / STREAMINFO
+        bits.write(0b11_1111_1111_1110, 14);
+        bits.write(0, 1); // reserved
+        bits.write(0, 1); // fixed block size
+        bits.write(0b0111, 4); // 16-bit (block size - 1) follows
+        bits.write(0b0000, 4); // sample rate from STREAMINFO
+        bits.write(self.channels as u64 - 1, 4); // independent channels
+        bits.write(0b100, 3); // 16 bits per sample
+        bits.write(0, 1); // reserved
+        write_utf8_number(&mut bits, self.frame_no);
+        bits.write(block_size as u64 - 1, 16);
+        let crc8 = flac_crc8(bits.bytes());
+        bits.write(u64::from(crc8), 8);
+
+        for channel in 0..self.channels {
+            let samples: Vec<i32> = interleaved
+                .iter()
+                .skip(channel)
+                .step_by(self.channels)
+                .map(|&s| i32::from(s))
+                .collect();
+            write_subframe(&mut bits, &samples);
+        }
+
+        bits.align();
+        let crc16 = flac_crc16(bits.bytes());
+        bits.write(u64::from(crc16), 16);
+
+        self.file.write_all(bits.bytes())?;
+        self.frame_no += 1;
+        self.total_samples += block_size as u64;
+        Ok(())
     }
 }
 
-impl Default for OggEncoder {
-    fn default() -> Self {
-        Self::for_speech()
+/// Build the STREAMINFO metadata block (marked as the last metadata block)
+fn create_flac_streaminfo(sample_rate: u32, channels: u16, total_samples: u64) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+
+    bits.write(1, 1); // last metadata block
+    bits.write(0, 7); // STREAMINFO
+    bits.write(34, 24); // block length
+    bits.write(FLAC_BLOCK_SIZE as u64, 16); // min block size
+    bits.write(FLAC_BLOCK_SIZE as u64, 16); // max block size
+    bits.write(0, 24); // min frame size (unknown)
+    bits.write(0, 24); // max frame size (unknown)
+    bits.write(u64::from(sample_rate), 20);
+    bits.write(u64::from(channels) - 1, 3);
+    bits.write(15, 5); // bits per sample - 1
+    bits.write(total_samples, 36);
+    for _ in 0..16 {
+        bits.write(0, 8); // MD5 signature (not computed)
+    }
+
+    bits.into_bytes()
+}
+
+/// Write the subframe for one channel, picking the cheapest encoding
+fn write_subframe(bits: &mut BitWriter, samples: &[i32]) {
+    if samples.iter().all(|&s| s == samples[0]) {
+        bits.write(0b0000_0000, 8); // CONSTANT
+        bits.write_signed(samples[0], 16);
+        return;
+    }
+
+    let verbatim_bits = samples.len() as u64 * 16;
+    let best = (0..=4usize)
+        .filter(|&order| order < samples.len())
+        .map(|order| {
+            let residual = fixed_residual(samples, order);
+            let param = rice_parameter(&residual);
+            let cost = order as u64 * 16 + 10 + rice_cost(&residual, param);
+            (order, residual, param, cost)
+        })
+        .min_by_key(|(_, _, _, cost)| *cost);
+
+    match best {
+        Some((order, residual, param, cost)) if cost < verbatim_bits => {
+            bits.write(0b0001_0000 | ((order as u64) << 1), 8); // FIXED, order
+            for &warmup in &samples[..order] {
+                bits.write_signed(warmup, 16);
+            }
+            bits.write(0b00, 2); // Rice coding, 4-bit parameter
+            bits.write(0, 4); // partition order 0
+            bits.write(u64::from(param), 4);
+            for &value in &residual {
+                bits.write_rice(value, param);
+            }
+        }
+        _ => {
+            bits.write(0b0000_0010, 8); // VERBATIM
+            for &sample in samples {
+                bits.write_signed(sample, 16);
+            }
+        }
+    }
+}
+
+/// Residual of the fixed polynomial predictor of the given order
+fn fixed_residual(samples: &[i32], order: usize) -> Vec<i32> {
+    (order..samples.len())
+        .map(|i| {
+            let s = |k: usize| samples[i - k];
+            match order {
+                0 => s(0),
+                1 => s(0) - s(1),
+                2 => s(0) - 2 * s(1) + s(2),
+                3 => s(0) - 3 * s(1) + 3 * s(2) - s(3),
+                _ => s(0) - 4 * s(1) + 6 * s(2) - 4 * s(3) + s(4),
+            }
+        })
+        .collect()
+}
+
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+/// Estimate the Rice parameter from the mean zigzagged residual
+fn rice_parameter(residual: &[i32]) -> u32 {
+    if residual.is_empty() {
+        return 0;
+    }
+
+    let sum: u64 = residual.iter().map(|&r| u64::from(zigzag(r))).sum();
+    let mean = sum / residual.len() as u64;
+    let mut param = 0;
+    while param < FLAC_MAX_RICE_PARAM && (1u64 << (param + 1)) <= mean {
+        param += 1;
+    }
+    param
+}
+
+fn rice_cost(residual: &[i32], param: u32) -> u64 {
+    residual
+        .iter()
+        .map(|&r| u64::from(zigzag(r) >> param) + 1 + u64::from(param))
+        .sum()
+}
+
+/// Frame numbers use the UTF-8 style variable length encoding
+fn write_utf8_number(bits: &mut BitWriter, value: u64) {
+    if value < 0x80 {
+        bits.write(value, 8);
+        return;
+    }
+
+    let mut continuation = 1;
+    while value >= 1u64 << (5 * continuation + 6) {
+        continuation += 1;
+    }
+
+    let lead_bits = 6 - continuation;
+    let lead_marker = (0xFFu64 << (7 - continuation)) & 0xFF;
+    bits.write(
+        lead_marker | ((value >> (6 * continuation)) & ((1 << lead_bits) - 1)),
+        8,
+    );
+    for i in (0..continuation).rev() {
+        bits.write(0x80 | ((value >> (6 * i)) & 0x3F), 8);
+    }
+}
+
+/// MSB-first bit writer used for FLAC frames
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    used: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, bits: u32) {
+        for i in (0..bits).rev() {
+            self.acc = (self.acc << 1) | ((value >> i) & 1);
+            self.used += 1;
+            if self.used == 8 {
+                self.bytes.push(self.acc as u8);
+                self.acc = 0;
+                self.used = 0;
+            }
+        }
+    }
+
+    fn write_signed(&mut self, value: i32, bits: u32) {
+        self.write(u64::from(value as u32) & ((1u64 << bits) - 1), bits);
+    }
+
+    fn write_rice(&mut self, value: i32, param: u32) {
+        let folded = zigzag(value);
+        let quotient = folded >> param;
+        for _ in 0..quotient {
+            self.write(0, 1);
+        }
+        self.write(1, 1);
+        self.write(u64::from(folded), param);
+    }
+
+    fn align(&mut self) {
+        if self.used > 0 {
+            self.write(0, 8 - self.used);
+        }
+    }
+
+    /// Completed bytes (any partial byte is not included)
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
     }
 }
 
+/// FLAC frame header CRC-8 (polynomial 0x07)
+fn flac_crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// FLAC frame CRC-16 (polynomial 0x8005)
+fn flac_crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |mut crc, &byte| {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
 /// Create Opus ID header packet
 fn create_opus_id_header(channels: u8, sample_rate: u32) -> Vec<u8> {
     let mut header = Vec::with_capacity(19);
@@ -315,8 +930,9 @@ fn write_ogg_page<W: Write>(
     let crc_pos = header.len();
     header.extend_from_slice(&0u32.to_le_bytes());
 
-    // Segment count and table
-    let segment_count = data.len().div_ceil(255);
+    // Segment count and table; a packet ends with the first lacing value
+    // below 255, so exact multiples of 255 need a trailing zero-length segment
+    let segment_count = data.len() / 255 + 1;
     header.push(segment_count as u8);
 
     let mut remaining = data.len();
@@ -421,6 +1037,77 @@ mod tests {
         assert_eq!(&header[..8], b"OpusTags");
     }
 
+    #[test]
+    fn test_read_ogg_packets_follows_lacing() {
+        let mut stream = Vec::new();
+        write_ogg_page(&mut stream, 1, 0, 2, 0, b"first").unwrap();
+        let long = vec![7u8; 300];
+        write_ogg_page(&mut stream, 1, 0, 0, 1, &long).unwrap();
+
+        let packets = read_ogg_packets(&stream).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0], b"first");
+        assert_eq!(packets[1].len(), 300);
+    }
+
+    #[test]
+    fn test_streaming_writer_rejects_unsupported_rate() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = StreamingOggWriter::create(&dir.path().join("a.ogg"), 44100, 1, 24000);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_flac_round_trip_is_lossless() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.flac");
+
+        // Two channels, a bit more than two blocks, with a constant stretch
+        let mut samples = Vec::new();
+        for i in 0..(FLAC_BLOCK_SIZE * 2 + 123) {
+            let t = i as f32 / 16000.0;
+            let left = ((t * 440.0 * std::f32::consts::TAU).sin() * 12000.0) as i16;
+            let right = if i < 500 {
+                0
+            } else {
+                (i % 97) as i16 * 300 - 14000
+            };
+            samples.push(left);
+            samples.push(right);
+        }
+
+        let mut writer = FlacWriter::create(&path, 16000, 2).unwrap();
+        writer.write_samples(&samples[..1001]).unwrap();
+        writer.write_samples(&samples[1001..]).unwrap();
+        writer.finalize().unwrap();
+
+        let mut reader = claxon::FlacReader::open(&path).unwrap();
+        assert_eq!(reader.streaminfo().sample_rate, 16000);
+        assert_eq!(reader.streaminfo().channels, 2);
+        assert_eq!(
+            reader.streaminfo().samples,
+            Some((FLAC_BLOCK_SIZE * 2 + 123) as u64)
+        );
+        let decoded: Vec<i16> = reader.samples().map(|s| s.unwrap() as i16).collect();
+        assert_eq!(decoded, samples);
+    }
+
+    #[test]
+    fn test_flac_compresses_speech_like_signal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.flac");
+
+        let samples: Vec<i16> = (0..16000)
+            .map(|i| ((i as f32 / 16000.0 * 220.0 * std::f32::consts::TAU).sin() * 8000.0) as i16)
+            .collect();
+        let mut writer = FlacWriter::create(&path, 16000, 1).unwrap();
+        writer.write_samples(&samples).unwrap();
+        writer.finalize().unwrap();
+
+        let size = std::fs::metadata(&path).unwrap().len();
+        assert!(size < (samples.len() * 2) as u64 / 2, "size was {}", size);
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(500), "500 B");
@@ -0,0 +1,524 @@
+//! Audio capture through a JACK server
+//!
+//! Studio setups running JACK (or pipewire-jack) route audio explicitly, so
+//! the default sink monitor is rarely the signal that should be recorded.
+//! This backend registers a `minutes` client with one input port per source,
+//! connects the ports named in `audio.system_device` and `audio.device` to
+//! them, and mixes the two inputs like the PipeWire backend does.
+
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+
+use crate::config::Settings;
+
+use super::encoder::CaptureSink;
+use super::mixer::{f32_to_i16, resample_interleaved, MixOptions};
+use super::{AudioCapture, AudioDevice, AudioMixer, NoiseSuppressor};
+
+/// Client name registered with the JACK server
+const CLIENT_NAME: &str = "minutes";
+/// Port type string JACK uses for audio ports
+const AUDIO_PORT_TYPE: &str = "32 bit float mono audio";
+/// Process cycles buffered between the real-time thread and the writer
+const CYCLE_QUEUE: usize = 512;
+
+type JackClient = jack::AsyncClient<(), PortReader>;
+
+/// JACK audio capture
+///
+/// Records a mono mix of the system and microphone ports at the configured
+/// sample rate, resampling from the server rate when they differ.
+pub struct JackCapture {
+    sample_rate: u32,
+    capture_system: bool,
+    capture_microphone: bool,
+    /// Ports connected to the system input (comma-separated in settings)
+    system_ports: Vec<String>,
+    /// Ports connected to the microphone input (physical capture when empty)
+    mic_ports: Vec<String>,
+    mix: MixOptions,
+    /// Encode to OGG Opus during capture instead of writing WAV
+    stream_to_ogg: bool,
+    /// OGG Opus bitrate when streaming
+    ogg_bitrate: u32,
+    /// Whether recording is active
+    recording: Arc<AtomicBool>,
+    /// Active JACK client, dropped to stop the process callback
+    client: Option<JackClient>,
+    /// Mixer/writer thread fed by the process callback
+    writer_thread: Option<JoinHandle<Result<()>>>,
+    /// Cycles lost because the writer fell behind
+    dropped_cycles: Arc<AtomicUsize>,
+}
+
+impl JackCapture {
+    /// Create a new JACK capture instance
+    pub fn new(settings: &Settings) -> Result<Self> {
+        Ok(Self {
+            sample_rate: settings.audio.sample_rate,
+            capture_system: settings.audio.capture_system,
+            capture_microphone: settings.audio.capture_microphone,
+            system_ports: parse_ports(&settings.audio.system_device),
+            mic_ports: parse_ports(&settings.audio.device),
+            mix: MixOptions::from_settings(settings),
+            stream_to_ogg: settings.audio.stream_to_ogg,
+            ogg_bitrate: settings.audio.ogg_bitrate,
+            recording: Arc::new(AtomicBool::new(false)),
+            client: None,
+            writer_thread: None,
+            dropped_cycles: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Check if a JACK server is running
+    pub fn is_available() -> bool {
+        open_client().is_ok()
+    }
+}
+
+impl AudioCapture for JackCapture {
+    fn start(&mut self, output_path: &Path) -> Result<()> {
+        let client = open_client()?;
+
+        let system_ports = if self.capture_system {
+            if self.system_ports.is_empty() {
+                tracing::warn!(
+                    "JACK: audio.system_device is empty, recording the microphone only. \
+                     Set it to the playback monitor ports to include system audio."
+                );
+            }
+            self.system_ports.clone()
+        } else {
+            Vec::new()
+        };
+        let mic_ports = match (self.capture_microphone, self.mic_ports.is_empty()) {
+            (false, _) => Vec::new(),
+            (true, false) => self.mic_ports.clone(),
+            (true, true) => client.ports(
+                None,
+                Some(AUDIO_PORT_TYPE),
+                jack::PortFlags::IS_OUTPUT | jack::PortFlags::IS_PHYSICAL,
+            ),
+        };
+        if system_ports.is_empty() && mic_ports.is_empty() {
+            anyhow::bail!(
+                "No JACK ports to record. Set audio.system_device and/or audio.device \
+                 (run `minutes devices` to list ports)."
+            );
+        }
+
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let jack_rate = client.sample_rate() as u32;
+        let mix = self
+            .mix
+            .for_sources(!system_ports.is_empty(), !mic_ports.is_empty());
+        let sink = CaptureSink::create(
+            output_path,
+            self.sample_rate,
+            mix.channels(),
+            self.stream_to_ogg.then_some(self.ogg_bitrate),
+        )?;
+
+        let register = |name: &str| -> Result<jack::Port<jack::AudioIn>> {
+            client
+                .register_port(name, jack::AudioIn)
+                .with_context(|| format!("Failed to register JACK port '{}'", name))
+        };
+        let system_in = (!system_ports.is_empty())
+            .then(|| register("system_in"))
+            .transpose()?;
+        let mic_in = (!mic_ports.is_empty())
+            .then(|| register("mic_in"))
+            .transpose()?;
+        let system_in_name = system_in.as_ref().map(|p| p.name()).transpose()?;
+        let mic_in_name = mic_in.as_ref().map(|p| p.name()).transpose()?;
+
+        let mixer = CycleMixer::new(
+            system_in.is_some(),
+            mic_in.is_some(),
+            jack_rate,
+            self.sample_rate,
+            mix,
+        );
+        let (tx, rx) = mpsc::sync_channel(CYCLE_QUEUE);
+        self.dropped_cycles.store(0, Ordering::SeqCst);
+        self.recording.store(true, Ordering::SeqCst);
+
+        let reader = PortReader {
+            system: system_in,
+            mic: mic_in,
+            tx,
+            recording: self.recording.clone(),
+            dropped: self.dropped_cycles.clone(),
+        };
+        let active = client
+            .activate_async((), reader)
+            .context("Failed to activate JACK client")?;
+
+        self.writer_thread = Some(std::thread::spawn(move || run_writer(rx, mixer, sink)));
+        self.client = Some(active);
+
+        // Several sources connected to one input port are summed by JACK
+        let connections = system_in_name
+            .iter()
+            .flat_map(|input| system_ports.iter().map(move |port| (port, input)))
+            .chain(
+                mic_in_name
+                    .iter()
+                    .flat_map(|input| mic_ports.iter().map(move |port| (port, input))),
+            );
+        for (source, input) in connections {
+            let client = self.client.as_ref().expect("client was just activated");
+            match client.as_client().connect_ports_by_name(source, input) {
+                Ok(()) | Err(jack::Error::PortAlreadyConnected(_, _)) => {}
+                Err(e) => {
+                    let _ = self.stop();
+                    return Err(e).with_context(|| {
+                        format!(
+                            "Failed to connect JACK port '{}'. Run `minutes devices` to list ports.",
+                            source
+                        )
+                    });
+                }
+            }
+        }
+
+        tracing::info!(
+            "JACK: Recording at {} Hz (system_ports={}, mic_ports={})",
+            jack_rate,
+            if system_ports.is_empty() {
+                "-".to_string()
+            } else {
+                system_ports.join(",")
+            },
+            if mic_ports.is_empty() {
+                "-".to_string()
+            } else {
+                mic_ports.join(",")
+            }
+        );
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.recording.store(false, Ordering::SeqCst);
+
+        // Deactivating drops the process handler and its sender, which lets
+        // the writer drain the queue and finalize the file
+        if let Some(client) = self.client.take() {
+            if let Err(e) = client.deactivate() {
+                tracing::warn!("JACK: Failed to deactivate client: {}", e);
+            }
+        }
+
+        if let Some(handle) = self.writer_thread.take() {
+            handle
+                .join()
+                .map_err(|_| anyhow::anyhow!("JACK writer thread panicked"))??;
+
+            let dropped = self.dropped_cycles.load(Ordering::SeqCst);
+            if dropped > 0 {
+                tracing::warn!(
+                    "JACK: {} process cycles were dropped because the writer fell behind",
+                    dropped
+                );
+            }
+            tracing::info!("JACK: Audio recording stopped");
+        }
+
+        Ok(())
+    }
+
+    fn is_recording(&self) -> bool {
+        self.recording.load(Ordering::SeqCst)
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "jack"
+    }
+
+    fn output_extension(&self) -> &'static str {
+        if self.stream_to_ogg {
+            "ogg"
+        } else {
+            "wav"
+        }
+    }
+}
+
+impl Drop for JackCapture {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// One JACK process cycle of samples from each input port
+struct Cycle {
+    system: Vec<f32>,
+    mic: Vec<f32>,
+}
+
+/// Real-time process handler that copies port buffers to the writer thread
+struct PortReader {
+    system: Option<jack::Port<jack::AudioIn>>,
+    mic: Option<jack::Port<jack::AudioIn>>,
+    tx: mpsc::SyncSender<Cycle>,
+    recording: Arc<AtomicBool>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl jack::ProcessHandler for PortReader {
+    fn process(&mut self, _: &jack::Client, scope: &jack::ProcessScope) -> jack::Control {
+        if !self.recording.load(Ordering::Relaxed) {
+            return jack::Control::Continue;
+        }
+
+        let read = |port: &Option<jack::Port<jack::AudioIn>>| {
+            port.as_ref()
+                .map(|port| port.as_slice(scope).to_vec())
+                .unwrap_or_default()
+        };
+        let cycle = Cycle {
+            system: read(&self.system),
+            mic: read(&self.mic),
+        };
+
+        // Never block the real-time thread; count what doesn't fit instead
+        if self.tx.try_send(cycle).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        jack::Control::Continue
+    }
+}
+
+/// Mix queued cycles and write them until the process handler goes away
+fn run_writer(
+    rx: mpsc::Receiver<Cycle>,
+    mut mixer: CycleMixer,
+    mut sink: CaptureSink,
+) -> Result<()> {
+    for cycle in rx {
+        sink.write_samples(&mixer.push(&cycle.system, &cycle.mic))?;
+    }
+
+    sink.write_samples(&mixer.flush())?;
+    sink.finalize()
+}
+
+/// Mixes lock-step JACK cycles and resamples them to the output rate
+struct CycleMixer {
+    mixer: AudioMixer,
+    has_system: bool,
+    has_mic: bool,
+    jack_rate: u32,
+    output_rate: u32,
+    system: VecDeque<f32>,
+    mic: VecDeque<f32>,
+    /// Noise suppressor applied to the microphone before mixing
+    suppressor: Option<NoiseSuppressor>,
+    /// Mixed audio at the JACK rate waiting to be resampled
+    pending: Vec<f32>,
+}
+
+impl CycleMixer {
+    fn new(
+        has_system: bool,
+        has_mic: bool,
+        jack_rate: u32,
+        output_rate: u32,
+        options: MixOptions,
+    ) -> Self {
+        Self {
+            mixer: options.mixer(jack_rate),
+            has_system,
+            has_mic,
+            jack_rate,
+            output_rate,
+            system: VecDeque::new(),
+            mic: VecDeque::new(),
+            suppressor: (has_mic && options.noise_suppression)
+                .then(|| NoiseSuppressor::new(jack_rate)),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Queue one cycle and return whatever can be written now
+    fn push(&mut self, system: &[f32], mic: &[f32]) -> Vec<i16> {
+        self.system.extend(system);
+        match self.suppressor.as_mut() {
+            // The suppressor lags its input, so the queues keep both sides aligned
+            Some(suppressor) => self.mic.extend(suppressor.process(mic)),
+            None => self.mic.extend(mic),
+        }
+
+        let ready = match (self.has_system, self.has_mic) {
+            (true, true) => self.system.len().min(self.mic.len()),
+            (true, false) => self.system.len(),
+            _ => self.mic.len(),
+        };
+        self.mix_queued(ready);
+
+        // Resample whole seconds so block boundaries never drift
+        let channels = self.mixer.channels() as usize;
+        let block = self.jack_rate as usize * channels;
+        let whole = self.pending.len() / block * block;
+        let mut output = Vec::new();
+        for chunk in self.pending[..whole].chunks(block) {
+            let mut resampled =
+                resample_interleaved(chunk, channels, self.jack_rate, self.output_rate);
+            resampled.truncate(self.output_rate as usize * channels);
+            output.extend(resampled);
+        }
+        self.pending.drain(..whole);
+        output.into_iter().map(f32_to_i16).collect()
+    }
+
+    /// Mix and resample everything still queued
+    fn flush(&mut self) -> Vec<i16> {
+        if let Some(suppressor) = self.suppressor.as_mut() {
+            self.mic.extend(suppressor.flush());
+        }
+        self.mix_queued(self.system.len().max(self.mic.len()));
+
+        let pending = std::mem::take(&mut self.pending);
+        let channels = self.mixer.channels() as usize;
+        resample_interleaved(&pending, channels, self.jack_rate, self.output_rate)
+            .into_iter()
+            .map(f32_to_i16)
+            .collect()
+    }
+
+    fn mix_queued(&mut self, count: usize) {
+        let take = |queue: &mut VecDeque<f32>| -> Vec<f32> {
+            let n = count.min(queue.len());
+            queue.drain(..n).collect()
+        };
+        let system = take(&mut self.system);
+        let mic = take(&mut self.mic);
+        self.pending.extend(self.mixer.mix(&system, &mic));
+    }
+}
+
+fn open_client() -> Result<jack::Client> {
+    let (client, _status) = jack::Client::new(CLIENT_NAME, jack::ClientOptions::NO_START_SERVER)
+        .context("Failed to connect to the JACK server. Is it running?")?;
+    Ok(client)
+}
+
+/// Split a comma-separated port list from the settings
+fn parse_ports(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|port| !port.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// List JACK output ports that can be recorded
+///
+/// Physical capture ports are reported as microphones, everything else
+/// (playback monitors, application outputs) as system sources.
+pub(crate) fn list_jack_ports() -> Vec<AudioDevice> {
+    let Ok(client) = open_client() else {
+        return Vec::new();
+    };
+
+    client
+        .ports(None, Some(AUDIO_PORT_TYPE), jack::PortFlags::IS_OUTPUT)
+        .into_iter()
+        .filter_map(|name| {
+            let port = client.port_by_name(&name)?;
+            let kind = if port.flags().contains(jack::PortFlags::IS_PHYSICAL) {
+                "microphone"
+            } else {
+                "system"
+            };
+            let label = port
+                .aliases()
+                .ok()
+                .and_then(|aliases| aliases.into_iter().next())
+                .unwrap_or_else(|| name.clone());
+            Some(AudioDevice {
+                backend: "jack",
+                kind,
+                id: name,
+                name: label,
+                is_default: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain_mix() -> MixOptions {
+        MixOptions {
+            mic_boost: 1.0,
+            noise_suppression: false,
+            echo_cancellation: false,
+            auto_gain: None,
+            split_stereo: false,
+        }
+    }
+
+    #[test]
+    fn parses_comma_separated_ports() {
+        assert_eq!(
+            parse_ports(" system:monitor_1, system:monitor_2 ,,"),
+            vec!["system:monitor_1", "system:monitor_2"]
+        );
+        assert!(parse_ports("  ").is_empty());
+    }
+
+    #[test]
+    fn resamples_cycles_to_the_output_rate() {
+        let mut mixer = CycleMixer::new(true, true, 44100, 16000, plain_mix());
+
+        let mut output = Vec::new();
+        for _ in 0..(44100 * 2 / 1024) {
+            output.extend(mixer.push(&[0.1; 1024], &[0.1; 1024]));
+        }
+        output.extend(mixer.flush());
+
+        let input_secs = (44100 * 2 / 1024 * 1024) as f64 / 44100.0;
+        let expected = (input_secs * 16000.0).round() as i64;
+        assert!((output.len() as i64 - expected).abs() <= 1);
+        assert!(output.iter().all(|&s| (s - 6553).abs() <= 1));
+    }
+
+    #[test]
+    fn keeps_length_with_noise_suppression_on_the_microphone() {
+        let options = MixOptions {
+            noise_suppression: true,
+            echo_cancellation: false,
+            ..plain_mix()
+        };
+        let mut mixer = CycleMixer::new(true, true, 16000, 16000, options);
+
+        let mut output = Vec::new();
+        for _ in 0..10 {
+            output.extend(mixer.push(&[0.0; 512], &[0.01; 512]));
+        }
+        output.extend(mixer.flush());
+        assert_eq!(output.len(), 5120);
+    }
+
+    #[test]
+    fn records_a_single_source() {
+        let mut mixer = CycleMixer::new(false, true, 16000, 16000, plain_mix());
+
+        let mut output = mixer.push(&[], &[0.5; 16000]);
+        output.extend(mixer.flush());
+        assert_eq!(output.len(), 16000);
+    }
+}
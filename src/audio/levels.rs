@@ -0,0 +1,253 @@
+//! Level checks for captured tracks
+//!
+//! A muted or unplugged microphone records silence without any error, and a
+//! badly set input gain clips speech or buries it in noise. Each track's
+//! levels are measured as the recording is finalized, so problems are
+//! reported when it stops rather than discovered after the meeting.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Peak level below which a track counts as silent
+const SILENT_PEAK_DBFS: f32 = -60.0;
+/// Absolute sample value treated as clipped
+const CLIP_LEVEL: f32 = 0.999;
+/// Share of clipped samples above which a track counts as heavily clipped
+const MAX_CLIPPED_SHARE: f64 = 0.001;
+/// Level of the loudest stretches below which speech is too quiet to transcribe well
+const QUIET_DBFS: f32 = -40.0;
+/// Share of blocks that count as the loudest stretches of a track
+const LOUD_BLOCK_SHARE: f64 = 0.05;
+/// Length of the blocks levels are measured over
+const BLOCK_SECS: f32 = 0.1;
+/// Tracks shorter than this many blocks are too short to judge
+const MIN_BLOCKS: usize = 10;
+
+/// A problem found in a track's levels
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelProblem {
+    /// No signal at all, e.g. a muted or disconnected device
+    Silent,
+    /// Share of samples at full scale
+    Clipped(f64),
+    /// Level of the loudest stretches in dBFS
+    Quiet(f32),
+}
+
+impl LevelProblem {
+    /// Warning shown to the user for a track such as "Microphone"
+    pub fn describe(&self, track: &str) -> String {
+        match self {
+            Self::Silent => format!(
+                "{} track is silent; check that the device is connected and not muted",
+                track
+            ),
+            Self::Clipped(share) => format!(
+                "{} track is heavily clipped ({:.1}% of samples at full scale); lower the input gain",
+                track,
+                share * 100.0
+            ),
+            Self::Quiet(dbfs) => format!(
+                "{} track is very quiet (loudest parts around {:.0} dBFS); raise the input gain",
+                track, dbfs
+            ),
+        }
+    }
+}
+
+/// Streaming level statistics for one track
+#[derive(Debug, Clone)]
+pub struct LevelMeter {
+    block_len: usize,
+    block_sum: f32,
+    block_count: usize,
+    /// Mean square of every finished block
+    block_levels: Vec<f32>,
+    samples: u64,
+    clipped: u64,
+    peak: f32,
+}
+
+impl LevelMeter {
+    /// Create a meter for audio at `sample_rate`
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            block_len: ((sample_rate as f32 * BLOCK_SECS) as usize).max(1),
+            block_sum: 0.0,
+            block_count: 0,
+            block_levels: Vec::new(),
+            samples: 0,
+            clipped: 0,
+            peak: 0.0,
+        }
+    }
+
+    /// Measure a WAV file
+    pub fn measure_wav(path: &Path) -> Result<Self> {
+        let mut reader = hound::WavReader::open(path)
+            .with_context(|| format!("Failed to open audio file: {}", path.display()))?;
+        let spec = reader.spec();
+        let mut meter = Self::new(spec.sample_rate * spec.channels as u32);
+
+        match spec.sample_format {
+            hound::SampleFormat::Float => {
+                for sample in reader.samples::<f32>().filter_map(|s| s.ok()) {
+                    meter.push_sample(sample);
+                }
+            }
+            hound::SampleFormat::Int => {
+                let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+                for sample in reader.samples::<i32>().filter_map(|s| s.ok()) {
+                    meter.push_sample(sample as f32 / scale);
+                }
+            }
+        }
+
+        Ok(meter)
+    }
+
+    /// Add samples to the statistics
+    pub fn push(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            self.push_sample(sample);
+        }
+    }
+
+    fn push_sample(&mut self, sample: f32) {
+        let magnitude = sample.abs();
+        self.samples += 1;
+        self.peak = self.peak.max(magnitude);
+        if magnitude >= CLIP_LEVEL {
+            self.clipped += 1;
+        }
+
+        self.block_sum += sample * sample;
+        self.block_count += 1;
+        if self.block_count == self.block_len {
+            self.block_levels
+                .push(self.block_sum / self.block_len as f32);
+            self.block_sum = 0.0;
+            self.block_count = 0;
+        }
+    }
+
+    /// Problems found in the audio measured so far
+    pub fn problems(&self) -> Vec<LevelProblem> {
+        if self.block_levels.len() < MIN_BLOCKS {
+            return Vec::new();
+        }
+        if amplitude_to_db(self.peak) < SILENT_PEAK_DBFS {
+            return vec![LevelProblem::Silent];
+        }
+
+        let mut problems = Vec::new();
+        let clipped_share = self.clipped as f64 / self.samples as f64;
+        if clipped_share > MAX_CLIPPED_SHARE {
+            problems.push(LevelProblem::Clipped(clipped_share));
+        }
+
+        let loud_level = amplitude_to_db(self.loud_level_ms().sqrt());
+        if loud_level < QUIET_DBFS {
+            problems.push(LevelProblem::Quiet(loud_level));
+        }
+        problems
+    }
+
+    /// Mean square of the loudest blocks, which are speech if there is any
+    fn loud_level_ms(&self) -> f32 {
+        let mut levels = self.block_levels.clone();
+        levels.sort_by(|a, b| b.total_cmp(a));
+        let count = ((levels.len() as f64 * LOUD_BLOCK_SHARE).ceil() as usize).max(1);
+        levels[..count].iter().sum::<f32>() / count as f32
+    }
+}
+
+/// Level meters for the sources of one recording
+#[derive(Debug, Clone, Default)]
+pub struct TrackLevels {
+    pub system: Option<LevelMeter>,
+    pub mic: Option<LevelMeter>,
+}
+
+impl TrackLevels {
+    /// Warnings for both tracks
+    ///
+    /// System audio is only checked for clipping, since a meeting where
+    /// nobody else is heard is not a capture problem.
+    pub fn warnings(&self) -> Vec<String> {
+        let system = self.system.iter().flat_map(|meter| {
+            meter
+                .problems()
+                .into_iter()
+                .filter(|problem| matches!(problem, LevelProblem::Clipped(_)))
+                .map(|problem| problem.describe("System audio"))
+        });
+        let mic = self.mic.iter().flat_map(|meter| {
+            meter
+                .problems()
+                .into_iter()
+                .map(|problem| problem.describe("Microphone"))
+        });
+        system.chain(mic).collect()
+    }
+}
+
+fn amplitude_to_db(amplitude: f32) -> f32 {
+    20.0 * amplitude.max(1e-10).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meter(samples: &[f32]) -> LevelMeter {
+        let mut meter = LevelMeter::new(1000);
+        meter.push(samples);
+        meter
+    }
+
+    /// One second of a 50 Hz tone at `amplitude`, sampled at 1 kHz
+    fn tone(amplitude: f32) -> Vec<f32> {
+        (0..1000)
+            .map(|i| amplitude * (i as f32 * std::f32::consts::TAU / 20.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn flags_silent_clipped_and_quiet_tracks() {
+        assert_eq!(meter(&[0.0; 2000]).problems(), [LevelProblem::Silent]);
+
+        let clipped: Vec<f32> = tone(1.5).iter().map(|s| s.clamp(-1.0, 1.0)).collect();
+        assert!(matches!(
+            meter(&clipped).problems()[..],
+            [LevelProblem::Clipped(share)] if share > 0.1
+        ));
+
+        let quiet = meter(&tone(0.005)).problems();
+        assert!(matches!(quiet[..], [LevelProblem::Quiet(db)] if db < -45.0));
+
+        assert!(meter(&tone(0.3)).problems().is_empty());
+    }
+
+    #[test]
+    fn judges_a_mostly_idle_microphone_by_its_speech() {
+        // Ten seconds of faint room noise with one second of speech
+        let mut samples = vec![0.002; 9000];
+        samples.extend(tone(0.3));
+
+        assert!(meter(&samples).problems().is_empty());
+        assert!(meter(&samples[..500]).problems().is_empty());
+    }
+
+    #[test]
+    fn only_reports_clipping_for_system_audio() {
+        let levels = TrackLevels {
+            system: Some(meter(&[0.0; 2000])),
+            mic: Some(meter(&[0.0; 2000])),
+        };
+
+        let warnings = levels.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Microphone track is silent"));
+    }
+}
@@ -0,0 +1,757 @@
+//! System audio + microphone capture through cpal on Windows and macOS
+//!
+//! Neither platform exposes a monitor source like PipeWire does, so system
+//! audio is reached differently on each:
+//! - Windows: cpal's WASAPI host records what is being played on an output
+//!   device when an input stream is opened on it (loopback).
+//! - macOS: a virtual loopback driver such as BlackHole, added next to the
+//!   speakers in a Multi-Output Device, shows up as an ordinary input device.
+//!
+//! Either way the backend opens one stream for system audio and one for the
+//! microphone, and mixes the two to mono like the PipeWire backend does.
+
+use anyhow::{Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{SampleFormat, Stream, StreamConfig};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::config::Settings;
+
+use super::cpal_capture::select_input_device;
+use super::encoder::CaptureSink;
+use super::mixer::{f32_to_i16, resample, MixOptions};
+use super::{AudioCapture, AudioDevice, AudioMixer, NoiseSuppressor};
+
+/// Callback buffers queued between the audio threads and the writer
+const CHUNK_QUEUE: usize = 512;
+/// Sources are resampled in blocks of 1/10 s so block boundaries never drift
+const BLOCKS_PER_SEC: u32 = 10;
+/// How long the writer waits for audio before checking for loopback gaps
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How far loopback may fall behind the wall clock before silence is filled in
+const LOOPBACK_SLACK_SECS: f64 = 0.5;
+/// Name fragments of macOS loopback drivers looked for when no system device is set
+#[cfg(target_os = "macos")]
+const LOOPBACK_DRIVERS: &[&str] = &["BlackHole", "Loopback Audio", "Soundflower"];
+
+/// How system audio reaches cpal on this platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SystemSource {
+    /// WASAPI loopback on a playback device
+    #[cfg(windows)]
+    OutputLoopback,
+    /// Input side of a virtual loopback driver
+    #[cfg(target_os = "macos")]
+    LoopbackDriver,
+}
+
+impl SystemSource {
+    /// Prefix used in log messages
+    fn label(self) -> &'static str {
+        match self {
+            #[cfg(windows)]
+            SystemSource::OutputLoopback => "WASAPI",
+            #[cfg(target_os = "macos")]
+            SystemSource::LoopbackDriver => "CoreAudio",
+        }
+    }
+
+    fn backend_name(self) -> &'static str {
+        match self {
+            #[cfg(windows)]
+            SystemSource::OutputLoopback => "wasapi",
+            #[cfg(target_os = "macos")]
+            SystemSource::LoopbackDriver => "coreaudio",
+        }
+    }
+}
+
+/// Audio source a callback buffer came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    System,
+    Microphone,
+}
+
+/// Interleaved samples delivered by one stream callback
+struct Chunk {
+    source: Source,
+    samples: Vec<f32>,
+}
+
+/// Loopback system audio + microphone capture
+///
+/// Records a mono mix at the configured sample rate. Each device runs at its
+/// own rate and channel count, so both sources are downmixed and resampled
+/// before they are mixed.
+pub struct LoopbackCapture {
+    system_source: SystemSource,
+    sample_rate: u32,
+    capture_system: bool,
+    capture_microphone: bool,
+    /// System audio device (empty = default output or detected driver)
+    system_device: String,
+    /// Microphone device (empty = default input)
+    mic_device: String,
+    mix: MixOptions,
+    /// Encode to OGG Opus during capture instead of writing WAV
+    stream_to_ogg: bool,
+    /// OGG Opus bitrate when streaming
+    ogg_bitrate: u32,
+    /// Whether recording is active
+    recording: Arc<AtomicBool>,
+    /// Active cpal streams, dropped to stop their callbacks
+    streams: Vec<Stream>,
+    /// Mixer/writer thread fed by the stream callbacks
+    writer_thread: Option<JoinHandle<Result<()>>>,
+    /// Buffers lost because the writer fell behind
+    dropped_chunks: Arc<AtomicUsize>,
+}
+
+impl LoopbackCapture {
+    /// Create a capture instance recording WASAPI loopback (Windows)
+    #[cfg(windows)]
+    pub fn wasapi(settings: &Settings) -> Result<Self> {
+        Self::new(settings, SystemSource::OutputLoopback)
+    }
+
+    /// Create a capture instance recording a loopback driver (macOS)
+    #[cfg(target_os = "macos")]
+    pub fn core_audio(settings: &Settings) -> Result<Self> {
+        Self::new(settings, SystemSource::LoopbackDriver)
+    }
+
+    /// Name of the loopback driver that would be recorded, if one is installed
+    #[cfg(target_os = "macos")]
+    pub fn loopback_driver(settings: &Settings) -> Option<String> {
+        let host = cpal::default_host();
+        find_loopback_driver(&host, settings.audio.system_device.trim())
+            .ok()
+            .flatten()
+            .and_then(|device| device.name().ok())
+    }
+
+    fn new(settings: &Settings, system_source: SystemSource) -> Result<Self> {
+        Ok(Self {
+            system_source,
+            sample_rate: settings.audio.sample_rate,
+            capture_system: settings.audio.capture_system,
+            capture_microphone: settings.audio.capture_microphone,
+            system_device: settings.audio.system_device.trim().to_string(),
+            mic_device: settings.audio.device.trim().to_string(),
+            mix: MixOptions::from_settings(settings),
+            stream_to_ogg: settings.audio.stream_to_ogg,
+            ogg_bitrate: settings.audio.ogg_bitrate,
+            recording: Arc::new(AtomicBool::new(false)),
+            streams: Vec::new(),
+            writer_thread: None,
+            dropped_chunks: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+}
+
+impl AudioCapture for LoopbackCapture {
+    fn start(&mut self, output_path: &Path) -> Result<()> {
+        let label = self.system_source.label();
+        let host = cpal::default_host();
+        let (tx, rx) = mpsc::sync_channel(CHUNK_QUEUE);
+        self.dropped_chunks.store(0, Ordering::SeqCst);
+
+        let mut streams = Vec::new();
+        let mut system = None;
+        let mut mic = None;
+
+        let system_device = if self.capture_system {
+            self.open_system_device(&host)?
+        } else {
+            None
+        };
+        if system_device.is_none() && !self.capture_microphone {
+            anyhow::bail!(
+                "Nothing to record: no system audio device is available and audio.capture_microphone is disabled"
+            );
+        }
+
+        if let Some((device, config)) = system_device {
+            tracing::info!(
+                "{}: System audio from {} ({} Hz, {} channels)",
+                label,
+                device.name().unwrap_or_default(),
+                config.sample_rate().0,
+                config.channels()
+            );
+            system = Some(SourceQueue::new(
+                config.sample_rate().0,
+                config.channels(),
+                self.sample_rate,
+                None,
+            ));
+            streams.push(self.open_stream(&device, &config, Source::System, tx.clone())?);
+        }
+
+        if self.capture_microphone {
+            let device = select_input_device(&host, &self.mic_device)?;
+            let config = device
+                .default_input_config()
+                .context("Failed to get microphone format")?;
+            tracing::info!(
+                "{}: Microphone {} ({} Hz, {} channels)",
+                label,
+                device.name().unwrap_or_default(),
+                config.sample_rate().0,
+                config.channels()
+            );
+            let suppressor = self
+                .mix
+                .noise_suppression
+                .then(|| NoiseSuppressor::new(self.sample_rate));
+            mic = Some(SourceQueue::new(
+                config.sample_rate().0,
+                config.channels(),
+                self.sample_rate,
+                suppressor,
+            ));
+            streams.push(self.open_stream(&device, &config, Source::Microphone, tx.clone())?);
+        }
+
+        // Only the stream callbacks hold senders, so dropping the streams
+        // ends the writer loop
+        drop(tx);
+
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mix = self.mix.for_sources(system.is_some(), mic.is_some());
+        let sink = CaptureSink::create(
+            output_path,
+            self.sample_rate,
+            mix.channels(),
+            self.stream_to_ogg.then_some(self.ogg_bitrate),
+        )?;
+
+        let mixer = StreamMixer::new(system, mic, self.sample_rate, mix);
+        let recording = self.recording.clone();
+        self.recording.store(true, Ordering::SeqCst);
+        self.writer_thread = Some(std::thread::spawn(move || {
+            run_writer(rx, mixer, sink, recording)
+        }));
+
+        self.streams = streams;
+        if let Err(e) = self.streams.iter().try_for_each(|stream| stream.play()) {
+            let _ = self.stop();
+            return Err(e).context("Failed to start audio stream");
+        }
+
+        tracing::info!("{}: Audio recording started", label);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        let label = self.system_source.label();
+        self.recording.store(false, Ordering::SeqCst);
+        self.streams.clear();
+
+        if let Some(handle) = self.writer_thread.take() {
+            handle
+                .join()
+                .map_err(|_| anyhow::anyhow!("{} writer thread panicked", label))??;
+
+            let dropped = self.dropped_chunks.load(Ordering::SeqCst);
+            if dropped > 0 {
+                tracing::warn!(
+                    "{}: {} buffers were dropped because the writer fell behind",
+                    label,
+                    dropped
+                );
+            }
+            tracing::info!("{}: Audio recording stopped", label);
+        }
+
+        Ok(())
+    }
+
+    fn is_recording(&self) -> bool {
+        self.recording.load(Ordering::SeqCst)
+    }
+
+    fn backend_name(&self) -> &'static str {
+        self.system_source.backend_name()
+    }
+
+    fn output_extension(&self) -> &'static str {
+        if self.stream_to_ogg {
+            "ogg"
+        } else {
+            "wav"
+        }
+    }
+}
+
+impl LoopbackCapture {
+    /// System audio device and its format, or `None` to record the mic only
+    fn open_system_device(
+        &self,
+        host: &cpal::Host,
+    ) -> Result<Option<(cpal::Device, cpal::SupportedStreamConfig)>> {
+        match self.system_source {
+            #[cfg(windows)]
+            SystemSource::OutputLoopback => {
+                let device = select_output_device(host, &self.system_device)?;
+                let config = device
+                    .default_output_config()
+                    .context("Failed to get playback device format")?;
+                Ok(Some((device, config)))
+            }
+            #[cfg(target_os = "macos")]
+            SystemSource::LoopbackDriver => {
+                let Some(device) = find_loopback_driver(host, &self.system_device)? else {
+                    tracing::warn!(
+                        "CoreAudio: No loopback device (e.g. BlackHole) found, recording the \
+                         microphone only. Run `minutes doctor` for setup steps."
+                    );
+                    return Ok(None);
+                };
+                let config = device
+                    .default_input_config()
+                    .context("Failed to get loopback device format")?;
+                Ok(Some((device, config)))
+            }
+        }
+    }
+
+    /// Open a stream in the device's native sample format
+    fn open_stream(
+        &self,
+        device: &cpal::Device,
+        config: &cpal::SupportedStreamConfig,
+        source: Source,
+        tx: mpsc::SyncSender<Chunk>,
+    ) -> Result<Stream> {
+        let stream_config = StreamConfig {
+            channels: config.channels(),
+            sample_rate: config.sample_rate(),
+            buffer_size: cpal::BufferSize::Default,
+        };
+        let feed = Feed {
+            source,
+            tx,
+            recording: self.recording.clone(),
+            dropped: self.dropped_chunks.clone(),
+        };
+
+        match config.sample_format() {
+            SampleFormat::I8 => build_stream::<i8>(device, &stream_config, feed),
+            SampleFormat::I16 => build_stream::<i16>(device, &stream_config, feed),
+            SampleFormat::I32 => build_stream::<i32>(device, &stream_config, feed),
+            SampleFormat::U8 => build_stream::<u8>(device, &stream_config, feed),
+            SampleFormat::U16 => build_stream::<u16>(device, &stream_config, feed),
+            SampleFormat::F32 => build_stream::<f32>(device, &stream_config, feed),
+            SampleFormat::F64 => build_stream::<f64>(device, &stream_config, feed),
+            format => anyhow::bail!("Unsupported sample format: {:?}", format),
+        }
+    }
+}
+
+impl Drop for LoopbackCapture {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// Where a stream callback sends its buffers
+struct Feed {
+    source: Source,
+    tx: mpsc::SyncSender<Chunk>,
+    recording: Arc<AtomicBool>,
+    dropped: Arc<AtomicUsize>,
+}
+
+fn build_stream<T>(device: &cpal::Device, config: &StreamConfig, feed: Feed) -> Result<Stream>
+where
+    T: cpal::Sample + cpal::SizedSample + 'static,
+    f32: cpal::FromSample<T>,
+{
+    let err_fn = |err| tracing::error!("Audio stream error: {}", err);
+
+    // On WASAPI an input stream on an output device is opened in loopback mode
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            if !feed.recording.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let chunk = Chunk {
+                source: feed.source,
+                samples: data
+                    .iter()
+                    .map(|&sample| cpal::Sample::from_sample(sample))
+                    .collect(),
+            };
+            // Never block the audio thread; count what doesn't fit instead
+            if feed.tx.try_send(chunk).is_err() {
+                feed.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        },
+        err_fn,
+        None,
+    )?;
+
+    Ok(stream)
+}
+
+/// Mix queued buffers and write them until the streams go away
+fn run_writer(
+    rx: mpsc::Receiver<Chunk>,
+    mut mixer: StreamMixer,
+    mut sink: CaptureSink,
+    recording: Arc<AtomicBool>,
+) -> Result<()> {
+    let started = Instant::now();
+
+    loop {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(chunk) => mixer.push(chunk.source, &chunk.samples),
+            Err(mpsc::RecvTimeoutError::Timeout) if !recording.load(Ordering::SeqCst) => break,
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        mixer.fill_loopback_gap(started.elapsed());
+        sink.write_samples(&mixer.take_ready())?;
+    }
+
+    // Buffers already queued when the streams stopped
+    for chunk in rx.try_iter() {
+        mixer.push(chunk.source, &chunk.samples);
+    }
+    sink.write_samples(&mixer.flush())?;
+    sink.finalize()
+}
+
+/// One source, downmixed to mono and resampled to the output rate
+struct SourceQueue {
+    rate: u32,
+    channels: usize,
+    output_rate: u32,
+    /// Mono audio at the device rate waiting to be resampled
+    pending: Vec<f32>,
+    /// Audio at the output rate waiting to be mixed
+    ready: VecDeque<f32>,
+    /// Samples added to `ready` so far, including filled-in silence
+    produced: usize,
+    /// Noise suppressor applied after resampling
+    suppressor: Option<NoiseSuppressor>,
+}
+
+impl SourceQueue {
+    fn new(
+        rate: u32,
+        channels: u16,
+        output_rate: u32,
+        suppressor: Option<NoiseSuppressor>,
+    ) -> Self {
+        Self {
+            rate,
+            channels: channels.max(1) as usize,
+            output_rate,
+            pending: Vec::new(),
+            ready: VecDeque::new(),
+            produced: 0,
+            suppressor,
+        }
+    }
+
+    fn push(&mut self, interleaved: &[f32]) {
+        let channels = self.channels;
+        self.pending.extend(
+            interleaved
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32),
+        );
+
+        let block = (self.rate / BLOCKS_PER_SEC) as usize;
+        let output_block = (self.output_rate / BLOCKS_PER_SEC) as usize;
+        let whole = self.pending.len() / block * block;
+        let mut output = Vec::with_capacity(whole / block * output_block);
+        for chunk in self.pending[..whole].chunks(block) {
+            let mut resampled = resample(chunk, self.rate, self.output_rate);
+            resampled.truncate(output_block);
+            output.extend(resampled);
+        }
+        self.pending.drain(..whole);
+        self.append(output);
+    }
+
+    fn flush(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        self.append(resample(&pending, self.rate, self.output_rate));
+        if let Some(suppressor) = self.suppressor.as_mut() {
+            let tail = suppressor.flush();
+            self.produced += tail.len();
+            self.ready.extend(tail);
+        }
+    }
+
+    /// Append silence, e.g. while nothing is playing on the loopback device
+    fn pad(&mut self, samples: usize) {
+        self.append(vec![0.0; samples]);
+    }
+
+    fn append(&mut self, samples: Vec<f32>) {
+        let samples = match self.suppressor.as_mut() {
+            Some(suppressor) => suppressor.process(&samples),
+            None => samples,
+        };
+        self.produced += samples.len();
+        self.ready.extend(samples);
+    }
+
+    /// Output-rate samples received so far, resampled or not
+    fn received(&self) -> usize {
+        self.produced + self.pending.len() * self.output_rate as usize / self.rate as usize
+    }
+}
+
+/// Mixes the loopback and microphone sources at the output rate
+struct StreamMixer {
+    mixer: AudioMixer,
+    output_rate: u32,
+    system: Option<SourceQueue>,
+    mic: Option<SourceQueue>,
+}
+
+impl StreamMixer {
+    fn new(
+        system: Option<SourceQueue>,
+        mic: Option<SourceQueue>,
+        output_rate: u32,
+        options: MixOptions,
+    ) -> Self {
+        Self {
+            mixer: options.mixer(output_rate),
+            output_rate,
+            system,
+            mic,
+        }
+    }
+
+    fn push(&mut self, source: Source, samples: &[f32]) {
+        let queue = match source {
+            Source::System => self.system.as_mut(),
+            Source::Microphone => self.mic.as_mut(),
+        };
+        if let Some(queue) = queue {
+            queue.push(samples);
+        }
+    }
+
+    /// WASAPI delivers no loopback buffers while nothing is playing, so the
+    /// system source is topped up with silence to keep pace with the clock.
+    /// Loopback drivers deliver silence themselves and never fall behind.
+    fn fill_loopback_gap(&mut self, elapsed: Duration) {
+        let Some(system) = self.system.as_mut() else {
+            return;
+        };
+        let expected_secs = elapsed.as_secs_f64() - LOOPBACK_SLACK_SECS;
+        if expected_secs <= 0.0 {
+            return;
+        }
+
+        let expected = (expected_secs * self.output_rate as f64) as usize;
+        let received = system.received();
+        if received < expected {
+            system.pad(expected - received);
+        }
+    }
+
+    /// Mix whatever both sources have ready
+    fn take_ready(&mut self) -> Vec<i16> {
+        let count = match (&self.system, &self.mic) {
+            (Some(system), Some(mic)) => system.ready.len().min(mic.ready.len()),
+            (Some(system), None) => system.ready.len(),
+            (None, Some(mic)) => mic.ready.len(),
+            (None, None) => 0,
+        };
+        self.mix(count)
+    }
+
+    /// Resample and mix everything still queued
+    fn flush(&mut self) -> Vec<i16> {
+        let mut count = 0;
+        for queue in [self.system.as_mut(), self.mic.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            queue.flush();
+            count = count.max(queue.ready.len());
+        }
+        self.mix(count)
+    }
+
+    fn mix(&mut self, count: usize) -> Vec<i16> {
+        let take = |queue: Option<&mut SourceQueue>| -> Vec<f32> {
+            queue
+                .map(|queue| {
+                    let n = count.min(queue.ready.len());
+                    queue.ready.drain(..n).collect()
+                })
+                .unwrap_or_default()
+        };
+        let system = take(self.system.as_mut());
+        let mic = take(self.mic.as_mut());
+        self.mixer
+            .mix(&system, &mic)
+            .into_iter()
+            .map(f32_to_i16)
+            .collect()
+    }
+}
+
+/// Pick the configured playback device, or the host default when none is set
+#[cfg(windows)]
+fn select_output_device(host: &cpal::Host, preferred: &str) -> Result<cpal::Device> {
+    if preferred.is_empty() {
+        return host
+            .default_output_device()
+            .context("No playback device available for loopback capture");
+    }
+
+    host.output_devices()
+        .context("Failed to enumerate playback devices")?
+        .find(|device| device.name().map(|n| n == preferred).unwrap_or(false))
+        .with_context(|| {
+            format!(
+                "Configured system device '{}' not found. Run `minutes devices` to list available devices.",
+                preferred
+            )
+        })
+}
+
+/// The configured system device, or the first installed loopback driver
+#[cfg(target_os = "macos")]
+fn find_loopback_driver(host: &cpal::Host, preferred: &str) -> Result<Option<cpal::Device>> {
+    if !preferred.is_empty() {
+        return select_input_device(host, preferred).map(Some);
+    }
+
+    Ok(host
+        .input_devices()
+        .context("Failed to enumerate input devices")?
+        .find(|device| device.name().is_ok_and(|name| is_loopback_driver(&name))))
+}
+
+#[cfg(target_os = "macos")]
+fn is_loopback_driver(name: &str) -> bool {
+    LOOPBACK_DRIVERS
+        .iter()
+        .any(|driver| name.to_lowercase().contains(&driver.to_lowercase()))
+}
+
+/// List playback devices that can be recorded through loopback
+#[cfg(windows)]
+pub(crate) fn list_system_devices() -> Vec<AudioDevice> {
+    let host = cpal::default_host();
+    let default_name = host.default_output_device().and_then(|d| d.name().ok());
+
+    let Ok(devices) = host.output_devices() else {
+        return Vec::new();
+    };
+
+    devices
+        .filter_map(|device| device.name().ok())
+        .map(|name| AudioDevice {
+            backend: "wasapi",
+            kind: "system",
+            id: name.clone(),
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect()
+}
+
+/// List installed loopback drivers that can provide system audio
+#[cfg(target_os = "macos")]
+pub(crate) fn list_system_devices() -> Vec<AudioDevice> {
+    let host = cpal::default_host();
+    let Ok(devices) = host.input_devices() else {
+        return Vec::new();
+    };
+
+    devices
+        .filter_map(|device| device.name().ok())
+        .filter(|name| is_loopback_driver(name))
+        .map(|name| AudioDevice {
+            backend: "coreaudio",
+            kind: "system",
+            id: name.clone(),
+            is_default: false,
+            name,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain_mix() -> MixOptions {
+        MixOptions {
+            mic_boost: 1.0,
+            noise_suppression: false,
+            echo_cancellation: false,
+            auto_gain: None,
+            split_stereo: false,
+        }
+    }
+
+    #[test]
+    fn mixes_sources_with_different_rates_and_channels() {
+        let system = SourceQueue::new(48000, 2, 16000, None);
+        let mic = SourceQueue::new(44100, 1, 16000, None);
+        let mut mixer = StreamMixer::new(Some(system), Some(mic), 16000, plain_mix());
+
+        let mut output = Vec::new();
+        for _ in 0..10 {
+            mixer.push(Source::System, &[0.1; 9600]);
+            mixer.push(Source::Microphone, &[0.1; 4410]);
+            output.extend(mixer.take_ready());
+        }
+        output.extend(mixer.flush());
+
+        assert_eq!(output.len(), 16000);
+        assert!(output.iter().all(|&s| (s - 6553).abs() <= 1));
+    }
+
+    #[test]
+    fn fills_silent_loopback_from_the_clock() {
+        let system = SourceQueue::new(48000, 2, 16000, None);
+        let mic = SourceQueue::new(16000, 1, 16000, None);
+        let mut mixer = StreamMixer::new(Some(system), Some(mic), 16000, plain_mix());
+
+        // Two seconds of speech while nothing plays
+        mixer.push(Source::Microphone, &[0.2; 32000]);
+        assert!(mixer.take_ready().is_empty());
+
+        mixer.fill_loopback_gap(Duration::from_secs(2));
+        let output = mixer.take_ready();
+        assert_eq!(output.len(), 24000);
+        assert!(output.iter().all(|&s| (s - 6553).abs() <= 1));
+    }
+
+    #[test]
+    fn records_the_microphone_alone() {
+        let mic = SourceQueue::new(16000, 2, 16000, None);
+        let mut mixer = StreamMixer::new(None, Some(mic), 16000, plain_mix());
+
+        mixer.push(Source::Microphone, &[0.5; 16000]);
+        mixer.fill_loopback_gap(Duration::from_secs(5));
+        let mut output = mixer.take_ready();
+        output.extend(mixer.flush());
+        assert_eq!(output.len(), 8000);
+    }
+}
@@ -1,7 +1,100 @@
 //! Audio mixing utilities
 //!
 //! Provides functions for combining multiple audio streams into one,
-//! used by the PipeWire backend to mix system audio and microphone.
+//! used by the PipeWire, JACK, and cpal loopback backends to mix system audio and microphone.
+
+use super::echo::EchoCanceller;
+use super::ChannelLayout;
+use crate::config::Settings;
+
+/// Level below which a block counts as silence and leaves the AGC level alone
+const AGC_GATE_DBFS: f32 = -50.0;
+/// Length of the blocks the AGC measures and ramps gain over
+const AGC_BLOCK_SECS: f32 = 0.01;
+/// Time constant of the AGC level estimate
+const AGC_TIME_CONSTANT_SECS: f32 = 1.5;
+
+/// Target level for automatic gain control
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GainTarget {
+    /// Desired RMS level of each source in dBFS
+    pub target_dbfs: f32,
+    /// Largest boost or cut applied to a source in dB
+    pub max_gain_db: f32,
+}
+
+/// Settings that control how the microphone is combined with system audio
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    not(any(feature = "pipewire", feature = "jack", windows, target_os = "macos")),
+    allow(dead_code)
+)]
+pub(crate) struct MixOptions {
+    /// Static microphone multiplier, used when AGC is off
+    pub(crate) mic_boost: f32,
+    /// Run the microphone through the noise suppressor before mixing
+    pub(crate) noise_suppression: bool,
+    /// Cancel system audio picked up by the microphone before mixing
+    pub(crate) echo_cancellation: bool,
+    /// Level both sources automatically instead of applying `mic_boost`
+    pub(crate) auto_gain: Option<GainTarget>,
+    /// Write system audio and microphone to the left and right channels
+    pub(crate) split_stereo: bool,
+}
+
+#[cfg_attr(
+    not(any(feature = "pipewire", feature = "jack", windows, target_os = "macos")),
+    allow(dead_code)
+)]
+impl MixOptions {
+    pub(crate) fn from_settings(settings: &Settings) -> Self {
+        let audio = &settings.audio;
+        Self {
+            mic_boost: audio.mic_boost,
+            noise_suppression: audio.noise_suppression,
+            echo_cancellation: audio.echo_cancellation,
+            auto_gain: audio.agc.then_some(GainTarget {
+                target_dbfs: audio.agc_target_dbfs,
+                max_gain_db: audio.agc_max_gain_db,
+            }),
+            split_stereo: audio.channel_layout == ChannelLayout::SplitStereo,
+        }
+    }
+
+    /// Options for a recording of the given sources
+    ///
+    /// Split stereo only applies when both sources are recorded; a single
+    /// source is written as mono.
+    pub(crate) fn for_sources(self, system: bool, mic: bool) -> Self {
+        Self {
+            split_stereo: self.split_stereo && system && mic,
+            ..self
+        }
+    }
+
+    /// Channels of the mixed output
+    pub(crate) fn channels(&self) -> u16 {
+        if self.split_stereo {
+            2
+        } else {
+            1
+        }
+    }
+
+    pub(crate) fn mixer(&self, sample_rate: u32) -> AudioMixer {
+        let mut mixer = AudioMixer::new(sample_rate, self.mic_boost);
+        if self.echo_cancellation {
+            mixer = mixer.with_echo_cancellation();
+        }
+        if self.split_stereo {
+            mixer = mixer.with_split_stereo();
+        }
+        match self.auto_gain {
+            Some(target) => mixer.with_auto_gain(target),
+            None => mixer,
+        }
+    }
+}
 
 /// Audio mixer for combining multiple streams
 pub struct AudioMixer {
@@ -9,6 +102,12 @@ pub struct AudioMixer {
     sample_rate: u32,
     /// Microphone boost factor (1.0 = no boost)
     mic_boost: f32,
+    /// Per-source automatic gain control (system, microphone)
+    auto_gain: Option<(AutoGain, AutoGain)>,
+    /// Removes system audio that reached the microphone through speakers
+    echo_canceller: Option<EchoCanceller>,
+    /// Output interleaved stereo with system audio left and microphone right
+    split_stereo: bool,
 }
 
 impl AudioMixer {
@@ -21,43 +120,103 @@ impl AudioMixer {
         Self {
             sample_rate,
             mic_boost,
+            auto_gain: None,
+            echo_canceller: None,
+            split_stereo: false,
         }
     }
 
+    /// Level each source towards `target` before mixing
+    ///
+    /// Replaces the static `mic_boost` multiplier, so a loud system track
+    /// and a quiet microphone end up at comparable levels.
+    pub fn with_auto_gain(mut self, target: GainTarget) -> Self {
+        self.auto_gain = Some((
+            AutoGain::new(self.sample_rate, target),
+            AutoGain::new(self.sample_rate, target),
+        ));
+        self
+    }
+
+    /// Subtract the echo of system audio from the microphone before mixing
+    ///
+    /// Both buffers passed to [`mix`](Self::mix) must start at the same
+    /// instant, and the filter needs a few seconds of system audio to adapt.
+    pub fn with_echo_cancellation(mut self) -> Self {
+        self.echo_canceller = Some(EchoCanceller::new(self.sample_rate));
+        self
+    }
+
+    /// Keep the sources apart: system audio left, microphone right
+    ///
+    /// [`mix`](Self::mix) then returns interleaved stereo frames, so players
+    /// can pan between the speakers. Transcription downmixes the file.
+    pub fn with_split_stereo(mut self) -> Self {
+        self.split_stereo = true;
+        self
+    }
+
     /// Get the target sample rate
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
     }
 
+    /// Channels of the mixed output
+    pub fn channels(&self) -> u16 {
+        if self.split_stereo {
+            2
+        } else {
+            1
+        }
+    }
+
     /// Mix two audio buffers together
     ///
     /// Combines system audio and microphone input into a single buffer.
-    /// Applies mic boost and prevents clipping.
+    /// Cancels echo if enabled, applies mic boost (or automatic gain control)
+    /// and prevents clipping. With AGC or echo cancellation enabled,
+    /// consecutive calls continue the same state, so streams can be mixed
+    /// chunk by chunk.
     ///
     /// # Arguments
     /// * `system` - System audio samples (what you hear)
     /// * `mic` - Microphone samples (what you say)
     ///
     /// # Returns
-    /// Mixed audio samples
-    pub fn mix(&self, system: &[f32], mic: &[f32]) -> Vec<f32> {
+    /// Mixed audio samples, or interleaved (system, microphone) frames in
+    /// split-stereo mode
+    pub fn mix(&mut self, system: &[f32], mic: &[f32]) -> Vec<f32> {
+        let mic = match self.echo_canceller.as_mut() {
+            Some(canceller) => canceller.process(system, mic),
+            None => mic.to_vec(),
+        };
+        let (system, mic, mic_boost) = match self.auto_gain.as_mut() {
+            Some((system_gain, mic_gain)) => (system_gain.apply(system), mic_gain.apply(&mic), 1.0),
+            None => (system.to_vec(), mic, self.mic_boost),
+        };
+
         let len = system.len().max(mic.len());
-        let mut output = Vec::with_capacity(len);
+        let mut output = Vec::with_capacity(len * self.channels() as usize);
 
         for i in 0..len {
             let sys_sample = system.get(i).copied().unwrap_or(0.0);
-            let mic_sample = mic.get(i).copied().unwrap_or(0.0) * self.mic_boost;
+            let mic_sample = mic.get(i).copied().unwrap_or(0.0) * mic_boost;
 
-            // Simple additive mixing with soft clipping
-            let mixed = sys_sample + mic_sample;
-            output.push(soft_clip(mixed));
+            if self.split_stereo {
+                output.push(soft_clip(sys_sample));
+                output.push(soft_clip(mic_sample));
+            } else {
+                // Simple additive mixing with soft clipping
+                let mixed = sys_sample + mic_sample;
+                output.push(soft_clip(mixed));
+            }
         }
 
         output
     }
 
     /// Mix and convert to i16 samples for WAV output
-    pub fn mix_to_i16(&self, system: &[f32], mic: &[f32]) -> Vec<i16> {
+    pub fn mix_to_i16(&mut self, system: &[f32], mic: &[f32]) -> Vec<i16> {
         self.mix(system, mic).into_iter().map(f32_to_i16).collect()
     }
 
@@ -80,39 +239,80 @@ impl AudioMixer {
     /// Note: For production use, consider a proper resampling library.
     /// Linear interpolation is simple but introduces aliasing.
     pub fn resample(&self, samples: &[f32], source_rate: u32) -> Vec<f32> {
-        if source_rate == self.sample_rate {
-            return samples.to_vec();
+        resample(samples, source_rate, self.sample_rate)
+    }
+}
+
+impl Default for AudioMixer {
+    fn default() -> Self {
+        Self::new(16000, 1.2) // 16kHz for Whisper, 20% mic boost
+    }
+}
+
+/// Automatic gain control for one source
+///
+/// Tracks a slow RMS estimate over non-silent blocks and ramps the gain
+/// towards `target / level` within the configured limits.
+struct AutoGain {
+    target_rms: f32,
+    max_gain: f32,
+    block_len: usize,
+    gate_ms: f32,
+    /// Weight of the previous level estimate per block
+    smoothing: f32,
+    /// Mean-square level estimate, `None` until the first non-silent block
+    level_ms: Option<f32>,
+    gain: f32,
+}
+
+impl AutoGain {
+    fn new(sample_rate: u32, target: GainTarget) -> Self {
+        let block_len = ((sample_rate as f32 * AGC_BLOCK_SECS) as usize).max(1);
+        Self {
+            target_rms: db_to_amplitude(target.target_dbfs),
+            max_gain: db_to_amplitude(target.max_gain_db.abs()),
+            block_len,
+            gate_ms: db_to_amplitude(AGC_GATE_DBFS).powi(2),
+            smoothing: (-AGC_BLOCK_SECS / AGC_TIME_CONSTANT_SECS).exp(),
+            level_ms: None,
+            gain: 1.0,
         }
+    }
 
-        let ratio = source_rate as f64 / self.sample_rate as f64;
-        let output_len = ((samples.len() as f64) / ratio).ceil() as usize;
-        let mut output = Vec::with_capacity(output_len);
+    fn apply(&mut self, samples: &[f32]) -> Vec<f32> {
+        let mut output = Vec::with_capacity(samples.len());
 
-        for i in 0..output_len {
-            let src_pos = i as f64 * ratio;
-            let src_idx = src_pos.floor() as usize;
-            let frac = src_pos.fract() as f32;
+        for block in samples.chunks(self.block_len) {
+            let mean_square = block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32;
+            if mean_square > self.gate_ms {
+                self.level_ms = Some(match self.level_ms {
+                    Some(level) => self.smoothing * level + (1.0 - self.smoothing) * mean_square,
+                    None => mean_square,
+                });
+            }
 
-            let sample = if src_idx + 1 < samples.len() {
-                // Linear interpolation between adjacent samples
-                samples[src_idx] * (1.0 - frac) + samples[src_idx + 1] * frac
-            } else if src_idx < samples.len() {
-                samples[src_idx]
-            } else {
-                0.0
+            let target_gain = match self.level_ms {
+                Some(level) => {
+                    (self.target_rms / level.sqrt()).clamp(1.0 / self.max_gain, self.max_gain)
+                }
+                None => self.gain,
             };
 
-            output.push(sample);
+            // Ramp across the block so gain changes don't click
+            let step = (target_gain - self.gain) / block.len() as f32;
+            for &sample in block {
+                self.gain += step;
+                output.push(sample * self.gain);
+            }
+            self.gain = target_gain;
         }
 
         output
     }
 }
 
-impl Default for AudioMixer {
-    fn default() -> Self {
-        Self::new(16000, 1.2) // 16kHz for Whisper, 20% mic boost
-    }
+fn db_to_amplitude(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
 }
 
 /// Soft clipping function to prevent harsh distortion
@@ -127,11 +327,70 @@ fn soft_clip(sample: f32) -> f32 {
 }
 
 /// Convert f32 sample (-1.0 to 1.0) to i16
-fn f32_to_i16(sample: f32) -> i16 {
+pub(crate) fn f32_to_i16(sample: f32) -> i16 {
     let clamped = sample.clamp(-1.0, 1.0);
     (clamped * 32767.0) as i16
 }
 
+/// Resample `samples` from `source_rate` to `target_rate` with linear interpolation
+pub fn resample(samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<f32> {
+    if source_rate == target_rate {
+        return samples.to_vec();
+    }
+
+    let ratio = source_rate as f64 / target_rate as f64;
+    let output_len = ((samples.len() as f64) / ratio).ceil() as usize;
+    let mut output = Vec::with_capacity(output_len);
+
+    for i in 0..output_len {
+        let src_pos = i as f64 * ratio;
+        let src_idx = src_pos.floor() as usize;
+        let frac = src_pos.fract() as f32;
+
+        let sample = if src_idx + 1 < samples.len() {
+            // Linear interpolation between adjacent samples
+            samples[src_idx] * (1.0 - frac) + samples[src_idx + 1] * frac
+        } else if src_idx < samples.len() {
+            samples[src_idx]
+        } else {
+            0.0
+        };
+
+        output.push(sample);
+    }
+
+    output
+}
+
+/// Resample interleaved audio with `channels` channels, one channel at a time
+#[cfg_attr(not(any(feature = "jack", feature = "pipewire")), allow(dead_code))]
+pub fn resample_interleaved(
+    samples: &[f32],
+    channels: usize,
+    source_rate: u32,
+    target_rate: u32,
+) -> Vec<f32> {
+    if channels <= 1 || source_rate == target_rate {
+        return resample(samples, source_rate, target_rate);
+    }
+
+    let resampled: Vec<Vec<f32>> = (0..channels)
+        .map(|channel| {
+            let samples: Vec<f32> = samples
+                .iter()
+                .skip(channel)
+                .step_by(channels)
+                .copied()
+                .collect();
+            resample(&samples, source_rate, target_rate)
+        })
+        .collect();
+    let frames = resampled.iter().map(Vec::len).min().unwrap_or(0);
+    (0..frames)
+        .flat_map(|frame| resampled.iter().map(move |channel| channel[frame]))
+        .collect()
+}
+
 /// Convert i16 sample to f32 (-1.0 to 1.0)
 #[allow(dead_code)]
 pub fn i16_to_f32(sample: i16) -> f32 {
@@ -144,7 +403,7 @@ mod tests {
 
     #[test]
     fn test_mix_equal_length() {
-        let mixer = AudioMixer::new(16000, 1.0);
+        let mut mixer = AudioMixer::new(16000, 1.0);
         let sys = vec![0.5, 0.3, -0.2];
         let mic = vec![0.2, -0.1, 0.4];
         let result = mixer.mix(&sys, &mic);
@@ -157,7 +416,7 @@ mod tests {
 
     #[test]
     fn test_mix_different_length() {
-        let mixer = AudioMixer::new(16000, 1.0);
+        let mut mixer = AudioMixer::new(16000, 1.0);
         let sys = vec![0.5, 0.3];
         let mic = vec![0.2, -0.1, 0.4, 0.1];
         let result = mixer.mix(&sys, &mic);
@@ -165,6 +424,49 @@ mod tests {
         assert_eq!(result.len(), 4);
     }
 
+    #[test]
+    fn test_auto_gain_levels_loud_system_and_quiet_mic() {
+        let target = GainTarget {
+            target_dbfs: -20.0,
+            max_gain_db: 30.0,
+        };
+        let mut system_gain = AutoGain::new(16000, target);
+        let mut mic_gain = AutoGain::new(16000, target);
+
+        let tone = |amplitude: f32| -> Vec<f32> {
+            (0..16000 * 4)
+                .map(|i| amplitude * (i as f32 * 0.05).sin())
+                .collect()
+        };
+        let system = system_gain.apply(&tone(0.8));
+        let mic = mic_gain.apply(&tone(0.02));
+
+        let rms_db = |samples: &[f32]| {
+            let tail = &samples[samples.len() - 16000..];
+            let ms = tail.iter().map(|s| s * s).sum::<f32>() / tail.len() as f32;
+            10.0 * ms.log10()
+        };
+        assert!((rms_db(&system) + 20.0).abs() < 1.0);
+        assert!((rms_db(&mic) + 20.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_auto_gain_respects_max_gain_and_ignores_silence() {
+        let target = GainTarget {
+            target_dbfs: -20.0,
+            max_gain_db: 6.0,
+        };
+        let mut gain = AutoGain::new(16000, target);
+
+        let silence = gain.apply(&[0.0; 1600]);
+        assert!(silence.iter().all(|s| *s == 0.0));
+        assert_eq!(gain.gain, 1.0);
+
+        let quiet: Vec<f32> = (0..16000).map(|i| 0.01 * (i as f32 * 0.05).sin()).collect();
+        gain.apply(&quiet);
+        assert!((gain.gain - db_to_amplitude(6.0)).abs() < 1e-3);
+    }
+
     #[test]
     fn test_soft_clip() {
         assert!((soft_clip(0.3) - 0.3).abs() < 0.001);
@@ -190,4 +492,45 @@ mod tests {
 
         assert_eq!(result, samples);
     }
+
+    #[test]
+    fn mix_options_use_auto_gain_when_enabled() {
+        let mut settings = Settings::default();
+        assert!(MixOptions::from_settings(&settings).auto_gain.is_none());
+
+        settings.audio.agc = true;
+        settings.audio.agc_target_dbfs = -18.0;
+        let target = MixOptions::from_settings(&settings).auto_gain.unwrap();
+        assert_eq!(target.target_dbfs, -18.0);
+    }
+
+    #[test]
+    fn split_stereo_keeps_each_source_on_its_own_channel() {
+        let mut settings = Settings::default();
+        settings.audio.channel_layout = ChannelLayout::SplitStereo;
+        let options = MixOptions::from_settings(&settings);
+        assert_eq!(options.for_sources(true, false).channels(), 1);
+
+        let mut mixer = options.for_sources(true, true).mixer(16000);
+        assert_eq!(mixer.channels(), 2);
+        let mixed = mixer.mix(&[0.5, 0.5], &[-0.25]);
+        assert_eq!(mixed.len(), 4);
+        assert!((mixed[0] - soft_clip(0.5)).abs() < 1e-6);
+        assert!((mixed[1] - soft_clip(-0.25 * settings.audio.mic_boost)).abs() < 1e-6);
+        assert_eq!(mixed[3], 0.0);
+
+        let resampled = resample_interleaved(&[0.5, -0.5, 0.5, -0.5], 2, 16000, 8000);
+        assert_eq!(resampled, [0.5, -0.5]);
+    }
+
+    #[test]
+    fn echo_cancellation_leaves_the_mix_length_alone() {
+        let mut settings = Settings::default();
+        settings.audio.echo_cancellation = true;
+        let mut mixer = MixOptions::from_settings(&settings).mixer(16000);
+
+        let system = vec![0.25; 320];
+        let mic = vec![0.1; 480];
+        assert_eq!(mixer.mix(&system, &mic).len(), 480);
+    }
 }
@@ -0,0 +1,224 @@
+//! Mock audio capture that synthesizes a known signal
+//!
+//! Writes a 440 Hz tone, or `audio.mock_fixture` played in a loop, in real
+//! time to the output file. Needs no sound server or devices, so it drives
+//! the daemon → record → stop → transcribe path in integration tests, and
+//! tells capture problems apart from the rest of the pipeline when
+//! troubleshooting with `minutes daemon start --backend mock`.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::config::Settings;
+
+use super::encoder::CaptureSink;
+use super::mixer::{f32_to_i16, resample};
+use super::AudioCapture;
+
+/// Frequency of the synthesized tone
+const TONE_HZ: f32 = 440.0;
+
+/// Amplitude of the synthesized tone
+const TONE_LEVEL: f32 = 0.3;
+
+/// How often the writer thread catches up with the wall clock
+const WRITE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Audio capture that writes a synthesized signal instead of recording
+pub struct MockCapture {
+    /// Sample rate of the output file
+    sample_rate: u32,
+
+    /// WAV file looped instead of the tone (empty = tone)
+    fixture: PathBuf,
+
+    /// Whether recording is active
+    recording: Arc<AtomicBool>,
+
+    /// Thread writing samples until recording stops
+    writer: Option<JoinHandle<Result<()>>>,
+}
+
+impl MockCapture {
+    pub fn new(settings: &Settings) -> Result<Self> {
+        Ok(Self {
+            sample_rate: settings.audio.sample_rate,
+            fixture: PathBuf::from(settings.audio.mock_fixture.trim()),
+            recording: Arc::new(AtomicBool::new(false)),
+            writer: None,
+        })
+    }
+
+    /// One loop of the signal at the output sample rate
+    fn signal(&self) -> Result<Vec<i16>> {
+        if self.fixture.as_os_str().is_empty() {
+            return Ok(tone(self.sample_rate));
+        }
+
+        let samples = load_fixture(&self.fixture, self.sample_rate)?;
+        if samples.is_empty() {
+            anyhow::bail!("Mock fixture {} has no audio", self.fixture.display());
+        }
+        Ok(samples)
+    }
+}
+
+impl AudioCapture for MockCapture {
+    fn start(&mut self, output_path: &Path) -> Result<()> {
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let signal = self.signal()?;
+        let mut sink = CaptureSink::create(output_path, self.sample_rate, 1, None)?;
+
+        self.recording.store(true, Ordering::SeqCst);
+        let recording = self.recording.clone();
+        let sample_rate = u64::from(self.sample_rate);
+
+        self.writer = Some(std::thread::spawn(move || {
+            let started = Instant::now();
+            let mut written = 0u64;
+            loop {
+                // Check before writing so the samples up to the stop are kept
+                let stopping = !recording.load(Ordering::SeqCst);
+                let due = started.elapsed().as_millis() as u64 * sample_rate / 1000;
+                let samples: Vec<i16> = (written..due)
+                    .map(|i| signal[(i % signal.len() as u64) as usize])
+                    .collect();
+                sink.write_samples(&samples)?;
+                written = due;
+
+                if stopping {
+                    break;
+                }
+                std::thread::sleep(WRITE_INTERVAL);
+            }
+            sink.finalize()
+        }));
+
+        tracing::info!(
+            "mock: Writing {} to {}",
+            if self.fixture.as_os_str().is_empty() {
+                "a test tone".to_string()
+            } else {
+                self.fixture.display().to_string()
+            },
+            output_path.display()
+        );
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.recording.store(false, Ordering::SeqCst);
+
+        if let Some(writer) = self.writer.take() {
+            writer
+                .join()
+                .map_err(|_| anyhow::anyhow!("Mock capture thread panicked"))??;
+            tracing::info!("mock: Audio recording stopped");
+        }
+        Ok(())
+    }
+
+    fn is_recording(&self) -> bool {
+        self.recording.load(Ordering::SeqCst)
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "mock"
+    }
+}
+
+impl Drop for MockCapture {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// One second of the test tone
+fn tone(sample_rate: u32) -> Vec<i16> {
+    (0..sample_rate)
+        .map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            f32_to_i16((2.0 * std::f32::consts::PI * TONE_HZ * t).sin() * TONE_LEVEL)
+        })
+        .collect()
+}
+
+/// Read a WAV fixture as mono 16-bit samples at `sample_rate`
+fn load_fixture(path: &Path, sample_rate: u32) -> Result<Vec<i16>> {
+    let mut reader = hound::WavReader::open(path)
+        .with_context(|| format!("Failed to open mock fixture {}", path.display()))?;
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let channels = usize::from(spec.channels.max(1));
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok(resample(&mono, spec.sample_rate, sample_rate)
+        .into_iter()
+        .map(f32_to_i16)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_the_fixture_for_as_long_as_it_records() {
+        let tmp = tempfile::tempdir().unwrap();
+        let fixture = tmp.path().join("fixture.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&fixture, spec).unwrap();
+        for i in 0..800 {
+            writer.write_sample((i * 10) as i16).unwrap();
+            writer.write_sample((i * 10) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut settings = Settings::default();
+        settings.audio.sample_rate = 16000;
+        settings.audio.mock_fixture = fixture.to_string_lossy().to_string();
+        let mut capture = MockCapture::new(&settings).unwrap();
+
+        let output = tmp.path().join("out.wav");
+        capture.start(&output).unwrap();
+        assert!(capture.is_recording());
+        std::thread::sleep(Duration::from_millis(300));
+        capture.stop().unwrap();
+
+        let mut reader = hound::WavReader::open(&output).unwrap();
+        assert_eq!(reader.spec().sample_rate, 16000);
+        assert_eq!(reader.spec().channels, 1);
+        let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+        assert!((4800..8000).contains(&samples.len()), "{}", samples.len());
+        assert_eq!(samples[0], 0);
+        assert!((samples[2] - 10).abs() <= 1);
+        // The 0.1 s fixture loops
+        assert_eq!(samples[1600], 0);
+    }
+}
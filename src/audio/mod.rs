@@ -3,25 +3,56 @@
 //! Provides unified audio capture with multiple backends:
 //! - PipeWire (Linux, primary) - captures system audio + microphone
 //! - cpal (fallback) - cross-platform, microphone only
+//! - JACK (optional) - explicit port connections for pro-audio setups
+//! - WASAPI (Windows) - loopback system audio + microphone through cpal
+//! - CoreAudio (macOS) - loopback driver (e.g. BlackHole) + microphone through cpal
+//! - Mock - synthesized test signal for integration tests and troubleshooting
 
 mod cpal_capture;
+mod denoise;
+mod devices;
+mod dsp;
+mod echo;
 mod encoder;
+mod levels;
 mod mixer;
+mod mock_capture;
+mod mp3;
+mod probe;
+mod wav_repair;
 
+#[cfg(feature = "jack")]
+mod jack_capture;
+#[cfg(any(windows, target_os = "macos"))]
+mod loopback_capture;
 #[cfg(feature = "pipewire")]
 mod pipewire_capture;
 
 pub use cpal_capture::CpalCapture;
-pub use encoder::OggEncoder;
-pub use mixer::AudioMixer;
+pub use denoise::NoiseSuppressor;
+pub use devices::{list_devices, AudioDevice};
+pub use echo::EchoCanceller;
+pub use encoder::{decode_ogg_opus, FlacEncoder, FlacWriter, OggEncoder, StreamingOggWriter};
+pub use levels::{LevelMeter, LevelProblem, TrackLevels};
+pub use mixer::{AudioMixer, GainTarget};
+pub use mock_capture::MockCapture;
+pub use mp3::write_mp3;
+pub use probe::{audio_duration_secs, NATIVE_AUDIO_EXTENSIONS};
+pub use wav_repair::{is_wav_path, repair_wav, wav_needs_repair};
 
+pub(crate) use dsp::fft;
+
+#[cfg(feature = "jack")]
+pub use jack_capture::JackCapture;
+#[cfg(any(windows, target_os = "macos"))]
+pub use loopback_capture::LoopbackCapture;
 #[cfg(feature = "pipewire")]
 pub use pipewire_capture::PipeWireCapture;
 #[cfg(feature = "pipewire")]
 pub(crate) use pipewire_capture::{resolve_capture_targets, TargetResolutionMethod};
 
 use anyhow::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::config::Settings;
 
@@ -36,6 +67,75 @@ pub enum AudioBackend {
     PipeWire,
     /// Force cpal backend (cross-platform)
     Cpal,
+    /// JACK server with explicitly connected ports (requires the jack feature)
+    Jack,
+    /// WASAPI loopback for system audio plus the microphone (Windows only)
+    Wasapi,
+    /// Loopback driver such as BlackHole plus the microphone (macOS only)
+    CoreAudio,
+    /// Synthesized test signal instead of real devices
+    Mock,
+}
+
+impl AudioBackend {
+    /// Name of the backend as written in the config file
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::PipeWire => "pipewire",
+            Self::Cpal => "cpal",
+            Self::Jack => "jack",
+            Self::Wasapi => "wasapi",
+            Self::CoreAudio => "coreaudio",
+            Self::Mock => "mock",
+        }
+    }
+}
+
+impl std::str::FromStr for AudioBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        [
+            Self::Auto,
+            Self::PipeWire,
+            Self::Cpal,
+            Self::Jack,
+            Self::Wasapi,
+            Self::CoreAudio,
+            Self::Mock,
+        ]
+        .into_iter()
+        .find(|backend| backend.as_str() == s.to_ascii_lowercase())
+        .ok_or_else(|| {
+            format!(
+                "unknown audio backend '{}' (expected auto, pipewire, cpal, jack, wasapi, coreaudio, or mock)",
+                s
+            )
+        })
+    }
+}
+
+/// Channel layout of the saved recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChannelLayout {
+    /// System audio and microphone mixed into one channel
+    #[default]
+    Mono,
+    /// System audio on the left channel, microphone on the right
+    SplitStereo,
+}
+
+/// Storage format for recordings compressed after transcription
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    /// Lossy OGG Opus, smallest files
+    #[default]
+    Ogg,
+    /// Lossless FLAC for archival
+    Flac,
 }
 
 /// Unified audio capture trait
@@ -53,6 +153,37 @@ pub trait AudioCapture {
 
     /// Get capture backend name for logging
     fn backend_name(&self) -> &'static str;
+
+    /// File extension the backend writes for the configured output format
+    ///
+    /// "ogg" when the backend streams OGG Opus during capture, "wav" otherwise.
+    fn output_extension(&self) -> &'static str {
+        "wav"
+    }
+
+    /// Separate (system, microphone) tracks kept by the last recording
+    ///
+    /// Only backends that capture both sources independently report tracks,
+    /// and only when `audio.keep_separate_tracks` is enabled.
+    fn track_paths(&self) -> Option<(PathBuf, PathBuf)> {
+        None
+    }
+
+    /// Warnings about the levels of the last recording's tracks
+    ///
+    /// Reports a silent, clipped, or very quiet microphone (and clipped system
+    /// audio) found while the recording was finalized.
+    fn level_warnings(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Move capture to the current default devices if they changed
+    ///
+    /// Called periodically while recording. Backends that pin the devices
+    /// resolved at start keep the default no-op.
+    fn refresh_targets(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Check if PipeWire is available on this system
@@ -68,8 +199,9 @@ pub fn pipewire_available() -> bool {
 
 /// Create an audio capture instance based on settings and platform
 ///
-/// Uses PipeWire on Linux if available (for system audio + mic capture),
-/// falls back to cpal otherwise.
+/// Uses PipeWire on Linux if available, WASAPI loopback on Windows, and a
+/// loopback driver on macOS when one is installed (for system audio + mic
+/// capture), falls back to cpal otherwise.
 pub fn create_capture(settings: &Settings) -> Result<Box<dyn AudioCapture>> {
     match settings.audio.backend {
         AudioBackend::Auto => {
@@ -80,6 +212,22 @@ pub fn create_capture(settings: &Settings) -> Result<Box<dyn AudioCapture>> {
                     return Ok(Box::new(PipeWireCapture::new(settings)?));
                 }
             }
+            #[cfg(windows)]
+            {
+                if settings.audio.capture_system {
+                    tracing::info!("Using WASAPI audio backend (auto-detected)");
+                    return Ok(Box::new(LoopbackCapture::wasapi(settings)?));
+                }
+            }
+            #[cfg(target_os = "macos")]
+            {
+                if settings.audio.capture_system
+                    && LoopbackCapture::loopback_driver(settings).is_some()
+                {
+                    tracing::info!("Using CoreAudio audio backend (auto-detected)");
+                    return Ok(Box::new(LoopbackCapture::core_audio(settings)?));
+                }
+            }
             tracing::info!("Using cpal audio backend (fallback)");
             Ok(Box::new(CpalCapture::new(settings)?))
         }
@@ -98,5 +246,42 @@ pub fn create_capture(settings: &Settings) -> Result<Box<dyn AudioCapture>> {
             tracing::info!("Using cpal audio backend (forced)");
             Ok(Box::new(CpalCapture::new(settings)?))
         }
+        AudioBackend::Jack => {
+            #[cfg(feature = "jack")]
+            {
+                tracing::info!("Using JACK audio backend (forced)");
+                Ok(Box::new(JackCapture::new(settings)?))
+            }
+            #[cfg(not(feature = "jack"))]
+            {
+                anyhow::bail!("JACK backend requires building with the jack feature")
+            }
+        }
+        AudioBackend::Wasapi => {
+            #[cfg(windows)]
+            {
+                tracing::info!("Using WASAPI audio backend (forced)");
+                Ok(Box::new(LoopbackCapture::wasapi(settings)?))
+            }
+            #[cfg(not(windows))]
+            {
+                anyhow::bail!("WASAPI backend is only available on Windows")
+            }
+        }
+        AudioBackend::CoreAudio => {
+            #[cfg(target_os = "macos")]
+            {
+                tracing::info!("Using CoreAudio audio backend (forced)");
+                Ok(Box::new(LoopbackCapture::core_audio(settings)?))
+            }
+            #[cfg(not(target_os = "macos"))]
+            {
+                anyhow::bail!("CoreAudio backend is only available on macOS")
+            }
+        }
+        AudioBackend::Mock => {
+            tracing::info!("Using mock audio backend (forced)");
+            Ok(Box::new(MockCapture::new(settings)?))
+        }
     }
 }
@@ -0,0 +1,84 @@
+//! MP3 writer for sharing recordings
+//!
+//! Audio is encoded with LAME through `mp3lame-encoder`, which builds the
+//! library from source, so no ffmpeg is needed. Recordings are speech, so a
+//! constant 64 kbps per channel keeps them small while staying clear.
+
+use anyhow::{Context, Result};
+use mp3lame_encoder::{Bitrate, Builder, FlushNoGap, InterleavedPcm, MonoPcm, Quality};
+use std::path::Path;
+
+/// Write interleaved 16-bit PCM to an MP3 file
+pub fn write_mp3(path: &Path, sample_rate: u32, channels: u16, samples: &[i16]) -> Result<()> {
+    let data = encode_mp3(sample_rate, channels, samples)?;
+    std::fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Encode interleaved 16-bit PCM as MP3 in memory
+fn encode_mp3(sample_rate: u32, channels: u16, samples: &[i16]) -> Result<Vec<u8>> {
+    if !(1..=2).contains(&channels) {
+        anyhow::bail!(
+            "MP3 export supports mono or stereo audio, not {} channels",
+            channels
+        );
+    }
+
+    let mut builder = Builder::new().context("Failed to start the MP3 encoder")?;
+    builder
+        .set_num_channels(channels as u8)
+        .map_err(|error| anyhow::anyhow!("Failed to set MP3 channels: {}", error))?;
+    builder.set_sample_rate(sample_rate).map_err(|error| {
+        anyhow::anyhow!(
+            "MP3 export does not support a sample rate of {} Hz: {}",
+            sample_rate,
+            error
+        )
+    })?;
+    builder
+        .set_brate(if channels == 2 {
+            Bitrate::Kbps128
+        } else {
+            Bitrate::Kbps64
+        })
+        .map_err(|error| anyhow::anyhow!("Failed to set MP3 bitrate: {}", error))?;
+    builder
+        .set_quality(Quality::Good)
+        .map_err(|error| anyhow::anyhow!("Failed to set MP3 quality: {}", error))?;
+    let mut encoder = builder
+        .build()
+        .map_err(|error| anyhow::anyhow!("Failed to start the MP3 encoder: {}", error))?;
+
+    let mut data = Vec::new();
+    let encoded = if channels == 2 {
+        encoder.encode_to_vec(InterleavedPcm(samples), &mut data)
+    } else {
+        encoder.encode_to_vec(MonoPcm(samples), &mut data)
+    };
+    encoded.map_err(|error| anyhow::anyhow!("Failed to encode MP3: {}", error))?;
+    encoder
+        .flush_to_vec::<FlushNoGap>(&mut data)
+        .map_err(|error| anyhow::anyhow!("Failed to finish MP3: {}", error))?;
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_mpeg_audio_frames() {
+        let samples: Vec<i16> = (0..16000)
+            .map(|i| ((i as f32 * 440.0 * std::f32::consts::TAU / 16000.0).sin() * 8000.0) as i16)
+            .collect();
+        let data = encode_mp3(16000, 1, &samples).unwrap();
+
+        // Frame sync, then MPEG-2 Layer III for 16 kHz
+        assert_eq!(data[0], 0xff);
+        assert_eq!(data[1] & 0xfe, 0xf2);
+        // 64 kbps for a second of audio, give or take the padding frames
+        assert!((6000..12000).contains(&data.len()), "{}", data.len());
+
+        assert!(encode_mp3(16000, 3, &samples).is_err());
+    }
+}
@@ -8,14 +8,22 @@
 
 use anyhow::{Context, Result};
 use hound::{WavReader, WavSpec, WavWriter};
+use std::collections::VecDeque;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, ChildStdout, Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::Instant;
 
 use crate::config::Settings;
 
-use super::{AudioCapture, AudioMixer};
+use super::mixer::{f32_to_i16, i16_to_f32, resample_interleaved, MixOptions};
+use super::{
+    AudioCapture, AudioDevice, AudioMixer, LevelMeter, NoiseSuppressor, StreamingOggWriter,
+    TrackLevels,
+};
 
 /// PipeWire audio capture
 ///
@@ -30,8 +38,12 @@ pub struct PipeWireCapture {
     capture_system: bool,
     /// Whether to capture microphone input
     capture_microphone: bool,
-    /// Microphone boost applied during software mixing
-    mic_boost: f32,
+    /// Configured system monitor target (empty = default sink)
+    system_device: String,
+    /// Configured microphone target (empty = default source)
+    mic_device: String,
+    /// How system audio and microphone are combined
+    mix: MixOptions,
     /// Whether recording is active
     recording: Arc<AtomicBool>,
     /// pw-record process handle for system monitor capture
@@ -42,6 +54,70 @@ pub struct PipeWireCapture {
     output_path: Option<PathBuf>,
     /// Temporary microphone capture path used when dual capture is active
     mic_path: Option<PathBuf>,
+    /// Keep system and microphone tracks on disk next to the mixed file
+    keep_separate_tracks: bool,
+    /// System track path used when separate tracks are kept
+    system_path: Option<PathBuf>,
+    /// Separate (system, microphone) tracks from the last finished recording
+    tracks: Option<(PathBuf, PathBuf)>,
+    /// Level warnings for the last finished recording
+    level_warnings: Vec<String>,
+    /// Notes about sources that changed sample format mid-recording
+    format_warnings: Vec<String>,
+    /// When the current recording started
+    started_at: Option<Instant>,
+    /// Encode to OGG Opus during capture instead of writing WAV files
+    stream_to_ogg: bool,
+    /// OGG Opus bitrate when streaming
+    ogg_bitrate: u32,
+    /// Mixer/encoder thread used when streaming to OGG, returning level warnings
+    stream_thread: Option<JoinHandle<Result<Vec<String>>>>,
+    /// Sender for readers started after a device switch while streaming
+    stream_tx: Option<mpsc::Sender<(TargetKind, Vec<i16>)>>,
+    /// System monitor source state while recording
+    system_source: SourceState,
+    /// Microphone source state while recording
+    mic_source: SourceState,
+}
+
+/// Where one pw-record source is recording from and to
+///
+/// When a source follows the default device and the user switches devices
+/// mid-recording, a new pw-record writes a continuation part that is appended
+/// to `file` when the recording stops.
+#[derive(Debug, Default)]
+struct SourceState {
+    /// Node the source currently records from
+    target: Option<String>,
+    /// File the first pw-record writes (`None` when streaming)
+    file: Option<PathBuf>,
+    /// Continuation files written after device switches, in order
+    parts: Vec<PathBuf>,
+    /// Format the node was last seen running at
+    format: Option<NodeFormat>,
+}
+
+impl SourceState {
+    fn new(target: &str, file: Option<&Path>) -> Self {
+        Self {
+            target: Some(target.to_string()),
+            file: file.map(Path::to_path_buf),
+            parts: Vec::new(),
+            format: None,
+        }
+    }
+}
+
+/// Sample format a PipeWire node is running at
+///
+/// Bluetooth headsets drop to 8 or 16kHz mono when they switch from A2DP to
+/// the headset (HFP/HSP) profile, for example when a call app opens the mic.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct NodeFormat {
+    rate: u32,
+    channels: u16,
+    /// The node is a Bluetooth device in a headset profile
+    headset_profile: bool,
 }
 
 const SYSTEM_TARGET_FALLBACK: &str = "@DEFAULT_AUDIO_SINK.monitor";
@@ -66,6 +142,7 @@ impl TargetKind {
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum TargetResolutionMethod {
+    Configured,
     WpctlInspect,
     WpctlStatus,
     FallbackAlias,
@@ -74,6 +151,7 @@ pub(crate) enum TargetResolutionMethod {
 impl TargetResolutionMethod {
     pub(crate) fn as_str(self) -> &'static str {
         match self {
+            TargetResolutionMethod::Configured => "configured",
             TargetResolutionMethod::WpctlInspect => "wpctl-inspect",
             TargetResolutionMethod::WpctlStatus => "wpctl-status",
             TargetResolutionMethod::FallbackAlias => "fallback-alias",
@@ -107,12 +185,26 @@ impl PipeWireCapture {
             channels: 1, // Always mono for Whisper compatibility
             capture_system: settings.audio.capture_system,
             capture_microphone: settings.audio.capture_microphone,
-            mic_boost: settings.audio.mic_boost,
+            system_device: settings.audio.system_device.trim().to_string(),
+            mic_device: settings.audio.device.trim().to_string(),
+            mix: MixOptions::from_settings(settings),
             recording: Arc::new(AtomicBool::new(false)),
             system_process: None,
             mic_process: None,
             output_path: None,
             mic_path: None,
+            keep_separate_tracks: settings.audio.keep_separate_tracks,
+            system_path: None,
+            tracks: None,
+            level_warnings: Vec::new(),
+            format_warnings: Vec::new(),
+            started_at: None,
+            stream_to_ogg: settings.audio.stream_to_ogg,
+            ogg_bitrate: settings.audio.ogg_bitrate,
+            stream_thread: None,
+            stream_tx: None,
+            system_source: SourceState::default(),
+            mic_source: SourceState::default(),
         })
     }
 
@@ -125,11 +217,106 @@ impl PipeWireCapture {
             .status()
             .is_ok()
     }
+
+    /// Whether this recording is encoded to OGG while capturing
+    ///
+    /// Separate tracks are kept as WAV files, so streaming only applies to
+    /// the mixed-only mode.
+    fn streams_to_ogg(&self) -> bool {
+        self.stream_to_ogg && !self.keep_separate_tracks
+    }
+
+    /// Start pw-record writing raw PCM to stdout and encode it on the fly
+    fn start_streaming(&mut self, output_path: &Path, targets: &[String]) -> Result<()> {
+        let (system_target, mic_target) = match targets {
+            [system, mic] => (Some(system.as_str()), Some(mic.as_str())),
+            [target] if self.capture_system => (Some(target.as_str()), None),
+            [target] => (None, Some(target.as_str())),
+            _ => (None, None),
+        };
+
+        let (tx, rx) = mpsc::channel();
+
+        if let Some(target) = system_target {
+            let mut child = spawn_pw_record_stdout(target, self.sample_rate, self.channels)?;
+            spawn_pcm_reader(child.stdout.take(), TargetKind::System, tx.clone());
+            self.system_process = Some(child);
+            self.system_source = SourceState::new(target, None);
+        }
+
+        if let Some(target) = mic_target {
+            match spawn_pw_record_stdout(target, self.sample_rate, self.channels) {
+                Ok(mut child) => {
+                    spawn_pcm_reader(child.stdout.take(), TargetKind::Microphone, tx.clone());
+                    self.mic_process = Some(child);
+                    self.mic_source = SourceState::new(target, None);
+                }
+                Err(e) if self.system_process.is_some() => {
+                    tracing::warn!(
+                        "PipeWire: microphone capture unavailable, continuing with system audio only: {}",
+                        e
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        // Kept so a source that moves to a new device can feed the same encoder
+        self.stream_tx = Some(tx);
+
+        let dual = self.system_process.is_some() && self.mic_process.is_some();
+        let mix = self.mix.for_sources(dual, dual);
+        let writer = StreamingOggWriter::create(
+            output_path,
+            self.sample_rate,
+            mix.channels(),
+            self.ogg_bitrate,
+        )
+        .inspect_err(|_| {
+            for child in [self.system_process.take(), self.mic_process.take()]
+                .into_iter()
+                .flatten()
+            {
+                wait_for_process(child);
+            }
+        })?;
+        let mixer = StreamMixer::new(dual, self.sample_rate, mix);
+        let levels = TrackLevels {
+            system: self
+                .system_process
+                .is_some()
+                .then(|| LevelMeter::new(self.sample_rate)),
+            mic: self
+                .mic_process
+                .is_some()
+                .then(|| LevelMeter::new(self.sample_rate)),
+        };
+        self.stream_thread = Some(std::thread::spawn(move || {
+            run_stream_encoder(rx, mixer, writer, levels)
+        }));
+
+        tracing::info!(
+            "PipeWire: Streaming {} to OGG Opus (system_target={}, mic_target={})",
+            if dual {
+                "system monitor + microphone"
+            } else {
+                "single source"
+            },
+            system_target.unwrap_or("-"),
+            mic_target.unwrap_or("-")
+        );
+
+        Ok(())
+    }
 }
 
 impl AudioCapture for PipeWireCapture {
     fn start(&mut self, output_path: &Path) -> Result<()> {
-        let targets = capture_targets(self.capture_system, self.capture_microphone);
+        let targets = capture_targets(
+            self.capture_system,
+            self.capture_microphone,
+            &self.system_device,
+            &self.mic_device,
+        );
         if targets.is_empty() {
             anyhow::bail!("No audio sources enabled. Enable system and/or microphone capture.");
         }
@@ -140,15 +327,35 @@ impl AudioCapture for PipeWireCapture {
         }
 
         self.output_path = Some(output_path.to_path_buf());
+        self.system_path = None;
+        self.tracks = None;
+        self.level_warnings.clear();
+        self.format_warnings.clear();
+        self.started_at = Some(Instant::now());
+        self.system_source = SourceState::default();
+        self.mic_source = SourceState::default();
         self.recording.store(true, Ordering::SeqCst);
 
+        if self.streams_to_ogg() {
+            return self.start_streaming(output_path, &targets);
+        }
+
         if targets.len() == 2 {
             let system_target = targets[0].as_str();
             let mic_target = targets[1].as_str();
             let mic_path = output_path.with_extension("mic.wav");
+            let system_path = if self.keep_separate_tracks {
+                output_path.with_extension("system.wav")
+            } else {
+                output_path.to_path_buf()
+            };
 
             let system_process =
-                spawn_pw_record(system_target, self.sample_rate, self.channels, output_path)?;
+                spawn_pw_record(system_target, self.sample_rate, self.channels, &system_path)?;
+            self.system_source = SourceState::new(system_target, Some(&system_path));
+            if self.keep_separate_tracks {
+                self.system_path = Some(system_path);
+            }
 
             let mic_process = match spawn_pw_record(
                 mic_target,
@@ -172,6 +379,7 @@ impl AudioCapture for PipeWireCapture {
             self.system_process = Some(system_process);
             self.mic_process = Some(mic_process);
 
+            self.mic_source = SourceState::new(mic_target, Some(&mic_path));
             self.mic_path = Some(mic_path);
 
             tracing::info!(
@@ -187,6 +395,7 @@ impl AudioCapture for PipeWireCapture {
                 self.channels,
                 output_path,
             )?);
+            self.system_source = SourceState::new(system_target, Some(output_path));
             tracing::info!(
                 "PipeWire: Recording system monitor via pw-record (system_target={})",
                 system_target
@@ -199,6 +408,7 @@ impl AudioCapture for PipeWireCapture {
                 self.channels,
                 output_path,
             )?);
+            self.mic_source = SourceState::new(mic_target, Some(output_path));
             tracing::info!(
                 "PipeWire: Recording microphone via pw-record (mic_target={})",
                 mic_target
@@ -219,10 +429,49 @@ impl AudioCapture for PipeWireCapture {
             wait_for_process(child);
         }
 
-        if let (Some(output_path), Some(mic_path)) =
+        // pw-record exiting closes its stdout, which drains and finalizes the stream
+        self.stream_tx = None;
+        if let Some(handle) = self.stream_thread.take() {
+            match handle.join() {
+                Ok(Ok(warnings)) => self.level_warnings = warnings,
+                Ok(Err(e)) => tracing::warn!("PipeWire: failed to finalize OGG stream: {}", e),
+                Err(_) => tracing::warn!("PipeWire: OGG stream encoder panicked"),
+            }
+        }
+
+        for source in [&mut self.system_source, &mut self.mic_source] {
+            let parts = std::mem::take(&mut source.parts);
+            if let (Some(file), false) = (source.file.as_ref(), parts.is_empty()) {
+                if let Err(e) = append_wav_parts(file, &parts) {
+                    tracing::warn!(
+                        "PipeWire: failed to join audio recorded after a device switch: {}",
+                        e
+                    );
+                }
+            }
+        }
+
+        // Measured before mixing, while each source is still its own file
+        if self.system_source.file.is_some() || self.mic_source.file.is_some() {
+            let levels = TrackLevels {
+                system: self.system_source.file.as_deref().and_then(measure_track),
+                mic: self.mic_source.file.as_deref().and_then(measure_track),
+            };
+            self.level_warnings = levels.warnings();
+        }
+
+        if let (Some(output_path), Some(system_path)) =
+            (self.output_path.as_ref(), self.system_path.take())
+        {
+            let mic_path = self.mic_path.take();
+            match finish_separate_tracks(output_path, &system_path, mic_path, self.mix) {
+                Ok(tracks) => self.tracks = tracks,
+                Err(e) => tracing::warn!("PipeWire: failed to finalize separate tracks: {}", e),
+            }
+        } else if let (Some(output_path), Some(mic_path)) =
             (self.output_path.as_ref(), self.mic_path.take())
         {
-            if let Err(e) = maybe_mix_microphone_track(output_path, &mic_path, self.mic_boost) {
+            if let Err(e) = maybe_mix_microphone_track(output_path, &mic_path, self.mix) {
                 tracing::warn!(
                     "PipeWire: failed to mix microphone track, keeping system-only capture: {}",
                     e
@@ -243,6 +492,208 @@ impl AudioCapture for PipeWireCapture {
     fn backend_name(&self) -> &'static str {
         "pipewire"
     }
+
+    fn output_extension(&self) -> &'static str {
+        if self.streams_to_ogg() {
+            "ogg"
+        } else {
+            "wav"
+        }
+    }
+
+    fn track_paths(&self) -> Option<(PathBuf, PathBuf)> {
+        self.tracks.clone()
+    }
+
+    fn level_warnings(&self) -> Vec<String> {
+        self.level_warnings
+            .iter()
+            .chain(&self.format_warnings)
+            .cloned()
+            .collect()
+    }
+
+    fn refresh_targets(&mut self) -> Result<()> {
+        if !self.is_recording() {
+            return Ok(());
+        }
+
+        for kind in [TargetKind::System, TargetKind::Microphone] {
+            self.check_format(kind)?;
+        }
+
+        for kind in [TargetKind::System, TargetKind::Microphone] {
+            let (configured, running) = match kind {
+                TargetKind::System => (&self.system_device, self.system_process.is_some()),
+                TargetKind::Microphone => (&self.mic_device, self.mic_process.is_some()),
+            };
+            // Configured devices are pinned; only default-following sources move
+            if !configured.is_empty() || !running {
+                continue;
+            }
+
+            let resolved = resolve_target(kind, "");
+            if resolved.method == TargetResolutionMethod::FallbackAlias
+                || self.source(kind).target.as_deref() == Some(resolved.target.as_str())
+            {
+                continue;
+            }
+            tracing::info!(
+                "PipeWire: default {} device changed, now recording from target {}",
+                kind.label(),
+                resolved.target
+            );
+            self.retarget(kind, resolved.target)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PipeWireCapture {
+    fn source(&self, kind: TargetKind) -> &SourceState {
+        match kind {
+            TargetKind::System => &self.system_source,
+            TargetKind::Microphone => &self.mic_source,
+        }
+    }
+
+    /// Start recording `kind` from `target` and stop the pw-record on the old node
+    ///
+    /// The new process starts before the old one is stopped, so the switch
+    /// overlaps by a few milliseconds instead of dropping audio.
+    fn retarget(&mut self, kind: TargetKind, target: String) -> Result<()> {
+        let process = if let Some(tx) = self.stream_tx.as_ref() {
+            let mut child = spawn_pw_record_stdout(&target, self.sample_rate, self.channels)?;
+            spawn_pcm_reader(child.stdout.take(), kind, tx.clone());
+            child
+        } else {
+            let source = self.source(kind);
+            let Some(file) = source.file.as_ref() else {
+                return Ok(());
+            };
+            let part = part_path(file, source.parts.len() + 1);
+            let child = spawn_pw_record(&target, self.sample_rate, self.channels, &part)?;
+            match kind {
+                TargetKind::System => self.system_source.parts.push(part),
+                TargetKind::Microphone => self.mic_source.parts.push(part),
+            }
+            child
+        };
+
+        let (old, source) = match kind {
+            TargetKind::System => (
+                self.system_process.replace(process),
+                &mut self.system_source,
+            ),
+            TargetKind::Microphone => (self.mic_process.replace(process), &mut self.mic_source),
+        };
+        source.target = Some(target);
+        if let Some(old) = old {
+            wait_for_process(old);
+        }
+        Ok(())
+    }
+
+    /// Restart a source whose node changed sample rate or channel count
+    ///
+    /// A stream negotiated before the change keeps its old format, so the
+    /// audio after a Bluetooth profile switch would play back at the wrong
+    /// speed. The new pw-record negotiates afresh; its part is resampled to
+    /// the file's format when the recording stops.
+    fn check_format(&mut self, kind: TargetKind) -> Result<()> {
+        let running = match kind {
+            TargetKind::System => self.system_process.is_some(),
+            TargetKind::Microphone => self.mic_process.is_some(),
+        };
+        let Some(target) = self.source(kind).target.clone().filter(|_| running) else {
+            return Ok(());
+        };
+        let Some(format) = node_format(&target) else {
+            return Ok(());
+        };
+
+        let source = match kind {
+            TargetKind::System => &mut self.system_source,
+            TargetKind::Microphone => &mut self.mic_source,
+        };
+        let Some(previous) = source.format.replace(format) else {
+            return Ok(());
+        };
+        if previous == format {
+            return Ok(());
+        }
+
+        let elapsed = self
+            .started_at
+            .map(|started| started.elapsed().as_secs())
+            .unwrap_or(0);
+        let warning = format_change_warning(kind, format, elapsed);
+        tracing::warn!("PipeWire: {}", warning);
+        self.format_warnings.push(warning);
+        self.retarget(kind, target)
+    }
+}
+
+/// Recording note for a source that changed format `elapsed_secs` into a recording
+fn format_change_warning(kind: TargetKind, format: NodeFormat, elapsed_secs: u64) -> String {
+    let layout = match format.channels {
+        1 => "mono".to_string(),
+        2 => "stereo".to_string(),
+        channels => format!("{} channels", channels),
+    };
+    let reason = if format.headset_profile {
+        " (Bluetooth headset profile)"
+    } else {
+        ""
+    };
+    format!(
+        "{} switched to {} Hz {}{} at {}:{:02}; later audio was resampled",
+        match kind {
+            TargetKind::System => "System audio",
+            TargetKind::Microphone => "Microphone",
+        },
+        format.rate,
+        layout,
+        reason,
+        elapsed_secs / 60,
+        elapsed_secs % 60
+    )
+}
+
+/// Current format of a node, looked up with pw-dump
+///
+/// Only resolved node IDs are checked; aliases such as
+/// `@DEFAULT_AUDIO_SOURCE@` follow whichever node is the default.
+fn node_format(target: &str) -> Option<NodeFormat> {
+    if target.is_empty() || !target.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let output = Command::new("pw-dump").arg(target).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_pw_dump_format(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Format of the first node in pw-dump JSON output that reports one
+fn parse_pw_dump_format(output: &str) -> Option<NodeFormat> {
+    let objects: Vec<serde_json::Value> = serde_json::from_str(output).ok()?;
+    objects.iter().find_map(|object| {
+        let info = object.get("info")?;
+        let format = info.get("params")?.get("Format")?.get(0)?;
+        let profile = info
+            .get("props")
+            .and_then(|props| props.get("api.bluez5.profile"))
+            .and_then(|profile| profile.as_str())
+            .unwrap_or_default();
+        Some(NodeFormat {
+            rate: u32::try_from(format.get("rate")?.as_u64()?).ok()?,
+            channels: u16::try_from(format.get("channels")?.as_u64()?).ok()?,
+            headset_profile: profile.contains("headset") || profile.contains("hfp"),
+        })
+    })
 }
 
 fn spawn_pw_record(
@@ -269,6 +720,167 @@ fn spawn_pw_record(
         .with_context(|| format!("Failed to start pw-record for target {}", target))
 }
 
+/// Spawn pw-record writing raw s16 PCM to stdout
+fn spawn_pw_record_stdout(target: &str, sample_rate: u32, channels: u16) -> Result<Child> {
+    Command::new("pw-record")
+        .args([
+            "--target",
+            target,
+            "--rate",
+            &sample_rate.to_string(),
+            "--channels",
+            &channels.to_string(),
+            "--format",
+            "s16",
+            "-",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to start pw-record for target {}", target))
+}
+
+/// Forward little-endian s16 PCM from a pw-record pipe to the stream encoder
+fn spawn_pcm_reader(
+    stdout: Option<ChildStdout>,
+    kind: TargetKind,
+    tx: mpsc::Sender<(TargetKind, Vec<i16>)>,
+) {
+    let Some(mut stdout) = stdout else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        let mut carry: Option<u8> = None;
+
+        loop {
+            let read = match stdout.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+
+            let mut bytes = buf[..read].to_vec();
+            if let Some(byte) = carry.take() {
+                bytes.insert(0, byte);
+            }
+            if bytes.len() % 2 == 1 {
+                carry = bytes.pop();
+            }
+
+            let samples = bytes
+                .chunks_exact(2)
+                .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+            if tx.send((kind, samples)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Encode PCM chunks from all sources until every reader has finished
+///
+/// Returns the level warnings for the sources that were streamed.
+fn run_stream_encoder(
+    rx: mpsc::Receiver<(TargetKind, Vec<i16>)>,
+    mut mixer: StreamMixer,
+    mut writer: StreamingOggWriter,
+    mut levels: TrackLevels,
+) -> Result<Vec<String>> {
+    for (kind, samples) in rx {
+        let meter = match kind {
+            TargetKind::System => levels.system.as_mut(),
+            TargetKind::Microphone => levels.mic.as_mut(),
+        };
+        if let Some(meter) = meter {
+            let samples: Vec<f32> = samples.iter().map(|&s| i16_to_f32(s)).collect();
+            meter.push(&samples);
+        }
+        writer.write_samples(&mixer.push(kind, &samples))?;
+    }
+
+    writer.write_samples(&mixer.flush())?;
+    writer.finalize()?;
+    Ok(levels.warnings())
+}
+
+/// Level statistics of a recorded source file, if it can be read
+fn measure_track(path: &Path) -> Option<LevelMeter> {
+    LevelMeter::measure_wav(path)
+        .map_err(|e| tracing::debug!("PipeWire: skipping level check: {}", e))
+        .ok()
+}
+
+/// Aligns system and microphone PCM arriving in independent chunks
+struct StreamMixer {
+    mixer: AudioMixer,
+    dual: bool,
+    system: VecDeque<f32>,
+    mic: VecDeque<f32>,
+    /// Maximum samples one source may run ahead before the other is treated as silent
+    max_lag: usize,
+    /// Noise suppressor applied to microphone chunks before queueing
+    suppressor: Option<NoiseSuppressor>,
+}
+
+impl StreamMixer {
+    fn new(dual: bool, sample_rate: u32, options: MixOptions) -> Self {
+        Self {
+            mixer: options.mixer(sample_rate),
+            dual,
+            system: VecDeque::new(),
+            mic: VecDeque::new(),
+            max_lag: sample_rate as usize * 2,
+            suppressor: (dual && options.noise_suppression)
+                .then(|| NoiseSuppressor::new(sample_rate)),
+        }
+    }
+
+    /// Queue samples from one source and return whatever can be mixed now
+    fn push(&mut self, kind: TargetKind, samples: &[i16]) -> Vec<i16> {
+        if !self.dual {
+            return samples.to_vec();
+        }
+
+        let samples: Vec<f32> = samples.iter().map(|&s| i16_to_f32(s)).collect();
+        match (kind, self.suppressor.as_mut()) {
+            (TargetKind::System, _) => self.system.extend(samples),
+            (TargetKind::Microphone, Some(suppressor)) => {
+                self.mic.extend(suppressor.process(&samples))
+            }
+            (TargetKind::Microphone, None) => self.mic.extend(samples),
+        }
+
+        let longest = self.system.len().max(self.mic.len());
+        let ready = self
+            .system
+            .len()
+            .min(self.mic.len())
+            .max(longest.saturating_sub(self.max_lag));
+        self.drain(ready)
+    }
+
+    /// Mix everything still queued, padding the shorter source with silence
+    fn flush(&mut self) -> Vec<i16> {
+        if let Some(suppressor) = self.suppressor.as_mut() {
+            self.mic.extend(suppressor.flush());
+        }
+        let longest = self.system.len().max(self.mic.len());
+        self.drain(longest)
+    }
+
+    fn drain(&mut self, count: usize) -> Vec<i16> {
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let system: Vec<f32> = self.system.drain(..count.min(self.system.len())).collect();
+        let mic: Vec<f32> = self.mic.drain(..count.min(self.mic.len())).collect();
+        self.mixer.mix_to_i16(&system, &mic)
+    }
+}
+
 fn wait_for_process(mut child: Child) {
     #[cfg(unix)]
     unsafe {
@@ -287,11 +899,77 @@ fn wait_for_process(mut child: Child) {
     }
 }
 
+/// Path of continuation part `index` (1-based) of a pw-record output file
+fn part_path(file: &Path, index: usize) -> PathBuf {
+    file.with_extension(format!("part{}.wav", index))
+}
+
+/// Append the audio of continuation parts to `path`, then remove the parts
+///
+/// Parts recorded at another sample rate or channel count are converted to
+/// the format of `path` first, so they do not play back at the wrong speed.
+fn append_wav_parts(path: &Path, parts: &[PathBuf]) -> Result<()> {
+    let mut writer = WavWriter::append(path)
+        .with_context(|| format!("Failed to open WAV file: {}", path.display()))?;
+    let spec = writer.spec();
+
+    for part in parts {
+        match WavReader::open(part) {
+            Ok(reader) if reader.spec() == spec => {
+                for sample in reader.into_samples::<i16>().filter_map(|s| s.ok()) {
+                    writer.write_sample(sample)?;
+                }
+            }
+            Ok(reader) => {
+                let part_spec = reader.spec();
+                drop(reader);
+                tracing::info!(
+                    "PipeWire: converting {} from {} Hz x{} to {} Hz x{}",
+                    part.display(),
+                    part_spec.sample_rate,
+                    part_spec.channels,
+                    spec.sample_rate,
+                    spec.channels
+                );
+                let (rate, channels, samples) = read_wav_as_f32(part)?;
+                let samples = convert_channels(&samples, channels, spec.channels);
+                let samples =
+                    resample_interleaved(&samples, spec.channels.into(), rate, spec.sample_rate);
+                for sample in samples {
+                    writer.write_sample(f32_to_i16(sample))?;
+                }
+            }
+            Err(e) => tracing::warn!(
+                "PipeWire: skipping unreadable part {}: {}",
+                part.display(),
+                e
+            ),
+        }
+        let _ = std::fs::remove_file(part);
+    }
+
+    writer.finalize()?;
+    Ok(())
+}
+
+/// Convert interleaved audio between channel counts by way of a mono downmix
+fn convert_channels(samples: &[f32], from: u16, to: u16) -> Vec<f32> {
+    if from == to || from == 0 {
+        return samples.to_vec();
+    }
+
+    samples
+        .chunks(from.into())
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .flat_map(|mono| std::iter::repeat_n(mono, to.into()))
+        .collect()
+}
+
 fn mix_wav_files(
     system_path: &Path,
     mic_path: &Path,
     output_path: &Path,
-    mic_boost: f32,
+    options: MixOptions,
 ) -> Result<()> {
     let (system_rate, system_channels, mut system_samples) = read_wav_as_f32(system_path)?;
     let (mic_rate, mic_channels, mut mic_samples) = read_wav_as_f32(mic_path)?;
@@ -304,15 +982,19 @@ fn mix_wav_files(
         mic_samples = AudioMixer::stereo_to_mono(&mic_samples);
     }
 
-    let mixer = AudioMixer::new(system_rate, mic_boost);
+    let mut mixer = options.mixer(system_rate);
     if mic_rate != system_rate {
         mic_samples = mixer.resample(&mic_samples, mic_rate);
     }
 
+    if options.noise_suppression {
+        mic_samples = NoiseSuppressor::process_all(system_rate, &mic_samples);
+    }
+
     let mixed = mixer.mix_to_i16(&system_samples, &mic_samples);
 
     let spec = WavSpec {
-        channels: 1,
+        channels: mixer.channels(),
         sample_rate: system_rate,
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
@@ -329,7 +1011,11 @@ fn mix_wav_files(
     Ok(())
 }
 
-fn maybe_mix_microphone_track(output_path: &Path, mic_path: &Path, mic_boost: f32) -> Result<()> {
+fn maybe_mix_microphone_track(
+    output_path: &Path,
+    mic_path: &Path,
+    options: MixOptions,
+) -> Result<()> {
     if !mic_path.exists() {
         return Ok(());
     }
@@ -340,7 +1026,41 @@ fn maybe_mix_microphone_track(output_path: &Path, mic_path: &Path, mic_boost: f3
         return Ok(());
     }
 
-    mix_wav_files(output_path, mic_path, output_path, mic_boost)
+    mix_wav_files(output_path, mic_path, output_path, options)
+}
+
+/// Mix separately recorded tracks into `output_path`, keeping both tracks
+///
+/// Returns the (system, microphone) track paths when both were kept. When the
+/// microphone produced no audio the system track becomes the output file.
+fn finish_separate_tracks(
+    output_path: &Path,
+    system_path: &Path,
+    mic_path: Option<PathBuf>,
+    options: MixOptions,
+) -> Result<Option<(PathBuf, PathBuf)>> {
+    let mic_path = mic_path.filter(|path| {
+        std::fs::metadata(path)
+            .map(|meta| meta.len() > 44)
+            .unwrap_or(false)
+    });
+
+    match mic_path {
+        Some(mic_path) => {
+            mix_wav_files(system_path, &mic_path, output_path, options)?;
+            Ok(Some((system_path.to_path_buf(), mic_path)))
+        }
+        None => {
+            let _ = std::fs::remove_file(output_path.with_extension("mic.wav"));
+            std::fs::rename(system_path, output_path).with_context(|| {
+                format!(
+                    "Failed to move system track into place: {}",
+                    output_path.display()
+                )
+            })?;
+            Ok(None)
+        }
+    }
 }
 
 fn read_wav_as_f32(path: &Path) -> Result<(u32, u16, Vec<f32>)> {
@@ -374,18 +1094,36 @@ fn read_wav_as_f32(path: &Path) -> Result<(u32, u16, Vec<f32>)> {
     Ok((spec.sample_rate, spec.channels, samples))
 }
 
-fn capture_targets(capture_system: bool, capture_microphone: bool) -> Vec<String> {
-    resolve_capture_targets(capture_system, capture_microphone)
-        .into_iter()
-        .map(|target| target.target)
-        .collect()
+fn capture_targets(
+    capture_system: bool,
+    capture_microphone: bool,
+    system_device: &str,
+    mic_device: &str,
+) -> Vec<String> {
+    resolve_capture_targets(
+        capture_system,
+        capture_microphone,
+        system_device,
+        mic_device,
+    )
+    .into_iter()
+    .map(|target| target.target)
+    .collect()
 }
 
 pub(crate) fn resolve_capture_targets(
     capture_system: bool,
     capture_microphone: bool,
+    system_device: &str,
+    mic_device: &str,
 ) -> Vec<ResolvedCaptureTarget> {
-    capture_targets_with_resolver(capture_system, capture_microphone, resolve_target)
+    capture_targets_with_resolver(capture_system, capture_microphone, |kind| {
+        let configured = match kind {
+            TargetKind::System => system_device,
+            TargetKind::Microphone => mic_device,
+        };
+        resolve_target(kind, configured)
+    })
 }
 
 fn capture_targets_with_resolver<F>(
@@ -406,7 +1144,16 @@ where
     targets
 }
 
-fn resolve_target(kind: TargetKind) -> ResolvedCaptureTarget {
+fn resolve_target(kind: TargetKind, configured: &str) -> ResolvedCaptureTarget {
+    let configured = configured.trim();
+    if !configured.is_empty() {
+        return ResolvedCaptureTarget {
+            kind,
+            target: configured.to_string(),
+            method: TargetResolutionMethod::Configured,
+        };
+    }
+
     let alias = match kind {
         TargetKind::System => SYSTEM_ALIAS,
         TargetKind::Microphone => MICROPHONE_ALIAS,
@@ -486,13 +1233,30 @@ fn parse_wpctl_node_id(output: &str) -> Option<String> {
 }
 
 fn parse_wpctl_status_default_node_id(output: &str, kind: TargetKind) -> Option<String> {
+    let nodes = parse_wpctl_status_nodes(output, kind);
+
+    if let Some((id, _, _)) = nodes.iter().find(|(_, _, is_default)| *is_default) {
+        return Some(id.clone());
+    }
+
+    if let Some(configured_name) = parse_wpctl_configured_default_name(output, kind) {
+        if let Some((id, _, _)) = nodes.iter().find(|(_, name, _)| name == &configured_name) {
+            return Some(id.clone());
+        }
+    }
+
+    nodes.first().map(|(id, _, _)| id.clone())
+}
+
+/// Parse `(id, name, is_default)` entries from the Sinks/Sources section of `wpctl status -n`
+fn parse_wpctl_status_nodes(output: &str, kind: TargetKind) -> Vec<(String, String, bool)> {
     let section_label = match kind {
         TargetKind::System => "Sinks:",
         TargetKind::Microphone => "Sources:",
     };
 
     let mut in_section = false;
-    let mut nodes: Vec<(String, String, bool)> = Vec::new();
+    let mut nodes = Vec::new();
 
     for line in output.lines() {
         let trimmed = line.trim();
@@ -505,28 +1269,52 @@ fn parse_wpctl_status_default_node_id(output: &str, kind: TargetKind) -> Option<
             continue;
         }
 
-        if trimmed.ends_with("Filters:") || trimmed.ends_with("Streams:") {
+        // Any other section header (Sources:, Filters:, Streams:, ...) ends this one.
+        if trimmed.ends_with(':') {
             break;
         }
 
-        let Some((id, name, is_default)) = parse_wpctl_status_node_line(trimmed) else {
-            continue;
-        };
-
-        nodes.push((id, name, is_default));
+        if let Some(node) = parse_wpctl_status_node_line(trimmed) {
+            nodes.push(node);
+        }
     }
 
-    if let Some((id, _, _)) = nodes.iter().find(|(_, _, is_default)| *is_default) {
-        return Some(id.clone());
-    }
+    nodes
+}
 
-    if let Some(configured_name) = parse_wpctl_configured_default_name(output, kind) {
-        if let Some((id, _, _)) = nodes.iter().find(|(_, name, _)| name == &configured_name) {
-            return Some(id.clone());
-        }
+/// List PipeWire sinks (system monitor targets) and sources (microphones)
+pub(crate) fn list_pipewire_devices() -> Vec<AudioDevice> {
+    let Ok(output) = Command::new("wpctl")
+        .args(["status", "-n"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+    else {
+        return Vec::new();
+    };
+
+    if !output.status.success() {
+        return Vec::new();
     }
 
-    nodes.first().map(|(id, _, _)| id.clone())
+    devices_from_wpctl_status(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn devices_from_wpctl_status(output: &str) -> Vec<AudioDevice> {
+    [TargetKind::System, TargetKind::Microphone]
+        .into_iter()
+        .flat_map(|kind| {
+            parse_wpctl_status_nodes(output, kind)
+                .into_iter()
+                .map(move |(id, name, is_default)| AudioDevice {
+                    backend: "pipewire",
+                    kind: kind.label(),
+                    id,
+                    name,
+                    is_default,
+                })
+        })
+        .collect()
 }
 
 fn parse_wpctl_status_node_line(line: &str) -> Option<(String, String, bool)> {
@@ -583,6 +1371,16 @@ mod tests {
     use hound::{SampleFormat, WavWriter};
     use tempfile::tempdir;
 
+    fn plain_mix(mic_boost: f32) -> MixOptions {
+        MixOptions {
+            mic_boost,
+            noise_suppression: false,
+            echo_cancellation: false,
+            auto_gain: None,
+            split_stereo: false,
+        }
+    }
+
     #[test]
     fn selects_monitor_and_microphone_targets_when_both_enabled() {
         let targets = capture_targets_with_resolver(true, true, |kind| match kind {
@@ -710,7 +1508,41 @@ Settings
 
     #[test]
     fn fails_when_no_capture_sources_enabled() {
-        assert!(capture_targets(false, false).is_empty());
+        assert!(capture_targets(false, false, "", "").is_empty());
+    }
+
+    #[test]
+    fn configured_devices_override_default_resolution() {
+        let targets = resolve_capture_targets(true, true, "bluez_output.headset", "55");
+        assert_eq!(targets[0].target, "bluez_output.headset");
+        assert_eq!(targets[0].method, TargetResolutionMethod::Configured);
+        assert_eq!(targets[1].target, "55");
+        assert_eq!(targets[1].method, TargetResolutionMethod::Configured);
+    }
+
+    #[test]
+    fn lists_sinks_and_sources_from_wpctl_status_output() {
+        let status = r#"
+Audio
+ ├─ Sinks:
+ │  *   61. alsa_output.pci-0000_65_00.6.analog-stereo [vol: 0.44]
+ │      72. bluez_output.14:06:A7:95:AC:6C [vol: 0.34]
+ │
+ ├─ Sources:
+ │  *   62. alsa_input.pci-0000_65_00.6.analog-stereo [vol: 0.39 MUTED]
+ │
+ ├─ Filters:
+"#;
+
+        let devices = devices_from_wpctl_status(status);
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[0].kind, "system");
+        assert_eq!(devices[0].id, "61");
+        assert!(devices[0].is_default);
+        assert_eq!(devices[1].name, "bluez_output.14:06:A7:95:AC:6C");
+        assert!(!devices[1].is_default);
+        assert_eq!(devices[2].kind, "microphone");
+        assert_eq!(devices[2].id, "62");
     }
 
     #[test]
@@ -720,7 +1552,7 @@ Settings
         let missing_mic_path = dir.path().join("missing.wav");
 
         write_test_wav(&system_path, &[1000, -1000, 500, -500]);
-        maybe_mix_microphone_track(&system_path, &missing_mic_path, 1.2).unwrap();
+        maybe_mix_microphone_track(&system_path, &missing_mic_path, plain_mix(1.2)).unwrap();
 
         let (_, _, samples) = read_wav_as_f32(&system_path).unwrap();
         assert_eq!(samples.len(), 4);
@@ -735,12 +1567,180 @@ Settings
         write_test_wav(&system_path, &[1000, -1000, 500, -500]);
         write_test_wav(&mic_path, &[]);
 
-        maybe_mix_microphone_track(&system_path, &mic_path, 1.2).unwrap();
+        maybe_mix_microphone_track(&system_path, &mic_path, plain_mix(1.2)).unwrap();
 
         let (_, _, samples) = read_wav_as_f32(&system_path).unwrap();
         assert_eq!(samples.len(), 4);
     }
 
+    #[test]
+    fn stream_mixer_waits_for_both_sources() {
+        let mut mixer = StreamMixer::new(true, 16000, plain_mix(1.0));
+        assert!(mixer.push(TargetKind::System, &[1000; 160]).is_empty());
+
+        let mixed = mixer.push(TargetKind::Microphone, &[1000; 100]);
+        assert_eq!(mixed.len(), 100);
+        assert_eq!(mixer.flush().len(), 60);
+    }
+
+    #[test]
+    fn stream_mixer_does_not_wait_forever_on_a_stalled_source() {
+        let mut mixer = StreamMixer::new(true, 100, plain_mix(1.0));
+        let mixed = mixer.push(TargetKind::System, &[1000; 250]);
+        assert_eq!(mixed.len(), 50);
+    }
+
+    #[test]
+    fn stream_mixer_passes_single_source_through() {
+        let mut mixer = StreamMixer::new(false, 16000, plain_mix(1.2));
+        assert_eq!(
+            mixer.push(TargetKind::Microphone, &[5, 6, 7]),
+            vec![5, 6, 7]
+        );
+        assert!(mixer.flush().is_empty());
+    }
+
+    #[test]
+    fn stream_mixer_keeps_length_with_noise_suppression() {
+        let mut mixer = StreamMixer::new(
+            true,
+            16000,
+            MixOptions {
+                noise_suppression: true,
+                echo_cancellation: false,
+                ..plain_mix(1.0)
+            },
+        );
+        let mut mixed = mixer.push(TargetKind::System, &[100; 4000]);
+        mixed.extend(mixer.push(TargetKind::Microphone, &[100; 4000]));
+        mixed.extend(mixer.flush());
+        assert_eq!(mixed.len(), 4000);
+    }
+
+    #[test]
+    fn keeps_both_tracks_next_to_mixed_output() {
+        let dir = tempdir().unwrap();
+        let output_path = dir.path().join("rec.wav");
+        let system_path = output_path.with_extension("system.wav");
+        let mic_path = output_path.with_extension("mic.wav");
+
+        write_test_wav(&system_path, &[1000, -1000, 500, -500]);
+        write_test_wav(&mic_path, &[200, 200, 200]);
+
+        let tracks = finish_separate_tracks(
+            &output_path,
+            &system_path,
+            Some(mic_path.clone()),
+            plain_mix(1.0),
+        )
+        .unwrap();
+        assert_eq!(tracks, Some((system_path.clone(), mic_path.clone())));
+        assert!(system_path.exists());
+        assert!(mic_path.exists());
+
+        let (_, _, samples) = read_wav_as_f32(&output_path).unwrap();
+        assert_eq!(samples.len(), 4);
+    }
+
+    #[test]
+    fn separate_tracks_fall_back_to_system_only_without_mic_audio() {
+        let dir = tempdir().unwrap();
+        let output_path = dir.path().join("rec.wav");
+        let system_path = output_path.with_extension("system.wav");
+        let mic_path = output_path.with_extension("mic.wav");
+
+        write_test_wav(&system_path, &[1000, -1000]);
+        write_test_wav(&mic_path, &[]);
+
+        let tracks = finish_separate_tracks(
+            &output_path,
+            &system_path,
+            Some(mic_path.clone()),
+            plain_mix(1.0),
+        )
+        .unwrap();
+        assert!(tracks.is_none());
+        assert!(output_path.exists());
+        assert!(!system_path.exists());
+        assert!(!mic_path.exists());
+    }
+
+    #[test]
+    fn joins_parts_recorded_after_a_device_switch() {
+        let dir = tempdir().unwrap();
+        let mic_path = dir.path().join("rec.mic.wav");
+        let parts = vec![part_path(&mic_path, 1), part_path(&mic_path, 2)];
+        assert_eq!(parts[0], dir.path().join("rec.mic.part1.wav"));
+
+        write_test_wav(&mic_path, &[1, 2]);
+        write_test_wav(&parts[0], &[3]);
+        write_test_wav(&parts[1], &[4, 5]);
+
+        append_wav_parts(&mic_path, &parts).unwrap();
+
+        let samples: Vec<i16> = WavReader::open(&mic_path)
+            .unwrap()
+            .into_samples::<i16>()
+            .map(|s| s.unwrap())
+            .collect();
+        assert_eq!(samples, vec![1, 2, 3, 4, 5]);
+        assert!(parts.iter().all(|part| !part.exists()));
+    }
+
+    #[test]
+    fn parses_node_format_from_pw_dump_output() {
+        let output = r#"[{
+            "id": 71,
+            "type": "PipeWire:Interface:Node",
+            "info": {
+                "props": { "api.bluez5.profile": "headset-head-unit" },
+                "params": { "Format": [{ "format": "S16LE", "rate": 16000, "channels": 1 }] }
+            }
+        }]"#;
+        let format = parse_pw_dump_format(output).unwrap();
+        assert_eq!(
+            format,
+            NodeFormat {
+                rate: 16000,
+                channels: 1,
+                headset_profile: true,
+            }
+        );
+        assert_eq!(
+            format_change_warning(TargetKind::Microphone, format, 95),
+            "Microphone switched to 16000 Hz mono (Bluetooth headset profile) at 1:35; later audio was resampled"
+        );
+        assert!(parse_pw_dump_format("[]").is_none());
+    }
+
+    #[test]
+    fn resamples_parts_recorded_at_another_rate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rec.wav");
+        let part = dir.path().join("rec.part1.wav");
+        write_test_wav(&path, &[100; 160]);
+
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(&part, spec).unwrap();
+        for _ in 0..80 {
+            writer.write_sample(1000i16).unwrap();
+            writer.write_sample(3000i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        append_wav_parts(&path, std::slice::from_ref(&part)).unwrap();
+        let (rate, channels, samples) = read_wav_as_f32(&path).unwrap();
+        assert_eq!((rate, channels), (16000, 1));
+        assert_eq!(samples.len(), 320);
+        assert!((samples[200] - 2000.0 / 32768.0).abs() < 1e-3);
+        assert!(!part.exists());
+    }
+
     fn write_test_wav(path: &Path, samples: &[i16]) {
         let spec = hound::WavSpec {
             channels: 1,
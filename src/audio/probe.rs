@@ -0,0 +1,118 @@
+//! Reading the length of audio files from their headers
+//!
+//! Decoding a long recording just to learn its duration would take seconds
+//! and hold the whole file in memory, so only the metadata is read.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Opus granule positions count samples at 48kHz
+const OPUS_GRANULE_RATE: f64 = 48000.0;
+
+/// Audio formats that can be read without conversion
+pub const NATIVE_AUDIO_EXTENSIONS: &[&str] = &["wav", "ogg", "oga", "opus", "flac"];
+
+/// Duration in seconds of a WAV, OGG Opus, or FLAC file
+pub fn audio_duration_secs(path: &Path) -> Result<f64> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match extension.as_str() {
+        "ogg" | "oga" | "opus" => ogg_opus_duration(path),
+        "flac" => flac_duration(path),
+        "wav" => wav_duration(path),
+        other => anyhow::bail!("Unsupported audio format: .{}", other),
+    }
+}
+
+fn wav_duration(path: &Path) -> Result<f64> {
+    let reader = hound::WavReader::open(path)
+        .with_context(|| format!("Invalid WAV file: {}", path.display()))?;
+    Ok(reader.duration() as f64 / f64::from(reader.spec().sample_rate))
+}
+
+fn flac_duration(path: &Path) -> Result<f64> {
+    let reader = claxon::FlacReader::open(path)
+        .with_context(|| format!("Invalid FLAC file: {}", path.display()))?;
+    let info = reader.streaminfo();
+    let samples = info
+        .samples
+        .with_context(|| format!("{} does not record its length", path.display()))?;
+    Ok(samples as f64 / f64::from(info.sample_rate))
+}
+
+/// The last page's granule position, less the decoder pre-skip
+fn ogg_opus_duration(path: &Path) -> Result<f64> {
+    let data = std::fs::read(path).with_context(|| format!("Failed to open {}", path.display()))?;
+
+    let head = find(&data, b"OpusHead")
+        .with_context(|| format!("{} is not an OGG Opus file", path.display()))?;
+    let pre_skip = data
+        .get(head + 10..head + 12)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .context("Truncated Opus header")?;
+
+    let last_page = data
+        .windows(4)
+        .rposition(|window| window == b"OggS")
+        .context("OGG file has no pages")?;
+    let granule = data
+        .get(last_page + 6..last_page + 14)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .context("Truncated OGG page")?;
+
+    Ok(granule.saturating_sub(u64::from(pre_skip)) as f64 / OPUS_GRANULE_RATE)
+}
+
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::FlacWriter;
+
+    #[test]
+    fn durations_come_from_file_headers() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let wav = dir.path().join("call.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&wav, spec).unwrap();
+        for _ in 0..2 * 12000 {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        assert_eq!(audio_duration_secs(&wav).unwrap(), 1.5);
+
+        let flac = dir.path().join("call.flac");
+        let mut writer = FlacWriter::create(&flac, 16000, 1).unwrap();
+        writer.write_samples(&vec![0i16; 40000]).unwrap();
+        writer.finalize().unwrap();
+        assert_eq!(audio_duration_secs(&flac).unwrap(), 2.5);
+
+        // A minimal OGG stream: the Opus header page and a final page at 3 s
+        let mut ogg = b"OggS\0\x02".to_vec();
+        ogg.extend_from_slice(&0u64.to_le_bytes());
+        ogg.extend_from_slice(&[0; 13]);
+        ogg.extend_from_slice(b"OpusHead\x01\x01");
+        ogg.extend_from_slice(&312u16.to_le_bytes());
+        ogg.extend_from_slice(b"OggS\0\x04");
+        ogg.extend_from_slice(&(3 * 48000 + 312u64).to_le_bytes());
+        let path = dir.path().join("call.ogg");
+        std::fs::write(&path, ogg).unwrap();
+        assert_eq!(audio_duration_secs(&path).unwrap(), 3.0);
+
+        assert!(audio_duration_secs(&dir.path().join("call.mp3")).is_err());
+    }
+}
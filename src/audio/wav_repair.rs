@@ -0,0 +1,210 @@
+//! Recovery of WAV files that were never finalized
+//!
+//! WAV writers only fill in the RIFF and `data` chunk sizes when a recording
+//! is closed. If the daemon or pw-record is killed first, the header still
+//! claims no audio (or more audio than was written) and decoders give up.
+//! The sizes are rebuilt here from what is actually on disk.
+
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Header sizes a WAV file declares and the sizes its contents call for
+struct HeaderCheck {
+    data_start: u64,
+    declared_riff: u32,
+    declared_data: u32,
+    actual_data: u32,
+}
+
+impl HeaderCheck {
+    fn needs_repair(&self) -> bool {
+        // The RIFF size may also cover chunks after the audio, but never less
+        self.declared_data != self.actual_data
+            || (self.declared_riff as u64) + 8 < self.data_start + self.actual_data as u64
+    }
+}
+
+/// Whether a path has a `.wav` extension
+pub fn is_wav_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+}
+
+/// Whether a WAV file's header sizes disagree with the audio it contains
+pub fn wav_needs_repair(path: &Path) -> Result<bool> {
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open audio file: {}", path.display()))?;
+    Ok(check_header(&mut file)?.needs_repair())
+}
+
+/// Rewrite the RIFF and `data` chunk sizes from the data actually written
+///
+/// Returns whether the header was changed. Files with a correct header are
+/// left untouched.
+pub fn repair_wav(path: &Path) -> Result<bool> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Failed to open audio file: {}", path.display()))?;
+
+    let check = check_header(&mut file)?;
+    if !check.needs_repair() {
+        return Ok(false);
+    }
+
+    let riff_size = (check.data_start + check.actual_data as u64 - 8) as u32;
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&riff_size.to_le_bytes())?;
+    file.seek(SeekFrom::Start(check.data_start - 4))?;
+    file.write_all(&check.actual_data.to_le_bytes())?;
+    file.sync_all()?;
+
+    tracing::info!(
+        "Repaired WAV header of {} ({} bytes of audio)",
+        path.display(),
+        check.actual_data
+    );
+    Ok(true)
+}
+
+/// Walk the chunk list up to `data` and work out how much audio follows it
+fn check_header(file: &mut File) -> Result<HeaderCheck> {
+    let file_len = file.metadata()?.len();
+
+    let mut riff = [0u8; 12];
+    file.read_exact(&mut riff)
+        .context("File is too short to be a WAV file")?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        anyhow::bail!("Not a WAV file");
+    }
+    let declared_riff = u32::from_le_bytes(riff[4..8].try_into().unwrap());
+
+    let mut block_align = 1u64;
+    let mut position = 12u64;
+    loop {
+        if position + 8 > file_len {
+            anyhow::bail!("WAV file has no data chunk");
+        }
+
+        let mut chunk = [0u8; 8];
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(&mut chunk)?;
+        let id = &chunk[0..4];
+        let size = u32::from_le_bytes(chunk[4..8].try_into().unwrap());
+
+        if id == b"fmt " {
+            let mut fmt = [0u8; 14];
+            file.read_exact(&mut fmt)
+                .context("WAV fmt chunk is truncated")?;
+            block_align = u16::from_le_bytes(fmt[12..14].try_into().unwrap()).max(1) as u64;
+        }
+
+        if id == b"data" {
+            let data_start = position + 8;
+            let available = file_len - data_start;
+            let declared_end = data_start + size as u64;
+
+            // A correct size followed by trailing chunks (LIST, id3, ...) is fine
+            let actual = if size > 0
+                && declared_end <= file_len
+                && (declared_end == file_len
+                    || trailing_chunk_at(file, declared_end + (size as u64 & 1), file_len)?)
+            {
+                size as u64
+            } else {
+                available - available % block_align
+            };
+
+            return Ok(HeaderCheck {
+                data_start,
+                declared_riff,
+                declared_data: size,
+                actual_data: actual.min(u32::MAX as u64 - data_start) as u32,
+            });
+        }
+
+        position += 8 + size as u64 + (size as u64 & 1);
+    }
+}
+
+/// Whether a well-formed chunk header starts at `position`
+///
+/// Used to tell chunks written after the audio apart from audio that the
+/// header does not account for.
+fn trailing_chunk_at(file: &mut File, position: u64, file_len: u64) -> Result<bool> {
+    if position == file_len {
+        return Ok(true);
+    }
+    if position + 8 > file_len {
+        return Ok(false);
+    }
+
+    let mut chunk = [0u8; 8];
+    file.seek(SeekFrom::Start(position))?;
+    file.read_exact(&mut chunk)?;
+    let size = u32::from_le_bytes(chunk[4..8].try_into().unwrap()) as u64;
+    Ok(chunk[0..4]
+        .iter()
+        .all(|b| b.is_ascii_alphanumeric() || *b == b' ')
+        && position + 8 + size <= file_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_wav(path: &Path, samples: usize) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for i in 0..samples {
+            writer.write_sample((i % 100) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    /// Overwrite both size fields the way an interrupted writer leaves them
+    fn clear_sizes(path: &Path) {
+        let mut file = OpenOptions::new().write(true).open(path).unwrap();
+        file.seek(SeekFrom::Start(4)).unwrap();
+        file.write_all(&0u32.to_le_bytes()).unwrap();
+        file.seek(SeekFrom::Start(40)).unwrap();
+        file.write_all(&0u32.to_le_bytes()).unwrap();
+    }
+
+    #[test]
+    fn restores_sizes_of_an_unfinalized_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("crashed.wav");
+        write_wav(&path, 16000);
+        clear_sizes(&path);
+        assert!(wav_needs_repair(&path).unwrap());
+
+        assert!(repair_wav(&path).unwrap());
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.len(), 16000);
+        assert!(!wav_needs_repair(&path).unwrap());
+    }
+
+    #[test]
+    fn leaves_finalized_files_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clean.wav");
+        write_wav(&path, 8000);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"LIST\x04\x00\x00\x00INFO").unwrap();
+
+        assert!(!wav_needs_repair(&path).unwrap());
+        assert!(!repair_wav(&path).unwrap());
+        assert_eq!(hound::WavReader::open(&path).unwrap().len(), 8000);
+    }
+}
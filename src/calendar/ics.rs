@@ -0,0 +1,738 @@
+//! Minimal iCalendar (RFC 5545) event parser
+//!
+//! Only what the dashboard needs is read: summary, start/end times, and
+//! attendee names. Times with a TZID are read in that timezone. Recurring
+//! events are expanded for the common RRULE forms (daily, weekly, monthly
+//! and yearly with INTERVAL, COUNT, UNTIL, BYDAY and BYMONTHDAY), with EXDATE
+//! and RECURRENCE-ID overrides applied; rules using other parts keep only
+//! their first occurrence. All-day events are skipped, since they are not a
+//! meeting someone starts recording.
+
+use std::collections::HashSet;
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+use super::CalendarEvent;
+
+/// Recurrence periods (days, weeks, months or years) looked at per event,
+/// enough for a daily meeting started decades ago
+const MAX_PERIODS: u32 = 50_000;
+
+/// Parse every timed, non-cancelled VEVENT in an iCalendar document
+///
+/// Recurring events yield each occurrence starting no later than `until`.
+pub fn parse_events(content: &str, until: DateTime<Utc>) -> Vec<CalendarEvent> {
+    let mut parsed = Vec::new();
+    let mut current: Option<EventBuilder> = None;
+    // Depth of components nested in the event, such as VALARM
+    let mut nested = 0;
+
+    for line in unfold(content) {
+        let Some(property) = Property::parse(&line) else {
+            continue;
+        };
+
+        match (property.name.as_str(), property.value.as_str()) {
+            ("BEGIN", "VEVENT") if current.is_none() => {
+                current = Some(EventBuilder::default());
+                nested = 0;
+            }
+            ("END", "VEVENT") if nested == 0 => {
+                if let Some(event) = current.take() {
+                    parsed.push(event);
+                }
+            }
+            ("BEGIN", _) if current.is_some() => nested += 1,
+            ("END", _) if nested > 0 => nested -= 1,
+            _ if nested > 0 => {}
+            _ => {
+                if let Some(event) = current.as_mut() {
+                    event.apply(&property);
+                }
+            }
+        }
+    }
+
+    // Occurrences moved or cancelled by a RECURRENCE-ID instance
+    let replaced: HashSet<(String, DateTime<Utc>)> = parsed
+        .iter()
+        .filter_map(|event| Some((event.uid.clone(), event.recurrence_id?)))
+        .collect();
+
+    let mut events = Vec::new();
+    for event in parsed {
+        if event.recurrence_id.is_some() {
+            events.extend(event.build(None));
+            continue;
+        }
+        for start in event.occurrences(until) {
+            if !replaced.contains(&(event.uid.clone(), start)) {
+                events.extend(event.build(Some(start)));
+            }
+        }
+    }
+
+    events.sort_by_key(|event| event.start);
+    events
+}
+
+/// Timezone a DATE-TIME value is written in
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    Utc,
+    Named(Tz),
+    /// No timezone given: read in the local timezone
+    Floating,
+}
+
+impl Zone {
+    fn to_utc(self, time: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Utc => Some(Utc.from_utc_datetime(&time)),
+            Zone::Named(tz) => resolve(&tz, time),
+            Zone::Floating => resolve(&Local, time),
+        }
+    }
+}
+
+/// A wall-clock time in `zone`, taking a time skipped by a DST change as
+/// the hour after
+fn resolve<Z: TimeZone>(zone: &Z, time: NaiveDateTime) -> Option<DateTime<Utc>> {
+    zone.from_local_datetime(&time)
+        .earliest()
+        .or_else(|| {
+            zone.from_local_datetime(&(time + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[derive(Default)]
+struct EventBuilder {
+    uid: String,
+    title: Option<String>,
+    start: Option<(NaiveDateTime, Zone)>,
+    end: Option<DateTime<Utc>>,
+    rule: Option<String>,
+    exdates: Vec<DateTime<Utc>>,
+    recurrence_id: Option<DateTime<Utc>>,
+    all_day: bool,
+    cancelled: bool,
+    participants: Vec<String>,
+}
+
+impl EventBuilder {
+    fn apply(&mut self, property: &Property) {
+        let (params, value) = (property.params.as_slice(), property.value.as_str());
+        match property.name.as_str() {
+            "UID" => self.uid = value.to_string(),
+            "SUMMARY" => self.title = Some(unescape(value)),
+            "DTSTART" => match parse_local_time(params, value) {
+                Some(start) => self.start = Some(start),
+                None => self.all_day = true,
+            },
+            "DTEND" => self.end = parse_time(params, value),
+            "RRULE" => self.rule = Some(value.to_string()),
+            "EXDATE" => self.exdates.extend(
+                value
+                    .split(',')
+                    .filter_map(|value| parse_time(params, value)),
+            ),
+            "RECURRENCE-ID" => self.recurrence_id = parse_time(params, value),
+            "STATUS" => self.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "ORGANIZER" | "ATTENDEE" => {
+                let name = param(params, "CN")
+                    .map(unescape)
+                    .or_else(|| strip_mailto(value))
+                    .filter(|name| !name.is_empty());
+                if let Some(name) = name {
+                    if !self.participants.contains(&name) {
+                        self.participants.push(name);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Start times of the event, up to `until` for a recurring one
+    fn occurrences(&self, until: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let Some((start, zone)) = self.start else {
+            return Vec::new();
+        };
+        let first = zone.to_utc(start).into_iter().collect();
+        let Some(rule) = self.rule.as_deref() else {
+            return first;
+        };
+        let Some(rule) = Rule::parse(rule, zone) else {
+            tracing::debug!("Not expanding unsupported RRULE: {}", rule);
+            return first;
+        };
+
+        rule.expand(start, zone, until)
+            .into_iter()
+            .filter(|time| !self.exdates.contains(time))
+            .collect()
+    }
+
+    /// The event at `start`, or at its own start for a single instance
+    fn build(&self, start: Option<DateTime<Utc>>) -> Option<CalendarEvent> {
+        if self.all_day || self.cancelled {
+            return None;
+        }
+        let (first, zone) = self.start?;
+        let first = zone.to_utc(first)?;
+        let start = start.unwrap_or(first);
+
+        Some(CalendarEvent {
+            title: self
+                .title
+                .clone()
+                .filter(|title| !title.trim().is_empty())
+                .unwrap_or_else(|| "Untitled event".to_string()),
+            start,
+            end: self.end.map(|end| start + (end - first)),
+            participants: self.participants.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The supported subset of an RRULE
+#[derive(Debug, PartialEq)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<usize>,
+    until: Option<DateTime<Utc>>,
+    /// Weekdays, with their ordinal within the month (`2TU`, `-1FR`) if given
+    by_day: Vec<(Option<i32>, Weekday)>,
+    by_month_day: Vec<i32>,
+}
+
+impl Rule {
+    /// Read an RRULE value; `None` when it uses parts that are not supported
+    fn parse(value: &str, zone: Zone) -> Option<Self> {
+        let mut frequency = None;
+        let mut rule = Self {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+        };
+
+        for part in value.split(';') {
+            let (key, value) = part.split_once('=')?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => rule.interval = value.parse().ok().filter(|&n| n > 0)?,
+                "COUNT" => rule.count = Some(value.parse().ok()?),
+                "UNTIL" => {
+                    rule.until = Some(match parse_local_time(&[], value) {
+                        // UNTIL is UTC when the start has a timezone
+                        Some((time, Zone::Utc)) => Utc.from_utc_datetime(&time),
+                        Some((time, _)) => zone.to_utc(time)?,
+                        // A DATE runs to the end of that day
+                        None => {
+                            let day = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+                            zone.to_utc(day.and_hms_opt(23, 59, 59)?)?
+                        }
+                    })
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        rule.by_day.push(parse_weekday(day)?);
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for day in value.split(',') {
+                        rule.by_month_day.push(day.parse().ok()?);
+                    }
+                }
+                "WKST" => {}
+                _ => return None,
+            }
+        }
+        rule.frequency = frequency?;
+
+        // Ordinal weekdays only mean something within a month
+        let ordinals = rule.by_day.iter().any(|(ordinal, _)| ordinal.is_some());
+        let supported = match rule.frequency {
+            Frequency::Daily | Frequency::Weekly => !ordinals && rule.by_month_day.is_empty(),
+            Frequency::Monthly => rule.by_day.is_empty() || rule.by_month_day.is_empty(),
+            Frequency::Yearly => rule.by_day.is_empty() && rule.by_month_day.is_empty(),
+        };
+        supported.then_some(rule)
+    }
+
+    /// Occurrence start times from `start` up to `until`
+    fn expand(&self, start: NaiveDateTime, zone: Zone, until: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let until = self.until.map_or(until, |end| end.min(until));
+        // Wall-clock dates past this cannot start before `until` in any zone
+        let last_day = until.date_naive() + Duration::days(1);
+
+        let mut occurrences = Vec::new();
+        let mut seen = 0;
+        for period in 0..MAX_PERIODS {
+            let Some((first_day, days)) = self.period(start.date(), period) else {
+                break;
+            };
+            if first_day > last_day {
+                break;
+            }
+            for day in days {
+                let time = day.and_time(start.time());
+                if time < start {
+                    continue;
+                }
+                let Some(occurrence) = zone.to_utc(time) else {
+                    continue;
+                };
+                if occurrence > until || self.count.is_some_and(|count| seen >= count) {
+                    return occurrences;
+                }
+                seen += 1;
+                occurrences.push(occurrence);
+            }
+        }
+        occurrences
+    }
+
+    /// The first day of the `index`-th period after `start` and its days that
+    /// match the rule, in order
+    fn period(&self, start: NaiveDate, index: u32) -> Option<(NaiveDate, Vec<NaiveDate>)> {
+        let step = index.checked_mul(self.interval)?;
+        let matches_weekday = |day: &NaiveDate| {
+            self.by_day.is_empty()
+                || self
+                    .by_day
+                    .iter()
+                    .any(|(_, weekday)| *weekday == day.weekday())
+        };
+
+        match self.frequency {
+            Frequency::Daily => {
+                let day = start.checked_add_signed(Duration::try_days(step.into())?)?;
+                Some((day, vec![day].into_iter().filter(matches_weekday).collect()))
+            }
+            Frequency::Weekly => {
+                let monday = start
+                    .checked_sub_signed(Duration::days(
+                        start.weekday().num_days_from_monday().into(),
+                    ))?
+                    .checked_add_signed(Duration::try_weeks(step.into())?)?;
+                let days = (0..7)
+                    .map(|offset| monday + Duration::days(offset))
+                    .filter(|day| {
+                        if self.by_day.is_empty() {
+                            day.weekday() == start.weekday()
+                        } else {
+                            matches_weekday(day)
+                        }
+                    })
+                    .collect();
+                Some((monday, days))
+            }
+            Frequency::Monthly => {
+                let first = start.with_day(1)?.checked_add_months(Months::new(step))?;
+                let mut days: Vec<NaiveDate> = if !self.by_day.is_empty() {
+                    self.by_day
+                        .iter()
+                        .flat_map(|&(ordinal, weekday)| weekdays_in_month(first, ordinal, weekday))
+                        .collect()
+                } else if !self.by_month_day.is_empty() {
+                    self.by_month_day
+                        .iter()
+                        .filter_map(|&day| month_day(first, day))
+                        .collect()
+                } else {
+                    // Months without the start's day are skipped
+                    month_day(first, start.day() as i32).into_iter().collect()
+                };
+                days.sort_unstable();
+                days.dedup();
+                Some((first, days))
+            }
+            Frequency::Yearly => {
+                let first =
+                    NaiveDate::from_ymd_opt(start.year().checked_add_unsigned(step)?, 1, 1)?;
+                let day = NaiveDate::from_ymd_opt(first.year(), start.month(), start.day());
+                Some((first, day.into_iter().collect()))
+            }
+        }
+    }
+}
+
+/// A BYDAY entry such as `MO`, `2TU` or `-1FR`
+fn parse_weekday(value: &str) -> Option<(Option<i32>, Weekday)> {
+    let split = value
+        .len()
+        .checked_sub(2)
+        .filter(|&split| value.is_char_boundary(split))?;
+    let (ordinal, day) = value.split_at(split);
+    let weekday = match day.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    };
+    let ordinal = match ordinal {
+        "" => None,
+        ordinal => Some(ordinal.parse().ok().filter(|&n: &i32| n != 0)?),
+    };
+    Some((ordinal, weekday))
+}
+
+/// Every `weekday` in the month starting at `first`, or only the
+/// `ordinal`-th one, counting from the end when negative
+fn weekdays_in_month(first: NaiveDate, ordinal: Option<i32>, weekday: Weekday) -> Vec<NaiveDate> {
+    let days: Vec<NaiveDate> = first
+        .iter_days()
+        .take_while(|day| day.month() == first.month())
+        .filter(|day| day.weekday() == weekday)
+        .collect();
+    match ordinal {
+        None => days,
+        Some(ordinal) => {
+            let index = if ordinal > 0 {
+                ordinal - 1
+            } else {
+                days.len() as i32 + ordinal
+            };
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| days.get(index).copied())
+                .into_iter()
+                .collect()
+        }
+    }
+}
+
+/// Day `day` of the month starting at `first`, counting from the end when
+/// negative; `None` when the month is too short
+fn month_day(first: NaiveDate, day: i32) -> Option<NaiveDate> {
+    if day > 0 {
+        first.with_day(day as u32)
+    } else {
+        let next = first.checked_add_months(Months::new(1))?;
+        let date = next.checked_sub_signed(Duration::days((-day).into()))?;
+        (date.month() == first.month()).then_some(date)
+    }
+}
+
+/// Join folded continuation lines (those starting with a space or tab)
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// One content line: `NAME;PARAM=value;...:VALUE`
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    /// Split a content line into its parts, respecting quoted params
+    fn parse(line: &str) -> Option<Self> {
+        let mut in_quotes = false;
+        let mut parts = Vec::new();
+        let mut start = 0;
+        let mut value_at = None;
+
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                ';' if !in_quotes => {
+                    parts.push(&line[start..i]);
+                    start = i + 1;
+                }
+                ':' if !in_quotes => {
+                    parts.push(&line[start..i]);
+                    value_at = Some(i + 1);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let value = line[value_at?..].to_string();
+        let mut parts = parts.into_iter();
+        let name = parts.next()?.to_ascii_uppercase();
+        let params = parts
+            .filter_map(|part| part.split_once('='))
+            .map(|(key, value)| {
+                (
+                    key.to_ascii_uppercase(),
+                    value.trim_matches('"').to_string(),
+                )
+            })
+            .collect();
+        Some(Self {
+            name,
+            params,
+            value,
+        })
+    }
+}
+
+fn param<'a>(params: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Parse a DATE-TIME value; `None` for DATE values (all-day) or bad input
+fn parse_time(params: &[(String, String)], value: &str) -> Option<DateTime<Utc>> {
+    let (time, zone) = parse_local_time(params, value)?;
+    zone.to_utc(time)
+}
+
+/// Parse a DATE-TIME value as a wall-clock time and its timezone
+///
+/// UTC times end in `Z`. A TZID names an IANA timezone; floating times, and
+/// TZIDs that are not IANA names, are read in the local timezone.
+fn parse_local_time(params: &[(String, String)], value: &str) -> Option<(NaiveDateTime, Zone)> {
+    if param(params, "VALUE") == Some("DATE") || NaiveDate::parse_from_str(value, "%Y%m%d").is_ok()
+    {
+        return None;
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((time, Zone::Utc));
+    }
+
+    let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let zone = match param(params, "TZID") {
+        Some(tzid) => match tzid.parse::<Tz>() {
+            Ok(tz) => Zone::Named(tz),
+            Err(_) => {
+                tracing::debug!("Reading times in unknown timezone {} as local", tzid);
+                Zone::Floating
+            }
+        },
+        None => Zone::Floating,
+    };
+    Some((time, zone))
+}
+
+fn strip_mailto(value: &str) -> Option<String> {
+    let address = value
+        .strip_prefix("mailto:")
+        .or_else(|| value.strip_prefix("MAILTO:"))
+        .unwrap_or(value);
+    Some(address.trim().to_string())
+}
+
+fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => text.push(' '),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Roadmap review\\, Q3\r\n\
+DTSTART:20261016T140000Z\r\n\
+DTEND:20261016T150000Z\r\n\
+ORGANIZER;CN=Alice Smith:mailto:alice@example.com\r\n\
+ATTENDEE;ROLE=REQ-PARTICIPANT;CN=\"Bob: Platform\":mailto:bob@example.com\r\n\
+ATTENDEE;RSVP=TRUE:mailto:carol@exam\r\n\x20ple.com\r\n\
+BEGIN:VALARM\r\n\
+ACTION:EMAIL\r\n\
+SUMMARY:Reminder\r\n\
+ATTENDEE:mailto:alarm@example.com\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Company holiday\r\n\
+DTSTART;VALUE=DATE:20261017\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Cancelled sync\r\n\
+DTSTART:20261016T090000Z\r\n\
+STATUS:CANCELLED\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Standup\r\n\
+DTSTART:20261016T080000Z\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn starts(events: &[CalendarEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| event.start.format("%Y-%m-%d %H:%M").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn parses_timed_events_with_participants() {
+        let events = parse_events(SAMPLE, utc("2027-01-01T00:00:00Z"));
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].title, "Standup");
+        assert!(events[0].end.is_none());
+
+        let review = &events[1];
+        assert_eq!(review.title, "Roadmap review, Q3");
+        assert_eq!(review.start.to_rfc3339(), "2026-10-16T14:00:00+00:00");
+        assert_eq!(
+            review.end.unwrap().to_rfc3339(),
+            "2026-10-16T15:00:00+00:00"
+        );
+        assert_eq!(
+            review.participants,
+            vec!["Alice Smith", "Bob: Platform", "carol@example.com"]
+        );
+    }
+
+    #[test]
+    fn reads_times_in_their_timezone() {
+        let events = parse_events(
+            "BEGIN:VEVENT\nSUMMARY:Lunch\nDTSTART;TZID=Europe/Berlin:20261016T120000\nEND:VEVENT\n\
+             BEGIN:VEVENT\nSUMMARY:Floating\nDTSTART:20261016T120000\nEND:VEVENT\n",
+            utc("2027-01-01T00:00:00Z"),
+        );
+
+        let lunch = events.iter().find(|event| event.title == "Lunch").unwrap();
+        assert_eq!(lunch.start, utc("2026-10-16T10:00:00Z"));
+        let floating = events
+            .iter()
+            .find(|event| event.title == "Floating")
+            .unwrap();
+        let expected = Local
+            .with_ymd_and_hms(2026, 10, 16, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(floating.start, expected);
+    }
+
+    #[test]
+    fn expands_recurring_events_with_exceptions_and_overrides() {
+        // Weekly on Monday and Thursday at 09:00 Berlin time across the end
+        // of summer time, skipping one date and moving another
+        let content = "BEGIN:VEVENT\n\
+UID:sync\n\
+SUMMARY:Sync\n\
+DTSTART;TZID=Europe/Berlin:20261019T090000\n\
+DTEND;TZID=Europe/Berlin:20261019T093000\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,TH;COUNT=6\n\
+EXDATE;TZID=Europe/Berlin:20261022T090000\n\
+END:VEVENT\n\
+BEGIN:VEVENT\n\
+UID:sync\n\
+RECURRENCE-ID;TZID=Europe/Berlin:20261029T090000\n\
+SUMMARY:Sync (moved)\n\
+DTSTART;TZID=Europe/Berlin:20261030T140000\n\
+DTEND;TZID=Europe/Berlin:20261030T143000\n\
+END:VEVENT\n";
+
+        let events = parse_events(content, utc("2027-01-01T00:00:00Z"));
+        assert_eq!(
+            starts(&events),
+            [
+                "2026-10-19 07:00",
+                "2026-10-26 08:00",
+                "2026-10-30 13:00",
+                "2026-11-02 08:00",
+                "2026-11-05 08:00",
+            ]
+        );
+        assert_eq!(events[2].title, "Sync (moved)");
+        assert_eq!(events[1].end, Some(utc("2026-10-26T08:30:00Z")));
+
+        // Occurrences stop at the window end; the moved one is its own event
+        let events = parse_events(content, utc("2026-10-27T00:00:00Z"));
+        assert_eq!(
+            starts(&events),
+            ["2026-10-19 07:00", "2026-10-26 08:00", "2026-10-30 13:00"]
+        );
+    }
+
+    #[test]
+    fn reads_monthly_and_daily_rules() {
+        let rule = |value: &str| Rule::parse(value, Zone::Utc).unwrap();
+        let expand = |rule: Rule, start: &str| -> Vec<String> {
+            let start = NaiveDateTime::parse_from_str(start, "%Y%m%dT%H%M%S").unwrap();
+            rule.expand(start, Zone::Utc, utc("2027-12-31T00:00:00Z"))
+                .iter()
+                .map(|time| time.format("%Y-%m-%d").to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            expand(rule("FREQ=MONTHLY;BYDAY=-1FR;COUNT=3"), "20261030T100000"),
+            ["2026-10-30", "2026-11-27", "2026-12-25"]
+        );
+        assert_eq!(
+            expand(rule("FREQ=MONTHLY;COUNT=3"), "20270131T100000"),
+            ["2027-01-31", "2027-03-31", "2027-05-31"]
+        );
+        assert_eq!(
+            expand(
+                rule("FREQ=DAILY;INTERVAL=2;UNTIL=20261106T100000Z"),
+                "20261101T100000"
+            ),
+            ["2026-11-01", "2026-11-03", "2026-11-05"]
+        );
+        assert!(Rule::parse("FREQ=MONTHLY;BYSETPOS=-1;BYDAY=MO", Zone::Utc).is_none());
+        assert!(Rule::parse("FREQ=HOURLY", Zone::Utc).is_none());
+    }
+}
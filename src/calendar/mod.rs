@@ -0,0 +1,106 @@
+//! Calendar integration for minutes
+//!
+//! Reads upcoming meetings from a local iCalendar (.ics) file, such as one
+//! kept in sync by vdirsyncer or exported from a calendar app, so the
+//! dashboard can offer to record them with the right title and attendees.
+
+mod ics;
+
+pub use ics::parse_events;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::Settings;
+
+/// A meeting read from the calendar
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+    /// Organizer and attendee names (email addresses when no name is given)
+    pub participants: Vec<String>,
+}
+
+impl CalendarEvent {
+    /// Whether the event has started and not yet ended at `now`
+    pub fn is_in_progress(&self, now: DateTime<Utc>) -> bool {
+        self.start <= now && self.end.is_some_and(|end| end > now)
+    }
+}
+
+/// Whether a calendar file is configured
+pub fn is_configured(settings: &Settings) -> bool {
+    !settings.calendar.ics_path.trim().is_empty()
+}
+
+/// Events in progress or starting within `calendar.lookahead_hours`, soonest first
+pub fn upcoming_events(settings: &Settings, now: DateTime<Utc>) -> Result<Vec<CalendarEvent>> {
+    if !is_configured(settings) {
+        return Ok(Vec::new());
+    }
+
+    let path = settings.calendar.ics_path.trim();
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read calendar file: {}", path))?;
+
+    let lookahead = Duration::hours(settings.calendar.lookahead_hours as i64);
+    Ok(filter_upcoming(
+        parse_events(&content, now + lookahead),
+        now,
+        lookahead,
+    ))
+}
+
+fn filter_upcoming(
+    events: Vec<CalendarEvent>,
+    now: DateTime<Utc>,
+    lookahead: Duration,
+) -> Vec<CalendarEvent> {
+    events
+        .into_iter()
+        .filter(|event| {
+            let ongoing = event.is_in_progress(now);
+            ongoing || (event.start >= now && event.start <= now + lookahead)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(
+        now: DateTime<Utc>,
+        title: &str,
+        start_mins: i64,
+        length_mins: Option<i64>,
+    ) -> CalendarEvent {
+        let start = now + Duration::minutes(start_mins);
+        CalendarEvent {
+            title: title.to_string(),
+            start,
+            end: length_mins.map(|m| start + Duration::minutes(m)),
+            participants: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn keeps_ongoing_and_soon_events() {
+        let now = Utc::now();
+        let events = vec![
+            event(now, "Finished", -120, Some(60)),
+            event(now, "Ongoing", -10, Some(30)),
+            event(now, "Started without an end", -5, None),
+            event(now, "Soon", 30, Some(30)),
+            event(now, "Tomorrow", 60 * 24, Some(30)),
+        ];
+
+        let titles: Vec<String> = filter_upcoming(events, now, Duration::hours(12))
+            .into_iter()
+            .map(|e| e.title)
+            .collect();
+        assert_eq!(titles, vec!["Ongoing", "Soon"]);
+    }
+}
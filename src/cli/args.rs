@@ -25,13 +25,41 @@ pub enum Commands {
         /// Optional title for the recording
         #[arg(short, long)]
         title: Option<String>,
+
+        /// Label the recording confidential
+        #[arg(long)]
+        confidential: bool,
+
+        /// Language spoken in the meeting (e.g. hi, german, auto), overriding
+        /// whisper.language for this recording
+        #[arg(short, long)]
+        language: Option<String>,
+
+        /// Comma-separated names of the people attending (e.g. alice,bob)
+        #[arg(long = "with", value_delimiter = ',', value_name = "NAMES")]
+        participants: Vec<String>,
     },
 
     /// Stop the current recording
     Stop,
 
     /// Show current recording status
-    Status,
+    Status {
+        /// Keep polling and show a live progress bar (Ctrl-C to stop)
+        #[arg(short, long)]
+        watch: bool,
+    },
+
+    /// Record a short voice memo and print its transcript (no daemon needed)
+    Memo {
+        /// Maximum length of the memo in seconds (Ctrl-C stops earlier)
+        #[arg(short, long, default_value = "60")]
+        seconds: u64,
+
+        /// Keep the memo as a recording tagged "memo"
+        #[arg(long)]
+        save: bool,
+    },
 
     /// List recorded meetings
     List {
@@ -42,6 +70,38 @@ pub enum Commands {
         /// Search term to filter recordings
         #[arg(short, long)]
         search: Option<String>,
+
+        /// Only recordings whose summary has not been approved yet
+        #[arg(long, conflicts_with = "search")]
+        unapproved: bool,
+
+        /// Only recordings in this project
+        #[arg(short, long, conflicts_with_all = ["search", "unapproved"])]
+        project: Option<String>,
+
+        /// Only recordings with this tag
+        #[arg(long, conflicts_with_all = ["search", "unapproved", "project"])]
+        tag: Option<String>,
+
+        /// Only recordings from this date or within this window (e.g. 2026-03-01, 30d, 2w)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only recordings up to this date, inclusive, or older than this window
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only recordings at least this long (e.g. 90s, 30m, 1h; minutes without a unit)
+        #[arg(long)]
+        min_duration: Option<String>,
+
+        /// Only recordings whose transcription completed or failed
+        #[arg(long, value_parser = ["completed", "failed"])]
+        state: Option<String>,
+
+        /// Only recordings this person attended
+        #[arg(long = "with", value_name = "NAME")]
+        participant: Option<String>,
     },
 
     /// View a specific recording's transcript
@@ -50,10 +110,116 @@ pub enum Commands {
         id: String,
     },
 
+    /// Correct a transcript in $EDITOR
+    Edit {
+        /// Recording ID or partial ID
+        id: String,
+    },
+
+    /// Edit a recording's notes or summary in $EDITOR
+    Notes {
+        /// Recording ID or partial ID
+        id: String,
+    },
+
+    /// Move a recording to the trash, or delete it for good with --permanent
+    Delete {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Delete the recording, its transcript, summaries and audio files right away
+        #[arg(long)]
+        permanent: bool,
+
+        /// Delete permanently without asking for confirmation
+        #[arg(short, long, requires = "permanent")]
+        force: bool,
+
+        /// Leave the audio files on disk when deleting permanently
+        #[arg(long, requires = "permanent")]
+        keep_audio: bool,
+    },
+
+    /// Move a recording's audio to cold storage, keeping its transcript local
+    Archive {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Directory to move the audio to, overriding storage.archive_dir
+        #[arg(long, value_name = "DIR")]
+        dest: Option<PathBuf>,
+    },
+
+    /// Change the title of a recording
+    Rename {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// New title
+        title: String,
+    },
+
+    /// Replace the notes, tags or participants of a recording
+    EditMeta {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Read the notes from a file (an empty file clears them)
+        #[arg(long, value_name = "FILE")]
+        notes_file: Option<PathBuf>,
+
+        /// Comma-separated tags replacing the current ones ("" clears them)
+        #[arg(long, value_delimiter = ',', value_name = "TAGS")]
+        tags: Option<Vec<String>>,
+
+        /// Comma-separated participants replacing the current ones ("" clears them)
+        #[arg(long, value_delimiter = ',', value_name = "NAMES")]
+        participants: Option<Vec<String>>,
+    },
+
     /// Search through all transcripts
     Search {
-        /// Search query (supports full-text search)
+        /// Words to find; "quoted phrases", prefixes like budg*, AND, OR, NOT and (groups) work too
         query: String,
+
+        /// Match by meaning instead of words, using LLM embeddings
+        #[arg(long)]
+        semantic: bool,
+
+        /// With --semantic, index confidential recordings with a cloud provider anyway
+        #[arg(long, requires = "semantic")]
+        include_confidential: bool,
+
+        /// Only recordings from this date or within this window (e.g. 2026-03-01, 30d, 2w)
+        #[arg(long, conflicts_with = "semantic")]
+        since: Option<String>,
+
+        /// Only recordings up to this date, inclusive, or older than this window
+        #[arg(long, conflicts_with = "semantic")]
+        until: Option<String>,
+
+        /// Only recordings at least this long (e.g. 90s, 30m, 1h; minutes without a unit)
+        #[arg(long, conflicts_with = "semantic")]
+        min_duration: Option<String>,
+
+        /// Only recordings whose transcription completed or failed
+        #[arg(long, value_parser = ["completed", "failed"], conflicts_with = "semantic")]
+        state: Option<String>,
+
+        /// Only recordings this person attended
+        #[arg(long = "with", value_name = "NAME", conflicts_with = "semantic")]
+        participant: Option<String>,
+    },
+
+    /// Transcribe a recording again from its saved audio (WAV, OGG, or FLAC)
+    Transcribe {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Language to transcribe in (e.g. hi, german, auto), kept with the
+        /// recording for later transcriptions
+        #[arg(short, long)]
+        language: Option<String>,
     },
 
     /// Run environment diagnostics (audio/backend checks)
@@ -61,32 +227,387 @@ pub enum Commands {
         /// Print machine-readable JSON output
         #[arg(long)]
         json: bool,
+
+        /// Repair recordings whose WAV files were left unfinalized by a crash
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// List available audio capture devices
+    Devices {
+        /// Print machine-readable JSON output
+        #[arg(long)]
+        json: bool,
     },
 
     /// Generate and store an AI summary for a recording
     Summarize {
+        /// Recording ID or partial ID
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        id: Option<String>,
+
+        /// Summarize every recording with a transcript
+        #[arg(long)]
+        all: bool,
+
+        /// With --all, only recordings that do not have a summary yet
+        #[arg(long, requires = "all")]
+        missing: bool,
+
+        /// With --all, only recordings created within this window (e.g. 30d, 2w, 12h)
+        #[arg(long, requires = "all")]
+        since: Option<String>,
+
+        /// With --all, only recordings in this project
+        #[arg(long, requires = "all")]
+        project: Option<String>,
+
+        /// Seconds to wait between provider requests when summarizing in batch
+        #[arg(long, default_value_t = 2, requires = "all")]
+        delay: u64,
+
+        /// Prompt template from the prompts directory to use (e.g. standup)
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Language to write the summary in (e.g. en, English), overriding llm.summary_language
+        #[arg(long, value_name = "LANGUAGE")]
+        lang: Option<String>,
+
+        /// Summarize again even if the summary is up to date
+        #[arg(long)]
+        force: bool,
+
+        /// Send confidential recordings to a cloud provider anyway
+        #[arg(long)]
+        include_confidential: bool,
+    },
+
+    /// Ask a question about a recording and get an answer citing timestamps
+    Ask {
+        /// Recording ID or partial ID
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+
+        /// Question to answer from the transcript
+        #[arg(required_unless_present = "all")]
+        question: Option<String>,
+
+        /// Answer a question from the closest segments of every recording
+        #[arg(long, value_name = "QUESTION", conflicts_with_all = ["id", "question"])]
+        all: Option<String>,
+
+        /// Send a confidential recording to a cloud provider anyway
+        #[arg(long)]
+        include_confidential: bool,
+    },
+
+    /// Extract action items from a recording, or list and check them off
+    Actions {
+        /// Recording ID or partial ID to extract action items from
+        #[arg(
+            required_unless_present_any = ["open", "done", "undone"],
+            conflicts_with_all = ["open", "done", "undone"]
+        )]
+        id: Option<String>,
+
+        /// List open action items across all recordings
+        #[arg(long, conflicts_with_all = ["done", "undone"])]
+        open: bool,
+
+        /// Mark an action item done
+        #[arg(long, value_name = "ITEM", conflicts_with = "undone")]
+        done: Option<i64>,
+
+        /// Mark a done action item open again
+        #[arg(long, value_name = "ITEM")]
+        undone: Option<i64>,
+
+        /// Send a confidential recording to a cloud provider anyway
+        #[arg(long)]
+        include_confidential: bool,
+    },
+
+    /// Extract the decisions made in a recording, with supporting timestamps
+    Decisions {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Send a confidential recording to a cloud provider anyway
+        #[arg(long)]
+        include_confidential: bool,
+    },
+
+    /// Write formal meeting minutes: attendees, agenda, discussion, decisions and action items
+    Minutes {
         /// Recording ID or partial ID
         id: String,
+
+        /// Output format (md, pdf)
+        #[arg(short, long, default_value = "md")]
+        format: String,
+
+        /// Output file (md defaults to stdout, pdf to <id>-minutes.pdf)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Send a confidential recording to a cloud provider anyway
+        #[arg(long)]
+        include_confidential: bool,
     },
 
-    /// Export a recording to a file
+    /// Show per-speaker talk time, interruptions and longest monologues
+    Analytics {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Add a narrative on the participation written by the LLM
+        #[arg(long)]
+        narrative: bool,
+
+        /// Send a confidential recording to a cloud provider anyway
+        #[arg(long, requires = "narrative")]
+        include_confidential: bool,
+    },
+
+    /// Generate a title for a recording from its transcript
+    Title {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Send a confidential recording to a cloud provider anyway
+        #[arg(long)]
+        include_confidential: bool,
+    },
+
+    /// Show or change the tags of a recording, or have the LLM suggest some
+    Tags {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Ask the LLM for tags, then confirm each one
+        #[arg(long)]
+        suggest: bool,
+
+        /// Accept all suggested tags without asking
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Add a tag (repeatable)
+        #[arg(long, value_name = "TAG")]
+        add: Vec<String>,
+
+        /// Remove a tag (repeatable)
+        #[arg(long, value_name = "TAG")]
+        remove: Vec<String>,
+
+        /// Send a confidential recording to a cloud provider anyway
+        #[arg(long)]
+        include_confidential: bool,
+    },
+
+    /// Show or change the people who attended a recording
+    Participants {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Add a participant (repeatable)
+        #[arg(long, value_name = "NAME")]
+        add: Vec<String>,
+
+        /// Remove a participant (repeatable)
+        #[arg(long, value_name = "NAME")]
+        remove: Vec<String>,
+    },
+
+    /// Divide a recording into chapters by topic
+    Chapters {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Send a confidential recording to a cloud provider anyway
+        #[arg(long)]
+        include_confidential: bool,
+    },
+
+    /// Export a recording to a file, or the whole archive as a static site
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Export {
+        #[command(subcommand)]
+        target: Option<ExportTarget>,
+
         /// Recording ID
-        id: String,
+        #[arg(required = true)]
+        id: Option<String>,
 
-        /// Output format (txt, json, srt)
+        /// Output format (txt, md, json, srt, vtt, or mp3 for the audio)
         #[arg(short, long, default_value = "txt")]
         format: String,
 
-        /// Output file path (defaults to stdout)
+        /// Output file path (defaults to stdout, or <id>.mp3 for audio)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Export the recording even if it is labelled confidential
+        #[arg(long)]
+        include_confidential: bool,
+
+        /// Use the English translation of segments that have one (txt, srt, vtt)
+        #[arg(long)]
+        translated: bool,
+
+        /// Export the chapters as cues instead of the transcript (srt, vtt)
+        #[arg(long, conflicts_with = "translated")]
+        chapters: bool,
+    },
+
+    /// Add an existing audio file to the library and queue it for transcription
+    Import {
+        /// Audio file (WAV, OGG, or FLAC; other formats such as MP3 need ffmpeg)
+        path: PathBuf,
+
+        /// Title for the recording (defaults to the file name)
+        #[arg(short, long)]
+        title: Option<String>,
+
+        /// Link to the file where it is instead of copying it into the library
+        #[arg(long)]
+        link: bool,
+    },
+
+    /// Attach pre-recorded system audio to a recording and re-transcribe both
+    Align {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// System audio file (WAV, OGG Opus, or FLAC), e.g. a webinar download
+        #[arg(long)]
+        system: PathBuf,
+
+        /// Where the file starts in the recording (e.g. 2.5s, or -3s if it began earlier)
+        #[arg(long, default_value = "0", allow_hyphen_values = true)]
+        offset: String,
+    },
+
+    /// Approve a recording's summary as final minutes
+    Approve {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Return the summary to draft instead
+        #[arg(long)]
+        revoke: bool,
+    },
+
+    /// List a recording's summaries, or show, compare or restore one
+    Summaries {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Print the summary with this ID
+        #[arg(long, value_name = "SUMMARY", conflicts_with_all = ["diff", "restore"])]
+        show: Option<i64>,
+
+        /// Compare two summaries line by line
+        #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with = "restore")]
+        diff: Option<Vec<i64>>,
+
+        /// Make an earlier summary the recording's current summary again
+        #[arg(long, value_name = "SUMMARY")]
+        restore: Option<i64>,
+    },
+
+    /// Show or change a recording's sensitivity label
+    Sensitivity {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// New label
+        #[arg(value_parser = ["normal", "confidential"])]
+        level: Option<String>,
+    },
+
+    /// Show library totals and activity, or LLM token usage and estimated cost per month
+    Stats {
+        /// Show LLM token usage and estimated cost per month and provider
+        #[arg(long)]
+        llm: bool,
+
+        /// Print machine-readable JSON output
+        #[arg(long, conflicts_with = "llm")]
+        json: bool,
+    },
+
+    /// Revert the most recent destructive operation
+    Undo {
+        /// List the operations that can be undone instead
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Write the database and audio files to a .tar.zst archive
+    Backup {
+        /// Archive to write (e.g. minutes.tar.zst)
+        path: PathBuf,
+
+        /// Overwrite an existing file
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Replace the library with a backup made by `minutes backup`
+    Restore {
+        /// Archive to restore
+        path: PathBuf,
+
+        /// Restore even if the library already has recordings
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Apply the storage retention policy to old recordings' audio now
+    Purge {
+        /// Only list the recordings whose audio would be removed
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Purge without asking for confirmation
+        #[arg(short, long, conflicts_with = "dry_run")]
+        force: bool,
     },
 
     /// Daemon management commands
     #[command(subcommand)]
     Daemon(DaemonCommand),
 
+    /// Privacy mode (pause automatic LLM titles, tags and embeddings of new transcripts)
+    #[command(subcommand)]
+    Privacy(PrivacyCommand),
+
+    /// Manage the transcription queue
+    #[command(subcommand)]
+    Queue(QueueCommand),
+
+    /// List, restore or empty deleted recordings
+    #[command(subcommand)]
+    Trash(TrashCommand),
+
+    /// Check and compact the database
+    #[command(subcommand)]
+    Db(DbCommand),
+
+    /// Manage enrolled speaker voice profiles
+    #[command(subcommand)]
+    Speakers(SpeakersCommand),
+
+    /// Group recordings into projects
+    #[command(subcommand)]
+    Project(ProjectCommand),
+
+    /// Manage Whisper model files
+    #[command(subcommand)]
+    Model(ModelCommand),
+
     /// Launch the interactive TUI
     Tui,
 
@@ -108,6 +629,11 @@ pub enum DaemonCommand {
         /// Run in foreground (don't daemonize)
         #[arg(short, long)]
         foreground: bool,
+
+        /// Audio backend to use instead of audio.backend (auto, pipewire, cpal,
+        /// jack, wasapi, coreaudio, or mock for a synthesized test signal)
+        #[arg(long)]
+        backend: Option<crate::audio::AudioBackend>,
     },
 
     /// Stop the running daemon
@@ -120,6 +646,154 @@ pub enum DaemonCommand {
     Status,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum PrivacyCommand {
+    /// Enable privacy mode until turned off or the daemon restarts
+    On,
+
+    /// Disable privacy mode
+    Off,
+
+    /// Show whether privacy mode is enabled
+    Status,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ExportTarget {
+    /// Generate a static HTML site with searchable transcripts and summaries
+    Site {
+        /// Directory to write the site into
+        #[arg(short, long, default_value = "site")]
+        output_dir: PathBuf,
+
+        /// Include recordings labelled confidential
+        #[arg(long)]
+        include_confidential: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum QueueCommand {
+    /// List queued and running transcriptions
+    List,
+
+    /// Take a recording out of the queue, stopping it if running
+    Cancel {
+        /// Recording ID or partial ID
+        id: String,
+    },
+
+    /// Move a queued recording to the front of the queue
+    Bump {
+        /// Recording ID or partial ID
+        id: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TrashCommand {
+    /// List recordings in the trash
+    List,
+
+    /// Take a recording out of the trash
+    Restore {
+        /// Recording ID or partial ID
+        id: String,
+    },
+
+    /// Delete every recording in the trash and its audio files for good
+    Empty {
+        /// Empty without asking for confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DbCommand {
+    /// Check integrity, rebuild the search index, vacuum and report table sizes
+    Maintain,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SpeakersCommand {
+    /// Enroll a speaker from a stretch of a recording where only they talk
+    Enroll {
+        /// Name to label the speaker with
+        name: String,
+
+        /// Recording ID or partial ID to take the voice sample from
+        #[arg(long)]
+        from: String,
+
+        /// Time range of the sample (e.g. 05:00-06:00)
+        #[arg(long)]
+        range: String,
+    },
+
+    /// List enrolled speakers
+    List,
+
+    /// Remove an enrolled speaker
+    Remove {
+        /// Speaker name
+        name: String,
+    },
+
+    /// Name a speaker of one recording, or list its speakers
+    Rename {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Current label (e.g. "Speaker 1")
+        #[arg(requires = "to")]
+        from: Option<String>,
+
+        /// New name
+        to: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProjectCommand {
+    /// Create a project
+    Create {
+        /// Project name
+        name: String,
+    },
+
+    /// List projects with their recording counts and total duration
+    List,
+
+    /// Move a recording into a project
+    Assign {
+        /// Recording ID or partial ID
+        id: String,
+
+        /// Project name
+        project: String,
+    },
+
+    /// Remove a recording from its project
+    Unassign {
+        /// Recording ID or partial ID
+        id: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ModelCommand {
+    /// Download a Whisper model into the models directory
+    Download {
+        /// Model name (e.g. tiny, base, small.en); defaults to whisper.model
+        model: Option<String>,
+
+        /// Download again even if the model file exists
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum ConfigCommand {
     /// Show current configuration
@@ -135,6 +809,32 @@ pub enum ConfigCommand {
         force: bool,
     },
 
+    /// Write non-secret settings (audio, whisper, llm) to a shareable preset file
+    ExportPreset {
+        /// Preset file to write
+        path: PathBuf,
+
+        /// Overwrite an existing file
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Merge a preset file into the current configuration
+    ImportPreset {
+        /// Preset file to read
+        path: PathBuf,
+    },
+
+    /// Store an API key in the system keyring, read from stdin
+    SetSecret {
+        /// Secret setting (llm.api_key or transcription.api_key)
+        key: String,
+
+        /// Remove the stored key instead
+        #[arg(long)]
+        delete: bool,
+    },
+
     /// Set a configuration value
     Set {
         /// Configuration key (e.g., whisper.model)
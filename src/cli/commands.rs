@@ -3,45 +3,106 @@
 use anyhow::{Context, Result};
 use chrono::Local;
 use serde::Serialize;
-use std::path::PathBuf;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
-use crate::audio::AudioBackend;
-use crate::cli::args::{ConfigCommand, DaemonCommand};
-use crate::config::Settings;
+use crate::audio::{
+    audio_duration_secs, create_capture, write_mp3, AudioBackend, AudioDevice, FlacWriter,
+    NATIVE_AUDIO_EXTENSIONS,
+};
+use crate::cli::args::{
+    ConfigCommand, DaemonCommand, DbCommand, ModelCommand, PrivacyCommand, ProjectCommand,
+    QueueCommand, SpeakersCommand, TrashCommand,
+};
+use crate::cli::{diff, edit, pdf, site};
+use crate::config::{secrets, Settings};
 use crate::daemon::client::DaemonClient;
 use crate::daemon::ipc::{DaemonRequest, DaemonResponse, RecordingStatus};
-use crate::llm::{build_provider, SummaryRequest};
-use crate::storage::{Database, Recording};
+use crate::llm::{
+    answer_library_question, answer_question, build_provider, describe_participation,
+    ensure_cloud_allowed, extract_action_items, extract_decisions, generate_chapters,
+    generate_title, suggest_tags, write_minutes, Chunking, LibraryExcerpt, LibraryQuestionRequest,
+    LlmProvider, MeetingMinutes, PromptTemplate, QuestionRequest, SummaryRequest, SummaryResponse,
+    TextSink, TokenUsage, EMBED_BATCH_SIZE, SUMMARY_PROMPT_VERSION,
+};
+use crate::storage::{
+    backup, retention, snapshot, trash, ActionItem, Chapter, Database, Decision, JobState,
+    LlmUsage, Project, Recording, RecordingFilter, RecordingState, SearchQuery, Sensitivity,
+    SpeakerProfile, Summary,
+};
+use crate::t;
+use crate::transcription::speakers::{parse_offset, parse_time_range, VoiceEmbedding};
+use crate::transcription::{
+    language_code, language_name, load_audio, load_chunks, load_chunks_native, load_native,
+    shift_track, silence_marker_text, silence_markers, ModelCache, TranscriptionPipeline,
+};
 
 /// Start a new recording
-pub async fn start_recording(settings: &Settings, title: Option<String>) -> Result<()> {
+pub async fn start_recording(
+    settings: &Settings,
+    title: Option<String>,
+    confidential: bool,
+    language: Option<String>,
+    participants: Vec<String>,
+) -> Result<()> {
+    let language = language.as_deref().map(language_argument).transpose()?;
+    let mut names = Vec::new();
+    add_participants(&mut names, participants);
     let mut client = DaemonClient::connect(settings).await?;
 
-    let title =
-        title.unwrap_or_else(|| format!("Meeting {}", Local::now().format("%Y-%m-%d %H:%M")));
+    let title = title.unwrap_or_else(Recording::default_title);
+    let sensitivity = if confidential {
+        Sensitivity::Confidential
+    } else {
+        Sensitivity::Normal
+    };
 
     let response = client
         .send(DaemonRequest::StartRecording {
             title: title.clone(),
+            sensitivity,
+            participants: names,
+            language,
         })
         .await?;
 
     match response {
         DaemonResponse::RecordingStarted { id } => {
-            println!("Recording started: {} ({})", title, &id[..8]);
+            println!("{}", t!("recording-started", title = title, id = &id[..8]));
         }
         DaemonResponse::Error { message } => {
-            anyhow::bail!("Failed to start recording: {}", message);
+            anyhow::bail!(t!("recording-start-failed", message = message));
         }
         _ => {
-            anyhow::bail!("Unexpected response from daemon");
+            anyhow::bail!(t!("daemon-unexpected-response"));
         }
     }
 
     Ok(())
 }
 
+/// Add names to a list of participants, skipping blanks and names already
+/// listed in any case
+fn add_participants(participants: &mut Vec<String>, names: Vec<String>) {
+    for name in names {
+        let name = name.trim();
+        if !name.is_empty() && !participants.iter().any(|p| p.eq_ignore_ascii_case(name)) {
+            participants.push(name.to_string());
+        }
+    }
+}
+
+/// Language code of a `--language` argument, or "auto" to detect it
+fn language_argument(language: &str) -> Result<String> {
+    if language.trim().eq_ignore_ascii_case("auto") {
+        return Ok("auto".to_string());
+    }
+    language_code(language)
+        .map(str::to_string)
+        .with_context(|| format!("Unknown language '{}'", language))
+}
+
 /// Stop the current recording
 pub async fn stop_recording(settings: &Settings) -> Result<()> {
     let mut client = DaemonClient::connect(settings).await?;
@@ -49,22 +110,29 @@ pub async fn stop_recording(settings: &Settings) -> Result<()> {
     let response = client.send(DaemonRequest::StopRecording).await?;
 
     match response {
-        DaemonResponse::RecordingStopped { id, duration_secs } => {
-            let minutes = duration_secs / 60;
-            let seconds = duration_secs % 60;
+        DaemonResponse::RecordingStopped {
+            id,
+            duration_secs,
+            warnings,
+        } => {
             println!(
-                "Recording stopped: {} (duration: {}:{:02})",
-                &id[..8],
-                minutes,
-                seconds
+                "{}",
+                t!(
+                    "recording-stopped",
+                    id = &id[..8],
+                    duration = format_duration(duration_secs)
+                )
             );
-            println!("Transcription queued...");
+            for warning in &warnings {
+                println!("{}", t!("recording-warning", warning = warning));
+            }
+            println!("{}", t!("transcription-queued"));
         }
         DaemonResponse::Error { message } => {
-            anyhow::bail!("Failed to stop recording: {}", message);
+            anyhow::bail!(t!("recording-stop-failed", message = message));
         }
         _ => {
-            anyhow::bail!("Unexpected response from daemon");
+            anyhow::bail!(t!("daemon-unexpected-response"));
         }
     }
 
@@ -72,7 +140,10 @@ pub async fn stop_recording(settings: &Settings) -> Result<()> {
 }
 
 /// Show current recording status
-pub async fn show_status(settings: &Settings) -> Result<()> {
+///
+/// With `watch`, the status is redrawn every second as a single line with a
+/// progress bar until Ctrl-C.
+pub async fn show_status(settings: &Settings, watch: bool) -> Result<()> {
     let mut client = match DaemonClient::connect(settings).await {
         Ok(c) => c,
         Err(_) => {
@@ -81,40 +152,223 @@ pub async fn show_status(settings: &Settings) -> Result<()> {
         }
     };
 
-    let response = client.send(DaemonRequest::GetStatus).await?;
+    if watch {
+        return watch_status(&mut client).await;
+    }
 
-    match response {
-        DaemonResponse::Status(status) => match status {
-            RecordingStatus::Idle => {
-                println!("Status: Idle (not recording)");
-            }
-            RecordingStatus::Recording {
-                id,
-                title,
-                duration_secs,
-                ..
-            } => {
-                let minutes = duration_secs / 60;
-                let seconds = duration_secs % 60;
-                println!("Status: Recording");
-                println!("  Title: {}", title);
-                println!("  ID: {}", &id[..8]);
-                println!("  Duration: {}:{:02}", minutes, seconds);
-            }
-            RecordingStatus::Transcribing { id, progress } => {
-                println!("Status: Transcribing");
-                println!("  ID: {}", &id[..8]);
-                println!("  Progress: {:.0}%", progress * 100.0);
+    match fetch_status(&mut client).await? {
+        RecordingStatus::Idle => {
+            println!("{}", t!("status-idle"));
+        }
+        RecordingStatus::Recording {
+            id,
+            title,
+            duration_secs,
+            ..
+        } => {
+            println!("{}", t!("status-recording"));
+            println!("  {}", t!("status-title", title = title));
+            println!("  {}", t!("status-id", id = &id[..8]));
+            println!(
+                "  {}",
+                t!("status-duration", duration = format_duration(duration_secs))
+            );
+        }
+        RecordingStatus::Transcribing {
+            id,
+            progress,
+            processed_secs,
+            total_secs,
+            eta_secs,
+        } => {
+            println!("{}", t!("status-transcribing"));
+            println!("  {}", t!("status-id", id = &id[..8]));
+            println!(
+                "  {}",
+                t!(
+                    "status-progress",
+                    progress = format!("{:.0}", progress * 100.0)
+                )
+            );
+            if total_secs > 0 {
+                println!(
+                    "  {}",
+                    t!(
+                        "status-transcribed",
+                        processed = format_duration(processed_secs),
+                        total = format_duration(total_secs)
+                    )
+                );
             }
-        },
+            if let Some(eta) = eta_secs {
+                println!("  {}", t!("status-eta", eta = format_duration(eta)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_status(client: &mut DaemonClient) -> Result<RecordingStatus> {
+    match client.send(DaemonRequest::GetStatus).await? {
+        DaemonResponse::Status(status) => Ok(status),
         DaemonResponse::Error { message } => {
-            anyhow::bail!("Failed to get status: {}", message);
+            anyhow::bail!(t!("status-failed", message = message));
         }
         _ => {
-            anyhow::bail!("Unexpected response from daemon");
+            anyhow::bail!(t!("daemon-unexpected-response"));
+        }
+    }
+}
+
+/// Poll the daemon every second and redraw the status line in place
+async fn watch_status(client: &mut DaemonClient) -> Result<()> {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tokio::signal::ctrl_c() => {
+                println!();
+                return Ok(());
+            }
+        }
+
+        let line = status_line(&fetch_status(client).await?);
+        // Return to the start of the line and clear it before redrawing
+        print!("\r\x1b[2K{}", line);
+        std::io::stdout().flush()?;
+    }
+}
+
+/// One-line status for `status --watch`
+fn status_line(status: &RecordingStatus) -> String {
+    match status {
+        RecordingStatus::Idle => t!("status-watch-idle"),
+        RecordingStatus::Recording {
+            id, duration_secs, ..
+        } => t!(
+            "status-watch-recording",
+            id = &id[..8],
+            duration = format_duration(*duration_secs)
+        ),
+        RecordingStatus::Transcribing {
+            id,
+            progress,
+            processed_secs,
+            total_secs,
+            eta_secs,
+        } => {
+            let mut line = format!(
+                "{} {} {:>3.0}%",
+                t!("status-watch-transcribing", id = &id[..8]),
+                progress_bar(*progress, 30),
+                progress * 100.0
+            );
+            if *total_secs > 0 {
+                line.push_str(&format!(
+                    "  {} / {}",
+                    format_duration(*processed_secs),
+                    format_duration(*total_secs)
+                ));
+            }
+            if let Some(eta) = eta_secs {
+                line.push_str("  ");
+                line.push_str(&t!("status-watch-eta", eta = format_duration(*eta)));
+            }
+            line
+        }
+    }
+}
+
+/// A bar such as `[######----]` filled to `progress` (0.0 - 1.0)
+fn progress_bar(progress: f32, width: usize) -> String {
+    let filled = ((progress.clamp(0.0, 1.0) * width as f32).round() as usize).min(width);
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+}
+
+/// Record a short microphone-only memo, transcribe it in the foreground, and print it
+///
+/// The daemon is not involved. With `save` the memo is kept as a completed
+/// recording tagged "memo"; otherwise its audio is deleted after transcription.
+pub async fn record_memo(settings: &Settings, seconds: u64, save: bool) -> Result<()> {
+    settings.ensure_dirs()?;
+
+    // A memo is the microphone only, written as a single file
+    let mut memo_settings = settings.clone();
+    memo_settings.audio.capture_system = false;
+    memo_settings.audio.capture_microphone = true;
+    memo_settings.audio.keep_separate_tracks = false;
+    memo_settings.audio.segment_minutes = 0;
+
+    let mut recording = Recording::new(format!("Memo {}", Local::now().format("%Y-%m-%d %H:%M")));
+    recording.tags = vec!["memo".to_string()];
+
+    let mut capture = create_capture(&memo_settings)?;
+    let audio_path =
+        settings
+            .audio_dir()
+            .join(format!("{}.{}", recording.id, capture.output_extension()));
+    capture.start(&audio_path)?;
+    let started_at = std::time::Instant::now();
+
+    println!(
+        "Recording memo (up to {}s, press Ctrl-C to finish)...",
+        seconds
+    );
+    tokio::select! {
+        _ = tokio::time::sleep(std::time::Duration::from_secs(seconds)) => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    capture.stop()?;
+    let duration_secs = started_at.elapsed().as_secs();
+
+    println!("Transcribing...");
+    let audio_file = audio_path.to_string_lossy().to_string();
+    let transcribed = match TranscriptionPipeline::new(settings) {
+        Ok(pipeline) => pipeline
+            .transcribe(&audio_file, &recording.id, Box::new(|_| {}))
+            .await
+            .map(|segments| (segments, pipeline.language())),
+        Err(e) => Err(e),
+    };
+    let (segments, language) = match transcribed {
+        Ok(transcribed) => transcribed,
+        Err(e) => {
+            if !save {
+                let _ = std::fs::remove_file(&audio_path);
+            }
+            return Err(e);
         }
+    };
+
+    let text = segments
+        .iter()
+        .map(|segment| segment.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    println!();
+    if text.is_empty() {
+        println!("(no speech detected)");
+    } else {
+        println!("{}", text);
+    }
+
+    if !save {
+        let _ = std::fs::remove_file(&audio_path);
+        return Ok(());
     }
 
+    recording.audio_path = Some(audio_file);
+    recording.duration_secs = Some(duration_secs);
+    recording.language = language;
+    recording.state = RecordingState::Completed;
+    let db = Database::open(settings)?;
+    db.insert_recording(&recording)?;
+    db.insert_segments(&segments)?;
+    println!();
+    println!("Memo saved: {}", &recording.id[..8]);
+
     Ok(())
 }
 
@@ -123,18 +377,43 @@ pub async fn list_recordings(
     settings: &Settings,
     limit: usize,
     search: Option<String>,
+    unapproved: bool,
+    project: Option<String>,
+    tag: Option<String>,
+    filter: &RecordingFilter,
 ) -> Result<()> {
     let db = Database::open(settings)?;
 
+    let project = project.map(|name| find_project(&db, &name)).transpose()?;
     let query = search.as_deref();
     let recordings = if let Some(query) = query {
-        db.search_recordings(query, limit)?
+        db.search_recordings(query, filter, limit)?
+    } else if let Some(project) = &project {
+        db.list_project_recordings(&project.name, filter, limit)?
+    } else if let Some(tag) = &tag {
+        db.list_tagged_recordings(tag, filter, limit)?
+    } else if unapproved {
+        db.list_unapproved_recordings(filter, limit)?
     } else {
-        db.list_recordings(limit)?
+        db.list_recordings(filter, limit)?
     };
 
     if recordings.is_empty() {
-        if let Some(query) = query {
+        if !filter.is_empty() {
+            println!("No recordings match the filters.");
+            println!("Widen or drop --since, --until, --min-duration, --state, or --with.");
+        } else if let Some(project) = &project {
+            println!("No recordings in project {}.", project.name);
+            println!(
+                "Add one with: minutes project assign <id> \"{}\"",
+                project.name
+            );
+        } else if let Some(tag) = &tag {
+            println!("No recordings tagged \"{}\".", tag);
+            println!("Tag one with: minutes tags <id> --add \"{}\"", tag);
+        } else if unapproved {
+            println!("No summaries are waiting for approval.");
+        } else if let Some(query) = query {
             println!("No recordings found for query \"{}\".", query);
             println!("Try listing recent meetings with: minutes list");
         } else {
@@ -150,26 +429,46 @@ pub async fn list_recordings(
             recordings.len(),
             query
         );
+    } else if let Some(project) = &project {
+        println!(
+            "Showing {} recording(s) in project {} ({} in total, {}):",
+            recordings.len(),
+            project.name,
+            project.recording_count,
+            format_duration(project.total_duration_secs)
+        );
+    } else if let Some(tag) = &tag {
+        println!(
+            "Showing {} recording(s) tagged \"{}\":",
+            recordings.len(),
+            tag
+        );
+    } else if unapproved {
+        println!(
+            "Showing {} recording(s) with unapproved summaries:",
+            recordings.len()
+        );
     } else {
         println!("Showing {} recent recording(s):", recordings.len());
     }
     println!();
 
     println!(
-        "{:<10} {:<30} {:<12} {:<10}",
-        "ID", "Title", "Date", "Duration"
+        "{:<10} {:<30} {:<12} {:<10} {:<4}",
+        "ID", "Title", "Date", "Duration", "Lang"
     );
-    println!("{}", "-".repeat(65));
+    println!("{}", "-".repeat(70));
 
     for recording in recordings {
         let duration = format_duration(recording.duration_secs.unwrap_or(0));
         let date = recording.created_at.format("%Y-%m-%d");
         println!(
-            "{:<10} {:<30} {:<12} {:<10}",
+            "{:<10} {:<30} {:<12} {:<10} {:<4}",
             &recording.id[..8],
             truncate(&recording.title, 28),
             date,
-            duration
+            duration,
+            recording.language.as_deref().unwrap_or("-")
         );
     }
 
@@ -188,14 +487,75 @@ pub async fn view_recording(settings: &Settings, id: &str) -> Result<()> {
     println!("  ID: {}", &recording.id[..8]);
     println!("  Title: {}", recording.title);
     println!("  State: {}", recording.state.as_str());
+    if let Some(reason) = &recording.failure_reason {
+        println!("  Last failure: {}", reason);
+    }
+    if recording.is_confidential() {
+        println!("  Sensitivity: {}", recording.sensitivity.as_str());
+    }
+    if let Some(project) = &recording.project {
+        println!("  Project: {}", project);
+    }
+    if !recording.tags.is_empty() {
+        println!("  Tags: {}", recording.tags.join(", "));
+    }
+    if !recording.suggested_tags.is_empty() {
+        println!("  Suggested tags: {}", recording.suggested_tags.join(", "));
+    }
+    let participants = db.get_participants(&recording.id)?;
+    if !participants.is_empty() {
+        println!("  Participants: {}", participants.join(", "));
+    }
     println!("  Date: {}", recording.created_at.format("%Y-%m-%d %H:%M"));
     if let Some(duration) = recording.duration_secs {
         println!("  Duration: {}", format_duration(duration));
     }
+    if let Some(archived_at) = recording.archived_at {
+        let dir = recording
+            .audio_files()
+            .next()
+            .and_then(|file| Path::new(file).parent());
+        let reachable = recording.audio_files().all(|file| Path::new(file).exists());
+        println!(
+            "  Audio: archived {}{}{}",
+            archived_at.with_timezone(&Local).format("%Y-%m-%d"),
+            dir.map(|dir| format!(" to {}", dir.display()))
+                .unwrap_or_default(),
+            if reachable { "" } else { " (not reachable)" }
+        );
+    }
+    if let Some(language) = &recording.language {
+        match language_name(language) {
+            Some(name) => println!("  Language: {} ({})", name, language),
+            None => println!("  Language: {}", language),
+        }
+    }
+    if let Some(language) = &recording.language_override {
+        println!(
+            "  Language setting: {}",
+            language_name(language).unwrap_or_else(|| language.clone())
+        );
+    }
     println!();
 
+    let warnings = db.get_warnings(&recording.id)?;
+    if !warnings.is_empty() {
+        println!("Warnings:");
+        for warning in &warnings {
+            println!("  - {}", warning);
+        }
+        println!();
+    }
+
     if let Some(summary) = recording.notes.as_deref() {
         println!("Summary:");
+        if let Some(latest) = db.latest_summary(&recording.id)? {
+            println!(
+                "(Generated by {}; {})",
+                latest.provenance(),
+                latest.status()
+            );
+        }
         println!("{}", summary);
     } else {
         println!("Summary:");
@@ -206,6 +566,32 @@ pub async fn view_recording(settings: &Settings, id: &str) -> Result<()> {
     }
     println!();
 
+    let decisions = db.get_decisions(&recording.id)?;
+    if !decisions.is_empty() {
+        println!("Decisions:");
+        for decision in &decisions {
+            println!(
+                "  - {}{}",
+                decision.text,
+                format_decision_timestamps(decision)
+            );
+        }
+        println!();
+    }
+
+    let chapters = db.get_chapters(&recording.id)?;
+    if !chapters.is_empty() {
+        println!("Chapters:");
+        for chapter in &chapters {
+            println!(
+                "  {} {}",
+                format_timestamp(chapter.start_time),
+                chapter.title
+            );
+        }
+        println!();
+    }
+
     println!("Transcript:");
     let segments = db.get_transcript_segments(&recording.id)?;
     if segments.is_empty() {
@@ -213,24 +599,59 @@ pub async fn view_recording(settings: &Settings, id: &str) -> Result<()> {
         return Ok(());
     }
 
-    for segment in segments {
+    let threshold = settings.transcription.low_confidence_threshold;
+    let silences = db.get_silences(&recording.id)?;
+    let mut markers = silence_markers(&segments, &silences).into_iter().peekable();
+    let mut flagged = 0;
+    for (index, segment) in segments.iter().enumerate() {
+        while let Some((_, silence)) = markers.next_if(|(before, _)| *before == index) {
+            println!(
+                "[{}] {}",
+                format_timestamp(silence.start_time),
+                silence_marker_text(&silence)
+            );
+        }
         let timestamp = format_timestamp(segment.start_time);
-        println!("[{}] {}", timestamp, segment.text);
+        if segment.is_low_confidence(threshold) {
+            flagged += 1;
+            println!("[{}] (?) {}", timestamp, segment.labelled_text());
+        } else {
+            println!("[{}] {}", timestamp, segment.labelled_text());
+        }
+    }
+    for (_, silence) in markers {
+        println!(
+            "[{}] {}",
+            format_timestamp(silence.start_time),
+            silence_marker_text(&silence)
+        );
+    }
+    if flagged > 0 {
+        println!();
+        println!(
+            "(?) marks {} low-confidence segment(s); check them against the audio.",
+            flagged
+        );
     }
 
     Ok(())
 }
 
-/// Generate and store an AI summary for a recording.
-pub async fn summarize_recording(settings: &Settings, id: &str) -> Result<()> {
+/// Correct a transcript by hand in `$VISUAL` or `$EDITOR`
+///
+/// Changed segments are marked edited; `minutes undo` reverts the edit.
+pub fn edit_transcript(settings: &Settings, id: &str) -> Result<()> {
     let db = Database::open(settings)?;
 
-    let mut recording = db
+    let recording = db
         .find_recording_by_prefix(id)?
         .context("Recording not found")?;
-
-    println!("Generating summary for {}...", &recording.id[..8]);
-
+    if recording.state == RecordingState::Transcribing {
+        anyhow::bail!(
+            "Recording {} is being transcribed; edit it when that finishes",
+            &recording.id[..8]
+        );
+    }
     let segments = db.get_transcript_segments(&recording.id)?;
     if segments.is_empty() {
         anyhow::bail!(
@@ -239,137 +660,3271 @@ pub async fn summarize_recording(settings: &Settings, id: &str) -> Result<()> {
         );
     }
 
-    let transcript = build_summary_transcript(&segments);
-    let provider = build_provider(settings)?;
-    let summary = provider
-        .summarize(SummaryRequest {
-            title: &recording.title,
-            transcript: &transcript,
+    let path = std::env::temp_dir().join(format!("minutes-edit-{}.txt", &recording.id[..8]));
+    std::fs::write(&path, edit::render(&recording, &segments))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let status = open_in_editor(&path)?;
+    if !status.success() {
+        anyhow::bail!(
+            "Editor exited with {}; transcript unchanged. Your edits are in {}",
+            status,
+            path.display()
+        );
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let edits = edit::parse(&content)
+        .with_context(|| format!("Transcript unchanged. Your edits are in {}", path.display()))?;
+    if let Some((unknown, _)) = edits
+        .iter()
+        .find(|(id, _)| !segments.iter().any(|segment| segment.id == *id))
+    {
+        anyhow::bail!(
+            "Segment #{} is not part of this transcript. Your edits are in {}",
+            unknown,
+            path.display()
+        );
+    }
+    let _ = std::fs::remove_file(&path);
+
+    if edits.is_empty() {
+        println!("Edit cancelled; transcript unchanged.");
+        return Ok(());
+    }
+    let changed: Vec<(i64, String)> = edits
+        .into_iter()
+        .filter(|(id, text)| {
+            segments
+                .iter()
+                .any(|segment| segment.id == *id && segment.text != *text)
         })
-        .await?;
+        .collect();
+    if changed.is_empty() {
+        println!("No changes.");
+        return Ok(());
+    }
 
-    recording.notes = Some(summary.clone());
-    db.update_recording(&recording)?;
+    snapshot::take(settings, &db, "edit", &[&recording.id])?;
+    let count = db.update_segment_texts(&recording.id, &changed)?;
+    println!(
+        "Saved {} corrected segment(s) of {}. Revert with: minutes undo",
+        count,
+        &recording.id[..8]
+    );
 
-    println!("Summary saved for {}.", &recording.id[..8]);
-    println!("View it with: minutes view {}", &recording.id[..8]);
-    println!();
-    println!("Summary:");
-    println!("{}", summary);
+    Ok(())
+}
+
+/// Edit a recording's notes in `$VISUAL` or `$EDITOR`
+///
+/// The notes are usually its summary; saving changes them like notes written
+/// by hand, so `summarize` writes a fresh summary rather than keeping them.
+/// `minutes undo` reverts the edit.
+pub fn edit_notes(settings: &Settings, id: &str) -> Result<()> {
+    let db = Database::open(settings)?;
+    let mut recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+
+    let path = std::env::temp_dir().join(format!("minutes-notes-{}.md", &recording.id[..8]));
+    let original = recording.notes.clone().unwrap_or_default();
+    std::fs::write(&path, &original)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let status = open_in_editor(&path)?;
+    if !status.success() {
+        anyhow::bail!(
+            "Editor exited with {}; notes unchanged. Your edits are in {}",
+            status,
+            path.display()
+        );
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let _ = std::fs::remove_file(&path);
+
+    if content.trim() == original.trim() {
+        println!("No changes.");
+        return Ok(());
+    }
+
+    snapshot::take(settings, &db, "notes", &[&recording.id])?;
+    let notes = content.trim();
+    recording.notes = (!notes.is_empty()).then(|| notes.to_string());
+    db.update_recording(&recording)?;
+    println!(
+        "Saved the notes of {}. Revert with: minutes undo",
+        &recording.id[..8]
+    );
 
     Ok(())
 }
 
-/// Search through all transcripts
-pub async fn search_transcripts(settings: &Settings, query: &str) -> Result<()> {
+/// Open `path` in `$VISUAL` or `$EDITOR` (vi by default) and wait for it
+fn open_in_editor(path: &Path) -> Result<std::process::ExitStatus> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Run through the shell so editors configured with flags ("code --wait") work
+    Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'", editor))
+}
+
+/// Move a recording to the trash, or with `permanent` delete it for good
+///
+/// Permanent deletion asks for confirmation unless `force` and removes the
+/// audio files unless `keep_audio`. `minutes undo` brings the rows back, and
+/// the audio too when `general.snapshot_audio` is on.
+pub fn delete_recording(
+    settings: &Settings,
+    id: &str,
+    permanent: bool,
+    force: bool,
+    keep_audio: bool,
+) -> Result<()> {
     let db = Database::open(settings)?;
 
-    let results = db.search_transcripts(query, 20)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    match recording.state {
+        RecordingState::Recording => anyhow::bail!(
+            "Recording {} is still being recorded; stop it first with: minutes stop",
+            &recording.id[..8]
+        ),
+        RecordingState::Transcribing => anyhow::bail!(
+            "Recording {} is being transcribed; cancel it first with: minutes queue cancel {}",
+            &recording.id[..8],
+            &recording.id[..8]
+        ),
+        _ => {}
+    }
 
-    if results.is_empty() {
-        println!("No transcript matches found for \"{}\".", query);
-        println!("Try listing meetings first: minutes list");
+    if !permanent {
+        db.trash_recording(&recording.id)?;
+        trash::purge_expired(settings, &db)?;
+        println!(
+            "Moved \"{}\" ({}) to the trash.",
+            recording.title,
+            &recording.id[..8]
+        );
+        if settings.general.trash_days > 0 {
+            println!(
+                "It is deleted for good after {} days.",
+                settings.general.trash_days
+            );
+        }
+        println!(
+            "Restore it with: minutes trash restore {}",
+            &recording.id[..8]
+        );
         return Ok(());
     }
 
-    println!("Found {} results for: {}", results.len(), query);
-    println!();
+    if !force {
+        let has_audio = recording
+            .audio_files()
+            .chain(db.get_chunks(&recording.id)?.iter().map(String::as_str))
+            .any(|file| Path::new(file).exists());
+        let with_audio = if has_audio && !keep_audio {
+            " and its audio files"
+        } else {
+            ""
+        };
+        let question = format!(
+            "Delete \"{}\" ({}){} for good?",
+            recording.title,
+            &recording.id[..8],
+            with_audio
+        );
+        if !confirm(&question)? {
+            println!("Nothing deleted.");
+            return Ok(());
+        }
+    }
 
-    let mut current_recording_id = String::new();
+    let removed = trash::purge(settings, &db, std::slice::from_ref(&recording), keep_audio)?;
+    println!(
+        "Deleted \"{}\" ({}) and {} audio file(s).",
+        recording.title,
+        &recording.id[..8],
+        removed
+    );
+    print_undo_hint(settings, removed);
 
-    for (recording, segment) in results {
-        if recording.id != current_recording_id {
-            if !current_recording_id.is_empty() {
+    Ok(())
+}
+
+/// Handle trash subcommands
+pub fn trash_command(settings: &Settings, cmd: TrashCommand) -> Result<()> {
+    let db = Database::open(settings)?;
+    trash::purge_expired(settings, &db)?;
+
+    match cmd {
+        TrashCommand::List => {
+            let recordings = db.list_trashed_recordings(None)?;
+            if recordings.is_empty() {
+                println!("The trash is empty.");
+                return Ok(());
+            }
+
+            println!("{:<10} {:<18} Title", "ID", "Deleted");
+            println!("{}", "-".repeat(70));
+            for recording in &recordings {
+                let deleted = recording
+                    .deleted_at
+                    .map(|at| {
+                        at.with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string()
+                    })
+                    .unwrap_or_default();
+                println!(
+                    "{:<10} {:<18} {}",
+                    &recording.id[..8],
+                    deleted,
+                    recording.title
+                );
+            }
+            if settings.general.trash_days > 0 {
                 println!();
+                println!(
+                    "Recordings are deleted for good {} days after they were moved here.",
+                    settings.general.trash_days
+                );
+            }
+        }
+        TrashCommand::Restore { id } => {
+            let recording = db
+                .find_trashed_recording_by_prefix(&id)?
+                .context("Recording not found in the trash")?;
+            db.restore_trashed_recording(&recording.id)?;
+            println!("Restored \"{}\" ({}).", recording.title, &recording.id[..8]);
+        }
+        TrashCommand::Empty { force } => {
+            let recordings = db.list_trashed_recordings(None)?;
+            if recordings.is_empty() {
+                println!("The trash is empty.");
+                return Ok(());
             }
+
+            let question = format!(
+                "Delete {} recording(s) in the trash and their audio files for good?",
+                recordings.len()
+            );
+            if !force && !confirm(&question)? {
+                println!("Nothing deleted.");
+                return Ok(());
+            }
+
+            let removed = trash::purge(settings, &db, &recordings, false)?;
             println!(
-                "== {} ({}) ==",
-                recording.title,
-                recording.created_at.format("%Y-%m-%d")
+                "Deleted {} recording(s) and {} audio file(s).",
+                recordings.len(),
+                removed
             );
-            current_recording_id = recording.id.clone();
+            print_undo_hint(settings, removed);
         }
+    }
 
-        let timestamp = format_timestamp(segment.start_time);
-        println!("  [{}] {}", timestamp, segment.text);
+    Ok(())
+}
+
+/// Apply the retention policy now, or list what it would remove
+pub fn purge_recordings(settings: &Settings, dry_run: bool, force: bool) -> Result<()> {
+    if !retention::is_enabled(settings) {
+        println!("No retention policy is set.");
+        println!("Set storage.retention_days or storage.retention_max_gb in the config file.");
+        return Ok(());
+    }
+
+    let db = Database::open(settings)?;
+    let expired = retention::plan(settings, &db)?;
+    if expired.is_empty() {
+        println!("Nothing to purge.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<10} {:<18} {:>10} {:<8} Title",
+        "ID", "Created", "Audio", "Reason"
+    );
+    println!("{}", "-".repeat(80));
+    for entry in &expired {
+        let reason = match entry.reason {
+            retention::Reason::Age => "age",
+            retention::Reason::Size => "size",
+        };
+        println!(
+            "{:<10} {:<18} {:>10} {:<8} {}",
+            &entry.recording.id[..8],
+            entry
+                .recording
+                .created_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M"),
+            format_bytes(entry.bytes),
+            reason,
+            entry.recording.title
+        );
+    }
+
+    let bytes = format_bytes(expired.iter().map(|entry| entry.bytes).sum());
+    let storage = &settings.storage;
+    let action = match (
+        storage.archive_dir.trim().is_empty(),
+        storage.keep_transcripts,
+    ) {
+        (true, true) => format!("remove {} of audio, keeping the transcripts", bytes),
+        (false, true) => format!(
+            "move {} of audio to {}, keeping the transcripts",
+            bytes,
+            storage.archive_dir.trim()
+        ),
+        (true, false) => format!("delete the recordings with {} of audio", bytes),
+        (false, false) => format!(
+            "delete the recordings, moving {} of audio to {}",
+            bytes,
+            storage.archive_dir.trim()
+        ),
+    };
+    if dry_run {
+        println!("\nPurging would {}.", action);
+        return Ok(());
+    }
+    if !force
+        && !confirm(&format!(
+            "\nPurge {} recording(s) and {}?",
+            expired.len(),
+            action
+        ))?
+    {
+        println!("Nothing purged.");
+        return Ok(());
+    }
+
+    let summary = retention::apply(settings, &db, &expired)?;
+    println!(
+        "Purged {} recording(s): {} audio file(s), {}.",
+        summary.recordings,
+        summary.files,
+        format_bytes(summary.bytes)
+    );
+    print_undo_hint(
+        settings,
+        if storage.archive_dir.trim().is_empty() {
+            summary.files
+        } else {
+            0
+        },
+    );
+    Ok(())
+}
+
+/// Tables and indexes listed by `minutes db maintain`
+const DB_MAINTAIN_LISTED_TABLES: usize = 10;
+
+/// Handle database subcommands
+pub fn db_command(settings: &Settings, cmd: DbCommand) -> Result<()> {
+    match cmd {
+        DbCommand::Maintain => {
+            let db = Database::open(settings)?;
+            let path = settings.database_path();
+
+            let problems = db.integrity_check()?;
+            if !problems.is_empty() {
+                for problem in &problems {
+                    println!("  {}", problem);
+                }
+                anyhow::bail!(
+                    "The database failed its integrity check. Restore a backup with: minutes restore <file>"
+                );
+            }
+            println!("Integrity check: ok");
+
+            db.rebuild_search_index()?;
+            println!("Rebuilt and optimized the search index.");
+
+            let before = std::fs::metadata(&path)?.len();
+            db.vacuum()?;
+            let after = std::fs::metadata(&path)?.len();
+            println!(
+                "Vacuumed {}: {} -> {}",
+                path.display(),
+                format_bytes(before),
+                format_bytes(after)
+            );
+
+            println!();
+            println!("{:<32} Size", "Table or index");
+            println!("{}", "-".repeat(44));
+            for (name, bytes) in db
+                .table_sizes()?
+                .into_iter()
+                .take(DB_MAINTAIN_LISTED_TABLES)
+            {
+                println!("{:<32} {:>10}", name, format_bytes(bytes));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// e.g. "512 KB" or "12.3 MB"
+fn format_bytes(bytes: u64) -> String {
+    if bytes < 1_000_000 {
+        format!("{} KB", bytes.div_ceil(1_000))
+    } else {
+        format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+    }
+}
+
+/// Ask a yes/no question on the terminal, defaulting to no
+///
+/// Fails without a terminal, so scripts have to pass `--force`.
+fn confirm(question: &str) -> Result<bool> {
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        anyhow::bail!("Not deleting without confirmation; re-run with --force");
+    }
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    stdin.read_line(&mut answer)?;
+    Ok(answer.trim().to_lowercase().starts_with('y'))
+}
+
+/// Point at `minutes undo`, warning when removed audio files cannot come back
+fn print_undo_hint(settings: &Settings, removed_audio: usize) {
+    if removed_audio > 0 && !settings.general.snapshot_audio {
+        println!("Revert with: minutes undo (the audio files cannot be restored)");
+    } else {
+        println!("Revert with: minutes undo");
+    }
+}
+
+/// Move a recording's audio to an archive directory, keeping the rest in the library
+pub fn archive_recording(settings: &Settings, id: &str, dest: Option<&Path>) -> Result<()> {
+    let archive_dir = settings.storage.archive_dir.trim();
+    let dest = match dest {
+        Some(dest) => dest.to_path_buf(),
+        None if !archive_dir.is_empty() => PathBuf::from(archive_dir),
+        None => anyhow::bail!("No archive directory: pass --dest or set storage.archive_dir"),
+    };
+
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    if matches!(
+        recording.state,
+        RecordingState::Recording | RecordingState::Pending | RecordingState::Transcribing
+    ) {
+        anyhow::bail!(
+            "Recording {} is still being recorded or transcribed; archive it once it is done",
+            &recording.id[..8]
+        );
+    }
+
+    let mut files: Vec<PathBuf> = recording
+        .audio_files()
+        .map(str::to_string)
+        .chain(db.get_chunks(&recording.id)?)
+        .map(PathBuf::from)
+        .filter(|file| file.exists() && !file.starts_with(&dest))
+        .collect();
+    // An aligned recording can list its main file again as the mic track
+    files.sort();
+    files.dedup();
+    if files.is_empty() {
+        if recording.archived_at.is_some() {
+            println!("Recording {} is already archived.", &recording.id[..8]);
+            return Ok(());
+        }
+        anyhow::bail!("Recording {} has no audio files", &recording.id[..8]);
+    }
+
+    std::fs::create_dir_all(&dest)
+        .with_context(|| format!("Failed to create {}", dest.display()))?;
+    let mut bytes = 0;
+    for file in &files {
+        bytes += file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        retention::archive_file(&db, file, &dest)?;
+    }
+    db.set_recording_archived(&recording.id, true)?;
+
+    println!(
+        "Moved {} audio file(s) of \"{}\" ({}) to {}.",
+        files.len(),
+        recording.title,
+        format_bytes(bytes),
+        dest.display()
+    );
+    println!("The transcript, summaries and notes stay in the library.");
+    Ok(())
+}
+
+/// Give a recording a title of the user's choosing
+pub fn rename_recording(settings: &Settings, id: &str, title: &str) -> Result<()> {
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let title = title.trim();
+    if title.is_empty() {
+        anyhow::bail!("Title cannot be empty");
+    }
+
+    db.set_recording_title(&recording.id, title)?;
+    println!("Renamed \"{}\" to \"{}\".", recording.title, title);
+    Ok(())
+}
+
+/// Replace the notes and/or tags of a recording
+///
+/// Notes written this way count as edited by hand, so `summarize` writes a
+/// fresh summary instead of keeping them. `minutes undo` reverts the change.
+pub fn edit_recording_meta(
+    settings: &Settings,
+    id: &str,
+    notes_file: Option<&Path>,
+    tags: Option<Vec<String>>,
+    participants: Option<Vec<String>>,
+) -> Result<()> {
+    if notes_file.is_none() && tags.is_none() && participants.is_none() {
+        anyhow::bail!("Nothing to change; pass --notes-file, --tags and/or --participants");
+    }
+
+    let db = Database::open(settings)?;
+    let mut recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+
+    if let Some(path) = notes_file {
+        let notes = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let notes = notes.trim();
+        recording.notes = (!notes.is_empty()).then(|| notes.to_string());
+    }
+    if let Some(tags) = tags {
+        recording.tags.clear();
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !recording.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                recording.tags.push(tag.to_string());
+            }
+        }
+        recording
+            .suggested_tags
+            .retain(|tag| !recording.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+    }
+
+    snapshot::take(settings, &db, "edit-meta", &[&recording.id])?;
+    db.update_recording(&recording)?;
+    if let Some(names) = participants {
+        let mut participants = Vec::new();
+        add_participants(&mut participants, names);
+        db.set_participants(&recording.id, &participants)?;
+        if participants.is_empty() {
+            println!("No participants on \"{}\".", recording.title);
+        } else {
+            println!("Participants: {}", participants.join(", "));
+        }
+    }
+
+    if notes_file.is_some() {
+        match &recording.notes {
+            Some(notes) => println!("Notes: {} characters", notes.chars().count()),
+            None => println!("Notes cleared."),
+        }
+    }
+    if recording.tags.is_empty() {
+        println!("No tags on \"{}\".", recording.title);
+    } else {
+        println!("Tags: {}", recording.tags.join(", "));
+    }
+    println!("Revert with: minutes undo");
+    Ok(())
+}
+
+/// Generate and store an AI summary for a recording.
+pub async fn summarize_recording(
+    settings: &Settings,
+    id: &str,
+    template: Option<&str>,
+    force: bool,
+    include_confidential: bool,
+) -> Result<()> {
+    let template = summary_template(template)?;
+    let summary_language = summary_language(settings)?;
+    let db = Database::open(settings)?;
+
+    let mut recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+
+    let segments = db.get_transcript_segments(&recording.id)?;
+    if segments.is_empty() {
+        anyhow::bail!(
+            "No transcript available for recording {}",
+            &recording.id[..8]
+        );
+    }
+
+    let provider = build_provider(settings)?;
+    let input = SummaryInput {
+        transcript: build_summary_transcript(&segments),
+        attendees: db.get_participants(&recording.id)?,
+    };
+    let transcript_hash = input.hash();
+    let language = summary_language_for(summary_language.as_deref(), &recording);
+    if !force {
+        if let Some(summary) = current_summary(
+            &db,
+            provider.as_ref(),
+            &recording,
+            &transcript_hash,
+            template.as_ref(),
+            language,
+        )? {
+            println!(
+                "Summary of {} is up to date ({}). Use --force to generate it again.",
+                &recording.id[..8],
+                summary.provenance()
+            );
+            println!();
+            println!("{}", summary.text);
+            return Ok(());
+        }
+    }
+
+    ensure_cloud_allowed(&recording, provider.as_ref(), include_confidential)?;
+
+    println!("Generating summary for {}...", &recording.id[..8]);
+    println!();
+    let mut print = print_streamed;
+    let summary = generate_summary(
+        settings,
+        provider.as_ref(),
+        &recording,
+        &input,
+        template.as_ref(),
+        language,
+        Some(&mut print),
+    )
+    .await?;
+    println!();
+    println!();
+    report_redactions(provider.as_ref());
+    record_usage(
+        &db,
+        settings,
+        provider.as_ref(),
+        "summarize",
+        Some(&recording.id),
+        summary.usage,
+    )?;
+    store_summary(
+        &db,
+        provider.as_ref(),
+        &mut recording,
+        &summary.text,
+        template.as_ref(),
+        &transcript_hash,
+        language,
+    )?;
+
+    println!("Summary saved for {}.", &recording.id[..8]);
+    println!("View it with: minutes view {}", &recording.id[..8]);
+
+    Ok(())
+}
+
+/// Answer a question about a recording from its transcript
+pub async fn ask_question(
+    settings: &Settings,
+    id: &str,
+    question: &str,
+    include_confidential: bool,
+) -> Result<()> {
+    let question = question.trim();
+    if question.is_empty() {
+        anyhow::bail!("Question cannot be empty");
+    }
+
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let segments = db.get_transcript_segments(&recording.id)?;
+    if segments.is_empty() {
+        anyhow::bail!(
+            "No transcript available for recording {}",
+            &recording.id[..8]
+        );
+    }
+
+    let provider = build_provider(settings)?;
+    ensure_cloud_allowed(&recording, provider.as_ref(), include_confidential)?;
+
+    let transcript = build_question_transcript(&segments);
+    let mut print = print_streamed;
+    let answer = answer_question(
+        provider.as_ref(),
+        QuestionRequest {
+            title: &recording.title,
+            transcript: &transcript,
+            question,
+            on_text: Some(&mut print),
+        },
+    )
+    .await?;
+    println!();
+    report_redactions(provider.as_ref());
+    record_usage(
+        &db,
+        settings,
+        provider.as_ref(),
+        "ask",
+        Some(&recording.id),
+        answer.usage,
+    )?;
+
+    Ok(())
+}
+
+/// Print library totals, or LLM usage per month with `llm`
+pub fn show_stats(settings: &Settings, llm: bool, json: bool) -> Result<()> {
+    let db = Database::open(settings)?;
+
+    if !llm {
+        let stats = db.library_stats()?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+            return Ok(());
+        }
+
+        println!("Recordings:      {}", stats.recordings);
+        println!("Recorded:        {}", format_duration(stats.recorded_secs));
+        println!("Transcribed:     {}", stats.transcribed);
+        println!("Summarized:      {}", stats.summarized);
+        println!("Segments:        {}", stats.segments);
+        println!("Average length:  {}", format_duration(stats.average_secs));
+        if let Some(weekday) = &stats.busiest_weekday {
+            println!("Busiest weekday: {}", weekday);
+        }
+
+        let busiest_week = stats.per_week.iter().map(|week| week.recordings).max();
+        println!();
+        println!("{:<12} {:>10}", "Week of", "Recordings");
+        for week in &stats.per_week {
+            println!(
+                "{:<12} {:>10}  {}",
+                week.week_of.format("%Y-%m-%d"),
+                week.recordings,
+                stats_bar(week.recordings as f64, busiest_week.unwrap_or(0) as f64)
+            );
+        }
+
+        let busiest_month = stats
+            .per_month
+            .iter()
+            .map(|month| month.hours)
+            .fold(0.0, f64::max);
+        println!();
+        println!("{:<12} {:>10}", "Month", "Hours");
+        for month in &stats.per_month {
+            println!(
+                "{:<12} {:>10.1}  {}",
+                month.month,
+                month.hours,
+                stats_bar(month.hours, busiest_month)
+            );
+        }
+        return Ok(());
+    }
+
+    let totals = db.llm_usage_by_month()?;
+    if totals.is_empty() {
+        println!("No LLM usage recorded yet.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<8} {:<8} {:<24} {:>6} {:>14} {:>14} {:>10}",
+        "Month", "Provider", "Model", "Calls", "Input tokens", "Output tokens", "Est. cost"
+    );
+    println!("{}", "-".repeat(90));
+    let format_cost = |cost: Option<f64>| cost.map_or("-".to_string(), |c| format!("${:.4}", c));
+    for total in &totals {
+        println!(
+            "{:<8} {:<8} {:<24} {:>6} {:>14} {:>14} {:>10}",
+            total.month,
+            total.provider,
+            total.model,
+            total.calls,
+            total.input_tokens,
+            total.output_tokens,
+            format_cost(total.cost_usd)
+        );
+    }
+
+    if totals.iter().any(|total| total.cost_usd.is_none()) {
+        println!();
+        println!(
+            "Calls made without llm.input_cost_per_million and llm.output_cost_per_million set have no cost estimate."
+        );
+    }
+    Ok(())
+}
+
+/// Bar of `value` scaled so that `max` fills 30 columns
+fn stats_bar(value: f64, max: f64) -> String {
+    if max <= 0.0 {
+        return String::new();
+    }
+    "█".repeat((value / max * 30.0).round() as usize)
+}
+
+/// Store the tokens an LLM call used, priced with the configured rates
+fn record_usage(
+    db: &Database,
+    settings: &Settings,
+    provider: &dyn LlmProvider,
+    operation: &str,
+    recording_id: Option<&str>,
+    usage: TokenUsage,
+) -> Result<()> {
+    let priced =
+        settings.llm.input_cost_per_million > 0.0 || settings.llm.output_cost_per_million > 0.0;
+    db.record_llm_usage(&LlmUsage {
+        operation: operation.to_string(),
+        provider: provider.name().to_string(),
+        model: provider.model().to_string(),
+        recording_id: recording_id.map(str::to_string),
+        input_tokens: usage.input_tokens,
+        output_tokens: usage.output_tokens,
+        cost_usd: priced.then(|| {
+            usage.estimated_cost(
+                settings.llm.input_cost_per_million,
+                settings.llm.output_cost_per_million,
+            )
+        }),
+        created_at: chrono::Utc::now(),
+    })
+}
+
+/// Tell what `llm.redact_pii` masked in the transcripts sent to the provider
+///
+/// Printed to stderr so it never mixes into output meant for a file.
+fn report_redactions(provider: &dyn LlmProvider) {
+    let report = provider.redactions();
+    if !report.is_empty() {
+        eprintln!("Redacted before sending to {}: {}", provider.name(), report);
+    }
+}
+
+/// Print generated text as the provider streams it
+fn print_streamed(text: &str) {
+    print!("{}", text);
+    let _ = std::io::stdout().flush();
+}
+
+/// Replace a recording's title with one generated from its transcript
+pub async fn generate_recording_title(
+    settings: &Settings,
+    id: &str,
+    include_confidential: bool,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let segments = db.get_transcript_segments(&recording.id)?;
+    if segments.is_empty() {
+        anyhow::bail!(
+            "No transcript available for recording {}",
+            &recording.id[..8]
+        );
+    }
+
+    let provider = build_provider(settings)?;
+    ensure_cloud_allowed(&recording, provider.as_ref(), include_confidential)?;
+
+    let transcript = segments
+        .iter()
+        .map(TranscriptSegment::labelled_text)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let title = generate_title(provider.as_ref(), &transcript).await?;
+    report_redactions(provider.as_ref());
+    db.set_recording_title(&recording.id, &title)?;
+
+    println!("Renamed \"{}\" to \"{}\".", recording.title, title);
+    Ok(())
+}
+
+/// Show a recording's tags after applying changes and confirming suggested ones
+///
+/// Suggestions come from `--suggest` or from `llm.suggest_tags` after
+/// transcription; on a terminal each one is confirmed, `--yes` accepts them
+/// all. Declined suggestions are dropped.
+pub async fn recording_tags(
+    settings: &Settings,
+    id: &str,
+    suggest: bool,
+    yes: bool,
+    add: Vec<String>,
+    remove: Vec<String>,
+    include_confidential: bool,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let mut tags = recording.tags.clone();
+    let mut suggested = recording.suggested_tags.clone();
+
+    for tag in add {
+        let tag = tag.trim();
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    for tag in &remove {
+        tags.retain(|t| !t.eq_ignore_ascii_case(tag.trim()));
+    }
+    let editing = tags != recording.tags;
+
+    if suggest {
+        let segments = db.get_transcript_segments(&recording.id)?;
+        if segments.is_empty() {
+            anyhow::bail!(
+                "No transcript available for recording {}",
+                &recording.id[..8]
+            );
+        }
+
+        let provider = build_provider(settings)?;
+        ensure_cloud_allowed(&recording, provider.as_ref(), include_confidential)?;
+
+        let transcript = segments
+            .iter()
+            .map(TranscriptSegment::labelled_text)
+            .collect::<Vec<_>>()
+            .join("\n");
+        suggested = suggest_tags(
+            provider.as_ref(),
+            &recording.title,
+            &db.list_tags()?,
+            &transcript,
+        )
+        .await?;
+        report_redactions(provider.as_ref());
+    }
+    suggested.retain(|tag| !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+
+    // Plain edits leave pending suggestions for later
+    let stdin = std::io::stdin();
+    if !suggested.is_empty() && (suggest || yes || !editing) {
+        if yes {
+            tags.append(&mut suggested);
+        } else if stdin.is_terminal() {
+            for tag in std::mem::take(&mut suggested) {
+                print!("Add tag \"{}\"? [Y/n] ", tag);
+                std::io::stdout().flush()?;
+                let mut answer = String::new();
+                stdin.read_line(&mut answer)?;
+                if !answer.trim().to_lowercase().starts_with('n') {
+                    tags.push(tag);
+                }
+            }
+        }
+    }
+
+    if tags != recording.tags {
+        db.set_recording_tags(&recording.id, &tags)?;
+    }
+    if suggested != recording.suggested_tags {
+        db.set_suggested_tags(&recording.id, &suggested)?;
+    }
+
+    if tags.is_empty() {
+        println!("No tags on \"{}\".", recording.title);
+    } else {
+        println!("Tags: {}", tags.join(", "));
+    }
+    if !suggested.is_empty() {
+        println!("Suggested: {}", suggested.join(", "));
+        println!(
+            "Accept them with: minutes tags {} --yes",
+            &recording.id[..8]
+        );
+    }
+    Ok(())
+}
+
+/// Show a recording's participants, adding and removing names first
+pub fn recording_participants(
+    settings: &Settings,
+    id: &str,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let current = db.get_participants(&recording.id)?;
+
+    let mut participants = current.clone();
+    add_participants(&mut participants, add);
+    for name in &remove {
+        participants.retain(|p| !p.eq_ignore_ascii_case(name.trim()));
+    }
+    if participants != current {
+        db.set_participants(&recording.id, &participants)?;
+    }
+
+    if participants.is_empty() {
+        println!("No participants on \"{}\".", recording.title);
+        println!(
+            "Add one with: minutes participants {} --add <name>",
+            &recording.id[..8]
+        );
+    } else {
+        println!("Participants: {}", participants.join(", "));
+    }
+    Ok(())
+}
+
+/// Divide a recording into topic chapters and store them
+pub async fn generate_recording_chapters(
+    settings: &Settings,
+    id: &str,
+    include_confidential: bool,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let segments = db.get_transcript_segments(&recording.id)?;
+    if segments.is_empty() {
+        anyhow::bail!(
+            "No transcript available for recording {}",
+            &recording.id[..8]
+        );
+    }
+
+    let provider = build_provider(settings)?;
+    ensure_cloud_allowed(&recording, provider.as_ref(), include_confidential)?;
+
+    let transcript = build_question_transcript(&segments);
+    let chapters: Vec<Chapter> =
+        generate_chapters(provider.as_ref(), &recording.title, &transcript)
+            .await?
+            .into_iter()
+            .map(|marker| Chapter {
+                start_time: marker.start_time,
+                title: marker.title,
+            })
+            .collect();
+    report_redactions(provider.as_ref());
+    db.replace_chapters(&recording.id, &chapters)?;
+
+    for chapter in &chapters {
+        println!("{} {}", format_timestamp(chapter.start_time), chapter.title);
+    }
+    Ok(())
+}
+
+/// Extract a recording's action items, list open ones, or check one off
+///
+/// Extracting again replaces the recording's items; items already done
+/// stay done.
+pub async fn action_items(
+    settings: &Settings,
+    id: Option<&str>,
+    open: bool,
+    done: Option<i64>,
+    undone: Option<i64>,
+    include_confidential: bool,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+
+    if let Some((item_id, done_at)) = done
+        .map(|item_id| (item_id, Some(chrono::Utc::now())))
+        .or(undone.map(|item_id| (item_id, None)))
+    {
+        if !db.set_action_item_done(item_id, done_at)? {
+            anyhow::bail!("Action item {} not found", item_id);
+        }
+        let state = if done_at.is_some() { "done" } else { "open" };
+        println!("Marked action item {} {}.", item_id, state);
+        return Ok(());
+    }
+
+    if open {
+        let items = db.list_open_action_items()?;
+        if items.is_empty() {
+            println!("No open action items.");
+            return Ok(());
+        }
+
+        // Items come grouped by recording, newest meeting first
+        let mut current: Option<String> = None;
+        for item in items {
+            if current.as_deref() != Some(item.recording_id.as_str()) {
+                let title = db
+                    .get_recording(&item.recording_id)?
+                    .map(|recording| recording.title)
+                    .unwrap_or_default();
+                if current.is_some() {
+                    println!();
+                }
+                println!("{} ({})", title, &item.recording_id[..8]);
+                current = Some(item.recording_id.clone());
+            }
+            println!(
+                "  {:<6} {:<16} {:<12} {}",
+                item.id,
+                item.owner.as_deref().unwrap_or("-"),
+                item.due.as_deref().unwrap_or("-"),
+                item.task
+            );
+        }
+        return Ok(());
+    }
+
+    let id = id.context("Recording ID is required")?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let segments = db.get_transcript_segments(&recording.id)?;
+    if segments.is_empty() {
+        anyhow::bail!(
+            "No transcript available for recording {}",
+            &recording.id[..8]
+        );
+    }
+
+    let provider = build_provider(settings)?;
+    ensure_cloud_allowed(&recording, provider.as_ref(), include_confidential)?;
+
+    let transcript = build_question_transcript(&segments);
+    let extracted = extract_action_items(provider.as_ref(), &recording.title, &transcript).await?;
+    report_redactions(provider.as_ref());
+    let items: Vec<ActionItem> = extracted
+        .into_iter()
+        .map(|item| ActionItem::new(recording.id.clone(), item.owner, item.task, item.due))
+        .collect();
+    db.replace_action_items(&recording.id, &items)?;
+
+    let items = db.get_action_items(&recording.id)?;
+    if items.is_empty() {
+        println!("No action items found in \"{}\".", recording.title);
+        return Ok(());
+    }
+
+    println!(
+        "{:<6} {:<6} {:<16} {:<12} Task",
+        "ID", "Done", "Owner", "Due"
+    );
+    println!("{}", "-".repeat(70));
+    for item in items {
+        println!(
+            "{:<6} {:<6} {:<16} {:<12} {}",
+            item.id,
+            if item.is_done() { "x" } else { "" },
+            item.owner.as_deref().unwrap_or("-"),
+            item.due.as_deref().unwrap_or("-"),
+            item.task
+        );
+    }
+    println!();
+    println!("Run `minutes actions --done <ID>` to check an item off.");
+    Ok(())
+}
+
+/// Extract the decisions made in a recording, replacing earlier ones
+pub async fn extract_recording_decisions(
+    settings: &Settings,
+    id: &str,
+    include_confidential: bool,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let segments = db.get_transcript_segments(&recording.id)?;
+    if segments.is_empty() {
+        anyhow::bail!(
+            "No transcript available for recording {}",
+            &recording.id[..8]
+        );
+    }
+
+    let provider = build_provider(settings)?;
+    ensure_cloud_allowed(&recording, provider.as_ref(), include_confidential)?;
+
+    let transcript = build_question_transcript(&segments);
+    let decisions: Vec<Decision> =
+        extract_decisions(provider.as_ref(), &recording.title, &transcript)
+            .await?
+            .into_iter()
+            .map(|decision| Decision::new(recording.id.clone(), decision.text, decision.timestamps))
+            .collect();
+    report_redactions(provider.as_ref());
+    db.replace_decisions(&recording.id, &decisions)?;
+
+    if decisions.is_empty() {
+        println!("No decisions found in \"{}\".", recording.title);
+        return Ok(());
+    }
+    for decision in &decisions {
+        println!(
+            "- {}{}",
+            decision.text,
+            format_decision_timestamps(decision)
+        );
+    }
+    Ok(())
+}
+
+/// Write the formal minutes of a recording as Markdown or PDF
+pub async fn write_recording_minutes(
+    settings: &Settings,
+    id: &str,
+    format: &str,
+    output: Option<PathBuf>,
+    include_confidential: bool,
+) -> Result<()> {
+    if !matches!(format, "md" | "pdf") {
+        anyhow::bail!("Unsupported format: {}. Supported: md, pdf", format);
+    }
+
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let segments = db.get_transcript_segments(&recording.id)?;
+    if segments.is_empty() {
+        anyhow::bail!(
+            "No transcript available for recording {}",
+            &recording.id[..8]
+        );
+    }
+
+    let provider = build_provider(settings)?;
+    ensure_cloud_allowed(&recording, provider.as_ref(), include_confidential)?;
+
+    let transcript = build_question_transcript(&segments);
+    let participants = db.get_participants(&recording.id)?;
+    let minutes = write_minutes(
+        provider.as_ref(),
+        &recording.title,
+        &participants,
+        &transcript,
+    )
+    .await?;
+    report_redactions(provider.as_ref());
+    let markdown = minutes_as_md(&recording, &minutes);
+
+    if format == "pdf" {
+        let path =
+            output.unwrap_or_else(|| PathBuf::from(format!("{}-minutes.pdf", &recording.id[..8])));
+        let document = match pdf::markdown_to_pdf(&markdown) {
+            Ok(document) => document,
+            Err(e) => {
+                // Keep the generated minutes rather than make the user wait for them again
+                let fallback = path.with_extension("md");
+                std::fs::write(&fallback, &markdown)?;
+                anyhow::bail!(
+                    "{}. Wrote the minutes as Markdown to {} instead.",
+                    e,
+                    fallback.display()
+                );
+            }
+        };
+        std::fs::write(&path, document)?;
+        println!("Exported to: {}", path.display());
+    } else if let Some(path) = output {
+        std::fs::write(&path, markdown)?;
+        println!("Exported to: {}", path.display());
+    } else {
+        print!("{}", markdown);
+    }
+    Ok(())
+}
+
+/// Minutes as a Markdown document, with "None" under empty sections
+fn minutes_as_md(recording: &Recording, minutes: &MeetingMinutes) -> String {
+    let mut output = String::new();
+    output.push_str(&format!("# Minutes: {}\n\n", recording.title));
+    output.push_str(&format!(
+        "- Date: {}\n",
+        recording.created_at.format("%Y-%m-%d %H:%M")
+    ));
+    if let Some(duration) = recording.duration_secs {
+        output.push_str(&format!("- Duration: {}\n", format_duration(duration)));
+    }
+
+    output.push_str("\n## Attendees\n\n");
+    if minutes.attendees.is_empty() {
+        output.push_str("None\n");
+    }
+    for attendee in &minutes.attendees {
+        output.push_str(&format!("- {}\n", attendee));
+    }
+
+    output.push_str("\n## Agenda\n");
+    if minutes.agenda.is_empty() {
+        output.push_str("\nNone\n");
+    }
+    for (i, item) in minutes.agenda.iter().enumerate() {
+        let start = item
+            .start
+            .map(|start| format!(" [{}]", format_timestamp(start)))
+            .unwrap_or_default();
+        output.push_str(&format!("\n### {}. {}{}\n", i + 1, item.topic, start));
+        if !item.discussion.is_empty() {
+            output.push_str(&format!("\n{}\n", item.discussion));
+        }
+    }
+
+    output.push_str("\n## Decisions\n\n");
+    if minutes.decisions.is_empty() {
+        output.push_str("None\n");
+    }
+    for decision in &minutes.decisions {
+        output.push_str(&format!("- {}\n", decision));
+    }
+
+    output.push_str("\n## Action Items\n\n");
+    if minutes.action_items.is_empty() {
+        output.push_str("None\n");
+    }
+    for item in &minutes.action_items {
+        let details: Vec<String> = item
+            .owner
+            .iter()
+            .cloned()
+            .chain(item.due.iter().map(|due| format!("due {}", due)))
+            .collect();
+        if details.is_empty() {
+            output.push_str(&format!("- {}\n", item.task));
+        } else {
+            output.push_str(&format!("- {} ({})\n", item.task, details.join(", ")));
+        }
+    }
+    output
+}
+
+/// Show how the speakers of a recording took part, optionally described by the LLM
+pub async fn recording_analytics(
+    settings: &Settings,
+    id: &str,
+    narrative: bool,
+    include_confidential: bool,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    let segments = db.get_transcript_segments(&recording.id)?;
+    if segments.is_empty() {
+        anyhow::bail!(
+            "No transcript available for recording {}",
+            &recording.id[..8]
+        );
+    }
+
+    let stats = TranscriptStats::compute(&recording, &segments);
+    if stats.speakers.iter().all(|s| s.speaker.is_none()) {
+        println!("No speaker labels in {}.", &recording.id[..8]);
+        println!("Talk time per speaker needs a diarized transcript.");
+        return Ok(());
+    }
+
+    let table = participation_table(&stats);
+    println!(
+        "{} ({} of speech)",
+        recording.title,
+        format_duration(stats.speaking_secs as u64)
+    );
+    println!();
+    print!("{}", table);
+
+    if narrative {
+        let provider = build_provider(settings)?;
+        ensure_cloud_allowed(&recording, provider.as_ref(), include_confidential)?;
+
+        println!();
+        let transcript = build_question_transcript(&segments);
+        let mut print = print_streamed;
+        let narrative = describe_participation(
+            provider.as_ref(),
+            &recording.title,
+            &table,
+            &transcript,
+            Some(&mut print),
+        )
+        .await?;
+        println!();
+        report_redactions(provider.as_ref());
+        record_usage(
+            &db,
+            settings,
+            provider.as_ref(),
+            "analytics",
+            Some(&recording.id),
+            narrative.usage,
+        )?;
+    }
+    Ok(())
+}
+
+/// Per-speaker participation as a plain-text table, most talk time first
+fn participation_table(stats: &TranscriptStats) -> String {
+    let mut table = format!(
+        "{:<24} {:>9} {:>6} {:>7} {:>13} {:>17}\n",
+        "Speaker", "Talk time", "Share", "Words", "Interruptions", "Longest monologue"
+    );
+    table.push_str(&"-".repeat(80));
+    table.push('\n');
+    for speaker in &stats.speakers {
+        table.push_str(&format!(
+            "{:<24} {:>9} {:>5.0}% {:>7} {:>13} {:>17}\n",
+            speaker.speaker.as_deref().unwrap_or("(unlabelled)"),
+            format_duration(speaker.speaking_secs as u64),
+            stats.talk_share(speaker) * 100.0,
+            speaker.word_count,
+            speaker.interruptions,
+            format_duration(speaker.longest_monologue_secs as u64)
+        ));
+    }
+    table
+}
+
+/// Timestamps supporting a decision as ` [mm:ss, mm:ss]`, or nothing
+fn format_decision_timestamps(decision: &Decision) -> String {
+    if decision.timestamps.is_empty() {
+        return String::new();
+    }
+    let timestamps: Vec<String> = decision
+        .timestamps
+        .iter()
+        .map(|&timestamp| format_timestamp(timestamp))
+        .collect();
+    format!(" [{}]", timestamps.join(", "))
+}
+
+/// Summarize recordings in batch, optionally only those without a summary
+pub async fn summarize_all(
+    settings: &Settings,
+    missing: bool,
+    since: Option<&str>,
+    project: Option<&str>,
+    delay_secs: u64,
+    template: Option<&str>,
+    force: bool,
+    include_confidential: bool,
+) -> Result<()> {
+    let template = summary_template(template)?;
+    let summary_language = summary_language(settings)?;
+    let since = since.map(parse_since).transpose()?;
+    let db = Database::open(settings)?;
+    let project = project.map(|name| find_project(&db, name)).transpose()?;
+
+    let mut queue = Vec::new();
+    let mut skipped = 0;
+    for recording in db.list_recordings_since(since)? {
+        if missing && has_summary(&recording) {
+            continue;
+        }
+        if project
+            .as_ref()
+            .is_some_and(|project| recording.project.as_deref() != Some(&project.name))
+        {
+            continue;
+        }
+
+        let segments = db.get_transcript_segments(&recording.id)?;
+        if segments.is_empty() {
+            skipped += 1;
+            continue;
+        }
+        let input = SummaryInput {
+            transcript: build_summary_transcript(&segments),
+            attendees: db.get_participants(&recording.id)?,
+        };
+        queue.push((recording, input));
+    }
+
+    if queue.is_empty() {
+        println!("No recordings to summarize.");
+        return Ok(());
+    }
+
+    let provider = build_provider(settings)?;
+    let queued = queue.len();
+    if provider.is_cloud() && !include_confidential {
+        queue.retain(|(recording, _)| !recording.is_confidential());
+    }
+    let confidential = queued - queue.len();
+    if confidential > 0 {
+        println!(
+            "Skipping {} confidential recording(s); use --include-confidential to send them to '{}'.",
+            confidential, settings.llm.provider
+        );
+    }
+    if !force {
+        let mut stale = Vec::with_capacity(queue.len());
+        for (recording, input) in queue {
+            let hash = input.hash();
+            let language = summary_language_for(summary_language.as_deref(), &recording);
+            if current_summary(
+                &db,
+                provider.as_ref(),
+                &recording,
+                &hash,
+                template.as_ref(),
+                language,
+            )?
+            .is_none()
+            {
+                stale.push((recording, input));
+            }
+        }
+        let up_to_date = queued - confidential - stale.len();
+        if up_to_date > 0 {
+            println!(
+                "Skipping {} recording(s) with an up-to-date summary; use --force to summarize them again.",
+                up_to_date
+            );
+        }
+        queue = stale;
+    }
+    if queue.is_empty() {
+        println!("No recordings to summarize.");
+        return Ok(());
+    }
+
+    let total = queue.len();
+    let mut usage = TokenUsage::default();
+    let mut failed = 0;
+
+    for (index, (mut recording, input)) in queue.into_iter().enumerate() {
+        if index > 0 && delay_secs > 0 {
+            tokio::time::sleep(std::time::Duration::from_secs(delay_secs)).await;
+        }
+
+        print!(
+            "[{}/{}] {} ({})... ",
+            index + 1,
+            total,
+            recording.title,
+            &recording.id[..8]
+        );
+        let _ = std::io::stdout().flush();
+
+        let language = summary_language_for(summary_language.as_deref(), &recording);
+        match generate_summary(
+            settings,
+            provider.as_ref(),
+            &recording,
+            &input,
+            template.as_ref(),
+            language,
+            None,
+        )
+        .await
+        {
+            Ok(summary) => {
+                usage.add(summary.usage);
+                record_usage(
+                    &db,
+                    settings,
+                    provider.as_ref(),
+                    "summarize",
+                    Some(&recording.id),
+                    summary.usage,
+                )?;
+                store_summary(
+                    &db,
+                    provider.as_ref(),
+                    &mut recording,
+                    &summary.text,
+                    template.as_ref(),
+                    &input.hash(),
+                    language,
+                )?;
+                println!("done");
+            }
+            Err(e) => {
+                failed += 1;
+                println!("failed: {}", e);
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "Summarized {} of {} recordings ({} failed, {} without transcript skipped).",
+        total - failed,
+        total,
+        failed,
+        skipped
+    );
+    println!(
+        "Tokens: {} input, {} output",
+        usage.input_tokens, usage.output_tokens
+    );
+    report_redactions(provider.as_ref());
+    if settings.llm.input_cost_per_million > 0.0 || settings.llm.output_cost_per_million > 0.0 {
+        println!(
+            "Estimated cost: ${:.4}",
+            usage.estimated_cost(
+                settings.llm.input_cost_per_million,
+                settings.llm.output_cost_per_million
+            )
+        );
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} summaries failed", failed, total);
+    }
+
+    Ok(())
+}
+
+/// Template named on the command line, or the user's default template
+fn summary_template(name: Option<&str>) -> Result<Option<PromptTemplate>> {
+    let dir = Settings::prompts_dir()?;
+    match name {
+        Some(name) => PromptTemplate::load(&dir, name).map(Some),
+        None => PromptTemplate::load_default(&dir),
+    }
+}
+
+/// English name of the language set by `llm.summary_language`, if any
+fn summary_language(settings: &Settings) -> Result<Option<String>> {
+    let language = settings.llm.summary_language.trim();
+    if language.is_empty() {
+        return Ok(None);
+    }
+    language_name(language)
+        .map(Some)
+        .with_context(|| format!("Unknown summary language '{}'", language))
+}
+
+/// Summary language asked for a recording, `None` when it is the meeting's own
+fn summary_language_for<'a>(language: Option<&'a str>, recording: &Recording) -> Option<&'a str> {
+    let spoken = recording.language.as_deref().and_then(language_name);
+    language.filter(|language| spoken.as_deref() != Some(*language))
+}
+
+/// What a summary is written from: the transcript and who attended
+struct SummaryInput {
+    /// Built with `build_summary_transcript`
+    transcript: String,
+    attendees: Vec<String>,
+}
+
+impl SummaryInput {
+    /// Fingerprint stored with the summary to tell when it is out of date
+    ///
+    /// Attendees count only once there are some, so summaries of recordings
+    /// without participants stay up to date.
+    fn hash(&self) -> String {
+        if self.attendees.is_empty() {
+            Summary::hash_transcript(&self.transcript)
+        } else {
+            Summary::hash_transcript(&format!(
+                "Attendees: {}\n{}",
+                self.attendees.join(", "),
+                self.transcript
+            ))
+        }
+    }
+}
+
+/// Summarize a recording
+///
+/// `summary_language` comes from `summary_language_for`.
+async fn generate_summary(
+    settings: &Settings,
+    provider: &dyn LlmProvider,
+    recording: &Recording,
+    input: &SummaryInput,
+    template: Option<&PromptTemplate>,
+    summary_language: Option<&str>,
+    on_text: Option<TextSink<'_>>,
+) -> Result<SummaryResponse> {
+    let language = recording.language.as_deref().and_then(language_name);
+    provider
+        .summarize(SummaryRequest {
+            title: &recording.title,
+            attendees: &input.attendees,
+            transcript: &input.transcript,
+            language: language.as_deref(),
+            summary_language,
+            template,
+            chunking: Chunking::from_settings(settings),
+            // Shorten the sink's lifetime to that of the local language name
+            on_text: on_text.map(|sink| sink as TextSink<'_>),
+        })
+        .await
+}
+
+/// Save a summary as the recording's notes and record its provenance
+fn store_summary(
+    db: &Database,
+    provider: &dyn LlmProvider,
+    recording: &mut Recording,
+    text: &str,
+    template: Option<&PromptTemplate>,
+    transcript_hash: &str,
+    language: Option<&str>,
+) -> Result<()> {
+    recording.notes = Some(text.to_string());
+    db.update_recording(recording)?;
+    let mut summary = Summary::new(
+        recording.id.clone(),
+        text.to_string(),
+        provider.name(),
+        provider.model(),
+        SUMMARY_PROMPT_VERSION,
+    );
+    summary.template = template.map(|template| template.name.clone());
+    summary.transcript_hash = Some(transcript_hash.to_string());
+    summary.language = language.map(str::to_string);
+    db.insert_summary(&summary)?;
+    Ok(())
+}
+
+/// Latest summary of a recording if summarizing it again would not change its input
+///
+/// The summary must still be the recording's notes, so notes cleared or
+/// written by hand are summarized again.
+fn current_summary(
+    db: &Database,
+    provider: &dyn LlmProvider,
+    recording: &Recording,
+    transcript_hash: &str,
+    template: Option<&PromptTemplate>,
+    language: Option<&str>,
+) -> Result<Option<Summary>> {
+    let summary = db.latest_summary(&recording.id)?.filter(|summary| {
+        recording.notes.as_deref() == Some(summary.text.as_str())
+            && summary.is_up_to_date(
+                transcript_hash,
+                provider.name(),
+                provider.model(),
+                SUMMARY_PROMPT_VERSION,
+                template.map(|template| template.name.as_str()),
+                language,
+            )
+    });
+    Ok(summary)
+}
+
+fn has_summary(recording: &Recording) -> bool {
+    recording
+        .notes
+        .as_deref()
+        .map(|notes| !notes.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Parse a `--since` window such as `30d`, `2w`, `12h` into a cutoff time
+fn parse_since(value: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .with_context(|| format!("Invalid --since value '{}'. Use e.g. 30d, 2w, 12h", value))?;
+
+    let duration = match unit {
+        "h" => chrono::TimeDelta::try_hours(amount),
+        "d" | "" => chrono::TimeDelta::try_days(amount),
+        "w" => chrono::TimeDelta::try_weeks(amount),
+        _ => anyhow::bail!("Invalid --since unit '{}'. Use h, d, or w", unit),
+    };
+
+    duration
+        .and_then(|duration| chrono::Utc::now().checked_sub_signed(duration))
+        .with_context(|| format!("--since value '{}' reaches too far back", value))
+}
+
+/// Build the filter of the `--since`, `--until`, `--min-duration`, `--state`,
+/// and `--with` flags of `minutes list` and `minutes search`
+pub fn recording_filter(
+    since: Option<&str>,
+    until: Option<&str>,
+    min_duration: Option<&str>,
+    state: Option<&str>,
+    participant: Option<&str>,
+) -> Result<RecordingFilter> {
+    let filter = RecordingFilter {
+        since: since
+            .map(|value| parse_date_bound(value, "--since", false))
+            .transpose()?,
+        until: until
+            .map(|value| parse_date_bound(value, "--until", true))
+            .transpose()?,
+        min_duration_secs: min_duration.map(parse_min_duration).transpose()?,
+        state: state
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Unknown state: {}", value))
+            })
+            .transpose()?,
+        participant: participant
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string),
+    };
+    if let (Some(since), Some(until)) = (filter.since, filter.until) {
+        if until <= since {
+            anyhow::bail!("--until must be later than --since");
+        }
+    }
+    Ok(filter)
+}
+
+/// Parse a `YYYY-MM-DD` date in local time, or a window such as `30d` back
+/// from now
+///
+/// With `end_of_day` a date stands for the end of that day, so `--until`
+/// includes it.
+fn parse_date_bound(
+    value: &str,
+    flag: &str,
+    end_of_day: bool,
+) -> Result<chrono::DateTime<chrono::Utc>> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d") {
+        let date = if end_of_day {
+            date.succ_opt().unwrap_or(date)
+        } else {
+            date
+        };
+        return date
+            .and_time(chrono::NaiveTime::MIN)
+            .and_local_timezone(Local)
+            .earliest()
+            .map(|time| time.with_timezone(&chrono::Utc))
+            .with_context(|| format!("Invalid {} date '{}'", flag, value));
+    }
+    parse_since(value).map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid {} value '{}'. Use a date such as 2026-03-01 or a window such as 30d, 2w, 12h",
+            flag,
+            value
+        )
+    })
+}
+
+/// Parse a `--min-duration` such as `90s`, `30m`, `1h` into seconds,
+/// reading a bare number as minutes
+fn parse_min_duration(value: &str) -> Result<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount.parse().with_context(|| {
+        format!(
+            "Invalid --min-duration value '{}'. Use e.g. 90s, 30m, 1h",
+            value
+        )
+    })?;
+
+    match unit {
+        "s" => Ok(amount),
+        "m" | "" => Ok(amount * 60),
+        "h" => Ok(amount * 3600),
+        _ => anyhow::bail!("Invalid --min-duration unit '{}'. Use s, m, or h", unit),
+    }
+}
+
+/// Search through all transcripts
+pub async fn search_transcripts(
+    settings: &Settings,
+    query: &str,
+    filter: &RecordingFilter,
+) -> Result<()> {
+    if SearchQuery::parse(query).is_empty() {
+        println!(
+            "Nothing to search for in \"{}\": give at least one word that is not excluded with NOT.",
+            query
+        );
+        return Ok(());
+    }
+
+    let db = Database::open(settings)?;
+
+    let results = db.search_transcripts(query, filter, 20)?;
+
+    if results.is_empty() {
+        println!("No transcript matches found for \"{}\".", query);
+        if filter.is_empty() {
+            println!("Try listing meetings first: minutes list");
+        } else {
+            println!("Widen or drop --since, --until, --min-duration, --state, or --with.");
+        }
+        return Ok(());
+    }
+
+    println!("Found {} results for: {}", results.len(), query);
+    println!();
+
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let mut current_recording_id = String::new();
+
+    for hit in results {
+        let (recording, segment) = (&hit.recording, &hit.segment);
+        if recording.id != current_recording_id {
+            if !current_recording_id.is_empty() {
+                println!();
+            }
+            println!(
+                "== {} ({}) ==",
+                recording.title,
+                recording.created_at.format("%Y-%m-%d")
+            );
+            current_recording_id = recording.id.clone();
+        }
+
+        let parts = hit.excerpt_parts();
+        // With word timestamps, point at the first matched word itself
+        let start = parts
+            .iter()
+            .filter(|(_, matched)| *matched)
+            .find_map(|(term, _)| segment.find_word(term))
+            .map_or(segment.start_time, |word| word.start_time);
+        let excerpt: String = parts
+            .into_iter()
+            .map(|(text, matched)| {
+                if matched && color {
+                    format!("\x1b[1;33m{}\x1b[0m", text)
+                } else {
+                    text.to_string()
+                }
+            })
+            .collect();
+        let speaker = segment
+            .speaker
+            .as_deref()
+            .map(|speaker| format!("{}: ", speaker))
+            .unwrap_or_default();
+        println!("  [{}] {}{}", format_timestamp(start), speaker, excerpt);
+    }
+
+    Ok(())
+}
+
+/// Segments returned by a semantic search
+const SEMANTIC_SEARCH_LIMIT: usize = 20;
+
+/// Find transcript segments by meaning, embedding the ones not indexed yet
+pub async fn semantic_search(
+    settings: &Settings,
+    query: &str,
+    include_confidential: bool,
+) -> Result<()> {
+    let query = query.trim();
+    if query.is_empty() {
+        anyhow::bail!("Search query cannot be empty");
+    }
+
+    let db = Database::open(settings)?;
+    let provider = build_provider(settings)?;
+    let results = closest_segments(
+        settings,
+        &db,
+        provider.as_ref(),
+        query,
+        include_confidential,
+        SEMANTIC_SEARCH_LIMIT,
+    )
+    .await?;
+    report_redactions(provider.as_ref());
+
+    if results.is_empty() {
+        println!("No transcripts to search yet.");
+        println!("Try listing meetings first: minutes list");
+        return Ok(());
+    }
+
+    println!("Closest {} segments to: {}", results.len(), query);
+    println!();
+    for (recording, segment, score) in &results {
+        println!(
+            "{:.2}  {} ({}) [{}] {}",
+            score,
+            recording.title,
+            recording.created_at.format("%Y-%m-%d"),
+            format_timestamp(segment.start_time),
+            segment.labelled_text()
+        );
+    }
+
+    Ok(())
+}
+
+/// Segments most similar to `query` with their scores, best first
+///
+/// Segments without an embedding from the provider's model are indexed
+/// first. Confidential recordings are left out for a cloud provider unless
+/// `include_confidential` is set.
+async fn closest_segments(
+    settings: &Settings,
+    db: &Database,
+    provider: &dyn LlmProvider,
+    query: &str,
+    include_confidential: bool,
+    limit: usize,
+) -> Result<Vec<(Recording, TranscriptSegment, f32)>> {
+    let model = provider
+        .embedding_model()
+        .with_context(|| {
+            format!(
+                "llm.provider '{}' does not support embeddings",
+                settings.llm.provider
+            )
+        })?
+        .to_string();
+    // Indexing sends segment text to the provider
+    let include_confidential = include_confidential || !provider.is_cloud();
+
+    let missing = db.segments_missing_embeddings(&model, include_confidential)?;
+    if !missing.is_empty() {
+        println!(
+            "Indexing {} new segment(s) with {}...",
+            missing.len(),
+            model
+        );
+    }
+    for batch in missing.chunks(EMBED_BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|segment| segment.text.clone()).collect();
+        let vectors = provider.embed(&texts).await?;
+        let embeddings: Vec<(&TranscriptSegment, Vec<f32>)> = batch.iter().zip(vectors).collect();
+        db.insert_segment_embeddings(&model, &embeddings)?;
+    }
+
+    let query_vector = provider
+        .embed(&[query.to_string()])
+        .await?
+        .pop()
+        .context("Provider returned no embedding for the query")?;
+    db.search_semantic(&model, &query_vector, limit, include_confidential)
+}
+
+/// Segments retrieved to answer a question over every recording
+const LIBRARY_ANSWER_SEGMENTS: usize = 40;
+
+/// Segments kept on each side of a retrieved one, so a short reply like
+/// "yes, let's do that" comes with what it agreed to
+const LIBRARY_ANSWER_CONTEXT: usize = 1;
+
+/// Answer a question from the closest transcript segments of every recording
+pub async fn ask_library(
+    settings: &Settings,
+    question: &str,
+    include_confidential: bool,
+) -> Result<()> {
+    let question = question.trim();
+    if question.is_empty() {
+        anyhow::bail!("Question cannot be empty");
+    }
+
+    let db = Database::open(settings)?;
+    let provider = build_provider(settings)?;
+    let results = closest_segments(
+        settings,
+        &db,
+        provider.as_ref(),
+        question,
+        include_confidential,
+        LIBRARY_ANSWER_SEGMENTS,
+    )
+    .await?;
+    if results.is_empty() {
+        println!("No transcripts to search yet.");
+        println!("Try listing meetings first: minutes list");
+        return Ok(());
+    }
+
+    // Recordings in order of their best segment, each with the lines around
+    // its retrieved segments in transcript order
+    let mut sources: Vec<(Recording, Vec<i64>)> = Vec::new();
+    for (recording, segment, _) in results {
+        match sources.iter_mut().find(|(r, _)| r.id == recording.id) {
+            Some((_, hits)) => hits.push(segment.id),
+            None => sources.push((recording, vec![segment.id])),
+        }
+    }
+    let mut excerpts = Vec::new();
+    for (recording, hits) in &sources {
+        let segments = db.get_transcript_segments(&recording.id)?;
+        let lines: String = segments
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                let window = i.saturating_sub(LIBRARY_ANSWER_CONTEXT)..=i + LIBRARY_ANSWER_CONTEXT;
+                window
+                    .filter_map(|j| segments.get(j))
+                    .any(|segment| hits.contains(&segment.id))
+            })
+            .map(|(_, segment)| {
+                format!(
+                    "[{}] {}\n",
+                    format_timestamp(segment.start_time),
+                    segment.labelled_text()
+                )
+            })
+            .collect();
+        excerpts.push(LibraryExcerpt {
+            title: recording.title.clone(),
+            date: recording.created_at.format("%Y-%m-%d").to_string(),
+            transcript: lines,
+        });
+    }
+
+    let mut print = print_streamed;
+    let answer = answer_library_question(
+        provider.as_ref(),
+        LibraryQuestionRequest {
+            excerpts: &excerpts,
+            question,
+            on_text: Some(&mut print),
+        },
+    )
+    .await?;
+    println!();
+    report_redactions(provider.as_ref());
+    record_usage(&db, settings, provider.as_ref(), "ask", None, answer.usage)?;
+    println!();
+    println!("Sources:");
+    for (recording, _) in &sources {
+        println!(
+            "  {}  {} ({})",
+            &recording.id[..8],
+            recording.title,
+            recording.created_at.format("%Y-%m-%d")
+        );
+    }
+
+    Ok(())
+}
+
+/// Export a recording to a file
+pub async fn export_recording(
+    settings: &Settings,
+    id: &str,
+    format: &str,
+    output: Option<PathBuf>,
+    include_confidential: bool,
+    translated: bool,
+    chapters: bool,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+
+    if recording.is_confidential() && !include_confidential {
+        anyhow::bail!(
+            "Recording {} is confidential. Re-run with --include-confidential to export it.",
+            &recording.id[..8]
+        );
+    }
+
+    if format == "mp3" {
+        let path = output.unwrap_or_else(|| PathBuf::from(format!("{}.mp3", &recording.id[..8])));
+        return export_audio(&db, &recording, &path);
+    }
+
+    let mut segments = db.get_transcript_segments(&recording.id)?;
+    let summary = db.latest_summary(&recording.id)?;
+    let stored_chapters = db.get_chapters(&recording.id)?;
+    let participants = db.get_participants(&recording.id)?;
+    // JSON always carries both texts
+    if translated && format != "json" {
+        use_translations(&mut segments);
+    }
+
+    if chapters {
+        if !matches!(format, "srt" | "vtt") {
+            anyhow::bail!("--chapters is supported for srt and vtt only");
+        }
+        if stored_chapters.is_empty() {
+            anyhow::bail!(
+                "Recording {} has no chapters. Run: minutes chapters {}",
+                &recording.id[..8],
+                &recording.id[..8]
+            );
+        }
+        segments = chapter_cues(&recording, &segments, &stored_chapters);
+    }
+
+    let content = match format {
+        "txt" => export_as_txt(&recording, &participants, &segments, &stored_chapters),
+        "md" => export_as_md(
+            &recording,
+            &participants,
+            &segments,
+            &stored_chapters,
+            &db.get_decisions(&recording.id)?,
+        ),
+        "json" => export_as_json(
+            &recording,
+            &participants,
+            &segments,
+            summary.as_ref(),
+            &stored_chapters,
+        )?,
+        "srt" => export_as_srt(&segments),
+        "vtt" => export_as_vtt(&segments),
+        _ => anyhow::bail!(
+            "Unsupported format: {}. Supported: txt, md, json, srt, vtt, mp3",
+            format
+        ),
+    };
+
+    if let Some(path) = output {
+        std::fs::write(&path, content)?;
+        println!("Exported to: {}", path.display());
+    } else {
+        print!("{}", content);
+    }
+
+    Ok(())
+}
+
+/// Fail with a pointer to the archive when archived audio is not reachable,
+/// e.g. because the network share it was moved to is not mounted
+fn ensure_audio_reachable(
+    recording: &Recording,
+    files: impl IntoIterator<Item = impl AsRef<Path>>,
+) -> Result<()> {
+    if recording.archived_at.is_some() && files.into_iter().any(|file| !file.as_ref().exists()) {
+        anyhow::bail!(recording.missing_audio_message());
+    }
+    Ok(())
+}
+
+/// Write a recording's audio as MP3 for players without OGG Opus support
+fn export_audio(db: &Database, recording: &Recording, path: &Path) -> Result<()> {
+    let chunks = db.get_chunks(&recording.id)?;
+    // At the stored rate and channels, so split-stereo keeps speakers apart
+    let (sample_rate, channels, samples) = if chunks.is_empty() {
+        let audio_path = recording
+            .audio_path
+            .as_deref()
+            .context("Recording has no audio file")?;
+        ensure_audio_reachable(recording, [audio_path])?;
+        load_native(Path::new(audio_path))?
+    } else {
+        ensure_audio_reachable(recording, &chunks)?;
+        load_chunks_native(&chunks)?
+    };
+
+    let pcm: Vec<i16> = samples
+        .iter()
+        .map(|&sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect();
+    write_mp3(path, sample_rate, channels as u16, &pcm)?;
+    println!("Exported audio to: {}", path.display());
+
+    Ok(())
+}
+
+/// Export every recording as a static HTML site
+pub fn export_site(
+    settings: &Settings,
+    output_dir: &Path,
+    include_confidential: bool,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+
+    let mut recordings = Vec::new();
+    let mut confidential = 0;
+    for recording in db.list_recordings_since(None)? {
+        if recording.is_confidential() && !include_confidential {
+            confidential += 1;
+            continue;
+        }
+        recordings.push(site::SitePage {
+            segments: db.get_transcript_segments(&recording.id)?,
+            summary: db.latest_summary(&recording.id)?,
+            recording,
+        });
+    }
+
+    let count = site::write_site(&recordings, output_dir)?;
+    println!(
+        "Exported {} recording(s) to: {}",
+        count,
+        output_dir.join("index.html").display()
+    );
+    if confidential > 0 {
+        println!(
+            "Left out {} confidential recording(s); use --include-confidential to include them.",
+            confidential
+        );
+    }
+
+    Ok(())
+}
+
+/// Add an existing audio file to the library as a pending recording
+///
+/// WAV, OGG, and FLAC files are copied (or linked with `link`); other formats
+/// are converted to FLAC with ffmpeg. The daemon's transcription worker picks
+/// the recording up like one it captured itself.
+pub async fn import_recording(
+    settings: &Settings,
+    path: &Path,
+    title: Option<String>,
+    link: bool,
+) -> Result<()> {
+    let source = path
+        .canonicalize()
+        .with_context(|| format!("Audio file not found: {}", path.display()))?;
+    if !source.is_file() {
+        anyhow::bail!("Not a file: {}", source.display());
+    }
+
+    let extension = source
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let native = NATIVE_AUDIO_EXTENSIONS.contains(&extension.as_str());
+
+    let title = title.unwrap_or_else(|| {
+        source
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Imported recording".to_string())
+    });
+    let mut recording = Recording::new(title);
+    // Date the meeting by the file rather than by the import
+    if let Ok(modified) = source.metadata().and_then(|meta| meta.modified()) {
+        recording.created_at = modified.into();
+    }
+
+    let audio_dir = settings.audio_dir();
+    std::fs::create_dir_all(&audio_dir)?;
+    let target = audio_dir.join(format!(
+        "{}.{}",
+        recording.id,
+        if native { extension.as_str() } else { "flac" }
+    ));
+
+    if !native {
+        if link {
+            println!(
+                "Note: .{} files are converted, so a copy is stored.",
+                extension
+            );
+        }
+        println!("Converting {} to FLAC with ffmpeg...", source.display());
+        convert_with_ffmpeg(&source, &target)?;
+    } else if link {
+        link_audio(&source, &target)?;
+    } else {
+        std::fs::copy(&source, &target)
+            .with_context(|| format!("Failed to copy {}", source.display()))?;
+    }
+
+    let duration_secs = match audio_duration_secs(&target) {
+        Ok(secs) => secs,
+        Err(e) => {
+            let _ = std::fs::remove_file(&target);
+            return Err(e.context(format!("Could not read {} as audio", source.display())));
+        }
+    };
+
+    recording.audio_path = Some(target.to_string_lossy().to_string());
+    recording.duration_secs = Some(duration_secs.round() as u64);
+    recording.state = RecordingState::Pending;
+    let db = Database::open(settings)?;
+    db.insert_recording(&recording)?;
+
+    println!(
+        "Imported \"{}\" as {} ({}).",
+        recording.title,
+        &recording.id[..8],
+        format_duration(duration_secs.round() as u64)
+    );
+    if DaemonClient::connect(settings).await.is_ok() {
+        println!("Queued for transcription. Check progress with: minutes status --watch");
+    } else {
+        println!("It is transcribed once the daemon runs: minutes daemon start");
+    }
+    Ok(())
+}
+
+/// Convert an audio file ffmpeg can read into FLAC
+fn convert_with_ffmpeg(source: &Path, target: &Path) -> Result<()> {
+    let output = Command::new("ffmpeg")
+        .args(["-nostdin", "-loglevel", "error", "-y", "-i"])
+        .arg(source)
+        .args(["-vn", "-c:a", "flac"])
+        .arg(target)
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => {
+            let _ = std::fs::remove_file(target);
+            anyhow::bail!(
+                "ffmpeg could not convert {}: {}",
+                source.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => anyhow::bail!(
+            "Importing {} needs ffmpeg. Install ffmpeg, or convert the file to WAV, OGG, or FLAC first.",
+            source.display()
+        ),
+        Err(e) => Err(e).context("Failed to run ffmpeg"),
+    }
+}
+
+/// Reference an audio file in place; deleting the recording removes only the link
+fn link_audio(source: &Path, target: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(source, target)
+            .with_context(|| format!("Failed to link {}", source.display()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (source, target);
+        anyhow::bail!(
+            "--link is only supported on Linux and macOS; import without it to copy the file"
+        )
+    }
+}
+
+/// Attach separately recorded system audio to a recording
+///
+/// The file is shifted by `offset` onto the recording's timeline and stored
+/// next to it as the system track, with the live capture as the microphone
+/// track. Both are transcribed and the merged timeline replaces the transcript.
+pub async fn align_system_audio(
+    settings: &Settings,
+    id: &str,
+    system: &Path,
+    offset: &str,
+) -> Result<()> {
+    let offset_secs = parse_offset(offset)?;
+    let db = Database::open(settings)?;
+    let mut recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+
+    let chunks = db.get_chunks(&recording.id)?;
+    let mic_samples = if chunks.is_empty() {
+        let mic_path = recording
+            .mic_audio_path
+            .as_deref()
+            .or(recording.audio_path.as_deref())
+            .context("Recording has no audio file")?;
+        ensure_audio_reachable(&recording, [mic_path])?;
+        load_audio(Path::new(mic_path))?
+    } else {
+        ensure_audio_reachable(&recording, &chunks)?;
+        load_chunks(&chunks)?
+    };
+    let system_samples = shift_track(
+        &load_audio(system)
+            .with_context(|| format!("Failed to read system audio: {}", system.display()))?,
+        offset_secs,
+    );
+
+    // Kept lossless at 16kHz so the aligned track can be transcribed again later
+    let system_path = settings
+        .audio_dir()
+        .join(format!("{}.system.flac", recording.id));
+    let mut writer = FlacWriter::create(&system_path, 16000, 1)?;
+    let pcm: Vec<i16> = system_samples
+        .iter()
+        .map(|&sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect();
+    writer.write_samples(&pcm)?;
+    writer.finalize()?;
+
+    println!(
+        "Transcribing {} with system audio offset by {:+.1}s...",
+        &recording.id[..8],
+        offset_secs
+    );
+    let pipeline =
+        TranscriptionPipeline::for_recording(settings, &mut ModelCache::new(), &recording)?
+            .with_speaker_profiles(db.list_speaker_profiles()?);
+    let mut segments = pipeline
+        .transcribe_track_samples(
+            &system_samples,
+            &mic_samples,
+            &recording.id,
+            Box::new(|_| {}),
+        )
+        .await?;
+    db.apply_speaker_names(&recording.id, &mut segments)?;
+    snapshot::take(settings, &db, "align", &[&recording.id])?;
+    db.replace_segments(&recording.id, &segments)?;
+    db.replace_silences(&recording.id, &pipeline.silences())?;
+
+    if recording.mic_audio_path.is_none() && chunks.is_empty() {
+        recording.mic_audio_path = recording.audio_path.clone();
+    }
+    recording.system_audio_path = Some(system_path.to_string_lossy().to_string());
+    recording.language = pipeline.language().or(recording.language);
+    recording.state = RecordingState::Completed;
+    db.update_recording(&recording)?;
+
+    println!(
+        "Aligned transcript saved: {} segment(s). View it with: minutes view {}",
+        segments.len(),
+        &recording.id[..8]
+    );
+    Ok(())
+}
+
+/// Mark a recording's latest summary as approved, or back to draft
+pub fn approve_summary(settings: &Settings, id: &str, revoke: bool) -> Result<()> {
+    let db = Database::open(settings)?;
+
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+
+    let summary = db.latest_summary(&recording.id)?.with_context(|| {
+        format!(
+            "Recording {} has no generated summary to approve. Run: minutes summarize {}",
+            &recording.id[..8],
+            &recording.id[..8]
+        )
+    })?;
+
+    if revoke {
+        db.set_summary_approved(summary.id, None)?;
+        println!("Summary for {} is a draft again.", &recording.id[..8]);
+    } else if summary.is_approved() {
+        println!(
+            "Summary for {} is already {}.",
+            &recording.id[..8],
+            summary.status()
+        );
+    } else {
+        db.set_summary_approved(summary.id, Some(chrono::Utc::now()))?;
+        println!(
+            "Approved summary for {} ({}).",
+            &recording.id[..8],
+            summary.provenance()
+        );
+    }
+
+    Ok(())
+}
+
+/// List the summaries generated for a recording, or show, compare or restore one
+pub fn summary_history(
+    settings: &Settings,
+    id: &str,
+    show: Option<i64>,
+    diff: Option<&[i64]>,
+    restore: Option<i64>,
+) -> Result<()> {
+    let db = Database::open(settings)?;
+
+    let recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+    // Summary IDs are global; only accept those of this recording
+    let summary_of_recording = |summary_id: i64| -> Result<Summary> {
+        db.get_summary(summary_id)?
+            .filter(|summary| summary.recording_id == recording.id)
+            .with_context(|| {
+                format!(
+                    "Recording {} has no summary {}. List them with: minutes summaries {}",
+                    &recording.id[..8],
+                    summary_id,
+                    &recording.id[..8]
+                )
+            })
+    };
+
+    if let Some(summary_id) = show {
+        let summary = summary_of_recording(summary_id)?;
+        println!(
+            "Summary {} ({}; {})",
+            summary.id,
+            summary.provenance(),
+            summary.status()
+        );
+        println!();
+        println!("{}", summary.text);
+        return Ok(());
+    }
+
+    if let Some(&[old, new]) = diff {
+        let old = summary_of_recording(old)?;
+        let new = summary_of_recording(new)?;
+        println!("--- summary {} ({})", old.id, old.provenance());
+        println!("+++ summary {} ({})", new.id, new.provenance());
+        for line in diff::diff_lines(&old.text, &new.text) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    if let Some(summary_id) = restore {
+        let summary = summary_of_recording(summary_id)?;
+        snapshot::take(settings, &db, "restore", &[&recording.id])?;
+        db.restore_summary(&summary)?;
+        println!(
+            "Restored summary {} of {} ({}; {}).",
+            summary.id,
+            &recording.id[..8],
+            summary.provenance(),
+            summary.status()
+        );
+        return Ok(());
+    }
+
+    let summaries = db.list_summaries(&recording.id)?;
+    if summaries.is_empty() {
+        println!(
+            "No summaries for {}. Run: minutes summarize {}",
+            &recording.id[..8],
+            &recording.id[..8]
+        );
+        return Ok(());
+    }
+    let current = db.latest_summary(&recording.id)?.map(|summary| summary.id);
+
+    println!("Summaries of {} ({})", recording.title, &recording.id[..8]);
+    println!();
+    println!(
+        "{:<6} {:<17} {:<32} {:<20} {}",
+        "ID", "Created", "Provider / model", "Prompt", "Status"
+    );
+    println!("{}", "-".repeat(90));
+    for summary in &summaries {
+        let source = format!("{} / {}", summary.provider, summary.model);
+        let marker = if Some(summary.id) == current {
+            ", current"
+        } else {
+            ""
+        };
+        println!(
+            "{:<6} {:<17} {:<32} {:<20} {}{}",
+            summary.id,
+            summary
+                .created_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M"),
+            source,
+            summary.prompt(),
+            summary.status(),
+            marker
+        );
+    }
+    println!();
+    println!(
+        "Compare two with: minutes summaries {} --diff <OLD> <NEW>",
+        &recording.id[..8]
+    );
+    println!(
+        "Restore one with: minutes summaries {} --restore <SUMMARY>",
+        &recording.id[..8]
+    );
+    Ok(())
+}
+
+/// Show or change the sensitivity label of a recording
+pub fn set_sensitivity(settings: &Settings, id: &str, level: Option<&str>) -> Result<()> {
+    let db = Database::open(settings)?;
+
+    let mut recording = db
+        .find_recording_by_prefix(id)?
+        .context("Recording not found")?;
+
+    let Some(level) = level else {
+        println!("{}", recording.sensitivity.as_str());
+        return Ok(());
+    };
+
+    recording.sensitivity = level
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Unknown sensitivity: {}", level))?;
+    db.update_recording(&recording)?;
+    println!(
+        "Recording {} is now {}.",
+        &recording.id[..8],
+        recording.sensitivity.as_str()
+    );
+
+    Ok(())
+}
+
+/// Revert the most recent destructive operation, or list those that can be undone
+pub fn undo(settings: &Settings, list: bool) -> Result<()> {
+    let db = Database::open(settings)?;
+
+    if list {
+        let snapshots = db.list_snapshots(settings.general.max_snapshots)?;
+        if snapshots.is_empty() {
+            println!("Nothing to undo.");
+            return Ok(());
+        }
+
+        println!("{:<6} {:<12} {:<18} Recordings", "ID", "Operation", "Taken");
+        println!("{}", "-".repeat(60));
+        for snapshot in snapshots {
+            let titles: Vec<&str> = snapshot
+                .recordings
+                .iter()
+                .map(|saved| saved.recording.title.as_str())
+                .collect();
+            println!(
+                "{:<6} {:<12} {:<18} {}",
+                snapshot.id,
+                snapshot.operation,
+                snapshot
+                    .created_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M"),
+                titles.join(", ")
+            );
+        }
+        return Ok(());
+    }
+
+    let Some(snapshot) = snapshot::undo_latest(&db)? else {
+        println!("Nothing to undo.");
+        return Ok(());
+    };
+
+    println!(
+        "Undid {} from {}.",
+        snapshot.operation,
+        snapshot
+            .created_at
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+    );
+    for saved in &snapshot.recordings {
+        println!(
+            "  Restored {} ({})",
+            &saved.recording.id[..8],
+            saved.recording.title
+        );
+    }
+
+    Ok(())
+}
+
+/// Write the database and audio files to a backup archive
+pub fn backup_library(settings: &Settings, path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists. Use --force to overwrite.",
+            path.display()
+        );
+    }
+
+    let summary = backup::create(settings, path)?;
+    let written = std::fs::metadata(path)?.len();
+    println!(
+        "Backed up {} recording(s) and {} audio file(s) to {} ({} MB, {} MB uncompressed).",
+        summary.recordings,
+        summary.audio_files,
+        path.display(),
+        written / 1_000_000,
+        summary.bytes / 1_000_000
+    );
+    Ok(())
+}
+
+/// Replace the library with a backup archive
+pub async fn restore_library(settings: &Settings, path: &Path, force: bool) -> Result<()> {
+    if DaemonClient::connect(settings).await.is_ok() {
+        anyhow::bail!("Stop the daemon before restoring a backup: minutes daemon stop");
+    }
+    if settings.database_path().exists() && !force {
+        let recordings = Database::open(settings)?.get_stats()?.total_recordings;
+        if recordings > 0 {
+            anyhow::bail!(
+                "The library already has {} recording(s). Use --force to replace it.",
+                recordings
+            );
+        }
+    }
+
+    let summary = backup::restore(settings, path)?;
+    println!(
+        "Restored {} recording(s) and {} audio file(s) from {}.",
+        summary.recordings,
+        summary.audio_files,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Handle daemon subcommands
+pub async fn daemon_command(settings: &Settings, cmd: DaemonCommand) -> Result<()> {
+    match cmd {
+        DaemonCommand::Start {
+            foreground,
+            backend,
+        } => {
+            let mut settings = settings.clone();
+            if let Some(backend) = backend {
+                settings.audio.backend = backend;
+            }
+
+            if foreground {
+                crate::daemon::run_foreground(&settings).await?;
+            } else {
+                crate::daemon::start_daemon(&settings)?;
+                println!("Daemon started");
+            }
+        }
+        DaemonCommand::Stop => {
+            let mut client = DaemonClient::connect(settings).await?;
+            client.send(DaemonRequest::Shutdown).await?;
+            println!("Daemon stopped");
+        }
+        DaemonCommand::Restart => {
+            // Try to stop existing daemon
+            if let Ok(mut client) = DaemonClient::connect(settings).await {
+                let _ = client.send(DaemonRequest::Shutdown).await;
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            }
+            crate::daemon::start_daemon(settings)?;
+            println!("Daemon restarted");
+        }
+        DaemonCommand::Status => match DaemonClient::connect(settings).await {
+            Ok(mut client) => {
+                let response = client.send(DaemonRequest::Ping).await?;
+                if matches!(response, DaemonResponse::Pong) {
+                    println!("Daemon is running");
+                }
+            }
+            Err(_) => {
+                print_daemon_not_running();
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Handle privacy subcommands
+pub async fn privacy_command(settings: &Settings, cmd: PrivacyCommand) -> Result<()> {
+    let mut client = DaemonClient::connect(settings).await?;
+
+    let request = match cmd {
+        PrivacyCommand::On => DaemonRequest::SetPrivacyMode { enabled: true },
+        PrivacyCommand::Off => DaemonRequest::SetPrivacyMode { enabled: false },
+        PrivacyCommand::Status => DaemonRequest::GetPrivacyMode,
+    };
+
+    match client.send(request).await? {
+        DaemonResponse::PrivacyMode { enabled: true } => {
+            println!("Privacy mode: on");
+            println!("New transcripts are not titled, tagged or embedded by the LLM until it is turned off.");
+        }
+        DaemonResponse::PrivacyMode { enabled: false } => {
+            println!("Privacy mode: off");
+        }
+        DaemonResponse::Error { message } => {
+            anyhow::bail!("Failed to update privacy mode: {}", message);
+        }
+        _ => {
+            anyhow::bail!(t!("daemon-unexpected-response"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle transcription queue subcommands
+///
+/// Cancelling goes through the daemon so a running job is stopped; listing
+/// and reordering only touch the database.
+pub async fn queue_command(settings: &Settings, cmd: QueueCommand) -> Result<()> {
+    let db = Database::open(settings)?;
+
+    match cmd {
+        QueueCommand::List => {
+            // Recordings stopped since the daemon last checked are queued too
+            db.queue_pending_recordings()?;
+            let jobs = db.list_jobs(settings.transcription.newest_first())?;
+            if jobs.is_empty() {
+                println!("No transcriptions queued.");
+                return Ok(());
+            }
+
+            println!("{:<10} {:<10} {:<20} Title", "ID", "State", "Queued");
+            println!("{}", "-".repeat(70));
+            for job in jobs {
+                let state = if job.retry_at.is_some() && job.state == JobState::Queued {
+                    "retrying"
+                } else if job.priority > 0 && job.state == JobState::Queued {
+                    "bumped"
+                } else {
+                    job.state.as_str()
+                };
+                println!(
+                    "{:<10} {:<10} {:<20} {}",
+                    &job.recording_id[..job.recording_id.len().min(8)],
+                    state,
+                    job.queued_at.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                    job.title
+                );
+            }
+        }
+        QueueCommand::Cancel { id } => {
+            let mut client = DaemonClient::connect(settings).await?;
+            match client
+                .send(DaemonRequest::CancelTranscription {
+                    recording_id: id.clone(),
+                })
+                .await?
+            {
+                DaemonResponse::Ok => {
+                    println!("Cancelled transcription of {}.", id);
+                    println!("Run `minutes transcribe {}` to queue it again.", id);
+                }
+                DaemonResponse::Error { message } => {
+                    anyhow::bail!("Failed to cancel transcription: {}", message);
+                }
+                _ => {
+                    anyhow::bail!(t!("daemon-unexpected-response"));
+                }
+            }
+        }
+        QueueCommand::Bump { id } => {
+            let recording = db
+                .find_recording_by_prefix(&id)?
+                .context("Recording not found")?;
+            db.queue_pending_recordings()?;
+            if !db.bump_job(&recording.id)? {
+                anyhow::bail!("Recording {} is not waiting in the queue", id);
+            }
+            println!("Moved {} to the front of the queue.", id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Queue a recording for transcription again
+///
+/// The daemon decodes the saved audio, including recordings already
+/// compressed to OGG or FLAC, and replaces the existing transcript.
+pub async fn retranscribe(settings: &Settings, id: &str, language: Option<String>) -> Result<()> {
+    let language = language.as_deref().map(language_argument).transpose()?;
+    let mut client = DaemonClient::connect(settings).await?;
+
+    match client
+        .send(DaemonRequest::Transcribe {
+            recording_id: id.to_string(),
+            language,
+        })
+        .await?
+    {
+        DaemonResponse::Ok => {
+            println!("Transcription queued for {}", id);
+            println!("The current transcript is replaced when it finishes.");
+        }
+        DaemonResponse::Error { message } => {
+            anyhow::bail!("Failed to queue transcription: {}", message);
+        }
+        _ => {
+            anyhow::bail!(t!("daemon-unexpected-response"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle model subcommands
+pub async fn model_command(settings: &Settings, cmd: ModelCommand) -> Result<()> {
+    match cmd {
+        ModelCommand::Download { model, force } => {
+            let model = model.unwrap_or_else(|| settings.whisper.model.clone());
+            let path = settings.model_file(&model);
+            if path.exists() && !force {
+                println!(
+                    "Model '{}' is already downloaded: {}",
+                    model,
+                    path.display()
+                );
+                return Ok(());
+            }
+
+            println!("Downloading {}", crate::transcription::model_url(&model));
+            let last_percent = std::cell::Cell::new(u64::MAX);
+            let path = crate::transcription::download_model(settings, &model, |received, total| {
+                let Some(total) = total.filter(|total| *total > 0) else {
+                    return;
+                };
+                let percent = received * 100 / total;
+                if last_percent.replace(percent) != percent {
+                    print!("\r{:>3}% of {} MB", percent, total / 1_000_000);
+                    let _ = std::io::Write::flush(&mut std::io::stdout());
+                }
+            })
+            .await?;
+            println!();
+            println!("Model saved to: {}", path.display());
+        }
     }
 
     Ok(())
 }
 
-/// Export a recording to a file
-pub async fn export_recording(
-    settings: &Settings,
-    id: &str,
-    format: &str,
-    output: Option<PathBuf>,
-) -> Result<()> {
+/// Handle speaker profile subcommands
+pub fn speakers_command(settings: &Settings, cmd: SpeakersCommand) -> Result<()> {
     let db = Database::open(settings)?;
 
-    let recording = db
-        .find_recording_by_prefix(id)?
-        .context("Recording not found")?;
+    match cmd {
+        SpeakersCommand::Enroll { name, from, range } => {
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                anyhow::bail!("Speaker name cannot be empty");
+            }
 
-    let segments = db.get_transcript_segments(&recording.id)?;
+            let recording = db
+                .find_recording_by_prefix(&from)?
+                .context("Recording not found")?;
+            let chunks = db.get_chunks(&recording.id)?;
+            let (start, end) = parse_time_range(&range)?;
 
-    let content = match format {
-        "txt" => export_as_txt(&recording, &segments),
-        "json" => export_as_json(&recording, &segments)?,
-        "srt" => export_as_srt(&segments),
-        _ => anyhow::bail!("Unsupported format: {}. Supported: txt, json, srt", format),
-    };
+            let samples = if chunks.is_empty() {
+                let audio_path = recording
+                    .audio_path
+                    .as_deref()
+                    .context("Recording has no audio file")?;
+                ensure_audio_reachable(&recording, [audio_path])?;
+                load_audio(std::path::Path::new(audio_path))?
+            } else {
+                ensure_audio_reachable(&recording, &chunks)?;
+                load_chunks(&chunks)?
+            };
+            let first = (start * 16000.0) as usize;
+            let last = ((end * 16000.0) as usize).min(samples.len());
+            if first >= last {
+                anyhow::bail!(
+                    "Range {} is past the end of the recording ({})",
+                    range,
+                    format_timestamp(samples.len() as f64 / 16000.0)
+                );
+            }
 
-    if let Some(path) = output {
-        std::fs::write(&path, content)?;
-        println!("Exported to: {}", path.display());
-    } else {
-        print!("{}", content);
+            let embedding = VoiceEmbedding::from_samples(&samples[first..last]).context(
+                "Not enough speech in that range to enroll. Pick a longer stretch where the speaker talks.",
+            )?;
+
+            let mut profile = SpeakerProfile::new(name.clone(), embedding.0);
+            profile.source_recording_id = Some(recording.id.clone());
+            db.upsert_speaker_profile(&profile)?;
+
+            println!("Enrolled {} from {} ({}).", name, &recording.id[..8], range);
+            println!("New transcriptions will label matching voices as {}.", name);
+        }
+        SpeakersCommand::List => {
+            let profiles = db.list_speaker_profiles()?;
+            if profiles.is_empty() {
+                println!("No speakers enrolled.");
+                println!("Enroll one with: minutes speakers enroll \"Name\" --from <id> --range 05:00-06:00");
+                return Ok(());
+            }
+
+            println!("{:<24} {:<10} Enrolled", "Name", "From");
+            println!("{}", "-".repeat(50));
+            for profile in profiles {
+                let source = profile
+                    .source_recording_id
+                    .as_deref()
+                    .map(|id| &id[..id.len().min(8)])
+                    .unwrap_or("-");
+                println!(
+                    "{:<24} {:<10} {}",
+                    profile.name,
+                    source,
+                    profile.created_at.format("%Y-%m-%d")
+                );
+            }
+        }
+        SpeakersCommand::Remove { name } => {
+            if db.delete_speaker_profile(&name)? {
+                println!("Removed speaker {}.", name);
+            } else {
+                anyhow::bail!("Speaker '{}' is not enrolled", name);
+            }
+        }
+        SpeakersCommand::Rename { id, from, to } => {
+            let recording = db
+                .find_recording_by_prefix(&id)?
+                .context("Recording not found")?;
+
+            let (Some(from), Some(to)) = (from, to) else {
+                let stats = TranscriptStats::compute(
+                    &recording,
+                    &db.get_transcript_segments(&recording.id)?,
+                );
+                let labelled: Vec<_> = stats
+                    .speakers
+                    .iter()
+                    .filter_map(|s| s.speaker.as_deref().map(|name| (name, s.segment_count)))
+                    .collect();
+                if labelled.is_empty() {
+                    println!("No speaker labels in {}.", &recording.id[..8]);
+                    return Ok(());
+                }
+                println!("{:<24} Segments", "Speaker");
+                println!("{}", "-".repeat(34));
+                for (name, count) in labelled {
+                    println!("{:<24} {}", name, count);
+                }
+                println!();
+                println!(
+                    "Rename one with: minutes speakers rename {} \"Speaker 1\" \"Name\"",
+                    &recording.id[..8]
+                );
+                return Ok(());
+            };
+
+            let to = to.trim();
+            if to.is_empty() {
+                anyhow::bail!("Speaker name cannot be empty");
+            }
+            let relabelled = db.rename_speaker(&recording.id, &from, to)?;
+            if relabelled == 0 {
+                anyhow::bail!(
+                    "No segments of {} are labelled '{}'",
+                    &recording.id[..8],
+                    from
+                );
+            }
+            println!(
+                "Renamed {} to {} in {} segment(s) of {}.",
+                from,
+                to,
+                relabelled,
+                &recording.id[..8]
+            );
+        }
     }
 
     Ok(())
 }
 
-/// Handle daemon subcommands
-pub async fn daemon_command(settings: &Settings, cmd: DaemonCommand) -> Result<()> {
+/// Handle project subcommands
+pub fn project_command(settings: &Settings, cmd: ProjectCommand) -> Result<()> {
+    let db = Database::open(settings)?;
+
     match cmd {
-        DaemonCommand::Start { foreground } => {
-            if foreground {
-                crate::daemon::run_foreground(settings).await?;
-            } else {
-                crate::daemon::start_daemon(settings)?;
-                println!("Daemon started");
+        ProjectCommand::Create { name } => {
+            let name = name.trim();
+            if name.is_empty() {
+                anyhow::bail!("Project name cannot be empty");
             }
+            if !db.create_project(name)? {
+                anyhow::bail!("Project '{}' already exists", name);
+            }
+            println!("Created project {}.", name);
+            println!(
+                "Add recordings with: minutes project assign <id> \"{}\"",
+                name
+            );
         }
-        DaemonCommand::Stop => {
-            let mut client = DaemonClient::connect(settings).await?;
-            client.send(DaemonRequest::Shutdown).await?;
-            println!("Daemon stopped");
-        }
-        DaemonCommand::Restart => {
-            // Try to stop existing daemon
-            if let Ok(mut client) = DaemonClient::connect(settings).await {
-                let _ = client.send(DaemonRequest::Shutdown).await;
-                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        ProjectCommand::List => {
+            let projects = db.list_projects()?;
+            if projects.is_empty() {
+                println!("No projects yet.");
+                println!("Create one with: minutes project create \"Apollo\"");
+                return Ok(());
+            }
+
+            println!(
+                "{:<24} {:<12} {:<10} Created",
+                "Name", "Recordings", "Duration"
+            );
+            println!("{}", "-".repeat(60));
+            for project in projects {
+                println!(
+                    "{:<24} {:<12} {:<10} {}",
+                    truncate(&project.name, 22),
+                    project.recording_count,
+                    format_duration(project.total_duration_secs),
+                    project.created_at.format("%Y-%m-%d")
+                );
             }
-            crate::daemon::start_daemon(settings)?;
-            println!("Daemon restarted");
         }
-        DaemonCommand::Status => match DaemonClient::connect(settings).await {
-            Ok(mut client) => {
-                let response = client.send(DaemonRequest::Ping).await?;
-                if matches!(response, DaemonResponse::Pong) {
-                    println!("Daemon is running");
+        ProjectCommand::Assign { id, project } => {
+            let project = find_project(&db, &project)?;
+            let recording = db
+                .find_recording_by_prefix(&id)?
+                .context("Recording not found")?;
+            db.set_recording_project(&recording.id, Some(&project.name))?;
+            println!(
+                "Moved {} ({}) into project {}.",
+                &recording.id[..8],
+                recording.title,
+                project.name
+            );
+        }
+        ProjectCommand::Unassign { id } => {
+            let recording = db
+                .find_recording_by_prefix(&id)?
+                .context("Recording not found")?;
+            match recording.project {
+                Some(project) => {
+                    db.set_recording_project(&recording.id, None)?;
+                    println!("Removed {} from project {}.", &recording.id[..8], project);
                 }
+                None => println!("Recording {} is not in a project.", &recording.id[..8]),
             }
-            Err(_) => {
-                print_daemon_not_running();
-            }
-        },
+        }
     }
 
     Ok(())
 }
 
+/// Look up a project by name, with a hint to create it when missing
+fn find_project(db: &Database, name: &str) -> Result<Project> {
+    db.get_project(name)?.with_context(|| {
+        format!(
+            "Project '{}' does not exist. Create it with: minutes project create \"{}\"",
+            name, name
+        )
+    })
+}
+
 /// Handle config subcommands
 pub fn config_command(settings: &Settings, cmd: ConfigCommand) -> Result<()> {
     match cmd {
@@ -392,6 +3947,82 @@ pub fn config_command(settings: &Settings, cmd: ConfigCommand) -> Result<()> {
             Settings::write_default(&path)?;
             println!("Configuration initialized at: {}", path.display());
         }
+        ConfigCommand::ExportPreset { path, force } => {
+            if path.exists() && !force {
+                anyhow::bail!(
+                    "{} already exists. Use --force to overwrite.",
+                    path.display()
+                );
+            }
+            let preset = settings.to_preset()?;
+            let content = format!(
+                "# minutes settings preset\n# Import with: minutes config import-preset {}\n\n{}",
+                path.display(),
+                toml::to_string_pretty(&preset)?
+            );
+            std::fs::write(&path, content)?;
+            println!("Preset written to: {}", path.display());
+        }
+        ConfigCommand::ImportPreset { path } => {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read preset: {}", path.display()))?;
+            let preset: toml::Table = toml::from_str(&content)
+                .with_context(|| format!("Failed to parse preset: {}", path.display()))?;
+
+            let config_path = Settings::config_path()?;
+            let skipped = Settings::import_preset(&config_path, &preset)?;
+            for key in &skipped {
+                println!("Skipped {} (not shared through presets)", key);
+            }
+            println!(
+                "Preset {} applied to: {}",
+                path.display(),
+                config_path.display()
+            );
+        }
+        ConfigCommand::SetSecret { key, delete } => {
+            if delete {
+                if secrets::delete_secret(&key)? {
+                    println!("Removed {} from the system keyring.", key);
+                } else {
+                    println!("No {} stored in the system keyring.", key);
+                }
+                return Ok(());
+            }
+
+            let stdin = std::io::stdin();
+            if stdin.is_terminal() {
+                print!("Value for {}: ", key);
+                std::io::stdout().flush()?;
+            }
+            let mut value = String::new();
+            stdin.read_line(&mut value)?;
+            let value = value.trim();
+            if value.is_empty() {
+                anyhow::bail!("No value given for {}", key);
+            }
+            secrets::set_secret(&key, value)?;
+            println!("Stored {} in the system keyring.", key);
+
+            // A key left in the file is now unused but still readable
+            let config_path = Settings::config_path()?;
+            let in_file = std::fs::read_to_string(&config_path)
+                .ok()
+                .and_then(|content| content.parse::<toml::Table>().ok())
+                .and_then(|config| {
+                    let (section, name) = key.split_once('.')?;
+                    let value = config.get(section)?.get(name)?.as_str()?.trim().to_string();
+                    Some(!value.is_empty())
+                })
+                .unwrap_or(false);
+            if in_file {
+                println!(
+                    "{} is also set in {}; remove it there to keep it out of the file.",
+                    key,
+                    config_path.display()
+                );
+            }
+        }
         ConfigCommand::Set { key, value } => {
             // Simple key=value setting - would need more sophisticated implementation
             // for nested keys like "whisper.model"
@@ -423,18 +4054,32 @@ struct DoctorPipeWireTarget {
     method: &'static str,
 }
 
+#[derive(Serialize)]
+struct DoctorModel {
+    configured: String,
+    downloaded: bool,
+    memory_bytes: Option<u64>,
+    cpus: usize,
+    recommended: Option<String>,
+}
+
 #[derive(Serialize)]
 struct DoctorReport {
     backend: String,
     capture: DoctorCapture,
+    model: DoctorModel,
     checks: Vec<DoctorCheck>,
     pipewire_targets: Vec<DoctorPipeWireTarget>,
     notes: Vec<String>,
 }
 
 /// Run diagnostic checks to help troubleshoot local setup issues.
-pub async fn run_doctor(settings: &Settings, json: bool) -> Result<()> {
-    let report = collect_doctor_report(settings);
+pub async fn run_doctor(settings: &Settings, json: bool, fix: bool) -> Result<()> {
+    let mut report = collect_doctor_report(settings);
+    let active_id = active_recording_id(settings).await;
+    report
+        .notes
+        .extend(unfinalized_audio_notes(settings, active_id.as_deref(), fix));
 
     if json {
         println!("{}", serde_json::to_string_pretty(&report)?);
@@ -447,6 +4092,15 @@ pub async fn run_doctor(settings: &Settings, json: bool) -> Result<()> {
         "capture: system={} microphone={}",
         report.capture.system, report.capture.microphone
     );
+    println!(
+        "model: {} ({})",
+        report.model.configured,
+        if report.model.downloaded {
+            "downloaded"
+        } else {
+            "missing"
+        }
+    );
     println!();
 
     for check in &report.checks {
@@ -474,6 +4128,150 @@ pub async fn run_doctor(settings: &Settings, json: bool) -> Result<()> {
     Ok(())
 }
 
+/// ID of the recording the daemon is capturing right now, if any
+async fn active_recording_id(settings: &Settings) -> Option<String> {
+    let mut client = DaemonClient::connect(settings).await.ok()?;
+    match client.send(DaemonRequest::GetStatus).await.ok()? {
+        DaemonResponse::Status(RecordingStatus::Recording { id, .. }) => Some(id),
+        _ => None,
+    }
+}
+
+/// Find WAV files whose header was never finalized, repairing them with `fix`
+///
+/// The recording currently being captured is skipped, since its header is
+/// only written when it stops.
+fn unfinalized_audio_notes(settings: &Settings, active_id: Option<&str>, fix: bool) -> Vec<String> {
+    let mut notes = Vec::new();
+    let db = match Database::open(settings) {
+        Ok(db) => db,
+        Err(e) => {
+            notes.push(format!("warning: could not open the database: {}", e));
+            return notes;
+        }
+    };
+    let recordings = match db.list_recordings_since(None) {
+        Ok(recordings) => recordings,
+        Err(e) => {
+            notes.push(format!("warning: could not list recordings: {}", e));
+            return notes;
+        }
+    };
+
+    let mut damaged = 0;
+    for recording in recordings {
+        if Some(recording.id.as_str()) == active_id {
+            continue;
+        }
+        let chunks = db.get_chunks(&recording.id).unwrap_or_default();
+        let paths = recording
+            .audio_files()
+            .chain(chunks.iter().map(String::as_str))
+            .map(Path::new)
+            .filter(|path| crate::audio::is_wav_path(path) && path.exists());
+
+        for path in paths {
+            let result = if fix {
+                crate::audio::repair_wav(path)
+            } else {
+                crate::audio::wav_needs_repair(path)
+            };
+            match result {
+                Ok(true) if fix => notes.push(format!(
+                    "fixed: rebuilt the WAV header of {} ({})",
+                    path.display(),
+                    &recording.id[..8]
+                )),
+                Ok(true) => damaged += 1,
+                Ok(false) => {}
+                Err(e) => notes.push(format!(
+                    "warning: could not check {} ({}): {}",
+                    path.display(),
+                    &recording.id[..8],
+                    e
+                )),
+            }
+        }
+    }
+
+    if damaged > 0 {
+        notes.push(format!(
+            "warning: {} WAV file(s) were not finalized, likely after a crash.",
+            damaged
+        ));
+        notes.push("hint: run `minutes doctor --fix` to rebuild their headers.".to_string());
+    }
+    notes
+}
+
+/// Explain what macOS system audio capture needs and whether it is set up
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+fn core_audio_notes(settings: &Settings, notes: &mut Vec<String>) {
+    #[cfg(target_os = "macos")]
+    {
+        match crate::audio::LoopbackCapture::loopback_driver(settings) {
+            Some(name) => notes.push(format!("ok: system audio is recorded from '{}'.", name)),
+            None => {
+                notes.push(
+                    "warning: no loopback device found; recordings will be microphone-only."
+                        .to_string(),
+                );
+                notes.push("hint: install a loopback driver such as BlackHole (`brew install blackhole-2ch`).".to_string());
+                notes.push("hint: in Audio MIDI Setup, create a Multi-Output Device with your speakers and BlackHole, and make it the system output.".to_string());
+                notes.push("hint: set audio.system_device if the driver has a custom name; run `minutes devices` to list inputs.".to_string());
+            }
+        }
+        if settings.audio.capture_microphone {
+            notes.push("hint: grant your terminal microphone access in System Settings > Privacy & Security > Microphone.".to_string());
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        notes.push("warning: the CoreAudio backend is only available on macOS.".to_string());
+    }
+}
+
+/// Check the configured Whisper model and recommend one for this machine
+fn doctor_model(settings: &Settings, notes: &mut Vec<String>) -> DoctorModel {
+    let configured = settings.whisper.model.clone();
+    let downloaded = settings.model_path().exists();
+    let memory_bytes = crate::transcription::system_memory();
+    let cpus = std::thread::available_parallelism().map_or(1, |cpus| cpus.get());
+    let english = settings.whisper.language.trim().eq_ignore_ascii_case("en");
+    let recommended =
+        memory_bytes.map(|memory| crate::transcription::recommend_model(memory, cpus, english));
+
+    if settings.transcription.uses_whisper() {
+        if !downloaded {
+            notes.push(format!(
+                "warning: Whisper model '{}' is not downloaded; run `minutes model download`.",
+                configured
+            ));
+        }
+        match (&recommended, memory_bytes) {
+            (Some(model), Some(memory)) if *model != configured => notes.push(format!(
+                "hint: with {:.1} GB of RAM and {} CPU(s), whisper.model = \"{}\" is a good fit.",
+                memory as f64 / 1e9,
+                cpus,
+                model
+            )),
+            (Some(_), _) => {}
+            (None, _) => notes.push(
+                "info: could not read the memory size to recommend a Whisper model.".to_string(),
+            ),
+        }
+    }
+
+    DoctorModel {
+        configured,
+        downloaded,
+        memory_bytes,
+        cpus,
+        recommended,
+    }
+}
+
 fn collect_doctor_report(settings: &Settings) -> DoctorReport {
     let pw_record_ok = command_exists("pw-record");
     let wpctl_ok = command_exists("wpctl");
@@ -488,6 +4286,42 @@ fn collect_doctor_report(settings: &Settings) -> DoctorReport {
                     .to_string(),
             );
         }
+        AudioBackend::Jack => {
+            #[cfg(feature = "jack")]
+            {
+                if crate::audio::JackCapture::is_available() {
+                    notes.push("ok: JACK server is running.".to_string());
+                } else {
+                    notes.push("warning: could not connect to a JACK server.".to_string());
+                }
+                if settings.audio.capture_system && settings.audio.system_device.trim().is_empty() {
+                    notes.push("hint: set audio.system_device to the JACK ports carrying system audio; run `minutes devices` to list them.".to_string());
+                }
+            }
+
+            #[cfg(not(feature = "jack"))]
+            {
+                notes.push("warning: this build has no JACK feature enabled.".to_string());
+            }
+        }
+        AudioBackend::Wasapi => {
+            if cfg!(windows) {
+                notes.push(
+                    "info: WASAPI records system audio through loopback on the playback device."
+                        .to_string(),
+                );
+            } else {
+                notes.push("warning: the WASAPI backend is only available on Windows.".to_string());
+            }
+        }
+        AudioBackend::CoreAudio => core_audio_notes(settings, &mut notes),
+        AudioBackend::Mock => {
+            notes.push(
+                "info: the mock backend writes a test tone instead of recording any device."
+                    .to_string(),
+            );
+        }
+        AudioBackend::Auto if cfg!(target_os = "macos") => core_audio_notes(settings, &mut notes),
         AudioBackend::Auto | AudioBackend::PipeWire => {
             #[cfg(feature = "pipewire")]
             {
@@ -495,6 +4329,8 @@ fn collect_doctor_report(settings: &Settings) -> DoctorReport {
                     let resolved = crate::audio::resolve_capture_targets(
                         settings.audio.capture_system,
                         settings.audio.capture_microphone,
+                        &settings.audio.system_device,
+                        &settings.audio.device,
                     );
 
                     for target in &resolved {
@@ -533,6 +4369,7 @@ fn collect_doctor_report(settings: &Settings) -> DoctorReport {
             system: settings.audio.capture_system,
             microphone: settings.audio.capture_microphone,
         },
+        model: doctor_model(settings, &mut notes),
         checks: vec![
             DoctorCheck {
                 name: "pw-record",
@@ -550,6 +4387,45 @@ fn collect_doctor_report(settings: &Settings) -> DoctorReport {
     }
 }
 
+/// List audio capture devices reported by the available backends.
+pub fn list_devices(json: bool) -> Result<()> {
+    let devices: Vec<AudioDevice> = crate::audio::list_devices();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&devices)?);
+        return Ok(());
+    }
+
+    if devices.is_empty() {
+        println!("No audio capture devices found.");
+        println!("Run `minutes doctor` to check your audio setup.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<10} {:<12} {:<3} {:<10} Name",
+        "Backend", "Kind", "", "ID"
+    );
+    println!("{}", "-".repeat(65));
+
+    for device in &devices {
+        println!(
+            "{:<10} {:<12} {:<3} {:<10} {}",
+            device.backend,
+            device.kind,
+            if device.is_default { "*" } else { "" },
+            truncate(&device.id, 10),
+            device.name
+        );
+    }
+
+    println!();
+    println!("Set audio.device (microphone) or audio.system_device (system monitor)");
+    println!("in your config to record from a specific device.");
+
+    Ok(())
+}
+
 // Helper functions
 
 fn command_exists(bin: &str) -> bool {
@@ -561,7 +4437,7 @@ fn command_exists(bin: &str) -> bool {
         .is_ok()
 }
 
-fn format_duration(secs: u64) -> String {
+pub(crate) fn format_duration(secs: u64) -> String {
     let hours = secs / 3600;
     let minutes = (secs % 3600) / 60;
     let seconds = secs % 60;
@@ -573,7 +4449,7 @@ fn format_duration(secs: u64) -> String {
     }
 }
 
-fn format_timestamp(secs: f64) -> String {
+pub(crate) fn format_timestamp(secs: f64) -> String {
     let total_secs = secs as u64;
     let hours = total_secs / 3600;
     let minutes = (total_secs % 3600) / 60;
@@ -595,11 +4471,11 @@ fn truncate(s: &str, max_len: usize) -> String {
 }
 
 fn print_daemon_not_running() {
-    println!("Daemon is not running.");
-    println!("Start it with: minutes daemon start");
+    println!("{}", t!("daemon-not-running"));
+    println!("{}", t!("daemon-start-hint"));
 }
 
-use crate::storage::TranscriptSegment;
+use crate::storage::{TranscriptSegment, TranscriptStats};
 
 fn build_summary_transcript(segments: &[TranscriptSegment]) -> String {
     let mut transcript = String::new();
@@ -614,7 +4490,68 @@ fn build_summary_transcript(segments: &[TranscriptSegment]) -> String {
     transcript
 }
 
-fn export_as_txt(recording: &Recording, segments: &[TranscriptSegment]) -> String {
+/// Timestamped transcript lines with speakers, so answers can say who said what
+fn build_question_transcript(segments: &[TranscriptSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| {
+            format!(
+                "[{}] {}\n",
+                format_timestamp(segment.start_time),
+                segment.labelled_text()
+            )
+        })
+        .collect()
+}
+
+/// Show the English translation instead of the original text where stored
+///
+/// Word timings belong to the original text, so they are dropped with it.
+fn use_translations(segments: &mut [TranscriptSegment]) {
+    for segment in segments {
+        if let Some(translation) = segment.translated_text.take() {
+            segment.text = translation;
+            segment.words.clear();
+        }
+    }
+}
+
+/// One cue per chapter, lasting until the next chapter or the end of the recording
+fn chapter_cues(
+    recording: &Recording,
+    segments: &[TranscriptSegment],
+    chapters: &[Chapter],
+) -> Vec<TranscriptSegment> {
+    let end_of_recording = recording
+        .duration_secs
+        .map(|secs| secs as f64)
+        .or_else(|| segments.last().map(|segment| segment.end_time))
+        .unwrap_or(0.0);
+
+    chapters
+        .iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            let end_time = chapters
+                .get(i + 1)
+                .map_or(end_of_recording, |next| next.start_time)
+                .max(chapter.start_time);
+            TranscriptSegment::new(
+                recording.id.clone(),
+                chapter.start_time,
+                end_time,
+                chapter.title.clone(),
+            )
+        })
+        .collect()
+}
+
+fn export_as_txt(
+    recording: &Recording,
+    participants: &[String],
+    segments: &[TranscriptSegment],
+    chapters: &[Chapter],
+) -> String {
     let mut output = String::new();
     output.push_str(&format!("Title: {}\n", recording.title));
     output.push_str(&format!(
@@ -624,26 +4561,126 @@ fn export_as_txt(recording: &Recording, segments: &[TranscriptSegment]) -> Strin
     if let Some(duration) = recording.duration_secs {
         output.push_str(&format!("Duration: {}\n", format_duration(duration)));
     }
+    if !participants.is_empty() {
+        output.push_str(&format!("Participants: {}\n", participants.join(", ")));
+    }
+    if !chapters.is_empty() {
+        output.push_str("Chapters:\n");
+        for chapter in chapters {
+            output.push_str(&format!(
+                "  {} {}\n",
+                format_timestamp(chapter.start_time),
+                chapter.title
+            ));
+        }
+    }
     output.push_str("\n---\n\n");
 
     for segment in segments {
         let timestamp = format_timestamp(segment.start_time);
-        output.push_str(&format!("[{}] {}\n", timestamp, segment.text));
+        output.push_str(&format!("[{}] {}\n", timestamp, segment.labelled_text()));
+    }
+
+    output
+}
+
+/// Markdown notes: summary, decisions, chapters and transcript
+///
+/// The summary's own `##` sections are nested one level down, under
+/// `## Summary`.
+fn export_as_md(
+    recording: &Recording,
+    participants: &[String],
+    segments: &[TranscriptSegment],
+    chapters: &[Chapter],
+    decisions: &[Decision],
+) -> String {
+    let mut output = String::new();
+    output.push_str(&format!("# {}\n\n", recording.title));
+    output.push_str(&format!(
+        "- Date: {}\n",
+        recording.created_at.format("%Y-%m-%d %H:%M")
+    ));
+    if let Some(duration) = recording.duration_secs {
+        output.push_str(&format!("- Duration: {}\n", format_duration(duration)));
+    }
+    if !participants.is_empty() {
+        output.push_str(&format!("- Participants: {}\n", participants.join(", ")));
+    }
+
+    if let Some(notes) = recording.notes.as_deref() {
+        output.push_str("\n## Summary\n\n");
+        for line in notes.trim().lines() {
+            if line.starts_with('#') {
+                output.push('#');
+            }
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    if !decisions.is_empty() {
+        output.push_str("\n## Decisions\n\n");
+        for decision in decisions {
+            output.push_str(&format!(
+                "- {}{}\n",
+                decision.text,
+                format_decision_timestamps(decision)
+            ));
+        }
+    }
+
+    if !chapters.is_empty() {
+        output.push_str("\n## Chapters\n\n");
+        for chapter in chapters {
+            output.push_str(&format!(
+                "- [{}] {}\n",
+                format_timestamp(chapter.start_time),
+                chapter.title
+            ));
+        }
+    }
+
+    output.push_str("\n## Transcript\n\n");
+    for segment in segments {
+        output.push_str(&format!(
+            "[{}] {}  \n",
+            format_timestamp(segment.start_time),
+            segment.labelled_text()
+        ));
     }
 
     output
 }
 
-fn export_as_json(recording: &Recording, segments: &[TranscriptSegment]) -> Result<String> {
+fn export_as_json(
+    recording: &Recording,
+    participants: &[String],
+    segments: &[TranscriptSegment],
+    summary: Option<&Summary>,
+    chapters: &[Chapter],
+) -> Result<String> {
     #[derive(serde::Serialize)]
     struct ExportData<'a> {
         recording: &'a Recording,
+        /// People who attended, in the order they were listed
+        participants: &'a [String],
         segments: &'a [TranscriptSegment],
+        /// Latest generated summary with its provider, model and prompt version
+        summary: Option<&'a Summary>,
+        /// Topic chapters generated by `minutes chapters`
+        chapters: &'a [Chapter],
+        /// Word count, speaking time per speaker, and other computed totals
+        statistics: TranscriptStats,
     }
 
     let data = ExportData {
         recording,
+        participants,
         segments,
+        summary,
+        chapters,
+        statistics: TranscriptStats::compute(recording, segments),
     };
     Ok(serde_json::to_string_pretty(&data)?)
 }
@@ -664,6 +4701,54 @@ fn export_as_srt(segments: &[TranscriptSegment]) -> String {
     output
 }
 
+/// WebVTT cues; segments with word timestamps get karaoke-style timing tags
+fn export_as_vtt(segments: &[TranscriptSegment]) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+
+    for segment in segments {
+        output.push_str(&format!(
+            "{} --> {}\n",
+            format_vtt_timestamp(segment.start_time),
+            format_vtt_timestamp(segment.end_time)
+        ));
+
+        if segment.words.is_empty() {
+            output.push_str(&escape_vtt(&segment.text));
+        } else {
+            for (i, word) in segment.words.iter().enumerate() {
+                let text = escape_vtt(&word.text);
+                let text = if i == 0 { text } else { format!(" {}", text) };
+                // Timing tags must fall strictly inside the cue
+                if i > 0
+                    && word.start_time > segment.start_time
+                    && word.start_time < segment.end_time
+                {
+                    output.push_str(&format!(
+                        "<{}><c>{}</c>",
+                        format_vtt_timestamp(word.start_time),
+                        text
+                    ));
+                } else {
+                    output.push_str(&text);
+                }
+            }
+        }
+        output.push_str("\n\n");
+    }
+
+    output
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn format_vtt_timestamp(secs: f64) -> String {
+    format_srt_timestamp(secs).replace(',', ".")
+}
+
 fn format_srt_timestamp(secs: f64) -> String {
     let total_ms = (secs * 1000.0) as u64;
     let hours = total_ms / 3_600_000;
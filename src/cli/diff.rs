@@ -0,0 +1,64 @@
+//! Line diff for comparing two summaries
+//!
+//! Summaries are short, so the longest common subsequence of their lines is
+//! computed directly; lines only in the old text are marked `-`, lines only
+//! in the new one `+`, and shared lines are indented by two spaces.
+
+/// Lines of `old` and `new` with a `-`, `+` or blank marker, in reading order
+pub fn diff_lines(old: &str, new: &str) -> Vec<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j]: length of the common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_removed_and_added_lines() {
+        let old = "## Decisions\n- Ship Friday\n- Budget approved";
+        let new = "## Decisions\n- Ship Monday\n- Budget approved\n- Hire a designer";
+
+        assert_eq!(
+            diff_lines(old, new),
+            [
+                "  ## Decisions",
+                "- - Ship Friday",
+                "+ - Ship Monday",
+                "  - Budget approved",
+                "+ - Hire a designer",
+            ]
+        );
+        assert!(diff_lines("", "").is_empty());
+        assert_eq!(diff_lines("a", ""), ["- a"]);
+    }
+}
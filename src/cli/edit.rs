@@ -0,0 +1,128 @@
+//! Transcript correction in a text editor
+//!
+//! `minutes edit` writes the transcript to a temporary file with one marker
+//! line per segment, opens it in `$VISUAL` or `$EDITOR`, and reads the
+//! corrected text back. Markers tie each block of text to its segment, so
+//! timings and speakers stay as they were and only the text changes.
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+
+use super::commands::format_timestamp;
+use crate::storage::{Recording, TranscriptSegment};
+
+/// Render segments in the format read back by [`parse`]
+pub fn render(recording: &Recording, segments: &[TranscriptSegment]) -> String {
+    let mut out = format!(
+        "# Transcript of \"{}\" ({})\n\
+         # Correct the text below each [#id time speaker] line and keep the\n\
+         # lines themselves. Lines starting with # are ignored. Save and close\n\
+         # the editor to apply; delete everything to cancel.\n",
+        recording.title,
+        &recording.id[..8]
+    );
+
+    for segment in segments {
+        out.push('\n');
+        out.push_str(&format!(
+            "[#{} {}",
+            segment.id,
+            format_timestamp(segment.start_time)
+        ));
+        if let Some(speaker) = &segment.speaker {
+            out.push_str(&format!(" {}", speaker));
+        }
+        out.push_str("]\n");
+        out.push_str(&segment.text);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Read corrected segment texts back from an edited file
+///
+/// Returns `(segment id, text)` pairs; lines of a block are joined with
+/// spaces. An empty file yields no pairs.
+pub fn parse(content: &str) -> Result<Vec<(i64, String)>> {
+    let mut edits: Vec<(i64, Vec<&str>)> = Vec::new();
+
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(marker) = line
+            .strip_prefix("[#")
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            let id = marker
+                .split_whitespace()
+                .next()
+                .and_then(|id| id.parse().ok())
+                .with_context(|| format!("Line {}: malformed segment marker", number + 1))?;
+            edits.push((id, Vec::new()));
+            continue;
+        }
+
+        match edits.last_mut() {
+            Some((_, lines)) => lines.push(line),
+            None => anyhow::bail!("Line {}: text before the first segment marker", number + 1),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    edits
+        .into_iter()
+        .map(|(id, lines)| {
+            if !seen.insert(id) {
+                anyhow::bail!("Segment #{} appears more than once", id);
+            }
+            if lines.is_empty() {
+                anyhow::bail!("Segment #{} has no text", id);
+            }
+            Ok((id, lines.join(" ")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rendered_transcript_with_corrections() {
+        let recording = Recording::new("Planning".to_string());
+        let mut first = TranscriptSegment::new(
+            recording.id.clone(),
+            0.0,
+            2.0,
+            "Ship it on fryday.".to_string(),
+        );
+        first.id = 7;
+        first.speaker = Some("Ada".to_string());
+        let mut second =
+            TranscriptSegment::new(recording.id.clone(), 65.0, 67.0, "Sounds good.".to_string());
+        second.id = 8;
+
+        let rendered = render(&recording, &[first, second]);
+        assert!(rendered.contains("[#7 00:00 Ada]\nShip it on fryday.\n"));
+
+        let edited = rendered.replace("fryday.", "Friday,\nright after the release.");
+        assert_eq!(
+            parse(&edited).unwrap(),
+            [
+                (7, "Ship it on Friday, right after the release.".to_string()),
+                (8, "Sounds good.".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_segments_left_without_text() {
+        assert!(parse("[#3 00:00]\n\n[#4 00:05]\nHello").is_err());
+        assert!(parse("stray text\n[#4 00:05]\nHello").is_err());
+        assert!(parse("# only comments\n").unwrap().is_empty());
+    }
+}
@@ -5,5 +5,11 @@
 pub mod args;
 pub mod commands;
 pub mod completions;
+pub mod diff;
+pub mod edit;
+pub mod pdf;
+pub mod site;
 
-pub use args::{Cli, Commands, ConfigCommand, DaemonCommand};
+pub use args::{
+    Cli, Commands, ConfigCommand, DaemonCommand, ExportTarget, PrivacyCommand, SpeakersCommand,
+};
@@ -0,0 +1,307 @@
+//! PDF output for Markdown documents
+//!
+//! Renders the Markdown `minutes` writes itself (headings, bullets and
+//! paragraphs) in the Courier fonts every PDF reader has built in, so no font
+//! is embedded and wrapping a line is a matter of counting characters.
+//! Those fonts only cover the WinAnsi (Western European) characters, so a
+//! document with others, such as Cyrillic or Chinese, is refused rather than
+//! printed with `?` in their place.
+
+use anyhow::Result;
+
+/// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+
+/// Width of a Courier glyph relative to the font size
+const CHAR_WIDTH: f32 = 0.6;
+
+const BODY_SIZE: f32 = 10.0;
+
+/// One line of text placed on a page
+struct Line {
+    bold: bool,
+    size: f32,
+    /// Space above the line, in points
+    gap: f32,
+    text: String,
+}
+
+/// Most characters the WinAnsi check lists in its error
+const MAX_LISTED: usize = 5;
+
+/// Render a Markdown document as a PDF file
+///
+/// Fails when the document has characters WinAnsi cannot encode.
+pub fn markdown_to_pdf(markdown: &str) -> Result<Vec<u8>> {
+    let mut unsupported: Vec<char> = Vec::new();
+    for c in markdown.chars() {
+        if !c.is_whitespace() && win_ansi(c).is_none() && !unsupported.contains(&c) {
+            unsupported.push(c);
+        }
+    }
+    if !unsupported.is_empty() {
+        let listed: Vec<String> = unsupported
+            .iter()
+            .take(MAX_LISTED)
+            .map(|c| format!("'{}'", c))
+            .collect();
+        anyhow::bail!(
+            "PDF export only supports Western European text, and this document contains {}{}",
+            listed.join(", "),
+            if unsupported.len() > MAX_LISTED {
+                " and more"
+            } else {
+                ""
+            }
+        );
+    }
+
+    let mut lines = Vec::new();
+    let mut gap = 0.0;
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            gap = BODY_SIZE * 0.6;
+            continue;
+        }
+
+        let level = line.chars().take_while(|&c| c == '#').count();
+        if (1..=3).contains(&level) && line[level..].starts_with(' ') {
+            let size = [18.0, 14.0, 12.0][level - 1];
+            for text in wrap(line[level..].trim(), chars_per_line(size)) {
+                lines.push(Line {
+                    bold: true,
+                    size,
+                    gap: gap.max(size * 0.5),
+                    text,
+                });
+                gap = 0.0;
+            }
+            continue;
+        }
+
+        // Bullets keep their marker and wrap under their own text
+        let (marker, text) = match line.strip_prefix("- ") {
+            Some(text) => ("- ", text),
+            None => ("", line),
+        };
+        let width = chars_per_line(BODY_SIZE) - marker.len();
+        for (i, text) in wrap(text, width).into_iter().enumerate() {
+            let prefix = if i == 0 {
+                marker
+            } else {
+                &"  "[..marker.len()]
+            };
+            lines.push(Line {
+                bold: false,
+                size: BODY_SIZE,
+                gap,
+                text: format!("{}{}", prefix, text),
+            });
+            gap = 0.0;
+        }
+    }
+
+    Ok(write_pdf(&paginate(&lines)))
+}
+
+/// Characters of Courier at `size` that fit between the margins
+fn chars_per_line(size: f32) -> usize {
+    ((PAGE_WIDTH - 2.0 * MARGIN) / (size * CHAR_WIDTH)) as usize
+}
+
+/// Split `text` into lines of at most `width` characters at spaces
+///
+/// Words longer than a line are cut.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        let used = current.chars().count();
+        if used > 0 && used + 1 + word.len() <= width {
+            current.push(' ');
+            current.extend(&word);
+            continue;
+        }
+        if used > 0 {
+            lines.push(std::mem::take(&mut current));
+        }
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        current.extend(&word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Content streams of the pages the lines fill, top to bottom
+fn paginate(lines: &[Line]) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut content = String::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    for line in lines {
+        let height = line.size * 1.4;
+        if y - line.gap - height < MARGIN && !content.is_empty() {
+            pages.push(std::mem::take(&mut content));
+            y = PAGE_HEIGHT - MARGIN;
+        } else {
+            y -= line.gap;
+        }
+        y -= height;
+        content.push_str(&format!(
+            "BT /{} {} Tf {} {:.1} Td ({}) Tj ET\n",
+            if line.bold { "F2" } else { "F1" },
+            line.size,
+            MARGIN,
+            y,
+            escape(&line.text)
+        ));
+    }
+    pages.push(content);
+    pages
+}
+
+/// Text as a PDF string body in WinAnsi encoding
+///
+/// Characters WinAnsi lacks, which [`markdown_to_pdf`] refuses, become `?`.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push_str(&format!("\\{:03o}", win_ansi(c).unwrap_or(b'?'))),
+        }
+    }
+    escaped
+}
+
+/// Code of `c` in WinAnsi encoding, if it has one
+fn win_ansi(c: char) -> Option<u8> {
+    match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => Some(c as u8),
+        '€' => Some(0x80),
+        '…' => Some(0x85),
+        '‘' => Some(0x91),
+        '’' => Some(0x92),
+        '“' => Some(0x93),
+        '”' => Some(0x94),
+        '•' => Some(0x95),
+        '–' => Some(0x96),
+        '—' => Some(0x97),
+        _ => None,
+    }
+}
+
+/// Assemble the objects of a document with the given page contents
+fn write_pdf(pages: &[String]) -> Vec<u8> {
+    // 1: catalog, 2: page tree, 3-4: fonts, then each page and its content
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", 5 + 2 * i))
+        .collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            6 + 2 * i
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+    let xref = pdf.len();
+    pdf.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    ));
+    pdf.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_at_spaces_and_cuts_long_words() {
+        assert_eq!(wrap("aaa bbb ccc", 7), ["aaa bbb", "ccc"]);
+        assert_eq!(wrap("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("", 10), [""]);
+    }
+
+    #[test]
+    fn writes_text_with_a_matching_xref_table() {
+        let pdf = String::from_utf8(markdown_to_pdf("# Minutes\n\n- Café (draft) €5\n").unwrap())
+            .unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(Minutes) Tj"));
+        assert!(pdf.contains("(- Caf\\351 \\(draft\\) \\2005) Tj"));
+
+        // Each xref entry points at its object
+        let xref = pdf.rfind("xref\n").unwrap();
+        for (i, entry) in pdf[xref..].lines().skip(3).take(6).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn long_documents_continue_on_new_pages() {
+        let markdown = "Line\n".repeat(100);
+        let pdf = String::from_utf8(markdown_to_pdf(&markdown).unwrap()).unwrap();
+        assert!(pdf.contains("/Count 2 >>"));
+    }
+
+    #[test]
+    fn refuses_text_the_built_in_fonts_cannot_show() {
+        let err = markdown_to_pdf("# Протокол\n\n- 会议\n").unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.contains("'П', 'р', 'о', 'т', 'к' and more"),
+            "{}",
+            message
+        );
+    }
+}
@@ -0,0 +1,348 @@
+//! Static HTML export of the recording archive
+//!
+//! `minutes export site` writes a self-contained folder: an index page with
+//! client-side search over a prebuilt JSON index, and one page per recording
+//! with its summary and transcript. No server is needed, so the folder can be
+//! opened locally, shared as-is, or dropped onto any static host.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+use super::commands::{format_duration, format_timestamp};
+use crate::storage::{Recording, Summary, TranscriptSegment};
+
+/// A recording and everything its page shows
+pub struct SitePage {
+    pub recording: Recording,
+    pub segments: Vec<TranscriptSegment>,
+    /// Latest generated summary, for its provenance line
+    pub summary: Option<Summary>,
+}
+
+/// One recording in the search index
+#[derive(Debug, Serialize)]
+struct IndexEntry<'a> {
+    id: &'a str,
+    title: &'a str,
+    date: String,
+    duration: Option<String>,
+    tags: &'a [String],
+    url: String,
+    summary: &'a str,
+    transcript: String,
+}
+
+/// Write the site into `output_dir`, returning the number of recordings exported
+pub fn write_site(recordings: &[SitePage], output_dir: &Path) -> Result<usize> {
+    let pages_dir = output_dir.join("recordings");
+    std::fs::create_dir_all(&pages_dir)
+        .with_context(|| format!("Failed to create site directory: {}", pages_dir.display()))?;
+
+    let mut sorted: Vec<&SitePage> = recordings.iter().collect();
+    sorted.sort_by(|a, b| b.recording.created_at.cmp(&a.recording.created_at));
+
+    let mut entries = Vec::with_capacity(sorted.len());
+    for site_page in sorted {
+        let (recording, segments) = (&site_page.recording, &site_page.segments);
+        let page = pages_dir.join(format!("{}.html", recording.id));
+        std::fs::write(&page, render_recording_page(site_page))
+            .with_context(|| format!("Failed to write page: {}", page.display()))?;
+
+        entries.push(IndexEntry {
+            id: &recording.id,
+            title: &recording.title,
+            date: recording.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            duration: recording.duration_secs.map(format_duration),
+            tags: &recording.tags,
+            url: format!("recordings/{}.html", recording.id),
+            summary: recording.notes.as_deref().unwrap_or(""),
+            transcript: segments
+                .iter()
+                .map(|segment| segment.text.trim())
+                .collect::<Vec<_>>()
+                .join(" "),
+        });
+    }
+
+    let index_json = serde_json::to_string(&entries)?;
+    std::fs::write(output_dir.join("search-index.json"), &index_json)?;
+    std::fs::write(output_dir.join("style.css"), STYLE)?;
+    std::fs::write(
+        output_dir.join("index.html"),
+        render_index_page(&entries, &index_json),
+    )?;
+
+    Ok(entries.len())
+}
+
+fn render_index_page(entries: &[IndexEntry], index_json: &str) -> String {
+    let mut list = String::new();
+    for entry in entries {
+        list.push_str(&format!(
+            "<li><a href=\"{}\">{}</a> <span class=\"meta\">{}{}</span></li>\n",
+            escape_html(&entry.url),
+            escape_html(entry.title),
+            escape_html(&entry.date),
+            entry
+                .duration
+                .as_deref()
+                .map(|d| format!(" &middot; {}", escape_html(d)))
+                .unwrap_or_default()
+        ));
+    }
+
+    // The index is inlined as well so search works when opened from file://,
+    // where browsers refuse to fetch search-index.json.
+    let inline_json = index_json.replace("</", "<\\/");
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Meeting archive</title>
+<link rel="stylesheet" href="style.css">
+</head>
+<body>
+<header><h1>Meeting archive</h1></header>
+<main>
+<input id="search" type="search" placeholder="Search titles, summaries and transcripts" autofocus>
+<p id="count" class="meta">{count} recording(s)</p>
+<ul id="recordings">
+{list}</ul>
+</main>
+<script id="search-index" type="application/json">{inline_json}</script>
+<script>
+{SEARCH_SCRIPT}
+</script>
+</body>
+</html>
+"#,
+        count = entries.len(),
+    )
+}
+
+fn render_recording_page(page: &SitePage) -> String {
+    let (recording, segments) = (&page.recording, &page.segments);
+    let mut meta = vec![recording.created_at.format("%Y-%m-%d %H:%M").to_string()];
+    if let Some(duration) = recording.duration_secs {
+        meta.push(format_duration(duration));
+    }
+    if !recording.tags.is_empty() {
+        meta.push(recording.tags.join(", "));
+    }
+
+    let summary = match recording.notes.as_deref() {
+        Some(notes) if !notes.trim().is_empty() => {
+            let provenance = page
+                .summary
+                .as_ref()
+                .map(|summary| {
+                    format!(
+                        "<p class=\"meta\">Generated by {}</p>\n",
+                        escape_html(&summary.provenance())
+                    )
+                })
+                .unwrap_or_default();
+            format!(
+                "{}<div class=\"summary\">{}</div>",
+                provenance,
+                escape_html(notes.trim())
+            )
+        }
+        _ => "<p class=\"meta\">No summary.</p>".to_string(),
+    };
+
+    let transcript = if segments.is_empty() {
+        "<p class=\"meta\">No transcript.</p>".to_string()
+    } else {
+        let mut rows = String::new();
+        for segment in segments {
+            let speaker = segment
+                .speaker
+                .as_deref()
+                .map(|s| format!("<span class=\"speaker\">{}</span> ", escape_html(s)))
+                .unwrap_or_default();
+            rows.push_str(&format!(
+                "<p><span class=\"time\">{}</span> {}{}</p>\n",
+                format_timestamp(segment.start_time),
+                speaker,
+                escape_html(segment.text.trim())
+            ));
+        }
+        format!("<div class=\"transcript\">\n{}</div>", rows)
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<link rel="stylesheet" href="../style.css">
+</head>
+<body>
+<header><a href="../index.html">&larr; All recordings</a><h1>{title}</h1>
+<p class="meta">{meta}</p></header>
+<main>
+<h2>Summary</h2>
+{summary}
+<h2>Transcript</h2>
+{transcript}
+</main>
+</body>
+</html>
+"#,
+        title = escape_html(&recording.title),
+        meta = escape_html(&meta.join(" · ")),
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const SEARCH_SCRIPT: &str = r#"const index = JSON.parse(document.getElementById("search-index").textContent);
+const list = document.getElementById("recordings");
+const count = document.getElementById("count");
+const escape = (s) => s.replace(/[&<>"']/g, (c) => ({ "&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;", "'": "&#39;" })[c]);
+
+function snippet(entry, term) {
+  for (const text of [entry.summary, entry.transcript]) {
+    const at = text.toLowerCase().indexOf(term);
+    if (at >= 0) {
+      const start = Math.max(0, at - 60);
+      return (start > 0 ? "…" : "") + text.slice(start, at + term.length + 100) + "…";
+    }
+  }
+  return "";
+}
+
+function render(query) {
+  const terms = query.toLowerCase().split(/\s+/).filter(Boolean);
+  const matches = index.filter((entry) => {
+    const haystack = [entry.title, entry.summary, entry.transcript, entry.tags.join(" ")].join(" ").toLowerCase();
+    return terms.every((term) => haystack.includes(term));
+  });
+  list.innerHTML = matches.map((entry) => {
+    const meta = escape(entry.date + (entry.duration ? " · " + entry.duration : ""));
+    const text = terms.length ? snippet(entry, terms[0]) : "";
+    return `<li><a href="${escape(entry.url)}">${escape(entry.title)}</a> <span class="meta">${meta}</span>` +
+      (text ? `<p class="snippet">${escape(text)}</p>` : "") + "</li>";
+  }).join("\n");
+  count.textContent = terms.length ? `${matches.length} of ${index.length} recording(s)` : `${index.length} recording(s)`;
+}
+
+document.getElementById("search").addEventListener("input", (event) => render(event.target.value));"#;
+
+const STYLE: &str = r#"body { font-family: system-ui, sans-serif; max-width: 52rem; margin: 0 auto; padding: 1rem; line-height: 1.5; color: #222; }
+a { color: #2458b3; }
+h1 { margin: 0.5rem 0; }
+.meta { color: #666; font-size: 0.9rem; }
+#search { width: 100%; padding: 0.5rem; font-size: 1rem; box-sizing: border-box; }
+#recordings { list-style: none; padding: 0; }
+#recordings li { padding: 0.5rem 0; border-bottom: 1px solid #eee; }
+.snippet { margin: 0.25rem 0 0; color: #444; font-size: 0.9rem; }
+.summary { white-space: pre-wrap; background: #f6f6f6; padding: 0.75rem; border-radius: 4px; }
+.transcript p { margin: 0.25rem 0; }
+.time { color: #888; font-family: monospace; }
+.speaker { font-weight: 600; }
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn page(recording: Recording, segments: Vec<TranscriptSegment>) -> SitePage {
+        SitePage {
+            recording,
+            segments,
+            summary: None,
+        }
+    }
+
+    fn sample() -> SitePage {
+        let mut recording = Recording::new("Roadmap <Q3>".to_string());
+        recording.duration_secs = Some(95);
+        recording.notes = Some("Ship the beta & gather feedback.".to_string());
+        let mut segment = TranscriptSegment::new(
+            recording.id.clone(),
+            65.0,
+            70.0,
+            "Let's close </script> tags properly.".to_string(),
+        );
+        segment.speaker = Some("Alice".to_string());
+        let summary = Summary::new(
+            recording.id.clone(),
+            recording.notes.clone().unwrap(),
+            "gemini",
+            "gemini-2.5-flash",
+            1,
+        );
+        SitePage {
+            recording,
+            segments: vec![segment],
+            summary: Some(summary),
+        }
+    }
+
+    #[test]
+    fn writes_index_pages_and_search_index() {
+        let dir = tempdir().unwrap();
+        let sample = sample();
+        let id = sample.recording.id.clone();
+
+        let count = write_site(&[sample], dir.path()).unwrap();
+        assert_eq!(count, 1);
+
+        let index = std::fs::read_to_string(dir.path().join("index.html")).unwrap();
+        assert!(index.contains("Roadmap &lt;Q3&gt;"));
+        assert!(index.contains(&format!("recordings/{}.html", id)));
+        assert!(!index.contains("</script> tags"));
+        assert!(dir.path().join("style.css").exists());
+
+        let json = std::fs::read_to_string(dir.path().join("search-index.json")).unwrap();
+        let entries: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(entries[0]["id"], id.as_str());
+        assert_eq!(entries[0]["duration"], "1:35");
+
+        let page =
+            std::fs::read_to_string(dir.path().join(format!("recordings/{}.html", id))).unwrap();
+        assert!(page.contains("Ship the beta &amp; gather feedback."));
+        assert!(page.contains("Generated by gemini / gemini-2.5-flash, prompt v1"));
+        assert!(page.contains("<span class=\"time\">01:05</span>"));
+        assert!(page.contains("<span class=\"speaker\">Alice</span>"));
+    }
+
+    #[test]
+    fn lists_newest_recordings_first() {
+        let dir = tempdir().unwrap();
+        let mut older = Recording::new("Older".to_string());
+        older.created_at -= chrono::Duration::days(1);
+        let newer = Recording::new("Newer".to_string());
+
+        write_site(
+            &[page(older, Vec::new()), page(newer, Vec::new())],
+            dir.path(),
+        )
+        .unwrap();
+
+        let index = std::fs::read_to_string(dir.path().join("index.html")).unwrap();
+        assert!(index.find("Newer").unwrap() < index.find("Older").unwrap());
+    }
+}
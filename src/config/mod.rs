@@ -1,7 +1,9 @@
 //! Configuration module for minutes
 //!
-//! Handles loading and managing application settings from TOML files.
+//! Handles loading and managing application settings from TOML files, with
+//! API keys optionally kept in the system keyring.
 
+pub mod secrets;
 mod settings;
 
-pub use settings::Settings;
+pub use settings::{LlmSettings, Settings};
@@ -0,0 +1,105 @@
+//! API keys kept in the system keyring
+//!
+//! `minutes config set-secret` stores a key with the platform's credential
+//! store (Secret Service on Linux, Keychain on macOS, Credential Manager on
+//! Windows) instead of the plaintext config file. A provider asks the
+//! keyring for its key when it is built; without one, or with nothing stored,
+//! the config file and environment variables are used as before.
+
+use anyhow::{Context, Result};
+
+/// Service name the keys are stored under
+const SERVICE: &str = "minutes";
+
+/// Settings that may be kept in the keyring
+pub const SECRET_KEYS: &[&str] = &["llm.api_key", "transcription.api_key"];
+
+fn entry(key: &str) -> Result<keyring::Entry> {
+    if !SECRET_KEYS.contains(&key) {
+        anyhow::bail!(
+            "{} is not a secret setting. Supported: {}",
+            key,
+            SECRET_KEYS.join(", ")
+        );
+    }
+    keyring::Entry::new(SERVICE, key).context("Failed to open the system keyring")
+}
+
+/// Run a keyring call on a thread of its own
+///
+/// The Secret Service backend blocks on its own async runtime, which panics
+/// when called from a thread already driving the tokio runtime.
+fn outside_runtime<T: Send>(call: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| scope.spawn(call).join())
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+/// Value stored for `key`, or `None` when there is none or no keyring to ask
+///
+/// Unit tests never see the keyring of the machine they run on.
+pub fn get_secret(key: &str) -> Option<String> {
+    if cfg!(test) {
+        return None;
+    }
+    match read_secret(key) {
+        Ok(value) if !value.trim().is_empty() => Some(value),
+        Ok(_) => None,
+        Err(error) => {
+            if !matches!(
+                error.downcast_ref::<keyring::Error>(),
+                Some(keyring::Error::NoEntry)
+            ) {
+                tracing::debug!("Could not read {} from the keyring: {}", key, error);
+            }
+            None
+        }
+    }
+}
+
+fn read_secret(key: &str) -> Result<String> {
+    outside_runtime(|| {
+        entry(key).and_then(|entry| entry.get_password().map_err(anyhow::Error::from))
+    })
+}
+
+/// Store `value` for `key` in the keyring
+pub fn set_secret(key: &str, value: &str) -> Result<()> {
+    outside_runtime(|| {
+        entry(key)?
+            .set_password(value)
+            .with_context(|| format!("Failed to store {} in the system keyring", key))
+    })
+}
+
+/// Remove the value stored for `key`, returning false if there was none
+pub fn delete_secret(key: &str) -> Result<bool> {
+    outside_runtime(|| match entry(key)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(error) => {
+            Err(error).with_context(|| format!("Failed to remove {} from the system keyring", key))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_api_keys_are_secrets() {
+        let error = set_secret("llm.model", "gemini-2.5-pro").unwrap_err();
+        assert!(error.to_string().contains("not a secret setting"));
+        assert!(read_secret("whisper.model").is_err());
+    }
+
+    #[test]
+    #[ignore = "talks to the system keyring"]
+    fn keyring_is_read_inside_the_async_runtime() {
+        // Providers are built from within the tokio runtime
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let _ = read_secret("llm.api_key");
+        });
+    }
+}
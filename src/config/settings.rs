@@ -3,9 +3,27 @@
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::audio::AudioBackend;
+use crate::audio::{AudioBackend, AudioFormat, ChannelLayout};
+use crate::config::secrets;
+
+/// Files and directories that used to live in the data dir but belong in the state dir
+const LEGACY_STATE_ENTRIES: &[&str] = &["logs", "tui-state.json"];
+
+/// Sections a settings preset may contain
+const PRESET_SECTIONS: &[&str] = &["audio", "whisper", "transcription", "llm", "storage"];
+
+/// Secrets and machine-specific keys that never travel in a preset
+const PRESET_EXCLUDED_KEYS: &[&str] = &[
+    "audio.device",
+    "audio.system_device",
+    "whisper.models_dir",
+    "transcription.api_key",
+    "transcription.vosk_model",
+    "llm.api_key",
+    "storage.archive_dir",
+];
 
 /// Main application settings
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -22,10 +40,22 @@ pub struct Settings {
     #[serde(default)]
     pub whisper: WhisperSettings,
 
+    /// Transcription engine selection (local Whisper or a remote API)
+    #[serde(default)]
+    pub transcription: TranscriptionSettings,
+
     /// LLM settings (post-MVP)
     #[serde(default)]
     pub llm: LlmSettings,
 
+    /// Calendar integration settings
+    #[serde(default)]
+    pub calendar: CalendarSettings,
+
+    /// Retention policy for old recordings' audio
+    #[serde(default)]
+    pub storage: StorageSettings,
+
     /// TUI settings
     #[serde(default)]
     pub tui: TuiSettings,
@@ -37,14 +67,34 @@ pub struct GeneralSettings {
     #[serde(default = "default_data_dir")]
     pub data_dir: PathBuf,
 
+    /// State directory for logs and runtime state (XDG state dir)
+    #[serde(default = "default_state_dir")]
+    pub state_dir: PathBuf,
+
     /// Log level (trace, debug, info, warn, error)
     #[serde(default = "default_log_level")]
     pub log_level: String,
+
+    /// Language of messages, e.g. "de"; taken from LANG when unset
+    #[serde(default)]
+    pub locale: Option<String>,
+
+    /// Also copy audio files into safety snapshots taken before destructive operations
+    #[serde(default)]
+    pub snapshot_audio: bool,
+
+    /// Number of safety snapshots kept for `minutes undo`
+    #[serde(default = "default_max_snapshots")]
+    pub max_snapshots: usize,
+
+    /// Days a deleted recording stays in the trash before it is purged (0 = until emptied)
+    #[serde(default = "default_trash_days")]
+    pub trash_days: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioSettings {
-    /// Audio backend to use (auto, pipewire, cpal)
+    /// Audio backend to use (auto, pipewire, cpal, jack, wasapi, coreaudio)
     #[serde(default)]
     pub backend: AudioBackend,
 
@@ -64,14 +114,26 @@ pub struct AudioSettings {
     #[serde(default = "default_true")]
     pub capture_microphone: bool,
 
-    /// Preferred audio device (empty = default)
+    /// Preferred microphone device name or id (empty = default)
     #[serde(default)]
     pub device: String,
 
+    /// Preferred system audio monitor target name or id (empty = default sink)
+    #[serde(default)]
+    pub system_device: String,
+
     /// Whether to compress recordings to OGG Opus
     #[serde(default = "default_true")]
     pub compress_to_ogg: bool,
 
+    /// Format used when compressing after transcription (ogg, flac)
+    #[serde(default)]
+    pub format: AudioFormat,
+
+    /// Encode to OGG Opus while recording instead of writing a WAV first
+    #[serde(default)]
+    pub stream_to_ogg: bool,
+
     /// OGG Opus bitrate in bits per second (default: 24000 for speech)
     #[serde(default = "default_ogg_bitrate")]
     pub ogg_bitrate: u32,
@@ -79,11 +141,49 @@ pub struct AudioSettings {
     /// Microphone boost factor (1.0 = no boost, 1.2 = 20% boost)
     #[serde(default = "default_mic_boost")]
     pub mic_boost: f32,
+
+    /// Level system audio and microphone automatically instead of `mic_boost`
+    #[serde(default)]
+    pub agc: bool,
+
+    /// Target RMS level for automatic gain control in dBFS
+    #[serde(default = "default_agc_target_dbfs")]
+    pub agc_target_dbfs: f32,
+
+    /// Largest boost or cut automatic gain control may apply, in dB
+    #[serde(default = "default_agc_max_gain_db")]
+    pub agc_max_gain_db: f32,
+
+    /// Suppress background noise on the microphone with RNNoise before mixing
+    #[serde(default)]
+    pub noise_suppression: bool,
+
+    /// Cancel system audio that the microphone picks up from speakers
+    #[serde(default)]
+    pub echo_cancellation: bool,
+
+    /// Keep system and microphone tracks as separate files next to the mix
+    #[serde(default)]
+    pub keep_separate_tracks: bool,
+
+    /// Channels of the saved recording: "mono" mixes both sources,
+    /// "split-stereo" puts system audio left and the microphone right
+    #[serde(default)]
+    pub channel_layout: ChannelLayout,
+
+    /// Rotate the capture into a new chunk file every N minutes (0 = off)
+    #[serde(default)]
+    pub segment_minutes: u32,
+
+    /// WAV file the mock backend plays in a loop (empty = 440 Hz tone)
+    #[serde(default)]
+    pub mock_fixture: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhisperSettings {
-    /// Whisper model to use (tiny, base, small, medium, large)
+    /// Whisper model to use (tiny, base, small, medium, large-v3), optionally
+    /// quantized as in "base.en-q5_1"
     #[serde(default = "default_model")]
     pub model: String,
 
@@ -95,21 +195,149 @@ pub struct WhisperSettings {
     #[serde(default)]
     pub language: String,
 
-    /// Enable translation to English
+    /// Also store an English translation of each segment (a second pass)
     #[serde(default)]
     pub translate: bool,
 
     /// Number of threads for inference (0 = auto)
     #[serde(default)]
     pub threads: u32,
+
+    /// Transcribe separate system/microphone tracks individually when available
+    #[serde(default)]
+    pub per_track: bool,
+
+    /// Minimum voice similarity (0.0 - 1.0) to label a speaker with an enrolled name
+    #[serde(default = "default_speaker_match_threshold")]
+    pub speaker_match_threshold: f32,
+
+    /// Shorten long silent stretches before transcription
+    #[serde(default = "default_true")]
+    pub trim_silence: bool,
+
+    /// Shortest silent stretch, in seconds, that gets trimmed
+    #[serde(default = "default_min_silence_secs")]
+    pub min_silence_secs: f64,
+
+    /// Level in dBFS below which audio counts as silence
+    #[serde(default = "default_silence_threshold_db")]
+    pub silence_threshold_db: f32,
+
+    /// Drop segments Whisper invents during silence (blocklist, repetition, silent audio)
+    #[serde(default = "default_true")]
+    pub filter_hallucinations: bool,
+
+    /// Whisper's no-speech probability above which a segment counts as silence
+    #[serde(default = "default_no_speech_threshold")]
+    pub no_speech_threshold: f32,
+
+    /// Segment texts dropped when they make up a whole segment
+    #[serde(default = "default_hallucination_blocklist")]
+    pub hallucination_blocklist: Vec<String>,
+
+    /// Identical segments in a row kept before the rest are dropped (0 = no limit)
+    #[serde(default = "default_max_segment_repeats")]
+    pub max_segment_repeats: usize,
+
+    /// Clean up transcribed text (fillers, repeated words, sentence casing)
+    #[serde(default = "default_true")]
+    pub clean_up_text: bool,
+
+    /// Markers removed from transcribed text by the cleanup
+    #[serde(default = "default_filler_tokens")]
+    pub filler_tokens: Vec<String>,
+
+    /// Merge segments of the same speaker at most this many seconds apart (0 = never)
+    #[serde(default = "default_merge_gap_secs")]
+    pub merge_gap_secs: f64,
+
+    /// Longest segment merging may produce, in seconds (0 = no limit)
+    #[serde(default)]
+    pub merge_max_secs: f64,
+
+    /// Only merge segments within a sentence, never across its end
+    #[serde(default)]
+    pub merge_within_sentences: bool,
+
+    /// Store the start and end time of every word, not just of each segment
+    #[serde(default)]
+    pub word_timestamps: bool,
+
+    /// Beams kept by beam search decoding (0 = greedy decoding)
+    #[serde(default)]
+    pub beam_size: u32,
+
+    /// Candidates sampled per segment by greedy decoding at a non-zero temperature
+    #[serde(default = "default_best_of")]
+    pub best_of: u32,
+
+    /// Initial sampling temperature (0.0 = most likely token)
+    #[serde(default)]
+    pub temperature: f32,
+
+    /// Token entropy above which a segment is decoded again at a higher temperature
+    #[serde(default = "default_entropy_threshold")]
+    pub entropy_threshold: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionSettings {
+    /// Transcription provider (local, openai, deepgram)
+    #[serde(default = "default_transcription_provider")]
+    pub provider: String,
+
+    /// Engine used by the local provider (whisper, vosk, mock)
+    #[serde(default = "default_transcription_engine")]
+    pub engine: String,
+
+    /// Unpacked Vosk model directory (for the vosk engine)
+    #[serde(default)]
+    pub vosk_model: PathBuf,
+
+    /// API key (for remote providers)
+    #[serde(default)]
+    pub api_key: String,
+
+    /// Remote model name (empty = provider default)
+    #[serde(default)]
+    pub model: String,
+
+    /// API endpoint (empty = provider default)
+    #[serde(default)]
+    pub endpoint: String,
+
+    /// Confidence (0.0 - 1.0) below which segments are flagged for review (0 = never)
+    #[serde(default = "default_low_confidence_threshold")]
+    pub low_confidence_threshold: f64,
+
+    /// Number of recordings transcribed at the same time
+    #[serde(default = "default_max_jobs")]
+    pub max_jobs: usize,
+
+    /// Order queued recordings are transcribed in (oldest, newest)
+    #[serde(default = "default_queue_order")]
+    pub queue_order: String,
+
+    /// Attempts at a transcription that keeps failing for a transient reason (1 = no retries)
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Wait before the first retry, doubled for each one after it
+    #[serde(default = "default_retry_delay_secs")]
+    pub retry_delay_secs: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmSettings {
-    /// LLM provider (gemini, ollama)
+    /// LLM provider (gemini, ollama, llama)
     #[serde(default = "default_llm_provider")]
     pub provider: String,
 
+    /// Providers tried in order, each falling back to the next when it fails
+    /// (empty = `provider` alone)
+    #[serde(default)]
+    pub providers: Vec<String>,
+
     /// API key (for cloud providers)
     #[serde(default)]
     pub api_key: String,
@@ -121,6 +349,92 @@ pub struct LlmSettings {
     /// API endpoint (for local/custom providers)
     #[serde(default)]
     pub endpoint: String,
+
+    /// Price per million input tokens in USD, for cost estimates (0 = unknown)
+    #[serde(default)]
+    pub input_cost_per_million: f64,
+
+    /// Price per million output tokens in USD, for cost estimates (0 = unknown)
+    #[serde(default)]
+    pub output_cost_per_million: f64,
+
+    /// Ask the LLM for a title once a recording with a default title is transcribed
+    #[serde(default)]
+    pub auto_title: bool,
+
+    /// Ask the LLM for tags to accept once a recording is transcribed
+    #[serde(default)]
+    pub suggest_tags: bool,
+
+    /// Mask email addresses, phone numbers and card numbers before prompts reach a cloud provider
+    #[serde(default)]
+    pub redact_pii: bool,
+
+    /// Language summaries are written in, e.g. "English" (empty = the meeting's language)
+    #[serde(default)]
+    pub summary_language: String,
+
+    /// Longest transcript, in characters, summarized in one request; longer
+    /// ones are summarized in chunks and the results combined
+    #[serde(default = "default_summary_chunk_chars")]
+    pub summary_chunk_chars: usize,
+
+    /// Characters each chunk repeats from the end of the one before
+    #[serde(default = "default_summary_chunk_overlap_chars")]
+    pub summary_chunk_overlap_chars: usize,
+
+    /// Attempts at a request failing with a rate limit, server or network error (1 = no retries)
+    #[serde(default = "default_llm_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Wait before the first retry, doubled for each one after it, unless
+    /// the provider says how long to wait
+    #[serde(default = "default_llm_retry_delay_secs")]
+    pub retry_delay_secs: u64,
+
+    /// Seconds to wait for one response (0 = the provider's default)
+    #[serde(default)]
+    pub timeout_secs: u64,
+
+    /// Model embedding transcripts for semantic search (empty = provider default)
+    #[serde(default)]
+    pub embedding_model: String,
+
+    /// Embed a recording's segments for semantic search once it is transcribed
+    #[serde(default)]
+    pub auto_embed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarSettings {
+    /// iCalendar file to read upcoming meetings from (empty = disabled)
+    #[serde(default)]
+    pub ics_path: String,
+
+    /// How far ahead the dashboard looks for meetings, in hours
+    #[serde(default = "default_lookahead_hours")]
+    pub lookahead_hours: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageSettings {
+    /// Remove the audio of recordings older than this many days (0 = keep)
+    #[serde(default)]
+    pub retention_days: u32,
+
+    /// Remove the audio of the oldest recordings while the audio directory
+    /// holds more than this many gigabytes (0 = no limit)
+    #[serde(default)]
+    pub retention_max_gb: f64,
+
+    /// Keep transcripts, summaries and notes when removing audio; when off,
+    /// whole recordings are deleted
+    #[serde(default = "default_true")]
+    pub keep_transcripts: bool,
+
+    /// Move expired audio into this directory instead of deleting it (empty = delete)
+    #[serde(default)]
+    pub archive_dir: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -146,6 +460,18 @@ fn default_data_dir() -> PathBuf {
         .unwrap_or_else(|| PathBuf::from("~/.local/share/minutes"))
 }
 
+/// XDG state directory on Linux; macOS and Windows have none, so the local
+/// data directory is used there
+fn default_state_dir() -> PathBuf {
+    ProjectDirs::from("com", "minutes", "minutes")
+        .map(|dirs| {
+            dirs.state_dir()
+                .unwrap_or_else(|| dirs.data_local_dir())
+                .to_path_buf()
+        })
+        .unwrap_or_else(default_data_dir)
+}
+
 fn default_models_dir() -> PathBuf {
     let mut dir = default_data_dir();
     dir.push("models");
@@ -156,6 +482,14 @@ fn default_log_level() -> String {
     "info".to_string()
 }
 
+fn default_max_snapshots() -> usize {
+    20
+}
+
+fn default_trash_days() -> u32 {
+    30
+}
+
 fn default_sample_rate() -> u32 {
     16000
 }
@@ -176,10 +510,107 @@ fn default_mic_boost() -> f32 {
     1.2
 }
 
+fn default_agc_target_dbfs() -> f32 {
+    -20.0
+}
+
+fn default_agc_max_gain_db() -> f32 {
+    20.0
+}
+
 fn default_model() -> String {
     "base".to_string()
 }
 
+fn default_speaker_match_threshold() -> f32 {
+    0.92
+}
+
+fn default_min_silence_secs() -> f64 {
+    2.0
+}
+
+fn default_silence_threshold_db() -> f32 {
+    -45.0
+}
+
+fn default_no_speech_threshold() -> f32 {
+    0.6
+}
+
+fn default_max_segment_repeats() -> usize {
+    2
+}
+
+fn default_merge_gap_secs() -> f64 {
+    0.5
+}
+
+fn default_best_of() -> u32 {
+    5
+}
+
+fn default_entropy_threshold() -> f32 {
+    2.4
+}
+
+fn default_hallucination_blocklist() -> Vec<String> {
+    [
+        "Thanks for watching!",
+        "Thank you for watching.",
+        "Please subscribe to my channel.",
+        "Like and subscribe.",
+        "Subtitles by the Amara.org community",
+        "Transcription by CastingWords",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_filler_tokens() -> Vec<String> {
+    [
+        "[BLANK_AUDIO]",
+        "[MUSIC]",
+        "[NOISE]",
+        "[SILENCE]",
+        "[INAUDIBLE]",
+        "(silence)",
+        "(music)",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_transcription_provider() -> String {
+    "local".to_string()
+}
+
+fn default_transcription_engine() -> String {
+    "whisper".to_string()
+}
+
+fn default_low_confidence_threshold() -> f64 {
+    0.5
+}
+
+fn default_max_jobs() -> usize {
+    1
+}
+
+fn default_queue_order() -> String {
+    "oldest".to_string()
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_retry_delay_secs() -> u64 {
+    60
+}
+
 fn default_llm_provider() -> String {
     "gemini".to_string()
 }
@@ -188,6 +619,27 @@ fn default_llm_model() -> String {
     "gemini-2.5-flash".to_string()
 }
 
+fn default_summary_chunk_chars() -> usize {
+    // About 25k tokens, well inside the context of current models
+    100_000
+}
+
+fn default_summary_chunk_overlap_chars() -> usize {
+    2_000
+}
+
+fn default_llm_max_attempts() -> u32 {
+    4
+}
+
+fn default_llm_retry_delay_secs() -> u64 {
+    2
+}
+
+fn default_lookahead_hours() -> u32 {
+    12
+}
+
 fn default_recent_count() -> usize {
     5
 }
@@ -200,7 +652,12 @@ impl Default for GeneralSettings {
     fn default() -> Self {
         Self {
             data_dir: default_data_dir(),
+            state_dir: default_state_dir(),
             log_level: default_log_level(),
+            locale: None,
+            snapshot_audio: false,
+            max_snapshots: default_max_snapshots(),
+            trash_days: default_trash_days(),
         }
     }
 }
@@ -214,9 +671,21 @@ impl Default for AudioSettings {
             capture_system: true,
             capture_microphone: true,
             device: String::new(),
+            system_device: String::new(),
             compress_to_ogg: true,
+            format: AudioFormat::default(),
+            stream_to_ogg: false,
             ogg_bitrate: default_ogg_bitrate(),
             mic_boost: default_mic_boost(),
+            agc: false,
+            agc_target_dbfs: default_agc_target_dbfs(),
+            agc_max_gain_db: default_agc_max_gain_db(),
+            noise_suppression: false,
+            echo_cancellation: false,
+            keep_separate_tracks: false,
+            channel_layout: ChannelLayout::default(),
+            segment_minutes: 0,
+            mock_fixture: String::new(),
         }
     }
 }
@@ -229,17 +698,155 @@ impl Default for WhisperSettings {
             language: String::new(),
             translate: false,
             threads: 0,
+            per_track: false,
+            speaker_match_threshold: default_speaker_match_threshold(),
+            trim_silence: true,
+            min_silence_secs: default_min_silence_secs(),
+            silence_threshold_db: default_silence_threshold_db(),
+            filter_hallucinations: true,
+            no_speech_threshold: default_no_speech_threshold(),
+            hallucination_blocklist: default_hallucination_blocklist(),
+            max_segment_repeats: default_max_segment_repeats(),
+            clean_up_text: true,
+            filler_tokens: default_filler_tokens(),
+            merge_gap_secs: default_merge_gap_secs(),
+            merge_max_secs: 0.0,
+            merge_within_sentences: false,
+            word_timestamps: false,
+            beam_size: 0,
+            best_of: default_best_of(),
+            temperature: 0.0,
+            entropy_threshold: default_entropy_threshold(),
+        }
+    }
+}
+
+impl Default for TranscriptionSettings {
+    fn default() -> Self {
+        Self {
+            provider: default_transcription_provider(),
+            engine: default_transcription_engine(),
+            vosk_model: PathBuf::new(),
+            api_key: String::new(),
+            model: String::new(),
+            endpoint: String::new(),
+            low_confidence_threshold: default_low_confidence_threshold(),
+            max_jobs: default_max_jobs(),
+            queue_order: default_queue_order(),
+            max_attempts: default_max_attempts(),
+            retry_delay_secs: default_retry_delay_secs(),
         }
     }
 }
 
+impl TranscriptionSettings {
+    /// API key stored in the system keyring, else the one from the file or
+    /// environment
+    ///
+    /// The keyring is only asked when a remote provider is built, so commands
+    /// that never call one do not wait on it.
+    pub fn api_key(&self) -> String {
+        secrets::get_secret("transcription.api_key").unwrap_or_else(|| self.api_key.clone())
+    }
+
+    /// Whether transcription runs on this machine
+    pub fn is_local(&self) -> bool {
+        self.provider.trim().eq_ignore_ascii_case("local")
+    }
+
+    /// Wait before retrying a transcription that failed on attempt `attempts`
+    ///
+    /// Returns `None` once `max_attempts` is used up.
+    pub fn retry_delay(&self, attempts: u32) -> Option<std::time::Duration> {
+        backoff_delay(self.max_attempts, self.retry_delay_secs, attempts)
+    }
+
+    /// Whether the most recently queued recordings are transcribed first
+    pub fn newest_first(&self) -> bool {
+        self.queue_order.trim().eq_ignore_ascii_case("newest")
+    }
+
+    /// Whether transcription needs a downloaded Whisper model
+    pub fn uses_whisper(&self) -> bool {
+        self.is_local() && self.engine.trim().eq_ignore_ascii_case("whisper")
+    }
+}
+
+/// `delay_secs` doubled for each attempt after the first, until `max_attempts`
+fn backoff_delay(max_attempts: u32, delay_secs: u64, attempts: u32) -> Option<std::time::Duration> {
+    if attempts >= max_attempts {
+        return None;
+    }
+    let factor = 2u64.saturating_pow(attempts.saturating_sub(1));
+    Some(std::time::Duration::from_secs(
+        delay_secs.saturating_mul(factor),
+    ))
+}
+
 impl Default for LlmSettings {
     fn default() -> Self {
         Self {
             provider: default_llm_provider(),
+            providers: Vec::new(),
             api_key: String::new(),
             model: default_llm_model(),
             endpoint: String::new(),
+            input_cost_per_million: 0.0,
+            output_cost_per_million: 0.0,
+            auto_title: false,
+            suggest_tags: false,
+            redact_pii: false,
+            summary_language: String::new(),
+            summary_chunk_chars: default_summary_chunk_chars(),
+            summary_chunk_overlap_chars: default_summary_chunk_overlap_chars(),
+            max_attempts: default_llm_max_attempts(),
+            retry_delay_secs: default_llm_retry_delay_secs(),
+            timeout_secs: 0,
+            embedding_model: String::new(),
+            auto_embed: false,
+        }
+    }
+}
+
+impl LlmSettings {
+    /// API key stored in the system keyring, else the one from the file or
+    /// environment
+    pub fn api_key(&self) -> String {
+        secrets::get_secret("llm.api_key").unwrap_or_else(|| self.api_key.clone())
+    }
+
+    /// Wait before retrying a request that failed on attempt `attempts`
+    ///
+    /// Returns `None` once `max_attempts` is used up.
+    pub fn retry_delay(&self, attempts: u32) -> Option<std::time::Duration> {
+        backoff_delay(self.max_attempts, self.retry_delay_secs, attempts)
+    }
+
+    /// Request timeout, or `default` when none is configured
+    pub fn timeout(&self, default: std::time::Duration) -> std::time::Duration {
+        match self.timeout_secs {
+            0 => default,
+            secs => std::time::Duration::from_secs(secs),
+        }
+    }
+}
+
+impl Default for CalendarSettings {
+    fn default() -> Self {
+        Self {
+            ics_path: String::new(),
+            lookahead_hours: default_lookahead_hours(),
+        }
+    }
+}
+
+impl Default for StorageSettings {
+    fn default() -> Self {
+        Self {
+            retention_days: 0,
+            retention_max_gb: 0.0,
+            keep_transcripts: true,
+            archive_dir: String::new(),
         }
     }
 }
@@ -286,6 +893,18 @@ impl Settings {
                 }
             }
         }
+
+        let transcription_key_var = match self.transcription.provider.trim() {
+            "deepgram" => "MINUTES_DEEPGRAM_API_KEY",
+            _ => "MINUTES_OPENAI_API_KEY",
+        };
+        if self.transcription.api_key.trim().is_empty() {
+            if let Ok(key) = std::env::var(transcription_key_var) {
+                if !key.trim().is_empty() {
+                    self.transcription.api_key = key;
+                }
+            }
+        }
     }
 
     /// Get the path to the configuration file
@@ -297,6 +916,14 @@ impl Settings {
         Ok(config_dir.join("config.toml"))
     }
 
+    /// Get the directory holding user prompt templates, next to the config file
+    pub fn prompts_dir() -> Result<PathBuf> {
+        let dirs = ProjectDirs::from("com", "minutes", "minutes")
+            .context("Could not determine config directory")?;
+
+        Ok(dirs.config_dir().join("prompts"))
+    }
+
     /// Write default configuration to a file
     pub fn write_default(path: &PathBuf) -> Result<()> {
         let settings = Self::default();
@@ -310,6 +937,93 @@ impl Settings {
         Ok(())
     }
 
+    /// Non-secret, machine-independent settings to share as a preset
+    pub fn to_preset(&self) -> Result<toml::Table> {
+        let mut full = toml::Table::try_from(self)?;
+        let mut preset = toml::Table::new();
+
+        for section in PRESET_SECTIONS {
+            if let Some(toml::Value::Table(mut values)) = full.remove(*section) {
+                values.retain(|key, _| !is_excluded_from_preset(section, key));
+                preset.insert(section.to_string(), toml::Value::Table(values));
+            }
+        }
+
+        Ok(preset)
+    }
+
+    /// Merge a preset into the config file at `path`
+    ///
+    /// Keys outside the preset sections, secrets and machine-specific paths
+    /// are left untouched and returned as skipped. The merged config is
+    /// validated before it is written.
+    pub fn import_preset(path: &Path, preset: &toml::Table) -> Result<Vec<String>> {
+        let mut changes = toml::Table::new();
+        let mut skipped = Vec::new();
+        for (section, value) in preset {
+            let values = match value.as_table() {
+                Some(values) if PRESET_SECTIONS.contains(&section.as_str()) => values,
+                _ => {
+                    skipped.push(section.clone());
+                    continue;
+                }
+            };
+
+            let mut kept = toml::Table::new();
+            for (key, value) in values {
+                if is_excluded_from_preset(section, key) {
+                    skipped.push(format!("{}.{}", section, key));
+                } else {
+                    kept.insert(key.clone(), value.clone());
+                }
+            }
+            changes.insert(section.clone(), toml::Value::Table(kept));
+        }
+
+        Self::update_config(path, &changes).context("Preset contains invalid settings")?;
+        Ok(skipped)
+    }
+
+    /// Set keys in the config file at `path`, keeping everything else
+    ///
+    /// `changes` maps section names to tables of keys. The merged config is
+    /// validated before it is written.
+    pub fn update_config(path: &Path, changes: &toml::Table) -> Result<()> {
+        let mut config: toml::Table = if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+            toml::from_str(&content)
+                .with_context(|| format!("Failed to parse config file: {}", path.display()))?
+        } else {
+            toml::Table::new()
+        };
+
+        for (section, values) in changes {
+            let values = values
+                .as_table()
+                .with_context(|| format!("Changes to [{}] are not a table", section))?;
+            let target = config
+                .entry(section.clone())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .with_context(|| format!("Config section [{}] is not a table", section))?;
+            for (key, value) in values {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+
+        toml::Value::Table(config.clone())
+            .try_into::<Settings>()
+            .context("Invalid settings")?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(&config)?)?;
+
+        Ok(())
+    }
+
     /// Get the database path
     pub fn database_path(&self) -> PathBuf {
         self.general.data_dir.join("minutes.db")
@@ -320,6 +1034,26 @@ impl Settings {
         self.general.data_dir.join("audio")
     }
 
+    /// Get the directory for audio copied into safety snapshots
+    pub fn snapshots_dir(&self) -> PathBuf {
+        self.general.data_dir.join("snapshots")
+    }
+
+    /// Get the directory for log files
+    pub fn log_dir(&self) -> PathBuf {
+        self.general.state_dir.join("logs")
+    }
+
+    /// Get the log file written by the background daemon
+    pub fn daemon_log_path(&self) -> PathBuf {
+        self.log_dir().join("daemon.log")
+    }
+
+    /// Get the path for persisted TUI state
+    pub fn tui_state_path(&self) -> PathBuf {
+        self.general.state_dir.join("tui-state.json")
+    }
+
     /// Get the Unix socket path for IPC
     pub fn socket_path(&self) -> PathBuf {
         let runtime_dir = std::env::var("XDG_RUNTIME_DIR")
@@ -341,24 +1075,203 @@ impl Settings {
         std::fs::create_dir_all(&self.general.data_dir)?;
         std::fs::create_dir_all(self.audio_dir())?;
         std::fs::create_dir_all(&self.whisper.models_dir)?;
+        std::fs::create_dir_all(&self.general.state_dir)?;
+        self.migrate_legacy_state()?;
+        std::fs::create_dir_all(self.log_dir())?;
+        Ok(())
+    }
+
+    /// Move logs and runtime state left in the data dir by older versions
+    /// into the state dir. Entries already present in the state dir win.
+    fn migrate_legacy_state(&self) -> Result<()> {
+        if self.general.data_dir == self.general.state_dir {
+            return Ok(());
+        }
+
+        for entry in LEGACY_STATE_ENTRIES {
+            let source = self.general.data_dir.join(entry);
+            let target = self.general.state_dir.join(entry);
+            if !source.exists() || target.exists() {
+                continue;
+            }
+
+            std::fs::rename(&source, &target).with_context(|| {
+                format!(
+                    "Failed to migrate {} to {}",
+                    source.display(),
+                    target.display()
+                )
+            })?;
+            tracing::info!("Migrated {} to {}", source.display(), target.display());
+        }
+
         Ok(())
     }
 
     /// Get the path to a whisper model file
     pub fn model_path(&self) -> PathBuf {
-        self.whisper
-            .models_dir
-            .join(format!("ggml-{}.bin", self.whisper.model))
+        self.model_file(&self.whisper.model)
     }
+
+    /// Path a named Whisper model is stored at in the models directory
+    ///
+    /// Accepts the file name as well, so "ggml-base.en-q5_1.bin" and
+    /// "base.en-q5_1" name the same model.
+    pub fn model_file(&self, model: &str) -> PathBuf {
+        self.whisper.models_dir.join(format!(
+            "ggml-{}.bin",
+            crate::transcription::model_name(model)
+        ))
+    }
+
+    /// Path of the GGUF model named by `llm.model`, for the llama provider
+    ///
+    /// A file name is looked up in the models directory next to the Whisper
+    /// models, with ".gguf" added when missing; a path is used as is.
+    pub fn llm_model_file(&self) -> PathBuf {
+        let model = Path::new(self.llm.model.trim());
+        if model.components().count() > 1 {
+            return model.to_path_buf();
+        }
+        let mut file = model.as_os_str().to_os_string();
+        if model
+            .extension()
+            .is_none_or(|extension| extension != "gguf")
+        {
+            file.push(".gguf");
+        }
+        self.whisper.models_dir.join(file)
+    }
+}
+
+fn is_excluded_from_preset(section: &str, key: &str) -> bool {
+    PRESET_EXCLUDED_KEYS.contains(&format!("{}.{}", section, key).as_str())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn preset_leaves_out_secrets_and_machine_paths() {
+        let mut settings = Settings::default();
+        settings.llm.api_key = "secret".to_string();
+        settings.audio.device = "alsa_input.usb".to_string();
+        settings.whisper.model = "small".to_string();
+        settings.storage.retention_days = 30;
+        settings.storage.archive_dir = "/mnt/archive".to_string();
+
+        let preset = settings.to_preset().unwrap();
+
+        assert!(preset.get("general").is_none());
+        assert!(preset["llm"].get("api_key").is_none());
+        assert!(preset["audio"].get("device").is_none());
+        assert!(preset["whisper"].get("models_dir").is_none());
+        assert_eq!(preset["whisper"]["model"].as_str(), Some("small"));
+        assert!(preset["storage"].get("archive_dir").is_none());
+        assert_eq!(preset["storage"]["retention_days"].as_integer(), Some(30));
+    }
+
+    #[test]
+    fn import_preset_merges_into_existing_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[general]\ndata_dir = \"/srv/minutes\"\n\n[llm]\napi_key = \"mine\"\n",
+        )
+        .unwrap();
+
+        let preset: toml::Table = toml::from_str(
+            "[whisper]\nmodel = \"medium\"\n\n[llm]\napi_key = \"theirs\"\nmodel = \"gemini-2.5-pro\"\n\n[general]\nlog_level = \"debug\"\n",
+        )
+        .unwrap();
+
+        let skipped = Settings::import_preset(&path, &preset).unwrap();
+        assert_eq!(skipped, vec!["general", "llm.api_key"]);
+
+        let merged: Settings = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(merged.general.data_dir, PathBuf::from("/srv/minutes"));
+        assert_eq!(merged.llm.api_key, "mine");
+        assert_eq!(merged.llm.model, "gemini-2.5-pro");
+        assert_eq!(merged.whisper.model, "medium");
+    }
+
+    #[test]
+    fn import_preset_rejects_invalid_values() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        let preset: toml::Table = toml::from_str("[audio]\nsample_rate = \"fast\"\n").unwrap();
+
+        assert!(Settings::import_preset(&path, &preset).is_err());
+        assert!(!path.exists());
+    }
+
     #[test]
     fn defaults_to_gemini_25_flash() {
         let settings = Settings::default();
         assert_eq!(settings.llm.model, "gemini-2.5-flash");
     }
+
+    #[test]
+    fn llm_model_file_is_found_in_the_models_dir() {
+        let mut settings = Settings::default();
+        settings.whisper.models_dir = PathBuf::from("/models");
+
+        settings.llm.model = "qwen2.5-3b-instruct-q4_k_m".to_string();
+        assert_eq!(
+            settings.llm_model_file(),
+            Path::new("/models/qwen2.5-3b-instruct-q4_k_m.gguf")
+        );
+        settings.llm.model = "llama-3.2-3b.gguf".to_string();
+        assert_eq!(
+            settings.llm_model_file(),
+            Path::new("/models/llama-3.2-3b.gguf")
+        );
+        settings.llm.model = "/opt/llm/mistral.gguf".to_string();
+        assert_eq!(
+            settings.llm_model_file(),
+            Path::new("/opt/llm/mistral.gguf")
+        );
+    }
+
+    #[test]
+    fn ensure_dirs_migrates_legacy_state_out_of_data_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings.general.data_dir = tmp.path().join("data");
+        settings.general.state_dir = tmp.path().join("state");
+        settings.whisper.models_dir = tmp.path().join("data/models");
+
+        let legacy_logs = settings.general.data_dir.join("logs");
+        std::fs::create_dir_all(&legacy_logs).unwrap();
+        std::fs::write(legacy_logs.join("daemon.log"), "old log").unwrap();
+        std::fs::write(settings.general.data_dir.join("tui-state.json"), "{}").unwrap();
+
+        settings.ensure_dirs().unwrap();
+
+        assert!(!legacy_logs.exists());
+        assert_eq!(
+            std::fs::read_to_string(settings.daemon_log_path()).unwrap(),
+            "old log"
+        );
+        assert!(settings.tui_state_path().exists());
+        assert!(settings
+            .database_path()
+            .starts_with(&settings.general.data_dir));
+    }
+
+    #[test]
+    fn retry_delay_doubles_until_attempts_run_out() {
+        let transcription = TranscriptionSettings {
+            max_attempts: 3,
+            retry_delay_secs: 60,
+            ..TranscriptionSettings::default()
+        };
+
+        let delays: Vec<_> = (1..=3)
+            .map(|attempt| transcription.retry_delay(attempt).map(|d| d.as_secs()))
+            .collect();
+        assert_eq!(delays, [Some(60), Some(120), None]);
+    }
 }
@@ -17,14 +17,9 @@ impl DaemonClient {
     pub async fn connect(settings: &Settings) -> Result<Self> {
         let socket_path = settings.socket_path();
 
-        let stream = UnixStream::connect(&socket_path)
-            .await
-            .with_context(|| {
-                format!(
-                    "Failed to connect to daemon at {:?}. Is the daemon running? Try: minutes daemon start",
-                    socket_path
-                )
-            })?;
+        let stream = UnixStream::connect(&socket_path).await.with_context(|| {
+            crate::t!("daemon-connect-failed", path = format!("{:?}", socket_path))
+        })?;
 
         Ok(Self { stream })
     }
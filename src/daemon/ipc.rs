@@ -2,11 +2,22 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::storage::Sensitivity;
+
 /// Request sent from CLI/TUI to daemon
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DaemonRequest {
     /// Start a new recording
-    StartRecording { title: String },
+    StartRecording {
+        title: String,
+        #[serde(default)]
+        sensitivity: Sensitivity,
+        #[serde(default)]
+        participants: Vec<String>,
+        /// Language to transcribe in ("auto" to detect), overriding whisper.language
+        #[serde(default)]
+        language: Option<String>,
+    },
 
     /// Stop the current recording
     StopRecording,
@@ -21,7 +32,22 @@ pub enum DaemonRequest {
     Shutdown,
 
     /// Force transcription of a recording
-    Transcribe { recording_id: String },
+    ///
+    /// `language` replaces the language chosen for the recording, if given.
+    Transcribe {
+        recording_id: String,
+        #[serde(default)]
+        language: Option<String>,
+    },
+
+    /// Take a recording out of the transcription queue, stopping it if running
+    CancelTranscription { recording_id: String },
+
+    /// Enable or disable privacy mode
+    SetPrivacyMode { enabled: bool },
+
+    /// Query whether privacy mode is enabled
+    GetPrivacyMode,
 }
 
 /// Response sent from daemon to CLI/TUI
@@ -31,7 +57,13 @@ pub enum DaemonResponse {
     RecordingStarted { id: String },
 
     /// Recording stopped successfully
-    RecordingStopped { id: String, duration_secs: u64 },
+    RecordingStopped {
+        id: String,
+        duration_secs: u64,
+        /// Problems found in the recorded tracks (silent or clipped audio)
+        #[serde(default)]
+        warnings: Vec<String>,
+    },
 
     /// Current status
     Status(RecordingStatus),
@@ -39,6 +71,9 @@ pub enum DaemonResponse {
     /// Pong response to ping
     Pong,
 
+    /// Current privacy mode
+    PrivacyMode { enabled: bool },
+
     /// Acknowledgment (for shutdown, etc.)
     Ok,
 
@@ -61,7 +96,19 @@ pub enum RecordingStatus {
     },
 
     /// Transcription in progress
-    Transcribing { id: String, progress: f32 },
+    Transcribing {
+        id: String,
+        progress: f32,
+        /// Seconds of the recording transcribed so far
+        #[serde(default)]
+        processed_secs: u64,
+        /// Length of the recording in seconds (0 = unknown)
+        #[serde(default)]
+        total_secs: u64,
+        /// Estimated seconds until transcription finishes
+        #[serde(default)]
+        eta_secs: Option<u64>,
+    },
 }
 
 /// Serialize a request to bytes for IPC
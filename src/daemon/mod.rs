@@ -9,7 +9,7 @@ pub mod service;
 pub mod state;
 
 use anyhow::Result;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
 use crate::config::Settings;
@@ -38,13 +38,30 @@ pub fn start_daemon(settings: &Settings) -> Result<()> {
         let _ = std::fs::remove_file(&socket_path);
     }
 
+    // Send daemon logs to the state dir; fall back to discarding them if the
+    // log file cannot be opened.
+    let log_path = settings.daemon_log_path();
+    let stderr = log_path
+        .parent()
+        .map(std::fs::create_dir_all)
+        .transpose()
+        .and_then(|_| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&log_path)
+        })
+        .map(Stdio::from)
+        .unwrap_or_else(|_| Stdio::null());
+
     // Start daemon process
     let exe = std::env::current_exe()?;
     let mut child = Command::new(exe)
-        .args(["daemon", "start", "--foreground"])
-        .stdin(std::process::Stdio::null())
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
+        .args(["daemon", "start", "--foreground", "--backend"])
+        .arg(settings.audio.backend.as_str())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(stderr)
         .spawn()?;
 
     // Wait for daemon readiness so callers don't get a false positive start.
@@ -52,8 +69,9 @@ pub fn start_daemon(settings: &Settings) -> Result<()> {
     while Instant::now() < deadline {
         if let Some(status) = child.try_wait()? {
             anyhow::bail!(
-                "Daemon failed to start (exit: {}). Run `minutes daemon start --foreground` for details.",
-                status
+                "Daemon failed to start (exit: {}). See {} or run `minutes daemon start --foreground` for details.",
+                status,
+                log_path.display()
             );
         }
 
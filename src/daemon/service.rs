@@ -1,20 +1,37 @@
 //! Main daemon service implementation
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 
-use crate::audio::{create_capture, AudioCapture, OggEncoder};
+use crate::audio::{
+    create_capture, is_wav_path, repair_wav, AudioCapture, AudioFormat, FlacEncoder, OggEncoder,
+};
 use crate::config::Settings;
 use crate::daemon::ipc::{DaemonRequest, DaemonResponse};
 use crate::daemon::server::{CommandReceiver, IpcServer};
 use crate::daemon::state::{
-    new_shared_state, ActiveRecording, DaemonState, SharedState, TranscriptionState,
+    new_privacy_flag, new_shared_jobs, new_shared_state, ActiveRecording, DaemonState, PrivacyFlag,
+    RunningJob, SharedJobs, SharedState, TranscriptionState,
+};
+use crate::llm::{build_provider, cloud_allowed, generate_title, suggest_tags, EMBED_BATCH_SIZE};
+use crate::storage::{
+    retention, snapshot, trash, Database, Recording, RecordingState, Sensitivity, TranscriptSegment,
+};
+use crate::transcription::{
+    is_transient, CheckpointCallback, ModelCache, ProgressCallback, TranscriptionPipeline,
 };
-use crate::storage::{Database, Recording, RecordingState};
-use crate::transcription::TranscriptionPipeline;
+
+/// How often the daemon checks on an active capture (device changes, chunk rotation)
+const CAPTURE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often the transcription worker purges recordings left in the trash too
+/// long and applies the retention policy
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 /// Run the daemon service
 pub async fn run(settings: &Settings) -> Result<()> {
@@ -29,6 +46,8 @@ pub async fn run(settings: &Settings) -> Result<()> {
 
     // Initialize shared state
     let state = new_shared_state();
+    let jobs = new_shared_jobs();
+    let privacy = new_privacy_flag();
 
     // Create command channel
     let (cmd_tx, cmd_rx) = mpsc::channel::<(DaemonRequest, mpsc::Sender<DaemonResponse>)>(32);
@@ -46,13 +65,22 @@ pub async fn run(settings: &Settings) -> Result<()> {
 
     // Spawn transcription worker
     let transcription_state = state.clone();
+    let transcription_jobs = jobs.clone();
     let transcription_settings = settings.clone();
+    let transcription_privacy = privacy.clone();
     let transcription_handle = tokio::spawn(async move {
-        transcription_worker(transcription_settings, transcription_state).await;
+        transcription_worker(
+            transcription_settings,
+            transcription_state,
+            transcription_jobs,
+            transcription_privacy,
+        )
+        .await;
     });
 
     // Run command handler
-    let handler_result = command_handler(settings.clone(), state, cmd_rx).await;
+    let handler_result =
+        command_handler(settings.clone(), state, jobs.clone(), privacy, cmd_rx).await;
 
     // Cleanup
     info!("Shutting down daemon");
@@ -63,6 +91,9 @@ pub async fn run(settings: &Settings) -> Result<()> {
     // Abort spawned tasks
     server_handle.abort();
     transcription_handle.abort();
+    for job in jobs.read().await.iter() {
+        job.task.abort();
+    }
 
     handler_result
 }
@@ -71,30 +102,78 @@ pub async fn run(settings: &Settings) -> Result<()> {
 async fn command_handler(
     settings: Settings,
     state: SharedState,
+    jobs: SharedJobs,
+    privacy: PrivacyFlag,
     mut cmd_rx: CommandReceiver,
 ) -> Result<()> {
     let mut audio_capture: Option<Box<dyn AudioCapture>> = None;
+    let mut capture_check = tokio::time::interval(CAPTURE_CHECK_INTERVAL);
+
+    loop {
+        let (request, resp_tx) = tokio::select! {
+            received = cmd_rx.recv() => match received {
+                Some(received) => received,
+                None => break,
+            },
+            _ = capture_check.tick() => {
+                if let Some(capture) = audio_capture.as_mut() {
+                    if let Err(e) = capture.refresh_targets() {
+                        warn!("Failed to follow audio device change: {}", e);
+                    }
+                }
+                rotate_chunk_if_due(&settings, &state, &mut audio_capture).await;
+                continue;
+            }
+        };
 
-    while let Some((request, resp_tx)) = cmd_rx.recv().await {
         let response = match request {
-            DaemonRequest::StartRecording { title } => {
-                handle_start_recording(&settings, &state, &mut audio_capture, title).await
+            DaemonRequest::StartRecording {
+                title,
+                sensitivity,
+                participants,
+                language,
+            } => {
+                handle_start_recording(
+                    &settings,
+                    &state,
+                    &mut audio_capture,
+                    title,
+                    sensitivity,
+                    &participants,
+                    language,
+                )
+                .await
             }
             DaemonRequest::StopRecording => {
                 handle_stop_recording(&settings, &state, &mut audio_capture).await
             }
             DaemonRequest::GetStatus => {
                 let state = state.read().await;
-                DaemonResponse::Status(state.to_status())
+                DaemonResponse::Status(state.to_status(&jobs.read().await))
             }
             DaemonRequest::Ping => DaemonResponse::Pong,
             DaemonRequest::Shutdown => {
                 let _ = resp_tx.send(DaemonResponse::Ok).await;
                 break;
             }
-            DaemonRequest::Transcribe { recording_id } => {
-                handle_transcribe_request(&settings, &recording_id).await
+            DaemonRequest::Transcribe {
+                recording_id,
+                language,
+            } => handle_transcribe_request(&settings, &recording_id, language).await,
+            DaemonRequest::CancelTranscription { recording_id } => {
+                handle_cancel_transcription(&settings, &jobs, &recording_id).await
             }
+            DaemonRequest::SetPrivacyMode { enabled } => {
+                privacy.store(enabled, Ordering::SeqCst);
+                info!(
+                    "Privacy mode {}",
+                    if enabled { "enabled" } else { "disabled" }
+                );
+                DaemonResponse::PrivacyMode { enabled }
+            }
+            DaemonRequest::GetPrivacyMode => DaemonResponse::PrivacyMode {
+                enabled: privacy.load(Ordering::SeqCst),
+            },
         };
 
         let _ = resp_tx.send(response).await;
@@ -109,6 +188,9 @@ async fn handle_start_recording(
     state: &SharedState,
     audio_capture: &mut Option<Box<dyn AudioCapture>>,
     title: String,
+    sensitivity: Sensitivity,
+    participants: &[String],
+    language: Option<String>,
 ) -> DaemonResponse {
     let mut state_guard = state.write().await;
 
@@ -120,30 +202,24 @@ async fn handle_start_recording(
     }
 
     // Create new recording
-    let recording = Recording::new(title);
-    let audio_filename = format!("{}.wav", recording.id);
-    let audio_path = settings.audio_dir().join(&audio_filename);
+    let mut recording = Recording::new(title);
+    recording.sensitivity = sensitivity;
+    recording.language_override = language;
+    let segmented = settings.audio.segment_minutes > 0;
+    let first_chunk = segmented.then_some(1);
 
     // Initialize audio capture using factory (auto-detects backend)
-    match create_capture(settings) {
-        Ok(mut capture) => {
-            if let Err(e) = capture.start(&audio_path) {
-                return DaemonResponse::Error {
-                    message: format!("Failed to start audio capture: {}", e),
-                };
-            }
-            info!(
-                "Audio capture started with {} backend",
-                capture.backend_name()
-            );
+    let audio_path = match start_capture(settings, &recording.id, first_chunk) {
+        Ok((capture, audio_path)) => {
             *audio_capture = Some(capture);
+            audio_path
         }
         Err(e) => {
             return DaemonResponse::Error {
-                message: format!("Failed to initialize audio: {}", e),
+                message: format!("{:#}", e),
             };
         }
-    }
+    };
 
     // Save to database
     let db = match Database::open(settings) {
@@ -164,15 +240,31 @@ async fn handle_start_recording(
         };
     }
 
+    if !participants.is_empty() {
+        if let Err(e) = db.set_participants(&recording.id, participants) {
+            warn!("Failed to save participants: {}", e);
+        }
+    }
+
+    let mut chunks = Vec::new();
+    if segmented {
+        if let Err(e) = db.add_chunk(&recording.id, 0, &audio_path.to_string_lossy()) {
+            warn!("Failed to save recording chunk: {}", e);
+        }
+        chunks.push(audio_path.clone());
+    }
+
     let id = recording.id.clone();
 
     // Update state
-    *state_guard = DaemonState::Recording(ActiveRecording {
+    *state_guard = DaemonState::Recording(Box::new(ActiveRecording {
         recording,
         audio_path,
         started_at: Instant::now(),
         audio_level: 0.0,
-    });
+        chunks,
+        chunk_started_at: Instant::now(),
+    }));
 
     info!("Recording started: {}", id);
     DaemonResponse::RecordingStarted { id }
@@ -197,13 +289,22 @@ async fn handle_stop_recording(
 
     let id = active.recording.id.clone();
     let duration_secs = active.started_at.elapsed().as_secs();
-    let wav_path = active.audio_path.clone();
+    // A segmented recording is stored under its first chunk
+    let wav_path = active.chunks.first().unwrap_or(&active.audio_path).clone();
+    let segmented = !active.chunks.is_empty();
 
     // Stop audio capture
+    let mut track_paths = None;
+    let mut level_warnings = Vec::new();
     if let Some(ref mut capture) = audio_capture {
         if let Err(e) = capture.stop() {
             warn!("Error stopping audio capture: {}", e);
         }
+        level_warnings = capture.level_warnings();
+        // Tracks only cover the last chunk, so they are not kept when segmenting
+        if !segmented {
+            track_paths = capture.track_paths();
+        }
     }
     *audio_capture = None;
 
@@ -220,27 +321,150 @@ async fn handle_stop_recording(
     if let Ok(Some(mut recording)) = db.get_recording(&id) {
         recording.duration_secs = Some(duration_secs);
         recording.audio_path = Some(wav_path.to_string_lossy().to_string());
+        if let Some((system_path, mic_path)) = track_paths {
+            recording.system_audio_path = Some(system_path.to_string_lossy().to_string());
+            recording.mic_audio_path = Some(mic_path.to_string_lossy().to_string());
+        }
         recording.state = RecordingState::Pending;
         if let Err(e) = db.update_recording(&recording) {
             warn!("Failed to update recording: {}", e);
         }
     }
 
+    // Earlier chunks of a segmented recording stored their warnings already
+    let warnings = store_level_warnings(&db, &id, &level_warnings);
+
     // Update state to idle
     *state_guard = DaemonState::Idle;
 
     info!("Recording stopped: {} ({}s)", id, duration_secs);
-    DaemonResponse::RecordingStopped { id, duration_secs }
+    DaemonResponse::RecordingStopped {
+        id,
+        duration_secs,
+        warnings,
+    }
+}
+
+/// Log and store level warnings of a stopped capture
+///
+/// Returns every warning stored for the recording so far.
+fn store_level_warnings(db: &Database, recording_id: &str, warnings: &[String]) -> Vec<String> {
+    for warning in warnings {
+        warn!("Recording {}: {}", recording_id, warning);
+    }
+    if let Err(e) = db.add_warnings(recording_id, warnings) {
+        warn!("Failed to save recording warnings: {}", e);
+    }
+    db.get_warnings(recording_id)
+        .unwrap_or_else(|_| warnings.to_vec())
 }
 
-/// Compress WAV file to OGG Opus
-fn compress_to_ogg(settings: &Settings, wav_path: &Path) -> Result<PathBuf> {
-    let encoder = OggEncoder::new(
-        settings.audio.sample_rate,
-        settings.audio.channels as u8,
-        settings.audio.ogg_bitrate,
+/// Create and start a capture writing to `<id>.<ext>`, or `<id>.NNN.<ext>` for a chunk
+fn start_capture(
+    settings: &Settings,
+    recording_id: &str,
+    chunk: Option<usize>,
+) -> Result<(Box<dyn AudioCapture>, PathBuf)> {
+    let mut capture = create_capture(settings).context("Failed to initialize audio")?;
+    let audio_path = match chunk {
+        Some(index) => chunk_path(
+            &settings.audio_dir(),
+            recording_id,
+            index,
+            capture.output_extension(),
+        ),
+        None => {
+            settings
+                .audio_dir()
+                .join(format!("{}.{}", recording_id, capture.output_extension()))
+        }
+    };
+
+    capture
+        .start(&audio_path)
+        .context("Failed to start audio capture")?;
+    info!(
+        "Audio capture started with {} backend",
+        capture.backend_name()
     );
-    encoder.encode_and_cleanup(wav_path)
+    Ok((capture, audio_path))
+}
+
+/// Path of chunk `index` (1-based) of a segmented recording
+fn chunk_path(audio_dir: &Path, recording_id: &str, index: usize, extension: &str) -> PathBuf {
+    audio_dir.join(format!("{}.{:03}.{}", recording_id, index, extension))
+}
+
+/// Close the current chunk and continue in the next one once it is long enough
+///
+/// The capture is stopped before the next one starts so no audio is written
+/// twice, which leaves a short gap between chunks.
+async fn rotate_chunk_if_due(
+    settings: &Settings,
+    state: &SharedState,
+    audio_capture: &mut Option<Box<dyn AudioCapture>>,
+) {
+    let segment = Duration::from_secs(settings.audio.segment_minutes as u64 * 60);
+    let mut state_guard = state.write().await;
+    let DaemonState::Recording(active) = &mut *state_guard else {
+        return;
+    };
+    if active.chunks.is_empty() || active.chunk_started_at.elapsed() < segment {
+        return;
+    }
+
+    let id = active.recording.id.clone();
+
+    // Without a capture the previous attempt to start this chunk failed; retry
+    if let Some(mut capture) = audio_capture.take() {
+        if let Err(e) = capture.stop() {
+            warn!("Error stopping audio capture: {}", e);
+        }
+        let warnings = capture.level_warnings();
+        if !warnings.is_empty() {
+            match Database::open(settings) {
+                Ok(db) => {
+                    store_level_warnings(&db, &id, &warnings);
+                }
+                Err(e) => warn!("Database error: {}", e),
+            }
+        }
+    }
+
+    let index = active.chunks.len() + 1;
+    match start_capture(settings, &id, Some(index)) {
+        Ok((capture, path)) => {
+            *audio_capture = Some(capture);
+            match Database::open(settings) {
+                Ok(db) => {
+                    if let Err(e) = db.add_chunk(&id, index - 1, &path.to_string_lossy()) {
+                        warn!("Failed to save recording chunk: {}", e);
+                    }
+                }
+                Err(e) => warn!("Database error: {}", e),
+            }
+            info!("Recording {} continued in chunk {}", id, index);
+            active.audio_path = path.clone();
+            active.chunks.push(path);
+            active.chunk_started_at = Instant::now();
+        }
+        Err(e) => error!("Failed to start chunk {} of {}: {:#}", index, id, e),
+    }
+}
+
+/// Compress WAV file to the configured storage format (OGG Opus or FLAC)
+fn compress_audio(settings: &Settings, wav_path: &Path) -> Result<PathBuf> {
+    match settings.audio.format {
+        AudioFormat::Ogg => {
+            let encoder = OggEncoder::new(
+                settings.audio.sample_rate,
+                settings.audio.channels as u8,
+                settings.audio.ogg_bitrate,
+            );
+            encoder.encode_and_cleanup(wav_path)
+        }
+        AudioFormat::Flac => FlacEncoder.encode_and_cleanup(wav_path),
+    }
 }
 
 fn should_compress_after_transcription(enabled: bool, audio_path: &std::path::Path) -> bool {
@@ -263,18 +487,78 @@ fn maybe_compress_transcribed_audio(
     }
 
     let wav_path = audio_path.to_path_buf();
-    let ogg_path = compress_to_ogg(settings, &wav_path)?;
+    let compressed_path = compress_audio(settings, &wav_path)?;
 
     if let Some(mut recording) = db.get_recording(recording_id)? {
-        recording.audio_path = Some(ogg_path.to_string_lossy().to_string());
+        recording.audio_path = Some(compressed_path.to_string_lossy().to_string());
+        recording.system_audio_path =
+            compress_track(settings, recording_id, recording.system_audio_path.take());
+        recording.mic_audio_path =
+            compress_track(settings, recording_id, recording.mic_audio_path.take());
         db.update_recording(&recording)?;
     }
 
     Ok(())
 }
 
+/// Compress every chunk of a segmented recording after transcription
+///
+/// The recording keeps pointing at the first chunk under its new name.
+fn compress_chunks(settings: &Settings, db: &Database, recording_id: &str, chunks: Vec<String>) {
+    if !settings.audio.compress_to_ogg {
+        return;
+    }
+
+    let chunks: Vec<String> = chunks
+        .into_iter()
+        .filter_map(|chunk| compress_track(settings, recording_id, Some(chunk)))
+        .collect();
+
+    let result = db.set_chunks(recording_id, &chunks).and_then(|_| {
+        if let Some(mut recording) = db.get_recording(recording_id)? {
+            recording.audio_path = chunks.first().cloned();
+            db.update_recording(&recording)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!(
+            "Failed to update chunks of {} after compression: {}",
+            recording_id, e
+        );
+    }
+}
+
+/// Compress a separate track or chunk, keeping the original path if compression fails
+fn compress_track(
+    settings: &Settings,
+    recording_id: &str,
+    track_path: Option<String>,
+) -> Option<String> {
+    let track_path = track_path?;
+    let path = Path::new(&track_path);
+    if !should_compress_after_transcription(true, path) || !path.exists() {
+        return Some(track_path);
+    }
+
+    match compress_audio(settings, path) {
+        Ok(compressed_path) => Some(compressed_path.to_string_lossy().to_string()),
+        Err(e) => {
+            warn!(
+                "Failed to compress track {} for {}: {}",
+                track_path, recording_id, e
+            );
+            Some(track_path)
+        }
+    }
+}
+
 /// Handle transcription request
-async fn handle_transcribe_request(settings: &Settings, recording_id: &str) -> DaemonResponse {
+async fn handle_transcribe_request(
+    settings: &Settings,
+    recording_id: &str,
+    language: Option<String>,
+) -> DaemonResponse {
     let db = match Database::open(settings) {
         Ok(db) => db,
         Err(e) => {
@@ -285,9 +569,35 @@ async fn handle_transcribe_request(settings: &Settings, recording_id: &str) -> D
     };
 
     match db.find_recording_by_prefix(recording_id) {
+        Ok(Some(recording)) if recording.state == RecordingState::Recording => {
+            DaemonResponse::Error {
+                message: "Recording is still in progress".to_string(),
+            }
+        }
+        Ok(Some(recording)) if recording.state == RecordingState::Transcribing => {
+            DaemonResponse::Error {
+                message: "Recording is already being transcribed".to_string(),
+            }
+        }
+        Ok(Some(recording))
+            if !recording
+                .audio_path
+                .as_deref()
+                .is_some_and(|path| Path::new(path).exists()) =>
+        {
+            DaemonResponse::Error {
+                message: recording.missing_audio_message(),
+            }
+        }
         Ok(Some(mut recording)) => {
             recording.state = RecordingState::Pending;
-            if let Err(e) = db.update_recording(&recording) {
+            if language.is_some() {
+                recording.language_override = language;
+            }
+            if let Err(e) = db
+                .update_recording(&recording)
+                .and_then(|_| db.queue_job(&recording.id))
+            {
                 return DaemonResponse::Error {
                     message: format!("Failed to queue transcription: {}", e),
                 };
@@ -303,22 +613,38 @@ async fn handle_transcribe_request(settings: &Settings, recording_id: &str) -> D
     }
 }
 
-/// Background worker that processes pending transcriptions
-async fn transcription_worker(settings: Settings, state: SharedState) {
+/// Background worker that runs queued transcription jobs
+///
+/// Up to `transcription.max_jobs` jobs run at once. New jobs are not started
+/// while recording, but running ones finish.
+async fn transcription_worker(
+    settings: Settings,
+    state: SharedState,
+    jobs: SharedJobs,
+    privacy: PrivacyFlag,
+) {
     let check_interval = std::time::Duration::from_secs(5);
+    // The Whisper model stays loaded across queue items
+    let models = Arc::new(Mutex::new(ModelCache::new()));
+    let max_jobs = settings.transcription.max_jobs.max(1);
+    let newest_first = settings.transcription.newest_first();
+
+    // Transcriptions cut short by a restart continue from their checkpoints
+    match Database::open(&settings).and_then(|db| db.requeue_interrupted_transcriptions()) {
+        Ok(0) => {}
+        Ok(queued) => info!("Resuming {} interrupted transcription(s)", queued),
+        Err(e) => error!("Failed to queue interrupted transcriptions: {}", e),
+    }
+    let mut last_purge: Option<Instant> = None;
 
     loop {
         tokio::time::sleep(check_interval).await;
 
-        // Skip if currently recording or transcribing
-        {
-            let state_guard = state.read().await;
-            if !matches!(*state_guard, DaemonState::Idle) {
-                continue;
-            }
+        // Skip if currently recording
+        if matches!(*state.read().await, DaemonState::Recording(_)) {
+            continue;
         }
 
-        // Check for pending recordings
         let db = match Database::open(&settings) {
             Ok(db) => db,
             Err(e) => {
@@ -327,48 +653,222 @@ async fn transcription_worker(settings: Settings, state: SharedState) {
             }
         };
 
-        let pending = match db.get_pending_recordings() {
-            Ok(p) => p,
+        if last_purge.is_none_or(|at| at.elapsed() >= PURGE_INTERVAL) {
+            if let Err(e) = trash::purge_expired(&settings, &db) {
+                error!("Failed to purge the trash: {}", e);
+            }
+            if let Err(e) = retention::purge_expired(&settings, &db) {
+                error!("Failed to apply the retention policy: {}", e);
+            }
+            last_purge = Some(Instant::now());
+        }
+
+        // Recordings stopped or imported since the last check join the queue
+        if let Err(e) = db.queue_pending_recordings() {
+            error!("Failed to queue pending recordings: {}", e);
+            continue;
+        }
+
+        while jobs.read().await.len() < max_jobs {
+            let recording = match db.start_next_job(newest_first) {
+                Ok(Some(recording)) => recording,
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Failed to start the next transcription job: {}", e);
+                    break;
+                }
+            };
+            start_job(&settings, recording, &jobs, &models, &privacy).await;
+        }
+    }
+}
+
+/// Run a transcription job in its own task, tracked in `jobs` until it ends
+async fn start_job(
+    settings: &Settings,
+    recording: Recording,
+    jobs: &SharedJobs,
+    models: &Arc<Mutex<ModelCache>>,
+    privacy: &PrivacyFlag,
+) {
+    info!("Starting transcription for: {}", recording.id);
+
+    // Held until the job is listed, so a job that ends at once can't be
+    // removed before it was added
+    let mut running = jobs.write().await;
+    let transcription =
+        TranscriptionState::new(recording.id.clone(), recording.duration_secs.unwrap_or(0));
+
+    let (settings, task_jobs, models, privacy) = (
+        settings.clone(),
+        jobs.clone(),
+        models.clone(),
+        privacy.clone(),
+    );
+    let task = tokio::spawn(async move {
+        let result = run_transcription(&settings, &recording, &task_jobs, &models, &privacy).await;
+        task_jobs
+            .write()
+            .await
+            .retain(|job| job.state.recording_id != recording.id);
+
+        let db = match Database::open(&settings) {
+            Ok(db) => db,
             Err(e) => {
-                error!("Failed to get pending recordings: {}", e);
-                continue;
+                error!("Database error after transcribing {}: {}", recording.id, e);
+                return;
             }
         };
+        if let Err(e) = settle_job(&settings, &db, &recording.id, result) {
+            error!("Failed to record the outcome for {}: {}", recording.id, e);
+        }
+    });
 
-        for recording in pending {
-            // Update state
-            {
-                let mut state_guard = state.write().await;
-                *state_guard = DaemonState::Transcribing(TranscriptionState {
-                    recording_id: recording.id.clone(),
-                    progress: 0.0,
-                });
-            }
+    running.push(RunningJob {
+        state: transcription,
+        task: task.abort_handle(),
+    });
+}
 
-            info!("Starting transcription for: {}", recording.id);
+/// Record how a transcription job ended
+///
+/// Failures that may pass, such as a missing model or an unreachable
+/// provider, are queued again with a growing delay until
+/// `transcription.max_attempts` is used up; the rest fail right away.
+fn settle_job(
+    settings: &Settings,
+    db: &Database,
+    recording_id: &str,
+    result: Result<()>,
+) -> Result<()> {
+    let error = match result {
+        Ok(()) => {
+            info!("Transcription completed: {}", recording_id);
+            db.clear_failure(recording_id)?;
+            return db.finish_job(recording_id);
+        }
+        Err(error) => error,
+    };
 
-            // Run transcription
-            let result = run_transcription(&settings, &recording, &state).await;
+    let reason = format!("{:#}", error);
+    let attempts = db.get_job(recording_id)?.map_or(1, |job| job.attempts);
+    let retry = if is_transient(&error) {
+        settings.transcription.retry_delay(attempts)
+    } else {
+        None
+    };
 
-            // Update state back to idle
-            {
-                let mut state_guard = state.write().await;
-                *state_guard = DaemonState::Idle;
-            }
+    match retry {
+        Some(delay) => {
+            warn!(
+                "Transcription of {} failed on attempt {}, retrying in {}s: {}",
+                recording_id,
+                attempts,
+                delay.as_secs(),
+                reason
+            );
+            db.record_failure(recording_id, RecordingState::Pending, &reason)?;
+            db.retry_job(
+                recording_id,
+                chrono::Utc::now() + chrono::Duration::from_std(delay)?,
+            )
+        }
+        None => {
+            error!("Transcription failed for {}: {}", recording_id, reason);
+            db.record_failure(recording_id, RecordingState::Failed, &reason)?;
+            db.finish_job(recording_id)
+        }
+    }
+}
 
-            match result {
-                Ok(_) => {
-                    info!("Transcription completed: {}", recording.id);
-                }
-                Err(e) => {
-                    error!("Transcription failed for {}: {}", recording.id, e);
-                    // Mark as failed
-                    if let Err(e) = db.update_recording_state(&recording.id, RecordingState::Failed)
-                    {
-                        error!("Failed to update recording state: {}", e);
-                    }
-                }
-            }
+/// Handle a request to cancel a queued or running transcription
+///
+/// A running job is stopped; its saved progress is kept, so transcribing
+/// the recording again continues where it stopped.
+async fn handle_cancel_transcription(
+    settings: &Settings,
+    jobs: &SharedJobs,
+    recording_id: &str,
+) -> DaemonResponse {
+    let db = match Database::open(settings) {
+        Ok(db) => db,
+        Err(e) => {
+            return DaemonResponse::Error {
+                message: format!("Database error: {}", e),
+            };
+        }
+    };
+
+    let recording = match db.find_recording_by_prefix(recording_id) {
+        Ok(Some(recording)) => recording,
+        Ok(None) => {
+            return DaemonResponse::Error {
+                message: "Recording not found".to_string(),
+            };
+        }
+        Err(e) => {
+            return DaemonResponse::Error {
+                message: format!("Database error: {}", e),
+            };
+        }
+    };
+
+    {
+        let mut running = jobs.write().await;
+        if let Some(index) = running
+            .iter()
+            .position(|job| job.state.recording_id == recording.id)
+        {
+            running.remove(index).task.abort();
+            info!("Cancelled running transcription of {}", recording.id);
+        }
+    }
+
+    let cancelled = db
+        .queue_pending_recordings()
+        .and_then(|_| db.cancel_job(&recording.id));
+    match cancelled {
+        Ok(true) => DaemonResponse::Ok,
+        Ok(false) => DaemonResponse::Error {
+            message: "Recording is not queued for transcription".to_string(),
+        },
+        Err(e) => DaemonResponse::Error {
+            message: format!("Failed to cancel transcription: {}", e),
+        },
+    }
+}
+
+/// Separate track paths to transcribe individually, if enabled and present
+fn per_track_paths<'a>(
+    settings: &Settings,
+    recording: &'a Recording,
+) -> Option<(&'a str, &'a str)> {
+    if !settings.whisper.per_track {
+        return None;
+    }
+
+    let system_path = recording.system_audio_path.as_deref()?;
+    let mic_path = recording.mic_audio_path.as_deref()?;
+    if Path::new(system_path).exists() && Path::new(mic_path).exists() {
+        Some((system_path, mic_path))
+    } else {
+        None
+    }
+}
+
+/// Rebuild the headers of WAV files that were never finalized
+fn repair_interrupted_audio<'a>(recording_id: &str, paths: impl Iterator<Item = &'a str>) {
+    for path in paths.map(Path::new) {
+        if !is_wav_path(path) || !path.exists() {
+            continue;
+        }
+        if let Err(e) = repair_wav(path) {
+            warn!(
+                "Failed to repair {} of {}: {}",
+                path.display(),
+                recording_id,
+                e
+            );
         }
     }
 }
@@ -377,7 +877,9 @@ async fn transcription_worker(settings: Settings, state: SharedState) {
 async fn run_transcription(
     settings: &Settings,
     recording: &Recording,
-    state: &SharedState,
+    jobs: &SharedJobs,
+    models: &Mutex<ModelCache>,
+    privacy: &PrivacyFlag,
 ) -> Result<()> {
     let db = Database::open(settings)?;
 
@@ -391,35 +893,114 @@ async fn run_transcription(
         .ok_or_else(|| anyhow::anyhow!("No audio path"))?
         .to_string();
 
-    // Run transcription
-    let pipeline = TranscriptionPipeline::new(settings)?;
+    // A crash during capture leaves WAV headers that decoders reject
+    let chunks = db.get_chunks(&recording.id)?;
+    repair_interrupted_audio(
+        &recording.id,
+        recording
+            .audio_files()
+            .chain(chunks.iter().map(String::as_str)),
+    );
+
+    // Run transcription, saving progress after every window
+    let checkpoint_db = Mutex::new(Database::open(settings)?);
+    let checkpoint_id = recording.id.clone();
+    let save_checkpoint: CheckpointCallback = Box::new(move |checkpoint| {
+        let db = checkpoint_db.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = db.save_checkpoint(&checkpoint_id, checkpoint) {
+            warn!(
+                "Failed to save transcription progress of {}: {}",
+                checkpoint_id, e
+            );
+        }
+    });
+    let pipeline = {
+        let mut models = models.lock().unwrap_or_else(|e| e.into_inner());
+        TranscriptionPipeline::for_recording(settings, &mut models, recording)?
+    }
+    .with_speaker_profiles(db.list_speaker_profiles()?)
+    .with_checkpoints(db.get_checkpoints(&recording.id)?, save_checkpoint);
 
-    let progress_state = state.clone();
+    let progress_jobs = jobs.clone();
     let recording_id = recording.id.clone();
+    let progress_callback: ProgressCallback = Box::new(move |progress| {
+        let jobs = progress_jobs.clone();
+        let id = recording_id.clone();
+        tokio::spawn(async move {
+            let mut running = jobs.write().await;
+            if let Some(job) = running.iter_mut().find(|job| job.state.recording_id == id) {
+                job.state.progress = progress;
+            }
+        });
+    });
 
-    let segments = pipeline
-        .transcribe(
-            &audio_path,
-            &recording.id,
-            Box::new(move |progress| {
-                let state = progress_state.clone();
-                let _id = recording_id.clone();
-                tokio::spawn(async move {
-                    let mut state_guard = state.write().await;
-                    if let DaemonState::Transcribing(ref mut ts) = *state_guard {
-                        ts.progress = progress;
-                    }
-                });
-            }),
-        )
-        .await?;
+    let mut segments = match per_track_paths(settings, recording) {
+        Some((system_path, mic_path)) => {
+            info!("Transcribing {} per track", recording.id);
+            pipeline
+                .transcribe_tracks(system_path, mic_path, &recording.id, progress_callback)
+                .await?
+        }
+        None if chunks.len() > 1 => {
+            info!("Stitching {} chunks of {}", chunks.len(), recording.id);
+            pipeline
+                .transcribe_chunks(&chunks, &recording.id, progress_callback)
+                .await?
+        }
+        None => {
+            pipeline
+                .transcribe(&audio_path, &recording.id, progress_callback)
+                .await?
+        }
+    };
 
-    // Save segments
-    db.insert_segments(&segments)?;
+    // Save segments, replacing those of an earlier transcription
+    db.apply_speaker_names(&recording.id, &mut segments)?;
+    if !db.get_transcript_segments(&recording.id)?.is_empty() {
+        snapshot::take(settings, &db, "transcribe", &[&recording.id])?;
+    }
+    db.replace_segments(&recording.id, &segments)?;
+    db.replace_silences(&recording.id, &pipeline.silences())?;
+    db.clear_checkpoints(&recording.id)?;
+    if let Some(language) = pipeline.language() {
+        db.set_recording_language(&recording.id, Some(&language))?;
+    }
 
     // Mark as completed
     db.update_recording_state(&recording.id, RecordingState::Completed)?;
 
+    let private = privacy.load(Ordering::SeqCst);
+    let hooks = TranscriptHooks::enabled(settings, private);
+    if private && TranscriptHooks::enabled(settings, false) != hooks {
+        info!(
+            "Privacy mode is on; not titling, tagging or embedding {}",
+            recording.id
+        );
+    }
+    if hooks.title {
+        if let Err(e) = auto_title(settings, &recording.id, &segments).await {
+            warn!("Failed to generate a title for {}: {}", recording.id, e);
+        }
+    }
+    if hooks.tags {
+        if let Err(e) = auto_suggest_tags(settings, &recording.id, &segments).await {
+            warn!("Failed to suggest tags for {}: {}", recording.id, e);
+        }
+    }
+    if hooks.embed {
+        if let Err(e) = auto_embed(settings, &recording.id).await {
+            warn!(
+                "Failed to embed {} for semantic search: {}",
+                recording.id, e
+            );
+        }
+    }
+
+    if !chunks.is_empty() {
+        compress_chunks(settings, &db, &recording.id, chunks);
+        return Ok(());
+    }
+
     let audio_path = std::path::Path::new(&audio_path);
     if let Err(e) = maybe_compress_transcribed_audio(settings, &db, &recording.id, audio_path) {
         warn!(
@@ -431,11 +1012,161 @@ async fn run_transcription(
     Ok(())
 }
 
+/// LLM work on a new transcript, run once transcription completes
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TranscriptHooks {
+    title: bool,
+    tags: bool,
+    embed: bool,
+}
+
+impl TranscriptHooks {
+    /// Hooks turned on in the settings; none while privacy mode is on
+    fn enabled(settings: &Settings, privacy: bool) -> Self {
+        if privacy {
+            return Self::default();
+        }
+        Self {
+            title: settings.llm.auto_title,
+            tags: settings.llm.suggest_tags,
+            embed: settings.llm.auto_embed,
+        }
+    }
+}
+
+/// Replace the default title of a transcribed recording with a generated one
+///
+/// Recordings given a title, and confidential recordings when the LLM
+/// provider is a cloud service, are left alone.
+async fn auto_title(
+    settings: &Settings,
+    recording_id: &str,
+    segments: &[TranscriptSegment],
+) -> Result<()> {
+    let db = Database::open(settings)?;
+    // Read the title again, it may have been changed while transcribing
+    let Some(recording) = db.get_recording(recording_id)? else {
+        return Ok(());
+    };
+    if !recording.has_default_title() || segments.is_empty() {
+        return Ok(());
+    }
+
+    let provider = build_provider(settings)?;
+    if !cloud_allowed(&recording, provider.as_ref(), false) {
+        return Ok(());
+    }
+
+    let transcript = segments
+        .iter()
+        .map(TranscriptSegment::labelled_text)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let title = generate_title(provider.as_ref(), &transcript).await?;
+    db.set_recording_title(recording_id, &title)?;
+    info!("Titled {} \"{}\"", recording_id, title);
+    Ok(())
+}
+
+/// Store tags suggested for a transcribed recording, to be accepted with `minutes tags`
+///
+/// Like titles, confidential recordings are skipped when the LLM provider is
+/// a cloud service.
+async fn auto_suggest_tags(
+    settings: &Settings,
+    recording_id: &str,
+    segments: &[TranscriptSegment],
+) -> Result<()> {
+    let db = Database::open(settings)?;
+    let Some(recording) = db.get_recording(recording_id)? else {
+        return Ok(());
+    };
+    if segments.is_empty() {
+        return Ok(());
+    }
+
+    let provider = build_provider(settings)?;
+    if !cloud_allowed(&recording, provider.as_ref(), false) {
+        return Ok(());
+    }
+
+    let transcript = segments
+        .iter()
+        .map(TranscriptSegment::labelled_text)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let existing = db.list_tags()?;
+    let tags: Vec<String> =
+        suggest_tags(provider.as_ref(), &recording.title, &existing, &transcript)
+            .await?
+            .into_iter()
+            .filter(|tag| !recording.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            .collect();
+    db.set_suggested_tags(recording_id, &tags)?;
+    info!("Suggested tags for {}: {}", recording_id, tags.join(", "));
+    Ok(())
+}
+
+/// Embed the segments of a transcribed recording for semantic search
+///
+/// Like titles, confidential recordings are skipped when the LLM provider is
+/// a cloud service; they are embedded by a search that includes them.
+async fn auto_embed(settings: &Settings, recording_id: &str) -> Result<()> {
+    let db = Database::open(settings)?;
+    let Some(recording) = db.get_recording(recording_id)? else {
+        return Ok(());
+    };
+
+    let provider = build_provider(settings)?;
+    if !cloud_allowed(&recording, provider.as_ref(), false) {
+        return Ok(());
+    }
+    let Some(model) = provider.embedding_model().map(str::to_string) else {
+        return Ok(());
+    };
+
+    let segments = db.get_transcript_segments(recording_id)?;
+    for batch in segments.chunks(EMBED_BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|segment| segment.text.clone()).collect();
+        let vectors = provider.embed(&texts).await?;
+        let embeddings: Vec<(&TranscriptSegment, Vec<f32>)> = batch.iter().zip(vectors).collect();
+        db.insert_segment_embeddings(&model, &embeddings)?;
+    }
+    info!(
+        "Embedded {} segment(s) of {} with {}",
+        segments.len(),
+        recording_id,
+        model
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::Path;
 
+    #[test]
+    fn privacy_mode_pauses_transcript_hooks() {
+        let mut settings = Settings::default();
+        settings.llm.auto_title = true;
+        settings.llm.suggest_tags = true;
+        settings.llm.auto_embed = true;
+
+        assert_eq!(
+            TranscriptHooks::enabled(&settings, false),
+            TranscriptHooks {
+                title: true,
+                tags: true,
+                embed: true,
+            }
+        );
+        assert_eq!(
+            TranscriptHooks::enabled(&settings, true),
+            TranscriptHooks::default()
+        );
+    }
+
     #[test]
     fn compresses_only_wav_when_enabled() {
         assert!(should_compress_after_transcription(
@@ -451,4 +1182,17 @@ mod tests {
             Path::new("meeting.wav")
         ));
     }
+
+    #[test]
+    fn chunk_paths_are_numbered_from_one() {
+        let dir = Path::new("/data/audio");
+        assert_eq!(
+            chunk_path(dir, "abc", 1, "wav"),
+            Path::new("/data/audio/abc.001.wav")
+        );
+        assert_eq!(
+            chunk_path(dir, "abc", 12, "ogg"),
+            Path::new("/data/audio/abc.012.ogg")
+        );
+    }
 }
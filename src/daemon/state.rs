@@ -1,9 +1,11 @@
 //! Recording state machine for the daemon
 
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::RwLock;
+use tokio::task::AbortHandle;
 
 use crate::daemon::ipc::RecordingStatus;
 use crate::storage::Recording;
@@ -15,10 +17,7 @@ pub enum DaemonState {
     Idle,
 
     /// Actively recording
-    Recording(ActiveRecording),
-
-    /// Transcribing a recording
-    Transcribing(TranscriptionState),
+    Recording(Box<ActiveRecording>),
 }
 
 /// State of an active recording
@@ -33,6 +32,12 @@ pub struct ActiveRecording {
     /// When recording started
     pub started_at: Instant,
 
+    /// Chunk files written so far, when `audio.segment_minutes` is set
+    pub chunks: Vec<PathBuf>,
+
+    /// When the chunk currently being written was started
+    pub chunk_started_at: Instant,
+
     /// Current audio level (0.0 - 1.0)
     pub audio_level: f32,
 }
@@ -45,13 +50,69 @@ pub struct TranscriptionState {
 
     /// Progress (0.0 - 1.0)
     pub progress: f32,
+
+    /// Length of the recording in seconds (0 = unknown)
+    pub total_secs: u64,
+
+    /// When transcription started
+    pub started_at: Instant,
+}
+
+impl TranscriptionState {
+    pub fn new(recording_id: String, total_secs: u64) -> Self {
+        Self {
+            recording_id,
+            progress: 0.0,
+            total_secs,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Seconds left at the rate transcription has run so far
+    ///
+    /// `None` until there is enough progress to extrapolate from.
+    pub fn eta_secs(&self) -> Option<u64> {
+        eta_secs(self.progress, self.started_at.elapsed().as_secs_f64())
+    }
+
+    /// Progress for IPC
+    pub fn to_status(&self) -> RecordingStatus {
+        RecordingStatus::Transcribing {
+            id: self.recording_id.clone(),
+            progress: self.progress,
+            processed_secs: (self.total_secs as f64 * f64::from(self.progress)) as u64,
+            total_secs: self.total_secs,
+            eta_secs: self.eta_secs(),
+        }
+    }
+}
+
+fn eta_secs(progress: f32, elapsed_secs: f64) -> Option<u64> {
+    if !(0.01..1.0).contains(&progress) {
+        return None;
+    }
+    let progress = f64::from(progress);
+    Some((elapsed_secs * (1.0 - progress) / progress).round() as u64)
+}
+
+/// A transcription job running in the background
+#[derive(Debug)]
+pub struct RunningJob {
+    pub state: TranscriptionState,
+
+    /// Handle to stop the job's task when it is cancelled
+    pub task: AbortHandle,
 }
 
 impl DaemonState {
     /// Get the recording status for IPC
-    pub fn to_status(&self) -> RecordingStatus {
+    ///
+    /// While not recording, the first running transcription is reported.
+    pub fn to_status(&self, jobs: &[RunningJob]) -> RecordingStatus {
         match self {
-            DaemonState::Idle => RecordingStatus::Idle,
+            DaemonState::Idle => jobs
+                .first()
+                .map_or(RecordingStatus::Idle, |job| job.state.to_status()),
             DaemonState::Recording(active) => {
                 let duration = active.started_at.elapsed().as_secs();
                 RecordingStatus::Recording {
@@ -61,10 +122,6 @@ impl DaemonState {
                     audio_level: active.audio_level,
                 }
             }
-            DaemonState::Transcribing(state) => RecordingStatus::Transcribing {
-                id: state.recording_id.clone(),
-                progress: state.progress,
-            },
         }
     }
 }
@@ -76,3 +133,40 @@ pub type SharedState = Arc<RwLock<DaemonState>>;
 pub fn new_shared_state() -> SharedState {
     Arc::new(RwLock::new(DaemonState::Idle))
 }
+
+/// Transcriptions running in the background, in the order they started
+///
+/// Kept apart from [`DaemonState`] so transcriptions continue, and keep
+/// reporting progress, while a new recording is made.
+pub type SharedJobs = Arc<RwLock<Vec<RunningJob>>>;
+
+/// Create an empty list of running jobs
+pub fn new_shared_jobs() -> SharedJobs {
+    Arc::new(RwLock::new(Vec::new()))
+}
+
+/// Daemon-wide privacy switch
+///
+/// While set, new transcripts are not sent to the LLM in the background:
+/// `llm.auto_title`, `llm.suggest_tags` and `llm.auto_embed` are skipped for
+/// recordings transcribed meanwhile. The flag is held in memory only and
+/// resets when the daemon restarts.
+pub type PrivacyFlag = Arc<AtomicBool>;
+
+/// Create a new privacy flag (disabled)
+pub fn new_privacy_flag() -> PrivacyFlag {
+    Arc::new(AtomicBool::new(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eta_extrapolates_from_progress_so_far() {
+        assert_eq!(eta_secs(0.0, 5.0), None);
+        assert_eq!(eta_secs(0.25, 60.0), Some(180));
+        assert_eq!(eta_secs(0.5, 60.0), Some(60));
+        assert_eq!(eta_secs(1.0, 60.0), None);
+    }
+}
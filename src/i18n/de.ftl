@@ -0,0 +1,71 @@
+# German messages
+
+## Recording commands
+
+recording-started = Aufnahme gestartet: { $title } ({ $id })
+recording-start-failed = Aufnahme konnte nicht gestartet werden: { $message }
+recording-stopped = Aufnahme beendet: { $id } (Dauer: { $duration })
+recording-stop-failed = Aufnahme konnte nicht beendet werden: { $message }
+recording-warning = Warnung: { $warning }
+transcription-queued = Transkription eingereiht...
+
+## Status
+
+status-idle = Status: Bereit (keine Aufnahme)
+status-recording = Status: Aufnahme läuft
+status-transcribing = Status: Transkription läuft
+status-title = Titel: { $title }
+status-id = ID: { $id }
+status-duration = Dauer: { $duration }
+status-progress = Fortschritt: { $progress } %
+status-transcribed = Transkribiert: { $processed } von { $total }
+status-eta = Verbleibend: etwa { $eta }
+status-watch-idle = Bereit
+status-watch-recording = Aufnahme { $id } { $duration }
+status-watch-transcribing = Transkription { $id }
+status-watch-eta = noch { $eta }
+status-failed = Status konnte nicht abgefragt werden: { $message }
+
+## Daemon
+
+daemon-not-running = Der Daemon läuft nicht.
+daemon-start-hint = Starten mit: minutes daemon start
+daemon-connect-failed = Keine Verbindung zum Daemon unter { $path }. Läuft der Daemon? Versuche: minutes daemon start
+daemon-unexpected-response = Unerwartete Antwort vom Daemon
+
+## TUI dashboard
+
+tui-status = Status
+tui-not-recording = Keine Aufnahme
+tui-recording = Aufnahme
+tui-transcribing = Transkription läuft...
+tui-title = Titel
+tui-duration = Dauer
+tui-audio = Audio
+tui-progress = Fortschritt
+tui-press-to-start = [r] drücken, um die Aufnahme zu starten
+tui-press-to-stop = [r] drücken, um die Aufnahme zu beenden
+
+## TUI help bars
+
+hint-record = Aufnehmen
+hint-record-meeting = Termin aufnehmen
+hint-list = Liste
+hint-help = Hilfe
+hint-quit = Beenden
+hint-navigate = Navigieren
+hint-view = Anzeigen
+hint-search = Suchen
+hint-dashboard = Übersicht
+hint-back = Zurück
+hint-scroll = Blättern
+hint-page = Seite
+hint-top-bottom = Anfang/Ende
+hint-name-speaker = Sprecher benennen
+hint-talk-time = Redeanteile
+hint-accept-tags = Tags übernehmen
+hint-check-again = Erneut prüfen
+hint-settings = Einstellungen
+hint-continue = Weiter
+hint-change = Ändern
+hint-save = Speichern
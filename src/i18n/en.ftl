@@ -0,0 +1,71 @@
+# English messages; every other catalog translates these keys.
+
+## Recording commands
+
+recording-started = Recording started: { $title } ({ $id })
+recording-start-failed = Failed to start recording: { $message }
+recording-stopped = Recording stopped: { $id } (duration: { $duration })
+recording-stop-failed = Failed to stop recording: { $message }
+recording-warning = Warning: { $warning }
+transcription-queued = Transcription queued...
+
+## Status
+
+status-idle = Status: Idle (not recording)
+status-recording = Status: Recording
+status-transcribing = Status: Transcribing
+status-title = Title: { $title }
+status-id = ID: { $id }
+status-duration = Duration: { $duration }
+status-progress = Progress: { $progress }%
+status-transcribed = Transcribed: { $processed } of { $total }
+status-eta = Time remaining: about { $eta }
+status-watch-idle = Idle
+status-watch-recording = Recording { $id } { $duration }
+status-watch-transcribing = Transcribing { $id }
+status-watch-eta = ETA { $eta }
+status-failed = Failed to get status: { $message }
+
+## Daemon
+
+daemon-not-running = Daemon is not running.
+daemon-start-hint = Start it with: minutes daemon start
+daemon-connect-failed = Failed to connect to daemon at { $path }. Is the daemon running? Try: minutes daemon start
+daemon-unexpected-response = Unexpected response from daemon
+
+## TUI dashboard
+
+tui-status = Status
+tui-not-recording = Not Recording
+tui-recording = Recording
+tui-transcribing = Transcribing...
+tui-title = Title
+tui-duration = Duration
+tui-audio = Audio
+tui-progress = Progress
+tui-press-to-start = Press [r] to start recording
+tui-press-to-stop = Press [r] to stop recording
+
+## TUI help bars
+
+hint-record = Record
+hint-record-meeting = Record meeting
+hint-list = List
+hint-help = Help
+hint-quit = Quit
+hint-navigate = Navigate
+hint-view = View
+hint-search = Search
+hint-dashboard = Dashboard
+hint-back = Back
+hint-scroll = Scroll
+hint-page = Page
+hint-top-bottom = Top/Bottom
+hint-name-speaker = Name speaker
+hint-talk-time = Talk time
+hint-accept-tags = Accept tags
+hint-check-again = Check again
+hint-settings = Settings
+hint-continue = Continue
+hint-change = Change
+hint-save = Save
@@ -0,0 +1,182 @@
+//! Localized user-facing messages
+//!
+//! Messages live in Fluent-style catalogs (`<locale>.ftl` in this directory)
+//! compiled into the binary. Each line is `key = text`, where `{ $name }`
+//! is replaced by an argument. Keys missing from a translation fall back to
+//! English, so a partial catalog is still usable.
+//!
+//! The locale comes from `general.locale`, or from `LC_ALL`, `LC_MESSAGES`,
+//! or `LANG` when that is not set.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::config::Settings;
+
+const ENGLISH: &str = include_str!("en.ftl");
+const GERMAN: &str = include_str!("de.ftl");
+
+/// A supported language
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+impl Locale {
+    /// Parse a tag such as "de", "de-AT", or "de_DE.UTF-8"
+    ///
+    /// Returns `None` for languages without a catalog.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Self::English),
+            "de" => Some(Self::German),
+            _ => None,
+        }
+    }
+
+    /// Locale from the environment, English if unset or unsupported
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_tag(&value))
+            .unwrap_or_default()
+    }
+
+    /// Locale configured by `general.locale`, falling back to the environment
+    pub fn from_settings(settings: &Settings) -> Self {
+        match settings.general.locale.as_deref() {
+            Some(tag) => Self::from_tag(tag).unwrap_or_else(|| {
+                tracing::warn!("Unsupported locale '{}', using English", tag);
+                Self::English
+            }),
+            None => Self::from_env(),
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::English => ENGLISH,
+            Self::German => GERMAN,
+        }
+    }
+}
+
+/// Messages of one locale with English fallback
+#[derive(Debug)]
+pub struct Catalog {
+    messages: HashMap<&'static str, &'static str>,
+    fallback: HashMap<&'static str, &'static str>,
+}
+
+impl Catalog {
+    pub fn new(locale: Locale) -> Self {
+        Self {
+            messages: parse(locale.source()),
+            fallback: parse(ENGLISH),
+        }
+    }
+
+    /// Message for `key` with `args` filled in; the key itself if unknown
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        let Some(template) = self.messages.get(key).or_else(|| self.fallback.get(key)) else {
+            return key.to_string();
+        };
+
+        let mut message = template.to_string();
+        for (name, value) in args {
+            message = message.replace(&format!("{{ ${} }}", name), value);
+        }
+        message
+    }
+}
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// Select the locale for this process
+///
+/// Call once at startup; later calls are ignored. Messages formatted before
+/// this use the locale from the environment.
+pub fn init(settings: &Settings) {
+    let _ = CATALOG.set(Catalog::new(Locale::from_settings(settings)));
+}
+
+/// Format a message in the process locale
+///
+/// Usually called through the [`t!`](crate::t) macro.
+pub fn message(key: &str, args: &[(&str, String)]) -> String {
+    CATALOG
+        .get_or_init(|| Catalog::new(Locale::from_env()))
+        .format(key, args)
+}
+
+/// Format a localized message, e.g. `t!("recording-started", title = title)`
+#[macro_export]
+macro_rules! t {
+    ($key:literal) => {
+        $crate::i18n::message($key, &[])
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::message($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+/// Parse `key = text` lines, skipping blanks and `#` comments
+fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(key, text)| (key.trim(), text.trim()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(source: &'static str) -> Vec<&'static str> {
+        let mut keys: Vec<_> = parse(source).into_keys().collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    #[test]
+    fn parses_locale_tags() {
+        assert_eq!(Locale::from_tag("de_DE.UTF-8"), Some(Locale::German));
+        assert_eq!(Locale::from_tag("de-AT"), Some(Locale::German));
+        assert_eq!(Locale::from_tag("C"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("xx"), None);
+    }
+
+    #[test]
+    fn fills_arguments_and_falls_back_to_english() {
+        let catalog = Catalog::new(Locale::German);
+        assert_eq!(
+            catalog.format("status-duration", &[("duration", "1:05".to_string())]),
+            "Dauer: 1:05"
+        );
+        assert_eq!(catalog.format("no-such-key", &[]), "no-such-key");
+
+        let mut partial = Catalog::new(Locale::German);
+        partial.messages.remove("status-idle");
+        assert_eq!(
+            partial.format("status-idle", &[]),
+            "Status: Idle (not recording)"
+        );
+    }
+
+    #[test]
+    fn every_catalog_translates_every_english_message() {
+        assert_eq!(keys(GERMAN), keys(ENGLISH));
+    }
+}
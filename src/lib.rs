@@ -3,9 +3,11 @@
 //! "minutes" is a playful take on "minutes" (meeting notes)
 
 pub mod audio;
+pub mod calendar;
 pub mod cli;
 pub mod config;
 pub mod daemon;
+pub mod i18n;
 pub mod llm;
 pub mod storage;
 pub mod transcription;
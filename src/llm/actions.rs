@@ -0,0 +1,131 @@
+//! Action item extraction from transcripts
+//!
+//! The model is asked for a JSON array of `{owner, task, due}` objects. Long
+//! transcripts are split like for questions, and the items of all parts are
+//! joined, dropping tasks listed twice.
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::llm::answer::{split_transcript, MAX_PART_CHARS};
+use crate::llm::client::LlmProvider;
+use crate::llm::json::{complete_json, json_array, valid_items};
+use crate::llm::prompts::build_actions_prompt;
+
+/// An action item as returned by the model.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExtractedAction {
+    #[serde(default)]
+    pub owner: Option<String>,
+    pub task: String,
+    #[serde(default)]
+    pub due: Option<String>,
+}
+
+/// Extract the action items agreed on in a meeting.
+///
+/// `transcript` is in the same `[mm:ss]` line format as for questions.
+pub async fn extract_action_items(
+    provider: &dyn LlmProvider,
+    title: &str,
+    transcript: &str,
+) -> Result<Vec<ExtractedAction>> {
+    let parts = split_transcript(transcript, MAX_PART_CHARS);
+    let mut items: Vec<ExtractedAction> = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let numbered = (parts.len() > 1).then_some((i + 1, parts.len()));
+        let prompt = build_actions_prompt(title, part, numbered);
+        let extracted = complete_json(
+            provider,
+            &prompt,
+            &action_items_schema(),
+            "action items",
+            parse_action_items,
+        )
+        .await?;
+        for item in extracted {
+            if !items
+                .iter()
+                .any(|existing| existing.task.eq_ignore_ascii_case(&item.task))
+            {
+                items.push(item);
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Schema of the JSON array of action items
+pub(super) fn action_items_schema() -> Value {
+    json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {
+                "owner": {"type": ["string", "null"]},
+                "task": {"type": "string"},
+                "due": {"type": ["string", "null"]}
+            },
+            "required": ["owner", "task", "due"]
+        }
+    })
+}
+
+/// Read the JSON array of a reply, tolerating code fences and text around it
+fn parse_action_items(reply: &str) -> Result<Vec<ExtractedAction>> {
+    let items = valid_items(json_array(reply, "action items")?, "action items");
+    Ok(tidy_action_items(items))
+}
+
+/// Trim action items, turning empty owners and due dates into `None` and
+/// dropping items without a task
+pub(super) fn tidy_action_items(items: Vec<ExtractedAction>) -> Vec<ExtractedAction> {
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let task = item.task.trim().to_string();
+            (!task.is_empty()).then(|| ExtractedAction {
+                owner: non_empty(item.owner),
+                task,
+                due: non_empty(item.due),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fenced_json_and_drops_empty_fields() {
+        let reply = "Here are the action items:\n```json\n[\n  \
+{\"owner\": \"Ada\", \"task\": \"Send the budget\", \"due\": \"2024-05-03\"},\n  \
+{\"owner\": \"\", \"task\": \"Book a room\", \"due\": null},\n  \
+{\"owner\": null, \"task\": \" \"}\n]\n```";
+
+        assert_eq!(
+            parse_action_items(reply).unwrap(),
+            [
+                ExtractedAction {
+                    owner: Some("Ada".to_string()),
+                    task: "Send the budget".to_string(),
+                    due: Some("2024-05-03".to_string()),
+                },
+                ExtractedAction {
+                    owner: None,
+                    task: "Book a room".to_string(),
+                    due: None,
+                },
+            ]
+        );
+        assert!(parse_action_items("[]").unwrap().is_empty());
+        assert!(parse_action_items("No action items were agreed.").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Question answering over transcripts
+//!
+//! A transcript too long for one prompt is split into parts at line
+//! boundaries. Each part is asked the question on its own, and the answers
+//! of the parts that covered it are merged by one more request.
+//!
+//! Questions over the whole library get excerpts of the recordings closest
+//! to the question instead, which fit in a single prompt.
+
+use anyhow::Result;
+
+use crate::llm::client::{LlmProvider, SummaryResponse, TextSink, TokenUsage};
+use crate::llm::prompts::{
+    build_answer_prompt, build_combine_prompt, build_library_answer_prompt, NO_ANSWER,
+};
+
+/// Transcript characters sent in one prompt, about 8k tokens, so local
+/// models with small context windows see the whole part
+pub(super) const MAX_PART_CHARS: usize = 32_000;
+
+/// Question about one recording.
+pub struct QuestionRequest<'a> {
+    pub title: &'a str,
+    /// Transcript lines, each starting with a `[mm:ss]` timestamp
+    pub transcript: &'a str,
+    pub question: &'a str,
+    /// Receives the answer text as it is generated
+    pub on_text: Option<TextSink<'a>>,
+}
+
+/// Answer a question from a transcript, citing the timestamps it relies on.
+pub async fn answer_question(
+    provider: &dyn LlmProvider,
+    request: QuestionRequest<'_>,
+) -> Result<SummaryResponse> {
+    let parts = split_transcript(request.transcript, MAX_PART_CHARS);
+    if parts.len() <= 1 {
+        let prompt = build_answer_prompt(request.title, request.transcript, request.question, None);
+        return match request.on_text {
+            Some(on_text) => provider.complete_streaming(&prompt, on_text).await,
+            None => provider.complete(&prompt).await,
+        };
+    }
+
+    let mut usage = TokenUsage::default();
+    let mut answers = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let prompt = build_answer_prompt(
+            request.title,
+            part,
+            request.question,
+            Some((i + 1, parts.len())),
+        );
+        let response = provider.complete(&prompt).await?;
+        usage.add(response.usage);
+        if !response.text.contains(NO_ANSWER) {
+            answers.push(response.text);
+        }
+    }
+
+    // The answers of the parts are not streamed, only the final one
+    let text = if answers.len() <= 1 {
+        let text = answers
+            .pop()
+            .unwrap_or_else(|| "The transcript does not answer this question.".to_string());
+        if let Some(on_text) = request.on_text {
+            on_text(&text);
+        }
+        text
+    } else {
+        let prompt = build_combine_prompt(request.title, request.question, &answers);
+        let response = match request.on_text {
+            Some(on_text) => provider.complete_streaming(&prompt, on_text).await?,
+            None => provider.complete(&prompt).await?,
+        };
+        usage.add(response.usage);
+        response.text
+    };
+
+    Ok(SummaryResponse { text, usage })
+}
+
+/// Transcript lines of one recording retrieved for a library-wide question.
+pub struct LibraryExcerpt {
+    pub title: String,
+    /// Recording date, `YYYY-MM-DD`
+    pub date: String,
+    /// Transcript lines, each starting with a `[mm:ss]` timestamp
+    pub transcript: String,
+}
+
+/// Question about every recording, with the excerpts closest to it.
+pub struct LibraryQuestionRequest<'a> {
+    pub excerpts: &'a [LibraryExcerpt],
+    pub question: &'a str,
+    /// Receives the answer text as it is generated
+    pub on_text: Option<TextSink<'a>>,
+}
+
+/// Answer a question from excerpts of several recordings, citing the
+/// recording title and timestamp of each line it relies on.
+pub async fn answer_library_question(
+    provider: &dyn LlmProvider,
+    request: LibraryQuestionRequest<'_>,
+) -> Result<SummaryResponse> {
+    let prompt = build_library_answer_prompt(request.excerpts, request.question);
+    match request.on_text {
+        Some(on_text) => provider.complete_streaming(&prompt, on_text).await,
+        None => provider.complete(&prompt).await,
+    }
+}
+
+/// Split a transcript into parts of at most `max_chars` at line boundaries
+///
+/// A single line longer than `max_chars` becomes a part of its own.
+pub(super) fn split_transcript(transcript: &str, max_chars: usize) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    for line in transcript.split_inclusive('\n') {
+        if end > start && end - start + line.len() > max_chars {
+            parts.push(&transcript[start..end]);
+            start = end;
+        }
+        end += line.len();
+    }
+    if end > start {
+        parts.push(&transcript[start..end]);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_long_transcripts_between_lines() {
+        let transcript = "[00:00] Hello\n[00:05] Budget is fine\n[00:09] Ship on Friday\n";
+
+        assert_eq!(split_transcript(transcript, 1000), [transcript]);
+        assert_eq!(
+            split_transcript(transcript, 40),
+            [
+                "[00:00] Hello\n[00:05] Budget is fine\n",
+                "[00:09] Ship on Friday\n"
+            ]
+        );
+        assert_eq!(
+            split_transcript(transcript, 10),
+            [
+                "[00:00] Hello\n",
+                "[00:05] Budget is fine\n",
+                "[00:09] Ship on Friday\n"
+            ]
+        );
+        assert!(split_transcript("", 10).is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! Topic chapters of long meetings
+//!
+//! The model replies with a JSON array of `{timestamp, title}` objects. Long
+//! transcripts are split like for questions, and the chapters of all parts
+//! are joined in time order.
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::llm::answer::{split_transcript, MAX_PART_CHARS};
+use crate::llm::client::LlmProvider;
+use crate::llm::json::{complete_json, json_array, valid_items};
+use crate::llm::prompts::build_chapters_prompt;
+
+/// A chapter as returned by the model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapterMarker {
+    /// Start time in seconds
+    pub start_time: f64,
+    pub title: String,
+}
+
+/// A chapter as written in the reply
+#[derive(Deserialize)]
+struct ChapterReply {
+    timestamp: String,
+    title: String,
+}
+
+/// Divide a meeting into chapters by topic.
+///
+/// `transcript` is in the same `[mm:ss]` line format as for questions.
+pub async fn generate_chapters(
+    provider: &dyn LlmProvider,
+    title: &str,
+    transcript: &str,
+) -> Result<Vec<ChapterMarker>> {
+    let parts = split_transcript(transcript, MAX_PART_CHARS);
+    let mut chapters = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let numbered = (parts.len() > 1).then_some((i + 1, parts.len()));
+        let prompt = build_chapters_prompt(title, part, numbered);
+        let extracted = complete_json(
+            provider,
+            &prompt,
+            &chapters_schema(),
+            "chapters",
+            parse_chapters,
+        )
+        .await?;
+        chapters.extend(extracted);
+    }
+
+    chapters.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    chapters.dedup_by(|later, earlier| later.start_time == earlier.start_time);
+    if chapters.is_empty() {
+        anyhow::bail!("LLM reply did not contain any chapters");
+    }
+    Ok(chapters)
+}
+
+/// Schema of the JSON array of chapters
+fn chapters_schema() -> Value {
+    json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {
+                "timestamp": {"type": "string"},
+                "title": {"type": "string"}
+            },
+            "required": ["timestamp", "title"]
+        }
+    })
+}
+
+/// Read the JSON array of a reply, tolerating code fences and text around it
+///
+/// Brackets around timestamps are optional and hours may lead them; chapters
+/// without a `mm:ss` or `hh:mm:ss` timestamp or without a title are dropped.
+fn parse_chapters(reply: &str) -> Result<Vec<ChapterMarker>> {
+    let chapters: Vec<ChapterReply> = valid_items(json_array(reply, "chapters")?, "chapters");
+    Ok(chapters
+        .into_iter()
+        .filter_map(|chapter| {
+            let start_time = parse_clock(chapter.timestamp.trim().trim_matches(['[', ']']))?;
+            let title = chapter.title.trim().trim_matches('"').trim();
+            (!title.is_empty()).then(|| ChapterMarker {
+                start_time,
+                title: title.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Seconds of a `mm:ss` or `hh:mm:ss` timestamp
+pub(super) fn parse_clock(value: &str) -> Option<f64> {
+    let fields: Vec<&str> = value.split(':').collect();
+    if !(2..=3).contains(&fields.len()) {
+        return None;
+    }
+    let mut seconds = 0u64;
+    for field in fields {
+        seconds = seconds * 60 + field.parse::<u64>().ok()?;
+    }
+    Some(seconds as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_chapters_and_drops_unusable_ones() {
+        let reply = "Here are the chapters:\n```json\n[\n  \
+{\"timestamp\": \"[00:00]\", \"title\": \"Intro\"},\n  \
+{\"timestamp\": \"12:30\", \"title\": \"Budget discussion\"},\n  \
+{\"timestamp\": \"1:02:05\", \"title\": \"\\\"Hiring plan\\\"\"},\n  \
+{\"timestamp\": \"15:00\", \"title\": \" \"},\n  \
+{\"timestamp\": \"later\", \"title\": \"Wrap-up\"}\n]\n```";
+
+        assert_eq!(
+            parse_chapters(reply).unwrap(),
+            [
+                ChapterMarker {
+                    start_time: 0.0,
+                    title: "Intro".to_string(),
+                },
+                ChapterMarker {
+                    start_time: 750.0,
+                    title: "Budget discussion".to_string(),
+                },
+                ChapterMarker {
+                    start_time: 3725.0,
+                    title: "Hiring plan".to_string(),
+                },
+            ]
+        );
+        assert!(parse_chapters("No chapters.").is_err());
+    }
+}
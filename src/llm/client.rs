@@ -1,29 +1,408 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use serde_json::Value;
 
 use crate::config::Settings;
+use crate::llm::failover::FailoverProvider;
 use crate::llm::gemini::GeminiClient;
+#[cfg(feature = "llama")]
+use crate::llm::llama::LlamaClient;
+use crate::llm::ollama::OllamaClient;
+use crate::llm::prompts::{
+    build_merge_notes_prompt, build_notes_prompt, build_summary_from_notes_prompt,
+    build_summary_prompt, join_notes,
+};
+use crate::llm::redact::{RedactingProvider, RedactionReport};
+use crate::llm::retry::RetryingProvider;
+use crate::llm::template::PromptTemplate;
+use crate::storage::Recording;
 
 /// Summary generation request payload.
 pub struct SummaryRequest<'a> {
     pub title: &'a str,
+    /// People who attended the meeting, if known
+    pub attendees: &'a [String],
     pub transcript: &'a str,
+    /// Language of the transcript by English name, e.g. "German"
+    pub language: Option<&'a str>,
+    /// Language to write the summary in when it is not the transcript's
+    pub summary_language: Option<&'a str>,
+    /// User template replacing the built-in summary prompt
+    pub template: Option<&'a PromptTemplate>,
+    /// How to split a transcript too long for one prompt
+    pub chunking: Chunking,
+    /// Receives the summary text as it is generated
+    pub on_text: Option<TextSink<'a>>,
 }
 
+/// Callback receiving generated text piece by piece as a provider streams it.
+pub type TextSink<'a> = &'a mut (dyn FnMut(&str) + Send);
+
+/// Splitting of long transcripts for map-reduce summaries.
+///
+/// A transcript longer than `max_chars` is summarized in chunks of at most
+/// that size, each starting with the last `overlap_chars` of the one before
+/// so a point made across the cut is not lost; the notes on all chunks are
+/// then combined into one summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunking {
+    pub max_chars: usize,
+    pub overlap_chars: usize,
+}
+
+impl Chunking {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            max_chars: settings.llm.summary_chunk_chars.max(1),
+            // Each chunk has to bring new text for splitting to make progress
+            overlap_chars: settings
+                .llm
+                .summary_chunk_overlap_chars
+                .min(settings.llm.summary_chunk_chars / 2),
+        }
+    }
+}
+
+/// Token counts reported by a provider for a single call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
+
+    /// Estimated cost in USD from per-million-token prices.
+    pub fn estimated_cost(&self, input_per_million: f64, output_per_million: f64) -> f64 {
+        (self.input_tokens as f64 * input_per_million
+            + self.output_tokens as f64 * output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Generated text, a summary or an answer, and what it cost.
+pub struct SummaryResponse {
+    pub text: String,
+    pub usage: TokenUsage,
+}
+
+/// Texts embedded per provider request when indexing transcript segments
+pub const EMBED_BATCH_SIZE: usize = 100;
+
 #[async_trait]
 pub trait LlmProvider: Send + Sync {
-    async fn summarize(&self, request: SummaryRequest<'_>) -> Result<String>;
+    /// Generate text for a single prompt.
+    async fn complete(&self, prompt: &str) -> Result<SummaryResponse>;
+
+    /// Generate text for a single prompt, passing it to `on_text` as it arrives.
+    ///
+    /// Providers that cannot stream pass the whole text at once.
+    async fn complete_streaming(
+        &self,
+        prompt: &str,
+        on_text: TextSink<'_>,
+    ) -> Result<SummaryResponse> {
+        let response = self.complete(prompt).await?;
+        on_text(&response.text);
+        Ok(response)
+    }
+
+    /// Generate a JSON reply for a single prompt, following `schema`.
+    ///
+    /// `schema` is a JSON Schema. Providers that cannot constrain their output
+    /// to it only have the prompt to go by, so replies still need checking.
+    async fn complete_json(&self, prompt: &str, schema: &Value) -> Result<SummaryResponse> {
+        let _ = schema;
+        self.complete(prompt).await
+    }
+
+    /// Summarize a transcript, in chunks if it is longer than `request.chunking` allows.
+    async fn summarize(&self, request: SummaryRequest<'_>) -> Result<SummaryResponse> {
+        let chunks = split_with_overlap(request.transcript, request.chunking);
+        // Templates name the language to write in
+        let template_language = request.summary_language.or(request.language);
+        if chunks.len() <= 1 {
+            let prompt = match request.template {
+                Some(template) => template.render(
+                    request.title,
+                    request.attendees,
+                    request.transcript,
+                    template_language,
+                ),
+                None => build_summary_prompt(
+                    request.title,
+                    request.attendees,
+                    request.transcript,
+                    request.language,
+                    request.summary_language,
+                ),
+            };
+            return match request.on_text {
+                Some(on_text) => self.complete_streaming(&prompt, on_text).await,
+                None => self.complete(&prompt).await,
+            };
+        }
+
+        let mut usage = TokenUsage::default();
+        let mut notes = Vec::with_capacity(chunks.len());
+        for (i, chunk) in chunks.iter().enumerate() {
+            let prompt = build_notes_prompt(
+                request.title,
+                request.attendees,
+                chunk,
+                (i + 1, chunks.len()),
+                request.language,
+                request.summary_language,
+            );
+            let response = self.complete(&prompt).await?;
+            usage.add(response.usage);
+            notes.push(response.text);
+        }
+
+        // Notes on a very long meeting may not fit one prompt either
+        while notes.len() > 1 && join_notes(&notes).len() > request.chunking.max_chars {
+            let mut merged = Vec::new();
+            for group in group_notes(notes, request.chunking.max_chars) {
+                if group.len() == 1 {
+                    merged.extend(group);
+                    continue;
+                }
+                let prompt = build_merge_notes_prompt(
+                    request.title,
+                    request.attendees,
+                    &group,
+                    request.language,
+                    request.summary_language,
+                );
+                let response = self.complete(&prompt).await?;
+                usage.add(response.usage);
+                merged.push(response.text);
+            }
+            notes = merged;
+        }
+
+        let prompt = match request.template {
+            Some(template) => template.render(
+                request.title,
+                request.attendees,
+                &join_notes(&notes),
+                template_language,
+            ),
+            None => build_summary_from_notes_prompt(
+                request.title,
+                request.attendees,
+                &notes,
+                request.language,
+                request.summary_language,
+            ),
+        };
+        // Only the final summary is streamed; notes are an intermediate step
+        let response = match request.on_text {
+            Some(on_text) => self.complete_streaming(&prompt, on_text).await?,
+            None => self.complete(&prompt).await?,
+        };
+        usage.add(response.usage);
+
+        Ok(SummaryResponse {
+            text: response.text,
+            usage,
+        })
+    }
+
+    /// Provider name recorded with generated summaries.
+    fn name(&self) -> &str;
+
+    /// Model used for requests.
+    fn model(&self) -> &str;
+
+    /// Whether transcripts leave the machine when sent to this provider.
+    fn is_cloud(&self) -> bool {
+        true
+    }
+
+    /// Personal data masked in what was sent so far, with `llm.redact_pii`.
+    fn redactions(&self) -> RedactionReport {
+        RedactionReport::default()
+    }
+
+    /// Model used for embeddings, or `None` when the provider has none.
+    fn embedding_model(&self) -> Option<&str> {
+        None
+    }
+
+    /// Embed texts as vectors for semantic search, one per text in order.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let _ = texts;
+        anyhow::bail!("llm.provider '{}' does not support embeddings", self.name())
+    }
+}
+
+/// Split a transcript at line boundaries into chunks that overlap
+///
+/// A single line longer than `max_chars` becomes a chunk of its own.
+fn split_with_overlap(transcript: &str, chunking: Chunking) -> Vec<&str> {
+    let lines: Vec<(usize, usize)> = transcript
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, *offset))
+        })
+        .collect();
+
+    let mut chunks = Vec::new();
+    let mut first = 0;
+    while first < lines.len() {
+        let start = lines[first].0;
+        let mut last = first;
+        while last + 1 < lines.len() && lines[last + 1].1 - start <= chunking.max_chars {
+            last += 1;
+        }
+        chunks.push(&transcript[start..lines[last].1]);
+        if last + 1 == lines.len() {
+            break;
+        }
+
+        // Back up over whole lines that fit in the overlap, keeping at least one new line
+        let end = lines[last].1;
+        let mut next = last + 1;
+        while next > first + 1 && end - lines[next - 1].0 <= chunking.overlap_chars {
+            next -= 1;
+        }
+        first = next;
+    }
+    chunks
+}
+
+/// Group consecutive notes so each group fits `max_chars` when joined
+///
+/// Groups are cut only once they hold two notes, so merging always makes
+/// fewer notes even when single notes are long.
+fn group_notes(notes: Vec<String>, max_chars: usize) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut group: Vec<String> = Vec::new();
+    let mut size = 0;
+    for note in notes {
+        if group.len() >= 2 && size + note.len() > max_chars {
+            groups.push(std::mem::take(&mut group));
+            size = 0;
+        }
+        size += note.len();
+        group.push(note);
+    }
+    if !group.is_empty() {
+        groups.push(group);
+    }
+    groups
+}
+
+/// Whether `recording` may be sent to `provider`
+///
+/// Confidential recordings only go to a cloud provider when
+/// `include_confidential` is set.
+pub fn cloud_allowed(
+    recording: &Recording,
+    provider: &dyn LlmProvider,
+    include_confidential: bool,
+) -> bool {
+    !recording.is_confidential() || !provider.is_cloud() || include_confidential
+}
+
+/// Fail with a hint at `--include-confidential` unless [`cloud_allowed`]
+pub fn ensure_cloud_allowed(
+    recording: &Recording,
+    provider: &dyn LlmProvider,
+    include_confidential: bool,
+) -> Result<()> {
+    if !cloud_allowed(recording, provider, include_confidential) {
+        anyhow::bail!(
+            "Recording {} is confidential and llm provider '{}' is a cloud service. Re-run with --include-confidential to send it anyway.",
+            &recording.id[..8],
+            provider.name()
+        );
+    }
+    Ok(())
 }
 
 /// Build an LLM provider from runtime settings.
+///
+/// With `llm.providers` set, requests go to the first provider of the list
+/// and fall back to the next one when it fails. Providers that cannot be
+/// set up, such as Gemini without an API key, are left out of the chain.
 pub fn build_provider(settings: &Settings) -> Result<Box<dyn LlmProvider>> {
-    match settings.llm.provider.to_lowercase().as_str() {
-        "gemini" => Ok(Box::new(GeminiClient::from_settings(settings)?)),
+    if settings.llm.providers.is_empty() {
+        return build_named_provider(settings);
+    }
+
+    let mut providers = Vec::new();
+    let mut first_error = None;
+    for name in &settings.llm.providers {
+        match build_named_provider(&chain_settings(settings, name)) {
+            Ok(provider) => providers.push(provider),
+            Err(e) => {
+                tracing::warn!(
+                    "Leaving llm provider '{}' out of llm.providers: {}",
+                    name,
+                    e
+                );
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match (providers.len(), first_error) {
+        (0, Some(e)) => Err(e),
+        (1, _) => Ok(providers.remove(0)),
+        _ => Ok(Box::new(FailoverProvider::new(providers))),
+    }
+}
+
+/// Settings for one provider of `llm.providers`
+///
+/// `llm.model`, `llm.endpoint` and `llm.embedding_model` configure the
+/// provider named in `llm.provider`; the others use their defaults.
+fn chain_settings(settings: &Settings, name: &str) -> Settings {
+    let mut settings = settings.clone();
+    if !name
+        .trim()
+        .eq_ignore_ascii_case(settings.llm.provider.trim())
+    {
+        settings.llm.model.clear();
+        settings.llm.endpoint.clear();
+        settings.llm.embedding_model.clear();
+    }
+    settings.llm.provider = name.to_string();
+    settings
+}
+
+/// Build the provider named in `llm.provider`
+fn build_named_provider(settings: &Settings) -> Result<Box<dyn LlmProvider>> {
+    let provider: Box<dyn LlmProvider> = match settings.llm.provider.to_lowercase().as_str() {
+        "gemini" => Box::new(GeminiClient::from_settings(settings)?),
+        "ollama" => Box::new(OllamaClient::from_settings(settings)?),
+        "llama" => {
+            #[cfg(feature = "llama")]
+            {
+                Box::new(LlamaClient::from_settings(settings)?)
+            }
+            #[cfg(not(feature = "llama"))]
+            {
+                anyhow::bail!("llm.provider 'llama' requires building with the llama feature")
+            }
+        }
         other => anyhow::bail!(
-            "Unsupported llm.provider '{}'. Supported providers: gemini",
+            "Unsupported llm.provider '{}'. Supported providers: gemini, ollama, llama",
             other
         ),
+    };
+    let provider = Box::new(RetryingProvider::new(provider, &settings.llm));
+    if settings.llm.redact_pii && provider.is_cloud() {
+        return Ok(Box::new(RedactingProvider::new(provider)));
     }
+    Ok(provider)
 }
 
 #[cfg(test)]
@@ -31,6 +410,58 @@ mod tests {
     use super::*;
     use crate::config::Settings;
 
+    #[test]
+    fn token_usage_estimates_cost_per_million() {
+        let mut usage = TokenUsage::default();
+        usage.add(TokenUsage {
+            input_tokens: 2_000_000,
+            output_tokens: 500_000,
+        });
+        assert_eq!(usage.estimated_cost(0.3, 2.5), 1.85);
+    }
+
+    #[test]
+    fn splits_long_transcripts_into_overlapping_chunks() {
+        let transcript = "[00:00] a\n[00:05] b\n[00:10] c\n[00:15] d\n";
+        let chunking = |max_chars, overlap_chars| Chunking {
+            max_chars,
+            overlap_chars,
+        };
+
+        assert_eq!(
+            split_with_overlap(transcript, chunking(1000, 100)),
+            [transcript]
+        );
+        assert_eq!(
+            split_with_overlap(transcript, chunking(25, 10)),
+            [
+                "[00:00] a\n[00:05] b\n",
+                "[00:05] b\n[00:10] c\n",
+                "[00:10] c\n[00:15] d\n"
+            ]
+        );
+        assert_eq!(
+            split_with_overlap(transcript, chunking(20, 0)),
+            ["[00:00] a\n[00:05] b\n", "[00:10] c\n[00:15] d\n"]
+        );
+        assert_eq!(
+            split_with_overlap(transcript, chunking(5, 5)),
+            ["[00:00] a\n", "[00:05] b\n", "[00:10] c\n", "[00:15] d\n"]
+        );
+        assert!(split_with_overlap("", chunking(10, 0)).is_empty());
+    }
+
+    #[test]
+    fn groups_notes_to_fit_and_always_merge() {
+        let notes =
+            |lens: &[usize]| -> Vec<String> { lens.iter().map(|&n| "x".repeat(n)).collect() };
+        let sizes =
+            |groups: Vec<Vec<String>>| -> Vec<usize> { groups.iter().map(Vec::len).collect() };
+
+        assert_eq!(sizes(group_notes(notes(&[4, 4, 4, 4, 4]), 10)), [2, 2, 1]);
+        assert_eq!(sizes(group_notes(notes(&[30, 30, 30]), 10)), [2, 1]);
+    }
+
     #[test]
     fn unsupported_provider_returns_error() {
         let mut settings = Settings::default();
@@ -53,4 +484,80 @@ mod tests {
         };
         assert!(err.contains("Gemini API key is missing"));
     }
+
+    #[test]
+    fn confidential_recordings_stay_off_cloud_providers() {
+        let mut settings = Settings::default();
+        settings.llm.provider = "ollama".to_string();
+        let local = build_provider(&settings).unwrap();
+        settings.llm.endpoint = "http://gpu-box.example.com:11434".to_string();
+        let cloud = build_provider(&settings).unwrap();
+
+        let mut recording = Recording::new("Board meeting".to_string());
+        assert!(ensure_cloud_allowed(&recording, cloud.as_ref(), false).is_ok());
+
+        recording.sensitivity = crate::storage::Sensitivity::Confidential;
+        assert!(ensure_cloud_allowed(&recording, local.as_ref(), false).is_ok());
+        assert!(ensure_cloud_allowed(&recording, cloud.as_ref(), true).is_ok());
+        let err = ensure_cloud_allowed(&recording, cloud.as_ref(), false).unwrap_err();
+        assert!(err.to_string().contains("--include-confidential"));
+    }
+
+    #[test]
+    fn ollama_provider_runs_locally_without_api_key() {
+        let mut settings = Settings::default();
+        settings.llm.provider = "ollama".to_string();
+
+        let provider = build_provider(&settings).unwrap();
+        assert_eq!(provider.name(), "ollama");
+        assert_eq!(provider.model(), "llama3.1");
+        assert!(!provider.is_cloud());
+
+        settings.llm.model = "mistral".to_string();
+        settings.llm.endpoint = "http://gpu-box.example.com:11434/".to_string();
+        let provider = build_provider(&settings).unwrap();
+        assert_eq!(provider.model(), "mistral");
+        assert!(provider.is_cloud());
+    }
+
+    #[test]
+    fn provider_chain_starts_with_the_first_provider_that_can_be_built() {
+        let mut settings = Settings::default();
+        settings.llm.providers = vec!["ollama".to_string(), "gemini".to_string()];
+
+        // Without an API key Gemini is left out and Ollama runs alone
+        let provider = build_provider(&settings).unwrap();
+        assert_eq!(provider.name(), "ollama");
+        assert_eq!(provider.model(), "llama3.1");
+        assert!(!provider.is_cloud());
+
+        // Gemini keeps llm.model; Ollama, not being llm.provider, uses its default
+        settings.llm.api_key = "test-key".to_string();
+        let provider = build_provider(&settings).unwrap();
+        assert_eq!(provider.name(), "ollama");
+        assert_eq!(provider.model(), "llama3.1");
+        assert!(provider.is_cloud());
+        assert_eq!(provider.embedding_model(), Some("nomic-embed-text"));
+
+        settings.llm.providers = vec!["gemini".to_string()];
+        settings.llm.api_key.clear();
+        let err = match build_provider(&settings) {
+            Ok(_) => panic!("expected provider creation to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("Gemini API key is missing"));
+    }
+
+    #[cfg(not(feature = "llama"))]
+    #[test]
+    fn llama_provider_needs_the_llama_feature() {
+        let mut settings = Settings::default();
+        settings.llm.provider = "llama".to_string();
+
+        let err = match build_provider(&settings) {
+            Ok(_) => panic!("expected provider creation to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("requires building with the llama feature"));
+    }
 }
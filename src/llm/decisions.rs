@@ -0,0 +1,131 @@
+//! Decision extraction from transcripts
+//!
+//! The model is asked for a JSON array of `{decision, timestamps}` objects,
+//! the timestamps being those of the transcript lines where the decision was
+//! made. Long transcripts are split like for questions, and the decisions of
+//! all parts are joined, dropping ones listed twice.
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::llm::answer::{split_transcript, MAX_PART_CHARS};
+use crate::llm::chapters::parse_clock;
+use crate::llm::client::LlmProvider;
+use crate::llm::json::{complete_json, json_array, valid_items};
+use crate::llm::prompts::build_decisions_prompt;
+
+/// A decision as returned by the model, with its timestamps in seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedDecision {
+    pub text: String,
+    pub timestamps: Vec<f64>,
+}
+
+/// A decision as written in the reply
+#[derive(Deserialize)]
+struct DecisionReply {
+    decision: String,
+    #[serde(default)]
+    timestamps: Vec<String>,
+}
+
+/// Extract the decisions made in a meeting.
+///
+/// `transcript` is in the same `[mm:ss]` line format as for questions.
+pub async fn extract_decisions(
+    provider: &dyn LlmProvider,
+    title: &str,
+    transcript: &str,
+) -> Result<Vec<ExtractedDecision>> {
+    let parts = split_transcript(transcript, MAX_PART_CHARS);
+    let mut decisions: Vec<ExtractedDecision> = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let numbered = (parts.len() > 1).then_some((i + 1, parts.len()));
+        let prompt = build_decisions_prompt(title, part, numbered);
+        let extracted = complete_json(
+            provider,
+            &prompt,
+            &decisions_schema(),
+            "decisions",
+            parse_decisions,
+        )
+        .await?;
+        for decision in extracted {
+            if !decisions
+                .iter()
+                .any(|existing| existing.text.eq_ignore_ascii_case(&decision.text))
+            {
+                decisions.push(decision);
+            }
+        }
+    }
+    Ok(decisions)
+}
+
+/// Schema of the JSON array of decisions
+fn decisions_schema() -> Value {
+    json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {
+                "decision": {"type": "string"},
+                "timestamps": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["decision", "timestamps"]
+        }
+    })
+}
+
+/// Read the JSON array of a reply, tolerating code fences and text around it
+///
+/// Timestamps that are not `mm:ss` or `hh:mm:ss` are dropped, the rest are
+/// sorted; decisions without text are dropped.
+fn parse_decisions(reply: &str) -> Result<Vec<ExtractedDecision>> {
+    let decisions: Vec<DecisionReply> = valid_items(json_array(reply, "decisions")?, "decisions");
+
+    Ok(decisions
+        .into_iter()
+        .filter_map(|decision| {
+            let text = decision.decision.trim().to_string();
+            let mut timestamps: Vec<f64> = decision
+                .timestamps
+                .iter()
+                .filter_map(|timestamp| parse_clock(timestamp.trim().trim_matches(['[', ']'])))
+                .collect();
+            timestamps.sort_by(f64::total_cmp);
+            timestamps.dedup();
+            (!text.is_empty()).then_some(ExtractedDecision { text, timestamps })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decisions_with_their_timestamps() {
+        let reply = "```json\n[\n  \
+{\"decision\": \"Ship on Monday\", \"timestamps\": [\"[12:30]\", \"01:15\", \"soon\"]},\n  \
+{\"decision\": \"Hire a designer\"},\n  \
+{\"decision\": \"\", \"timestamps\": [\"00:10\"]}\n]\n```";
+
+        assert_eq!(
+            parse_decisions(reply).unwrap(),
+            [
+                ExtractedDecision {
+                    text: "Ship on Monday".to_string(),
+                    timestamps: vec![75.0, 750.0],
+                },
+                ExtractedDecision {
+                    text: "Hire a designer".to_string(),
+                    timestamps: vec![],
+                },
+            ]
+        );
+        assert!(parse_decisions("[]").unwrap().is_empty());
+        assert!(parse_decisions("Nothing was decided.").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! LLM failures that may succeed when tried again
+//!
+//! Rate limits, overloaded servers and network hiccups go away on their own,
+//! so provider calls failing with them are retried. A bad API key or an
+//! unknown model fails the same way every time and is reported at once.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+/// A provider answered with an error status
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct LlmHttpError {
+    pub status: u16,
+    /// How long the provider asked to wait before the next request
+    pub retry_after: Option<Duration>,
+    pub message: String,
+}
+
+/// Whether an LLM call that failed with `error` may succeed later
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(error) = cause.downcast_ref::<LlmHttpError>() {
+            return error.status == 408 || error.status == 429 || error.status >= 500;
+        }
+        // Timeouts, refused connections and responses cut off mid-stream
+        cause.is::<reqwest::Error>()
+    })
+}
+
+/// Wait asked for by a `Retry-After` header, in seconds or as an HTTP date
+pub(super) fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+    #[test]
+    fn tells_retryable_failures_from_lasting_ones() {
+        let http_error = |status| {
+            anyhow::Error::new(LlmHttpError {
+                status,
+                retry_after: None,
+                message: "failed".to_string(),
+            })
+        };
+        assert!(is_transient(&http_error(429)));
+        assert!(is_transient(&http_error(503)));
+        assert!(!is_transient(&http_error(401)));
+        assert!(!is_transient(&http_error(404)));
+
+        let parse: anyhow::Result<()> =
+            Err(anyhow::anyhow!("expected value")).context("Failed to parse Gemini response");
+        assert!(!is_transient(&parse.unwrap_err()));
+    }
+
+    #[test]
+    fn reads_retry_after_in_seconds_and_as_date() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(30)));
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+}
@@ -0,0 +1,161 @@
+//! Falling back to the next provider of `llm.providers` when one fails
+//!
+//! A local model that is not running or a cloud API that is down does not
+//! fail the command while another provider in the chain can answer. Each
+//! provider has retried transient failures before it is given up on, and
+//! once one is given up on the rest of the command uses the next one, so a
+//! long summary is not written by a mix of models.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::llm::client::{LlmProvider, SummaryResponse, TextSink};
+use crate::llm::redact::RedactionReport;
+
+/// Providers tried in order until one answers
+pub(super) struct FailoverProvider {
+    providers: Vec<Box<dyn LlmProvider>>,
+    /// Provider answering requests, reported by `name` and `model`
+    active: AtomicUsize,
+}
+
+impl FailoverProvider {
+    pub fn new(providers: Vec<Box<dyn LlmProvider>>) -> Self {
+        assert!(!providers.is_empty(), "a failover chain needs a provider");
+        Self {
+            providers,
+            active: AtomicUsize::new(0),
+        }
+    }
+
+    fn active(&self) -> &dyn LlmProvider {
+        self.providers[self.active.load(Ordering::Relaxed)].as_ref()
+    }
+
+    /// Move on from the provider at `index` after it failed with `error`
+    ///
+    /// Returns the error when no provider is left to try.
+    fn fail_over(&self, index: usize, error: anyhow::Error) -> Result<()> {
+        let failed = &self.providers[index];
+        let Some(next) = self.providers.get(index + 1) else {
+            return Err(error.context("Every provider in llm.providers failed"));
+        };
+        tracing::warn!(
+            "{} failed, falling back to {}: {}",
+            failed.name(),
+            next.name(),
+            error
+        );
+        self.active.store(index + 1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn answered(&self, index: usize) {
+        if index > 0 {
+            let provider = &self.providers[index];
+            tracing::info!("Answered by {} / {}", provider.name(), provider.model());
+        }
+    }
+
+    /// Provider embedding texts: the first one that can
+    ///
+    /// Embeddings do not fail over, so the stored vectors all come from one model.
+    fn embedder(&self) -> &dyn LlmProvider {
+        self.providers
+            .iter()
+            .find(|provider| provider.embedding_model().is_some())
+            .unwrap_or(&self.providers[0])
+            .as_ref()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for FailoverProvider {
+    fn name(&self) -> &str {
+        self.active().name()
+    }
+
+    fn model(&self) -> &str {
+        self.active().model()
+    }
+
+    /// A transcript may reach any provider in the chain
+    fn is_cloud(&self) -> bool {
+        self.providers.iter().any(|provider| provider.is_cloud())
+    }
+
+    fn embedding_model(&self) -> Option<&str> {
+        self.embedder().embedding_model()
+    }
+
+    fn redactions(&self) -> RedactionReport {
+        let mut report = RedactionReport::default();
+        for provider in &self.providers {
+            report.add(provider.redactions());
+        }
+        report
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<SummaryResponse> {
+        let mut index = self.active.load(Ordering::Relaxed);
+        loop {
+            match self.providers[index].complete(prompt).await {
+                Ok(response) => {
+                    self.answered(index);
+                    return Ok(response);
+                }
+                Err(error) => self.fail_over(index, error)?,
+            }
+            index += 1;
+        }
+    }
+
+    async fn complete_json(&self, prompt: &str, schema: &Value) -> Result<SummaryResponse> {
+        let mut index = self.active.load(Ordering::Relaxed);
+        loop {
+            match self.providers[index].complete_json(prompt, schema).await {
+                Ok(response) => {
+                    self.answered(index);
+                    return Ok(response);
+                }
+                Err(error) => self.fail_over(index, error)?,
+            }
+            index += 1;
+        }
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.embedder().embed(texts).await
+    }
+
+    /// A stream that fails after passing on text is not continued by another provider
+    async fn complete_streaming(
+        &self,
+        prompt: &str,
+        on_text: TextSink<'_>,
+    ) -> Result<SummaryResponse> {
+        let mut index = self.active.load(Ordering::Relaxed);
+        loop {
+            let mut streamed = false;
+            let mut sink = |text: &str| {
+                streamed = true;
+                on_text(text);
+            };
+            match self.providers[index]
+                .complete_streaming(prompt, &mut sink)
+                .await
+            {
+                Ok(response) => {
+                    self.answered(index);
+                    return Ok(response);
+                }
+                Err(error) if streamed => return Err(error),
+                Err(error) => self.fail_over(index, error)?,
+            }
+            index += 1;
+        }
+    }
+}
@@ -2,24 +2,37 @@ use anyhow::{Context, Result};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
 
 use crate::config::Settings;
-use crate::llm::client::{LlmProvider, SummaryRequest};
-use crate::llm::prompts::build_summary_prompt;
+use crate::llm::client::{LlmProvider, SummaryResponse, TextSink, TokenUsage};
+use crate::llm::error::{retry_after, LlmHttpError};
+use crate::llm::stream::LineReader;
 
 const DEFAULT_GEMINI_ENDPOINT: &str = "https://generativelanguage.googleapis.com/v1beta";
 const DEFAULT_GEMINI_MODEL: &str = "gemini-2.5-flash";
+const DEFAULT_GEMINI_EMBEDDING_MODEL: &str = "gemini-embedding-001";
+const GEMINI_TIMEOUT_SECS: u64 = 45;
+
+/// A streamed response keeps the connection open while the text is written
+const GEMINI_STREAM_TIMEOUT_SECS: u64 = 300;
+
+/// Most texts `batchEmbedContents` accepts in one request
+const GEMINI_EMBED_BATCH: usize = 100;
 
 pub struct GeminiClient {
     http: Client,
+    stream_timeout: Duration,
     api_key: String,
     model: String,
+    embedding_model: String,
     endpoint: String,
 }
 
 impl GeminiClient {
     pub fn from_settings(settings: &Settings) -> Result<Self> {
-        let api_key = settings.llm.api_key.trim().to_string();
+        let api_key = settings.llm.api_key().trim().to_string();
         if api_key.is_empty() {
             anyhow::bail!(
                 "Gemini API key is missing. Set llm.api_key in config or MINUTES_GEMINI_API_KEY."
@@ -32,6 +45,11 @@ impl GeminiClient {
             settings.llm.model.trim().to_string()
         };
 
+        let embedding_model = match settings.llm.embedding_model.trim() {
+            "" => DEFAULT_GEMINI_EMBEDDING_MODEL.to_string(),
+            model => model.to_string(),
+        };
+
         let endpoint = if settings.llm.endpoint.trim().is_empty() {
             DEFAULT_GEMINI_ENDPOINT.to_string()
         } else {
@@ -45,11 +63,19 @@ impl GeminiClient {
 
         Ok(Self {
             http: Client::builder()
-                .timeout(std::time::Duration::from_secs(45))
+                .timeout(
+                    settings
+                        .llm
+                        .timeout(Duration::from_secs(GEMINI_TIMEOUT_SECS)),
+                )
                 .build()
                 .context("Failed to build Gemini HTTP client")?,
+            stream_timeout: settings
+                .llm
+                .timeout(Duration::from_secs(GEMINI_STREAM_TIMEOUT_SECS)),
             api_key,
             model,
+            embedding_model,
             endpoint,
         })
     }
@@ -60,31 +86,33 @@ impl GeminiClient {
             self.endpoint, self.model, self.api_key
         )
     }
-}
 
-#[async_trait]
-impl LlmProvider for GeminiClient {
-    async fn summarize(&self, request: SummaryRequest<'_>) -> Result<String> {
-        let prompt = build_summary_prompt(request.title, request.transcript);
+    fn embed_url(&self) -> String {
+        format!(
+            "{}/models/{}:batchEmbedContents?key={}",
+            self.endpoint, self.embedding_model, self.api_key
+        )
+    }
 
-        let body = GeminiGenerateContentRequest {
-            contents: vec![GeminiContent {
-                parts: vec![GeminiPart { text: prompt }],
-            }],
-        };
+    fn stream_url(&self) -> String {
+        format!(
+            "{}/models/{}:streamGenerateContent?alt=sse&key={}",
+            self.endpoint, self.model, self.api_key
+        )
+    }
 
+    /// Send a `generateContent` request and read the text of its reply
+    async fn generate(&self, body: &GeminiGenerateContentRequest) -> Result<SummaryResponse> {
         let response = self
             .http
             .post(self.request_url())
-            .json(&body)
+            .json(body)
             .send()
             .await
             .context("Gemini request failed")?;
 
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("{}", format_gemini_http_error(status, &body));
+        if !response.status().is_success() {
+            return Err(gemini_http_error(response).await.into());
         }
 
         let payload: GeminiGenerateContentResponse = response
@@ -92,7 +120,7 @@ impl LlmProvider for GeminiClient {
             .await
             .context("Failed to parse Gemini response")?;
 
-        let summary = payload
+        let text = payload
             .candidates
             .iter()
             .flat_map(|c| c.content.parts.iter())
@@ -100,15 +128,224 @@ impl LlmProvider for GeminiClient {
             .map(str::trim)
             .find(|t| !t.is_empty())
             .map(str::to_string)
-            .context("Gemini response did not contain summary text")?;
+            .context("Gemini response did not contain any text")?;
+
+        let usage = payload
+            .usage_metadata
+            .map(|usage| TokenUsage {
+                input_tokens: usage.prompt_token_count,
+                output_tokens: usage.candidates_token_count,
+            })
+            .unwrap_or_default();
+
+        Ok(SummaryResponse { text, usage })
+    }
+}
+
+fn request_body(prompt: &str) -> GeminiGenerateContentRequest {
+    GeminiGenerateContentRequest {
+        contents: vec![GeminiContent {
+            parts: vec![GeminiPart {
+                text: prompt.to_string(),
+            }],
+        }],
+        generation_config: None,
+    }
+}
+
+/// Request for a JSON reply following `schema`
+fn json_request_body(prompt: &str, schema: &Value) -> GeminiGenerateContentRequest {
+    GeminiGenerateContentRequest {
+        generation_config: Some(GeminiGenerationConfig {
+            response_mime_type: "application/json",
+            response_schema: gemini_schema(schema),
+        }),
+        ..request_body(prompt)
+    }
+}
+
+/// A JSON Schema in the OpenAPI subset `responseSchema` accepts
+///
+/// Types are upper case, `["string", "null"]` becomes `nullable`, and
+/// keywords Gemini rejects, such as `additionalProperties`, are dropped.
+fn gemini_schema(schema: &Value) -> Value {
+    let Value::Object(schema) = schema else {
+        return schema.clone();
+    };
+    let mut converted = serde_json::Map::new();
+    for (key, value) in schema {
+        match (key.as_str(), value) {
+            ("type", Value::String(kind)) => {
+                converted.insert("type".to_string(), kind.to_uppercase().into());
+            }
+            ("type", Value::Array(kinds)) => {
+                let kind = kinds
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .find(|kind| *kind != "null")
+                    .unwrap_or("string");
+                converted.insert("type".to_string(), kind.to_uppercase().into());
+                if kinds.iter().any(|kind| kind == "null") {
+                    converted.insert("nullable".to_string(), true.into());
+                }
+            }
+            ("properties", Value::Object(properties)) => {
+                let properties = properties
+                    .iter()
+                    .map(|(name, property)| (name.clone(), gemini_schema(property)))
+                    .collect();
+                converted.insert(key.clone(), Value::Object(properties));
+            }
+            ("items", items) => {
+                converted.insert(key.clone(), gemini_schema(items));
+            }
+            ("additionalProperties" | "$schema" | "title", _) => {}
+            _ => {
+                converted.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Value::Object(converted)
+}
+
+#[async_trait]
+impl LlmProvider for GeminiClient {
+    fn name(&self) -> &str {
+        "gemini"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embedding_model(&self) -> Option<&str> {
+        Some(&self.embedding_model)
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<SummaryResponse> {
+        self.generate(&request_body(prompt)).await
+    }
+
+    /// Constrained with `responseSchema`, so the reply is JSON in that shape
+    async fn complete_json(&self, prompt: &str, schema: &Value) -> Result<SummaryResponse> {
+        self.generate(&json_request_body(prompt, schema)).await
+    }
+
+    /// Stream the response as server-sent events, one partial response per event
+    async fn complete_streaming(
+        &self,
+        prompt: &str,
+        on_text: TextSink<'_>,
+    ) -> Result<SummaryResponse> {
+        let mut response = self
+            .http
+            .post(self.stream_url())
+            .timeout(self.stream_timeout)
+            .json(&request_body(prompt))
+            .send()
+            .await
+            .context("Gemini request failed")?;
+
+        if !response.status().is_success() {
+            return Err(gemini_http_error(response).await.into());
+        }
+
+        let mut reader = LineReader::default();
+        let mut text = String::new();
+        let mut usage = TokenUsage::default();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .context("Gemini response stream failed")?
+        {
+            for line in reader.push(&chunk) {
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                let payload: GeminiGenerateContentResponse =
+                    serde_json::from_str(data.trim()).context("Failed to parse Gemini response")?;
+                for part in payload
+                    .candidates
+                    .iter()
+                    .flat_map(|c| c.content.parts.iter())
+                    .filter_map(|p| p.text.as_deref())
+                {
+                    on_text(part);
+                    text.push_str(part);
+                }
+                // Each event reports the totals so far
+                if let Some(metadata) = payload.usage_metadata {
+                    usage = TokenUsage {
+                        input_tokens: metadata.prompt_token_count,
+                        output_tokens: metadata.candidates_token_count,
+                    };
+                }
+            }
+        }
 
-        Ok(summary)
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            anyhow::bail!("Gemini response did not contain any text");
+        }
+        Ok(SummaryResponse { text, usage })
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(GEMINI_EMBED_BATCH) {
+            let model = format!("models/{}", self.embedding_model);
+            let body = GeminiBatchEmbedRequest {
+                requests: batch
+                    .iter()
+                    .map(|text| GeminiEmbedRequest {
+                        model: &model,
+                        content: GeminiContent {
+                            parts: vec![GeminiPart { text: text.clone() }],
+                        },
+                    })
+                    .collect(),
+            };
+
+            let response = self
+                .http
+                .post(self.embed_url())
+                .json(&body)
+                .send()
+                .await
+                .context("Gemini embedding request failed")?;
+            if !response.status().is_success() {
+                return Err(gemini_http_error(response).await.into());
+            }
+
+            let payload: GeminiBatchEmbedResponse = response
+                .json()
+                .await
+                .context("Failed to parse Gemini embedding response")?;
+            if payload.embeddings.len() != batch.len() {
+                anyhow::bail!(
+                    "Gemini returned {} embeddings for {} texts",
+                    payload.embeddings.len(),
+                    batch.len()
+                );
+            }
+            vectors.extend(payload.embeddings.into_iter().map(|e| e.values));
+        }
+        Ok(vectors)
     }
 }
 
 #[derive(Debug, Serialize)]
 struct GeminiGenerateContentRequest {
     contents: Vec<GeminiContent>,
+    #[serde(rename = "generationConfig", skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GeminiGenerationConfig>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiGenerationConfig {
+    response_mime_type: &'static str,
+    response_schema: Value,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -125,6 +362,16 @@ struct GeminiPart {
 struct GeminiGenerateContentResponse {
     #[serde(default)]
     candidates: Vec<GeminiCandidate>,
+    #[serde(rename = "usageMetadata")]
+    usage_metadata: Option<GeminiUsageMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiUsageMetadata {
+    #[serde(rename = "promptTokenCount", default)]
+    prompt_token_count: u64,
+    #[serde(rename = "candidatesTokenCount", default)]
+    candidates_token_count: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -143,6 +390,28 @@ struct GeminiPartResponse {
     text: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct GeminiBatchEmbedRequest<'a> {
+    requests: Vec<GeminiEmbedRequest<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiEmbedRequest<'a> {
+    model: &'a str,
+    content: GeminiContent,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiBatchEmbedResponse {
+    #[serde(default)]
+    embeddings: Vec<GeminiEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiEmbedding {
+    values: Vec<f32>,
+}
+
 #[derive(Debug, Deserialize)]
 struct GeminiErrorResponse {
     error: GeminiErrorPayload,
@@ -153,6 +422,31 @@ struct GeminiErrorPayload {
     code: Option<u16>,
     message: Option<String>,
     status: Option<String>,
+    #[serde(default)]
+    details: Vec<serde_json::Value>,
+}
+
+async fn gemini_http_error(response: reqwest::Response) -> LlmHttpError {
+    let status = response.status();
+    let header_wait = retry_after(response.headers());
+    let body = response.text().await.unwrap_or_default();
+    LlmHttpError {
+        status: status.as_u16(),
+        retry_after: header_wait.or_else(|| gemini_retry_delay(&body)),
+        message: format_gemini_http_error(status, &body),
+    }
+}
+
+/// Wait asked for by a quota error, given in its details as `"retryDelay": "37s"`
+fn gemini_retry_delay(body: &str) -> Option<Duration> {
+    let payload: GeminiErrorResponse = serde_json::from_str(body).ok()?;
+    payload.error.details.iter().find_map(|detail| {
+        let secs = detail.get("retryDelay")?.as_str()?.strip_suffix('s')?;
+        secs.parse::<f64>()
+            .ok()
+            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+            .map(Duration::from_secs_f64)
+    })
 }
 
 fn format_gemini_http_error(status: reqwest::StatusCode, body: &str) -> String {
@@ -221,3 +515,39 @@ fn gemini_status_hint(status: reqwest::StatusCode) -> Option<&'static str> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_json_schema_for_response_schema() {
+        let schema = serde_json::json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "task": {"type": "string"},
+                    "owner": {"type": ["string", "null"]}
+                },
+                "required": ["task"]
+            }
+        });
+
+        assert_eq!(
+            gemini_schema(&schema),
+            serde_json::json!({
+                "type": "ARRAY",
+                "items": {
+                    "type": "OBJECT",
+                    "properties": {
+                        "task": {"type": "STRING"},
+                        "owner": {"type": "STRING", "nullable": true}
+                    },
+                    "required": ["task"]
+                }
+            })
+        );
+    }
+}
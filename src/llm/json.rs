@@ -0,0 +1,187 @@
+//! Structured JSON replies for extractions
+//!
+//! Action items, decisions, chapters, minutes and tags are asked for as JSON
+//! matching a schema. Gemini and Ollama constrain generation to the schema;
+//! other providers only see the request in the prompt. Replies are checked
+//! locally either way: text and code fences around the JSON are dropped,
+//! trailing commas and a reply cut off mid-array are repaired, and elements
+//! that do not fit are skipped. A reply that still cannot be read is sent
+//! back to the model once to be fixed.
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::llm::client::LlmProvider;
+use crate::llm::prompts::build_json_repair_prompt;
+
+/// Ask for a JSON reply matching `schema` and read it with `parse`
+///
+/// `what` names the content in errors, e.g. "action items".
+pub(super) async fn complete_json<T>(
+    provider: &dyn LlmProvider,
+    prompt: &str,
+    schema: &Value,
+    what: &str,
+    parse: impl Fn(&str) -> Result<T>,
+) -> Result<T> {
+    let response = provider.complete_json(prompt, schema).await?;
+    let error = match parse(&response.text) {
+        Ok(parsed) => return Ok(parsed),
+        Err(error) => error,
+    };
+
+    tracing::warn!("Asking the LLM to fix its reply: {}", error);
+    let repair = build_json_repair_prompt(what, &response.text, &format!("{:#}", error));
+    let response = provider.complete_json(&repair, schema).await?;
+    parse(&response.text)
+}
+
+/// The JSON array of a reply
+pub(super) fn json_array(reply: &str, what: &str) -> Result<Vec<Value>> {
+    match find_json(reply, '[', ']') {
+        Some(Value::Array(values)) => Ok(values),
+        _ => anyhow::bail!("LLM reply did not contain a JSON array of {}", what),
+    }
+}
+
+/// The JSON object of a reply
+pub(super) fn json_object(reply: &str, what: &str) -> Result<Map<String, Value>> {
+    match find_json(reply, '{', '}') {
+        Some(Value::Object(object)) => Ok(object),
+        _ => anyhow::bail!("LLM reply did not contain a JSON object of {}", what),
+    }
+}
+
+/// Elements of `values` that read as `T`; the others are skipped with a warning
+pub(super) fn valid_items<T: DeserializeOwned>(values: Vec<Value>, what: &str) -> Vec<T> {
+    let total = values.len();
+    let items: Vec<T> = values
+        .into_iter()
+        .filter_map(|value| serde_json::from_value(value).ok())
+        .collect();
+    if items.len() < total {
+        tracing::warn!(
+            "Skipped {} malformed {} in the LLM reply",
+            total - items.len(),
+            what
+        );
+    }
+    items
+}
+
+/// Parse the JSON from the first `open` of a reply to its last `close`,
+/// repairing it when it does not parse as is
+///
+/// A reply without `close` is taken to the end, as output cut off at the
+/// token limit is.
+fn find_json(reply: &str, open: char, close: char) -> Option<Value> {
+    let start = reply.find(open)?;
+    let json = match reply.rfind(close) {
+        Some(end) if end > start => &reply[start..=end],
+        _ => &reply[start..],
+    };
+    serde_json::from_str(json)
+        .or_else(|_| serde_json::from_str(&repair(json)))
+        .ok()
+}
+
+/// Drop trailing commas and close the strings and brackets left open
+fn repair(json: &str) -> String {
+    let mut repaired = String::with_capacity(json.len());
+    let mut open = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in json.chars() {
+        if in_string {
+            repaired.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '[' => open.push(']'),
+            '{' => open.push('}'),
+            ']' | '}' => {
+                drop_trailing_comma(&mut repaired);
+                open.pop();
+            }
+            _ => {}
+        }
+        repaired.push(c);
+    }
+
+    if in_string {
+        if escaped {
+            repaired.pop();
+        }
+        repaired.push('"');
+    }
+    for close in open.into_iter().rev() {
+        drop_trailing_comma(&mut repaired);
+        repaired.push(close);
+    }
+    repaired
+}
+
+fn drop_trailing_comma(json: &mut String) {
+    let trimmed = json.trim_end().len();
+    if json[..trimmed].ends_with(',') {
+        json.truncate(trimmed - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        task: String,
+    }
+
+    #[test]
+    fn repairs_trailing_commas_and_cut_off_replies() {
+        let reply = "Sure:\n```json\n[{\"task\": \"Book a room\",}, {\"task\": \"Send the agenda";
+
+        let values = json_array(reply, "action items").unwrap();
+        let items: Vec<Item> = valid_items(values, "action items");
+
+        assert_eq!(
+            items,
+            [
+                Item {
+                    task: "Book a room".to_string()
+                },
+                Item {
+                    task: "Send the agenda".to_string()
+                },
+            ]
+        );
+        assert_eq!(repair("{\"a\": [1, 2,"), "{\"a\": [1, 2]}");
+        assert_eq!(repair("[\"a\\"), "[\"a\"]");
+    }
+
+    #[test]
+    fn skips_elements_that_do_not_fit() {
+        let values =
+            json_array("[{\"task\": \"Ship\"}, {\"owner\": \"Ada\"}, 3]", "tasks").unwrap();
+
+        let items: Vec<Item> = valid_items(values, "tasks");
+
+        assert_eq!(
+            items,
+            [Item {
+                task: "Ship".to_string()
+            }]
+        );
+        assert!(json_array("No items.", "tasks").is_err());
+        assert!(json_object("[1]", "minutes").is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! In-process LLM with llama.cpp
+//!
+//! Loads a GGUF model straight into the process, so summaries, answers and
+//! the other LLM commands work fully offline without an Ollama server. Models
+//! are kept in the models directory next to the Whisper models. Requires
+//! building with the `llama` feature, which compiles llama.cpp.
+
+/// Take the complete UTF-8 text off the front of `bytes`
+///
+/// A token can end partway through a multi-byte character; its remaining
+/// bytes stay in `bytes` until the next token completes it.
+#[cfg_attr(not(feature = "llama"), allow(dead_code))]
+fn take_complete_utf8(bytes: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(bytes) {
+        Ok(_) => bytes.len(),
+        // An invalid sequence is replaced rather than held back forever
+        Err(error) if error.error_len().is_some() => bytes.len(),
+        Err(error) => error.valid_up_to(),
+    };
+    let rest = bytes.split_off(complete);
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    *bytes = rest;
+    text
+}
+
+#[cfg(feature = "llama")]
+pub use engine::LlamaClient;
+
+#[cfg(feature = "llama")]
+mod engine {
+    use std::num::NonZeroU32;
+    use std::sync::{Arc, OnceLock};
+
+    use anyhow::{Context, Result};
+    use async_trait::async_trait;
+    use llama_cpp_2::context::params::LlamaContextParams;
+    use llama_cpp_2::llama_backend::LlamaBackend;
+    use llama_cpp_2::llama_batch::LlamaBatch;
+    use llama_cpp_2::model::params::LlamaModelParams;
+    use llama_cpp_2::model::{AddBos, LlamaChatMessage, LlamaModel, Special};
+    use llama_cpp_2::sampling::LlamaSampler;
+    use tokio::sync::mpsc;
+
+    use super::take_complete_utf8;
+    use crate::config::Settings;
+    use crate::llm::client::{LlmProvider, SummaryResponse, TextSink, TokenUsage};
+
+    /// Largest context allocated, whatever the model was trained on; memory
+    /// for the KV cache grows with it
+    const MAX_CONTEXT_TOKENS: u32 = 32_768;
+
+    /// Prompt tokens decoded per batch
+    const BATCH_TOKENS: usize = 512;
+
+    /// llama.cpp can only be initialized once per process
+    static BACKEND: OnceLock<Result<LlamaBackend, String>> = OnceLock::new();
+
+    fn backend() -> Result<&'static LlamaBackend> {
+        BACKEND
+            .get_or_init(|| {
+                let mut backend = LlamaBackend::init().map_err(|e| e.to_string())?;
+                backend.void_logs();
+                Ok(backend)
+            })
+            .as_ref()
+            .map_err(|e| anyhow::anyhow!("Failed to initialize llama.cpp: {}", e))
+    }
+
+    /// Provider running a GGUF model in this process
+    pub struct LlamaClient {
+        model: Arc<LlamaModel>,
+        model_name: String,
+    }
+
+    impl LlamaClient {
+        pub fn from_settings(settings: &Settings) -> Result<Self> {
+            let path = settings.llm_model_file();
+            if !path.is_file() {
+                anyhow::bail!(
+                    "GGUF model not found: {}. Download one into {} and set llm.model to its file name.",
+                    path.display(),
+                    settings.whisper.models_dir.display()
+                );
+            }
+
+            tracing::info!("Loading GGUF model from {}", path.display());
+            let model = LlamaModel::load_from_file(backend()?, &path, &LlamaModelParams::default())
+                .with_context(|| format!("Failed to load GGUF model from {}", path.display()))?;
+            let model_name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            Ok(Self {
+                model: Arc::new(model),
+                model_name,
+            })
+        }
+    }
+
+    /// Wrap `prompt` in the model's chat template so instruction-tuned models answer it
+    fn chat_prompt(model: &LlamaModel, prompt: &str) -> Result<String> {
+        let Ok(template) = model.chat_template(None) else {
+            // Base models without a template continue the text as is
+            return Ok(prompt.to_string());
+        };
+        let message = LlamaChatMessage::new("user".to_string(), prompt.to_string())?;
+        model
+            .apply_chat_template(&template, &[message], true)
+            .context("Failed to apply the model's chat template")
+    }
+
+    /// Generate a reply to `prompt`, sending its text to `sink` as it is decoded
+    ///
+    /// Blocks until the model is done; generation stops early when `sink`
+    /// is dropped.
+    fn generate(
+        model: &LlamaModel,
+        prompt: &str,
+        sink: mpsc::UnboundedSender<String>,
+    ) -> Result<TokenUsage> {
+        let prompt = chat_prompt(model, prompt)?;
+        let tokens = model
+            .str_to_token(&prompt, AddBos::Always)
+            .context("Failed to tokenize the prompt")?;
+        let context_tokens = model.n_ctx_train().min(MAX_CONTEXT_TOKENS);
+        if tokens.len() as u32 >= context_tokens {
+            anyhow::bail!(
+                "Prompt of {} tokens does not fit the model's context of {}; lower llm.summary_chunk_chars",
+                tokens.len(),
+                context_tokens
+            );
+        }
+
+        let threads = std::thread::available_parallelism()
+            .map(|threads| threads.get() as i32)
+            .unwrap_or(4);
+        let params = LlamaContextParams::default()
+            .with_n_ctx(NonZeroU32::new(context_tokens))
+            .with_n_threads(threads)
+            .with_n_threads_batch(threads);
+        let mut context = model
+            .new_context(backend()?, params)
+            .context("Failed to create a llama.cpp context")?;
+
+        let mut batch = LlamaBatch::new(BATCH_TOKENS, 1);
+        let mut position = 0;
+        for chunk in tokens.chunks(BATCH_TOKENS) {
+            batch.clear();
+            for &token in chunk {
+                let last = position as usize == tokens.len() - 1;
+                batch.add(token, position, &[0], last)?;
+                position += 1;
+            }
+            context
+                .decode(&mut batch)
+                .context("llama.cpp failed to read the prompt")?;
+        }
+
+        // Greedy sampling keeps summaries of the same transcript the same
+        let mut sampler = LlamaSampler::greedy();
+        let mut pending = Vec::new();
+        let mut output_tokens = 0;
+        while (position as u32) < context_tokens {
+            let token = sampler.sample(&context, batch.n_tokens() - 1);
+            sampler.accept(token);
+            if model.is_eog_token(token) {
+                break;
+            }
+            output_tokens += 1;
+
+            pending.extend(model.token_to_bytes(token, Special::Plaintext)?);
+            let text = take_complete_utf8(&mut pending);
+            if !text.is_empty() && sink.send(text).is_err() {
+                break;
+            }
+
+            batch.clear();
+            batch.add(token, position, &[0], true)?;
+            position += 1;
+            context
+                .decode(&mut batch)
+                .context("llama.cpp failed to generate the reply")?;
+        }
+
+        Ok(TokenUsage {
+            input_tokens: tokens.len() as u64,
+            output_tokens,
+        })
+    }
+
+    #[async_trait]
+    impl LlmProvider for LlamaClient {
+        fn name(&self) -> &str {
+            "llama"
+        }
+
+        fn model(&self) -> &str {
+            &self.model_name
+        }
+
+        /// The model runs in this process, so transcripts never leave the machine
+        fn is_cloud(&self) -> bool {
+            false
+        }
+
+        async fn complete(&self, prompt: &str) -> Result<SummaryResponse> {
+            self.complete_streaming(prompt, &mut |_: &str| {}).await
+        }
+
+        async fn complete_streaming(
+            &self,
+            prompt: &str,
+            on_text: TextSink<'_>,
+        ) -> Result<SummaryResponse> {
+            let (sender, mut receiver) = mpsc::unbounded_channel();
+            let model = Arc::clone(&self.model);
+            let prompt = prompt.to_string();
+            let generation = tokio::task::spawn_blocking(move || generate(&model, &prompt, sender));
+
+            let mut text = String::new();
+            while let Some(piece) = receiver.recv().await {
+                on_text(&piece);
+                text.push_str(&piece);
+            }
+            let usage = generation
+                .await
+                .context("llama.cpp generation panicked")??;
+
+            Ok(SummaryResponse { text, usage })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_back_characters_split_across_tokens() {
+        // "é" is two bytes; the first token ends after the first one
+        let mut bytes = b"caf\xc3".to_vec();
+        assert_eq!(take_complete_utf8(&mut bytes), "caf");
+        assert_eq!(bytes, b"\xc3");
+
+        bytes.extend(b"\xa9 ok");
+        assert_eq!(take_complete_utf8(&mut bytes), "é ok");
+        assert!(bytes.is_empty());
+
+        let mut bytes = b"a\xffb".to_vec();
+        assert_eq!(take_complete_utf8(&mut bytes), "a\u{fffd}b");
+        assert!(bytes.is_empty());
+    }
+}
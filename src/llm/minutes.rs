@@ -0,0 +1,219 @@
+//! Formal meeting minutes from transcripts
+//!
+//! The model is asked for a JSON object with the attendees, the agenda items
+//! discussed, the decisions and the action items. Long transcripts are split
+//! like for questions, and the minutes of all parts are joined in order,
+//! dropping attendees, decisions and tasks listed twice.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::llm::actions::{action_items_schema, tidy_action_items, ExtractedAction};
+use crate::llm::answer::{split_transcript, MAX_PART_CHARS};
+use crate::llm::chapters::parse_clock;
+use crate::llm::client::LlmProvider;
+use crate::llm::json::{complete_json, json_object, valid_items};
+use crate::llm::prompts::build_minutes_prompt;
+
+/// Minutes of a meeting as written by the model.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeetingMinutes {
+    pub attendees: Vec<String>,
+    pub agenda: Vec<AgendaItem>,
+    pub decisions: Vec<String>,
+    pub action_items: Vec<ExtractedAction>,
+}
+
+/// A topic of the meeting and what was said about it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgendaItem {
+    pub topic: String,
+    pub discussion: String,
+    /// When the topic came up, in seconds
+    pub start: Option<f64>,
+}
+
+/// Minutes as written in the reply; entries are checked one by one
+#[derive(Deserialize)]
+struct MinutesReply {
+    #[serde(default)]
+    attendees: Vec<Value>,
+    #[serde(default)]
+    agenda: Vec<Value>,
+    #[serde(default)]
+    decisions: Vec<Value>,
+    #[serde(default)]
+    action_items: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct AgendaReply {
+    topic: String,
+    #[serde(default)]
+    discussion: String,
+    #[serde(default)]
+    timestamp: Option<String>,
+}
+
+/// Write the minutes of a meeting.
+///
+/// `transcript` is in the same `[mm:ss]` line format as for questions;
+/// `participants` are the attendees already known, listed first.
+pub async fn write_minutes(
+    provider: &dyn LlmProvider,
+    title: &str,
+    participants: &[String],
+    transcript: &str,
+) -> Result<MeetingMinutes> {
+    let parts = split_transcript(transcript, MAX_PART_CHARS);
+    let mut minutes = MeetingMinutes {
+        attendees: participants.to_vec(),
+        ..MeetingMinutes::default()
+    };
+    for (i, part) in parts.iter().enumerate() {
+        let numbered = (parts.len() > 1).then_some((i + 1, parts.len()));
+        let prompt = build_minutes_prompt(title, participants, part, numbered);
+        let extracted = complete_json(
+            provider,
+            &prompt,
+            &minutes_schema(),
+            "minutes",
+            parse_minutes,
+        )
+        .await?;
+        minutes.extend(extracted);
+    }
+    Ok(minutes)
+}
+
+impl MeetingMinutes {
+    /// Append the minutes of the next part of a meeting
+    fn extend(&mut self, part: MeetingMinutes) {
+        for attendee in part.attendees {
+            if !self
+                .attendees
+                .iter()
+                .any(|a| a.eq_ignore_ascii_case(&attendee))
+            {
+                self.attendees.push(attendee);
+            }
+        }
+        self.agenda.extend(part.agenda);
+        for decision in part.decisions {
+            if !self
+                .decisions
+                .iter()
+                .any(|d| d.eq_ignore_ascii_case(&decision))
+            {
+                self.decisions.push(decision);
+            }
+        }
+        for item in part.action_items {
+            if !self
+                .action_items
+                .iter()
+                .any(|existing| existing.task.eq_ignore_ascii_case(&item.task))
+            {
+                self.action_items.push(item);
+            }
+        }
+    }
+}
+
+/// Schema of the JSON object of minutes
+fn minutes_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "attendees": {"type": "array", "items": {"type": "string"}},
+            "agenda": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "topic": {"type": "string"},
+                        "discussion": {"type": "string"},
+                        "timestamp": {"type": ["string", "null"]}
+                    },
+                    "required": ["topic", "discussion", "timestamp"]
+                }
+            },
+            "decisions": {"type": "array", "items": {"type": "string"}},
+            "action_items": action_items_schema()
+        },
+        "required": ["attendees", "agenda", "decisions", "action_items"]
+    })
+}
+
+/// Read the JSON object of a reply, tolerating code fences and text around it
+///
+/// Empty and malformed entries are dropped, and agenda timestamps that are
+/// not `mm:ss` or `hh:mm:ss` are left out.
+fn parse_minutes(reply: &str) -> Result<MeetingMinutes> {
+    let reply: MinutesReply = serde_json::from_value(Value::Object(json_object(reply, "minutes")?))
+        .context("Failed to parse minutes from LLM reply")?;
+
+    let non_empty = |values: Vec<String>| {
+        values
+            .into_iter()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<_>>()
+    };
+    Ok(MeetingMinutes {
+        attendees: non_empty(valid_items(reply.attendees, "attendees")),
+        agenda: valid_items::<AgendaReply>(reply.agenda, "agenda items")
+            .into_iter()
+            .filter_map(|item| {
+                let topic = item.topic.trim().to_string();
+                (!topic.is_empty()).then(|| AgendaItem {
+                    topic,
+                    discussion: item.discussion.trim().to_string(),
+                    start: item.timestamp.as_deref().and_then(|timestamp| {
+                        parse_clock(timestamp.trim().trim_matches(['[', ']']))
+                    }),
+                })
+            })
+            .collect(),
+        decisions: non_empty(valid_items(reply.decisions, "decisions")),
+        action_items: tidy_action_items(valid_items(reply.action_items, "action items")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_minutes_and_joins_parts() {
+        let reply = "```json\n{\n  \
+\"attendees\": [\"Ada\", \" \", \"Grace\"],\n  \
+\"agenda\": [\n    \
+{\"topic\": \"Budget\", \"discussion\": \"Costs are up.\", \"timestamp\": \"[01:15]\"},\n    \
+{\"topic\": \"\", \"discussion\": \"Small talk\"}\n  ],\n  \
+\"decisions\": [\"Ship on Monday\"],\n  \
+\"action_items\": [{\"owner\": \"\", \"task\": \"Book a room\"}, {\"owner\": \"Ada\"}]\n}\n```";
+
+        let mut minutes = MeetingMinutes {
+            attendees: vec!["ada".to_string()],
+            ..MeetingMinutes::default()
+        };
+        minutes.extend(parse_minutes(reply).unwrap());
+
+        assert_eq!(minutes.attendees, ["ada", "Grace"]);
+        assert_eq!(
+            minutes.agenda,
+            [AgendaItem {
+                topic: "Budget".to_string(),
+                discussion: "Costs are up.".to_string(),
+                start: Some(75.0),
+            }]
+        );
+        assert_eq!(minutes.decisions, ["Ship on Monday"]);
+        assert_eq!(minutes.action_items.len(), 1);
+        assert_eq!(minutes.action_items[0].owner, None);
+        assert!(parse_minutes("{}").unwrap().agenda.is_empty());
+        assert!(parse_minutes("No minutes today.").is_err());
+    }
+}
@@ -1,11 +1,50 @@
 //! LLM module for minutes (post-MVP)
 //!
-//! Handles AI-powered summaries and Q&A using Gemini API.
+//! Handles AI-powered summaries, titles, tags, chapters, Q&A, action items,
+//! decisions, meeting minutes, participation narratives and semantic search
+//! embeddings using the Gemini API, a local Ollama server or a GGUF model run
+//! in-process.
 
+mod actions;
+mod answer;
+mod chapters;
 mod client;
+mod decisions;
+mod error;
+mod failover;
 mod gemini;
+mod json;
+mod llama;
+mod minutes;
+mod ollama;
+mod participation;
 mod prompts;
+mod redact;
+mod retry;
+mod stream;
+mod tags;
+mod template;
+mod title;
 
-pub use client::{build_provider, LlmProvider, SummaryRequest};
+pub use actions::{extract_action_items, ExtractedAction};
+pub use answer::{
+    answer_library_question, answer_question, LibraryExcerpt, LibraryQuestionRequest,
+    QuestionRequest,
+};
+pub use chapters::{generate_chapters, ChapterMarker};
+pub use client::{
+    build_provider, cloud_allowed, ensure_cloud_allowed, Chunking, LlmProvider, SummaryRequest,
+    SummaryResponse, TextSink, TokenUsage, EMBED_BATCH_SIZE,
+};
+pub use decisions::{extract_decisions, ExtractedDecision};
 pub use gemini::GeminiClient;
-pub use prompts::build_summary_prompt;
+#[cfg(feature = "llama")]
+pub use llama::LlamaClient;
+pub use minutes::{write_minutes, AgendaItem, MeetingMinutes};
+pub use ollama::OllamaClient;
+pub use participation::describe_participation;
+pub use prompts::{build_summary_prompt, SUMMARY_PROMPT_VERSION};
+pub use redact::{redact_pii, RedactionReport};
+pub use tags::suggest_tags;
+pub use template::PromptTemplate;
+pub use title::generate_title;
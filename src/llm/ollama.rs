@@ -0,0 +1,320 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Settings;
+use crate::llm::client::{LlmProvider, SummaryResponse, TextSink, TokenUsage};
+use crate::llm::error::{retry_after, LlmHttpError};
+use crate::llm::stream::LineReader;
+
+const DEFAULT_OLLAMA_ENDPOINT: &str = "http://localhost:11434";
+const DEFAULT_OLLAMA_MODEL: &str = "llama3.1";
+const DEFAULT_OLLAMA_EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+/// Local models can take minutes on a long transcript, especially on CPU
+const OLLAMA_TIMEOUT_SECS: u64 = 600;
+
+pub struct OllamaClient {
+    http: Client,
+    model: String,
+    embedding_model: String,
+    endpoint: String,
+}
+
+impl OllamaClient {
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        // The Gemini default model left in the config means no model was chosen
+        let model = match settings.llm.model.trim() {
+            "" => DEFAULT_OLLAMA_MODEL.to_string(),
+            model if model.starts_with("gemini-") => DEFAULT_OLLAMA_MODEL.to_string(),
+            model => model.to_string(),
+        };
+
+        let embedding_model = match settings.llm.embedding_model.trim() {
+            "" => DEFAULT_OLLAMA_EMBEDDING_MODEL.to_string(),
+            model => model.to_string(),
+        };
+
+        let endpoint = if settings.llm.endpoint.trim().is_empty() {
+            DEFAULT_OLLAMA_ENDPOINT.to_string()
+        } else {
+            settings
+                .llm
+                .endpoint
+                .trim()
+                .trim_end_matches('/')
+                .to_string()
+        };
+
+        Ok(Self {
+            http: Client::builder()
+                .timeout(
+                    settings
+                        .llm
+                        .timeout(std::time::Duration::from_secs(OLLAMA_TIMEOUT_SECS)),
+                )
+                .build()
+                .context("Failed to build Ollama HTTP client")?,
+            model,
+            embedding_model,
+            endpoint,
+        })
+    }
+
+    /// Start a chat request, streamed or not, with a JSON Schema for the reply
+    async fn send(
+        &self,
+        prompt: &str,
+        stream: bool,
+        format: Option<&Value>,
+    ) -> Result<reqwest::Response> {
+        let body = OllamaChatRequest {
+            model: &self.model,
+            messages: vec![OllamaMessage {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            stream,
+            format,
+        };
+        self.post("/api/chat", &body, &self.model).await
+    }
+
+    /// POST `body` to an API path, turning error statuses into errors
+    async fn post<T: Serialize>(
+        &self,
+        path: &str,
+        body: &T,
+        model: &str,
+    ) -> Result<reqwest::Response> {
+        let response = self
+            .http
+            .post(format!("{}{}", self.endpoint, path))
+            .json(body)
+            .send()
+            .await
+            .with_context(|| {
+                format!(
+                    "Ollama request to {} failed. Is `ollama serve` running?",
+                    self.endpoint
+                )
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let wait = retry_after(response.headers());
+            let body = response.text().await.unwrap_or_default();
+            return Err(LlmHttpError {
+                status: status.as_u16(),
+                retry_after: wait,
+                message: format_ollama_http_error(status, &body, model),
+            }
+            .into());
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaClient {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embedding_model(&self) -> Option<&str> {
+        Some(&self.embedding_model)
+    }
+
+    /// Only an Ollama server on this machine keeps transcripts local
+    fn is_cloud(&self) -> bool {
+        let host = reqwest::Url::parse(&self.endpoint)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        !matches!(
+            host.as_deref(),
+            Some("localhost" | "127.0.0.1" | "[::1]" | "::1")
+        )
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<SummaryResponse> {
+        read_reply(self.send(prompt, false, None).await?).await
+    }
+
+    /// Structured outputs constrain the reply to `schema`
+    async fn complete_json(&self, prompt: &str, schema: &Value) -> Result<SummaryResponse> {
+        read_reply(self.send(prompt, false, Some(schema)).await?).await
+    }
+
+    /// Stream the response as one JSON object per line; the last one has `done` set
+    async fn complete_streaming(
+        &self,
+        prompt: &str,
+        on_text: TextSink<'_>,
+    ) -> Result<SummaryResponse> {
+        let mut response = self.send(prompt, true, None).await?;
+
+        let mut reader = LineReader::default();
+        let mut text = String::new();
+        let mut usage = TokenUsage::default();
+        loop {
+            let chunk = response
+                .chunk()
+                .await
+                .context("Ollama response stream failed")?;
+            let lines = match chunk {
+                Some(chunk) => reader.push(&chunk),
+                None => {
+                    let rest = std::mem::take(&mut reader).finish();
+                    if rest.is_none() {
+                        break;
+                    }
+                    rest.into_iter().collect()
+                }
+            };
+            for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+                let payload: OllamaChatResponse =
+                    serde_json::from_str(line).context("Failed to parse Ollama response")?;
+                if let Some(error) = payload.error {
+                    anyhow::bail!("Ollama request failed: {}", error.trim());
+                }
+                if let Some(message) = payload.message {
+                    on_text(&message.content);
+                    text.push_str(&message.content);
+                }
+                if payload.done {
+                    usage = TokenUsage {
+                        input_tokens: payload.prompt_eval_count,
+                        output_tokens: payload.eval_count,
+                    };
+                }
+            }
+        }
+
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            anyhow::bail!("Ollama response did not contain any text");
+        }
+        Ok(SummaryResponse { text, usage })
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let body = OllamaEmbedRequest {
+            model: &self.embedding_model,
+            input: texts,
+        };
+        let response = self
+            .post("/api/embed", &body, &self.embedding_model)
+            .await?;
+
+        let payload: OllamaEmbedResponse = response
+            .json()
+            .await
+            .context("Failed to parse Ollama embedding response")?;
+        if payload.embeddings.len() != texts.len() {
+            anyhow::bail!(
+                "Ollama returned {} embeddings for {} texts",
+                payload.embeddings.len(),
+                texts.len()
+            );
+        }
+        Ok(payload.embeddings)
+    }
+}
+
+/// Read the text of a chat reply that was not streamed
+async fn read_reply(response: reqwest::Response) -> Result<SummaryResponse> {
+    let payload: OllamaChatResponse = response
+        .json()
+        .await
+        .context("Failed to parse Ollama response")?;
+
+    let text = payload
+        .message
+        .map(|message| message.content.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .context("Ollama response did not contain any text")?;
+
+    Ok(SummaryResponse {
+        text,
+        usage: TokenUsage {
+            input_tokens: payload.prompt_eval_count,
+            output_tokens: payload.eval_count,
+        },
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<OllamaMessage>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OllamaMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaChatResponse {
+    message: Option<OllamaMessage>,
+    /// Set on the last object of a streamed response
+    #[serde(default)]
+    done: bool,
+    /// A streamed response reports failures after the headers were sent
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: u64,
+    #[serde(default)]
+    eval_count: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaEmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbedResponse {
+    #[serde(default)]
+    embeddings: Vec<Vec<f32>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaErrorResponse {
+    error: String,
+}
+
+fn format_ollama_http_error(status: reqwest::StatusCode, body: &str, model: &str) -> String {
+    let status_text = status.canonical_reason().unwrap_or("Unknown Status");
+    let mut message = format!(
+        "Ollama request failed ({} {})",
+        status.as_u16(),
+        status_text
+    );
+
+    let detail = serde_json::from_str::<OllamaErrorResponse>(body)
+        .map(|payload| payload.error.trim().to_string())
+        .unwrap_or_else(|_| body.split_whitespace().collect::<Vec<_>>().join(" "));
+    if !detail.is_empty() {
+        message.push_str(": ");
+        message.push_str(&detail);
+    }
+
+    if status.as_u16() == 404 {
+        message.push_str(&format!(". Run `ollama pull {}` or set llm.model", model));
+    }
+
+    message
+}
@@ -0,0 +1,33 @@
+//! Narrative on how a meeting's speakers took part
+//!
+//! The talk-time figures are computed from the transcript beforehand; the
+//! model only puts them into words, with the start of the transcript for
+//! what the meeting was about.
+
+use anyhow::Result;
+
+use crate::llm::answer::{split_transcript, MAX_PART_CHARS};
+use crate::llm::client::{LlmProvider, SummaryResponse, TextSink};
+use crate::llm::prompts::build_participation_prompt;
+
+/// Describe the participation in a meeting from its per-speaker figures.
+///
+/// `stats` is a plain-text table of the figures, `transcript` is in the same
+/// `[mm:ss]` line format as for questions.
+pub async fn describe_participation(
+    provider: &dyn LlmProvider,
+    title: &str,
+    stats: &str,
+    transcript: &str,
+    on_text: Option<TextSink<'_>>,
+) -> Result<SummaryResponse> {
+    let start = split_transcript(transcript, MAX_PART_CHARS)
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let prompt = build_participation_prompt(title, stats, start);
+    match on_text {
+        Some(on_text) => provider.complete_streaming(&prompt, on_text).await,
+        None => provider.complete(&prompt).await,
+    }
+}
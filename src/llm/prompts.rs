@@ -1,19 +1,513 @@
+use crate::llm::answer::LibraryExcerpt;
+
+/// Version of the summary prompt, stored with each summary.
+///
+/// Bump when the prompt changes in a way that affects the output.
+pub const SUMMARY_PROMPT_VERSION: u32 = 2;
+
+/// Sections of a summary, in the order the model is asked to write them.
+const SUMMARY_SECTIONS: &str = "\
+1. ## Summary (3-6 bullets)\n\
+2. ## Decisions\n\
+3. ## Action Items\n\
+4. ## Open Questions\n";
+
+/// Rule on the language of a summary and of the notes it is written from.
+///
+/// `summary_language` asks for a language other than the transcript's;
+/// without it, a summary of a non-English transcript stays in that language.
+fn language_rule(language: Option<&str>, summary_language: Option<&str>) -> String {
+    match (language, summary_language) {
+        (_, Some(summary_language)) => format!(
+            "- Write the bullets in {summary_language}, whatever the language of the \
+transcript; keep the section headings as given.\n"
+        ),
+        (Some(language), None) if !language.eq_ignore_ascii_case("english") => format!(
+            "- Write the bullets in {language}, the language of the transcript; \
+keep the section headings as given.\n"
+        ),
+        _ => String::new(),
+    }
+}
+
+/// Line naming the people who attended a meeting, empty when they are not known.
+fn attendees_line(attendees: &[String]) -> String {
+    if attendees.is_empty() {
+        String::new()
+    } else {
+        format!("Attendees: {}\n", attendees.join(", "))
+    }
+}
+
 /// Build a deterministic summary prompt for meeting transcripts.
-pub fn build_summary_prompt(title: &str, transcript: &str) -> String {
+///
+/// Summaries of transcripts in a language other than English are written in
+/// that language, unless `summary_language` asks for another one.
+pub fn build_summary_prompt(
+    title: &str,
+    attendees: &[String],
+    transcript: &str,
+    language: Option<&str>,
+    summary_language: Option<&str>,
+) -> String {
+    let attendees = attendees_line(attendees);
+    let language_rule = language_rule(language, summary_language);
+
     format!(
         "You are an assistant that writes concise, factual meeting summaries.\n\
 Meeting title: {title}\n\
+{attendees}\
 \n\
 Return Markdown with exactly these sections:\n\
-1. ## Summary (3-6 bullets)\n\
-2. ## Decisions\n\
-3. ## Action Items\n\
-4. ## Open Questions\n\
+{SUMMARY_SECTIONS}\
 \n\
 Rules:\n\
 - Use only information present in the transcript.\n\
 - If a section has no content, write 'None'.\n\
 - Keep each bullet short and concrete.\n\
+{language_rule}\
+\n\
+Transcript:\n\
+{transcript}"
+    )
+}
+
+/// Build a prompt taking notes on one part of a transcript too long to summarize at once.
+///
+/// `part` is `(number, count)`. Parts overlap a little, so the notes of
+/// neighbouring parts may repeat a point.
+pub fn build_notes_prompt(
+    title: &str,
+    attendees: &[String],
+    transcript: &str,
+    part: (usize, usize),
+    language: Option<&str>,
+    summary_language: Option<&str>,
+) -> String {
+    let (number, count) = part;
+    let attendees = attendees_line(attendees);
+    let language_rule = language_rule(language, summary_language);
+
+    format!(
+        "You are an assistant that takes notes on long meetings.\n\
+Meeting title: {title}\n\
+{attendees}\
+This is part {number} of {count} of the transcript.\n\
+\n\
+Write Markdown notes on this part with the points discussed, decisions made, \
+action items with their owners, and open questions.\n\
+\n\
+Rules:\n\
+- Use only information present in the transcript.\n\
+- Keep each bullet short and concrete; keep names, numbers and dates.\n\
+{language_rule}\
+\n\
+Transcript:\n\
+{transcript}"
+    )
+}
+
+/// Join the notes on the parts of a meeting, numbered, for a follow-up prompt.
+pub fn join_notes(notes: &[String]) -> String {
+    notes
+        .iter()
+        .enumerate()
+        .map(|(i, note)| format!("Notes {}:\n{}\n", i + 1, note.trim()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Build a prompt merging the notes on consecutive parts of a meeting into one set of notes.
+pub fn build_merge_notes_prompt(
+    title: &str,
+    attendees: &[String],
+    notes: &[String],
+    language: Option<&str>,
+    summary_language: Option<&str>,
+) -> String {
+    let notes = join_notes(notes);
+    let attendees = attendees_line(attendees);
+    let language_rule = language_rule(language, summary_language);
+
+    format!(
+        "You are an assistant that takes notes on long meetings.\n\
+Meeting title: {title}\n\
+{attendees}\
+\n\
+Below are notes on consecutive parts of the meeting. Merge them into one set \
+of Markdown notes with the points discussed, decisions made, action items with \
+their owners, and open questions.\n\
+\n\
+Rules:\n\
+- Use only information present in the notes.\n\
+- Drop points repeated across notes.\n\
+{language_rule}\
+\n\
+{notes}"
+    )
+}
+
+/// Build a summary prompt from the notes on all parts of a long meeting.
+///
+/// The summary has the same sections as one from [`build_summary_prompt`].
+pub fn build_summary_from_notes_prompt(
+    title: &str,
+    attendees: &[String],
+    notes: &[String],
+    language: Option<&str>,
+    summary_language: Option<&str>,
+) -> String {
+    let notes = join_notes(notes);
+    let attendees = attendees_line(attendees);
+    let language_rule = language_rule(language, summary_language);
+
+    format!(
+        "You are an assistant that writes concise, factual meeting summaries.\n\
+Meeting title: {title}\n\
+{attendees}\
+\n\
+The meeting was too long to read at once, so notes were taken on each part of \
+it. Summarize the whole meeting from these notes.\n\
+\n\
+Return Markdown with exactly these sections:\n\
+{SUMMARY_SECTIONS}\
+\n\
+Rules:\n\
+- Use only information present in the notes.\n\
+- Drop points repeated across notes.\n\
+- If a section has no content, write 'None'.\n\
+- Keep each bullet short and concrete.\n\
+{language_rule}\
+\n\
+{notes}"
+    )
+}
+
+/// Reply asked for when a part of a long transcript says nothing about the question.
+pub const NO_ANSWER: &str = "NOT IN TRANSCRIPT";
+
+/// Build a prompt answering a question from a meeting transcript.
+///
+/// `part` is `(number, count)` when the transcript is one part of a long
+/// meeting; the model is then told to reply [`NO_ANSWER`] if the part does
+/// not cover the question.
+pub fn build_answer_prompt(
+    title: &str,
+    transcript: &str,
+    question: &str,
+    part: Option<(usize, usize)>,
+) -> String {
+    let scope = match part {
+        Some((number, count)) => format!(
+            "This is part {number} of {count} of the transcript. If this part does not help \
+answer the question, reply with exactly {NO_ANSWER}.\n"
+        ),
+        None => "If the transcript does not answer the question, say so.\n".to_string(),
+    };
+
+    format!(
+        "You are an assistant that answers questions about a meeting from its transcript.\n\
+Meeting title: {title}\n\
+\n\
+Rules:\n\
+- Use only information present in the transcript.\n\
+- Cite the timestamp of each line you rely on in brackets, e.g. [12:34].\n\
+- Answer in the language of the question, briefly and concretely.\n\
+{scope}\
+\n\
+Transcript:\n\
+{transcript}\n\
+\n\
+Question: {question}"
+    )
+}
+
+/// Build a prompt merging the answers found in the parts of a long transcript.
+pub fn build_combine_prompt(title: &str, question: &str, answers: &[String]) -> String {
+    let answers = answers
+        .iter()
+        .enumerate()
+        .map(|(i, answer)| format!("Answer {}:\n{}\n", i + 1, answer.trim()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "You are an assistant that answers questions about a meeting.\n\
+Meeting title: {title}\n\
+\n\
+The question was asked of each part of the meeting transcript separately. \
+Combine the answers below into one answer.\n\
+\n\
+Rules:\n\
+- Use only information present in the answers.\n\
+- Keep the timestamps they cite in brackets, e.g. [12:34].\n\
+- Answer in the language of the question, briefly and concretely.\n\
+\n\
+{answers}\n\
+Question: {question}"
+    )
+}
+
+/// Build a prompt answering a question from excerpts of several meetings.
+pub fn build_library_answer_prompt(excerpts: &[LibraryExcerpt], question: &str) -> String {
+    let excerpts = excerpts
+        .iter()
+        .map(|excerpt| {
+            format!(
+                "Meeting: {} ({})\n{}",
+                excerpt.title,
+                excerpt.date,
+                excerpt.transcript.trim_end()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        "You are an assistant that answers questions about a library of meetings from \
+excerpts of their transcripts.\n\
+\n\
+Rules:\n\
+- Use only information present in the excerpts.\n\
+- Cite the meeting title and timestamp of each line you rely on in brackets, \
+e.g. [Weekly sync, 12:34].\n\
+- When meetings disagree, prefer the most recent one and say that it changed.\n\
+- Answer in the language of the question, briefly and concretely.\n\
+- If the excerpts do not answer the question, say so.\n\
+\n\
+Excerpts:\n\
+{excerpts}\n\
+\n\
+Question: {question}"
+    )
+}
+
+/// Build a prompt extracting action items from a meeting transcript as JSON.
+///
+/// `part` is `(number, count)` when the transcript is one part of a long
+/// meeting.
+pub fn build_actions_prompt(title: &str, transcript: &str, part: Option<(usize, usize)>) -> String {
+    let scope = match part {
+        Some((number, count)) => format!(
+            "This is part {number} of {count} of the transcript; list only the action items \
+agreed on in this part.\n"
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        "You are an assistant that extracts action items from a meeting transcript.\n\
+Meeting title: {title}\n\
+\n\
+Rules:\n\
+- Use only information present in the transcript.\n\
+- An action item is a task someone agreed to do after the meeting.\n\
+- Reply with a JSON array only, no other text. Each element is an object with the keys \
+\"owner\" (name of the person responsible, or null), \"task\" (short imperative sentence) \
+and \"due\" (due date as agreed, as YYYY-MM-DD when a date is given, or null).\n\
+- Reply with [] if there are no action items.\n\
+{scope}\
+\n\
+Transcript:\n\
+{transcript}"
+    )
+}
+
+/// Build a prompt extracting the decisions made in a meeting as JSON.
+///
+/// `part` is `(number, count)` when the transcript is one part of a long
+/// meeting.
+pub fn build_decisions_prompt(
+    title: &str,
+    transcript: &str,
+    part: Option<(usize, usize)>,
+) -> String {
+    let scope = match part {
+        Some((number, count)) => format!(
+            "This is part {number} of {count} of the transcript; list only the decisions \
+made in this part.\n"
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        "You are an assistant that extracts decisions from a meeting transcript.\n\
+Meeting title: {title}\n\
+\n\
+Rules:\n\
+- Use only information present in the transcript.\n\
+- A decision is a choice the participants settled on, not a proposal or an open question.\n\
+- Reply with a JSON array only, no other text. Each element is an object with the keys \
+\"decision\" (one short sentence stating what was decided) and \"timestamps\" (array of the \
+[mm:ss] timestamps of the transcript lines where it was decided, without brackets).\n\
+- Reply with [] if no decisions were made.\n\
+{scope}\
+\n\
+Transcript:\n\
+{transcript}"
+    )
+}
+
+/// Build a prompt writing formal minutes of a meeting as JSON.
+///
+/// `participants` are the attendees already known; `part` is
+/// `(number, count)` when the transcript is one part of a long meeting.
+pub fn build_minutes_prompt(
+    title: &str,
+    participants: &[String],
+    transcript: &str,
+    part: Option<(usize, usize)>,
+) -> String {
+    let known = if participants.is_empty() {
+        String::new()
+    } else {
+        format!("Known attendees: {}\n", participants.join(", "))
+    };
+    let scope = match part {
+        Some((number, count)) => {
+            format!("This is part {number} of {count} of the transcript; cover only this part.\n")
+        }
+        None => String::new(),
+    };
+
+    format!(
+        "You are an assistant that writes formal minutes of a meeting.\n\
+Meeting title: {title}\n\
+{known}\
+\n\
+Rules:\n\
+- Use only information present in the transcript.\n\
+- Reply with a JSON object only, no other text, with these keys:\n\
+  \"attendees\": array of the names of the people present;\n\
+  \"agenda\": array of the topics discussed in order, each an object with the keys \
+\"topic\" (a few words), \"discussion\" (two to four sentences summarizing what was said, \
+in the third person) and \"timestamp\" (the [mm:ss] timestamp where the topic starts, \
+without brackets);\n\
+  \"decisions\": array of short sentences stating what was decided;\n\
+  \"action_items\": array of objects with the keys \"owner\" (name or null), \"task\" \
+(short imperative sentence) and \"due\" (as YYYY-MM-DD when a date is given, or null).\n\
+- Use [] for a key with nothing to list.\n\
+- Write in the language of the transcript.\n\
+{scope}\
+\n\
+Transcript:\n\
+{transcript}"
+    )
+}
+
+/// Build a prompt asking the model to fix a JSON reply that could not be read.
+pub fn build_json_repair_prompt(what: &str, reply: &str, error: &str) -> String {
+    format!(
+        "Your reply below was meant to be JSON listing {what}, but it could not be read: \
+{error}\n\
+\n\
+Rules:\n\
+- Reply with the corrected JSON only, no other text and no code fences.\n\
+- Keep the content; only fix the format.\n\
+\n\
+Reply:\n\
+{reply}"
+    )
+}
+
+/// Build a prompt describing how the speakers of a meeting took part.
+pub fn build_participation_prompt(title: &str, stats: &str, transcript: &str) -> String {
+    format!(
+        "You are an assistant that describes how people took part in a meeting.\n\
+Meeting title: {title}\n\
+\n\
+Rules:\n\
+- Write one short paragraph of plain text, no headings or lists.\n\
+- Base the figures on the participation table only; use the transcript only to say \
+what the speakers talked about.\n\
+- Point out imbalances, such as one speaker dominating, frequent interruptions or \
+someone barely speaking, without judging the people.\n\
+- Write in the language of the transcript.\n\
+\n\
+Participation:\n\
+{stats}\n\
+\n\
+Transcript (start):\n\
+{transcript}"
+    )
+}
+
+/// Build a prompt asking for a short title for a meeting.
+pub fn build_title_prompt(transcript: &str) -> String {
+    format!(
+        "You are an assistant that names meeting recordings.\n\
+\n\
+Rules:\n\
+- Reply with a title of at most eight words and nothing else.\n\
+- Name the main topic of the meeting, e.g. \"Q3 budget review\".\n\
+- Do not use quotes, a date or a trailing period.\n\
+- Write the title in the language of the transcript.\n\
+\n\
+Transcript:\n\
+{transcript}"
+    )
+}
+
+/// Build a prompt asking for tags filing a meeting.
+///
+/// `existing` are tags already used in the library, preferred where they fit
+/// so filtering by tag keeps finding related meetings.
+pub fn build_tags_prompt(title: &str, existing: &[String], transcript: &str) -> String {
+    let known = if existing.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "- Reuse these existing tags where they fit: {}.\n",
+            existing.join(", ")
+        )
+    };
+
+    format!(
+        "You are an assistant that files meeting recordings under tags.\n\
+Meeting title: {title}\n\
+\n\
+Rules:\n\
+- Reply with a JSON array of three to five tags only, no other text, e.g. \
+[\"budget\", \"hiring\", \"Q3 planning\"].\n\
+- Each tag is one to three lowercase words naming a topic, project or kind of meeting; \
+keep proper names and abbreviations as written.\n\
+{known}\
+- Write the tags in the language of the transcript.\n\
+\n\
+Transcript:\n\
+{transcript}"
+    )
+}
+
+/// Build a prompt dividing a meeting transcript into topic chapters.
+///
+/// `part` is `(number, count)` when the transcript is one part of a long
+/// meeting.
+pub fn build_chapters_prompt(
+    title: &str,
+    transcript: &str,
+    part: Option<(usize, usize)>,
+) -> String {
+    let scope = match part {
+        Some((number, count)) => format!(
+            "This is part {number} of {count} of the transcript; the first chapter starts at \
+its first line.\n"
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        "You are an assistant that divides meeting transcripts into chapters.\n\
+Meeting title: {title}\n\
+\n\
+Rules:\n\
+- Start a new chapter where the meeting moves to another topic; most meetings have \
+3 to 10 chapters.\n\
+- Reply with a JSON array only, no other text. Each element is an object with the keys \
+\"timestamp\" (mm:ss of the transcript line where the chapter starts) and \"title\".\n\
+- Keep titles to a few words, e.g. \"Budget discussion\", in the language of the \
+transcript.\n\
+{scope}\
 \n\
 Transcript:\n\
 {transcript}"
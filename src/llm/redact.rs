@@ -0,0 +1,276 @@
+//! Masking of personal data before prompts leave the machine
+//!
+//! With `llm.redact_pii = true`, every cloud provider built by
+//! `build_provider` is wrapped so email addresses, phone numbers and card
+//! numbers in prompts and embedded texts are replaced by placeholders such
+//! as `[EMAIL]`. Matching is by pattern only: names and addresses are not
+//! recognized. Local providers see the transcript as is.
+
+use std::fmt;
+use std::sync::{LazyLock, Mutex};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::llm::client::{LlmProvider, SummaryResponse, TextSink};
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b").unwrap()
+});
+
+/// 13 to 19 digits, optionally grouped by spaces or dashes
+static CARD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap());
+
+/// A number shaped like a phone number: led by a `+` country code or an
+/// area code in parentheses, or else at least three digit groups split by
+/// spaces or dashes. Two groups or dots alone are years, ranges, decimals
+/// and versions far more often than phone numbers.
+static PHONE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\+\d{1,3}(?:[ .-]?\(\d{1,5}\))?(?:[ .-]?\d{2,5}){1,5}\b",
+        r"|\(\d{1,5}\)(?:[ .-]?\d{2,5}){1,4}\b",
+        r"|\b\d{2,5}(?:[ -]\d{2,5}){2,4}\b",
+    ))
+    .unwrap()
+});
+
+/// ISO dates, which look like phone numbers to the pattern
+static DATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap());
+
+/// What was masked, by kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RedactionReport {
+    pub emails: usize,
+    pub phone_numbers: usize,
+    pub card_numbers: usize,
+}
+
+impl RedactionReport {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn add(&mut self, other: RedactionReport) {
+        self.emails += other.emails;
+        self.phone_numbers += other.phone_numbers;
+        self.card_numbers += other.card_numbers;
+    }
+}
+
+impl fmt::Display for RedactionReport {
+    /// e.g. "2 email addresses, 1 phone number"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = [
+            (self.emails, "email address", "email addresses"),
+            (self.phone_numbers, "phone number", "phone numbers"),
+            (self.card_numbers, "card number", "card numbers"),
+        ];
+        let parts: Vec<String> = counts
+            .iter()
+            .filter(|(count, _, _)| *count > 0)
+            .map(|&(count, one, many)| format!("{} {}", count, if count == 1 { one } else { many }))
+            .collect();
+        if parts.is_empty() {
+            write!(f, "nothing")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+/// Replace email addresses, phone numbers and card numbers in `text`
+///
+/// Card numbers must pass the Luhn check, and phone numbers need a phone
+/// shape and seven to fifteen digits, so amounts, dates and versions are
+/// left alone.
+pub fn redact_pii(text: &str) -> (String, RedactionReport) {
+    let mut report = RedactionReport::default();
+    let text = mask(text, &CARD, "[CARD]", &mut report.card_numbers, |card| {
+        luhn_valid(card)
+    });
+    let text = mask(&text, &EMAIL, "[EMAIL]", &mut report.emails, |_| true);
+    let text = mask(
+        &text,
+        &PHONE,
+        "[PHONE]",
+        &mut report.phone_numbers,
+        |phone| {
+            let digits = phone.chars().filter(char::is_ascii_digit).count();
+            (7..=15).contains(&digits) && !DATE.is_match(phone)
+        },
+    );
+    (text, report)
+}
+
+/// Replace the matches of `pattern` that `confirm` accepts, counting them
+fn mask(
+    text: &str,
+    pattern: &Regex,
+    placeholder: &str,
+    count: &mut usize,
+    confirm: impl Fn(&str) -> bool,
+) -> String {
+    pattern
+        .replace_all(text, |captures: &regex::Captures| {
+            let found = &captures[0];
+            if confirm(found) {
+                *count += 1;
+                placeholder.to_string()
+            } else {
+                found.to_string()
+            }
+        })
+        .into_owned()
+}
+
+/// Whether the digits of `number` have a valid Luhn check digit
+fn luhn_valid(number: &str) -> bool {
+    let sum: u32 = number
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Provider masking personal data in everything sent to a cloud provider
+pub(super) struct RedactingProvider {
+    inner: Box<dyn LlmProvider>,
+    /// Everything masked so far
+    report: Mutex<RedactionReport>,
+}
+
+impl RedactingProvider {
+    pub fn new(inner: Box<dyn LlmProvider>) -> Self {
+        Self {
+            inner,
+            report: Mutex::new(RedactionReport::default()),
+        }
+    }
+
+    fn redact(&self, text: &str) -> String {
+        let (text, report) = redact_pii(text);
+        if !report.is_empty() {
+            tracing::info!(
+                "Redacted {} before sending to {}",
+                report,
+                self.inner.name()
+            );
+            self.report.lock().unwrap().add(report);
+        }
+        text
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RedactingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn is_cloud(&self) -> bool {
+        self.inner.is_cloud()
+    }
+
+    fn embedding_model(&self) -> Option<&str> {
+        self.inner.embedding_model()
+    }
+
+    fn redactions(&self) -> RedactionReport {
+        *self.report.lock().unwrap()
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<SummaryResponse> {
+        self.inner.complete(&self.redact(prompt)).await
+    }
+
+    async fn complete_streaming(
+        &self,
+        prompt: &str,
+        on_text: TextSink<'_>,
+    ) -> Result<SummaryResponse> {
+        self.inner
+            .complete_streaming(&self.redact(prompt), on_text)
+            .await
+    }
+
+    async fn complete_json(&self, prompt: &str, schema: &Value) -> Result<SummaryResponse> {
+        self.inner.complete_json(&self.redact(prompt), schema).await
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let texts: Vec<String> = texts.iter().map(|text| self.redact(text)).collect();
+        self.inner.embed(&texts).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_emails_phone_numbers_and_cards() {
+        let text = "[00:12] Ada: Mail ada.lovelace@example.co.uk or call +44 20 7946 0958.\n\
+                    [00:20] Grace: My card is 4111 1111 1111 1111, or (555) 123-4567.";
+
+        let (redacted, report) = redact_pii(text);
+
+        assert_eq!(
+            redacted,
+            "[00:12] Ada: Mail [EMAIL] or call [PHONE].\n\
+             [00:20] Grace: My card is [CARD], or [PHONE]."
+        );
+        assert_eq!(
+            report,
+            RedactionReport {
+                emails: 1,
+                phone_numbers: 2,
+                card_numbers: 1,
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "1 email address, 2 phone numbers, 1 card number"
+        );
+    }
+
+    #[test]
+    fn leaves_dates_amounts_and_timestamps_alone() {
+        let text = "[01:02:03] On 2024-05-03 we spent 1500 euros, order 1234 5678 9012 3456.";
+
+        let (redacted, report) = redact_pii(text);
+
+        assert_eq!(redacted, text);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn leaves_years_and_decimals_alone() {
+        for text in [
+            "Figures for 1999-2024 and 2023 2024 are in.",
+            "From 2019 to 2024, see section 12 34.",
+            "Revenue was 1234.5678 and pi is 3.14159 26535.",
+            "Upgrade from 10.15.7 to 14.4.1, the server at 192.168.100.12.",
+        ] {
+            let (redacted, report) = redact_pii(text);
+            assert_eq!(redacted, text);
+            assert!(report.is_empty());
+        }
+
+        // Three groups, a country code or an area code still make a number
+        let (redacted, _) = redact_pii("Call 555-123-4567, +4930123456 or (030) 1234567.");
+        assert_eq!(redacted, "Call [PHONE], [PHONE] or [PHONE].");
+    }
+}
@@ -0,0 +1,166 @@
+//! Retries of provider calls that failed for a transient reason
+//!
+//! Every provider built by `build_provider` is wrapped, so summaries, answers
+//! and the other LLM commands ride out a rate limit or a brief outage instead
+//! of failing the whole command.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::config::LlmSettings;
+use crate::llm::client::{LlmProvider, SummaryResponse, TextSink};
+use crate::llm::error::{is_transient, LlmHttpError};
+
+/// Longest `Retry-After` waited for; a provider asking for more is out of
+/// quota for longer than a command should hang
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// Provider that retries failed calls with backoff.
+pub(super) struct RetryingProvider {
+    inner: Box<dyn LlmProvider>,
+    settings: LlmSettings,
+}
+
+impl RetryingProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, settings: &LlmSettings) -> Self {
+        Self {
+            inner,
+            settings: settings.clone(),
+        }
+    }
+
+    /// Wait before the next attempt, or `None` when `error` should be returned
+    fn next_delay(&self, error: &anyhow::Error, attempts: u32) -> Option<Duration> {
+        if !is_transient(error) {
+            return None;
+        }
+        let backoff = self.settings.retry_delay(attempts)?;
+        let asked = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<LlmHttpError>())
+            .and_then(|error| error.retry_after);
+        match asked {
+            Some(wait) if wait > MAX_RETRY_AFTER => None,
+            Some(wait) => Some(wait),
+            None => Some(backoff),
+        }
+    }
+
+    /// Run `call` until it succeeds, fails for a lasting reason or runs out
+    /// of attempts
+    async fn retry<T, F>(&self, call: impl FnMut() -> F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        self.retry_unless(call, || false).await
+    }
+
+    /// Like [`Self::retry`], but give up once `settled` says a failed call
+    /// already had effects a new attempt would repeat
+    async fn retry_unless<T, F>(
+        &self,
+        mut call: impl FnMut() -> F,
+        settled: impl Fn() -> bool,
+    ) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let mut attempts = 1;
+        loop {
+            let error = match call().await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            let delay = if settled() {
+                None
+            } else {
+                self.next_delay(&error, attempts)
+            };
+            let Some(delay) = delay else {
+                return Err(error);
+            };
+
+            tracing::warn!(
+                "{} request failed, retrying in {}s (attempt {} of {}): {}",
+                self.inner.name(),
+                delay.as_secs(),
+                attempts + 1,
+                self.settings.max_attempts,
+                error
+            );
+            // The log is not on screen, and a silent wait looks like a hang
+            eprintln!(
+                "{} request failed, retrying in {}s (attempt {}/{})",
+                self.inner.name(),
+                delay.as_secs(),
+                attempts + 1,
+                self.settings.max_attempts
+            );
+            tokio::time::sleep(delay).await;
+            attempts += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RetryingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn is_cloud(&self) -> bool {
+        self.inner.is_cloud()
+    }
+
+    fn embedding_model(&self) -> Option<&str> {
+        self.inner.embedding_model()
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<SummaryResponse> {
+        self.retry(|| self.inner.complete(prompt)).await
+    }
+
+    async fn complete_json(&self, prompt: &str, schema: &Value) -> Result<SummaryResponse> {
+        self.retry(|| self.inner.complete_json(prompt, schema))
+            .await
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.retry(|| self.inner.embed(texts)).await
+    }
+
+    /// Text already passed on cannot be taken back, so a stream that fails
+    /// after it started is not retried
+    async fn complete_streaming(
+        &self,
+        prompt: &str,
+        on_text: TextSink<'_>,
+    ) -> Result<SummaryResponse> {
+        let streamed = AtomicBool::new(false);
+        let on_text = Mutex::new(on_text);
+        let (streamed, on_text) = (&streamed, &on_text);
+        self.retry_unless(
+            || async move {
+                let mut sink = |text: &str| {
+                    streamed.store(true, Ordering::Relaxed);
+                    if let Ok(mut on_text) = on_text.lock() {
+                        on_text(text);
+                    }
+                };
+                self.inner.complete_streaming(prompt, &mut sink).await
+            },
+            || streamed.load(Ordering::Relaxed),
+        )
+        .await
+    }
+}
@@ -0,0 +1,56 @@
+//! Line splitting for streamed provider responses
+//!
+//! Gemini streams server-sent events and Ollama streams one JSON object per
+//! line. Both arrive in network chunks that can end mid-line, or in the
+//! middle of a multi-byte character, so bytes are buffered until a line is
+//! complete.
+
+/// Collects response chunks and hands out complete lines.
+#[derive(Debug, Default)]
+pub(super) struct LineReader {
+    buffer: Vec<u8>,
+}
+
+impl LineReader {
+    /// Add a chunk, returning the lines it completed without their line endings
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            lines.push(
+                String::from_utf8_lossy(&line)
+                    .trim_end_matches(['\r', '\n'])
+                    .to_string(),
+            );
+        }
+        lines
+    }
+
+    /// Text left after the last line ending, if any
+    pub fn finish(self) -> Option<String> {
+        let rest = String::from_utf8_lossy(&self.buffer).trim().to_string();
+        (!rest.is_empty()).then_some(rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_lines_split_across_chunks() {
+        let mut reader = LineReader::default();
+        let text = "data: {\"text\": \"Grüße\"}\r\n\ndata: [1]\n{\"done\":true}";
+        let bytes = text.as_bytes();
+        // Cut inside the two-byte "ü"
+        let cut = text.find('ü').unwrap() + 1;
+
+        assert!(reader.push(&bytes[..cut]).is_empty());
+        assert_eq!(
+            reader.push(&bytes[cut..]),
+            ["data: {\"text\": \"Grüße\"}", "", "data: [1]"]
+        );
+        assert_eq!(reader.finish().as_deref(), Some("{\"done\":true}"));
+    }
+}
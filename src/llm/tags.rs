@@ -0,0 +1,87 @@
+//! Tag suggestions for recordings
+//!
+//! Suggestions are only proposed; they become tags of the recording once
+//! accepted, so a poor guess never ends up filtering the library.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use crate::llm::answer::{split_transcript, MAX_PART_CHARS};
+use crate::llm::client::LlmProvider;
+use crate::llm::json::{complete_json, json_array, valid_items};
+use crate::llm::prompts::build_tags_prompt;
+
+/// Most tags suggested for one recording
+const MAX_TAGS: usize = 5;
+
+/// Longest tag kept, in characters
+const MAX_TAG_CHARS: usize = 40;
+
+/// Ask for tags filing a meeting.
+///
+/// Only the start of a long transcript is sent, like for titles. `existing`
+/// are the tags already used in the library.
+pub async fn suggest_tags(
+    provider: &dyn LlmProvider,
+    title: &str,
+    existing: &[String],
+    transcript: &str,
+) -> Result<Vec<String>> {
+    let start = split_transcript(transcript, MAX_PART_CHARS)
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let prompt = build_tags_prompt(title, existing, start);
+    complete_json(provider, &prompt, &tags_schema(), "tags", |reply| {
+        parse_tags(reply, existing)
+    })
+    .await
+}
+
+/// Schema of the JSON array of tags
+fn tags_schema() -> Value {
+    json!({"type": "array", "items": {"type": "string"}})
+}
+
+/// Read the JSON array of a reply, tolerating code fences and text around it
+///
+/// Tags are trimmed of `#` marks and listed once, spelled like the existing
+/// tag they match.
+fn parse_tags(reply: &str, existing: &[String]) -> Result<Vec<String>> {
+    let tags: Vec<String> = valid_items(json_array(reply, "tags")?, "tags");
+
+    let mut suggested: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').trim();
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_CHARS {
+            continue;
+        }
+        let tag = existing
+            .iter()
+            .find(|known| known.eq_ignore_ascii_case(tag))
+            .map_or(tag, String::as_str);
+        if !suggested.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            suggested.push(tag.to_string());
+        }
+    }
+    suggested.truncate(MAX_TAGS);
+    Ok(suggested)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tags_matching_existing_spelling() {
+        let existing = vec!["Budget".to_string()];
+        let reply = "```json\n[\"budget\", \"#hiring\", \" \", \"Hiring\", \"Q3 planning\", \
+                     \"roadmap\", \"retro\", \"extra\"]\n```";
+
+        assert_eq!(
+            parse_tags(reply, &existing).unwrap(),
+            ["Budget", "hiring", "Q3 planning", "roadmap", "retro"]
+        );
+        assert!(parse_tags("No tags.", &existing).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! User prompt templates for summaries
+//!
+//! Templates are Markdown files in the `prompts` directory next to the
+//! config file, such as `~/.config/minutes/prompts/standup.md`, and are
+//! selected by file name. `{{title}}`, `{{attendees}}`, `{{transcript}}` and
+//! `{{language}}` are filled in when the prompt is built. A `default.md` template replaces
+//! the built-in summary prompt.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Template used for summaries when none is named
+const DEFAULT_TEMPLATE: &str = "default";
+
+const PLACEHOLDERS: [&str; 4] = ["title", "attendees", "transcript", "language"];
+
+/// A summary prompt written by the user.
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    /// File name without the `.md` extension
+    pub name: String,
+    body: String,
+}
+
+impl PromptTemplate {
+    /// Check a template's placeholders; it must include the transcript
+    pub fn parse(name: &str, body: &str) -> Result<Self> {
+        let mut has_transcript = false;
+        let mut rest = body;
+        while let Some(start) = rest.find("{{") {
+            let end = rest[start..]
+                .find("}}")
+                .with_context(|| format!("Template '{}' has an unclosed {{{{", name))?;
+            let placeholder = rest[start + 2..start + end].trim();
+            if !PLACEHOLDERS.contains(&placeholder) {
+                anyhow::bail!(
+                    "Template '{}' uses unknown placeholder {{{{{}}}}}. Supported: {}",
+                    name,
+                    placeholder,
+                    PLACEHOLDERS
+                        .iter()
+                        .map(|p| format!("{{{{{}}}}}", p))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            has_transcript |= placeholder == "transcript";
+            rest = &rest[start + end + 2..];
+        }
+        if !has_transcript {
+            anyhow::bail!("Template '{}' must include {{{{transcript}}}}", name);
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            body: body.to_string(),
+        })
+    }
+
+    /// Read `<name>.md` from the templates directory
+    pub fn load(dir: &Path, name: &str) -> Result<Self> {
+        let name = name.strip_suffix(".md").unwrap_or(name);
+        if name.is_empty() || name.contains(['/', '\\']) {
+            anyhow::bail!("Invalid template name '{}'", name);
+        }
+
+        let path = dir.join(format!("{}.md", name));
+        if !path.exists() {
+            let available = list_templates(dir)?;
+            anyhow::bail!(
+                "Template '{}' not found in {}. {}",
+                name,
+                dir.display(),
+                if available.is_empty() {
+                    "Add it as a Markdown file there.".to_string()
+                } else {
+                    format!("Available: {}", available.join(", "))
+                }
+            );
+        }
+        let body = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read template {}", path.display()))?;
+        Self::parse(name, &body)
+    }
+
+    /// The user's default template, if one was added
+    pub fn load_default(dir: &Path) -> Result<Option<Self>> {
+        if !dir.join(format!("{}.md", DEFAULT_TEMPLATE)).exists() {
+            return Ok(None);
+        }
+        Self::load(dir, DEFAULT_TEMPLATE).map(Some)
+    }
+
+    /// Build the prompt for one meeting
+    ///
+    /// `language` is the English name of the language to write in, if known:
+    /// `llm.summary_language` or else the transcript's.
+    pub fn render(
+        &self,
+        title: &str,
+        attendees: &[String],
+        transcript: &str,
+        language: Option<&str>,
+    ) -> String {
+        let language = language.unwrap_or("the language of the transcript");
+        let attendees = if attendees.is_empty() {
+            "unknown".to_string()
+        } else {
+            attendees.join(", ")
+        };
+        let mut prompt = String::with_capacity(self.body.len() + transcript.len());
+        let mut rest = self.body.as_str();
+        // Placeholders are replaced in one pass, so braces in the transcript stay as they are
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start..].find("}}") else {
+                break;
+            };
+            let end = start + len;
+            prompt.push_str(&rest[..start]);
+            match rest[start + 2..end].trim() {
+                "title" => prompt.push_str(title),
+                "attendees" => prompt.push_str(&attendees),
+                "transcript" => prompt.push_str(transcript),
+                "language" => prompt.push_str(language),
+                _ => prompt.push_str(&rest[start..end + 2]),
+            }
+            rest = &rest[end + 2..];
+        }
+        prompt.push_str(rest);
+        prompt
+    }
+}
+
+/// Names of the templates in the templates directory, sorted
+fn list_templates(dir: &Path) -> Result<Vec<String>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read templates in {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "md") {
+            if let Some(stem) = path.file_stem() {
+                names.push(stem.to_string_lossy().into_owned());
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_placeholders_once() {
+        let template = PromptTemplate::parse(
+            "standup",
+            "Standup notes for {{title}} ({{attendees}}) in {{ language }}:\n{{transcript}}",
+        )
+        .unwrap();
+        let attendees = ["Ada".to_string(), "Grace".to_string()];
+
+        assert_eq!(
+            template.render(
+                "Daily",
+                &attendees,
+                "[00:05] Ada: I fixed {{title}}",
+                Some("German")
+            ),
+            "Standup notes for Daily (Ada, Grace) in German:\n[00:05] Ada: I fixed {{title}}"
+        );
+        assert_eq!(
+            template.render("Daily", &[], "{{attendees}}", None),
+            "Standup notes for Daily (unknown) in the language of the transcript:\n{{attendees}}"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders_and_missing_transcript() {
+        assert!(PromptTemplate::parse("typo", "{{titel}} {{transcript}}").is_err());
+        assert!(PromptTemplate::parse("empty", "Summarize {{title}}").is_err());
+        assert!(PromptTemplate::parse("open", "{{transcript}} {{title").is_err());
+    }
+
+    #[test]
+    fn loads_templates_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(PromptTemplate::load_default(dir.path()).unwrap().is_none());
+
+        std::fs::write(dir.path().join("standup.md"), "{{transcript}}").unwrap();
+        std::fs::write(dir.path().join("default.md"), "Notes: {{transcript}}").unwrap();
+
+        assert_eq!(
+            PromptTemplate::load(dir.path(), "standup.md").unwrap().name,
+            "standup"
+        );
+        assert_eq!(
+            PromptTemplate::load_default(dir.path())
+                .unwrap()
+                .unwrap()
+                .name,
+            "default"
+        );
+        let missing = PromptTemplate::load(dir.path(), "retro").unwrap_err();
+        assert!(missing.to_string().contains("Available: default, standup"));
+        assert!(PromptTemplate::load(dir.path(), "../config").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! Title generation for recordings started without a title
+
+use anyhow::Result;
+
+use crate::llm::answer::{split_transcript, MAX_PART_CHARS};
+use crate::llm::client::LlmProvider;
+use crate::llm::prompts::build_title_prompt;
+
+/// Longest title kept, in characters
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Ask for a concise title for a meeting.
+///
+/// Only the start of a long transcript is sent; it usually says what the
+/// meeting is about.
+pub async fn generate_title(provider: &dyn LlmProvider, transcript: &str) -> Result<String> {
+    let start = split_transcript(transcript, MAX_PART_CHARS)
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let response = provider.complete(&build_title_prompt(start)).await?;
+    clean_title(&response.text).ok_or_else(|| anyhow::anyhow!("LLM reply did not contain a title"))
+}
+
+/// First line of a reply without quotes, a "Title:" label or a trailing period
+fn clean_title(reply: &str) -> Option<String> {
+    let line = reply.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line
+        .strip_prefix("Title:")
+        .unwrap_or(line)
+        .trim()
+        .trim_matches(['"', '\'', '*', '“', '”'])
+        .trim_end_matches('.')
+        .trim();
+    let title: String = line.chars().take(MAX_TITLE_CHARS).collect();
+    let title = title.trim_end().to_string();
+    (!title.is_empty()).then_some(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleans_up_title_replies() {
+        assert_eq!(
+            clean_title("\"Q3 budget review.\"\n").as_deref(),
+            Some("Q3 budget review")
+        );
+        assert_eq!(
+            clean_title("Title: **Hiring plan**\nThe meeting covered...").as_deref(),
+            Some("Hiring plan")
+        );
+        assert_eq!(clean_title(&"a".repeat(100)).map(|t| t.len()), Some(80));
+        assert_eq!(clean_title(" \n\"\""), None);
+    }
+}
@@ -6,7 +6,7 @@ use anyhow::Result;
 use clap::Parser;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use minutes::cli::{Cli, Commands};
+use minutes::cli::{Cli, Commands, ExportTarget};
 use minutes::config::Settings;
 
 #[tokio::main]
@@ -39,40 +39,397 @@ async fn main() -> Result<()> {
         command => {
             // Load configuration only for runtime commands.
             let settings = Settings::load()?;
+            minutes::i18n::init(&settings);
 
             // Execute command
             match command {
-                Commands::Start { title } => {
-                    minutes::cli::commands::start_recording(&settings, title).await?;
+                Commands::Start {
+                    title,
+                    confidential,
+                    language,
+                    participants,
+                } => {
+                    minutes::cli::commands::start_recording(
+                        &settings,
+                        title,
+                        confidential,
+                        language,
+                        participants,
+                    )
+                    .await?;
                 }
                 Commands::Stop => {
                     minutes::cli::commands::stop_recording(&settings).await?;
                 }
-                Commands::Status => {
-                    minutes::cli::commands::show_status(&settings).await?;
+                Commands::Status { watch } => {
+                    minutes::cli::commands::show_status(&settings, watch).await?;
                 }
-                Commands::List { limit, search } => {
-                    minutes::cli::commands::list_recordings(&settings, limit, search).await?;
+                Commands::Memo { seconds, save } => {
+                    minutes::cli::commands::record_memo(&settings, seconds, save).await?;
+                }
+                Commands::List {
+                    limit,
+                    search,
+                    unapproved,
+                    project,
+                    tag,
+                    since,
+                    until,
+                    min_duration,
+                    state,
+                    participant,
+                } => {
+                    let filter = minutes::cli::commands::recording_filter(
+                        since.as_deref(),
+                        until.as_deref(),
+                        min_duration.as_deref(),
+                        state.as_deref(),
+                        participant.as_deref(),
+                    )?;
+                    minutes::cli::commands::list_recordings(
+                        &settings, limit, search, unapproved, project, tag, &filter,
+                    )
+                    .await?;
                 }
                 Commands::View { id } => {
                     minutes::cli::commands::view_recording(&settings, &id).await?;
                 }
-                Commands::Search { query } => {
-                    minutes::cli::commands::search_transcripts(&settings, &query).await?;
+                Commands::Edit { id } => {
+                    minutes::cli::commands::edit_transcript(&settings, &id)?;
+                }
+                Commands::Notes { id } => {
+                    minutes::cli::commands::edit_notes(&settings, &id)?;
+                }
+                Commands::Delete {
+                    id,
+                    permanent,
+                    force,
+                    keep_audio,
+                } => {
+                    minutes::cli::commands::delete_recording(
+                        &settings, &id, permanent, force, keep_audio,
+                    )?;
+                }
+                Commands::Archive { id, dest } => {
+                    minutes::cli::commands::archive_recording(&settings, &id, dest.as_deref())?;
+                }
+                Commands::Rename { id, title } => {
+                    minutes::cli::commands::rename_recording(&settings, &id, &title)?;
                 }
-                Commands::Doctor { json } => {
-                    minutes::cli::commands::run_doctor(&settings, json).await?;
+                Commands::EditMeta {
+                    id,
+                    notes_file,
+                    tags,
+                    participants,
+                } => {
+                    minutes::cli::commands::edit_recording_meta(
+                        &settings,
+                        &id,
+                        notes_file.as_deref(),
+                        tags,
+                        participants,
+                    )?;
                 }
-                Commands::Summarize { id } => {
-                    minutes::cli::commands::summarize_recording(&settings, &id).await?;
+                Commands::Search {
+                    query,
+                    semantic,
+                    include_confidential,
+                    since,
+                    until,
+                    min_duration,
+                    state,
+                    participant,
+                } => {
+                    if semantic {
+                        minutes::cli::commands::semantic_search(
+                            &settings,
+                            &query,
+                            include_confidential,
+                        )
+                        .await?;
+                    } else {
+                        let filter = minutes::cli::commands::recording_filter(
+                            since.as_deref(),
+                            until.as_deref(),
+                            min_duration.as_deref(),
+                            state.as_deref(),
+                            participant.as_deref(),
+                        )?;
+                        minutes::cli::commands::search_transcripts(&settings, &query, &filter)
+                            .await?;
+                    }
+                }
+                Commands::Transcribe { id, language } => {
+                    minutes::cli::commands::retranscribe(&settings, &id, language).await?;
+                }
+                Commands::Doctor { json, fix } => {
+                    minutes::cli::commands::run_doctor(&settings, json, fix).await?;
                 }
-                Commands::Export { id, format, output } => {
-                    minutes::cli::commands::export_recording(&settings, &id, &format, output)
+                Commands::Devices { json } => {
+                    minutes::cli::commands::list_devices(json)?;
+                }
+                Commands::Ask {
+                    id,
+                    question,
+                    all,
+                    include_confidential,
+                } => {
+                    if let Some(question) = all {
+                        minutes::cli::commands::ask_library(
+                            &settings,
+                            &question,
+                            include_confidential,
+                        )
+                        .await?;
+                    } else {
+                        minutes::cli::commands::ask_question(
+                            &settings,
+                            &id.unwrap_or_default(),
+                            &question.unwrap_or_default(),
+                            include_confidential,
+                        )
                         .await?;
+                    }
+                }
+                Commands::Actions {
+                    id,
+                    open,
+                    done,
+                    undone,
+                    include_confidential,
+                } => {
+                    minutes::cli::commands::action_items(
+                        &settings,
+                        id.as_deref(),
+                        open,
+                        done,
+                        undone,
+                        include_confidential,
+                    )
+                    .await?;
+                }
+                Commands::Decisions {
+                    id,
+                    include_confidential,
+                } => {
+                    minutes::cli::commands::extract_recording_decisions(
+                        &settings,
+                        &id,
+                        include_confidential,
+                    )
+                    .await?;
+                }
+                Commands::Minutes {
+                    id,
+                    format,
+                    output,
+                    include_confidential,
+                } => {
+                    minutes::cli::commands::write_recording_minutes(
+                        &settings,
+                        &id,
+                        &format,
+                        output,
+                        include_confidential,
+                    )
+                    .await?;
+                }
+                Commands::Analytics {
+                    id,
+                    narrative,
+                    include_confidential,
+                } => {
+                    minutes::cli::commands::recording_analytics(
+                        &settings,
+                        &id,
+                        narrative,
+                        include_confidential,
+                    )
+                    .await?;
+                }
+                Commands::Title {
+                    id,
+                    include_confidential,
+                } => {
+                    minutes::cli::commands::generate_recording_title(
+                        &settings,
+                        &id,
+                        include_confidential,
+                    )
+                    .await?;
+                }
+                Commands::Tags {
+                    id,
+                    suggest,
+                    yes,
+                    add,
+                    remove,
+                    include_confidential,
+                } => {
+                    minutes::cli::commands::recording_tags(
+                        &settings,
+                        &id,
+                        suggest,
+                        yes,
+                        add,
+                        remove,
+                        include_confidential,
+                    )
+                    .await?;
+                }
+                Commands::Participants { id, add, remove } => {
+                    minutes::cli::commands::recording_participants(&settings, &id, add, remove)?;
+                }
+                Commands::Chapters {
+                    id,
+                    include_confidential,
+                } => {
+                    minutes::cli::commands::generate_recording_chapters(
+                        &settings,
+                        &id,
+                        include_confidential,
+                    )
+                    .await?;
+                }
+                Commands::Summarize {
+                    id,
+                    all,
+                    missing,
+                    since,
+                    project,
+                    delay,
+                    template,
+                    lang,
+                    force,
+                    include_confidential,
+                } => {
+                    let mut settings = settings.clone();
+                    if let Some(lang) = lang {
+                        settings.llm.summary_language = lang;
+                    }
+                    if all {
+                        minutes::cli::commands::summarize_all(
+                            &settings,
+                            missing,
+                            since.as_deref(),
+                            project.as_deref(),
+                            delay,
+                            template.as_deref(),
+                            force,
+                            include_confidential,
+                        )
+                        .await?;
+                    } else if let Some(id) = id {
+                        minutes::cli::commands::summarize_recording(
+                            &settings,
+                            &id,
+                            template.as_deref(),
+                            force,
+                            include_confidential,
+                        )
+                        .await?;
+                    }
+                }
+                Commands::Export {
+                    target,
+                    id,
+                    format,
+                    output,
+                    include_confidential,
+                    translated,
+                    chapters,
+                } => match target {
+                    Some(ExportTarget::Site {
+                        output_dir,
+                        include_confidential,
+                    }) => {
+                        minutes::cli::commands::export_site(
+                            &settings,
+                            &output_dir,
+                            include_confidential,
+                        )?;
+                    }
+                    None => {
+                        if let Some(id) = id {
+                            minutes::cli::commands::export_recording(
+                                &settings,
+                                &id,
+                                &format,
+                                output,
+                                include_confidential,
+                                translated,
+                                chapters,
+                            )
+                            .await?;
+                        }
+                    }
+                },
+                Commands::Import { path, title, link } => {
+                    minutes::cli::commands::import_recording(&settings, &path, title, link).await?;
+                }
+                Commands::Align { id, system, offset } => {
+                    minutes::cli::commands::align_system_audio(&settings, &id, &system, &offset)
+                        .await?;
+                }
+                Commands::Approve { id, revoke } => {
+                    minutes::cli::commands::approve_summary(&settings, &id, revoke)?;
+                }
+                Commands::Summaries {
+                    id,
+                    show,
+                    diff,
+                    restore,
+                } => {
+                    minutes::cli::commands::summary_history(
+                        &settings,
+                        &id,
+                        show,
+                        diff.as_deref(),
+                        restore,
+                    )?;
+                }
+                Commands::Sensitivity { id, level } => {
+                    minutes::cli::commands::set_sensitivity(&settings, &id, level.as_deref())?;
+                }
+                Commands::Stats { llm, json } => {
+                    minutes::cli::commands::show_stats(&settings, llm, json)?;
+                }
+                Commands::Undo { list } => {
+                    minutes::cli::commands::undo(&settings, list)?;
+                }
+                Commands::Backup { path, force } => {
+                    minutes::cli::commands::backup_library(&settings, &path, force)?;
+                }
+                Commands::Restore { path, force } => {
+                    minutes::cli::commands::restore_library(&settings, &path, force).await?;
+                }
+                Commands::Purge { dry_run, force } => {
+                    minutes::cli::commands::purge_recordings(&settings, dry_run, force)?;
                 }
                 Commands::Daemon(daemon_cmd) => {
                     minutes::cli::commands::daemon_command(&settings, daemon_cmd).await?;
                 }
+                Commands::Privacy(privacy_cmd) => {
+                    minutes::cli::commands::privacy_command(&settings, privacy_cmd).await?;
+                }
+                Commands::Queue(queue_cmd) => {
+                    minutes::cli::commands::queue_command(&settings, queue_cmd).await?;
+                }
+                Commands::Trash(trash_cmd) => {
+                    minutes::cli::commands::trash_command(&settings, trash_cmd)?;
+                }
+                Commands::Db(db_cmd) => {
+                    minutes::cli::commands::db_command(&settings, db_cmd)?;
+                }
+                Commands::Speakers(speakers_cmd) => {
+                    minutes::cli::commands::speakers_command(&settings, speakers_cmd)?;
+                }
+                Commands::Project(project_cmd) => {
+                    minutes::cli::commands::project_command(&settings, project_cmd)?;
+                }
+                Commands::Model(model_cmd) => {
+                    minutes::cli::commands::model_command(&settings, model_cmd).await?;
+                }
                 Commands::Tui => {
                     minutes::tui::run(&settings).await?;
                 }
@@ -0,0 +1,361 @@
+//! Library backups
+//!
+//! `minutes backup` writes a zstd-compressed tar archive holding a consistent
+//! copy of the database (`VACUUM INTO`), the audio files of every recording
+//! under `audio/`, and a `manifest.json` listing each file with its size and
+//! SHA-256 hash. `minutes restore` unpacks an archive inside the data
+//! directory and checks every file against the manifest before it replaces
+//! the database and points the recordings at the restored audio, so a
+//! damaged archive leaves the library untouched.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Component, Path};
+
+use crate::config::Settings;
+use crate::storage::Database;
+
+const MANIFEST: &str = "manifest.json";
+const DATABASE: &str = "minutes.db";
+
+/// Version of the archive layout, bumped when it changes incompatibly
+const FORMAT: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format: u32,
+    created_at: DateTime<Utc>,
+    files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestFile {
+    /// Path inside the archive
+    name: String,
+    /// Where an audio file was on the machine that made the backup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    original_path: Option<String>,
+    size: u64,
+    sha256: String,
+}
+
+/// What a backup holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackupSummary {
+    pub recordings: usize,
+    pub audio_files: usize,
+    /// Uncompressed size of the database and audio files
+    pub bytes: u64,
+}
+
+/// Write the library to a `.tar.zst` archive at `output`
+///
+/// Audio files that no longer exist are skipped with a warning; recordings
+/// still being captured are left out.
+pub fn create(settings: &Settings, output: &Path) -> Result<BackupSummary> {
+    let db = Database::open(settings)?;
+    let db_copy = std::env::temp_dir().join(format!("minutes-backup-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&db_copy);
+    db.vacuum_into(&db_copy)?;
+
+    let result = write_archive(&db, &db_copy, output);
+    let _ = std::fs::remove_file(&db_copy);
+    if result.is_err() {
+        let _ = std::fs::remove_file(output);
+    }
+    let (audio_files, bytes) = result?;
+
+    Ok(BackupSummary {
+        recordings: db.get_stats()?.total_recordings,
+        audio_files,
+        bytes,
+    })
+}
+
+/// Returns the number of audio files and the bytes written before compression
+fn write_archive(db: &Database, db_copy: &Path, output: &Path) -> Result<(usize, u64)> {
+    let file =
+        File::create(output).with_context(|| format!("Failed to create {}", output.display()))?;
+    let mut encoder = zstd::Encoder::new(file, 0)?;
+    encoder.include_checksum(true)?;
+    let mut tar = tar::Builder::new(encoder);
+
+    let mut files = vec![append_file(&mut tar, db_copy, DATABASE.to_string(), None)?];
+    let mut names = HashSet::new();
+    for path in db.audio_file_paths()? {
+        let source = Path::new(&path);
+        let Some(file_name) = source.file_name().map(|name| name.to_string_lossy()) else {
+            continue;
+        };
+        if !source.exists() {
+            tracing::warn!("Skipping missing audio file {}", path);
+            continue;
+        }
+        let mut name = format!("audio/{}", file_name);
+        let mut copy = 1;
+        while !names.insert(name.clone()) {
+            copy += 1;
+            name = format!("audio/{}-{}", copy, file_name);
+        }
+        files.push(append_file(&mut tar, source, name, Some(path.clone()))?);
+    }
+
+    let manifest = Manifest {
+        format: FORMAT,
+        created_at: Utc::now(),
+        files,
+    };
+    let json = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created_at.timestamp().max(0) as u64);
+    tar.append_data(&mut header, MANIFEST, json.as_slice())?;
+
+    tar.into_inner()?.finish()?.sync_all()?;
+    let bytes = manifest.files.iter().map(|file| file.size).sum();
+    Ok((manifest.files.len() - 1, bytes))
+}
+
+/// Add `source` to the archive as `name`, hashing it on the way
+fn append_file<W: Write>(
+    tar: &mut tar::Builder<W>,
+    source: &Path,
+    name: String,
+    original_path: Option<String>,
+) -> Result<ManifestFile> {
+    let file =
+        File::open(source).with_context(|| format!("Failed to read {}", source.display()))?;
+    let metadata = file.metadata()?;
+    let mut header = tar::Header::new_gnu();
+    header.set_metadata(&metadata);
+
+    // Read exactly the size in the header, even if the file grows meanwhile
+    let mut reader = HashingReader {
+        inner: file.take(metadata.len()),
+        hasher: Sha256::new(),
+    };
+    tar.append_data(&mut header, &name, &mut reader)
+        .with_context(|| format!("Failed to add {} to the backup", source.display()))?;
+
+    Ok(ManifestFile {
+        name,
+        original_path,
+        size: metadata.len(),
+        sha256: format!("{:x}", reader.hasher.finalize()),
+    })
+}
+
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Replace the library with the contents of a backup archive
+///
+/// The current database is kept next to it as `minutes.db.<time>.bak`.
+/// Restored audio goes to the audio directory, and recordings are pointed at
+/// it wherever the backup came from. The daemon must not be running.
+pub fn restore(settings: &Settings, archive: &Path) -> Result<BackupSummary> {
+    let data_dir = &settings.general.data_dir;
+    std::fs::create_dir_all(data_dir)
+        .with_context(|| format!("Failed to create {}", data_dir.display()))?;
+    // Unpacked on the same file system, so installing is a rename
+    let staging = data_dir.join(format!(".restore-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&staging);
+
+    let result =
+        unpack(archive, &staging).and_then(|manifest| install(settings, &staging, &manifest));
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+/// Unpack an archive into `staging` and check it against its manifest
+fn unpack(archive: &Path, staging: &Path) -> Result<Manifest> {
+    let file =
+        File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+    let mut tar = tar::Archive::new(zstd::Decoder::new(file)?);
+    tar.unpack(staging).with_context(|| {
+        format!(
+            "Failed to unpack {}; is it a minutes backup?",
+            archive.display()
+        )
+    })?;
+
+    let manifest = std::fs::read(staging.join(MANIFEST))
+        .context("The archive has no manifest; is it a minutes backup?")?;
+    let manifest: Manifest =
+        serde_json::from_slice(&manifest).context("Failed to read the backup manifest")?;
+    if manifest.format != FORMAT {
+        anyhow::bail!(
+            "Backup format {} is not supported by this version of minutes",
+            manifest.format
+        );
+    }
+    verify(staging, &manifest)?;
+    Ok(manifest)
+}
+
+/// Check that every file of the manifest was unpacked with its size and hash
+fn verify(staging: &Path, manifest: &Manifest) -> Result<()> {
+    if !manifest.files.iter().any(|file| file.name == DATABASE) {
+        anyhow::bail!("The backup does not contain a database");
+    }
+
+    for entry in &manifest.files {
+        let relative = Path::new(&entry.name);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            anyhow::bail!("The backup manifest lists an invalid path: {}", entry.name);
+        }
+
+        let mut file = File::open(staging.join(relative))
+            .with_context(|| format!("{} is missing from the backup", entry.name))?;
+        let mut hasher = Sha256::new();
+        let size = std::io::copy(&mut file, &mut hasher)?;
+        if size != entry.size || format!("{:x}", hasher.finalize()) != entry.sha256 {
+            anyhow::bail!(
+                "{} does not match its checksum; the backup is damaged",
+                entry.name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Move a verified backup from `staging` into the data directory
+fn install(settings: &Settings, staging: &Path, manifest: &Manifest) -> Result<BackupSummary> {
+    let db_path = settings.database_path();
+    if db_path.exists() {
+        let mut aside = db_path.as_os_str().to_owned();
+        aside.push(format!(".{}.bak", Local::now().format("%Y%m%d-%H%M%S")));
+        std::fs::rename(&db_path, &aside)
+            .with_context(|| format!("Failed to move {} aside", db_path.display()))?;
+        tracing::info!(
+            "Kept the previous database as {}",
+            Path::new(&aside).display()
+        );
+    }
+    std::fs::rename(staging.join(DATABASE), &db_path)
+        .with_context(|| format!("Failed to write {}", db_path.display()))?;
+
+    // Opening migrates backups made by older versions
+    let db = Database::open(settings)?;
+    let audio_dir = settings.audio_dir();
+    std::fs::create_dir_all(&audio_dir)
+        .with_context(|| format!("Failed to create {}", audio_dir.display()))?;
+
+    let mut audio_files = 0;
+    for entry in manifest.files.iter().filter(|file| file.name != DATABASE) {
+        let Some(file_name) = Path::new(&entry.name).file_name() else {
+            continue;
+        };
+        let target = audio_dir.join(file_name);
+        std::fs::rename(staging.join(&entry.name), &target)
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+        if let Some(original) = &entry.original_path {
+            db.relocate_audio_file(original, &target.to_string_lossy())?;
+        }
+        audio_files += 1;
+    }
+
+    Ok(BackupSummary {
+        recordings: db.get_stats()?.total_recordings,
+        audio_files,
+        bytes: manifest.files.iter().map(|file| file.size).sum(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Recording;
+
+    fn settings_in(dir: &Path) -> Settings {
+        let mut settings = Settings::default();
+        settings.general.data_dir = dir.to_path_buf();
+        settings
+    }
+
+    #[test]
+    fn restores_a_library_on_another_machine() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = settings_in(&tmp.path().join("laptop"));
+        let target = settings_in(&tmp.path().join("desktop"));
+
+        std::fs::create_dir_all(source.audio_dir()).unwrap();
+        let audio = source.audio_dir().join("standup.ogg");
+        std::fs::write(&audio, b"audio").unwrap();
+        let mut recording = Recording::new("Standup".to_string());
+        recording.audio_path = Some(audio.to_string_lossy().to_string());
+        recording.mic_audio_path = recording.audio_path.clone();
+        recording.state = crate::storage::RecordingState::Completed;
+        Database::open(&source)
+            .unwrap()
+            .insert_recording(&recording)
+            .unwrap();
+
+        let archive = tmp.path().join("library.tar.zst");
+        let created = create(&source, &archive).unwrap();
+        assert_eq!(created.recordings, 1);
+        assert_eq!(created.audio_files, 1);
+
+        let restored = restore(&target, &archive).unwrap();
+        assert_eq!(restored, created);
+        let moved = target.audio_dir().join("standup.ogg");
+        assert_eq!(std::fs::read(&moved).unwrap(), b"audio");
+        let recording = Database::open(&target)
+            .unwrap()
+            .get_recording(&recording.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            recording.audio_path.as_deref(),
+            Some(moved.to_string_lossy().as_ref())
+        );
+        assert_eq!(recording.mic_audio_path, recording.audio_path);
+        assert!(!target
+            .general
+            .data_dir
+            .join(format!(".restore-{}", std::process::id()))
+            .exists());
+    }
+
+    #[test]
+    fn rejects_damaged_files_before_touching_the_library() {
+        let tmp = tempfile::tempdir().unwrap();
+        let staging = tmp.path().join("staging");
+        std::fs::create_dir_all(&staging).unwrap();
+        std::fs::write(staging.join(DATABASE), b"database").unwrap();
+        let manifest = Manifest {
+            format: FORMAT,
+            created_at: Utc::now(),
+            files: vec![ManifestFile {
+                name: DATABASE.to_string(),
+                original_path: None,
+                size: 8,
+                sha256: format!("{:x}", Sha256::digest(b"database")),
+            }],
+        };
+        verify(&staging, &manifest).unwrap();
+
+        std::fs::write(staging.join(DATABASE), b"databasf").unwrap();
+        let error = verify(&staging, &manifest).unwrap_err();
+        assert!(error.to_string().contains("does not match its checksum"));
+    }
+}
@@ -1,19 +1,159 @@
 //! SQLite database management with FTS5 support
 
 use anyhow::{Context, Result};
-use chrono::{TimeZone, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use std::collections::HashMap;
 use std::path::Path;
 
 use crate::config::Settings;
-use crate::storage::models::{Recording, RecordingState, TranscriptSegment};
+use crate::storage::models::{
+    ActionItem, Chapter, Decision, JobState, LibraryStats, LlmUsage, LlmUsageTotal, Project,
+    Recording, RecordingFilter, RecordingSnapshot, RecordingState, SearchHit, Silence, Snapshot,
+    SpeakerProfile, Summary, TranscriptSegment, TranscriptionCheckpoint, TranscriptionJob,
+    MATCH_END, MATCH_START,
+};
+use crate::storage::query::SearchQuery;
+use crate::storage::vector;
 
 /// Database wrapper for minutes
 pub struct Database {
     conn: Connection,
 }
 
-const CURRENT_SCHEMA_VERSION: i64 = 1;
+const CURRENT_SCHEMA_VERSION: i64 = 34;
+
+/// Columns selected for a `Recording`, in the order `row_to_recording_offset` reads them
+const RECORDING_COLUMNS: &[&str] = &[
+    "id",
+    "title",
+    "audio_path",
+    "duration_secs",
+    "state",
+    "created_at",
+    "updated_at",
+    "notes",
+    "tags",
+    "system_audio_path",
+    "mic_audio_path",
+    "sensitivity",
+    "project",
+    "language",
+    "failure_reason",
+    "language_override",
+    "suggested_tags",
+    "deleted_at",
+    "archived_at",
+];
+
+/// Columns selected for a `TranscriptSegment`, in the order `row_to_segment_offset` reads them
+const SEGMENT_COLUMNS: &[&str] = &[
+    "id",
+    "recording_id",
+    "start_time",
+    "end_time",
+    "text",
+    "speaker",
+    "confidence",
+    "words",
+    "translated_text",
+    "edited",
+];
+
+/// Columns selected for a `Summary`, in the order `row_to_summary` reads them
+const SUMMARY_COLUMNS: &[&str] = &[
+    "id",
+    "recording_id",
+    "text",
+    "provider",
+    "model",
+    "prompt_version",
+    "created_at",
+    "approved_at",
+    "template",
+    "transcript_hash",
+    "selected_at",
+    "language",
+];
+
+/// Most words of a segment shown in a search excerpt
+const SEARCH_EXCERPT_TOKENS: usize = 24;
+
+/// Sort direction of queue times for newest-first or oldest-first order
+fn queue_direction(newest_first: bool) -> &'static str {
+    if newest_first {
+        "DESC"
+    } else {
+        "ASC"
+    }
+}
+
+/// Word timestamps of a segment as stored in the `words` column
+fn words_json(segment: &TranscriptSegment) -> Result<Option<String>> {
+    if segment.words.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string(&segment.words)?))
+}
+
+/// Comma-separated column list, optionally qualified with a table alias
+fn column_list(columns: &[&str], alias: Option<&str>) -> String {
+    columns
+        .iter()
+        .map(|column| match alias {
+            Some(alias) => format!("{}.{}", alias, column),
+            None => column.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// SQL conditions on the recordings table for `filter`, each led by `AND`,
+/// with their parameters numbered from `?first`
+fn filter_conditions(
+    filter: &RecordingFilter,
+    alias: Option<&str>,
+    first: usize,
+) -> (String, Vec<Value>) {
+    let prefix = alias.map(|alias| format!("{}.", alias)).unwrap_or_default();
+    let mut sql = String::new();
+    let mut values = Vec::new();
+    let mut condition = |test: &str, value: Value| {
+        sql.push_str(&format!(
+            " AND {}{} ?{}",
+            prefix,
+            test,
+            first + values.len()
+        ));
+        values.push(value);
+    };
+
+    if let Some(since) = filter.since {
+        condition("created_at >=", Value::Integer(since.timestamp()));
+    }
+    if let Some(until) = filter.until {
+        condition("created_at <", Value::Integer(until.timestamp()));
+    }
+    if let Some(secs) = filter.min_duration_secs {
+        condition("duration_secs >=", Value::Integer(secs as i64));
+    }
+    if let Some(state) = filter.state {
+        condition("state =", Value::Text(state.as_str().to_string()));
+    }
+    if let Some(participant) = &filter.participant {
+        sql.push_str(&format!(
+            " AND EXISTS (
+                 SELECT 1 FROM recording_participants
+                 WHERE recording_id = {}.id AND name = ?{} COLLATE NOCASE
+             )",
+            alias.unwrap_or("recordings"),
+            first + values.len()
+        ));
+        values.push(Value::Text(participant.trim().to_string()));
+    }
+    (sql, values)
+}
 
 impl Database {
     /// Open or create the database
@@ -30,18 +170,99 @@ impl Database {
 
     /// Open database at a specific path (useful for testing)
     pub fn open_path(path: &Path) -> Result<Self> {
+        #[cfg(feature = "sqlite-vec")]
+        vector::register();
         let conn = Connection::open(path)
             .with_context(|| format!("Failed to open database: {}", path.display()))?;
 
         let db = Self { conn };
+        db.backup_before_migration(path)?;
         db.initialize()?;
 
         Ok(db)
     }
 
+    /// Copy an existing database aside before its schema is migrated
+    ///
+    /// Migrations cannot be undone by the binary that ran them, so the old
+    /// file is kept next to the database as `<name>.v<version>.bak`.
+    fn backup_before_migration(&self, path: &Path) -> Result<()> {
+        let version = self.schema_version()?;
+        if version == 0 || version >= CURRENT_SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(format!(".v{}.bak", version));
+        let backup = std::path::PathBuf::from(backup);
+        if backup.exists() {
+            return Ok(());
+        }
+
+        self.vacuum_into(&backup)?;
+        tracing::info!(
+            "Backed up schema v{} database to {} before migrating",
+            version,
+            backup.display()
+        );
+        Ok(())
+    }
+
+    /// Write a consistent copy of the database to `path`, which must not exist
+    pub fn vacuum_into(&self, path: &Path) -> Result<()> {
+        self.conn
+            .execute("VACUUM INTO ?1", params![path.to_string_lossy()])
+            .with_context(|| format!("Failed to back up database to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Problems found by `PRAGMA integrity_check`, empty for a sound database
+    pub fn integrity_check(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let problems = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(problems
+            .into_iter()
+            .filter(|problem| problem != "ok")
+            .collect())
+    }
+
+    /// Rebuild the full-text index from the segments and merge its b-trees
+    pub fn rebuild_search_index(&self) -> Result<()> {
+        self.conn.execute_batch(
+            "INSERT INTO transcript_fts(transcript_fts) VALUES ('rebuild');
+             INSERT INTO transcript_fts(transcript_fts) VALUES ('optimize');",
+        )?;
+        Ok(())
+    }
+
+    /// Rewrite the database file, returning the space left by deleted rows
+    pub fn vacuum(&self) -> Result<()> {
+        self.conn.execute_batch("VACUUM")?;
+        Ok(())
+    }
+
+    /// Bytes used by each table and index, largest first
+    ///
+    /// The search index shows up as its `transcript_fts_*` shadow tables.
+    pub fn table_sizes(&self) -> Result<Vec<(String, u64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, SUM(pgsize) FROM dbstat
+             GROUP BY name
+             ORDER BY SUM(pgsize) DESC, name",
+        )?;
+        let sizes = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as u64)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(sizes)
+    }
+
     /// Open an in-memory database (for testing)
     #[cfg(test)]
     pub fn open_memory() -> Result<Self> {
+        #[cfg(feature = "sqlite-vec")]
+        vector::register();
         let conn = Connection::open_in_memory()?;
         let db = Self { conn };
         db.initialize()?;
@@ -67,6 +288,171 @@ impl Database {
             self.set_schema_version(1)?;
         }
 
+        if current_version < 2 {
+            self.migrate_to_v2()?;
+            self.set_schema_version(2)?;
+        }
+
+        if current_version < 3 {
+            self.migrate_to_v3()?;
+            self.set_schema_version(3)?;
+        }
+
+        if current_version < 4 {
+            self.migrate_to_v4()?;
+            self.set_schema_version(4)?;
+        }
+
+        if current_version < 5 {
+            self.migrate_to_v5()?;
+            self.set_schema_version(5)?;
+        }
+
+        if current_version < 6 {
+            self.migrate_to_v6()?;
+            self.set_schema_version(6)?;
+        }
+
+        if current_version < 7 {
+            self.migrate_to_v7()?;
+            self.set_schema_version(7)?;
+        }
+
+        if current_version < 8 {
+            self.migrate_to_v8()?;
+            self.set_schema_version(8)?;
+        }
+
+        if current_version < 9 {
+            self.migrate_to_v9()?;
+            self.set_schema_version(9)?;
+        }
+
+        if current_version < 10 {
+            self.migrate_to_v10()?;
+            self.set_schema_version(10)?;
+        }
+
+        if current_version < 11 {
+            self.migrate_to_v11()?;
+            self.set_schema_version(11)?;
+        }
+
+        if current_version < 12 {
+            self.migrate_to_v12()?;
+            self.set_schema_version(12)?;
+        }
+
+        if current_version < 13 {
+            self.migrate_to_v13()?;
+            self.set_schema_version(13)?;
+        }
+
+        if current_version < 14 {
+            self.migrate_to_v14()?;
+            self.set_schema_version(14)?;
+        }
+
+        if current_version < 15 {
+            self.migrate_to_v15()?;
+            self.set_schema_version(15)?;
+        }
+
+        if current_version < 16 {
+            self.migrate_to_v16()?;
+            self.set_schema_version(16)?;
+        }
+
+        if current_version < 17 {
+            self.migrate_to_v17()?;
+            self.set_schema_version(17)?;
+        }
+
+        if current_version < 18 {
+            self.migrate_to_v18()?;
+            self.set_schema_version(18)?;
+        }
+
+        if current_version < 19 {
+            self.migrate_to_v19()?;
+            self.set_schema_version(19)?;
+        }
+
+        if current_version < 20 {
+            self.migrate_to_v20()?;
+            self.set_schema_version(20)?;
+        }
+
+        if current_version < 21 {
+            self.migrate_to_v21()?;
+            self.set_schema_version(21)?;
+        }
+
+        if current_version < 22 {
+            self.migrate_to_v22()?;
+            self.set_schema_version(22)?;
+        }
+
+        if current_version < 23 {
+            self.migrate_to_v23()?;
+            self.set_schema_version(23)?;
+        }
+
+        if current_version < 24 {
+            self.migrate_to_v24()?;
+            self.set_schema_version(24)?;
+        }
+
+        if current_version < 25 {
+            self.migrate_to_v25()?;
+            self.set_schema_version(25)?;
+        }
+
+        if current_version < 26 {
+            self.migrate_to_v26()?;
+            self.set_schema_version(26)?;
+        }
+
+        if current_version < 27 {
+            self.migrate_to_v27()?;
+            self.set_schema_version(27)?;
+        }
+
+        if current_version < 28 {
+            self.migrate_to_v28()?;
+            self.set_schema_version(28)?;
+        }
+
+        if current_version < 29 {
+            self.migrate_to_v29()?;
+            self.set_schema_version(29)?;
+        }
+
+        if current_version < 30 {
+            self.migrate_to_v30()?;
+            self.set_schema_version(30)?;
+        }
+
+        if current_version < 31 {
+            self.migrate_to_v31()?;
+            self.set_schema_version(31)?;
+        }
+
+        if current_version < 32 {
+            self.migrate_to_v32()?;
+            self.set_schema_version(32)?;
+        }
+
+        if current_version < 33 {
+            self.migrate_to_v33()?;
+            self.set_schema_version(33)?;
+        }
+
+        if current_version < 34 {
+            self.migrate_to_v34()?;
+            self.set_schema_version(34)?;
+        }
+
         Ok(())
     }
 
@@ -161,382 +547,4034 @@ impl Database {
         Ok(())
     }
 
-    /// Insert a new recording
-    pub fn insert_recording(&self, recording: &Recording) -> Result<()> {
-        let tags_json = serde_json::to_string(&recording.tags)?;
-
-        self.conn.execute(
+    fn migrate_to_v2(&self) -> Result<()> {
+        // Separate per-source tracks kept alongside the mixed audio file
+        self.conn.execute_batch(
             r#"
-            INSERT INTO recordings (id, title, audio_path, duration_secs, state, created_at, updated_at, notes, tags)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            ALTER TABLE recordings ADD COLUMN system_audio_path TEXT;
+            ALTER TABLE recordings ADD COLUMN mic_audio_path TEXT;
             "#,
-            params![
-                recording.id,
-                recording.title,
-                recording.audio_path,
-                recording.duration_secs,
-                recording.state.as_str(),
-                recording.created_at.timestamp(),
-                recording.updated_at.timestamp(),
-                recording.notes,
-                tags_json,
-            ],
         )?;
 
         Ok(())
     }
 
-    /// Update a recording
-    pub fn update_recording(&self, recording: &Recording) -> Result<()> {
-        let tags_json = serde_json::to_string(&recording.tags)?;
-
-        self.conn.execute(
+    fn migrate_to_v3(&self) -> Result<()> {
+        // Enrolled voices used to name speakers
+        self.conn.execute_batch(
             r#"
-            UPDATE recordings
-            SET title = ?2, audio_path = ?3, duration_secs = ?4, state = ?5,
-                updated_at = ?6, notes = ?7, tags = ?8
-            WHERE id = ?1
+            CREATE TABLE IF NOT EXISTS speaker_profiles (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                embedding BLOB NOT NULL,
+                source_recording_id TEXT,
+                created_at INTEGER NOT NULL
+            );
             "#,
-            params![
-                recording.id,
-                recording.title,
-                recording.audio_path,
-                recording.duration_secs,
-                recording.state.as_str(),
-                Utc::now().timestamp(),
-                recording.notes,
-                tags_json,
-            ],
         )?;
 
         Ok(())
     }
 
-    /// Get a recording by ID
-    pub fn get_recording(&self, id: &str) -> Result<Option<Recording>> {
-        let result = self.conn.query_row(
-            "SELECT id, title, audio_path, duration_secs, state, created_at, updated_at, notes, tags FROM recordings WHERE id = ?1",
-            params![id],
-            |row| Ok(Self::row_to_recording(row)),
-        ).optional()?;
+    fn migrate_to_v4(&self) -> Result<()> {
+        // Sensitivity label (normal, confidential)
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE recordings ADD COLUMN sensitivity TEXT NOT NULL DEFAULT 'normal';
+            "#,
+        )?;
 
-        match result {
-            Some(r) => Ok(Some(r?)),
-            None => Ok(None),
-        }
+        Ok(())
     }
 
-    /// Find a recording by ID prefix
-    pub fn find_recording_by_prefix(&self, prefix: &str) -> Result<Option<Recording>> {
-        let pattern = format!("{}%", prefix);
-
-        let result = self.conn.query_row(
-            "SELECT id, title, audio_path, duration_secs, state, created_at, updated_at, notes, tags FROM recordings WHERE id LIKE ?1 LIMIT 1",
-            params![pattern],
-            |row| Ok(Self::row_to_recording(row)),
-        ).optional()?;
+    fn migrate_to_v5(&self) -> Result<()> {
+        // People who attended a recording, in the order they were listed
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS recording_participants (
+                recording_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                PRIMARY KEY (recording_id, name),
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+            "#,
+        )?;
 
-        match result {
-            Some(r) => Ok(Some(r?)),
-            None => Ok(None),
-        }
+        Ok(())
     }
 
-    /// List recordings ordered by creation date
-    pub fn list_recordings(&self, limit: usize) -> Result<Vec<Recording>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, title, audio_path, duration_secs, state, created_at, updated_at, notes, tags
-             FROM recordings
-             ORDER BY created_at DESC
-             LIMIT ?1",
-        )?;
+    fn migrate_to_v6(&self) -> Result<()> {
+        // Every generated summary with the provider, model and prompt behind it
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS summaries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recording_id TEXT NOT NULL,
+                text TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                prompt_version INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
 
-        let recordings = stmt
-            .query_map(params![limit], |row| Ok(Self::row_to_recording(row)))?
-            .collect::<rusqlite::Result<Vec<_>>>()?
-            .into_iter()
-            .collect::<Result<Vec<_>>>()?;
+            CREATE INDEX IF NOT EXISTS idx_summaries_recording_id
+                ON summaries(recording_id, created_at DESC);
+            "#,
+        )?;
 
-        Ok(recordings)
+        Ok(())
     }
 
-    /// Search recordings by title
-    pub fn search_recordings(&self, query: &str, limit: usize) -> Result<Vec<Recording>> {
-        let pattern = format!("%{}%", query);
-
-        let mut stmt = self.conn.prepare(
-            "SELECT id, title, audio_path, duration_secs, state, created_at, updated_at, notes, tags
-             FROM recordings
-             WHERE title LIKE ?1
-             ORDER BY created_at DESC
-             LIMIT ?2",
+    fn migrate_to_v7(&self) -> Result<()> {
+        // Sign-off time for minutes that went through review
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE summaries ADD COLUMN approved_at INTEGER;
+            "#,
         )?;
 
-        let recordings = stmt
-            .query_map(params![pattern, limit], |row| {
-                Ok(Self::row_to_recording(row))
-            })?
-            .collect::<rusqlite::Result<Vec<_>>>()?
-            .into_iter()
-            .collect::<Result<Vec<_>>>()?;
-
-        Ok(recordings)
+        Ok(())
     }
 
-    /// Delete a recording and its segments
-    pub fn delete_recording(&self, id: &str) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM recordings WHERE id = ?1", params![id])?;
+    fn migrate_to_v8(&self) -> Result<()> {
+        // Numbered chunk files of a segmented recording, in capture order
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS recording_chunks (
+                recording_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                PRIMARY KEY (recording_id, position),
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+            "#,
+        )?;
+
         Ok(())
     }
 
-    /// Insert a transcript segment
-    pub fn insert_segment(&self, segment: &TranscriptSegment) -> Result<i64> {
-        self.conn.execute(
+    fn migrate_to_v9(&self) -> Result<()> {
+        // Capture problems (silent or clipped tracks) found when a recording stopped
+        self.conn.execute_batch(
             r#"
-            INSERT INTO transcript_segments (recording_id, start_time, end_time, text, speaker, confidence)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            CREATE TABLE IF NOT EXISTS recording_warnings (
+                recording_id TEXT NOT NULL,
+                message TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (recording_id, message),
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
             "#,
-            params![
-                segment.recording_id,
-                segment.start_time,
-                segment.end_time,
-                segment.text,
-                segment.speaker,
-                segment.confidence,
-            ],
         )?;
 
-        Ok(self.conn.last_insert_rowid())
+        Ok(())
     }
 
-    /// Insert multiple segments in a transaction
-    pub fn insert_segments(&self, segments: &[TranscriptSegment]) -> Result<()> {
-        let tx = self.conn.unchecked_transaction()?;
+    fn migrate_to_v10(&self) -> Result<()> {
+        // Projects group recordings above tags; a recording belongs to at most one
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS projects (
+                name TEXT PRIMARY KEY COLLATE NOCASE,
+                created_at INTEGER NOT NULL
+            );
 
+            ALTER TABLE recordings ADD COLUMN project TEXT
+                REFERENCES projects(name) ON UPDATE CASCADE ON DELETE SET NULL;
+
+            CREATE INDEX IF NOT EXISTS idx_recordings_project ON recordings(project);
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v11(&self) -> Result<()> {
+        // Safety snapshots of rows about to be changed by a destructive operation
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                operation TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                data TEXT NOT NULL,
+                audio_dir TEXT,
+                undone_at INTEGER
+            );
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v12(&self) -> Result<()> {
+        // Word-level timestamps of a segment as a JSON array (NULL when not extracted)
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE transcript_segments ADD COLUMN words TEXT;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v13(&self) -> Result<()> {
+        // Language of the transcript, configured or detected during transcription
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE recordings ADD COLUMN language TEXT;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v14(&self) -> Result<()> {
+        // Progress of unfinished transcriptions, per audio stream of a recording
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS transcription_checkpoints (
+                recording_id TEXT NOT NULL,
+                stream TEXT NOT NULL,
+                total_samples INTEGER NOT NULL,
+                done_samples INTEGER NOT NULL,
+                segments TEXT NOT NULL,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (recording_id, stream),
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v15(&self) -> Result<()> {
+        // English translation of a segment, kept next to the original text
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE transcript_segments ADD COLUMN translated_text TEXT;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v16(&self) -> Result<()> {
+        // Transcription queue; recordings already waiting become queued jobs
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS transcription_jobs (
+                recording_id TEXT PRIMARY KEY,
+                state TEXT NOT NULL DEFAULT 'queued',
+                priority INTEGER NOT NULL DEFAULT 0,
+                queued_at INTEGER NOT NULL,
+                started_at INTEGER,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            INSERT OR IGNORE INTO transcription_jobs (recording_id, queued_at)
+                SELECT id, updated_at FROM recordings WHERE state IN ('pending', 'transcribing');
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v17(&self) -> Result<()> {
+        // Segments corrected by hand with `minutes edit`
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE transcript_segments ADD COLUMN edited INTEGER NOT NULL DEFAULT 0;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v18(&self) -> Result<()> {
+        // Names given to a recording's speakers, reapplied when it is transcribed again
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS speaker_names (
+                recording_id TEXT NOT NULL,
+                label TEXT NOT NULL,
+                name TEXT NOT NULL,
+                PRIMARY KEY (recording_id, label),
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v19(&self) -> Result<()> {
+        // Failed transcriptions are retried with backoff and keep their error
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE recordings ADD COLUMN failure_reason TEXT;
+            ALTER TABLE transcription_jobs ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE transcription_jobs ADD COLUMN retry_at INTEGER;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v20(&self) -> Result<()> {
+        // A recording can be transcribed in a language other than whisper.language
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE recordings ADD COLUMN language_override TEXT;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v21(&self) -> Result<()> {
+        // Stretches without speech, found before transcription
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS silences (
+                recording_id TEXT NOT NULL,
+                start_time REAL NOT NULL,
+                end_time REAL NOT NULL,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_silences_recording_id
+                ON silences(recording_id);
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v22(&self) -> Result<()> {
+        // Action items extracted from transcripts by the LLM
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS action_items (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recording_id TEXT NOT NULL,
+                owner TEXT,
+                task TEXT NOT NULL,
+                due TEXT,
+                done_at INTEGER,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_action_items_recording_id
+                ON action_items(recording_id);
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v23(&self) -> Result<()> {
+        // Topic chapters generated from transcripts by the LLM
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS chapters (
+                recording_id TEXT NOT NULL,
+                start_time REAL NOT NULL,
+                title TEXT NOT NULL,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_chapters_recording_id
+                ON chapters(recording_id);
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v24(&self) -> Result<()> {
+        // Summaries can come from a user prompt template instead of the built-in prompt
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE summaries ADD COLUMN template TEXT;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v25(&self) -> Result<()> {
+        // Summaries remember their transcript so an unchanged one is not summarized again
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE summaries ADD COLUMN transcript_hash TEXT;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v26(&self) -> Result<()> {
+        // A previous summary can be restored, making it current again
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE summaries ADD COLUMN selected_at INTEGER;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v27(&self) -> Result<()> {
+        // Segment embeddings for semantic search, dropped when the text changes
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS segment_embeddings (
+                segment_id INTEGER PRIMARY KEY,
+                recording_id TEXT NOT NULL,
+                model TEXT NOT NULL,
+                vector BLOB NOT NULL,
+                FOREIGN KEY (segment_id) REFERENCES transcript_segments(id) ON DELETE CASCADE,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_segment_embeddings_model
+                ON segment_embeddings(model);
+
+            CREATE TRIGGER IF NOT EXISTS segment_embeddings_au
+            AFTER UPDATE OF text ON transcript_segments
+            WHEN old.text != new.text BEGIN
+                DELETE FROM segment_embeddings WHERE segment_id = old.id;
+            END;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v28(&self) -> Result<()> {
+        // Decisions extracted from transcripts by the LLM, with the
+        // timestamps supporting them as a JSON array of seconds
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS decisions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recording_id TEXT NOT NULL,
+                text TEXT NOT NULL,
+                timestamps TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (recording_id) REFERENCES recordings(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_decisions_recording_id
+                ON decisions(recording_id);
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v29(&self) -> Result<()> {
+        // Token counts of LLM calls. Rows outlive their recording so usage
+        // reports keep past months complete.
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS llm_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                operation TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                recording_id TEXT,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cost_usd REAL,
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_llm_usage_created_at
+                ON llm_usage(created_at);
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v30(&self) -> Result<()> {
+        // Summaries can be written in a language other than the meeting's
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE summaries ADD COLUMN language TEXT;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v31(&self) -> Result<()> {
+        // Tags proposed by the LLM, kept apart until they are accepted
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE recordings ADD COLUMN suggested_tags TEXT DEFAULT '[]';
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v32(&self) -> Result<()> {
+        // Recordings moved to the trash (NULL = not deleted)
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE recordings ADD COLUMN deleted_at INTEGER;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v33(&self) -> Result<()> {
+        // Recordings whose audio was moved to an archive (NULL = not archived)
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE recordings ADD COLUMN archived_at INTEGER;
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn migrate_to_v34(&self) -> Result<()> {
+        // Vector length, so semantic search only compares vectors of the
+        // query's length; vectors are stored as 4-byte floats
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE segment_embeddings ADD COLUMN dimensions INTEGER NOT NULL DEFAULT 0;
+            UPDATE segment_embeddings SET dimensions = length(vector) / 4;
+
+            CREATE INDEX IF NOT EXISTS idx_segment_embeddings_model_dimensions
+                ON segment_embeddings(model, dimensions);
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    /// Insert a new recording
+    pub fn insert_recording(&self, recording: &Recording) -> Result<()> {
+        let tags_json = serde_json::to_string(&recording.tags)?;
+        let suggested_tags_json = serde_json::to_string(&recording.suggested_tags)?;
+
+        self.conn.execute(
+            r#"
+            INSERT INTO recordings (id, title, audio_path, duration_secs, state, created_at, updated_at, notes, tags,
+                                    system_audio_path, mic_audio_path, sensitivity, project, language,
+                                    failure_reason, language_override, suggested_tags, deleted_at,
+                                    archived_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
+                    ?19)
+            "#,
+            params![
+                recording.id,
+                recording.title,
+                recording.audio_path,
+                recording.duration_secs,
+                recording.state.as_str(),
+                recording.created_at.timestamp(),
+                recording.updated_at.timestamp(),
+                recording.notes,
+                tags_json,
+                recording.system_audio_path,
+                recording.mic_audio_path,
+                recording.sensitivity.as_str(),
+                recording.project,
+                recording.language,
+                recording.failure_reason,
+                recording.language_override,
+                suggested_tags_json,
+                recording.deleted_at.map(|at| at.timestamp()),
+                recording.archived_at.map(|at| at.timestamp()),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Update a recording
+    ///
+    /// Whether it is in the trash or archived is left alone; see
+    /// [`Self::trash_recording`] and [`Self::set_recording_archived`].
+    pub fn update_recording(&self, recording: &Recording) -> Result<()> {
+        let tags_json = serde_json::to_string(&recording.tags)?;
+        let suggested_tags_json = serde_json::to_string(&recording.suggested_tags)?;
+
+        self.conn.execute(
+            r#"
+            UPDATE recordings
+            SET title = ?2, audio_path = ?3, duration_secs = ?4, state = ?5,
+                updated_at = ?6, notes = ?7, tags = ?8,
+                system_audio_path = ?9, mic_audio_path = ?10, sensitivity = ?11,
+                project = ?12, language = ?13, failure_reason = ?14,
+                language_override = ?15, suggested_tags = ?16
+            WHERE id = ?1
+            "#,
+            params![
+                recording.id,
+                recording.title,
+                recording.audio_path,
+                recording.duration_secs,
+                recording.state.as_str(),
+                Utc::now().timestamp(),
+                recording.notes,
+                tags_json,
+                recording.system_audio_path,
+                recording.mic_audio_path,
+                recording.sensitivity.as_str(),
+                recording.project,
+                recording.language,
+                recording.failure_reason,
+                recording.language_override,
+                suggested_tags_json,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get a recording by ID
+    pub fn get_recording(&self, id: &str) -> Result<Option<Recording>> {
+        let result = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM recordings WHERE id = ?1",
+                    column_list(RECORDING_COLUMNS, None)
+                ),
+                params![id],
+                |row| Ok(Self::row_to_recording(row)),
+            )
+            .optional()?;
+
+        match result {
+            Some(r) => Ok(Some(r?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Find a recording by ID prefix, leaving out recordings in the trash
+    pub fn find_recording_by_prefix(&self, prefix: &str) -> Result<Option<Recording>> {
+        let pattern = format!("{}%", prefix);
+
+        let result = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM recordings WHERE id LIKE ?1 AND deleted_at IS NULL LIMIT 1",
+                    column_list(RECORDING_COLUMNS, None)
+                ),
+                params![pattern],
+                |row| Ok(Self::row_to_recording(row)),
+            )
+            .optional()?;
+
+        match result {
+            Some(r) => Ok(Some(r?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List recordings matching `filter` ordered by creation date
+    pub fn list_recordings(
+        &self,
+        filter: &RecordingFilter,
+        limit: usize,
+    ) -> Result<Vec<Recording>> {
+        let (conditions, values) = filter_conditions(filter, None, 2);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM recordings
+             WHERE deleted_at IS NULL{}
+             ORDER BY created_at DESC
+             LIMIT ?1",
+            column_list(RECORDING_COLUMNS, None),
+            conditions
+        ))?;
+
+        let values = std::iter::once(Value::Integer(limit as i64)).chain(values);
+        let recordings = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(Self::row_to_recording(row))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(recordings)
+    }
+
+    /// List recordings in a project matching `filter` ordered by creation date
+    pub fn list_project_recordings(
+        &self,
+        project: &str,
+        filter: &RecordingFilter,
+        limit: usize,
+    ) -> Result<Vec<Recording>> {
+        let (conditions, values) = filter_conditions(filter, None, 3);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM recordings
+             WHERE project = ?1 COLLATE NOCASE AND deleted_at IS NULL{}
+             ORDER BY created_at DESC
+             LIMIT ?2",
+            column_list(RECORDING_COLUMNS, None),
+            conditions
+        ))?;
+
+        let values = [
+            Value::Text(project.to_string()),
+            Value::Integer(limit as i64),
+        ]
+        .into_iter()
+        .chain(values);
+        let recordings = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(Self::row_to_recording(row))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(recordings)
+    }
+
+    /// Recordings with a tag, compared without case, matching `filter`, newest first
+    pub fn list_tagged_recordings(
+        &self,
+        tag: &str,
+        filter: &RecordingFilter,
+        limit: usize,
+    ) -> Result<Vec<Recording>> {
+        let (conditions, values) = filter_conditions(filter, None, 3);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM recordings
+             WHERE deleted_at IS NULL AND json_valid(tags) AND EXISTS (
+                 SELECT 1 FROM json_each(recordings.tags) WHERE value = ?1 COLLATE NOCASE
+             ){}
+             ORDER BY created_at DESC
+             LIMIT ?2",
+            column_list(RECORDING_COLUMNS, None),
+            conditions
+        ))?;
+
+        let values = [Value::Text(tag.to_string()), Value::Integer(limit as i64)]
+            .into_iter()
+            .chain(values);
+        let recordings = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(Self::row_to_recording(row))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(recordings)
+    }
+
+    /// Search recordings matching `filter` by title
+    pub fn search_recordings(
+        &self,
+        query: &str,
+        filter: &RecordingFilter,
+        limit: usize,
+    ) -> Result<Vec<Recording>> {
+        let pattern = format!("%{}%", query);
+
+        let (conditions, values) = filter_conditions(filter, None, 3);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM recordings
+             WHERE title LIKE ?1 AND deleted_at IS NULL{}
+             ORDER BY created_at DESC
+             LIMIT ?2",
+            column_list(RECORDING_COLUMNS, None),
+            conditions
+        ))?;
+
+        let values = [Value::Text(pattern), Value::Integer(limit as i64)]
+            .into_iter()
+            .chain(values);
+        let recordings = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(Self::row_to_recording(row))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(recordings)
+    }
+
+    /// Delete a recording and its segments
+    pub fn delete_recording(&self, id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM recordings WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Paths of every audio file and chunk of finished recordings, trash included
+    pub fn audio_file_paths(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT path FROM (
+                 SELECT audio_path AS path, state FROM recordings
+                 UNION SELECT system_audio_path, state FROM recordings
+                 UNION SELECT mic_audio_path, state FROM recordings
+                 UNION SELECT c.path, r.state FROM recording_chunks c
+                     JOIN recordings r ON r.id = c.recording_id
+             )
+             WHERE path IS NOT NULL AND state != 'recording'
+             GROUP BY path
+             ORDER BY path",
+        )?;
+        let paths = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(paths)
+    }
+
+    /// Point every recording and chunk using the audio file `from` at `to`
+    pub fn relocate_audio_file(&self, from: &str, to: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for column in ["audio_path", "system_audio_path", "mic_audio_path"] {
+            tx.execute(
+                &format!("UPDATE recordings SET {column} = ?2 WHERE {column} = ?1"),
+                params![from, to],
+            )?;
+        }
+        tx.execute(
+            "UPDATE recording_chunks SET path = ?2 WHERE path = ?1",
+            params![from, to],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Move a recording to the trash, taking it out of the transcription queue
+    pub fn trash_recording(&self, id: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE recordings SET deleted_at = ?2 WHERE id = ?1",
+            params![id, Utc::now().timestamp()],
+        )?;
+        tx.execute(
+            "DELETE FROM transcription_jobs WHERE recording_id = ?1",
+            params![id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Mark a recording's audio as moved to an archive, or as back in place
+    pub fn set_recording_archived(&self, id: &str, archived: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET archived_at = ?2 WHERE id = ?1",
+            params![id, archived.then(|| Utc::now().timestamp())],
+        )?;
+        Ok(())
+    }
+
+    /// Take a recording out of the trash
+    pub fn restore_trashed_recording(&self, id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET deleted_at = NULL WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Find a recording in the trash by ID prefix
+    pub fn find_trashed_recording_by_prefix(&self, prefix: &str) -> Result<Option<Recording>> {
+        let pattern = format!("{}%", prefix);
+
+        let result = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM recordings WHERE id LIKE ?1 AND deleted_at IS NOT NULL LIMIT 1",
+                    column_list(RECORDING_COLUMNS, None)
+                ),
+                params![pattern],
+                |row| Ok(Self::row_to_recording(row)),
+            )
+            .optional()?;
+
+        match result {
+            Some(r) => Ok(Some(r?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Recordings in the trash since before `before` (all when `None`), most recently deleted first
+    pub fn list_trashed_recordings(&self, before: Option<DateTime<Utc>>) -> Result<Vec<Recording>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM recordings
+             WHERE deleted_at IS NOT NULL AND deleted_at < ?1
+             ORDER BY deleted_at DESC",
+            column_list(RECORDING_COLUMNS, None)
+        ))?;
+
+        let before = before.map(|before| before.timestamp()).unwrap_or(i64::MAX);
+        let recordings = stmt
+            .query_map(params![before], |row| Ok(Self::row_to_recording(row)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(recordings)
+    }
+
+    /// Insert a transcript segment
+    pub fn insert_segment(&self, segment: &TranscriptSegment) -> Result<i64> {
+        self.conn.execute(
+            r#"
+            INSERT INTO transcript_segments (recording_id, start_time, end_time, text, speaker, confidence, words, translated_text, edited)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+            params![
+                segment.recording_id,
+                segment.start_time,
+                segment.end_time,
+                segment.text,
+                segment.speaker,
+                segment.confidence,
+                words_json(segment)?,
+                segment.translated_text,
+                segment.edited,
+            ],
+        )?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Insert multiple segments in a transaction
+    pub fn insert_segments(&self, segments: &[TranscriptSegment]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::insert_segment_rows(&tx, segments)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn insert_segment_rows(conn: &Connection, segments: &[TranscriptSegment]) -> Result<()> {
+        for segment in segments {
+            conn.execute(
+                r#"
+                INSERT INTO transcript_segments (recording_id, start_time, end_time, text, speaker, confidence, words, translated_text, edited)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                "#,
+                params![
+                    segment.recording_id,
+                    segment.start_time,
+                    segment.end_time,
+                    segment.text,
+                    segment.speaker,
+                    segment.confidence,
+                    words_json(segment)?,
+                    segment.translated_text,
+                    segment.edited,
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Replace all transcript segments of a recording
+    pub fn replace_segments(
+        &self,
+        recording_id: &str,
+        segments: &[TranscriptSegment],
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM transcript_segments WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        Self::insert_segment_rows(&tx, segments)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get transcript segments for a recording
+    pub fn get_transcript_segments(&self, recording_id: &str) -> Result<Vec<TranscriptSegment>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM transcript_segments
+             WHERE recording_id = ?1
+             ORDER BY start_time",
+            column_list(SEGMENT_COLUMNS, None)
+        ))?;
+
+        let segments = stmt
+            .query_map(params![recording_id], |row| {
+                Self::row_to_segment_offset(row, 0)
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(segments)
+    }
+
+    /// Replace the silences stored for a recording
+    pub fn replace_silences(&self, recording_id: &str, silences: &[Silence]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM silences WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO silences (recording_id, start_time, end_time) VALUES (?1, ?2, ?3)",
+            )?;
+            for silence in silences {
+                stmt.execute(params![recording_id, silence.start_time, silence.end_time])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stretches of a recording without speech, in order
+    pub fn get_silences(&self, recording_id: &str) -> Result<Vec<Silence>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT start_time, end_time FROM silences WHERE recording_id = ?1 ORDER BY start_time",
+        )?;
+
+        let silences = stmt
+            .query_map(params![recording_id], |row| {
+                Ok(Silence {
+                    start_time: row.get(0)?,
+                    end_time: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(silences)
+    }
+
+    /// Replace the chapters stored for a recording
+    pub fn replace_chapters(&self, recording_id: &str, chapters: &[Chapter]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM chapters WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO chapters (recording_id, start_time, title) VALUES (?1, ?2, ?3)",
+            )?;
+            for chapter in chapters {
+                stmt.execute(params![recording_id, chapter.start_time, chapter.title])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Chapters of a recording, in order
+    pub fn get_chapters(&self, recording_id: &str) -> Result<Vec<Chapter>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT start_time, title FROM chapters WHERE recording_id = ?1 ORDER BY start_time",
+        )?;
+
+        let chapters = stmt
+            .query_map(params![recording_id], |row| {
+                Ok(Chapter {
+                    start_time: row.get(0)?,
+                    title: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(chapters)
+    }
+
+    /// Replace the action items extracted from a recording
+    ///
+    /// An item whose task matches one already marked done, ignoring case,
+    /// stays done, so extracting again does not reopen finished work.
+    pub fn replace_action_items(&self, recording_id: &str, items: &[ActionItem]) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "SELECT task, done_at FROM action_items
+             WHERE recording_id = ?1 AND done_at IS NOT NULL",
+        )?;
+        let done: Vec<(String, i64)> = stmt
+            .query_map(params![recording_id], |row| {
+                Ok((row.get::<_, String>(0)?.to_lowercase(), row.get(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM action_items WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        for item in items {
+            let task = item.task.to_lowercase();
+            let done_at = item.done_at.map(|at| at.timestamp()).or_else(|| {
+                done.iter()
+                    .find(|(done_task, _)| *done_task == task)
+                    .map(|(_, at)| *at)
+            });
+            Self::insert_action_item_row(&tx, recording_id, item, done_at)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn insert_action_item_row(
+        conn: &Connection,
+        recording_id: &str,
+        item: &ActionItem,
+        done_at: Option<i64>,
+    ) -> Result<()> {
+        conn.execute(
+            "INSERT INTO action_items (recording_id, owner, task, due, done_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                recording_id,
+                item.owner,
+                item.task,
+                item.due,
+                done_at,
+                item.created_at.timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Action items of a recording, in the order they were extracted
+    pub fn get_action_items(&self, recording_id: &str) -> Result<Vec<ActionItem>> {
+        self.query_action_items(
+            "SELECT a.id, a.recording_id, a.owner, a.task, a.due, a.done_at, a.created_at
+             FROM action_items a
+             WHERE a.recording_id = ?1
+             ORDER BY a.id",
+            params![recording_id],
+        )
+    }
+
+    /// Action items not yet done across all recordings, newest meeting first
+    pub fn list_open_action_items(&self) -> Result<Vec<ActionItem>> {
+        self.query_action_items(
+            "SELECT a.id, a.recording_id, a.owner, a.task, a.due, a.done_at, a.created_at
+             FROM action_items a
+             JOIN recordings r ON r.id = a.recording_id
+             WHERE a.done_at IS NULL AND r.deleted_at IS NULL
+             ORDER BY r.created_at DESC, a.id",
+            params![],
+        )
+    }
+
+    fn query_action_items(
+        &self,
+        sql: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<ActionItem>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let items = stmt
+            .query_map(params, |row| {
+                let done_at: Option<i64> = row.get(5)?;
+                let created_at: i64 = row.get(6)?;
+                Ok(ActionItem {
+                    id: row.get(0)?,
+                    recording_id: row.get(1)?,
+                    owner: row.get(2)?,
+                    task: row.get(3)?,
+                    due: row.get(4)?,
+                    done_at: done_at.map(|at| Utc.timestamp_opt(at, 0).unwrap()),
+                    created_at: Utc.timestamp_opt(created_at, 0).unwrap(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    /// Mark an action item done, or open again with `None`
+    ///
+    /// Returns false if there is no item with that ID.
+    pub fn set_action_item_done(&self, id: i64, done_at: Option<DateTime<Utc>>) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE action_items SET done_at = ?2 WHERE id = ?1",
+            params![id, done_at.map(|at| at.timestamp())],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Replace the decisions extracted from a recording
+    pub fn replace_decisions(&self, recording_id: &str, decisions: &[Decision]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM decisions WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        for decision in decisions {
+            Self::insert_decision_row(&tx, recording_id, decision)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn insert_decision_row(
+        conn: &Connection,
+        recording_id: &str,
+        decision: &Decision,
+    ) -> Result<()> {
+        conn.execute(
+            "INSERT INTO decisions (recording_id, text, timestamps, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                recording_id,
+                decision.text,
+                serde_json::to_string(&decision.timestamps)?,
+                decision.created_at.timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Decisions of a recording, in the order they were extracted
+    pub fn get_decisions(&self, recording_id: &str) -> Result<Vec<Decision>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, recording_id, text, timestamps, created_at
+             FROM decisions
+             WHERE recording_id = ?1
+             ORDER BY id",
+        )?;
+        let decisions = stmt
+            .query_map(params![recording_id], |row| {
+                let timestamps: String = row.get(3)?;
+                let created_at: i64 = row.get(4)?;
+                Ok(Decision {
+                    id: row.get(0)?,
+                    recording_id: row.get(1)?,
+                    text: row.get(2)?,
+                    timestamps: serde_json::from_str(&timestamps).unwrap_or_default(),
+                    created_at: Utc.timestamp_opt(created_at, 0).unwrap(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(decisions)
+    }
+
+    /// Record the tokens used by an LLM call
+    pub fn record_llm_usage(&self, usage: &LlmUsage) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO llm_usage (operation, provider, model, recording_id, input_tokens,
+                                    output_tokens, cost_usd, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                usage.operation,
+                usage.provider,
+                usage.model,
+                usage.recording_id,
+                usage.input_tokens as i64,
+                usage.output_tokens as i64,
+                usage.cost_usd,
+                usage.created_at.timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// LLM usage per month (UTC), provider and model, newest month first
+    pub fn llm_usage_by_month(&self) -> Result<Vec<LlmUsageTotal>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT strftime('%Y-%m', created_at, 'unixepoch') AS month, provider, model,
+                    COUNT(*), SUM(input_tokens), SUM(output_tokens), SUM(cost_usd)
+             FROM llm_usage
+             GROUP BY month, provider, model
+             ORDER BY month DESC, provider, model",
+        )?;
+        let totals = stmt
+            .query_map([], |row| {
+                Ok(LlmUsageTotal {
+                    month: row.get(0)?,
+                    provider: row.get(1)?,
+                    model: row.get(2)?,
+                    calls: row.get::<_, i64>(3)? as u64,
+                    input_tokens: row.get::<_, i64>(4)? as u64,
+                    output_tokens: row.get::<_, i64>(5)? as u64,
+                    cost_usd: row.get(6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(totals)
+    }
+
+    /// Replace the text of segments corrected by hand and mark them edited
+    ///
+    /// Segments whose text is unchanged are left alone. Word timings of a
+    /// changed segment are dropped, since they no longer match its text.
+    /// Returns the number of segments changed.
+    pub fn update_segment_texts(
+        &self,
+        recording_id: &str,
+        edits: &[(i64, String)],
+    ) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut changed = 0;
+        for (id, text) in edits {
+            changed += tx.execute(
+                "UPDATE transcript_segments SET text = ?3, words = NULL, edited = 1
+                 WHERE id = ?1 AND recording_id = ?2 AND text != ?3",
+                params![id, recording_id, text],
+            )?;
+        }
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Full-text search across the transcripts of recordings matching `filter`
+    ///
+    /// The query is read by [`SearchQuery::parse`]. Each hit carries an
+    /// excerpt of the segment with the matched terms marked. Should the
+    /// full-text index reject the query, the transcripts are scanned for the
+    /// query's words instead, newest recording first.
+    pub fn search_transcripts(
+        &self,
+        query: &str,
+        filter: &RecordingFilter,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let query = SearchQuery::parse(query);
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        match self.match_transcripts(&query, filter, limit) {
+            Err(rusqlite::Error::SqliteFailure(_, Some(message)))
+                if message.starts_with("fts5") =>
+            {
+                tracing::debug!(
+                    "Scanning transcripts after full-text search failed: {}",
+                    message
+                );
+                self.scan_transcripts(&query, filter, limit)
+            }
+            results => Ok(results?),
+        }
+    }
+
+    fn match_transcripts(
+        &self,
+        query: &SearchQuery,
+        filter: &RecordingFilter,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<SearchHit>> {
+        let (conditions, values) = filter_conditions(filter, Some("r"), 3);
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            SELECT {}, {},
+                   snippet(transcript_fts, 1, '{}', '{}', '…', {})
+            FROM transcript_fts f
+            JOIN transcript_segments s ON f.rowid = s.id
+            JOIN recordings r ON s.recording_id = r.id
+            WHERE transcript_fts MATCH ?1 AND r.deleted_at IS NULL{}
+            ORDER BY rank
+            LIMIT ?2
+            "#,
+            column_list(RECORDING_COLUMNS, Some("r")),
+            column_list(SEGMENT_COLUMNS, Some("s")),
+            MATCH_START,
+            MATCH_END,
+            SEARCH_EXCERPT_TOKENS,
+            conditions
+        ))?;
+
+        let values = [Value::Text(query.to_fts()), Value::Integer(limit as i64)]
+            .into_iter()
+            .chain(values);
+        let results = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(SearchHit {
+                    recording: Self::row_to_recording_offset(row, 0)?,
+                    segment: Self::row_to_segment_offset(row, RECORDING_COLUMNS.len())?,
+                    excerpt: row.get(RECORDING_COLUMNS.len() + SEGMENT_COLUMNS.len())?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(results)
+    }
+
+    /// Segments whose text matches `query`, ignoring ASCII case
+    fn scan_transcripts(
+        &self,
+        query: &SearchQuery,
+        filter: &RecordingFilter,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let (matches, patterns) = query.like_condition("s.text", 2);
+        let (conditions, values) = filter_conditions(filter, Some("r"), patterns.len() + 2);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, {}
+             FROM transcript_segments s
+             JOIN recordings r ON s.recording_id = r.id
+             WHERE r.deleted_at IS NULL AND {}{}
+             ORDER BY r.created_at DESC, s.start_time
+             LIMIT ?1",
+            column_list(RECORDING_COLUMNS, Some("r")),
+            column_list(SEGMENT_COLUMNS, Some("s")),
+            matches,
+            conditions
+        ))?;
+
+        let values = std::iter::once(Value::Integer(limit as i64))
+            .chain(patterns.into_iter().map(Value::Text))
+            .chain(values);
+        let results = stmt
+            .query_map(params_from_iter(values), |row| {
+                let segment = Self::row_to_segment_offset(row, RECORDING_COLUMNS.len())?;
+                Ok(SearchHit {
+                    recording: Self::row_to_recording_offset(row, 0)?,
+                    excerpt: query.highlight(&segment.text),
+                    segment,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(results)
+    }
+
+    /// Segments without an embedding from `model`, oldest recording first
+    ///
+    /// Confidential recordings are left out unless `include_confidential`.
+    pub fn segments_missing_embeddings(
+        &self,
+        model: &str,
+        include_confidential: bool,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM transcript_segments s
+             JOIN recordings r ON s.recording_id = r.id
+             LEFT JOIN segment_embeddings e ON e.segment_id = s.id AND e.model = ?1
+             WHERE e.segment_id IS NULL
+               AND r.deleted_at IS NULL
+               AND (?2 OR r.sensitivity != 'confidential')
+             ORDER BY r.created_at, s.start_time",
+            column_list(SEGMENT_COLUMNS, Some("s"))
+        ))?;
+
+        let segments = stmt
+            .query_map(params![model, include_confidential], |row| {
+                Self::row_to_segment_offset(row, 0)
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(segments)
+    }
+
+    /// Store embeddings of segments, replacing any they had
+    pub fn insert_segment_embeddings(
+        &self,
+        model: &str,
+        embeddings: &[(&TranscriptSegment, Vec<f32>)],
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (segment, vector) in embeddings {
+            tx.execute(
+                "INSERT OR REPLACE INTO segment_embeddings
+                     (segment_id, recording_id, model, vector, dimensions)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    segment.id,
+                    segment.recording_id,
+                    model,
+                    vector::to_blob(vector),
+                    vector.len()
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Embeddings from `model` by segment ID
+    ///
+    /// Confidential recordings are left out unless `include_confidential`.
+    pub fn segment_embeddings(
+        &self,
+        model: &str,
+        include_confidential: bool,
+    ) -> Result<Vec<(i64, Vec<f32>)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT e.segment_id, e.vector
+             FROM segment_embeddings e
+             JOIN recordings r ON e.recording_id = r.id
+             WHERE e.model = ?1
+               AND r.deleted_at IS NULL
+               AND (?2 OR r.sensitivity != 'confidential')",
+        )?;
+
+        let embeddings = stmt
+            .query_map(params![model, include_confidential], |row| {
+                let vector: Vec<u8> = row.get(1)?;
+                Ok((row.get(0)?, vector::from_blob(&vector)))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(embeddings)
+    }
+
+    /// The `k` segments with embeddings from `model` closest to
+    /// `query_embedding`, best first, with their cosine similarity
+    ///
+    /// Confidential recordings are left out unless `include_confidential`.
+    pub fn search_semantic(
+        &self,
+        model: &str,
+        query_embedding: &[f32],
+        k: usize,
+        include_confidential: bool,
+    ) -> Result<Vec<(Recording, TranscriptSegment, f32)>> {
+        let ranked = self.nearest_segments(model, query_embedding, k, include_confidential)?;
+        let ids: Vec<i64> = ranked.iter().map(|(id, _)| *id).collect();
+
+        Ok(self
+            .get_segments_with_recordings(&ids)?
+            .into_iter()
+            .map(|(recording, segment)| {
+                let score = ranked
+                    .iter()
+                    .find(|(id, _)| *id == segment.id)
+                    .map_or(0.0, |(_, score)| *score);
+                (recording, segment, score)
+            })
+            .collect())
+    }
+
+    /// Segment IDs and scores of the `k` nearest embeddings, ranked by sqlite-vec
+    #[cfg(feature = "sqlite-vec")]
+    fn nearest_segments(
+        &self,
+        model: &str,
+        query_embedding: &[f32],
+        k: usize,
+        include_confidential: bool,
+    ) -> Result<Vec<(i64, f32)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT e.segment_id, 1.0 - vec_distance_cosine(e.vector, ?2) AS score
+             FROM segment_embeddings e
+             JOIN recordings r ON e.recording_id = r.id
+             WHERE e.model = ?1
+               AND e.dimensions = ?3
+               AND r.deleted_at IS NULL
+               AND (?4 OR r.sensitivity != 'confidential')
+             ORDER BY score DESC
+             LIMIT ?5",
+        )?;
+
+        let ranked = stmt
+            .query_map(
+                params![
+                    model,
+                    vector::to_blob(query_embedding),
+                    query_embedding.len(),
+                    include_confidential,
+                    k
+                ],
+                |row| Ok((row.get(0)?, row.get::<_, f64>(1)? as f32)),
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(ranked)
+    }
+
+    /// Segment IDs and scores of the `k` nearest embeddings, compared one by one
+    #[cfg(not(feature = "sqlite-vec"))]
+    fn nearest_segments(
+        &self,
+        model: &str,
+        query_embedding: &[f32],
+        k: usize,
+        include_confidential: bool,
+    ) -> Result<Vec<(i64, f32)>> {
+        let mut candidates = self.segment_embeddings(model, include_confidential)?;
+        candidates.retain(|(_, vector)| vector.len() == query_embedding.len());
+        Ok(vector::rank_by_similarity(query_embedding, &candidates, k))
+    }
+
+    /// Segments by ID with their recordings, in the order of `segment_ids`
+    pub fn get_segments_with_recordings(
+        &self,
+        segment_ids: &[i64],
+    ) -> Result<Vec<(Recording, TranscriptSegment)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, {}
+             FROM transcript_segments s
+             JOIN recordings r ON s.recording_id = r.id
+             WHERE s.id = ?1",
+            column_list(RECORDING_COLUMNS, Some("r")),
+            column_list(SEGMENT_COLUMNS, Some("s"))
+        ))?;
+
+        let mut results = Vec::with_capacity(segment_ids.len());
+        for id in segment_ids {
+            let result = stmt
+                .query_row(params![id], |row| {
+                    let recording = Self::row_to_recording_offset(row, 0)?;
+                    let segment = Self::row_to_segment_offset(row, RECORDING_COLUMNS.len())?;
+                    Ok((recording, segment))
+                })
+                .optional()?;
+            results.extend(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Get recordings with pending transcription
+    pub fn get_pending_recordings(&self) -> Result<Vec<Recording>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM recordings
+             WHERE state = 'pending' AND deleted_at IS NULL
+             ORDER BY created_at ASC",
+            column_list(RECORDING_COLUMNS, None)
+        ))?;
+
+        let recordings = stmt
+            .query_map([], |row| Ok(Self::row_to_recording(row)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(recordings)
+    }
+
+    /// List recordings created at or after `since` (all when `None`), oldest first
+    pub fn list_recordings_since(&self, since: Option<DateTime<Utc>>) -> Result<Vec<Recording>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM recordings
+             WHERE created_at >= ?1 AND deleted_at IS NULL
+             ORDER BY created_at ASC",
+            column_list(RECORDING_COLUMNS, None)
+        ))?;
+
+        let since = since.map(|since| since.timestamp()).unwrap_or(i64::MIN);
+        let recordings = stmt
+            .query_map(params![since], |row| Ok(Self::row_to_recording(row)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(recordings)
+    }
+
+    /// Insert a speaker profile, replacing any existing profile with the same name
+    pub fn upsert_speaker_profile(&self, profile: &SpeakerProfile) -> Result<i64> {
+        let embedding: Vec<u8> = profile
+            .embedding
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+
+        self.conn.execute(
+            r#"
+            INSERT INTO speaker_profiles (name, embedding, source_recording_id, created_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(name) DO UPDATE SET
+                embedding = excluded.embedding,
+                source_recording_id = excluded.source_recording_id,
+                created_at = excluded.created_at
+            "#,
+            params![
+                profile.name,
+                embedding,
+                profile.source_recording_id,
+                profile.created_at.timestamp(),
+            ],
+        )?;
+
+        Ok(self.conn.query_row(
+            "SELECT id FROM speaker_profiles WHERE name = ?1",
+            params![profile.name],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// List enrolled speaker profiles ordered by name
+    pub fn list_speaker_profiles(&self) -> Result<Vec<SpeakerProfile>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, embedding, source_recording_id, created_at
+             FROM speaker_profiles
+             ORDER BY name COLLATE NOCASE",
+        )?;
+
+        let profiles = stmt
+            .query_map([], |row| {
+                let embedding: Vec<u8> = row.get(2)?;
+                let created_at: i64 = row.get(4)?;
+                Ok(SpeakerProfile {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    embedding: embedding
+                        .chunks_exact(4)
+                        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                        .collect(),
+                    source_recording_id: row.get(3)?,
+                    created_at: Utc.timestamp_opt(created_at, 0).unwrap(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(profiles)
+    }
+
+    /// Delete a speaker profile by name, returning whether one existed
+    pub fn delete_speaker_profile(&self, name: &str) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM speaker_profiles WHERE name = ?1",
+            params![name],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Replace the participants of a recording
+    pub fn set_participants(&self, recording_id: &str, participants: &[String]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM recording_participants WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        for (position, name) in participants.iter().enumerate() {
+            if name.trim().is_empty() {
+                continue;
+            }
+            tx.execute(
+                "INSERT OR IGNORE INTO recording_participants (recording_id, position, name)
+                 VALUES (?1, ?2, ?3)",
+                params![recording_id, position as i64, name.trim()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Participants of a recording in the order they were listed
+    pub fn get_participants(&self, recording_id: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name FROM recording_participants
+             WHERE recording_id = ?1
+             ORDER BY position",
+        )?;
+
+        let names = stmt
+            .query_map(params![recording_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        Ok(names)
+    }
+
+    /// Rename a speaker of a recording and remember the name
+    ///
+    /// The name is kept against the speaker's original label, so a speaker
+    /// renamed twice still maps from the label transcription gives it.
+    /// Returns the number of segments relabelled.
+    pub fn rename_speaker(&self, recording_id: &str, from: &str, to: &str) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let relabelled = tx.execute(
+            "UPDATE transcript_segments SET speaker = ?3 WHERE recording_id = ?1 AND speaker = ?2",
+            params![recording_id, from, to],
+        )?;
+        let renamed = tx.execute(
+            "UPDATE speaker_names SET name = ?3 WHERE recording_id = ?1 AND name = ?2",
+            params![recording_id, from, to],
+        )?;
+        if renamed == 0 {
+            tx.execute(
+                "INSERT OR REPLACE INTO speaker_names (recording_id, label, name)
+                 VALUES (?1, ?2, ?3)",
+                params![recording_id, from, to],
+            )?;
+        }
+        tx.commit()?;
+        Ok(relabelled)
+    }
+
+    /// Names given to a recording's speakers, as `(label, name)` pairs
+    pub fn get_speaker_names(&self, recording_id: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT label, name FROM speaker_names WHERE recording_id = ?1 ORDER BY label",
+        )?;
+
+        let names = stmt
+            .query_map(params![recording_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(names)
+    }
+
+    /// Relabel new segments of a recording with the names given to its speakers
+    pub fn apply_speaker_names(
+        &self,
+        recording_id: &str,
+        segments: &mut [TranscriptSegment],
+    ) -> Result<()> {
+        let names = self.get_speaker_names(recording_id)?;
         for segment in segments {
+            if let Some((_, name)) = names
+                .iter()
+                .find(|(label, _)| segment.speaker.as_deref() == Some(label.as_str()))
+            {
+                segment.speaker = Some(name.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Record a chunk file of a segmented recording at `position` (0-based)
+    pub fn add_chunk(&self, recording_id: &str, position: usize, path: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO recording_chunks (recording_id, position, path)
+             VALUES (?1, ?2, ?3)",
+            params![recording_id, position as i64, path],
+        )?;
+        Ok(())
+    }
+
+    /// Replace the chunk list of a recording
+    pub fn set_chunks(&self, recording_id: &str, paths: &[String]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM recording_chunks WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        for (position, path) in paths.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO recording_chunks (recording_id, position, path)
+                 VALUES (?1, ?2, ?3)",
+                params![recording_id, position as i64, path],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Chunk files of a segmented recording in capture order (empty if unsegmented)
+    pub fn get_chunks(&self, recording_id: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT path FROM recording_chunks
+             WHERE recording_id = ?1
+             ORDER BY position",
+        )?;
+
+        let paths = stmt
+            .query_map(params![recording_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        Ok(paths)
+    }
+
+    /// Chunk files of every segmented recording by recording id, in capture order
+    pub fn all_chunks(&self) -> Result<HashMap<String, Vec<String>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT recording_id, path FROM recording_chunks
+             ORDER BY recording_id, position",
+        )?;
+
+        let mut chunks: HashMap<String, Vec<String>> = HashMap::new();
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (recording_id, path) = row?;
+            chunks.entry(recording_id).or_default().push(path);
+        }
+
+        Ok(chunks)
+    }
+
+    /// Store transcription progress, replacing earlier progress on the same stream
+    pub fn save_checkpoint(
+        &self,
+        recording_id: &str,
+        checkpoint: &TranscriptionCheckpoint,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO transcription_checkpoints
+                 (recording_id, stream, total_samples, done_samples, segments, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                recording_id,
+                checkpoint.stream,
+                checkpoint.total_samples as i64,
+                checkpoint.done_samples as i64,
+                serde_json::to_string(&checkpoint.segments)?,
+                Utc::now().timestamp()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Saved transcription progress of a recording, one entry per stream
+    pub fn get_checkpoints(&self, recording_id: &str) -> Result<Vec<TranscriptionCheckpoint>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT stream, total_samples, done_samples, segments
+             FROM transcription_checkpoints
+             WHERE recording_id = ?1
+             ORDER BY stream",
+        )?;
+
+        let rows = stmt
+            .query_map(params![recording_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(|(stream, total_samples, done_samples, segments)| {
+                Ok(TranscriptionCheckpoint {
+                    stream,
+                    total_samples: total_samples as usize,
+                    done_samples: done_samples as usize,
+                    segments: serde_json::from_str(&segments)
+                        .context("Invalid segments in transcription checkpoint")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Forget the transcription progress of a recording
+    pub fn clear_checkpoints(&self, recording_id: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM transcription_checkpoints WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        Ok(())
+    }
+
+    /// Queue recordings again whose transcription was cut short by the daemon stopping
+    ///
+    /// Returns the number of recordings queued.
+    pub fn requeue_interrupted_transcriptions(&self) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let queued = tx.execute(
+            "UPDATE recordings SET state = 'pending', updated_at = ?1 WHERE state = 'transcribing'",
+            params![Utc::now().timestamp()],
+        )?;
+        // An attempt cut short by a restart does not count toward the retry limit
+        tx.execute(
+            "UPDATE transcription_jobs
+             SET state = 'queued', started_at = NULL, attempts = MAX(attempts - 1, 0)
+             WHERE state = 'running'",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(queued)
+    }
+
+    /// Add a queued job for every pending recording that has none
+    ///
+    /// Cancelled jobs keep their recording out of the queue.
+    pub fn queue_pending_recordings(&self) -> Result<usize> {
+        let queued = self.conn.execute(
+            "INSERT OR IGNORE INTO transcription_jobs (recording_id, queued_at)
+             SELECT id, ?1 FROM recordings WHERE state = 'pending' AND deleted_at IS NULL",
+            params![Utc::now().timestamp()],
+        )?;
+        Ok(queued)
+    }
+
+    /// Queue a recording for transcription, also when its job was cancelled
+    pub fn queue_job(&self, recording_id: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO transcription_jobs (recording_id, queued_at) VALUES (?1, ?2)
+             ON CONFLICT(recording_id) DO UPDATE
+                 SET state = 'queued', queued_at = excluded.queued_at, started_at = NULL,
+                     attempts = 0, retry_at = NULL",
+            params![recording_id, Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Jobs in the order they will run: running first, then queued, then cancelled
+    pub fn list_jobs(&self, newest_first: bool) -> Result<Vec<TranscriptionJob>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT j.recording_id, r.title, j.state, j.priority, j.queued_at, j.started_at,
+                    j.attempts, j.retry_at
+             FROM transcription_jobs j
+             JOIN recordings r ON r.id = j.recording_id
+             ORDER BY CASE j.state WHEN 'running' THEN 0 WHEN 'queued' THEN 1 ELSE 2 END,
+                      j.priority DESC, j.queued_at {}, j.rowid {}",
+            queue_direction(newest_first),
+            queue_direction(newest_first)
+        ))?;
+
+        let jobs = stmt
+            .query_map([], |row| {
+                let state: String = row.get(2)?;
+                let queued_at: i64 = row.get(4)?;
+                let started_at: Option<i64> = row.get(5)?;
+                let retry_at: Option<i64> = row.get(7)?;
+                Ok(TranscriptionJob {
+                    recording_id: row.get(0)?,
+                    title: row.get(1)?,
+                    state: state.parse().unwrap_or(JobState::Queued),
+                    priority: row.get(3)?,
+                    queued_at: Utc.timestamp_opt(queued_at, 0).unwrap(),
+                    started_at: started_at.map(|ts| Utc.timestamp_opt(ts, 0).unwrap()),
+                    attempts: row.get(6)?,
+                    retry_at: retry_at.map(|ts| Utc.timestamp_opt(ts, 0).unwrap()),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(jobs)
+    }
+
+    /// Job of a recording, if it has one
+    pub fn get_job(&self, recording_id: &str) -> Result<Option<TranscriptionJob>> {
+        Ok(self
+            .list_jobs(false)?
+            .into_iter()
+            .find(|job| job.recording_id == recording_id))
+    }
+
+    /// Mark the next queued job that is due as running and return its recording
+    pub fn start_next_job(&self, newest_first: bool) -> Result<Option<Recording>> {
+        let now = Utc::now();
+        let next = self
+            .list_jobs(newest_first)?
+            .into_iter()
+            .find(|job| job.state == JobState::Queued && job.retry_at.is_none_or(|at| at <= now));
+        let Some(job) = next else {
+            return Ok(None);
+        };
+
+        self.conn.execute(
+            "UPDATE transcription_jobs
+             SET state = 'running', started_at = ?2, attempts = attempts + 1, retry_at = NULL
+             WHERE recording_id = ?1",
+            params![job.recording_id, now.timestamp()],
+        )?;
+        self.get_recording(&job.recording_id)
+    }
+
+    /// Put a failed job back in the queue, to start no earlier than `retry_at`
+    pub fn retry_job(&self, recording_id: &str, retry_at: DateTime<Utc>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transcription_jobs SET state = 'queued', started_at = NULL, retry_at = ?2
+             WHERE recording_id = ?1",
+            params![recording_id, retry_at.timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Record why transcription of a recording failed and move it to `state`
+    pub fn record_failure(
+        &self,
+        recording_id: &str,
+        state: RecordingState,
+        reason: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET state = ?2, failure_reason = ?3, updated_at = ?4 WHERE id = ?1",
+            params![recording_id, state.as_str(), reason, Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Forget the failure reason once a recording transcribed successfully
+    pub fn clear_failure(&self, recording_id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET failure_reason = NULL WHERE id = ?1",
+            params![recording_id],
+        )?;
+        Ok(())
+    }
+
+    /// Move a queued job ahead of every other job
+    ///
+    /// Returns false when the recording has no queued job.
+    pub fn bump_job(&self, recording_id: &str) -> Result<bool> {
+        let bumped = self.conn.execute(
+            "UPDATE transcription_jobs
+             SET priority = (SELECT MAX(priority) FROM transcription_jobs) + 1
+             WHERE recording_id = ?1 AND state = 'queued'",
+            params![recording_id],
+        )?;
+        Ok(bumped > 0)
+    }
+
+    /// Take a job out of the queue until transcription is requested again
+    ///
+    /// A running job goes back to pending; returns false when the recording
+    /// has no queued or running job.
+    pub fn cancel_job(&self, recording_id: &str) -> Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+        let cancelled = tx.execute(
+            "UPDATE transcription_jobs SET state = 'cancelled', started_at = NULL
+             WHERE recording_id = ?1 AND state IN ('queued', 'running')",
+            params![recording_id],
+        )?;
+        if cancelled > 0 {
             tx.execute(
-                r#"
-                INSERT INTO transcript_segments (recording_id, start_time, end_time, text, speaker, confidence)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
-                "#,
-                params![
-                    segment.recording_id,
-                    segment.start_time,
-                    segment.end_time,
-                    segment.text,
-                    segment.speaker,
-                    segment.confidence,
-                ],
+                "UPDATE recordings SET state = 'pending', updated_at = ?2
+                 WHERE id = ?1 AND state = 'transcribing'",
+                params![recording_id, Utc::now().timestamp()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(cancelled > 0)
+    }
+
+    /// Remove a job once its transcription completed or failed
+    pub fn finish_job(&self, recording_id: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM transcription_jobs WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        Ok(())
+    }
+
+    /// Store capture warnings for a recording, ignoring ones already stored
+    pub fn add_warnings(&self, recording_id: &str, messages: &[String]) -> Result<()> {
+        let now = Utc::now().timestamp();
+        for message in messages {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO recording_warnings (recording_id, message, created_at)
+                 VALUES (?1, ?2, ?3)",
+                params![recording_id, message, now],
             )?;
         }
+        Ok(())
+    }
+
+    /// Capture warnings of a recording, oldest first
+    pub fn get_warnings(&self, recording_id: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT message FROM recording_warnings
+             WHERE recording_id = ?1
+             ORDER BY created_at, rowid",
+        )?;
+
+        let messages = stmt
+            .query_map(params![recording_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        Ok(messages)
+    }
+
+    /// Create a project; returns false if one with that name already exists
+    pub fn create_project(&self, name: &str) -> Result<bool> {
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO projects (name, created_at) VALUES (?1, ?2)",
+            params![name, Utc::now().timestamp()],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Look up a project by name, ignoring case
+    pub fn get_project(&self, name: &str) -> Result<Option<Project>> {
+        Ok(self
+            .list_projects()?
+            .into_iter()
+            .find(|project| project.name.eq_ignore_ascii_case(name)))
+    }
+
+    /// List projects by name with their recording counts and durations
+    pub fn list_projects(&self) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT p.name, p.created_at, COUNT(r.id), COALESCE(SUM(r.duration_secs), 0)
+            FROM projects p
+            LEFT JOIN recordings r ON r.project = p.name AND r.deleted_at IS NULL
+            GROUP BY p.name
+            ORDER BY p.name
+            "#,
+        )?;
+
+        let projects = stmt
+            .query_map([], |row| {
+                let created_timestamp: i64 = row.get(1)?;
+                let count: i64 = row.get(2)?;
+                let duration: i64 = row.get(3)?;
+                Ok(Project {
+                    name: row.get(0)?,
+                    created_at: Utc.timestamp_opt(created_timestamp, 0).unwrap(),
+                    recording_count: count as usize,
+                    total_duration_secs: duration as u64,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(projects)
+    }
+
+    /// Record the language of a recording's transcript
+    pub fn set_recording_language(&self, recording_id: &str, language: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET language = ?2, updated_at = ?3 WHERE id = ?1",
+            params![recording_id, language, Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Rename a recording
+    pub fn set_recording_title(&self, recording_id: &str, title: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET title = ?2, updated_at = ?3 WHERE id = ?1",
+            params![recording_id, title, Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Replace the tags of a recording
+    pub fn set_recording_tags(&self, recording_id: &str, tags: &[String]) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET tags = ?2, updated_at = ?3 WHERE id = ?1",
+            params![
+                recording_id,
+                serde_json::to_string(tags)?,
+                Utc::now().timestamp()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Replace the tags suggested for a recording and not yet accepted
+    pub fn set_suggested_tags(&self, recording_id: &str, tags: &[String]) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET suggested_tags = ?2 WHERE id = ?1",
+            params![recording_id, serde_json::to_string(tags)?],
+        )?;
+        Ok(())
+    }
+
+    /// Every tag used in the library, sorted
+    pub fn list_tags(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT tag.value
+             FROM recordings, json_each(recordings.tags) AS tag
+             WHERE json_valid(recordings.tags) AND recordings.deleted_at IS NULL
+             ORDER BY tag.value COLLATE NOCASE",
+        )?;
+        let tags = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(tags)
+    }
+
+    /// Assign a recording to a project, or remove it from its project with `None`
+    pub fn set_recording_project(&self, recording_id: &str, project: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET project = ?2, updated_at = ?3 WHERE id = ?1",
+            params![recording_id, project, Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Record a generated summary
+    pub fn insert_summary(&self, summary: &Summary) -> Result<i64> {
+        self.conn.execute(
+            r#"
+            INSERT INTO summaries (recording_id, text, provider, model, prompt_version, created_at,
+                                   approved_at, template, transcript_hash, selected_at, language)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            "#,
+            params![
+                summary.recording_id,
+                summary.text,
+                summary.provider,
+                summary.model,
+                summary.prompt_version,
+                summary.created_at.timestamp(),
+                summary.approved_at.map(|at| at.timestamp()),
+                summary.template,
+                summary.transcript_hash,
+                summary.selected_at.map(|at| at.timestamp()),
+                summary.language,
+            ],
+        )?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Current summary of a recording: the most recently generated or restored one
+    pub fn latest_summary(&self, recording_id: &str) -> Result<Option<Summary>> {
+        let summary = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM summaries
+                     WHERE recording_id = ?1
+                     ORDER BY COALESCE(selected_at, created_at) DESC, id DESC
+                     LIMIT 1",
+                    column_list(SUMMARY_COLUMNS, None)
+                ),
+                params![recording_id],
+                Self::row_to_summary,
+            )
+            .optional()?;
+
+        Ok(summary)
+    }
+
+    /// Summary by ID
+    pub fn get_summary(&self, id: i64) -> Result<Option<Summary>> {
+        let summary = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM summaries WHERE id = ?1",
+                    column_list(SUMMARY_COLUMNS, None)
+                ),
+                params![id],
+                Self::row_to_summary,
+            )
+            .optional()?;
+
+        Ok(summary)
+    }
+
+    /// Make a previous summary current again, replacing the recording's notes with it
+    pub fn restore_summary(&self, summary: &Summary) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = Utc::now().timestamp();
+        tx.execute(
+            "UPDATE summaries SET selected_at = ?2 WHERE id = ?1",
+            params![summary.id, now],
+        )?;
+        tx.execute(
+            "UPDATE recordings SET notes = ?2, updated_at = ?3 WHERE id = ?1",
+            params![summary.recording_id, summary.text, now],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Approve a summary, or return it to draft with `None`
+    pub fn set_summary_approved(
+        &self,
+        summary_id: i64,
+        approved_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE summaries SET approved_at = ?2 WHERE id = ?1",
+            params![summary_id, approved_at.map(|at| at.timestamp())],
+        )?;
+        Ok(())
+    }
+
+    /// Recordings matching `filter` whose current summary is not approved, newest first
+    pub fn list_unapproved_recordings(
+        &self,
+        filter: &RecordingFilter,
+        limit: usize,
+    ) -> Result<Vec<Recording>> {
+        let (conditions, values) = filter_conditions(filter, None, 2);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM recordings
+             WHERE TRIM(COALESCE(notes, '')) != ''
+               AND deleted_at IS NULL
+               AND NOT EXISTS (
+                   SELECT 1 FROM summaries s
+                   WHERE s.recording_id = recordings.id
+                     AND s.approved_at IS NOT NULL
+                     AND s.id = (
+                         SELECT id FROM summaries
+                         WHERE recording_id = recordings.id
+                         ORDER BY COALESCE(selected_at, created_at) DESC, id DESC
+                         LIMIT 1
+                     )
+               ){}
+             ORDER BY created_at DESC
+             LIMIT ?1",
+            column_list(RECORDING_COLUMNS, None),
+            conditions
+        ))?;
+
+        let values = std::iter::once(Value::Integer(limit as i64)).chain(values);
+        let recordings = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(Self::row_to_recording(row))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(recordings)
+    }
+
+    /// Save the rows of `recording_ids` before `operation` changes or removes them
+    ///
+    /// Unknown IDs are skipped. Returns the snapshot ID.
+    pub fn create_snapshot(&self, operation: &str, recording_ids: &[&str]) -> Result<i64> {
+        let mut recordings = Vec::new();
+        for id in recording_ids {
+            let Some(recording) = self.get_recording(id)? else {
+                continue;
+            };
+            recordings.push(RecordingSnapshot {
+                segments: self.get_transcript_segments(id)?,
+                summaries: self.list_summaries(id)?,
+                participants: self.get_participants(id)?,
+                chunks: self.get_chunks(id)?,
+                warnings: self.get_warnings(id)?,
+                speaker_names: self.get_speaker_names(id)?,
+                action_items: self.get_action_items(id)?,
+                decisions: self.get_decisions(id)?,
+                recording,
+            });
+        }
+
+        self.conn.execute(
+            "INSERT INTO snapshots (operation, created_at, data) VALUES (?1, ?2, ?3)",
+            params![
+                operation,
+                Utc::now().timestamp(),
+                serde_json::to_string(&recordings)?
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Record where a snapshot's audio copies were written
+    pub fn set_snapshot_audio_dir(&self, id: i64, audio_dir: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE snapshots SET audio_dir = ?2 WHERE id = ?1",
+            params![id, audio_dir],
+        )?;
+        Ok(())
+    }
+
+    /// Snapshots that have not been undone yet, newest first
+    pub fn list_snapshots(&self, limit: usize) -> Result<Vec<Snapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, operation, created_at, data, audio_dir
+             FROM snapshots
+             WHERE undone_at IS NULL
+             ORDER BY id DESC
+             LIMIT ?1",
+        )?;
+
+        let rows = stmt
+            .query_map(params![limit], |row| {
+                let created_at: i64 = row.get(2)?;
+                let data: String = row.get(3)?;
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    created_at,
+                    data,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(|(id, operation, created_at, data, audio_dir)| {
+                Ok(Snapshot {
+                    id,
+                    operation,
+                    created_at: Utc.timestamp_opt(created_at, 0).unwrap(),
+                    recordings: serde_json::from_str(&data)
+                        .with_context(|| format!("Snapshot {} is corrupt", id))?,
+                    audio_dir,
+                })
+            })
+            .collect()
+    }
+
+    /// Put the rows of a snapshot back and mark it undone
+    ///
+    /// Each recording's current rows are replaced by the saved ones, and
+    /// recordings that were deleted are recreated.
+    pub fn restore_snapshot(&self, snapshot: &Snapshot) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for saved in &snapshot.recordings {
+            let id = &saved.recording.id;
+            tx.execute("DELETE FROM recordings WHERE id = ?1", params![id])?;
+
+            let mut recording = saved.recording.clone();
+            if let Some(project) = &recording.project {
+                recording.project = self.get_project(project)?.map(|project| project.name);
+            }
+            self.insert_recording(&recording)?;
+            Self::insert_segment_rows(&tx, &saved.segments)?;
+            for summary in &saved.summaries {
+                self.insert_summary(summary)?;
+            }
+            for (position, name) in saved.participants.iter().enumerate() {
+                tx.execute(
+                    "INSERT OR IGNORE INTO recording_participants (recording_id, position, name)
+                     VALUES (?1, ?2, ?3)",
+                    params![id, position as i64, name],
+                )?;
+            }
+            for (position, path) in saved.chunks.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO recording_chunks (recording_id, position, path)
+                     VALUES (?1, ?2, ?3)",
+                    params![id, position as i64, path],
+                )?;
+            }
+            self.add_warnings(id, &saved.warnings)?;
+            for (label, name) in &saved.speaker_names {
+                tx.execute(
+                    "INSERT INTO speaker_names (recording_id, label, name) VALUES (?1, ?2, ?3)",
+                    params![id, label, name],
+                )?;
+            }
+            for item in &saved.action_items {
+                let done_at = item.done_at.map(|at| at.timestamp());
+                Self::insert_action_item_row(&tx, id, item, done_at)?;
+            }
+            for decision in &saved.decisions {
+                Self::insert_decision_row(&tx, id, decision)?;
+            }
+        }
+        tx.execute(
+            "UPDATE snapshots SET undone_at = ?2 WHERE id = ?1",
+            params![snapshot.id, Utc::now().timestamp()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Delete all but the newest `keep` snapshots, returning the audio directories they used
+    pub fn prune_snapshots(&self, keep: usize) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT audio_dir FROM snapshots
+             WHERE id NOT IN (SELECT id FROM snapshots ORDER BY id DESC LIMIT ?1)
+               AND audio_dir IS NOT NULL",
+        )?;
+        let audio_dirs = stmt
+            .query_map(params![keep], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        self.conn.execute(
+            "DELETE FROM snapshots
+             WHERE id NOT IN (SELECT id FROM snapshots ORDER BY id DESC LIMIT ?1)",
+            params![keep],
+        )?;
+        Ok(audio_dirs)
+    }
+
+    /// All summaries of a recording, oldest first
+    pub fn list_summaries(&self, recording_id: &str) -> Result<Vec<Summary>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM summaries
+             WHERE recording_id = ?1
+             ORDER BY created_at, id",
+            column_list(SUMMARY_COLUMNS, None)
+        ))?;
+
+        let summaries = stmt
+            .query_map(params![recording_id], Self::row_to_summary)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(summaries)
+    }
+
+    fn row_to_summary(row: &rusqlite::Row) -> rusqlite::Result<Summary> {
+        let created_at: i64 = row.get(6)?;
+        let approved_at: Option<i64> = row.get(7)?;
+        let selected_at: Option<i64> = row.get(10)?;
+        Ok(Summary {
+            id: row.get(0)?,
+            recording_id: row.get(1)?,
+            text: row.get(2)?,
+            provider: row.get(3)?,
+            model: row.get(4)?,
+            prompt_version: row.get(5)?,
+            created_at: Utc.timestamp_opt(created_at, 0).unwrap(),
+            approved_at: approved_at.map(|at| Utc.timestamp_opt(at, 0).unwrap()),
+            template: row.get(8)?,
+            transcript_hash: row.get(9)?,
+            selected_at: selected_at.map(|at| Utc.timestamp_opt(at, 0).unwrap()),
+            language: row.get(11)?,
+        })
+    }
+
+    /// Update recording state
+    pub fn update_recording_state(&self, id: &str, state: RecordingState) -> Result<()> {
+        self.conn.execute(
+            "UPDATE recordings SET state = ?2, updated_at = ?3 WHERE id = ?1",
+            params![id, state.as_str(), Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    // Helper to convert a row to a Recording
+    fn row_to_recording(row: &rusqlite::Row) -> Result<Recording> {
+        Ok(Self::row_to_recording_offset(row, 0)?)
+    }
+
+    fn row_to_recording_offset(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Recording> {
+        let state_str: String = row.get(offset + 4)?;
+        let created_timestamp: i64 = row.get(offset + 5)?;
+        let updated_timestamp: i64 = row.get(offset + 6)?;
+        let tags_json: String = row.get(offset + 8)?;
+        let sensitivity_str: String = row.get(offset + 11)?;
+        let suggested_tags_json: Option<String> = row.get(offset + 16)?;
+        let deleted_timestamp: Option<i64> = row.get(offset + 17)?;
+        let archived_timestamp: Option<i64> = row.get(offset + 18)?;
+
+        Ok(Recording {
+            id: row.get(offset)?,
+            title: row.get(offset + 1)?,
+            audio_path: row.get(offset + 2)?,
+            duration_secs: row.get(offset + 3)?,
+            state: state_str.parse().unwrap_or(RecordingState::Pending),
+            created_at: Utc.timestamp_opt(created_timestamp, 0).unwrap(),
+            updated_at: Utc.timestamp_opt(updated_timestamp, 0).unwrap(),
+            notes: row.get(offset + 7)?,
+            tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+            system_audio_path: row.get(offset + 9)?,
+            mic_audio_path: row.get(offset + 10)?,
+            sensitivity: sensitivity_str.parse().unwrap_or_default(),
+            project: row.get(offset + 12)?,
+            language: row.get(offset + 13)?,
+            failure_reason: row.get(offset + 14)?,
+            language_override: row.get(offset + 15)?,
+            suggested_tags: suggested_tags_json
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            deleted_at: deleted_timestamp.and_then(|at| Utc.timestamp_opt(at, 0).single()),
+            archived_at: archived_timestamp.and_then(|at| Utc.timestamp_opt(at, 0).single()),
+        })
+    }
+
+    fn row_to_segment_offset(
+        row: &rusqlite::Row,
+        offset: usize,
+    ) -> rusqlite::Result<TranscriptSegment> {
+        Ok(TranscriptSegment {
+            id: row.get(offset)?,
+            recording_id: row.get(offset + 1)?,
+            start_time: row.get(offset + 2)?,
+            end_time: row.get(offset + 3)?,
+            text: row.get(offset + 4)?,
+            speaker: row.get(offset + 5)?,
+            confidence: row.get(offset + 6)?,
+            words: row
+                .get::<_, Option<String>>(offset + 7)?
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            translated_text: row.get(offset + 8)?,
+            edited: row.get(offset + 9)?,
+        })
+    }
+
+    /// Get database statistics
+    pub fn get_stats(&self) -> Result<DatabaseStats> {
+        let total_recordings: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM recordings WHERE deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let total_segments: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM transcript_segments s
+                     JOIN recordings r ON s.recording_id = r.id
+                     WHERE r.deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let total_duration: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT SUM(duration_secs) FROM recordings
+                 WHERE duration_secs IS NOT NULL AND deleted_at IS NULL",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+
+        Ok(DatabaseStats {
+            total_recordings: total_recordings as usize,
+            total_segments: total_segments as usize,
+            total_duration_secs: total_duration.unwrap_or(0) as u64,
+        })
+    }
+
+    /// Totals and weekly and monthly activity of the library
+    pub fn library_stats(&self) -> Result<LibraryStats> {
+        let recordings = self.list_recordings_since(None)?;
+        let segments = self.get_stats()?.total_segments;
+        Ok(LibraryStats::compute(
+            &recordings,
+            segments,
+            Local::now().date_naive(),
+        ))
+    }
+}
+
+/// Database statistics
+#[derive(Debug, Clone)]
+pub struct DatabaseStats {
+    pub total_recordings: usize,
+    pub total_segments: usize,
+    pub total_duration_secs: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use tempfile::tempdir;
+
+    use crate::storage::models::{Sensitivity, TranscriptWord};
+
+    #[test]
+    fn test_create_database() {
+        let db = Database::open_memory().unwrap();
+        let stats = db.get_stats().unwrap();
+        assert_eq!(stats.total_recordings, 0);
+    }
+
+    #[test]
+    fn test_insert_and_get_recording() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Test Meeting".to_string());
+        db.insert_recording(&recording).unwrap();
+
+        let retrieved = db.get_recording(&recording.id).unwrap().unwrap();
+        assert_eq!(retrieved.title, "Test Meeting");
+    }
+
+    #[test]
+    fn test_insert_and_search_segments() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Test Meeting".to_string());
+        db.insert_recording(&recording).unwrap();
+
+        let segment = TranscriptSegment::new(
+            recording.id.clone(),
+            0.0,
+            5.0,
+            "Hello world this is a test".to_string(),
+        );
+        db.insert_segment(&segment).unwrap();
+
+        let results = db
+            .search_transcripts("hello", &RecordingFilter::default(), 10)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].segment.text.contains("Hello"));
+    }
+
+    #[test]
+    fn test_replace_segments_updates_search_index() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Webinar".to_string());
+        db.insert_recording(&recording).unwrap();
+        let segment =
+            |text: &str| TranscriptSegment::new(recording.id.clone(), 0.0, 5.0, text.to_string());
+        db.insert_segments(&[segment("first draft wording")])
+            .unwrap();
+
+        db.replace_segments(&recording.id, &[segment("aligned keynote")])
+            .unwrap();
+
+        assert_eq!(db.get_transcript_segments(&recording.id).unwrap().len(), 1);
+        assert!(db
+            .search_transcripts("draft", &RecordingFilter::default(), 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            db.search_transcripts("keynote", &RecordingFilter::default(), 10)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_speaker_profiles_upsert_list_and_delete() {
+        let db = Database::open_memory().unwrap();
+
+        let mut alice = SpeakerProfile::new("Alice".to_string(), vec![0.5, -1.0]);
+        db.upsert_speaker_profile(&alice).unwrap();
+        alice.embedding = vec![0.25, 2.0];
+        db.upsert_speaker_profile(&alice).unwrap();
+        db.upsert_speaker_profile(&SpeakerProfile::new("bob".to_string(), vec![1.0]))
+            .unwrap();
+
+        let profiles = db.list_speaker_profiles().unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "Alice");
+        assert_eq!(profiles[0].embedding, vec![0.25, 2.0]);
+
+        assert!(db.delete_speaker_profile("bob").unwrap());
+        assert!(!db.delete_speaker_profile("bob").unwrap());
+        assert_eq!(db.list_speaker_profiles().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_new_database_sets_schema_version() {
+        let db = Database::open_memory().unwrap();
+        assert_eq!(db.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_recording_track_paths_round_trip() {
+        let db = Database::open_memory().unwrap();
+
+        let mut recording = Recording::new("Tracks".to_string());
+        recording.audio_path = Some("/tmp/a.wav".to_string());
+        db.insert_recording(&recording).unwrap();
+
+        recording.system_audio_path = Some("/tmp/a.system.wav".to_string());
+        recording.mic_audio_path = Some("/tmp/a.mic.wav".to_string());
+        db.update_recording(&recording).unwrap();
+
+        let retrieved = db.get_recording(&recording.id).unwrap().unwrap();
+        assert_eq!(
+            retrieved.system_audio_path.as_deref(),
+            Some("/tmp/a.system.wav")
+        );
+        assert_eq!(retrieved.mic_audio_path.as_deref(), Some("/tmp/a.mic.wav"));
+    }
+
+    #[test]
+    fn test_recording_sensitivity_round_trip() {
+        let db = Database::open_memory().unwrap();
+
+        let mut recording = Recording::new("Board meeting".to_string());
+        db.insert_recording(&recording).unwrap();
+        assert!(!db
+            .get_recording(&recording.id)
+            .unwrap()
+            .unwrap()
+            .is_confidential());
+
+        recording.sensitivity = Sensitivity::Confidential;
+        db.update_recording(&recording).unwrap();
+        assert!(db
+            .get_recording(&recording.id)
+            .unwrap()
+            .unwrap()
+            .is_confidential());
+    }
+
+    #[test]
+    fn test_recording_language_is_stored_after_transcription() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Standup".to_string());
+        db.insert_recording(&recording).unwrap();
+        assert_eq!(
+            db.get_recording(&recording.id).unwrap().unwrap().language,
+            None
+        );
+
+        db.set_recording_language(&recording.id, Some("de"))
+            .unwrap();
+        let stored = db.get_recording(&recording.id).unwrap().unwrap();
+        assert_eq!(stored.language.as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn test_silences_are_replaced_and_removed_with_the_recording() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Long break".to_string());
+        db.insert_recording(&recording).unwrap();
+        let silence = |start_time, end_time| Silence {
+            start_time,
+            end_time,
+        };
+
+        db.replace_silences(&recording.id, &[silence(600.0, 1320.0), silence(5.0, 9.0)])
+            .unwrap();
+        db.replace_silences(&recording.id, &[silence(600.0, 1300.0), silence(5.0, 8.0)])
+            .unwrap();
+        assert_eq!(
+            db.get_silences(&recording.id).unwrap(),
+            [silence(5.0, 8.0), silence(600.0, 1300.0)]
+        );
+
+        db.delete_recording(&recording.id).unwrap();
+        assert!(db.get_silences(&recording.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_chapters_are_replaced_in_order() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("All hands".to_string());
+        db.insert_recording(&recording).unwrap();
+        let chapter = |start_time, title: &str| Chapter {
+            start_time,
+            title: title.to_string(),
+        };
+
+        db.replace_chapters(&recording.id, &[chapter(0.0, "Intro")])
+            .unwrap();
+        db.replace_chapters(
+            &recording.id,
+            &[chapter(750.0, "Budget discussion"), chapter(0.0, "Intro")],
+        )
+        .unwrap();
+        assert_eq!(
+            db.get_chapters(&recording.id).unwrap(),
+            [chapter(0.0, "Intro"), chapter(750.0, "Budget discussion")]
+        );
+
+        db.delete_recording(&recording.id).unwrap();
+        assert!(db.get_chapters(&recording.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_action_items_keep_done_marks_when_extracted_again() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Planning".to_string());
+        db.insert_recording(&recording).unwrap();
+        let item = |owner: Option<&str>, task: &str| {
+            ActionItem::new(
+                recording.id.clone(),
+                owner.map(str::to_string),
+                task.to_string(),
+                None,
+            )
+        };
+
+        db.replace_action_items(
+            &recording.id,
+            &[
+                item(Some("Ada"), "Send the budget"),
+                item(None, "Book a room"),
+            ],
+        )
+        .unwrap();
+        let items = db.get_action_items(&recording.id).unwrap();
+        assert!(db
+            .set_action_item_done(items[0].id, Some(Utc::now()))
+            .unwrap());
+        assert!(!db.set_action_item_done(9999, Some(Utc::now())).unwrap());
+
+        let open: Vec<_> = db
+            .list_open_action_items()
+            .unwrap()
+            .into_iter()
+            .map(|item| item.task)
+            .collect();
+        assert_eq!(open, ["Book a room"]);
+
+        db.replace_action_items(
+            &recording.id,
+            &[
+                item(Some("Ada"), "send the budget"),
+                item(Some("Grace"), "Draft the agenda"),
+            ],
+        )
+        .unwrap();
+        let items = db.get_action_items(&recording.id).unwrap();
+        let done: Vec<_> = items.iter().map(|item| item.is_done()).collect();
+        assert_eq!(done, [true, false]);
+
+        db.delete_recording(&recording.id).unwrap();
+        assert!(db.list_open_action_items().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decisions_are_replaced_and_kept_in_snapshots() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Planning".to_string());
+        db.insert_recording(&recording).unwrap();
+        let decision = |text: &str, timestamps: &[f64]| {
+            Decision::new(recording.id.clone(), text.to_string(), timestamps.to_vec())
+        };
+
+        db.replace_decisions(&recording.id, &[decision("Ship on Friday", &[75.0])])
+            .unwrap();
+        db.replace_decisions(
+            &recording.id,
+            &[
+                decision("Ship on Monday", &[75.0, 610.0]),
+                decision("Hire a designer", &[]),
+            ],
+        )
+        .unwrap();
+        let decisions = db.get_decisions(&recording.id).unwrap();
+        let texts: Vec<_> = decisions.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(texts, ["Ship on Monday", "Hire a designer"]);
+        assert_eq!(decisions[0].timestamps, [75.0, 610.0]);
+
+        let snapshot_id = db.create_snapshot("delete", &[&recording.id]).unwrap();
+        db.delete_recording(&recording.id).unwrap();
+        assert!(db.get_decisions(&recording.id).unwrap().is_empty());
+
+        let snapshot = db
+            .list_snapshots(10)
+            .unwrap()
+            .into_iter()
+            .find(|snapshot| snapshot.id == snapshot_id)
+            .unwrap();
+        db.restore_snapshot(&snapshot).unwrap();
+        assert_eq!(db.get_decisions(&recording.id).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_llm_usage_is_totalled_per_month_and_model() {
+        let db = Database::open_memory().unwrap();
+
+        let usage = |model: &str, at: &str, input: u64, cost: Option<f64>| LlmUsage {
+            operation: "summarize".to_string(),
+            provider: "gemini".to_string(),
+            model: model.to_string(),
+            recording_id: None,
+            input_tokens: input,
+            output_tokens: input / 10,
+            cost_usd: cost,
+            created_at: DateTime::parse_from_rfc3339(at)
+                .unwrap()
+                .with_timezone(&Utc),
+        };
+        for call in [
+            usage("gemini-2.5-flash", "2026-09-30T23:00:00Z", 1000, Some(0.5)),
+            usage("gemini-2.5-flash", "2026-10-01T08:00:00Z", 2000, Some(1.0)),
+            usage("gemini-2.5-flash", "2026-10-02T08:00:00Z", 3000, None),
+            usage("gemini-2.5-pro", "2026-10-03T08:00:00Z", 500, None),
+        ] {
+            db.record_llm_usage(&call).unwrap();
+        }
+
+        let totals = db.llm_usage_by_month().unwrap();
+        let rows: Vec<_> = totals
+            .iter()
+            .map(|t| (t.month.as_str(), t.model.as_str(), t.calls, t.input_tokens))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("2026-10", "gemini-2.5-flash", 2, 5000),
+                ("2026-10", "gemini-2.5-pro", 1, 500),
+                ("2026-09", "gemini-2.5-flash", 1, 1000),
+            ]
+        );
+        assert_eq!(totals[0].output_tokens, 500);
+        assert_eq!(totals[0].cost_usd, Some(1.0));
+        assert_eq!(totals[1].cost_usd, None);
+    }
+
+    #[test]
+    fn test_language_override_is_kept_with_the_recording() {
+        let db = Database::open_memory().unwrap();
+
+        let mut recording = Recording::new("Sync with Delhi".to_string());
+        recording.language_override = Some("hi".to_string());
+        db.insert_recording(&recording).unwrap();
+        db.set_recording_language(&recording.id, Some("hi"))
+            .unwrap();
+
+        let mut stored = db.get_recording(&recording.id).unwrap().unwrap();
+        assert_eq!(stored.language_override.as_deref(), Some("hi"));
+
+        stored.language_override = None;
+        db.update_recording(&stored).unwrap();
+        let stored = db.get_recording(&recording.id).unwrap().unwrap();
+        assert_eq!(stored.language_override, None);
+        assert_eq!(stored.language.as_deref(), Some("hi"));
+    }
+
+    #[test]
+    fn test_checkpoints_resume_interrupted_transcriptions() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("All hands".to_string());
+        db.insert_recording(&recording).unwrap();
+        db.update_recording_state(&recording.id, RecordingState::Transcribing)
+            .unwrap();
+
+        let checkpoint = TranscriptionCheckpoint {
+            stream: "mixed".to_string(),
+            total_samples: 960_000,
+            done_samples: 480_000,
+            segments: vec![TranscriptSegment::new(
+                recording.id.clone(),
+                0.0,
+                2.5,
+                "Welcome, everyone.".to_string(),
+            )],
+        };
+        db.save_checkpoint(&recording.id, &checkpoint).unwrap();
+        let later = TranscriptionCheckpoint {
+            done_samples: 720_000,
+            ..checkpoint
+        };
+        db.save_checkpoint(&recording.id, &later).unwrap();
+        let saved = db.get_checkpoints(&recording.id).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].done_samples, 720_000);
+        assert_eq!(saved[0].segments[0].text, "Welcome, everyone.");
+
+        assert_eq!(db.requeue_interrupted_transcriptions().unwrap(), 1);
+        let pending = db.get_pending_recordings().unwrap();
+        assert_eq!(pending.len(), 1);
+
+        db.clear_checkpoints(&recording.id).unwrap();
+        assert!(db.get_checkpoints(&recording.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_transcription_queue_orders_bumps_and_cancels_jobs() {
+        let db = Database::open_memory().unwrap();
+
+        let mut ids = Vec::new();
+        for title in ["Monday", "Tuesday", "Wednesday"] {
+            let mut recording = Recording::new(title.to_string());
+            recording.state = RecordingState::Pending;
+            db.insert_recording(&recording).unwrap();
+            db.queue_job(&recording.id).unwrap();
+            ids.push(recording.id);
+        }
+        let titles = |jobs: Vec<TranscriptionJob>| -> Vec<String> {
+            jobs.into_iter().map(|job| job.title).collect()
+        };
+        assert_eq!(
+            titles(db.list_jobs(false).unwrap()),
+            ["Monday", "Tuesday", "Wednesday"]
+        );
+        assert_eq!(
+            titles(db.list_jobs(true).unwrap()),
+            ["Wednesday", "Tuesday", "Monday"]
+        );
+
+        assert!(db.bump_job(&ids[2]).unwrap());
+        let next = db.start_next_job(false).unwrap().unwrap();
+        assert_eq!(next.title, "Wednesday");
+        assert!(!db.bump_job(&ids[2]).unwrap());
+
+        assert!(db.cancel_job(&ids[0]).unwrap());
+        let jobs = db.list_jobs(false).unwrap();
+        let states: Vec<_> = jobs.iter().map(|job| job.state).collect();
+        assert_eq!(
+            states,
+            [JobState::Running, JobState::Queued, JobState::Cancelled]
+        );
+
+        // Cancelled jobs stay cancelled until queued explicitly
+        assert_eq!(db.queue_pending_recordings().unwrap(), 0);
+        db.queue_job(&ids[0]).unwrap();
+        db.finish_job(&ids[2]).unwrap();
+        let jobs = db.list_jobs(false).unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert!(jobs.iter().all(|job| job.state == JobState::Queued));
+    }
+
+    #[test]
+    fn test_failed_jobs_wait_for_their_retry_time() {
+        let db = Database::open_memory().unwrap();
+
+        let mut recording = Recording::new("Retro".to_string());
+        recording.state = RecordingState::Pending;
+        db.insert_recording(&recording).unwrap();
+        db.queue_job(&recording.id).unwrap();
+        db.start_next_job(false).unwrap().unwrap();
+
+        db.record_failure(&recording.id, RecordingState::Pending, "model missing")
+            .unwrap();
+        db.retry_job(&recording.id, Utc::now() + chrono::Duration::minutes(5))
+            .unwrap();
+        assert!(db.start_next_job(false).unwrap().is_none());
+
+        db.retry_job(&recording.id, Utc::now()).unwrap();
+        let retried = db.start_next_job(false).unwrap().unwrap();
+        assert_eq!(retried.failure_reason.as_deref(), Some("model missing"));
+        assert_eq!(db.get_job(&recording.id).unwrap().unwrap().attempts, 2);
+
+        // Queueing by hand starts the count over
+        db.queue_job(&recording.id).unwrap();
+        assert_eq!(db.get_job(&recording.id).unwrap().unwrap().attempts, 0);
+    }
+
+    #[test]
+    fn test_participants_round_trip_in_order() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Planning".to_string());
+        db.insert_recording(&recording).unwrap();
+        assert!(db.get_participants(&recording.id).unwrap().is_empty());
+
+        let names = vec![
+            "Carol".to_string(),
+            "Alice".to_string(),
+            "Carol".to_string(),
+        ];
+        db.set_participants(&recording.id, &names).unwrap();
+        assert_eq!(
+            db.get_participants(&recording.id).unwrap(),
+            vec!["Carol", "Alice"]
+        );
+
+        db.set_participants(&recording.id, &["Bob".to_string()])
+            .unwrap();
+        assert_eq!(db.get_participants(&recording.id).unwrap(), vec!["Bob"]);
+    }
+
+    #[test]
+    fn test_participants_filter_recordings() {
+        let db = Database::open_memory().unwrap();
+
+        let planning = Recording::new("Planning".to_string());
+        let standup = Recording::new("Standup".to_string());
+        db.insert_recording(&planning).unwrap();
+        db.insert_recording(&standup).unwrap();
+        db.set_participants(&planning.id, &["Alice".to_string(), " ".to_string()])
+            .unwrap();
+        db.set_participants(&standup.id, &["Bob".to_string()])
+            .unwrap();
+        assert_eq!(db.get_participants(&planning.id).unwrap(), vec!["Alice"]);
+
+        let with = |name: &str| RecordingFilter {
+            participant: Some(name.to_string()),
+            ..Default::default()
+        };
+        let recordings = db.list_recordings(&with(" alice"), 10).unwrap();
+        assert_eq!(recordings.len(), 1);
+        assert_eq!(recordings[0].id, planning.id);
+        assert!(db.list_recordings(&with("Carol"), 10).unwrap().is_empty());
+
+        db.insert_segments(&[TranscriptSegment::new(
+            standup.id.clone(),
+            0.0,
+            2.0,
+            "Budget review".to_string(),
+        )])
+        .unwrap();
+        assert_eq!(
+            db.search_transcripts("budget", &with("bob"), 10)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(db
+            .search_transcripts("budget", &with("alice"), 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_chunks_are_listed_in_capture_order() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("All-day workshop".to_string());
+        db.insert_recording(&recording).unwrap();
+        assert!(db.get_chunks(&recording.id).unwrap().is_empty());
+
+        db.add_chunk(&recording.id, 1, "/tmp/a.002.wav").unwrap();
+        db.add_chunk(&recording.id, 0, "/tmp/a.001.wav").unwrap();
+        assert_eq!(
+            db.get_chunks(&recording.id).unwrap(),
+            vec!["/tmp/a.001.wav", "/tmp/a.002.wav"]
+        );
+
+        db.set_chunks(&recording.id, &["/tmp/a.001.ogg".to_string()])
+            .unwrap();
+        assert_eq!(
+            db.get_chunks(&recording.id).unwrap(),
+            vec!["/tmp/a.001.ogg"]
+        );
+    }
+
+    #[test]
+    fn test_warnings_are_stored_once() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Standup".to_string());
+        db.insert_recording(&recording).unwrap();
+        assert!(db.get_warnings(&recording.id).unwrap().is_empty());
+
+        let silent = "Microphone track is silent".to_string();
+        let clipped = "System audio track is heavily clipped".to_string();
+        db.add_warnings(&recording.id, std::slice::from_ref(&silent))
+            .unwrap();
+        db.add_warnings(&recording.id, &[silent.clone(), clipped.clone()])
+            .unwrap();
+        assert_eq!(
+            db.get_warnings(&recording.id).unwrap(),
+            vec![silent, clipped]
+        );
+    }
+
+    #[test]
+    fn test_projects_group_recordings() {
+        let db = Database::open_memory().unwrap();
+
+        assert!(db.create_project("Apollo").unwrap());
+        assert!(!db.create_project("apollo").unwrap());
+
+        let mut kickoff = Recording::new("Kickoff".to_string());
+        kickoff.duration_secs = Some(600);
+        db.insert_recording(&kickoff).unwrap();
+        let other = Recording::new("Standup".to_string());
+        db.insert_recording(&other).unwrap();
+        db.set_recording_project(&kickoff.id, Some("Apollo"))
+            .unwrap();
+
+        let recordings = db
+            .list_project_recordings("apollo", &RecordingFilter::default(), 10)
+            .unwrap();
+        assert_eq!(recordings.len(), 1);
+        assert_eq!(recordings[0].project.as_deref(), Some("Apollo"));
 
-        tx.commit()?;
-        Ok(())
+        let project = db.get_project("APOLLO").unwrap().unwrap();
+        assert_eq!(project.recording_count, 1);
+        assert_eq!(project.total_duration_secs, 600);
+
+        db.set_recording_project(&kickoff.id, None).unwrap();
+        assert!(db
+            .list_project_recordings("Apollo", &RecordingFilter::default(), 10)
+            .unwrap()
+            .is_empty());
+        assert!(db.set_recording_project(&other.id, Some("Gemini")).is_err());
     }
 
-    /// Get transcript segments for a recording
-    pub fn get_transcript_segments(&self, recording_id: &str) -> Result<Vec<TranscriptSegment>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, recording_id, start_time, end_time, text, speaker, confidence
-             FROM transcript_segments
-             WHERE recording_id = ?1
-             ORDER BY start_time",
-        )?;
+    #[test]
+    fn test_tags_filter_recordings_and_suggestions_stay_apart() {
+        let db = Database::open_memory().unwrap();
 
-        let segments = stmt
-            .query_map(params![recording_id], |row| {
-                Ok(TranscriptSegment {
-                    id: row.get(0)?,
-                    recording_id: row.get(1)?,
-                    start_time: row.get(2)?,
-                    end_time: row.get(3)?,
-                    text: row.get(4)?,
-                    speaker: row.get(5)?,
-                    confidence: row.get(6)?,
-                })
-            })?
-            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let budget = Recording::new("Budget review".to_string());
+        db.insert_recording(&budget).unwrap();
+        let standup = Recording::new("Standup".to_string());
+        db.insert_recording(&standup).unwrap();
 
-        Ok(segments)
+        let tags = vec!["Budget".to_string(), "Q3 planning".to_string()];
+        db.set_recording_tags(&budget.id, &tags).unwrap();
+        db.set_suggested_tags(&standup.id, &["hiring".to_string()])
+            .unwrap();
+
+        let recordings = db
+            .list_tagged_recordings("budget", &RecordingFilter::default(), 10)
+            .unwrap();
+        assert_eq!(recordings.len(), 1);
+        assert_eq!(recordings[0].tags, tags);
+        assert!(db
+            .list_tagged_recordings("hiring", &RecordingFilter::default(), 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(db.list_tags().unwrap(), tags);
+
+        let standup = db.get_recording(&standup.id).unwrap().unwrap();
+        assert_eq!(standup.suggested_tags, ["hiring"]);
     }
 
-    /// Full-text search across transcripts
-    pub fn search_transcripts(
-        &self,
-        query: &str,
-        limit: usize,
-    ) -> Result<Vec<(Recording, TranscriptSegment)>> {
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT
-                r.id, r.title, r.audio_path, r.duration_secs, r.state, r.created_at, r.updated_at, r.notes, r.tags,
-                s.id, s.recording_id, s.start_time, s.end_time, s.text, s.speaker, s.confidence
-            FROM transcript_fts f
-            JOIN transcript_segments s ON f.rowid = s.id
-            JOIN recordings r ON s.recording_id = r.id
-            WHERE transcript_fts MATCH ?1
-            ORDER BY rank
-            LIMIT ?2
-            "#,
-        )?;
+    #[test]
+    fn test_filter_narrows_lists_and_searches() {
+        let db = Database::open_memory().unwrap();
+        let now = Utc::now();
 
-        let results = stmt
-            .query_map(params![query, limit], |row| {
-                let recording = Self::row_to_recording_offset(row, 0)?;
-                let segment = TranscriptSegment {
-                    id: row.get(9)?,
-                    recording_id: row.get(10)?,
-                    start_time: row.get(11)?,
-                    end_time: row.get(12)?,
-                    text: row.get(13)?,
-                    speaker: row.get(14)?,
-                    confidence: row.get(15)?,
-                };
-                Ok((recording, segment))
-            })?
-            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut ids = Vec::new();
+        for (title, days, secs, state) in [
+            ("Old planning", 40, 3600, RecordingState::Completed),
+            ("Planning sync", 10, 1800, RecordingState::Completed),
+            ("Planning chat", 5, 120, RecordingState::Completed),
+            ("Broken planning", 2, 2400, RecordingState::Failed),
+        ] {
+            let mut recording = Recording::new(title.to_string());
+            recording.created_at = now - chrono::Duration::days(days);
+            recording.duration_secs = Some(secs);
+            recording.state = state;
+            db.insert_recording(&recording).unwrap();
+            db.insert_segment(&TranscriptSegment::new(
+                recording.id.clone(),
+                0.0,
+                1.0,
+                "quarterly planning".to_string(),
+            ))
+            .unwrap();
+            ids.push(recording.id);
+        }
 
-        Ok(results)
+        let listed = |filter: &RecordingFilter| -> Vec<String> {
+            db.list_recordings(filter, 10)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.id)
+                .collect()
+        };
+        let filter = RecordingFilter {
+            since: Some(now - chrono::Duration::days(30)),
+            until: Some(now - chrono::Duration::days(3)),
+            ..Default::default()
+        };
+        assert_eq!(listed(&filter), [ids[2].clone(), ids[1].clone()]);
+        let filter = RecordingFilter {
+            min_duration_secs: Some(1800),
+            state: Some(RecordingState::Completed),
+            ..Default::default()
+        };
+        assert_eq!(listed(&filter), [ids[1].clone(), ids[0].clone()]);
+        assert_eq!(listed(&RecordingFilter::default()).len(), 4);
+
+        let failed = RecordingFilter {
+            state: Some(RecordingState::Failed),
+            ..Default::default()
+        };
+        let hits = db.search_transcripts("quarterly", &failed, 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].recording.id, ids[3]);
+        let titled = db.search_recordings("planning", &filter, 10).unwrap();
+        assert_eq!(titled.len(), 2);
     }
 
-    /// Get recordings with pending transcription
-    pub fn get_pending_recordings(&self) -> Result<Vec<Recording>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, title, audio_path, duration_secs, state, created_at, updated_at, notes, tags
-             FROM recordings
-             WHERE state = 'pending'
-             ORDER BY created_at ASC",
-        )?;
+    #[test]
+    fn test_search_excerpts_mark_matched_terms() {
+        let db = Database::open_memory().unwrap();
+        let recording = Recording::new("Budget review".to_string());
+        db.insert_recording(&recording).unwrap();
+        let long = (0..60)
+            .map(|i| format!("word{}", i))
+            .collect::<Vec<_>>()
+            .join(" ");
+        for text in [
+            "We moved the budget to next quarter".to_string(),
+            format!("{} budget {}", long, long),
+        ] {
+            db.insert_segment(&TranscriptSegment::new(
+                recording.id.clone(),
+                0.0,
+                1.0,
+                text,
+            ))
+            .unwrap();
+        }
 
-        let recordings = stmt
-            .query_map([], |row| Ok(Self::row_to_recording(row)))?
-            .collect::<rusqlite::Result<Vec<_>>>()?
-            .into_iter()
-            .collect::<Result<Vec<_>>>()?;
+        let hits = db
+            .search_transcripts("budget", &RecordingFilter::default(), 10)
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        let short = hits
+            .iter()
+            .find(|hit| hit.segment.text.starts_with("We"))
+            .unwrap();
+        assert_eq!(
+            short.excerpt_parts(),
+            [
+                ("We moved the ", false),
+                ("budget", true),
+                (" to next quarter", false)
+            ]
+        );
+        assert_eq!(short.excerpt_text(), short.segment.text);
 
-        Ok(recordings)
+        let long = hits
+            .iter()
+            .find(|hit| hit.segment.text.starts_with("word"))
+            .unwrap();
+        assert!(long.excerpt.starts_with('…') && long.excerpt.ends_with('…'));
+        assert!(long.excerpt_parts().contains(&("budget", true)));
+        assert!(long.excerpt_text().len() < long.segment.text.len());
     }
 
-    /// Update recording state
-    pub fn update_recording_state(&self, id: &str, state: RecordingState) -> Result<()> {
-        self.conn.execute(
-            "UPDATE recordings SET state = ?2, updated_at = ?3 WHERE id = ?1",
-            params![id, state.as_str(), Utc::now().timestamp()],
-        )?;
-        Ok(())
+    #[test]
+    fn test_search_reads_phrases_prefixes_and_operators() {
+        let db = Database::open_memory().unwrap();
+        let recording = Recording::new("Planning".to_string());
+        db.insert_recording(&recording).unwrap();
+        for text in [
+            "Send the follow-up about the budget",
+            "Budgeting for next quarter",
+            "Hiring plan for next year",
+        ] {
+            db.insert_segment(&TranscriptSegment::new(
+                recording.id.clone(),
+                0.0,
+                1.0,
+                text.to_string(),
+            ))
+            .unwrap();
+        }
+
+        let found = |query: &str| -> Vec<String> {
+            let mut texts: Vec<String> = db
+                .search_transcripts(query, &RecordingFilter::default(), 10)
+                .unwrap()
+                .into_iter()
+                .map(|hit| hit.segment.text)
+                .collect();
+            texts.sort();
+            texts
+        };
+        assert_eq!(found("follow-up"), ["Send the follow-up about the budget"]);
+        assert_eq!(found("budg*").len(), 2);
+        assert_eq!(found("\"next quarter\""), ["Budgeting for next quarter"]);
+        assert_eq!(found("next NOT hiring"), ["Budgeting for next quarter"]);
+        assert_eq!(found("hiring OR follow").len(), 2);
+        assert!(found("\"unclosed AND (").is_empty());
+        assert!(found("AND").is_empty());
+        assert!(found("NOT hiring").is_empty());
+        assert_eq!(found("NOT hiring next"), ["Budgeting for next quarter"]);
+        assert_eq!(
+            found("(budget* OR hiring) next"),
+            ["Budgeting for next quarter", "Hiring plan for next year"]
+        );
+        assert_eq!(found("next NOT (budget* OR year)"), Vec::<String>::new());
+        assert_eq!(found("(follow OR hiring) NOT year").len(), 1);
+
+        let query = SearchQuery::parse("NEXT 100%");
+        assert!(db
+            .scan_transcripts(&query, &RecordingFilter::default(), 10)
+            .unwrap()
+            .is_empty());
+        let scanned = |query: &str| -> usize {
+            db.scan_transcripts(&SearchQuery::parse(query), &RecordingFilter::default(), 10)
+                .unwrap()
+                .len()
+        };
+        assert_eq!(scanned("hiring OR follow"), 2);
+        assert_eq!(scanned("(budget OR hiring) NOT year"), 2);
+        assert_eq!(scanned("next NOT (quarter OR year)"), 0);
+
+        let query = SearchQuery::parse("next plan");
+        let hits = db
+            .scan_transcripts(&query, &RecordingFilter::default(), 10)
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            hits[0].excerpt_parts(),
+            [
+                ("Hiring ", false),
+                ("plan", true),
+                (" for ", false),
+                ("next", true),
+                (" year", false)
+            ]
+        );
     }
 
-    // Helper to convert a row to a Recording
-    fn row_to_recording(row: &rusqlite::Row) -> Result<Recording> {
-        Ok(Self::row_to_recording_offset(row, 0)?)
+    #[test]
+    fn test_word_timestamps_round_trip_and_locate_search_hits() {
+        let db = Database::open_memory().unwrap();
+        let recording = Recording::new("Retro".to_string());
+        db.insert_recording(&recording).unwrap();
+
+        let word = |start: f64, text: &str| TranscriptWord {
+            start_time: start,
+            end_time: start + 0.4,
+            text: text.to_string(),
+            confidence: Some(0.9),
+        };
+        let mut segment = TranscriptSegment::new(
+            recording.id.clone(),
+            10.0,
+            12.0,
+            "Ship the release, Friday.".to_string(),
+        );
+        segment.words = vec![
+            word(10.0, "Ship"),
+            word(10.4, "the"),
+            word(10.8, "release,"),
+            word(11.4, "Friday."),
+        ];
+        db.insert_segments(&[
+            segment,
+            TranscriptSegment::new(recording.id.clone(), 20.0, 21.0, "No words".to_string()),
+        ])
+        .unwrap();
+
+        let segments = db.get_transcript_segments(&recording.id).unwrap();
+        assert_eq!(segments[0].words.len(), 4);
+        assert!(segments[1].words.is_empty());
+
+        let hit = db
+            .search_transcripts("friday", &RecordingFilter::default(), 10)
+            .unwrap()
+            .pop()
+            .unwrap()
+            .segment;
+        assert_eq!(hit.find_word("friday").unwrap().start_time, 11.4);
+        assert_eq!(hit.find_word("rel*").unwrap().text, "release,");
+        assert!(hit.find_word("releases").is_none());
     }
 
-    fn row_to_recording_offset(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Recording> {
-        let state_str: String = row.get(offset + 4)?;
-        let created_timestamp: i64 = row.get(offset + 5)?;
-        let updated_timestamp: i64 = row.get(offset + 6)?;
-        let tags_json: String = row.get(offset + 8)?;
+    #[test]
+    fn test_translated_text_is_stored_next_to_the_original() {
+        let db = Database::open_memory().unwrap();
 
-        Ok(Recording {
-            id: row.get(offset)?,
-            title: row.get(offset + 1)?,
-            audio_path: row.get(offset + 2)?,
-            duration_secs: row.get(offset + 3)?,
-            state: state_str.parse().unwrap_or(RecordingState::Pending),
-            created_at: Utc.timestamp_opt(created_timestamp, 0).unwrap(),
-            updated_at: Utc.timestamp_opt(updated_timestamp, 0).unwrap(),
-            notes: row.get(offset + 7)?,
-            tags: serde_json::from_str(&tags_json).unwrap_or_default(),
-        })
+        let recording = Recording::new("Standup".to_string());
+        db.insert_recording(&recording).unwrap();
+        let mut segment =
+            TranscriptSegment::new(recording.id.clone(), 0.0, 2.0, "Guten Morgen.".to_string());
+        segment.translated_text = Some("Good morning.".to_string());
+        db.replace_segments(&recording.id, &[segment]).unwrap();
+
+        let stored = db.get_transcript_segments(&recording.id).unwrap();
+        assert_eq!(stored[0].text, "Guten Morgen.");
+        assert_eq!(stored[0].translated_text.as_deref(), Some("Good morning."));
     }
 
-    /// Get database statistics
-    pub fn get_stats(&self) -> Result<DatabaseStats> {
-        let total_recordings: i64 =
-            self.conn
-                .query_row("SELECT COUNT(*) FROM recordings", [], |row| row.get(0))?;
+    #[test]
+    fn test_speaker_names_follow_renames_and_new_transcripts() {
+        let db = Database::open_memory().unwrap();
 
-        let total_segments: i64 =
-            self.conn
-                .query_row("SELECT COUNT(*) FROM transcript_segments", [], |row| {
-                    row.get(0)
-                })?;
+        let recording = Recording::new("Sync".to_string());
+        db.insert_recording(&recording).unwrap();
+        let diarized = || {
+            ["Speaker 1", "Speaker 2", "Speaker 1"]
+                .iter()
+                .enumerate()
+                .map(|(i, label)| {
+                    let mut segment = TranscriptSegment::new(
+                        recording.id.clone(),
+                        i as f64,
+                        i as f64 + 1.0,
+                        "text".to_string(),
+                    );
+                    segment.speaker = Some(label.to_string());
+                    segment
+                })
+                .collect::<Vec<_>>()
+        };
+        db.insert_segments(&diarized()).unwrap();
 
-        let total_duration: Option<i64> = self
-            .conn
-            .query_row(
-                "SELECT SUM(duration_secs) FROM recordings WHERE duration_secs IS NOT NULL",
-                [],
-                |row| row.get(0),
+        assert_eq!(
+            db.rename_speaker(&recording.id, "Speaker 1", "Priya")
+                .unwrap(),
+            2
+        );
+        db.rename_speaker(&recording.id, "Priya", "Priya S.")
+            .unwrap();
+
+        let speakers: Vec<_> = db
+            .get_transcript_segments(&recording.id)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.speaker.unwrap())
+            .collect();
+        assert_eq!(speakers, ["Priya S.", "Speaker 2", "Priya S."]);
+
+        // Transcribing again brings back the diarized labels
+        let mut segments = diarized();
+        db.apply_speaker_names(&recording.id, &mut segments)
+            .unwrap();
+        assert_eq!(segments[0].speaker.as_deref(), Some("Priya S."));
+        assert_eq!(segments[1].speaker.as_deref(), Some("Speaker 2"));
+    }
+
+    #[test]
+    fn test_edited_segments_are_flagged_and_searchable() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Planning".to_string());
+        db.insert_recording(&recording).unwrap();
+        db.insert_segments(&[
+            TranscriptSegment::new(
+                recording.id.clone(),
+                0.0,
+                2.0,
+                "Ship it on Fryday.".to_string(),
+            ),
+            TranscriptSegment::new(recording.id.clone(), 2.0, 4.0, "Sounds good.".to_string()),
+        ])
+        .unwrap();
+        let segments = db.get_transcript_segments(&recording.id).unwrap();
+
+        let changed = db
+            .update_segment_texts(
+                &recording.id,
+                &[
+                    (segments[0].id, "Ship it on Friday.".to_string()),
+                    (segments[1].id, "Sounds good.".to_string()),
+                ],
             )
-            .optional()?
-            .flatten();
+            .unwrap();
 
-        Ok(DatabaseStats {
-            total_recordings: total_recordings as usize,
-            total_segments: total_segments as usize,
-            total_duration_secs: total_duration.unwrap_or(0) as u64,
-        })
+        assert_eq!(changed, 1);
+        let stored = db.get_transcript_segments(&recording.id).unwrap();
+        assert!(stored[0].edited);
+        assert!(!stored[1].edited);
+        assert_eq!(
+            db.search_transcripts("Friday", &RecordingFilter::default(), 10)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(db
+            .search_transcripts("Fryday", &RecordingFilter::default(), 10)
+            .unwrap()
+            .is_empty());
     }
-}
 
-/// Database statistics
-#[derive(Debug, Clone)]
-pub struct DatabaseStats {
-    pub total_recordings: usize,
-    pub total_segments: usize,
-    pub total_duration_secs: u64,
-}
+    #[test]
+    fn test_segment_embeddings_are_dropped_when_text_changes() {
+        let db = Database::open_memory().unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rusqlite::Connection;
-    use tempfile::tempdir;
+        let recording = Recording::new("Planning".to_string());
+        let mut secret = Recording::new("Board".to_string());
+        secret.sensitivity = Sensitivity::Confidential;
+        db.insert_recording(&recording).unwrap();
+        db.insert_recording(&secret).unwrap();
+        db.insert_segments(&[
+            TranscriptSegment::new(recording.id.clone(), 0.0, 2.0, "Launch moves.".to_string()),
+            TranscriptSegment::new(recording.id.clone(), 2.0, 4.0, "Why?".to_string()),
+            TranscriptSegment::new(secret.id.clone(), 0.0, 2.0, "Layoffs.".to_string()),
+        ])
+        .unwrap();
+
+        let missing = db.segments_missing_embeddings("embed-1", false).unwrap();
+        assert_eq!(missing.len(), 2);
+        assert_eq!(
+            db.segments_missing_embeddings("embed-1", true)
+                .unwrap()
+                .len(),
+            3
+        );
+
+        db.insert_segment_embeddings(
+            "embed-1",
+            &[
+                (&missing[0], vec![1.0, 0.5]),
+                (&missing[1], vec![0.0, -2.0]),
+            ],
+        )
+        .unwrap();
+        assert!(db
+            .segments_missing_embeddings("embed-1", false)
+            .unwrap()
+            .is_empty());
+        // Vectors of another model do not count
+        assert_eq!(
+            db.segments_missing_embeddings("embed-2", false)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            db.segment_embeddings("embed-1", false).unwrap(),
+            [
+                (missing[0].id, vec![1.0, 0.5]),
+                (missing[1].id, vec![0.0, -2.0])
+            ]
+        );
+
+        db.update_segment_texts(
+            &recording.id,
+            &[(missing[0].id, "Launch slips.".to_string())],
+        )
+        .unwrap();
+        let missing_again = db.segments_missing_embeddings("embed-1", false).unwrap();
+        assert_eq!(missing_again.len(), 1);
+        assert_eq!(missing_again[0].id, missing[0].id);
+
+        let found = db
+            .get_segments_with_recordings(&[missing[1].id, missing[0].id])
+            .unwrap();
+        assert_eq!(found[0].1.text, "Why?");
+        assert_eq!(found[1].0.title, "Planning");
+    }
 
     #[test]
-    fn test_create_database() {
+    fn test_semantic_search_ranks_segments_by_similarity() {
         let db = Database::open_memory().unwrap();
-        let stats = db.get_stats().unwrap();
-        assert_eq!(stats.total_recordings, 0);
+        let recording = Recording::new("Planning".to_string());
+        let mut secret = Recording::new("Board".to_string());
+        secret.sensitivity = Sensitivity::Confidential;
+        db.insert_recording(&recording).unwrap();
+        db.insert_recording(&secret).unwrap();
+        db.insert_segments(&[
+            TranscriptSegment::new(recording.id.clone(), 0.0, 2.0, "Launch moves.".to_string()),
+            TranscriptSegment::new(recording.id.clone(), 2.0, 4.0, "Lunch order.".to_string()),
+            TranscriptSegment::new(recording.id.clone(), 4.0, 6.0, "Weather.".to_string()),
+            TranscriptSegment::new(secret.id.clone(), 0.0, 2.0, "Launch cut.".to_string()),
+        ])
+        .unwrap();
+        let segments = db.segments_missing_embeddings("embed-1", true).unwrap();
+        let vectors: Vec<(&TranscriptSegment, Vec<f32>)> = segments
+            .iter()
+            .map(|segment| {
+                let vector = match segment.text.as_str() {
+                    "Launch moves." => vec![1.0, 0.1],
+                    "Lunch order." => vec![0.5, 0.5],
+                    "Weather." => vec![-1.0, 0.0, 0.0],
+                    _ => vec![1.0, 0.0],
+                };
+                (segment, vector)
+            })
+            .collect();
+        db.insert_segment_embeddings("embed-1", &vectors).unwrap();
+
+        let texts = |include_confidential: bool| -> Vec<(String, bool)> {
+            db.search_semantic("embed-1", &[2.0, 0.0], 5, include_confidential)
+                .unwrap()
+                .into_iter()
+                .map(|(_, segment, score)| (segment.text, score > 0.9))
+                .collect()
+        };
+        assert_eq!(
+            texts(false),
+            [
+                ("Launch moves.".to_string(), true),
+                ("Lunch order.".to_string(), false)
+            ]
+        );
+        assert_eq!(texts(true)[0].0, "Launch cut.");
+        assert!(db
+            .search_semantic("embed-2", &[2.0, 0.0], 5, true)
+            .unwrap()
+            .is_empty());
     }
 
     #[test]
-    fn test_insert_and_get_recording() {
+    fn test_restoring_a_snapshot_reverts_a_replaced_transcript() {
         let db = Database::open_memory().unwrap();
 
-        let recording = Recording::new("Test Meeting".to_string());
+        let recording = Recording::new("Review".to_string());
         db.insert_recording(&recording).unwrap();
+        db.set_participants(&recording.id, &["Ada".to_string()])
+            .unwrap();
+        let segment =
+            |text: &str| TranscriptSegment::new(recording.id.clone(), 0.0, 1.0, text.to_string());
+        db.insert_segment(&segment("before")).unwrap();
+        db.rename_speaker(&recording.id, "Speaker 1", "Ada")
+            .unwrap();
 
-        let retrieved = db.get_recording(&recording.id).unwrap().unwrap();
-        assert_eq!(retrieved.title, "Test Meeting");
+        db.create_snapshot("align", &[&recording.id]).unwrap();
+        db.replace_segments(&recording.id, &[segment("after"), segment("more")])
+            .unwrap();
+
+        let snapshot = db.list_snapshots(1).unwrap().pop().unwrap();
+        db.restore_snapshot(&snapshot).unwrap();
+        let segments = db.get_transcript_segments(&recording.id).unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].text, "before");
+        assert_eq!(db.get_participants(&recording.id).unwrap(), vec!["Ada"]);
+        assert_eq!(
+            db.get_speaker_names(&recording.id).unwrap(),
+            [("Speaker 1".to_string(), "Ada".to_string())]
+        );
+        assert!(db.list_snapshots(1).unwrap().is_empty());
+
+        for _ in 0..3 {
+            db.create_snapshot("align", &[&recording.id]).unwrap();
+        }
+        db.prune_snapshots(2).unwrap();
+        assert_eq!(db.list_snapshots(10).unwrap().len(), 2);
     }
 
     #[test]
-    fn test_insert_and_search_segments() {
+    fn test_delete_recording_removes_its_rows() {
         let db = Database::open_memory().unwrap();
 
-        let recording = Recording::new("Test Meeting".to_string());
+        let recording = Recording::new("Planning".to_string());
         db.insert_recording(&recording).unwrap();
-
-        let segment = TranscriptSegment::new(
+        db.insert_segment(&TranscriptSegment::new(
             recording.id.clone(),
             0.0,
-            5.0,
-            "Hello world this is a test".to_string(),
+            1.0,
+            "quarterly roadmap".to_string(),
+        ))
+        .unwrap();
+        db.insert_summary(&Summary::new(
+            recording.id.clone(),
+            "Summary".to_string(),
+            "gemini",
+            "gemini-2.5-flash",
+            1,
+        ))
+        .unwrap();
+        db.set_chunks(&recording.id, &["part-1.ogg".to_string()])
+            .unwrap();
+
+        db.delete_recording(&recording.id).unwrap();
+
+        assert!(db.get_recording(&recording.id).unwrap().is_none());
+        assert!(db
+            .get_transcript_segments(&recording.id)
+            .unwrap()
+            .is_empty());
+        assert!(db.list_summaries(&recording.id).unwrap().is_empty());
+        assert!(db.get_chunks(&recording.id).unwrap().is_empty());
+        assert!(db
+            .search_transcripts("roadmap", &RecordingFilter::default(), 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_latest_summary_is_newest_insert() {
+        let db = Database::open_memory().unwrap();
+
+        let recording = Recording::new("Planning".to_string());
+        db.insert_recording(&recording).unwrap();
+        assert!(db.latest_summary(&recording.id).unwrap().is_none());
+
+        let mut first = Summary::new(
+            recording.id.clone(),
+            "First".to_string(),
+            "gemini",
+            "gemini-2.5-flash",
+            1,
         );
-        db.insert_segment(&segment).unwrap();
+        first.created_at -= chrono::Duration::hours(1);
+        db.insert_summary(&first).unwrap();
+        let mut second = Summary::new(
+            recording.id.clone(),
+            "Second".to_string(),
+            "gemini",
+            "gemini-2.5-pro",
+            1,
+        );
+        second.template = Some("standup".to_string());
+        second.transcript_hash = Some(Summary::hash_transcript("[00:00] Hello\n"));
+        second.language = Some("English".to_string());
+        let id = db.insert_summary(&second).unwrap();
 
-        let results = db.search_transcripts("hello", 10).unwrap();
-        assert_eq!(results.len(), 1);
-        assert!(results[0].1.text.contains("Hello"));
+        let latest = db.latest_summary(&recording.id).unwrap().unwrap();
+        assert_eq!(latest.id, id);
+        assert_eq!(latest.text, "Second");
+        assert_eq!(latest.model, "gemini-2.5-pro");
+        assert_eq!(latest.template.as_deref(), Some("standup"));
+        assert_eq!(latest.transcript_hash, second.transcript_hash);
+        assert_eq!(latest.language.as_deref(), Some("English"));
+        assert_eq!(latest.created_at.timestamp(), second.created_at.timestamp());
     }
 
     #[test]
-    fn test_new_database_sets_schema_version() {
+    fn test_restored_summary_becomes_current() {
+        let db = Database::open_memory().unwrap();
+
+        let mut recording = Recording::new("Planning".to_string());
+        db.insert_recording(&recording).unwrap();
+        let mut first = Summary::new(
+            recording.id.clone(),
+            "First".to_string(),
+            "gemini",
+            "gemini-2.5-flash",
+            1,
+        );
+        first.created_at -= chrono::Duration::hours(1);
+        let first_id = db.insert_summary(&first).unwrap();
+        recording.notes = Some("Second".to_string());
+        db.update_recording(&recording).unwrap();
+        let mut second = Summary::new(
+            recording.id.clone(),
+            "Second".to_string(),
+            "ollama",
+            "llama3.1",
+            1,
+        );
+        second.created_at -= chrono::Duration::minutes(1);
+        db.insert_summary(&second).unwrap();
+        assert_eq!(
+            db.latest_summary(&recording.id).unwrap().unwrap().text,
+            "Second"
+        );
+
+        let first = db.get_summary(first_id).unwrap().unwrap();
+        db.restore_summary(&first).unwrap();
+
+        let current = db.latest_summary(&recording.id).unwrap().unwrap();
+        assert_eq!(current.id, first_id);
+        assert!(current.selected_at.is_some());
+        let notes = db.get_recording(&recording.id).unwrap().unwrap().notes;
+        assert_eq!(notes.as_deref(), Some("First"));
+        // History keeps generation order
+        let texts: Vec<_> = db
+            .list_summaries(&recording.id)
+            .unwrap()
+            .into_iter()
+            .map(|summary| summary.text)
+            .collect();
+        assert_eq!(texts, ["First", "Second"]);
+    }
+
+    #[test]
+    fn test_unapproved_lists_recordings_with_draft_summaries() {
         let db = Database::open_memory().unwrap();
-        assert_eq!(db.schema_version().unwrap(), 1);
+
+        let summary_for = |recording: &mut Recording| {
+            recording.notes = Some("Notes".to_string());
+            db.update_recording(recording).unwrap();
+            let summary = Summary::new(
+                recording.id.clone(),
+                "Notes".to_string(),
+                "gemini",
+                "gemini-2.5-flash",
+                1,
+            );
+            db.insert_summary(&summary).unwrap()
+        };
+
+        let mut approved = Recording::new("Approved".to_string());
+        let mut draft = Recording::new("Draft".to_string());
+        let mut legacy = Recording::new("Legacy".to_string());
+        let unsummarized = Recording::new("No summary".to_string());
+        for recording in [&approved, &draft, &legacy, &unsummarized] {
+            db.insert_recording(recording).unwrap();
+        }
+
+        let approved_id = summary_for(&mut approved);
+        db.set_summary_approved(approved_id, Some(Utc::now()))
+            .unwrap();
+        summary_for(&mut draft);
+        legacy.notes = Some("Written before summaries were tracked".to_string());
+        db.update_recording(&legacy).unwrap();
+
+        let mut titles: Vec<String> = db
+            .list_unapproved_recordings(&RecordingFilter::default(), 10)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.title)
+            .collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Draft", "Legacy"]);
+        assert!(db
+            .latest_summary(&approved.id)
+            .unwrap()
+            .unwrap()
+            .is_approved());
+
+        // A regenerated summary starts out as a draft again
+        summary_for(&mut approved);
+        assert_eq!(
+            db.list_unapproved_recordings(&RecordingFilter::default(), 10)
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_migration_keeps_a_copy_of_the_old_database() {
+        let tmp = tempdir().unwrap();
+        let db_path = tmp.path().join("minutes.db");
+
+        let db = Database::open_path(&db_path).unwrap();
+        db.set_schema_version(CURRENT_SCHEMA_VERSION - 1).unwrap();
+        db.backup_before_migration(&db_path).unwrap();
+
+        let backup = tmp
+            .path()
+            .join(format!("minutes.db.v{}.bak", CURRENT_SCHEMA_VERSION - 1));
+        let backup = Connection::open(backup).unwrap();
+        let version: i64 = backup
+            .query_row("PRAGMA user_version;", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, CURRENT_SCHEMA_VERSION - 1);
     }
 
     #[test]
@@ -575,7 +4613,7 @@ mod tests {
         drop(conn);
 
         let db = Database::open_path(&db_path).unwrap();
-        assert_eq!(db.schema_version().unwrap(), 1);
+        assert_eq!(db.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
 
         let recording = Recording::new("Legacy migration".to_string());
         db.insert_recording(&recording).unwrap();
@@ -587,7 +4625,44 @@ mod tests {
         );
         db.insert_segment(&segment).unwrap();
 
-        let results = db.search_transcripts("searchable", 10).unwrap();
+        let results = db
+            .search_transcripts("searchable", &RecordingFilter::default(), 10)
+            .unwrap();
         assert_eq!(results.len(), 1);
     }
+
+    #[test]
+    fn test_maintenance_rebuilds_the_search_index() {
+        let db = Database::open_memory().unwrap();
+        let recording = Recording::new("Standup".to_string());
+        db.insert_recording(&recording).unwrap();
+        db.insert_segment(&TranscriptSegment::new(
+            recording.id.clone(),
+            0.0,
+            1.0,
+            "quarterly roadmap".to_string(),
+        ))
+        .unwrap();
+        db.conn
+            .execute_batch("INSERT INTO transcript_fts(transcript_fts) VALUES ('delete-all')")
+            .unwrap();
+        assert!(db
+            .search_transcripts("roadmap", &RecordingFilter::default(), 10)
+            .unwrap()
+            .is_empty());
+
+        assert!(db.integrity_check().unwrap().is_empty());
+        db.rebuild_search_index().unwrap();
+        db.vacuum().unwrap();
+
+        assert_eq!(
+            db.search_transcripts("roadmap", &RecordingFilter::default(), 10)
+                .unwrap()
+                .len(),
+            1
+        );
+        let sizes = db.table_sizes().unwrap();
+        assert!(sizes.iter().any(|(name, _)| name == "transcript_fts_data"));
+        assert!(sizes.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
 }
@@ -2,10 +2,23 @@
 //!
 //! Handles database operations using SQLite with FTS5 for full-text search.
 
+pub mod backup;
 mod database;
 mod models;
+mod query;
 mod repository;
+pub mod retention;
+pub mod snapshot;
+pub mod trash;
+mod vector;
 
 pub use database::Database;
-pub use models::{Recording, RecordingState, TranscriptSegment};
+pub use models::{
+    ActionItem, Chapter, Decision, JobState, LibraryStats, LlmUsage, LlmUsageTotal, MonthActivity,
+    Project, Recording, RecordingFilter, RecordingSnapshot, RecordingState, SearchHit, Sensitivity,
+    Silence, Snapshot, SpeakerProfile, SpeakerStats, Summary, TranscriptSegment, TranscriptStats,
+    TranscriptWord, TranscriptionCheckpoint, TranscriptionJob, WeekActivity, MATCH_END,
+    MATCH_START, STATS_WEEKS,
+};
+pub use query::SearchQuery;
 pub use repository::Repository;
@@ -1,6 +1,6 @@
 //! Data models for storage
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -47,6 +47,76 @@ impl FromStr for RecordingState {
     }
 }
 
+/// State of a job in the transcription queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    /// Waiting for a free worker
+    Queued,
+    /// Being transcribed
+    Running,
+    /// Taken out of the queue until transcription is requested again
+    Cancelled,
+}
+
+impl JobState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl FromStr for JobState {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(Self::Queued),
+            "running" => Ok(Self::Running),
+            "cancelled" => Ok(Self::Cancelled),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Sensitivity label controlling where a recording may be sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sensitivity {
+    /// No restrictions
+    #[default]
+    Normal,
+    /// Kept away from cloud providers and sharing commands unless overridden
+    Confidential,
+}
+
+impl Sensitivity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Confidential => "confidential",
+        }
+    }
+}
+
+impl FromStr for Sensitivity {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(Self::Normal),
+            "confidential" => Ok(Self::Confidential),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Time format of [`Recording::default_title`]
+const DEFAULT_TITLE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
 /// A meeting recording
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Recording {
@@ -76,6 +146,43 @@ pub struct Recording {
 
     /// Tags for categorization
     pub tags: Vec<String>,
+
+    /// Separate system audio track, when tracks are kept unmixed
+    pub system_audio_path: Option<String>,
+
+    /// Separate microphone track, when tracks are kept unmixed
+    pub mic_audio_path: Option<String>,
+
+    /// Sensitivity label
+    #[serde(default)]
+    pub sensitivity: Sensitivity,
+
+    /// Project the recording is grouped under
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Language of the transcript (ISO 639-1 code), configured or detected
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// Why the last transcription attempt failed
+    #[serde(default)]
+    pub failure_reason: Option<String>,
+
+    /// Language to transcribe in (ISO 639-1 code or "auto"), overriding whisper.language
+    #[serde(default)]
+    pub language_override: Option<String>,
+
+    /// Tags suggested by the LLM that have not been accepted yet
+    #[serde(default)]
+    pub suggested_tags: Vec<String>,
+
+    /// When the recording was moved to the trash
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+
+    /// When the recording's audio was moved to an archive
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 impl Recording {
@@ -92,10 +199,174 @@ impl Recording {
             updated_at: now,
             notes: None,
             tags: Vec::new(),
+            system_audio_path: None,
+            mic_audio_path: None,
+            sensitivity: Sensitivity::Normal,
+            project: None,
+            language: None,
+            failure_reason: None,
+            language_override: None,
+            suggested_tags: Vec::new(),
+            deleted_at: None,
+            archived_at: None,
+        }
+    }
+
+    /// Title given to a recording started without one, e.g. "Meeting 2024-05-03 14:00"
+    pub fn default_title() -> String {
+        format!("Meeting {}", Local::now().format(DEFAULT_TITLE_FORMAT))
+    }
+
+    /// Whether the recording still has the title it was started with by default
+    pub fn has_default_title(&self) -> bool {
+        self.title
+            .strip_prefix("Meeting ")
+            .is_some_and(|at| NaiveDateTime::parse_from_str(at, DEFAULT_TITLE_FORMAT).is_ok())
+    }
+
+    /// Whether the recording is labelled confidential
+    pub fn is_confidential(&self) -> bool {
+        self.sensitivity == Sensitivity::Confidential
+    }
+
+    /// Audio files stored on the recording: the main file and any separate tracks
+    pub fn audio_files(&self) -> impl Iterator<Item = &str> {
+        [
+            &self.audio_path,
+            &self.system_audio_path,
+            &self.mic_audio_path,
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+    }
+
+    /// Why the audio cannot be read when a file is missing, pointing at the
+    /// archive if the audio was moved to one
+    pub fn missing_audio_message(&self) -> String {
+        let archive = self
+            .audio_files()
+            .next()
+            .and_then(|file| std::path::Path::new(file).parent());
+        match (self.archived_at, archive) {
+            (Some(_), Some(dir)) => format!(
+                "The audio of this recording is archived in {}, which is not reachable; \
+                 mount the archive and try again",
+                dir.display()
+            ),
+            _ => "Audio file of the recording is missing".to_string(),
         }
     }
 }
 
+/// Conditions narrowing recording lists and transcript searches, all of
+/// them optional
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordingFilter {
+    /// Only recordings created at or after this time
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only recordings created before this time
+    pub until: Option<DateTime<Utc>>,
+
+    /// Only recordings at least this many seconds long
+    pub min_duration_secs: Option<u64>,
+
+    /// Only recordings in this state
+    pub state: Option<RecordingState>,
+
+    /// Only recordings this person attended, compared without case
+    pub participant: Option<String>,
+}
+
+impl RecordingFilter {
+    /// Whether no condition is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A named group of recordings, with totals over its recordings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    /// Project name, unique ignoring case
+    pub name: String,
+
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+
+    /// Number of recordings assigned to the project
+    pub recording_count: usize,
+
+    /// Combined duration of those recordings in seconds
+    pub total_duration_secs: u64,
+}
+
+/// A recording's entry in the transcription queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionJob {
+    /// Recording to transcribe
+    pub recording_id: String,
+
+    /// Title of the recording
+    pub title: String,
+
+    /// Current state
+    pub state: JobState,
+
+    /// Higher priorities run first; raised by `minutes queue bump`
+    pub priority: i64,
+
+    /// When the job was queued
+    pub queued_at: DateTime<Utc>,
+
+    /// When the job last started running
+    pub started_at: Option<DateTime<Utc>>,
+
+    /// Transcription attempts started so far
+    pub attempts: u32,
+
+    /// Earliest time a failed job is tried again
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// Rows of one recording as they were before a destructive operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingSnapshot {
+    pub recording: Recording,
+    pub segments: Vec<TranscriptSegment>,
+    pub summaries: Vec<Summary>,
+    pub participants: Vec<String>,
+    pub chunks: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Names given to speakers, as `(label, name)` pairs
+    #[serde(default)]
+    pub speaker_names: Vec<(String, String)>,
+    #[serde(default)]
+    pub action_items: Vec<ActionItem>,
+    #[serde(default)]
+    pub decisions: Vec<Decision>,
+}
+
+/// Safety snapshot taken before a destructive operation, reverted by `minutes undo`
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Unique identifier
+    pub id: i64,
+
+    /// Operation that was about to run (e.g. "delete", "align")
+    pub operation: String,
+
+    /// When the snapshot was taken
+    pub created_at: DateTime<Utc>,
+
+    /// Affected recordings
+    pub recordings: Vec<RecordingSnapshot>,
+
+    /// Directory holding copies of the recordings' audio files, if backed up
+    pub audio_dir: Option<String>,
+}
+
 /// A segment of transcribed text
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptSegment {
@@ -119,6 +390,18 @@ pub struct TranscriptSegment {
 
     /// Confidence score (0.0 - 1.0)
     pub confidence: Option<f64>,
+
+    /// Word-level timestamps, when `whisper.word_timestamps` was enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TranscriptWord>,
+
+    /// English translation of `text`, when `whisper.translate` was enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translated_text: Option<String>,
+
+    /// Whether `text` was corrected by hand with `minutes edit`
+    #[serde(default)]
+    pub edited: bool,
 }
 
 impl TranscriptSegment {
@@ -132,6 +415,710 @@ impl TranscriptSegment {
             text,
             speaker: None,
             confidence: None,
+            words: Vec::new(),
+            translated_text: None,
+            edited: false,
+        }
+    }
+
+    /// Whether the segment's confidence is known and below `threshold`
+    pub fn is_low_confidence(&self, threshold: f64) -> bool {
+        self.confidence
+            .is_some_and(|confidence| confidence < threshold)
+    }
+
+    /// Text prefixed with the speaker, e.g. "Priya: Let's start."
+    pub fn labelled_text(&self) -> String {
+        match &self.speaker {
+            Some(speaker) => format!("{}: {}", speaker, self.text),
+            None => self.text.clone(),
+        }
+    }
+
+    /// First word matching a search term, ignoring case and punctuation
+    ///
+    /// Matches whole words, or word prefixes for terms ending in `*` as in
+    /// full-text queries.
+    pub fn find_word(&self, term: &str) -> Option<&TranscriptWord> {
+        let (term, prefix) = match term.strip_suffix('*') {
+            Some(term) => (term, true),
+            None => (term, false),
+        };
+        let term = normalize_word(term);
+        if term.is_empty() {
+            return None;
+        }
+
+        self.words.iter().find(|word| {
+            let word = normalize_word(&word.text);
+            if prefix {
+                word.starts_with(&term)
+            } else {
+                word == term
+            }
+        })
+    }
+}
+
+/// Marks the start of a matched term in a search excerpt
+pub const MATCH_START: char = '\u{2}';
+
+/// Marks the end of a matched term in a search excerpt
+pub const MATCH_END: char = '\u{3}';
+
+/// A transcript segment found by a full-text search
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub recording: Recording,
+    pub segment: TranscriptSegment,
+
+    /// Segment text around the matches, with each matched term between
+    /// `MATCH_START` and `MATCH_END` and cut-off ends shown as "…"
+    pub excerpt: String,
+}
+
+impl SearchHit {
+    /// Runs of the excerpt in order, each flagged when it is a matched term
+    pub fn excerpt_parts(&self) -> Vec<(&str, bool)> {
+        let mut parts = Vec::new();
+        let mut rest = self.excerpt.as_str();
+        while let Some(start) = rest.find(MATCH_START) {
+            if start > 0 {
+                parts.push((&rest[..start], false));
+            }
+            rest = &rest[start + MATCH_START.len_utf8()..];
+            let end = rest.find(MATCH_END).unwrap_or(rest.len());
+            parts.push((&rest[..end], true));
+            rest = rest.get(end + MATCH_END.len_utf8()..).unwrap_or_default();
+        }
+        if !rest.is_empty() {
+            parts.push((rest, false));
+        }
+        parts
+    }
+
+    /// The excerpt without match markers
+    pub fn excerpt_text(&self) -> String {
+        self.excerpt.replace([MATCH_START, MATCH_END], "")
+    }
+}
+
+/// Progress through one audio stream of an unfinished transcription
+///
+/// Saved after every window, so a restarted daemon continues a long
+/// recording where it stopped instead of starting over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionCheckpoint {
+    /// Stream of the recording: "mixed", "system", or "microphone"
+    pub stream: String,
+
+    /// Length of the stream in samples; progress on different audio is discarded
+    pub total_samples: usize,
+
+    /// Samples transcribed so far
+    pub done_samples: usize,
+
+    /// Segments of the finished windows, before filtering and merging
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// A stretch of a recording without speech, found before transcription
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Silence {
+    /// Start time in seconds from beginning of recording
+    pub start_time: f64,
+
+    /// End time in seconds
+    pub end_time: f64,
+}
+
+impl Silence {
+    /// Length of the silence in seconds
+    pub fn duration(&self) -> f64 {
+        self.end_time - self.start_time
+    }
+}
+
+/// A section of a meeting about one topic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    /// Start time in seconds from beginning of recording
+    pub start_time: f64,
+
+    /// Short description of the topic, e.g. "Budget discussion"
+    pub title: String,
+}
+
+/// A word of a transcript segment with its own timing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptWord {
+    /// Start time in seconds from beginning of recording
+    pub start_time: f64,
+
+    /// End time in seconds
+    pub end_time: f64,
+
+    /// The word, with any attached punctuation
+    pub text: String,
+
+    /// Probability Whisper assigned to the word (0.0 - 1.0)
+    pub confidence: Option<f64>,
+}
+
+/// Lowercase a word and strip surrounding punctuation for matching
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// A generated summary and what produced it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Summary {
+    /// Unique identifier
+    pub id: i64,
+
+    /// Recording the summary belongs to
+    pub recording_id: String,
+
+    /// Summary text as returned by the provider
+    pub text: String,
+
+    /// LLM provider that generated it (e.g. gemini)
+    pub provider: String,
+
+    /// Model name passed to the provider
+    pub model: String,
+
+    /// Version of the built-in summary prompt
+    pub prompt_version: u32,
+
+    /// When the summary was generated
+    pub created_at: DateTime<Utc>,
+
+    /// When the summary was signed off (None = draft)
+    pub approved_at: Option<DateTime<Utc>>,
+
+    /// Prompt template used instead of the built-in prompt
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// Hash of the transcript the summary was generated from
+    #[serde(default)]
+    pub transcript_hash: Option<String>,
+
+    /// When the summary was last restored as the recording's current summary
+    #[serde(default)]
+    pub selected_at: Option<DateTime<Utc>>,
+
+    /// Language the summary was written in when it is not the meeting's (e.g. English)
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl Summary {
+    /// Create a new summary record
+    pub fn new(
+        recording_id: String,
+        text: String,
+        provider: &str,
+        model: &str,
+        prompt_version: u32,
+    ) -> Self {
+        Self {
+            id: 0, // Will be set by database
+            recording_id,
+            text,
+            provider: provider.to_string(),
+            model: model.to_string(),
+            prompt_version,
+            created_at: Utc::now(),
+            approved_at: None,
+            template: None,
+            transcript_hash: None,
+            selected_at: None,
+            language: None,
+        }
+    }
+
+    /// Fingerprint of a transcript, stable across releases (64-bit FNV-1a)
+    pub fn hash_transcript(transcript: &str) -> String {
+        let hash = transcript
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+            });
+        format!("{:016x}", hash)
+    }
+
+    /// Whether generating the summary again would use the same input and prompt
+    ///
+    /// Summaries stored before transcripts were hashed are never up to date.
+    pub fn is_up_to_date(
+        &self,
+        transcript_hash: &str,
+        provider: &str,
+        model: &str,
+        prompt_version: u32,
+        template: Option<&str>,
+        language: Option<&str>,
+    ) -> bool {
+        self.transcript_hash.as_deref() == Some(transcript_hash)
+            && self.provider == provider
+            && self.model == model
+            && self.template.as_deref() == template
+            && self.language.as_deref() == language
+            // The built-in prompt only matters when no template replaced it
+            && (template.is_some() || self.prompt_version == prompt_version)
+    }
+
+    /// Whether the summary has been approved
+    pub fn is_approved(&self) -> bool {
+        self.approved_at.is_some()
+    }
+
+    /// Approval status for display
+    pub fn status(&self) -> String {
+        match self.approved_at {
+            Some(at) => format!("approved {}", at.format("%Y-%m-%d %H:%M")),
+            None => "draft".to_string(),
+        }
+    }
+
+    /// Prompt the summary was generated with, e.g. "prompt v3" or "template standup"
+    pub fn prompt(&self) -> String {
+        match &self.template {
+            Some(template) => format!("template {}", template),
+            None => format!("prompt v{}", self.prompt_version),
+        }
+    }
+
+    /// One-line description of where the summary came from
+    pub fn provenance(&self) -> String {
+        format!(
+            "{} / {}, {}, {}",
+            self.provider,
+            self.model,
+            self.prompt(),
+            self.created_at.format("%Y-%m-%d %H:%M")
+        )
+    }
+}
+
+/// A task agreed on in a meeting, extracted from its transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionItem {
+    /// Unique identifier
+    pub id: i64,
+
+    /// Recording the item was extracted from
+    pub recording_id: String,
+
+    /// Person who took the task on, if named
+    pub owner: Option<String>,
+
+    /// What needs to be done
+    pub task: String,
+
+    /// Due date as agreed (e.g. "2024-05-03" or "end of sprint")
+    pub due: Option<String>,
+
+    /// When the item was marked done (None = open)
+    pub done_at: Option<DateTime<Utc>>,
+
+    /// When the item was extracted
+    pub created_at: DateTime<Utc>,
+}
+
+impl ActionItem {
+    /// Create a new open action item
+    pub fn new(
+        recording_id: String,
+        owner: Option<String>,
+        task: String,
+        due: Option<String>,
+    ) -> Self {
+        Self {
+            id: 0, // Will be set by database
+            recording_id,
+            owner,
+            task,
+            due,
+            done_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Whether the item has been marked done
+    pub fn is_done(&self) -> bool {
+        self.done_at.is_some()
+    }
+}
+
+/// A decision made in a meeting, extracted from its transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    /// Unique identifier
+    pub id: i64,
+
+    /// Recording the decision was extracted from
+    pub recording_id: String,
+
+    /// What was decided
+    pub text: String,
+
+    /// Seconds from the beginning of the recording where it was discussed
+    pub timestamps: Vec<f64>,
+
+    /// When the decision was extracted
+    pub created_at: DateTime<Utc>,
+}
+
+impl Decision {
+    /// Create a new decision
+    pub fn new(recording_id: String, text: String, timestamps: Vec<f64>) -> Self {
+        Self {
+            id: 0, // Will be set by database
+            recording_id,
+            text,
+            timestamps,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Tokens used by one LLM call, for usage and cost reports
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmUsage {
+    /// What the call was for, e.g. "summarize" or "ask"
+    pub operation: String,
+
+    /// Provider name as in `llm.provider`
+    pub provider: String,
+
+    /// Model that answered
+    pub model: String,
+
+    /// Recording the call was about, if one
+    pub recording_id: Option<String>,
+
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+
+    /// Estimated cost in USD at the prices configured when the call was
+    /// made, `None` when no prices were configured
+    pub cost_usd: Option<f64>,
+
+    /// When the call was made
+    pub created_at: DateTime<Utc>,
+}
+
+/// LLM usage of one provider and model in one month
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmUsageTotal {
+    /// Month as `YYYY-MM`
+    pub month: String,
+    pub provider: String,
+    pub model: String,
+    pub calls: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Sum of the calls' estimated costs, `None` if none had one
+    pub cost_usd: Option<f64>,
+}
+
+/// Statistics computed from a recording's transcript
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TranscriptStats {
+    pub segment_count: usize,
+    pub word_count: usize,
+    /// Recording length, or the end of the last segment if it is unknown
+    pub duration_secs: f64,
+    /// Time covered by at least one segment
+    pub speaking_secs: f64,
+    /// Words per minute of speaking time
+    pub words_per_minute: f64,
+    /// Share of the recording not covered by any segment (0.0 - 1.0)
+    pub silence_ratio: f64,
+    /// Per-speaker totals, most speaking time first
+    pub speakers: Vec<SpeakerStats>,
+}
+
+/// Speaking time, word count and participation of one speaker
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpeakerStats {
+    /// Speaker label, `None` for segments without one
+    pub speaker: Option<String>,
+    pub segment_count: usize,
+    pub word_count: usize,
+    pub speaking_secs: f64,
+    /// Times the speaker started while another speaker was still talking
+    pub interruptions: usize,
+    /// Longest stretch of speech without another speaker or a long pause
+    pub longest_monologue_secs: f64,
+}
+
+/// Overlap with the previous speaker needed to count as an interruption;
+/// segment boundaries are not exact, so shorter overlaps are ignored
+const INTERRUPTION_MIN_OVERLAP_SECS: f64 = 0.5;
+
+/// Pause after which a speaker continuing counts as a new monologue
+const MONOLOGUE_MAX_PAUSE_SECS: f64 = 5.0;
+
+impl TranscriptStats {
+    /// Compute statistics for a recording from its segments
+    pub fn compute(recording: &Recording, segments: &[TranscriptSegment]) -> Self {
+        let word_count = |segment: &TranscriptSegment| segment.text.split_whitespace().count();
+
+        let mut speakers: Vec<SpeakerStats> = Vec::new();
+        for segment in segments {
+            let index = match speakers.iter().position(|s| s.speaker == segment.speaker) {
+                Some(index) => index,
+                None => {
+                    speakers.push(SpeakerStats {
+                        speaker: segment.speaker.clone(),
+                        segment_count: 0,
+                        word_count: 0,
+                        speaking_secs: 0.0,
+                        interruptions: 0,
+                        longest_monologue_secs: 0.0,
+                    });
+                    speakers.len() - 1
+                }
+            };
+            let stats = &mut speakers[index];
+            stats.segment_count += 1;
+            stats.word_count += word_count(segment);
+            stats.speaking_secs += (segment.end_time - segment.start_time).max(0.0);
+        }
+        count_turns(segments, &mut speakers);
+        speakers.sort_by(|a, b| b.speaking_secs.total_cmp(&a.speaking_secs));
+
+        let total_words = segments.iter().map(word_count).sum();
+        let last_end = segments.iter().map(|s| s.end_time).fold(0.0, f64::max);
+        let duration_secs = recording
+            .duration_secs
+            .map(|secs| secs as f64)
+            .unwrap_or(last_end);
+        let speaking_secs = covered_secs(segments);
+
+        Self {
+            segment_count: segments.len(),
+            word_count: total_words,
+            duration_secs,
+            speaking_secs,
+            words_per_minute: if speaking_secs > 0.0 {
+                total_words as f64 / (speaking_secs / 60.0)
+            } else {
+                0.0
+            },
+            silence_ratio: if duration_secs > 0.0 {
+                (1.0 - speaking_secs / duration_secs).clamp(0.0, 1.0)
+            } else {
+                0.0
+            },
+            speakers,
+        }
+    }
+
+    /// Share of all speaking time taken by `speaker` (0.0 - 1.0)
+    pub fn talk_share(&self, speaker: &SpeakerStats) -> f64 {
+        let total: f64 = self.speakers.iter().map(|s| s.speaking_secs).sum();
+        if total > 0.0 {
+            speaker.speaking_secs / total
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Add each speaker's interruptions and longest monologue to `speakers`
+///
+/// Only segments with a speaker label take part; an unlabelled segment
+/// could be anyone.
+fn count_turns(segments: &[TranscriptSegment], speakers: &mut [SpeakerStats]) {
+    let mut labelled: Vec<&TranscriptSegment> =
+        segments.iter().filter(|s| s.speaker.is_some()).collect();
+    labelled.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    // Current monologue as (speaker, start, end)
+    let mut run: Option<(&Option<String>, f64, f64)> = None;
+    let mut previous: Option<&TranscriptSegment> = None;
+    for segment in labelled {
+        let Some(stats) = speakers.iter_mut().find(|s| s.speaker == segment.speaker) else {
+            continue;
+        };
+        if let Some(previous) = previous {
+            if previous.speaker != segment.speaker
+                && segment.start_time < previous.end_time - INTERRUPTION_MIN_OVERLAP_SECS
+            {
+                stats.interruptions += 1;
+            }
+        }
+
+        run = match run {
+            Some((speaker, start, end))
+                if *speaker == segment.speaker
+                    && segment.start_time - end <= MONOLOGUE_MAX_PAUSE_SECS =>
+            {
+                Some((speaker, start, end.max(segment.end_time)))
+            }
+            _ => Some((&segment.speaker, segment.start_time, segment.end_time)),
+        };
+        if let Some((_, start, end)) = run {
+            stats.longest_monologue_secs = stats.longest_monologue_secs.max(end - start);
+        }
+        previous = Some(segment);
+    }
+}
+
+/// Total time covered by segments, counting overlapping speech once
+fn covered_secs(segments: &[TranscriptSegment]) -> f64 {
+    let mut spans: Vec<(f64, f64)> = segments
+        .iter()
+        .filter(|s| s.end_time > s.start_time)
+        .map(|s| (s.start_time, s.end_time))
+        .collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut total = 0.0;
+    let mut current: Option<(f64, f64)> = None;
+    for (start, end) in spans {
+        current = match current {
+            Some((from, to)) if start <= to => Some((from, to.max(end))),
+            Some((from, to)) => {
+                total += to - from;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    total + current.map_or(0.0, |(from, to)| to - from)
+}
+
+/// Weeks counted in [`LibraryStats::per_week`], the current one included
+pub const STATS_WEEKS: usize = 12;
+
+/// Months counted in [`LibraryStats::per_month`], the current one included
+pub const STATS_MONTHS: usize = 12;
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Totals and activity over the whole library, in local time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LibraryStats {
+    pub recordings: usize,
+    pub transcribed: usize,
+    pub summarized: usize,
+    pub segments: usize,
+    pub recorded_secs: u64,
+    /// Mean length of the recordings with a known duration
+    pub average_secs: u64,
+    /// Weekday with the most recordings, `None` for an empty library
+    pub busiest_weekday: Option<String>,
+    /// Recordings started in each of the last weeks, oldest first
+    pub per_week: Vec<WeekActivity>,
+    /// Hours recorded in each of the last months, oldest first
+    pub per_month: Vec<MonthActivity>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeekActivity {
+    /// Monday the week starts on
+    pub week_of: NaiveDate,
+    pub recordings: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthActivity {
+    /// e.g. "2024-05"
+    pub month: String,
+    pub hours: f64,
+}
+
+impl LibraryStats {
+    /// Compute statistics for `recordings`, counting weeks and months back from `today`
+    pub fn compute(recordings: &[Recording], segments: usize, today: NaiveDate) -> Self {
+        let dated: Vec<(NaiveDate, u64)> = recordings
+            .iter()
+            .map(|r| {
+                let date = r.created_at.with_timezone(&Local).date_naive();
+                (date, r.duration_secs.unwrap_or(0))
+            })
+            .collect();
+
+        let this_week = today - Duration::days(today.weekday().num_days_from_monday().into());
+        let per_week = (0..STATS_WEEKS)
+            .rev()
+            .map(|weeks_ago| {
+                let week_of = this_week - Duration::weeks(weeks_ago as i64);
+                let recordings = dated
+                    .iter()
+                    .filter(|(date, _)| (week_of..week_of + Duration::weeks(1)).contains(date))
+                    .count();
+                WeekActivity {
+                    week_of,
+                    recordings,
+                }
+            })
+            .collect();
+
+        let this_month = today.with_day(1).unwrap_or(today);
+        let per_month = (0..STATS_MONTHS)
+            .rev()
+            .filter_map(|months_ago| this_month.checked_sub_months(Months::new(months_ago as u32)))
+            .map(|month| {
+                let secs: u64 = dated
+                    .iter()
+                    .filter(|(date, _)| {
+                        date.year() == month.year() && date.month() == month.month()
+                    })
+                    .map(|(_, secs)| secs)
+                    .sum();
+                MonthActivity {
+                    month: month.format("%Y-%m").to_string(),
+                    hours: secs as f64 / 3600.0,
+                }
+            })
+            .collect();
+
+        let mut by_weekday = [0usize; 7];
+        for (date, _) in &dated {
+            by_weekday[date.weekday().num_days_from_monday() as usize] += 1;
+        }
+        // The first of equally busy days wins
+        let busiest_weekday = by_weekday
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .rev()
+            .max_by_key(|(_, count)| **count)
+            .map(|(day, _)| WEEKDAYS[day].to_string());
+
+        let durations: Vec<u64> = recordings.iter().filter_map(|r| r.duration_secs).collect();
+        let recorded_secs: u64 = durations.iter().sum();
+        Self {
+            recordings: recordings.len(),
+            transcribed: recordings
+                .iter()
+                .filter(|r| r.state == RecordingState::Completed)
+                .count(),
+            summarized: recordings.iter().filter(|r| r.notes.is_some()).count(),
+            segments,
+            recorded_secs,
+            average_secs: recorded_secs
+                .checked_div(durations.len() as u64)
+                .unwrap_or(0),
+            busiest_weekday,
+            per_week,
+            per_month,
         }
     }
 }
@@ -144,3 +1131,213 @@ pub struct SearchResult {
     pub segment: TranscriptSegment,
     pub rank: f64,
 }
+
+/// An enrolled voice used to put names on speakers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerProfile {
+    /// Unique identifier
+    pub id: i64,
+
+    /// Display name used as the segment speaker label
+    pub name: String,
+
+    /// Voice embedding computed from the enrollment clip
+    pub embedding: Vec<f32>,
+
+    /// Recording the enrollment clip was taken from
+    pub source_recording_id: Option<String>,
+
+    /// When the profile was enrolled
+    pub created_at: DateTime<Utc>,
+}
+
+impl SpeakerProfile {
+    /// Create a new speaker profile
+    pub fn new(name: String, embedding: Vec<f32>) -> Self {
+        Self {
+            id: 0, // Will be set by database
+            name,
+            embedding,
+            source_recording_id: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, end: f64, speaker: Option<&str>, text: &str) -> TranscriptSegment {
+        let mut segment = TranscriptSegment::new("rec".to_string(), start, end, text.to_string());
+        segment.speaker = speaker.map(str::to_string);
+        segment
+    }
+
+    #[test]
+    fn recognizes_default_titles() {
+        assert!(Recording::new(Recording::default_title()).has_default_title());
+        assert!(Recording::new("Meeting 2024-05-03 14:00".to_string()).has_default_title());
+        assert!(!Recording::new("Meeting with Ada".to_string()).has_default_title());
+        assert!(!Recording::new("Budget review".to_string()).has_default_title());
+    }
+
+    #[test]
+    fn summaries_are_up_to_date_for_the_same_transcript_and_prompt() {
+        let hash = Summary::hash_transcript("[00:00] Hello\n");
+        assert_eq!(hash, Summary::hash_transcript("[00:00] Hello\n"));
+        assert_ne!(hash, Summary::hash_transcript("[00:00] Hello!\n"));
+
+        let mut summary = Summary::new(
+            "rec".to_string(),
+            "Notes".to_string(),
+            "gemini",
+            "gemini-2.5-flash",
+            3,
+        );
+        assert!(!summary.is_up_to_date(&hash, "gemini", "gemini-2.5-flash", 3, None, None));
+
+        summary.transcript_hash = Some(hash.clone());
+        assert!(summary.is_up_to_date(&hash, "gemini", "gemini-2.5-flash", 3, None, None));
+        assert!(!summary.is_up_to_date(&hash, "gemini", "gemini-2.5-pro", 3, None, None));
+        assert!(!summary.is_up_to_date(&hash, "gemini", "gemini-2.5-flash", 4, None, None));
+        assert!(!summary.is_up_to_date(
+            &hash,
+            "gemini",
+            "gemini-2.5-flash",
+            3,
+            Some("standup"),
+            None
+        ));
+
+        summary.template = Some("standup".to_string());
+        assert!(summary.is_up_to_date(
+            &hash,
+            "gemini",
+            "gemini-2.5-flash",
+            4,
+            Some("standup"),
+            None
+        ));
+        assert!(!summary.is_up_to_date(
+            &hash,
+            "gemini",
+            "gemini-2.5-flash",
+            4,
+            Some("standup"),
+            Some("English")
+        ));
+    }
+
+    #[test]
+    fn transcript_stats_count_overlapping_speech_once() {
+        let mut recording = Recording::new("Sync".to_string());
+        recording.duration_secs = Some(60);
+        let segments = vec![
+            segment(0.0, 10.0, Some("Alice"), "one two three four five"),
+            segment(5.0, 15.0, Some("Bob"), "six seven"),
+            segment(30.0, 45.0, Some("Alice"), "eight nine ten"),
+        ];
+
+        let stats = TranscriptStats::compute(&recording, &segments);
+
+        assert_eq!(stats.segment_count, 3);
+        assert_eq!(stats.word_count, 10);
+        assert_eq!(stats.speaking_secs, 30.0);
+        assert_eq!(stats.words_per_minute, 20.0);
+        assert_eq!(stats.silence_ratio, 0.5);
+        assert_eq!(stats.speakers[0].speaker.as_deref(), Some("Alice"));
+        assert_eq!(stats.speakers[0].speaking_secs, 25.0);
+        assert_eq!(stats.speakers[0].word_count, 8);
+        assert_eq!(stats.speakers[1].segment_count, 1);
+    }
+
+    #[test]
+    fn transcript_stats_count_interruptions_and_monologues() {
+        let segments = vec![
+            segment(0.0, 20.0, Some("Alice"), "intro"),
+            segment(21.0, 40.0, Some("Alice"), "budget"),
+            segment(35.0, 38.0, Some("Bob"), "wait"),
+            segment(39.8, 45.0, Some("Alice"), "as I said"),
+            segment(60.0, 70.0, Some("Alice"), "next"),
+            segment(69.0, 75.0, None, "(crosstalk)"),
+        ];
+
+        let stats = TranscriptStats::compute(&Recording::new("Sync".to_string()), &segments);
+
+        let speaker = |name: Option<&str>| {
+            stats
+                .speakers
+                .iter()
+                .find(|s| s.speaker.as_deref() == name)
+                .unwrap()
+        };
+        assert_eq!(speaker(Some("Alice")).interruptions, 0);
+        assert_eq!(speaker(Some("Alice")).longest_monologue_secs, 40.0);
+        assert_eq!(speaker(Some("Bob")).interruptions, 1);
+        assert_eq!(speaker(Some("Bob")).longest_monologue_secs, 3.0);
+        assert_eq!(speaker(None).interruptions, 0);
+        assert_eq!(speaker(None).longest_monologue_secs, 0.0);
+        let share = stats.talk_share(speaker(Some("Bob")));
+        assert!((share - 3.0 / 63.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn transcript_stats_of_an_empty_transcript_are_zero() {
+        let stats = TranscriptStats::compute(&Recording::new("Empty".to_string()), &[]);
+
+        assert_eq!(stats.word_count, 0);
+        assert_eq!(stats.words_per_minute, 0.0);
+        assert_eq!(stats.silence_ratio, 0.0);
+        assert!(stats.speakers.is_empty());
+    }
+
+    #[test]
+    fn library_stats_count_weeks_months_and_the_busiest_weekday() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap(); // a Wednesday
+        let recording = |date: &str, minutes: u64| {
+            let mut recording = Recording::new("Sync".to_string());
+            let noon = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap();
+            recording.created_at = noon.and_local_timezone(Local).unwrap().to_utc();
+            recording.duration_secs = Some(minutes * 60);
+            recording.state = RecordingState::Completed;
+            recording
+        };
+        let recordings = [
+            recording("2024-05-14", 30), // Tuesday this week
+            recording("2024-05-07", 60), // Tuesday last week
+            recording("2024-05-13", 90), // Monday this week
+            recording("2024-04-02", 60), // Tuesday in April
+        ];
+
+        let stats = LibraryStats::compute(&recordings, 40, today);
+
+        assert_eq!(stats.recordings, 4);
+        assert_eq!(stats.transcribed, 4);
+        assert_eq!(stats.segments, 40);
+        assert_eq!(stats.recorded_secs, 240 * 60);
+        assert_eq!(stats.average_secs, 60 * 60);
+        assert_eq!(stats.busiest_weekday.as_deref(), Some("Tuesday"));
+
+        assert_eq!(stats.per_week.len(), STATS_WEEKS);
+        let this_week = stats.per_week.last().unwrap();
+        assert_eq!(
+            this_week.week_of,
+            NaiveDate::from_ymd_opt(2024, 5, 13).unwrap()
+        );
+        assert_eq!(this_week.recordings, 2);
+        assert_eq!(stats.per_week[STATS_WEEKS - 2].recordings, 1);
+
+        assert_eq!(stats.per_month.len(), STATS_MONTHS);
+        assert_eq!(stats.per_month[0].month, "2023-06");
+        let months: Vec<(&str, f64)> = stats.per_month[STATS_MONTHS - 2..]
+            .iter()
+            .map(|month| (month.month.as_str(), month.hours))
+            .collect();
+        assert_eq!(months, [("2024-04", 1.0), ("2024-05", 3.0)]);
+        assert_eq!(LibraryStats::compute(&[], 0, today).busiest_weekday, None);
+    }
+}
@@ -0,0 +1,420 @@
+//! Search queries typed by users
+//!
+//! `minutes search` takes words, "quoted phrases", prefixes such as `budg*`,
+//! parentheses and the operators AND, OR and NOT. [`SearchQuery::parse`]
+//! reads any input without failing: operators that have nothing to join are
+//! dropped, and so are groups whose every term is excluded with NOT, as
+//! nothing can be found by what it lacks alone. [`SearchQuery::to_fts`]
+//! writes the result as an FTS5 expression with every word quoted, so quotes,
+//! hyphens and other punctuation are never read as query syntax, and
+//! [`SearchQuery::like_condition`] writes the same query as LIKE patterns.
+
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+use crate::storage::models::{MATCH_END, MATCH_START};
+
+/// Parentheses nested deeper than this only separate words
+const MAX_GROUP_DEPTH: usize = 16;
+
+/// One part of a search query as typed
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// A word or phrase, matching words that start with it when `prefix`
+    Term {
+        text: String,
+        prefix: bool,
+    },
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+/// A part of a parsed query
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Term {
+        text: String,
+        prefix: bool,
+    },
+    /// Every included node matches and no excluded one does
+    All {
+        included: Vec<Node>,
+        excluded: Vec<Node>,
+    },
+    /// Any of the nodes matches
+    Any(Vec<Node>),
+}
+
+/// A parsed search query
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    root: Option<Node>,
+}
+
+impl SearchQuery {
+    /// Parse a query as typed on the command line
+    pub fn parse(input: &str) -> Self {
+        let mut tokens = Vec::new();
+        let mut word = String::new();
+        let mut depth = 0;
+        let mut chars = input.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    push_word(&mut tokens, &mut word);
+                    // An unterminated quote runs to the end of the input
+                    let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                    let mut prefix = false;
+                    while chars.next_if_eq(&'*').is_some() {
+                        prefix = true;
+                    }
+                    push_term(&mut tokens, &phrase, prefix);
+                }
+                '(' => {
+                    push_word(&mut tokens, &mut word);
+                    depth += 1;
+                    if depth <= MAX_GROUP_DEPTH {
+                        tokens.push(Token::Open);
+                    }
+                }
+                ')' => {
+                    push_word(&mut tokens, &mut word);
+                    // An unmatched closing parenthesis is left out
+                    if depth > 0 {
+                        if depth <= MAX_GROUP_DEPTH {
+                            tokens.push(Token::Close);
+                        }
+                        depth -= 1;
+                    }
+                }
+                c if c.is_whitespace() => push_word(&mut tokens, &mut word),
+                c => word.push(c),
+            }
+        }
+        push_word(&mut tokens, &mut word);
+
+        Self {
+            root: parse_any(&mut tokens.into_iter().peekable(), false),
+        }
+    }
+
+    /// Whether the query has no terms to search for
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// The query as an FTS5 MATCH expression
+    pub fn to_fts(&self) -> String {
+        self.root.as_ref().map(fts).unwrap_or_default()
+    }
+
+    /// The query as an SQL condition on `column`, with its LIKE patterns
+    ///
+    /// The patterns are numbered from `?{first}` and ignore ASCII case, so
+    /// the text is scanned when the full-text index cannot take the query.
+    /// An empty query matches nothing.
+    pub fn like_condition(&self, column: &str, first: usize) -> (String, Vec<String>) {
+        let mut patterns = Vec::new();
+        let condition = match &self.root {
+            Some(node) => like(node, column, first, &mut patterns),
+            None => "0".to_string(),
+        };
+        (condition, patterns)
+    }
+
+    /// `text` with the searched terms marked as matches, ignoring ASCII case
+    ///
+    /// Excluded terms are not marked.
+    pub fn highlight(&self, text: &str) -> String {
+        let mut terms = Vec::new();
+        if let Some(node) = &self.root {
+            included_terms(node, &mut terms);
+        }
+
+        let lower = text.to_ascii_lowercase();
+        let mut ranges = Vec::new();
+        for term in terms {
+            let term = term.to_ascii_lowercase();
+            ranges.extend(
+                lower
+                    .match_indices(&term)
+                    .map(|(start, _)| (start, start + term.len())),
+            );
+        }
+        ranges.sort_unstable();
+
+        let mut marked = String::new();
+        let mut done = 0;
+        for (start, end) in ranges {
+            if start < done {
+                continue;
+            }
+            marked.push_str(&text[done..start]);
+            marked.push(MATCH_START);
+            marked.push_str(&text[start..end]);
+            marked.push(MATCH_END);
+            done = end;
+        }
+        marked.push_str(&text[done..]);
+        marked
+    }
+}
+
+/// End the word being read, as an operator or a term
+fn push_word(tokens: &mut Vec<Token>, word: &mut String) {
+    match word.as_str() {
+        "AND" => tokens.push(Token::And),
+        "OR" => tokens.push(Token::Or),
+        "NOT" => tokens.push(Token::Not),
+        _ => {
+            let text = word.trim_end_matches('*');
+            push_term(tokens, text, text.len() < word.len());
+        }
+    }
+    word.clear();
+}
+
+fn push_term(tokens: &mut Vec<Token>, text: &str, prefix: bool) {
+    let text = text.trim();
+    if !text.is_empty() {
+        tokens.push(Token::Term {
+            text: text.to_string(),
+            prefix,
+        });
+    }
+}
+
+/// Alternatives joined by OR, up to the end of the enclosing group
+fn parse_any(tokens: &mut Peekable<IntoIter<Token>>, nested: bool) -> Option<Node> {
+    let mut alternatives = Vec::new();
+    loop {
+        alternatives.extend(parse_all(tokens, nested));
+        if tokens.next_if_eq(&Token::Or).is_none() {
+            break;
+        }
+    }
+    match alternatives.len() {
+        0 | 1 => alternatives.pop(),
+        _ => Some(Node::Any(alternatives)),
+    }
+}
+
+/// Terms and groups that all have to match, up to the next OR
+///
+/// NOT excludes the term or group after it; "AND NOT" means NOT.
+fn parse_all(tokens: &mut Peekable<IntoIter<Token>>, nested: bool) -> Option<Node> {
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    let mut negated = false;
+    while let Some(token) = tokens.next_if(|token| match token {
+        Token::Or => false,
+        Token::Close => !nested,
+        _ => true,
+    }) {
+        let node = match token {
+            Token::Term { text, prefix } => Node::Term { text, prefix },
+            Token::Open => {
+                let group = parse_any(tokens, true);
+                tokens.next_if_eq(&Token::Close);
+                match group {
+                    Some(group) => group,
+                    None => {
+                        negated = false;
+                        continue;
+                    }
+                }
+            }
+            Token::Not => {
+                negated = true;
+                continue;
+            }
+            Token::And | Token::Or | Token::Close => continue,
+        };
+        if std::mem::take(&mut negated) {
+            excluded.push(node);
+        } else {
+            included.push(node);
+        }
+    }
+
+    match included.len() {
+        // Excluded terms alone give nothing to search for
+        0 => None,
+        1 if excluded.is_empty() => included.pop(),
+        _ => Some(Node::All { included, excluded }),
+    }
+}
+
+fn fts(node: &Node) -> String {
+    match node {
+        Node::Term { text, prefix } => format!(
+            "\"{}\"{}",
+            text.replace('"', "\"\""),
+            if *prefix { "*" } else { "" }
+        ),
+        Node::All { included, excluded } => {
+            let mut expression = included
+                .iter()
+                .map(fts_operand)
+                .collect::<Vec<_>>()
+                .join(" ");
+            for node in excluded {
+                expression.push_str(" NOT ");
+                expression.push_str(&fts_operand(node));
+            }
+            expression
+        }
+        Node::Any(alternatives) => alternatives
+            .iter()
+            .map(fts_operand)
+            .collect::<Vec<_>>()
+            .join(" OR "),
+    }
+}
+
+/// A node inside another, in parentheses unless it is a single term
+fn fts_operand(node: &Node) -> String {
+    match node {
+        Node::Term { .. } => fts(node),
+        _ => format!("({})", fts(node)),
+    }
+}
+
+fn like(node: &Node, column: &str, first: usize, patterns: &mut Vec<String>) -> String {
+    match node {
+        Node::Term { text, .. } => {
+            let escaped = text
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            patterns.push(format!("%{}%", escaped));
+            format!(
+                "{} LIKE ?{} ESCAPE '\\'",
+                column,
+                first + patterns.len() - 1
+            )
+        }
+        Node::All { included, excluded } => {
+            let mut conditions: Vec<String> = included
+                .iter()
+                .map(|node| like(node, column, first, patterns))
+                .collect();
+            for node in excluded {
+                conditions.push(format!("NOT ({})", like(node, column, first, patterns)));
+            }
+            format!("({})", conditions.join(" AND "))
+        }
+        Node::Any(alternatives) => {
+            let conditions: Vec<String> = alternatives
+                .iter()
+                .map(|node| like(node, column, first, patterns))
+                .collect();
+            format!("({})", conditions.join(" OR "))
+        }
+    }
+}
+
+/// Terms a match may contain, leaving out excluded ones
+fn included_terms<'a>(node: &'a Node, terms: &mut Vec<&'a str>) {
+    match node {
+        Node::Term { text, .. } => terms.push(text),
+        Node::All { included, .. } => {
+            for node in included {
+                included_terms(node, terms);
+            }
+        }
+        Node::Any(alternatives) => {
+            for node in alternatives {
+                included_terms(node, terms);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_valid_fts_for_any_input() {
+        let cases = [
+            ("budget review", "\"budget\" \"review\""),
+            ("budg*", "\"budg\"*"),
+            ("\"next quarter\" OR Q3", "\"next quarter\" OR \"Q3\""),
+            ("follow-up", "\"follow-up\""),
+            ("roadmap AND NOT hiring", "\"roadmap\" NOT \"hiring\""),
+            ("AND OR budget NOT", "\"budget\""),
+            ("say \"hi", "\"say\" \"hi\""),
+            ("it's (done)", "\"it's\" \"done\""),
+            ("\"a \"\"quoted\"\" word\"", "\"a\" \"quoted\" \"word\""),
+            ("** \"\" ()", ""),
+        ];
+        for (input, fts) in cases {
+            assert_eq!(SearchQuery::parse(input).to_fts(), fts, "{}", input);
+        }
+        assert!(SearchQuery::parse("  OR ").is_empty());
+    }
+
+    #[test]
+    fn leading_not_excludes_instead_of_searching() {
+        let cases = [
+            ("NOT hiring roadmap", "\"roadmap\" NOT \"hiring\""),
+            ("budget OR NOT hiring", "\"budget\""),
+            ("(NOT hiring) roadmap", "\"roadmap\""),
+        ];
+        for (input, fts) in cases {
+            assert_eq!(SearchQuery::parse(input).to_fts(), fts, "{}", input);
+        }
+        assert!(SearchQuery::parse("NOT hiring").is_empty());
+        assert!(SearchQuery::parse("NOT hiring NOT budget").is_empty());
+    }
+
+    #[test]
+    fn keeps_grouping_in_parentheses() {
+        let cases = [
+            (
+                "(budget OR hiring) plan",
+                "(\"budget\" OR \"hiring\") \"plan\"",
+            ),
+            (
+                "budget (hiring OR plan",
+                "\"budget\" (\"hiring\" OR \"plan\")",
+            ),
+            (
+                "roadmap NOT (hiring OR budget)",
+                "\"roadmap\" NOT (\"hiring\" OR \"budget\")",
+            ),
+            ("a b OR c", "(\"a\" \"b\") OR \"c\""),
+            ("a) OR (b", "\"a\" OR \"b\""),
+            ("((((a))))", "\"a\""),
+        ];
+        for (input, fts) in cases {
+            assert_eq!(SearchQuery::parse(input).to_fts(), fts, "{}", input);
+        }
+        let deep = format!("{}a{}", "(".repeat(1000), ")".repeat(1000));
+        assert_eq!(SearchQuery::parse(&deep).to_fts(), "\"a\"");
+    }
+
+    #[test]
+    fn like_condition_keeps_alternatives_and_exclusions() {
+        let query = SearchQuery::parse("roadmap NOT hiring (\"next quarter\" OR q3*)");
+        let (condition, patterns) = query.like_condition("t", 2);
+        assert_eq!(
+            condition,
+            "(t LIKE ?2 ESCAPE '\\' AND (t LIKE ?3 ESCAPE '\\' OR t LIKE ?4 ESCAPE '\\') \
+             AND NOT (t LIKE ?5 ESCAPE '\\'))"
+        );
+        assert_eq!(
+            patterns,
+            ["%roadmap%", "%next quarter%", "%q3%", "%hiring%"]
+        );
+        assert_eq!(
+            query.highlight("Roadmap for the next quarter, not hiring"),
+            "\u{2}Roadmap\u{3} for the \u{2}next quarter\u{3}, not hiring"
+        );
+        assert_eq!(SearchQuery::default().like_condition("t", 2).0, "0");
+    }
+}
@@ -5,18 +5,24 @@
 use anyhow::Result;
 
 use crate::config::Settings;
-use crate::storage::{Database, Recording, RecordingState, TranscriptSegment};
+use crate::storage::{
+    snapshot, Database, Recording, RecordingFilter, RecordingState, SearchHit, TranscriptSegment,
+};
 
 /// Repository for managing recordings and transcripts
 pub struct Repository {
     db: Database,
+    settings: Settings,
 }
 
 impl Repository {
     /// Create a new repository
     pub fn new(settings: &Settings) -> Result<Self> {
         let db = Database::open(settings)?;
-        Ok(Self { db })
+        Ok(Self {
+            db,
+            settings: settings.clone(),
+        })
     }
 
     /// Create a new recording
@@ -67,7 +73,7 @@ impl Repository {
 
     /// List recent recordings
     pub fn list_recent(&self, limit: usize) -> Result<Vec<Recording>> {
-        self.db.list_recordings(limit)
+        self.db.list_recordings(&RecordingFilter::default(), limit)
     }
 
     /// Get recordings pending transcription
@@ -81,12 +87,14 @@ impl Repository {
     }
 
     /// Search transcripts
-    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<(Recording, TranscriptSegment)>> {
-        self.db.search_transcripts(query, limit)
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        self.db
+            .search_transcripts(query, &RecordingFilter::default(), limit)
     }
 
-    /// Delete a recording
+    /// Delete a recording, keeping a snapshot that `minutes undo` can restore
     pub fn delete(&self, id: &str) -> Result<()> {
+        snapshot::take(&self.settings, &self.db, "delete", &[id])?;
         self.db.delete_recording(id)
     }
 }
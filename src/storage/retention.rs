@@ -0,0 +1,357 @@
+//! Retention policy for old recordings
+//!
+//! With `storage.retention_days` set, recordings older than that many days
+//! lose their audio. With `storage.retention_max_gb` set, the oldest
+//! recordings lose theirs while the audio directory holds more than the
+//! limit. Only transcribed recordings are touched, and their transcripts,
+//! summaries and notes stay unless `storage.keep_transcripts` is off, in
+//! which case they are deleted outright. With `storage.archive_dir` the
+//! audio is moved there instead of deleted and the recordings are marked
+//! archived, as by `minutes archive`. The daemon applies the policy
+//! hourly; `minutes purge --dry-run` lists what it would remove.
+
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::config::Settings;
+use crate::storage::{snapshot, trash, Database, Recording, RecordingState};
+
+/// Why a recording's audio is due for removal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// Older than `storage.retention_days`
+    Age,
+    /// Among the oldest while the audio is over `storage.retention_max_gb`
+    Size,
+}
+
+/// A recording whose audio the retention policy removes
+#[derive(Debug, Clone)]
+pub struct Expired {
+    pub recording: Recording,
+    /// Audio files of the recording in the audio directory
+    pub files: Vec<PathBuf>,
+    pub bytes: u64,
+    pub reason: Reason,
+}
+
+/// What applying the policy removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionSummary {
+    pub recordings: usize,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Whether `storage.retention_days` or `storage.retention_max_gb` is set
+pub fn is_enabled(settings: &Settings) -> bool {
+    settings.storage.retention_days > 0 || settings.storage.retention_max_gb > 0.0
+}
+
+/// Recordings the policy removes audio from, oldest first
+///
+/// Audio outside the audio directory, such as already archived files, is
+/// neither removed nor counted toward `storage.retention_max_gb`.
+pub fn plan(settings: &Settings, db: &Database) -> Result<Vec<Expired>> {
+    if !is_enabled(settings) {
+        return Ok(Vec::new());
+    }
+    let storage = &settings.storage;
+    // A limit reaching past the earliest representable date expires nothing
+    let cutoff = (storage.retention_days > 0)
+        .then(|| Duration::try_days(storage.retention_days.into()))
+        .flatten()
+        .and_then(|age| Utc::now().checked_sub_signed(age));
+    let budget = (storage.retention_max_gb > 0.0)
+        .then_some((storage.retention_max_gb * 1_000_000_000.0) as u64);
+
+    let audio_dir = settings.audio_dir();
+    let mut chunks = db.all_chunks()?;
+    let mut total = 0;
+    let mut candidates = Vec::new();
+    for recording in db.list_recordings_since(None)? {
+        let mut files: Vec<PathBuf> = recording
+            .audio_files()
+            .map(str::to_string)
+            .chain(chunks.remove(&recording.id).unwrap_or_default())
+            .map(PathBuf::from)
+            .filter(|file| file.starts_with(&audio_dir) && file.exists())
+            .collect();
+        // An aligned recording can list its main file again as the mic track
+        files.sort();
+        files.dedup();
+        let bytes: u64 = files
+            .iter()
+            .filter_map(|file| file.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum();
+
+        total += bytes;
+        if recording.state == RecordingState::Completed && !files.is_empty() {
+            candidates.push((recording, files, bytes));
+        }
+    }
+
+    let mut expired = Vec::new();
+    for (recording, files, bytes) in candidates {
+        let reason = if cutoff.is_some_and(|cutoff| recording.created_at < cutoff) {
+            Reason::Age
+        } else if budget.is_some_and(|budget| total > budget) {
+            Reason::Size
+        } else {
+            continue;
+        };
+        total -= bytes;
+        expired.push(Expired {
+            recording,
+            files,
+            bytes,
+            reason,
+        });
+    }
+    Ok(expired)
+}
+
+/// Remove or archive the audio of `expired`, taking a snapshot first
+///
+/// The snapshot holds rows only: copying the audio aside would keep the
+/// space the policy frees in use.
+pub fn apply(settings: &Settings, db: &Database, expired: &[Expired]) -> Result<RetentionSummary> {
+    if expired.is_empty() {
+        return Ok(RetentionSummary::default());
+    }
+    let storage = &settings.storage;
+    let archive_dir = storage.archive_dir.trim();
+    let archive_dir = (!archive_dir.is_empty()).then(|| PathBuf::from(archive_dir));
+    if let Some(dir) = &archive_dir {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    let mut summary = RetentionSummary::default();
+    let ids: Vec<&str> = expired.iter().map(|e| e.recording.id.as_str()).collect();
+    snapshot::take_rows(settings, db, "retention", &ids)?;
+    if !storage.keep_transcripts {
+        let recordings: Vec<Recording> = expired.iter().map(|e| e.recording.clone()).collect();
+        // Archived audio is moved below instead
+        let removed = trash::remove(db, &recordings, archive_dir.is_some())?;
+        if archive_dir.is_none() {
+            return Ok(RetentionSummary {
+                recordings: recordings.len(),
+                files: removed,
+                bytes: expired.iter().map(|e| e.bytes).sum(),
+            });
+        }
+    }
+
+    for entry in expired {
+        let mut gone = HashSet::new();
+        for file in &entry.files {
+            let result = match &archive_dir {
+                Some(dir) => archive_file(db, file, dir),
+                None => std::fs::remove_file(file).map_err(Into::into),
+            };
+            match result {
+                Ok(()) => {
+                    gone.insert(file.to_string_lossy().to_string());
+                    summary.files += 1;
+                }
+                Err(e) => tracing::warn!("Failed to move or remove {}: {:#}", file.display(), e),
+            }
+        }
+        match &archive_dir {
+            Some(_) if !gone.is_empty() => db.set_recording_archived(&entry.recording.id, true)?,
+            Some(_) => {}
+            None => forget_audio(db, &entry.recording.id, &gone)?,
+        }
+        summary.recordings += 1;
+        summary.bytes += entry.bytes;
+    }
+    Ok(summary)
+}
+
+/// Apply the policy, logging what was removed
+pub fn purge_expired(settings: &Settings, db: &Database) -> Result<RetentionSummary> {
+    let summary = apply(settings, db, &plan(settings, db)?)?;
+    if summary.recordings > 0 {
+        tracing::info!(
+            "Retention policy removed {} audio file(s) ({} MB) of {} recording(s)",
+            summary.files,
+            summary.bytes / 1_000_000,
+            summary.recordings
+        );
+    }
+    Ok(summary)
+}
+
+/// Move `file` into `dir` and point the recordings using it there
+///
+/// Marking the recordings archived is left to the caller.
+pub fn archive_file(db: &Database, file: &Path, dir: &Path) -> Result<()> {
+    let Some(name) = file.file_name() else {
+        return Ok(());
+    };
+    let target = dir.join(name);
+    if std::fs::rename(file, &target).is_err() {
+        // The archive can be on another file system
+        std::fs::copy(file, &target)
+            .with_context(|| format!("Failed to copy to {}", target.display()))?;
+        std::fs::remove_file(file)?;
+    }
+    db.relocate_audio_file(&file.to_string_lossy(), &target.to_string_lossy())?;
+    Ok(())
+}
+
+/// Drop the paths in `gone` from a recording and its chunk list
+fn forget_audio(db: &Database, id: &str, gone: &HashSet<String>) -> Result<()> {
+    let Some(mut recording) = db.get_recording(id)? else {
+        return Ok(());
+    };
+    for path in [
+        &mut recording.audio_path,
+        &mut recording.system_audio_path,
+        &mut recording.mic_audio_path,
+    ] {
+        if path.as_ref().is_some_and(|p| gone.contains(p)) {
+            *path = None;
+        }
+    }
+    db.update_recording(&recording)?;
+
+    let chunks = db.get_chunks(id)?;
+    let kept: Vec<String> = chunks
+        .iter()
+        .filter(|chunk| !gone.contains(*chunk))
+        .cloned()
+        .collect();
+    if kept.len() < chunks.len() {
+        db.set_chunks(id, &kept)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::TranscriptSegment;
+
+    #[test]
+    fn removes_audio_past_the_age_and_size_limits_keeping_transcripts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings.general.data_dir = tmp.path().to_path_buf();
+        settings.storage.retention_days = 30;
+        settings.storage.retention_max_gb = 16.0 / 1_000_000_000.0;
+        settings.general.snapshot_audio = true;
+        let db = Database::open(&settings).unwrap();
+        std::fs::create_dir_all(settings.audio_dir()).unwrap();
+
+        let mut ids = Vec::new();
+        for (days, state) in [
+            (100, RecordingState::Completed),
+            (10, RecordingState::Completed),
+            (5, RecordingState::Pending),
+            (1, RecordingState::Completed),
+        ] {
+            let mut recording = Recording::new(format!("{} days ago", days));
+            let audio = settings.audio_dir().join(format!("{}.ogg", recording.id));
+            std::fs::write(&audio, b"8 bytes!").unwrap();
+            recording.audio_path = Some(audio.to_string_lossy().to_string());
+            recording.created_at = Utc::now() - Duration::days(days);
+            recording.state = state;
+            db.insert_recording(&recording).unwrap();
+            db.insert_segment(&TranscriptSegment::new(
+                recording.id.clone(),
+                0.0,
+                1.0,
+                "kept".to_string(),
+            ))
+            .unwrap();
+            ids.push(recording.id);
+        }
+
+        let expired = plan(&settings, &db).unwrap();
+        let planned: Vec<(&str, Reason)> = expired
+            .iter()
+            .map(|e| (e.recording.id.as_str(), e.reason))
+            .collect();
+        assert_eq!(
+            planned,
+            [
+                (ids[0].as_str(), Reason::Age),
+                (ids[1].as_str(), Reason::Size)
+            ]
+        );
+
+        let summary = apply(&settings, &db, &expired).unwrap();
+        assert_eq!(
+            summary,
+            RetentionSummary {
+                recordings: 2,
+                files: 2,
+                bytes: 16,
+            }
+        );
+        for e in &expired {
+            assert!(!e.files[0].exists());
+            let recording = db.get_recording(&e.recording.id).unwrap().unwrap();
+            assert!(recording.audio_path.is_none());
+            assert_eq!(db.get_transcript_segments(&recording.id).unwrap().len(), 1);
+        }
+        assert!(plan(&settings, &db).unwrap().is_empty());
+        // The snapshot holds no copy of the removed audio
+        assert!(!settings.snapshots_dir().exists());
+    }
+
+    #[test]
+    fn huge_retention_days_expire_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings.general.data_dir = tmp.path().to_path_buf();
+        settings.storage.retention_days = u32::MAX;
+        let db = Database::open(&settings).unwrap();
+        std::fs::create_dir_all(settings.audio_dir()).unwrap();
+
+        let mut recording = Recording::new("Old".to_string());
+        let audio = settings.audio_dir().join(format!("{}.ogg", recording.id));
+        std::fs::write(&audio, b"8 bytes!").unwrap();
+        recording.audio_path = Some(audio.to_string_lossy().to_string());
+        recording.created_at = Utc::now() - Duration::days(1000);
+        recording.state = RecordingState::Completed;
+        db.insert_recording(&recording).unwrap();
+
+        assert!(plan(&settings, &db).unwrap().is_empty());
+    }
+
+    #[test]
+    fn archived_audio_is_found_in_the_archive() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db = Database::open_path(&tmp.path().join("minutes.db")).unwrap();
+        let audio = tmp.path().join("standup.ogg");
+        std::fs::write(&audio, b"audio").unwrap();
+        let mut recording = Recording::new("Standup".to_string());
+        recording.audio_path = Some(audio.to_string_lossy().to_string());
+        db.insert_recording(&recording).unwrap();
+
+        let archive = tmp.path().join("nas");
+        std::fs::create_dir_all(&archive).unwrap();
+        archive_file(&db, &audio, &archive).unwrap();
+        db.set_recording_archived(&recording.id, true).unwrap();
+
+        let moved = archive.join("standup.ogg");
+        assert!(!audio.exists());
+        assert_eq!(std::fs::read(&moved).unwrap(), b"audio");
+        let archived = db.get_recording(&recording.id).unwrap().unwrap();
+        assert!(archived.archived_at.is_some());
+        assert_eq!(
+            archived.audio_path.as_deref(),
+            Some(moved.to_string_lossy().as_ref())
+        );
+
+        std::fs::remove_dir_all(&archive).unwrap();
+        assert!(archived.missing_audio_message().contains("is archived in"));
+        assert!(!recording.missing_audio_message().contains("archived"));
+    }
+}
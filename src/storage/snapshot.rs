@@ -0,0 +1,191 @@
+//! Safety snapshots taken before destructive operations
+//!
+//! Operations that delete or overwrite recording data first save the
+//! affected rows, and with `general.snapshot_audio` copies of the audio
+//! files. `minutes undo` puts the newest snapshot back. Only the newest
+//! `general.max_snapshots` snapshots are kept.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::config::Settings;
+use crate::storage::{Database, RecordingSnapshot, Snapshot};
+
+/// Snapshot `recording_ids` before `operation` changes or removes them
+pub fn take(
+    settings: &Settings,
+    db: &Database,
+    operation: &str,
+    recording_ids: &[&str],
+) -> Result<i64> {
+    save(
+        settings,
+        db,
+        operation,
+        recording_ids,
+        settings.general.snapshot_audio,
+    )
+}
+
+/// Snapshot only the rows of `recording_ids`, whatever `general.snapshot_audio`
+///
+/// For operations that remove audio to free space, which a copy would undo.
+pub fn take_rows(
+    settings: &Settings,
+    db: &Database,
+    operation: &str,
+    recording_ids: &[&str],
+) -> Result<i64> {
+    save(settings, db, operation, recording_ids, false)
+}
+
+fn save(
+    settings: &Settings,
+    db: &Database,
+    operation: &str,
+    recording_ids: &[&str],
+    audio: bool,
+) -> Result<i64> {
+    let id = db.create_snapshot(operation, recording_ids)?;
+
+    if audio {
+        let dir = settings.snapshots_dir().join(id.to_string());
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        for recording_id in recording_ids {
+            copy_audio(db, recording_id, &dir)?;
+        }
+        db.set_snapshot_audio_dir(id, &dir.to_string_lossy())?;
+    }
+
+    for dir in db.prune_snapshots(settings.general.max_snapshots)? {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    tracing::info!("Saved snapshot {} before {}", id, operation);
+    Ok(id)
+}
+
+/// Revert the newest snapshot that has not been undone yet
+///
+/// Returns `None` when there is nothing to undo.
+pub fn undo_latest(db: &Database) -> Result<Option<Snapshot>> {
+    let Some(mut snapshot) = db.list_snapshots(1)?.pop() else {
+        return Ok(None);
+    };
+
+    for saved in &mut snapshot.recordings {
+        if let Some(dir) = &snapshot.audio_dir {
+            restore_audio(saved, Path::new(dir))?;
+        }
+        keep_current_audio(db, saved)?;
+    }
+
+    db.restore_snapshot(&snapshot)?;
+    Ok(Some(snapshot))
+}
+
+fn copy_audio(db: &Database, recording_id: &str, dir: &Path) -> Result<()> {
+    let Some(recording) = db.get_recording(recording_id)? else {
+        return Ok(());
+    };
+    let chunks = db.get_chunks(recording_id)?;
+
+    for file in recording
+        .audio_files()
+        .chain(chunks.iter().map(String::as_str))
+        .map(Path::new)
+        .filter(|file| file.exists())
+    {
+        let Some(name) = file.file_name() else {
+            continue;
+        };
+        std::fs::copy(file, dir.join(name))
+            .with_context(|| format!("Failed to back up {}", file.display()))?;
+    }
+    Ok(())
+}
+
+/// Copy backed-up audio back to wherever the original file is missing
+fn restore_audio(saved: &RecordingSnapshot, dir: &Path) -> Result<()> {
+    for file in saved
+        .recording
+        .audio_files()
+        .chain(saved.chunks.iter().map(String::as_str))
+        .map(Path::new)
+        .filter(|file| !file.exists())
+    {
+        let Some(backup) = file.file_name().map(|name| dir.join(name)) else {
+            continue;
+        };
+        if backup.exists() {
+            std::fs::copy(&backup, file)
+                .with_context(|| format!("Failed to restore {}", file.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Keep the recording's current audio where the saved files no longer exist
+///
+/// Transcription compresses audio after the snapshot is taken, so without an
+/// audio backup the saved paths can point at files that were replaced.
+fn keep_current_audio(db: &Database, saved: &mut RecordingSnapshot) -> Result<()> {
+    let Some(current) = db.get_recording(&saved.recording.id)? else {
+        return Ok(());
+    };
+
+    let missing = |path: &Option<String>| path.as_deref().is_some_and(|p| !Path::new(p).exists());
+    let recording = &mut saved.recording;
+    if missing(&recording.audio_path) {
+        recording.audio_path = current.audio_path;
+    }
+    if missing(&recording.system_audio_path) {
+        recording.system_audio_path = current.system_audio_path;
+    }
+    if missing(&recording.mic_audio_path) {
+        recording.mic_audio_path = current.mic_audio_path;
+    }
+    if saved.chunks.iter().any(|chunk| !Path::new(chunk).exists()) {
+        saved.chunks = db.get_chunks(&saved.recording.id)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{Recording, TranscriptSegment};
+
+    #[test]
+    fn undo_restores_a_deleted_recording_and_its_audio() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings.general.data_dir = tmp.path().to_path_buf();
+        settings.general.snapshot_audio = true;
+        let db = Database::open_path(&tmp.path().join("minutes.db")).unwrap();
+
+        let audio = tmp.path().join("standup.ogg");
+        std::fs::write(&audio, b"audio").unwrap();
+        let mut recording = Recording::new("Standup".to_string());
+        recording.audio_path = Some(audio.to_string_lossy().to_string());
+        db.insert_recording(&recording).unwrap();
+        db.insert_segment(&TranscriptSegment::new(
+            recording.id.clone(),
+            0.0,
+            1.0,
+            "hello".to_string(),
+        ))
+        .unwrap();
+
+        take(&settings, &db, "delete", &[&recording.id]).unwrap();
+        db.delete_recording(&recording.id).unwrap();
+        std::fs::remove_file(&audio).unwrap();
+
+        let snapshot = undo_latest(&db).unwrap().unwrap();
+        assert_eq!(snapshot.operation, "delete");
+        assert_eq!(db.get_transcript_segments(&recording.id).unwrap().len(), 1);
+        assert_eq!(std::fs::read(&audio).unwrap(), b"audio");
+        assert!(undo_latest(&db).unwrap().is_none());
+    }
+}
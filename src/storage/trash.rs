@@ -0,0 +1,127 @@
+//! Trash for deleted recordings
+//!
+//! `minutes delete` only moves a recording to the trash: it drops out of
+//! lists, search and the transcription queue, but its rows and audio files
+//! stay until the trash is emptied or the recording has been in it for
+//! `general.trash_days` days. Purging takes a snapshot first like other
+//! destructive operations.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::path::Path;
+
+use crate::config::Settings;
+use crate::storage::{snapshot, Database, Recording};
+
+/// Delete `recordings` for good and, unless `keep_audio`, their audio files
+///
+/// Returns the number of audio files removed. Files that cannot be removed
+/// are logged and skipped.
+pub fn purge(
+    settings: &Settings,
+    db: &Database,
+    recordings: &[Recording],
+    keep_audio: bool,
+) -> Result<usize> {
+    if recordings.is_empty() {
+        return Ok(0);
+    }
+
+    let ids: Vec<&str> = recordings.iter().map(|r| r.id.as_str()).collect();
+    snapshot::take(settings, db, "delete", &ids)?;
+    remove(db, recordings, keep_audio)
+}
+
+/// Delete `recordings` like [`purge`], leaving the snapshot to the caller
+pub(crate) fn remove(db: &Database, recordings: &[Recording], keep_audio: bool) -> Result<usize> {
+    let mut audio_files = Vec::new();
+    for recording in recordings {
+        audio_files.extend(recording.audio_files().map(str::to_string));
+        audio_files.extend(db.get_chunks(&recording.id)?);
+    }
+    // An aligned recording can list its main file again as the mic track
+    audio_files.sort();
+    audio_files.dedup();
+
+    for recording in recordings {
+        db.delete_recording(&recording.id)?;
+    }
+
+    let mut removed = 0;
+    if !keep_audio {
+        for file in audio_files.iter().filter(|file| Path::new(file).exists()) {
+            match std::fs::remove_file(file) {
+                Ok(()) => removed += 1,
+                Err(e) => tracing::warn!("Failed to remove {}: {}", file, e),
+            }
+        }
+    }
+    Ok(removed)
+}
+
+/// Purge recordings that have been in the trash for `general.trash_days`
+///
+/// Returns the number of recordings purged; none when `trash_days` is 0.
+pub fn purge_expired(settings: &Settings, db: &Database) -> Result<usize> {
+    let days = settings.general.trash_days;
+    if days == 0 {
+        return Ok(0);
+    }
+
+    let expired = db.list_trashed_recordings(Some(Utc::now() - Duration::days(days.into())))?;
+    purge(settings, db, &expired, false)?;
+    if !expired.is_empty() {
+        tracing::info!(
+            "Purged {} recording(s) in the trash for over {} days",
+            expired.len(),
+            days
+        );
+    }
+    Ok(expired.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::RecordingFilter;
+
+    #[test]
+    fn trashed_recordings_are_hidden_until_restored_or_purged() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings.general.data_dir = tmp.path().to_path_buf();
+        let db = Database::open_path(&tmp.path().join("minutes.db")).unwrap();
+
+        let audio = tmp.path().join("standup.ogg");
+        std::fs::write(&audio, b"audio").unwrap();
+        let mut recording = Recording::new("Standup".to_string());
+        recording.audio_path = Some(audio.to_string_lossy().to_string());
+        db.insert_recording(&recording).unwrap();
+
+        db.trash_recording(&recording.id).unwrap();
+        assert!(db
+            .list_recordings(&RecordingFilter::default(), 10)
+            .unwrap()
+            .is_empty());
+        assert!(db
+            .find_recording_by_prefix(&recording.id)
+            .unwrap()
+            .is_none());
+        assert_eq!(purge_expired(&settings, &db).unwrap(), 0);
+
+        db.restore_trashed_recording(&recording.id).unwrap();
+        assert_eq!(
+            db.list_recordings(&RecordingFilter::default(), 10)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        db.trash_recording(&recording.id).unwrap();
+        let trashed = db.list_trashed_recordings(None).unwrap();
+        assert!(trashed[0].deleted_at.is_some());
+        assert_eq!(purge(&settings, &db, &trashed, false).unwrap(), 1);
+        assert!(!audio.exists());
+        assert!(db.get_recording(&recording.id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,92 @@
+//! Nearest-neighbour search over segment embeddings
+//!
+//! Built with the `sqlite-vec` feature, the sqlite-vec extension is loaded
+//! into every connection and the database ranks vectors itself with
+//! `vec_distance_cosine`. Without it the vectors of a model are read and
+//! compared here one by one, which is fast enough for the thousands of
+//! segments a year of meetings produces.
+
+/// Cosine similarity in [-1, 1], 0 for vectors of different or zero length
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+/// IDs of the `limit` vectors most similar to `query`, best first, with their scores
+#[cfg_attr(feature = "sqlite-vec", allow(dead_code))]
+pub fn rank_by_similarity(
+    query: &[f32],
+    candidates: &[(i64, Vec<f32>)],
+    limit: usize,
+) -> Vec<(i64, f32)> {
+    let mut scored: Vec<(i64, f32)> = candidates
+        .iter()
+        .map(|(id, vector)| (*id, cosine_similarity(query, vector)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(limit);
+    scored
+}
+
+/// Vector as stored in the database: little-endian `f32`s, the layout
+/// sqlite-vec reads as well
+pub fn to_blob(vector: &[f32]) -> Vec<u8> {
+    vector
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+/// Vector from its stored form
+pub fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+/// Load sqlite-vec into every connection opened from now on
+#[cfg(feature = "sqlite-vec")]
+pub fn register() {
+    static REGISTER: std::sync::Once = std::sync::Once::new();
+    REGISTER.call_once(|| {
+        // SAFETY: sqlite3_vec_init has the signature of an SQLite extension
+        // entry point, which is what sqlite3_auto_extension expects
+        unsafe {
+            rusqlite::ffi::sqlite3_auto_extension(Some(std::mem::transmute(
+                sqlite_vec::sqlite3_vec_init as *const (),
+            )));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_closest_vectors_first() {
+        let candidates = vec![
+            (1, vec![0.0, 1.0]),
+            (2, vec![1.0, 0.1]),
+            (3, vec![-1.0, 0.0]),
+            (4, vec![0.0, 0.0]),
+        ];
+
+        let ranked = rank_by_similarity(&[2.0, 0.0], &candidates, 3);
+
+        let ids: Vec<i64> = ranked.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, [2, 1, 4]);
+        assert!(ranked[0].1 > 0.99);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(from_blob(&to_blob(&[0.25, -2.0])), [0.25, -2.0]);
+    }
+}
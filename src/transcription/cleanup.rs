@@ -0,0 +1,250 @@
+//! Text cleanup of transcribed segments
+//!
+//! Raw ASR output carries markers like "[BLANK_AUDIO]", stutters such as
+//! "the the", and sentences that start in lowercase where a window cut
+//! them. Cleanup removes filler tokens, drops immediately repeated words and
+//! short phrases, and capitalizes sentence starts and the pronoun "I", so
+//! stored transcripts read like prose.
+
+use crate::config::Settings;
+use crate::storage::{TranscriptSegment, TranscriptWord};
+
+/// Longest phrase, in words, checked for an immediate repeat
+const MAX_REPEATED_PHRASE_WORDS: usize = 4;
+
+/// Normalization stage run on segments before they are stored
+#[derive(Debug, Clone)]
+pub struct TextCleanup {
+    /// Filler tokens as normalized word sequences
+    fillers: Vec<Vec<String>>,
+}
+
+impl TextCleanup {
+    /// Cleanup configured by `whisper.filler_tokens`, if enabled
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        settings
+            .whisper
+            .clean_up_text
+            .then(|| Self::new(&settings.whisper.filler_tokens))
+    }
+
+    pub fn new(fillers: &[String]) -> Self {
+        Self {
+            fillers: fillers
+                .iter()
+                .map(|filler| filler.split_whitespace().map(str::to_lowercase).collect())
+                .filter(|filler: &Vec<String>| !filler.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Clean up the text of each segment, dropping segments left empty
+    pub fn apply(&self, segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
+        let mut cleaned: Vec<TranscriptSegment> = Vec::with_capacity(segments.len());
+
+        for mut segment in segments {
+            let mut tokens: Vec<Token> = segment
+                .text
+                .split_whitespace()
+                .enumerate()
+                .map(|(index, text)| Token {
+                    index,
+                    text: text.to_string(),
+                })
+                .collect();
+            let aligned = tokens.len() == segment.words.len();
+
+            self.remove_fillers(&mut tokens);
+            remove_repeats(&mut tokens);
+            if tokens.is_empty() {
+                continue;
+            }
+
+            let sentence_start = cleaned
+                .last()
+                .is_none_or(|previous| ends_sentence(&previous.text));
+            fix_casing(&mut tokens, sentence_start);
+
+            if aligned {
+                let mut words: Vec<Option<TranscriptWord>> =
+                    segment.words.drain(..).map(Some).collect();
+                segment.words = tokens
+                    .iter()
+                    .filter_map(|token| {
+                        let mut word = words[token.index].take()?;
+                        word.text = token.text.clone();
+                        Some(word)
+                    })
+                    .collect();
+            }
+            segment.text = tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            cleaned.push(segment);
+        }
+
+        cleaned
+    }
+
+    /// Drop every run of tokens that spells out a filler
+    fn remove_fillers(&self, tokens: &mut Vec<Token>) {
+        let mut i = 0;
+        while i < tokens.len() {
+            let filler = self.fillers.iter().find(|filler| {
+                tokens.len() - i >= filler.len()
+                    && tokens[i..i + filler.len()]
+                        .iter()
+                        .zip(filler.iter())
+                        .all(|(token, word)| token.text.to_lowercase() == *word)
+            });
+            match filler {
+                Some(filler) => {
+                    tokens.drain(i..i + filler.len());
+                }
+                None => i += 1,
+            }
+        }
+    }
+}
+
+/// A word of the segment text and its position in the original text
+#[derive(Debug, Clone)]
+struct Token {
+    index: usize,
+    text: String,
+}
+
+/// Drop the first copy of words or short phrases said twice in a row
+///
+/// The second copy is kept, since it carries the punctuation of the
+/// sentence that continues after it.
+fn remove_repeats(tokens: &mut Vec<Token>) {
+    let mut i = 0;
+    while i < tokens.len() {
+        let repeated = (1..=MAX_REPEATED_PHRASE_WORDS).find(|&len| {
+            i + 2 * len <= tokens.len()
+                && (0..len).all(|k| {
+                    let word = normalize(&tokens[i + k].text);
+                    !word.is_empty() && word == normalize(&tokens[i + len + k].text)
+                })
+        });
+        match repeated {
+            Some(len) => {
+                tokens.drain(i..i + len);
+            }
+            None => i += 1,
+        }
+    }
+}
+
+/// Capitalize sentence starts and the pronoun "I"
+fn fix_casing(tokens: &mut [Token], mut sentence_start: bool) {
+    for token in tokens {
+        let word = normalize(&token.text);
+        if sentence_start || word == "i" || word.starts_with("i'") {
+            token.text = capitalize(&token.text);
+        }
+        sentence_start = ends_sentence(&token.text);
+    }
+}
+
+/// Uppercase the first letter of a word, after any leading punctuation
+fn capitalize(word: &str) -> String {
+    match word.char_indices().find(|(_, c)| c.is_alphabetic()) {
+        Some((at, c)) => {
+            let mut capitalized = word[..at].to_string();
+            capitalized.extend(c.to_uppercase());
+            capitalized.push_str(&word[at + c.len_utf8()..]);
+            capitalized
+        }
+        None => word.to_string(),
+    }
+}
+
+/// Whether text ends with sentence punctuation, ignoring closing quotes
+pub(super) fn ends_sentence(text: &str) -> bool {
+    text.trim_end_matches(['"', '\'', ')', '”', '’'])
+        .ends_with(['.', '?', '!', '…'])
+}
+
+/// Lowercase a word without punctuation, keeping apostrophes
+fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '\'')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(texts: &[&str]) -> Vec<TranscriptSegment> {
+        texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                TranscriptSegment::new(
+                    "rec".to_string(),
+                    i as f64,
+                    i as f64 + 1.0,
+                    text.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn texts(segments: &[TranscriptSegment]) -> Vec<&str> {
+        segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn removes_fillers_and_repeats_and_fixes_casing() {
+        let cleanup =
+            TextCleanup::new(&["[BLANK_AUDIO]".to_string(), "(upbeat music)".to_string()]);
+
+        let cleaned = cleanup.apply(segments(&[
+            "[BLANK_AUDIO]",
+            "so i think the the budget is fine.",
+            "(Upbeat music) we should, we should ship it",
+            "on friday. then i'll write it up.",
+        ]));
+
+        assert_eq!(
+            texts(&cleaned),
+            [
+                "So I think the budget is fine.",
+                "We should ship it",
+                "on friday. Then I'll write it up."
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_word_timings_of_remaining_words() {
+        let cleanup = TextCleanup::new(&[]);
+        let mut segment = segments(&["yes yes, agreed"]).remove(0);
+        segment.words = ["yes", "yes,", "agreed"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| TranscriptWord {
+                start_time: i as f64 * 0.3,
+                end_time: i as f64 * 0.3 + 0.2,
+                text: text.to_string(),
+                confidence: None,
+            })
+            .collect();
+
+        let cleaned = cleanup.apply(vec![segment]);
+
+        assert_eq!(cleaned[0].text, "Yes, agreed");
+        let words: Vec<_> = cleaned[0]
+            .words
+            .iter()
+            .map(|word| (word.text.as_str(), word.start_time))
+            .collect();
+        assert_eq!(words, [("Yes,", 0.3), ("agreed", 0.6)]);
+    }
+}
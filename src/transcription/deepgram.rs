@@ -0,0 +1,325 @@
+//! Remote transcription through the Deepgram API
+//!
+//! Deepgram returns punctuated utterances with per-word confidences and
+//! diarized speakers, so segments arrive labelled "Speaker 1", "Speaker 2",
+//! and so on. Enrolled speaker profiles can still rename those labels.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::config::Settings;
+use crate::storage::{TranscriptSegment, TranscriptWord};
+use crate::transcription::engine::{encode_ogg, LanguageTally, TranscriptionEngine};
+use crate::transcription::error::ProviderError;
+use crate::transcription::languages::language_code;
+
+const DEFAULT_DEEPGRAM_ENDPOINT: &str = "https://api.deepgram.com/v1";
+const DEFAULT_DEEPGRAM_MODEL: &str = "nova-2";
+
+/// Speakers are only numbered consistently within one request, so whole
+/// meetings are uploaded at once; four hours is about 60 MB of OGG Opus
+const UPLOAD_WINDOW_SECS: f32 = 4.0 * 3600.0;
+
+pub struct DeepgramTranscriber {
+    http: Client,
+    api_key: String,
+    model: String,
+    endpoint: String,
+    language: Option<String>,
+    bitrate: u32,
+    word_timestamps: bool,
+    /// Languages detected when `language` is unset
+    detected: LanguageTally,
+}
+
+impl DeepgramTranscriber {
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        let transcription = &settings.transcription;
+        let api_key = transcription.api_key().trim().to_string();
+        if api_key.is_empty() {
+            anyhow::bail!(
+                "Deepgram API key is missing. Set transcription.api_key in config or MINUTES_DEEPGRAM_API_KEY."
+            );
+        }
+        if settings.whisper.translate {
+            tracing::warn!("Deepgram does not translate; whisper.translate is ignored");
+        }
+
+        let model = match transcription.model.trim() {
+            "" => DEFAULT_DEEPGRAM_MODEL.to_string(),
+            model => model.to_string(),
+        };
+        let endpoint = match transcription.endpoint.trim().trim_end_matches('/') {
+            "" => DEFAULT_DEEPGRAM_ENDPOINT.to_string(),
+            endpoint => endpoint.to_string(),
+        };
+
+        Ok(Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(600))
+                .build()
+                .context("Failed to build Deepgram HTTP client")?,
+            api_key,
+            model,
+            endpoint,
+            language: Some(settings.whisper.language.trim().to_string())
+                .filter(|language| !language.is_empty()),
+            bitrate: settings.audio.ogg_bitrate,
+            word_timestamps: settings.whisper.word_timestamps,
+            detected: LanguageTally::default(),
+        })
+    }
+
+    /// Query parameters for `/listen`
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![
+            ("model", self.model.clone()),
+            ("punctuate", "true".to_string()),
+            ("smart_format", "true".to_string()),
+            ("diarize", "true".to_string()),
+            ("utterances", "true".to_string()),
+        ];
+        match &self.language {
+            Some(language) => query.push(("language", language.clone())),
+            None => query.push(("detect_language", "true".to_string())),
+        }
+        query
+    }
+
+    /// Convert diarized utterances into segments
+    fn segments_from_response(
+        &self,
+        response: DeepgramResponse,
+        recording_id: &str,
+    ) -> Vec<TranscriptSegment> {
+        let detected = response
+            .results
+            .channels
+            .iter()
+            .find_map(|channel| channel.detected_language.as_deref())
+            .and_then(language_code);
+        if let Some(language) = detected {
+            self.detected.record(language);
+        }
+
+        response
+            .results
+            .utterances
+            .into_iter()
+            .filter(|utterance| !utterance.transcript.trim().is_empty())
+            .map(|utterance| {
+                let mut segment = TranscriptSegment::new(
+                    recording_id.to_string(),
+                    utterance.start,
+                    utterance.end,
+                    utterance.transcript.trim().to_string(),
+                );
+                segment.confidence = utterance.confidence;
+                segment.speaker = utterance
+                    .speaker
+                    .map(|speaker| format!("Speaker {}", speaker + 1));
+                if self.word_timestamps {
+                    segment.words = utterance
+                        .words
+                        .into_iter()
+                        .map(|word| TranscriptWord {
+                            start_time: word.start,
+                            end_time: word.end,
+                            text: word.punctuated_word.unwrap_or(word.word),
+                            confidence: word.confidence,
+                        })
+                        .collect();
+                }
+                segment
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl TranscriptionEngine for DeepgramTranscriber {
+    fn name(&self) -> &str {
+        "deepgram"
+    }
+
+    fn window_secs(&self) -> f32 {
+        UPLOAD_WINDOW_SECS
+    }
+
+    fn is_cloud(&self) -> bool {
+        true
+    }
+
+    fn language(&self) -> Option<String> {
+        self.language
+            .clone()
+            .or_else(|| self.detected.most_common())
+    }
+
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        recording_id: &str,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let audio = encode_ogg(samples, self.bitrate, recording_id)?;
+
+        let response = self
+            .http
+            .post(format!("{}/listen", self.endpoint))
+            .query(&self.query())
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("Token {}", self.api_key),
+            )
+            .header(reqwest::header::CONTENT_TYPE, "audio/ogg")
+            .body(audio)
+            .send()
+            .await
+            .context("Deepgram transcription request failed")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError {
+                status: status.as_u16(),
+                message: format_deepgram_http_error(status, &body),
+            }
+            .into());
+        }
+
+        let payload: DeepgramResponse = response
+            .json()
+            .await
+            .context("Failed to parse Deepgram transcription response")?;
+
+        Ok(self.segments_from_response(payload, recording_id))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramResponse {
+    #[serde(default)]
+    results: DeepgramResults,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DeepgramResults {
+    #[serde(default)]
+    channels: Vec<DeepgramChannel>,
+    #[serde(default)]
+    utterances: Vec<DeepgramUtterance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramChannel {
+    detected_language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramUtterance {
+    start: f64,
+    end: f64,
+    transcript: String,
+    confidence: Option<f64>,
+    speaker: Option<u32>,
+    #[serde(default)]
+    words: Vec<DeepgramWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramWord {
+    word: String,
+    punctuated_word: Option<String>,
+    start: f64,
+    end: f64,
+    confidence: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramErrorResponse {
+    err_msg: Option<String>,
+}
+
+fn format_deepgram_http_error(status: reqwest::StatusCode, body: &str) -> String {
+    let mut message = format!(
+        "Deepgram transcription request failed ({} {})",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or("Unknown Status")
+    );
+
+    let detail = serde_json::from_str::<DeepgramErrorResponse>(body)
+        .ok()
+        .and_then(|payload| payload.err_msg)
+        .map(|detail| detail.trim().to_string())
+        .filter(|detail| !detail.is_empty());
+    if let Some(detail) = detail {
+        message.push_str(": ");
+        message.push_str(&detail);
+    }
+
+    let hint = match status.as_u16() {
+        401 | 403 => Some("Check MINUTES_DEEPGRAM_API_KEY and your Deepgram project access"),
+        400 => Some("Check transcription.model and whisper.language in your config"),
+        402 => Some("Deepgram account balance is exhausted"),
+        429 => Some("Deepgram rate limit exceeded; retry later"),
+        500..=599 => Some("Deepgram service appears unavailable; retry shortly"),
+        _ => None,
+    };
+    if let Some(hint) = hint {
+        message.push_str(". ");
+        message.push_str(hint);
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utterances_become_diarized_segments_with_confidence() {
+        let mut settings = Settings::default();
+        settings.transcription.provider = "deepgram".to_string();
+        settings.transcription.api_key = "test-key".to_string();
+        settings.whisper.word_timestamps = true;
+        let transcriber = DeepgramTranscriber::from_settings(&settings).unwrap();
+
+        let response: DeepgramResponse = serde_json::from_str(
+            r#"{
+                "metadata": {"duration": 4.2},
+                "results": {
+                    "channels": [{"detected_language": "de", "alternatives": []}],
+                    "utterances": [
+                        {
+                            "start": 0.2, "end": 1.4, "confidence": 0.97, "channel": 0,
+                            "transcript": "Hi, everyone.", "speaker": 0,
+                            "words": [
+                                {"word": "hi", "punctuated_word": "Hi,", "start": 0.2,
+                                 "end": 0.5, "confidence": 0.99, "speaker": 0},
+                                {"word": "everyone", "punctuated_word": "everyone.",
+                                 "start": 0.6, "end": 1.4, "confidence": 0.95, "speaker": 0}
+                            ]
+                        },
+                        {
+                            "start": 1.8, "end": 3.0, "confidence": 0.88, "channel": 0,
+                            "transcript": "Morning.", "speaker": 1, "words": []
+                        }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let segments = transcriber.segments_from_response(response, "rec");
+        assert_eq!(segments.len(), 2);
+        assert_eq!(transcriber.language().as_deref(), Some("de"));
+        assert_eq!(segments[0].speaker.as_deref(), Some("Speaker 1"));
+        assert_eq!(segments[1].speaker.as_deref(), Some("Speaker 2"));
+        assert_eq!(segments[1].confidence, Some(0.88));
+        let words: Vec<_> = segments[0].words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(words, ["Hi,", "everyone."]);
+    }
+}
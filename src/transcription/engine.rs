@@ -0,0 +1,246 @@
+//! Speech-to-text engines the transcription pipeline can run on
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::audio::StreamingOggWriter;
+use crate::config::Settings;
+use crate::storage::TranscriptSegment;
+use crate::transcription::deepgram::DeepgramTranscriber;
+use crate::transcription::mock::MockTranscriber;
+use crate::transcription::openai::OpenAiTranscriber;
+#[cfg(feature = "vosk")]
+use crate::transcription::vosk::VoskTranscriber;
+use crate::transcription::whisper::{ModelCache, WhisperTranscriber};
+
+/// Turns 16kHz mono audio into timed transcript segments
+#[async_trait]
+pub trait TranscriptionEngine: Send + Sync {
+    /// Transcribe one window of audio, timed from the start of `samples`
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        recording_id: &str,
+    ) -> Result<Vec<TranscriptSegment>>;
+
+    /// Provider name for logging.
+    fn name(&self) -> &str;
+
+    /// Seconds of audio handed to `transcribe` at a time
+    fn window_secs(&self) -> f32 {
+        30.0
+    }
+
+    /// Whether audio leaves the machine when transcribed by this engine.
+    fn is_cloud(&self) -> bool {
+        false
+    }
+
+    /// Language of the transcript produced so far, as an ISO 639-1 code
+    ///
+    /// This is the configured language or the language detected in the
+    /// audio, also when translating; `None` when it is not known.
+    fn language(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Languages detected per window, reported as the most common one
+#[derive(Debug, Default)]
+pub(super) struct LanguageTally(Mutex<HashMap<String, usize>>);
+
+impl LanguageTally {
+    pub(super) fn record(&self, language: &str) {
+        if let Ok(mut counts) = self.0.lock() {
+            *counts.entry(language.to_string()).or_default() += 1;
+        }
+    }
+
+    pub(super) fn most_common(&self) -> Option<String> {
+        let counts = self.0.lock().ok()?;
+        counts
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(language, _)| language.clone())
+    }
+}
+
+/// Store the text of a translation pass as `translated_text` of `segments`
+///
+/// The two passes split the audio differently, so each translated segment
+/// goes to the original segment it overlaps most, or the nearest one.
+pub(super) fn attach_translations(
+    segments: &mut [TranscriptSegment],
+    translated: Vec<TranscriptSegment>,
+) {
+    for translation in translated {
+        let overlap = |segment: &TranscriptSegment| {
+            segment.end_time.min(translation.end_time)
+                - segment.start_time.max(translation.start_time)
+        };
+        let Some(owner) = (0..segments.len())
+            .max_by(|&a, &b| overlap(&segments[a]).total_cmp(&overlap(&segments[b])))
+        else {
+            return;
+        };
+
+        match &mut segments[owner].translated_text {
+            Some(text) => {
+                text.push(' ');
+                text.push_str(&translation.text);
+            }
+            None => segments[owner].translated_text = Some(translation.text),
+        }
+    }
+}
+
+/// Build the engine selected by `transcription.provider`
+///
+/// A local engine reuses the Whisper model held by `cache` when given one.
+pub fn build_engine(
+    settings: &Settings,
+    cache: Option<&mut ModelCache>,
+) -> Result<Box<dyn TranscriptionEngine>> {
+    match settings
+        .transcription
+        .provider
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "local" => build_local_engine(settings, cache),
+        "openai" => Ok(Box::new(OpenAiTranscriber::from_settings(settings)?)),
+        "deepgram" => Ok(Box::new(DeepgramTranscriber::from_settings(settings)?)),
+        other => anyhow::bail!(
+            "Unsupported transcription.provider '{}'. Supported providers: local, openai, deepgram",
+            other
+        ),
+    }
+}
+
+/// Build the on-device engine selected by `transcription.engine`
+fn build_local_engine(
+    settings: &Settings,
+    cache: Option<&mut ModelCache>,
+) -> Result<Box<dyn TranscriptionEngine>> {
+    match settings.transcription.engine.trim().to_lowercase().as_str() {
+        "whisper" => Ok(Box::new(match cache {
+            Some(cache) => WhisperTranscriber::from_cache(settings, cache)?,
+            None => WhisperTranscriber::new(settings)?,
+        })),
+        "vosk" => {
+            #[cfg(feature = "vosk")]
+            {
+                Ok(Box::new(VoskTranscriber::new(settings)?))
+            }
+            #[cfg(not(feature = "vosk"))]
+            {
+                anyhow::bail!("Vosk engine requires building with the vosk feature")
+            }
+        }
+        "mock" => Ok(Box::new(MockTranscriber)),
+        other => anyhow::bail!(
+            "Unsupported transcription.engine '{}'. Supported engines: whisper, vosk, mock",
+            other
+        ),
+    }
+}
+
+/// Encode 16kHz mono samples as OGG Opus for upload to a remote engine
+pub(super) fn encode_ogg(samples: &[f32], bitrate: u32, recording_id: &str) -> Result<Vec<u8>> {
+    let path = std::env::temp_dir().join(format!(
+        "minutes-upload-{}-{}.ogg",
+        std::process::id(),
+        recording_id
+    ));
+    let pcm: Vec<i16> = samples
+        .iter()
+        .map(|&sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect();
+
+    let encoded = StreamingOggWriter::create(&path, 16000, 1, bitrate)
+        .and_then(|mut writer| {
+            writer.write_samples(&pcm)?;
+            writer.finalize()
+        })
+        .and_then(|_| std::fs::read(&path).context("Failed to read encoded audio"));
+    let _ = std::fs::remove_file(&path);
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_tally_reports_the_most_common_language() {
+        let tally = LanguageTally::default();
+        assert_eq!(tally.most_common(), None);
+
+        for language in ["de", "en", "de"] {
+            tally.record(language);
+        }
+        assert_eq!(tally.most_common().as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn translations_attach_to_the_segments_they_overlap() {
+        let segment = |start: f64, end: f64, text: &str| {
+            TranscriptSegment::new("rec".to_string(), start, end, text.to_string())
+        };
+        let mut segments = vec![
+            segment(0.0, 4.0, "Guten Morgen zusammen."),
+            segment(4.0, 9.0, "Fangen wir mit dem Budget an."),
+        ];
+
+        attach_translations(
+            &mut segments,
+            vec![
+                segment(0.0, 2.0, "Good morning,"),
+                segment(2.0, 4.5, "everyone."),
+                segment(4.5, 9.0, "Let's start with the budget."),
+            ],
+        );
+
+        assert_eq!(
+            segments[0].translated_text.as_deref(),
+            Some("Good morning, everyone.")
+        );
+        assert_eq!(
+            segments[1].translated_text.as_deref(),
+            Some("Let's start with the budget.")
+        );
+    }
+
+    #[test]
+    fn unsupported_provider_returns_error() {
+        let mut settings = Settings::default();
+        settings.transcription.provider = "unknown".to_string();
+
+        let err = match build_engine(&settings, None) {
+            Ok(_) => panic!("expected engine creation to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("Unsupported transcription.provider"));
+
+        settings.transcription.provider = "local".to_string();
+        settings.transcription.engine = "unknown".to_string();
+        let err = match build_engine(&settings, None) {
+            Ok(_) => panic!("expected engine creation to fail"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("Unsupported transcription.engine"));
+    }
+
+    #[test]
+    fn mock_engine_needs_no_model() {
+        let mut settings = Settings::default();
+        settings.transcription.engine = "mock".to_string();
+
+        let engine = build_engine(&settings, None).unwrap();
+        assert_eq!(engine.name(), "mock");
+        assert!(!engine.is_cloud());
+    }
+}
@@ -0,0 +1,85 @@
+//! Transcription failures that may succeed when tried again
+//!
+//! The daemon retries a failed transcription only when the cause can go
+//! away on its own or with a fix outside the recording: a model that is not
+//! downloaded yet, a provider that is rate limited or down, a network or
+//! file system hiccup. Audio that cannot be decoded fails the same way every
+//! time, so it is not retried.
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// The configured Whisper model file does not exist
+#[derive(Debug, Error)]
+#[error(
+    "Whisper model not found at {}. Please download the model first.\nRun: minutes model download {model}",
+    .path.display()
+)]
+pub struct ModelNotFound {
+    pub path: PathBuf,
+    pub model: String,
+}
+
+/// A remote provider answered with an error status
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct ProviderError {
+    pub status: u16,
+    pub message: String,
+}
+
+/// Whether a transcription that failed with `error` may succeed later
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if cause.is::<ModelNotFound>() || cause.is::<reqwest::Error>() {
+            return true;
+        }
+        if let Some(error) = cause.downcast_ref::<ProviderError>() {
+            return error.status == 408 || error.status == 429 || error.status >= 500;
+        }
+        if let Some(error) = cause.downcast_ref::<std::io::Error>() {
+            return !matches!(
+                error.kind(),
+                std::io::ErrorKind::InvalidData | std::io::ErrorKind::UnexpectedEof
+            );
+        }
+        cause.is::<rusqlite::Error>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn tells_retryable_failures_from_lasting_ones() {
+        let missing_model = anyhow::Error::new(ModelNotFound {
+            path: PathBuf::from("/models/ggml-base.bin"),
+            model: "base".to_string(),
+        })
+        .context("Failed to transcribe");
+        assert!(is_transient(&missing_model));
+
+        let rate_limited = anyhow::Error::new(ProviderError {
+            status: 429,
+            message: "rate limited".to_string(),
+        });
+        assert!(is_transient(&rate_limited));
+
+        let bad_key = anyhow::Error::new(ProviderError {
+            status: 401,
+            message: "unauthorized".to_string(),
+        });
+        assert!(!is_transient(&bad_key));
+
+        let corrupt: anyhow::Result<()> = Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "bad header",
+        ))
+        .context("Failed to read audio");
+        assert!(!is_transient(&corrupt.unwrap_err()));
+
+        assert!(!is_transient(&anyhow::anyhow!("Unsupported audio format")));
+    }
+}
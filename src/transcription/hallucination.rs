@@ -0,0 +1,232 @@
+//! Filtering of segments Whisper invents during silence
+//!
+//! Trained on subtitled video, Whisper fills quiet stretches with phrases
+//! like "Thanks for watching!" or loops on the last thing it heard. Segments
+//! matching a blocklist or timed over near-silent audio are dropped, phrases
+//! repeated within a segment are collapsed, and long runs of identical
+//! segments are cut short.
+
+use crate::config::Settings;
+use crate::storage::TranscriptSegment;
+use crate::transcription::silence::is_silent_span;
+
+/// Times a phrase must repeat within one segment before it is collapsed
+const MIN_PHRASE_REPEATS: usize = 3;
+
+/// Post-filter for hallucinated transcript segments
+#[derive(Debug, Clone)]
+pub struct HallucinationFilter {
+    /// Normalized blocklist entries
+    blocklist: Vec<String>,
+    /// Identical consecutive segments kept before the rest of the run is dropped
+    max_repeats: usize,
+    /// Level in dBFS below which a segment's audio counts as silence
+    silence_threshold_db: Option<f32>,
+}
+
+impl HallucinationFilter {
+    /// Filter configured by the `whisper.hallucination_*` settings, if enabled
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        let whisper = &settings.whisper;
+        whisper.filter_hallucinations.then(|| {
+            Self::new(&whisper.hallucination_blocklist)
+                .with_max_repeats(whisper.max_segment_repeats)
+                .with_silence_threshold(whisper.silence_threshold_db)
+        })
+    }
+
+    pub fn new(blocklist: &[String]) -> Self {
+        Self {
+            blocklist: blocklist
+                .iter()
+                .map(|entry| normalize(entry))
+                .filter(|entry| !entry.is_empty())
+                .collect(),
+            max_repeats: 2,
+            silence_threshold_db: None,
+        }
+    }
+
+    /// Keep at most `max_repeats` identical segments in a row (0 = no limit)
+    pub fn with_max_repeats(mut self, max_repeats: usize) -> Self {
+        self.max_repeats = max_repeats;
+        self
+    }
+
+    /// Drop segments whose audio is quieter than `threshold_db` dBFS
+    pub fn with_silence_threshold(mut self, threshold_db: f32) -> Self {
+        self.silence_threshold_db = Some(threshold_db);
+        self
+    }
+
+    /// Drop or clean up hallucinated segments, keeping the rest in order
+    ///
+    /// `samples` is the 16kHz mono audio the segment times refer to.
+    pub fn apply(
+        &self,
+        segments: Vec<TranscriptSegment>,
+        samples: &[f32],
+    ) -> Vec<TranscriptSegment> {
+        let mut kept: Vec<TranscriptSegment> = Vec::with_capacity(segments.len());
+        let mut run = 0;
+        let mut dropped = 0;
+
+        for mut segment in segments {
+            let collapsed = collapse_repeated_phrase(&segment.text);
+            if collapsed != segment.text {
+                segment.words.truncate(collapsed.split_whitespace().count());
+                segment.text = collapsed;
+            }
+            let normalized = normalize(&segment.text);
+            if normalized.is_empty() || self.blocklist.contains(&normalized) {
+                dropped += 1;
+                continue;
+            }
+            if self.silence_threshold_db.is_some_and(|threshold_db| {
+                is_silent_span(samples, segment.start_time, segment.end_time, threshold_db)
+            }) {
+                dropped += 1;
+                continue;
+            }
+
+            let repeats_previous = kept
+                .last()
+                .is_some_and(|previous| normalize(&previous.text) == normalized);
+            run = if repeats_previous { run + 1 } else { 1 };
+            if self.max_repeats > 0 && run > self.max_repeats {
+                dropped += 1;
+                continue;
+            }
+
+            kept.push(segment);
+        }
+
+        if dropped > 0 {
+            tracing::info!("Dropped {} hallucinated segment(s)", dropped);
+        }
+        kept
+    }
+}
+
+/// Lowercase words without punctuation, for comparing segment texts
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reduce a segment made of one phrase repeated over and over to a single copy
+fn collapse_repeated_phrase(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let keys: Vec<String> = words.iter().map(|word| normalize(word)).collect();
+
+    for len in 1..=words.len() / MIN_PHRASE_REPEATS {
+        if !words.len().is_multiple_of(len) {
+            continue;
+        }
+        let phrase = &keys[..len];
+        if keys.chunks(len).all(|chunk| chunk == phrase) {
+            return words[..len].join(" ");
+        }
+    }
+
+    text.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(texts: &[&str]) -> Vec<TranscriptSegment> {
+        texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                TranscriptSegment::new(
+                    "rec".to_string(),
+                    i as f64,
+                    i as f64 + 1.0,
+                    text.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn texts(segments: &[TranscriptSegment]) -> Vec<&str> {
+        segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn drops_blocklisted_segments_but_not_longer_sentences() {
+        let filter = HallucinationFilter::new(&["Thanks for watching!".to_string()]);
+
+        let kept = filter.apply(
+            segments(&[
+                "Let's review the numbers.",
+                "thanks for watching",
+                "Thanks for watching the demo, everyone.",
+            ]),
+            &[],
+        );
+
+        assert_eq!(
+            texts(&kept),
+            [
+                "Let's review the numbers.",
+                "Thanks for watching the demo, everyone."
+            ]
+        );
+    }
+
+    #[test]
+    fn collapses_repetition_within_and_across_segments() {
+        let filter = HallucinationFilter::new(&[]);
+
+        let kept = filter.apply(
+            segments(&[
+                "Thank you. Thank you. Thank you. Thank you.",
+                "Okay.",
+                "Okay.",
+                "Okay.",
+                "Okay.",
+                "Next item.",
+            ]),
+            &[],
+        );
+
+        assert_eq!(texts(&kept), ["Thank you.", "Okay.", "Okay.", "Next item."]);
+    }
+
+    #[test]
+    fn repeat_limit_is_configurable() {
+        let filter = HallucinationFilter::new(&[]).with_max_repeats(1);
+        let kept = filter.apply(segments(&["Okay.", "Okay.", "Next item."]), &[]);
+        assert_eq!(texts(&kept), ["Okay.", "Next item."]);
+
+        let filter = HallucinationFilter::new(&[]).with_max_repeats(0);
+        let kept = filter.apply(segments(&["Okay."; 5]), &[]);
+        assert_eq!(kept.len(), 5);
+    }
+
+    #[test]
+    fn drops_segments_over_silent_audio() {
+        let filter = HallucinationFilter::new(&[]).with_silence_threshold(-45.0);
+        // One second of speech-level audio, then two of silence
+        let mut samples: Vec<f32> = (0..16000).map(|i| 0.3 * (i as f32 * 0.1).sin()).collect();
+        samples.extend(vec![0.0; 32000]);
+
+        let kept = filter.apply(
+            segments(&["Let's get started.", "Thank you.", "Bye."]),
+            &samples,
+        );
+
+        assert_eq!(texts(&kept), ["Let's get started."]);
+    }
+}
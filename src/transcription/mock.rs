@@ -0,0 +1,40 @@
+//! Fake transcription engine for tests
+//!
+//! Recognizes no speech: each window of audio becomes one fixed segment
+//! naming its length. With the mock audio backend it drives the daemon's
+//! record → transcribe → search path without a model, in CI or when ruling
+//! out the speech engine while troubleshooting.
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::storage::TranscriptSegment;
+use crate::transcription::engine::TranscriptionEngine;
+
+/// Engine that transcribes every window as "Mock transcript of N seconds"
+pub struct MockTranscriber;
+
+#[async_trait]
+impl TranscriptionEngine for MockTranscriber {
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        recording_id: &str,
+    ) -> Result<Vec<TranscriptSegment>> {
+        if samples.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let secs = samples.len() as f64 / 16000.0;
+        Ok(vec![TranscriptSegment::new(
+            recording_id.to_string(),
+            0.0,
+            secs,
+            format!("Mock transcript of {:.1} seconds.", secs),
+        )])
+    }
+
+    fn name(&self) -> &str {
+        "mock"
+    }
+}
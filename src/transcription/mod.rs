@@ -1,9 +1,37 @@
 //! Transcription module for minutes
 //!
-//! Handles speech-to-text using whisper-rs.
+//! Handles speech-to-text using whisper-rs, Vosk, or a remote service.
 
+mod cleanup;
+mod deepgram;
+mod engine;
+mod error;
+mod hallucination;
+mod languages;
+mod mock;
+mod models;
+mod openai;
 mod pipeline;
+mod silence;
+pub mod speakers;
+mod vosk;
 mod whisper;
 
-pub use pipeline::TranscriptionPipeline;
-pub use whisper::WhisperTranscriber;
+pub use cleanup::TextCleanup;
+pub use deepgram::DeepgramTranscriber;
+pub use engine::{build_engine, TranscriptionEngine};
+pub use error::{is_transient, ModelNotFound, ProviderError};
+pub use hallucination::HallucinationFilter;
+pub use languages::{language_code, language_name};
+pub use models::{
+    download_model, is_valid_model_name, model_name, model_url, recommend_model,
+    split_quantization, system_memory, QUANTIZATIONS,
+};
+pub use openai::OpenAiTranscriber;
+pub use pipeline::{shift_track, CheckpointCallback, ProgressCallback, TranscriptionPipeline};
+pub use silence::{silence_marker_text, silence_markers};
+#[cfg(feature = "vosk")]
+pub use vosk::VoskTranscriber;
+pub use whisper::{
+    load_audio, load_chunks, load_chunks_native, load_native, ModelCache, WhisperTranscriber,
+};
@@ -0,0 +1,246 @@
+//! Whisper model downloads
+//!
+//! Models are the ggml files published with whisper.cpp. A download is
+//! written to a `.part` file next to the destination and only renamed into
+//! place once complete, so an interrupted download never looks like a model.
+//!
+//! Quantized variants are named after the model they shrink, with the
+//! quantization appended: "base.en-q5_1" is stored as `ggml-base.en-q5_1.bin`.
+//! They need a fraction of the memory for a small loss in accuracy.
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::config::Settings;
+
+/// Where whisper.cpp publishes its ggml models
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Quantizations whisper.cpp publishes, as model name suffixes
+pub const QUANTIZATIONS: [&str; 3] = ["q5_0", "q5_1", "q8_0"];
+
+/// Approximate memory whisper.cpp needs to run each model size, in MB
+const MODEL_MEMORY_MB: [(&str, u64); 5] = [
+    ("tiny", 273),
+    ("base", 388),
+    ("small", 852),
+    ("medium", 2100),
+    ("large-v3", 3900),
+];
+
+/// Model name of a model given by name or file name
+///
+/// "ggml-base.en-q5_1.bin" and "base.en-q5_1" both yield "base.en-q5_1".
+pub fn model_name(model: &str) -> &str {
+    let model = model.strip_prefix("ggml-").unwrap_or(model);
+    model.strip_suffix(".bin").unwrap_or(model)
+}
+
+/// Download URL of a named model such as "base" or "small.en"
+pub fn model_url(model: &str) -> String {
+    format!("{}/ggml-{}.bin", MODEL_BASE_URL, model_name(model))
+}
+
+/// Whether a model name is safe to use in a file name and URL
+pub fn is_valid_model_name(model: &str) -> bool {
+    !model.is_empty()
+        && !model.starts_with('.')
+        && model
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+}
+
+/// Split a model name into the model and its quantization
+///
+/// "base.en-q5_1" yields `("base.en", Some("q5_1"))`; a name without a
+/// quantization suffix is returned whole.
+pub fn split_quantization(model: &str) -> (&str, Option<&str>) {
+    match model.rsplit_once('-') {
+        Some((base, suffix)) if is_quantization_suffix(suffix) => (base, Some(suffix)),
+        _ => (model, None),
+    }
+}
+
+/// Whether a name suffix looks like a quantization such as "q5_1" or "q4_k"
+fn is_quantization_suffix(suffix: &str) -> bool {
+    suffix.len() > 1
+        && suffix.starts_with('q')
+        && suffix[1..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Model best suited to a machine with `memory_bytes` of RAM and `cpus` cores
+///
+/// Picks the largest model the machine transcribes at a usable speed, and
+/// its quantized variant when the full model would take more than a quarter
+/// of the memory. `english` picks the English-only variant where one exists.
+pub fn recommend_model(memory_bytes: u64, cpus: usize, english: bool) -> String {
+    let memory_mb = memory_bytes / 1_000_000;
+    let index = if memory_mb < 1_000 || cpus < 2 {
+        0
+    } else if memory_mb < 2_000 || cpus < 4 {
+        1
+    } else if memory_mb < 4_000 || cpus < 8 {
+        2
+    } else if memory_mb < 8_000 || cpus < 12 {
+        3
+    } else {
+        4
+    };
+    let (size, needed_mb) = MODEL_MEMORY_MB[index];
+
+    let mut model = size.to_string();
+    if english && size != "large-v3" {
+        model.push_str(".en");
+    }
+    if needed_mb * 4 > memory_mb {
+        // whisper.cpp publishes q5_1 for the small models and q5_0 for the rest
+        model.push_str(if index < 3 { "-q5_1" } else { "-q5_0" });
+    }
+    model
+}
+
+/// Total physical memory of this machine, when it can be read
+pub fn system_memory() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+        let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+        let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kb * 1024)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("sysctl")
+            .args(["-n", "hw.memsize"])
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Download a model into `whisper.models_dir`, returning its path
+///
+/// `progress` is called with the bytes received so far and the total size
+/// when the server reports it.
+pub async fn download_model(
+    settings: &Settings,
+    model: &str,
+    progress: impl Fn(u64, Option<u64>),
+) -> Result<PathBuf> {
+    let model = model_name(model);
+    if !is_valid_model_name(model) {
+        anyhow::bail!("Invalid model name: {}", model);
+    }
+    if let (_, Some(quantization)) = split_quantization(model) {
+        if !QUANTIZATIONS.contains(&quantization) {
+            anyhow::bail!(
+                "Unknown quantization '{}' in model '{}'; whisper.cpp publishes {}",
+                quantization,
+                model,
+                QUANTIZATIONS.join(", ")
+            );
+        }
+    }
+
+    let path = settings.model_file(model);
+    let partial = path.with_extension("bin.part");
+    std::fs::create_dir_all(&settings.whisper.models_dir).with_context(|| {
+        format!(
+            "Failed to create models directory: {}",
+            settings.whisper.models_dir.display()
+        )
+    })?;
+
+    let url = model_url(model);
+    tracing::info!("Downloading Whisper model from {}", url);
+    let mut response = reqwest::get(&url)
+        .await
+        .with_context(|| format!("Failed to download {}", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to download model '{}': server returned {}",
+            model,
+            response.status()
+        );
+    }
+
+    let total = response.content_length();
+    let mut file = std::fs::File::create(&partial)
+        .with_context(|| format!("Failed to create {}", partial.display()))?;
+    let mut received = 0u64;
+    progress(received, total);
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| format!("Download of model '{}' was interrupted", model))?
+    {
+        file.write_all(&chunk)?;
+        received += chunk.len() as u64;
+        progress(received, total);
+    }
+    file.sync_all()?;
+    drop(file);
+
+    if total.is_some_and(|total| total != received) {
+        let _ = std::fs::remove_file(&partial);
+        anyhow::bail!("Download of model '{}' ended early", model);
+    }
+
+    std::fs::rename(&partial, &path)
+        .with_context(|| format!("Failed to move model into place: {}", path.display()))?;
+    tracing::info!("Saved Whisper model to {}", path.display());
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_names_map_to_whisper_cpp_files() {
+        assert_eq!(
+            model_url("base.en"),
+            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.en.bin"
+        );
+        assert_eq!(
+            model_url("ggml-base.en-q5_1.bin"),
+            model_url("base.en-q5_1")
+        );
+        assert!(is_valid_model_name("large-v3"));
+        assert!(!is_valid_model_name("../base"));
+        assert!(!is_valid_model_name(""));
+    }
+
+    #[test]
+    fn splits_quantized_model_names() {
+        assert_eq!(
+            split_quantization("base.en-q5_1"),
+            ("base.en", Some("q5_1"))
+        );
+        assert_eq!(
+            split_quantization("large-v3-q5_0"),
+            ("large-v3", Some("q5_0"))
+        );
+        assert_eq!(split_quantization("large-v3"), ("large-v3", None));
+        assert_eq!(split_quantization("small"), ("small", None));
+    }
+
+    #[test]
+    fn recommends_models_that_fit_the_machine() {
+        const GB: u64 = 1_000_000_000;
+        assert_eq!(recommend_model(GB / 2, 4, false), "tiny-q5_1");
+        assert_eq!(recommend_model(3 * GB, 4, true), "small.en-q5_1");
+        assert_eq!(recommend_model(16 * GB, 8, true), "medium.en");
+        assert_eq!(recommend_model(8 * GB, 8, false), "medium-q5_0");
+        assert_eq!(recommend_model(32 * GB, 16, true), "large-v3");
+        assert_eq!(recommend_model(32 * GB, 2, false), "base");
+    }
+}
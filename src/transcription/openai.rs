@@ -0,0 +1,434 @@
+//! Remote transcription through the OpenAI Whisper API
+//!
+//! Each window of audio is encoded as OGG Opus and uploaded, so machines too
+//! slow for local inference can still transcribe. Confidential recordings
+//! are never sent; see [`TranscriptionPipeline::for_recording`].
+//!
+//! [`TranscriptionPipeline::for_recording`]: crate::transcription::TranscriptionPipeline::for_recording
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::config::Settings;
+use crate::storage::{TranscriptSegment, TranscriptWord};
+use crate::transcription::engine::{
+    attach_translations, encode_ogg, LanguageTally, TranscriptionEngine,
+};
+use crate::transcription::error::ProviderError;
+use crate::transcription::languages::language_code;
+
+const DEFAULT_OPENAI_ENDPOINT: &str = "https://api.openai.com/v1";
+const DEFAULT_OPENAI_MODEL: &str = "whisper-1";
+
+/// Ten minutes of 16kHz speech at the default bitrate is about 2 MB, well
+/// below the API's 25 MB upload limit
+const UPLOAD_WINDOW_SECS: f32 = 600.0;
+
+pub struct OpenAiTranscriber {
+    http: Client,
+    api_key: String,
+    model: String,
+    endpoint: String,
+    language: Option<String>,
+    translate: bool,
+    bitrate: u32,
+    /// Segments more likely silence than this are dropped
+    no_speech_threshold: Option<f32>,
+    word_timestamps: bool,
+    /// Languages detected when `language` is unset
+    detected: LanguageTally,
+}
+
+impl OpenAiTranscriber {
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        let transcription = &settings.transcription;
+        let api_key = transcription.api_key().trim().to_string();
+        if api_key.is_empty() {
+            anyhow::bail!(
+                "OpenAI API key is missing. Set transcription.api_key in config or MINUTES_OPENAI_API_KEY."
+            );
+        }
+
+        let model = match transcription.model.trim() {
+            "" => DEFAULT_OPENAI_MODEL.to_string(),
+            model => model.to_string(),
+        };
+        let endpoint = match transcription.endpoint.trim().trim_end_matches('/') {
+            "" => DEFAULT_OPENAI_ENDPOINT.to_string(),
+            endpoint => endpoint.to_string(),
+        };
+
+        Ok(Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(300))
+                .build()
+                .context("Failed to build OpenAI HTTP client")?,
+            api_key,
+            model,
+            endpoint,
+            language: Some(settings.whisper.language.trim().to_string())
+                .filter(|language| !language.is_empty()),
+            translate: settings.whisper.translate,
+            bitrate: settings.audio.ogg_bitrate,
+            no_speech_threshold: settings
+                .whisper
+                .filter_hallucinations
+                .then_some(settings.whisper.no_speech_threshold),
+            word_timestamps: settings.whisper.word_timestamps,
+            detected: LanguageTally::default(),
+        })
+    }
+
+    fn request_url(&self, translate: bool) -> String {
+        let action = if translate {
+            "translations"
+        } else {
+            "transcriptions"
+        };
+        format!("{}/audio/{}", self.endpoint, action)
+    }
+
+    /// Form fields sent along with the audio file
+    fn form_fields(&self, translate: bool) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("model", self.model.clone()),
+            ("response_format", "verbose_json".to_string()),
+            ("timestamp_granularities[]", "segment".to_string()),
+        ];
+        if self.word_timestamps && !translate {
+            fields.push(("timestamp_granularities[]", "word".to_string()));
+        }
+        // Translations are always into English and take no source language
+        if let (Some(language), false) = (&self.language, translate) {
+            fields.push(("language", language.clone()));
+        }
+        fields
+    }
+
+    /// Upload a window of audio for transcription or translation into English
+    async fn request(
+        &self,
+        samples: &[f32],
+        recording_id: &str,
+        translate: bool,
+    ) -> Result<OpenAiTranscription> {
+        let audio = encode_ogg(samples, self.bitrate, recording_id)?;
+        let boundary = format!("minutes-{:016x}", rand_boundary());
+        let body = multipart_body(&boundary, &self.form_fields(translate), &audio);
+
+        let response = self
+            .http
+            .post(self.request_url(translate))
+            .bearer_auth(&self.api_key)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", boundary),
+            )
+            .body(body)
+            .send()
+            .await
+            .context("OpenAI transcription request failed")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError {
+                status: status.as_u16(),
+                message: format_openai_http_error(status, &body),
+            }
+            .into());
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse OpenAI transcription response")
+    }
+
+    /// Convert an API response into segments, attaching words to their segment
+    fn segments_from_response(
+        &self,
+        response: OpenAiTranscription,
+        recording_id: &str,
+    ) -> Vec<TranscriptSegment> {
+        // Reported by name, e.g. "english"
+        if let Some(language) = response.language.as_deref().and_then(language_code) {
+            if !response.segments.is_empty() {
+                self.detected.record(language);
+            }
+        }
+
+        let mut segments = self.kept_segments(response.segments, recording_id);
+
+        for word in response.words {
+            let text = word.word.trim();
+            if text.is_empty() {
+                continue;
+            }
+            // Words are listed for the whole file; attach each to the last
+            // segment starting at or before it
+            let owner = segments
+                .iter_mut()
+                .rev()
+                .find(|segment| segment.start_time <= word.start);
+            if let Some(segment) = owner.filter(|segment| word.start < segment.end_time) {
+                segment.words.push(TranscriptWord {
+                    start_time: word.start,
+                    end_time: word.end,
+                    text: text.to_string(),
+                    confidence: None,
+                });
+            }
+        }
+
+        segments
+    }
+
+    /// Segments of a response with text and likely speech
+    fn kept_segments(
+        &self,
+        segments: Vec<OpenAiSegment>,
+        recording_id: &str,
+    ) -> Vec<TranscriptSegment> {
+        segments
+            .into_iter()
+            .filter(|segment| {
+                self.no_speech_threshold
+                    .is_none_or(|threshold| segment.no_speech_prob <= threshold)
+            })
+            .filter(|segment| !segment.text.trim().is_empty())
+            .map(|segment| {
+                TranscriptSegment::new(
+                    recording_id.to_string(),
+                    segment.start,
+                    segment.end,
+                    segment.text.trim().to_string(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl TranscriptionEngine for OpenAiTranscriber {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn window_secs(&self) -> f32 {
+        UPLOAD_WINDOW_SECS
+    }
+
+    fn is_cloud(&self) -> bool {
+        true
+    }
+
+    fn language(&self) -> Option<String> {
+        self.language
+            .clone()
+            .or_else(|| self.detected.most_common())
+    }
+
+    /// Transcribe a window; with `whisper.translate`, also translate it
+    ///
+    /// The translation is a second upload of the same audio, stored next to
+    /// the original text.
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        recording_id: &str,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let response = self.request(samples, recording_id, false).await?;
+        let language = self.language.clone().or_else(|| {
+            response
+                .language
+                .as_deref()
+                .and_then(language_code)
+                .map(str::to_string)
+        });
+        let mut segments = self.segments_from_response(response, recording_id);
+
+        if self.translate && !segments.is_empty() && language.as_deref() != Some("en") {
+            let translation = self.request(samples, recording_id, true).await?;
+            attach_translations(
+                &mut segments,
+                self.kept_segments(translation.segments, recording_id),
+            );
+        }
+
+        Ok(segments)
+    }
+}
+
+/// multipart/form-data body with text fields and the audio as `file`
+fn multipart_body(boundary: &str, fields: &[(&str, String)], audio: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(audio.len() + 1024);
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                boundary, name, value
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"audio.ogg\"\r\n\
+             Content-Type: audio/ogg\r\n\r\n",
+            boundary
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(audio);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+fn rand_boundary() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    nanos ^ (u64::from(std::process::id()) << 32)
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiTranscription {
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<OpenAiSegment>,
+    #[serde(default)]
+    words: Vec<OpenAiWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiSegment {
+    start: f64,
+    end: f64,
+    text: String,
+    #[serde(default)]
+    no_speech_prob: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiWord {
+    word: String,
+    start: f64,
+    end: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiErrorResponse {
+    error: OpenAiErrorPayload,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiErrorPayload {
+    message: Option<String>,
+}
+
+fn format_openai_http_error(status: reqwest::StatusCode, body: &str) -> String {
+    let mut message = format!(
+        "OpenAI transcription request failed ({} {})",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or("Unknown Status")
+    );
+
+    let detail = serde_json::from_str::<OpenAiErrorResponse>(body)
+        .ok()
+        .and_then(|payload| payload.error.message)
+        .map(|detail| detail.trim().to_string())
+        .filter(|detail| !detail.is_empty());
+    if let Some(detail) = detail {
+        message.push_str(": ");
+        message.push_str(&detail);
+    }
+
+    let hint = match status.as_u16() {
+        401 | 403 => Some("Check MINUTES_OPENAI_API_KEY and your OpenAI account access"),
+        404 => Some("Check transcription.model and transcription.endpoint in your config"),
+        413 => Some("The audio upload was too large"),
+        429 => Some("OpenAI quota or rate limit exceeded; retry later"),
+        500..=599 => Some("OpenAI service appears unavailable; retry shortly"),
+        _ => None,
+    };
+    if let Some(hint) = hint {
+        message.push_str(". ");
+        message.push_str(hint);
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcriber(word_timestamps: bool) -> OpenAiTranscriber {
+        let mut settings = Settings::default();
+        settings.transcription.provider = "openai".to_string();
+        settings.transcription.api_key = "test-key".to_string();
+        settings.whisper.word_timestamps = word_timestamps;
+        OpenAiTranscriber::from_settings(&settings).unwrap()
+    }
+
+    #[test]
+    fn requires_an_api_key() {
+        let mut settings = Settings::default();
+        settings.transcription.provider = "openai".to_string();
+
+        let err = OpenAiTranscriber::from_settings(&settings)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("OpenAI API key is missing"));
+    }
+
+    #[test]
+    fn response_segments_keep_their_words_and_drop_silence() {
+        let response: OpenAiTranscription = serde_json::from_str(
+            r#"{
+                "language": "english",
+                "text": "Hello there. Thanks for watching!",
+                "segments": [
+                    {"start": 0.0, "end": 1.5, "text": " Hello there.", "no_speech_prob": 0.01},
+                    {"start": 1.5, "end": 3.0, "text": " Thanks for watching!", "no_speech_prob": 0.9}
+                ],
+                "words": [
+                    {"word": "Hello", "start": 0.1, "end": 0.5},
+                    {"word": "there", "start": 0.6, "end": 1.2},
+                    {"word": "Thanks", "start": 1.6, "end": 2.0}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let transcriber = transcriber(true);
+        let segments = transcriber.segments_from_response(response, "rec");
+        assert_eq!(transcriber.language().as_deref(), Some("en"));
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].text, "Hello there.");
+        let words: Vec<_> = segments[0].words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(words, ["Hello", "there"]);
+    }
+
+    #[test]
+    fn requests_word_timings_only_when_enabled() {
+        let count = |transcriber: &OpenAiTranscriber, translate: bool| {
+            transcriber
+                .form_fields(translate)
+                .iter()
+                .filter(|(name, _)| *name == "timestamp_granularities[]")
+                .count()
+        };
+        assert_eq!(count(&transcriber(false), false), 1);
+        assert_eq!(count(&transcriber(true), false), 2);
+        // Translations only need segment timings
+        assert_eq!(count(&transcriber(true), true), 1);
+    }
+}
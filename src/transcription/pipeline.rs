@@ -1,30 +1,152 @@
 //! Transcription pipeline orchestration
 
 use anyhow::Result;
+use std::ops::Range;
 use std::path::Path;
+use std::sync::Mutex;
 
 use crate::config::Settings;
-use crate::storage::TranscriptSegment;
-use crate::transcription::whisper::{load_audio, WhisperTranscriber};
+use crate::storage::{
+    Recording, Silence, SpeakerProfile, TranscriptSegment, TranscriptionCheckpoint,
+};
+use crate::transcription::cleanup::{ends_sentence, TextCleanup};
+use crate::transcription::engine::{build_engine, TranscriptionEngine};
+use crate::transcription::hallucination::HallucinationFilter;
+use crate::transcription::silence::{
+    common_silences, detect_silences, split_at_pauses, trim_silence,
+};
+use crate::transcription::speakers::identify_speakers;
+use crate::transcription::whisper::{load_audio, load_chunks, ModelCache};
 
 /// Progress callback type
 pub type ProgressCallback = Box<dyn Fn(f32) + Send + Sync>;
 
+/// Called with the progress of a stream after each transcribed window
+pub type CheckpointCallback = Box<dyn Fn(&TranscriptionCheckpoint) + Send + Sync>;
+
 /// Transcription pipeline for processing audio files
 pub struct TranscriptionPipeline {
-    transcriber: WhisperTranscriber,
+    engine: Box<dyn TranscriptionEngine>,
     chunk_duration_secs: f32,
+    speaker_profiles: Vec<SpeakerProfile>,
+    speaker_match_threshold: f32,
+    /// Shortest silent run detected, and shortened with `trim_silence`
+    min_silence_secs: f64,
+    trim_silence: bool,
+    silence_threshold_db: f32,
+    /// Silences found in the audio transcribed last
+    silences: Mutex<Vec<Silence>>,
+    /// Drops segments Whisper invents during silence
+    hallucination_filter: Option<HallucinationFilter>,
+    /// Removes fillers and repeats and fixes casing of segment text
+    text_cleanup: Option<TextCleanup>,
+    merge_rules: MergeRules,
+    /// Progress saved by an earlier, interrupted run
+    resume_from: Vec<TranscriptionCheckpoint>,
+    save_checkpoint: Option<CheckpointCallback>,
 }
 
 impl TranscriptionPipeline {
-    /// Create a new transcription pipeline
+    /// Create a pipeline on the engine selected by `transcription.provider`
     pub fn new(settings: &Settings) -> Result<Self> {
-        let transcriber = WhisperTranscriber::new(settings)?;
+        Ok(Self::with_engine(settings, build_engine(settings, None)?))
+    }
 
-        Ok(Self {
-            transcriber,
-            chunk_duration_secs: 30.0, // Process in 30-second chunks
-        })
+    /// Create a pipeline that reuses the Whisper model held by `cache`
+    pub fn from_cache(settings: &Settings, cache: &mut ModelCache) -> Result<Self> {
+        Ok(Self::with_engine(
+            settings,
+            build_engine(settings, Some(cache))?,
+        ))
+    }
+
+    /// Create a pipeline for `recording`
+    ///
+    /// Confidential recordings are transcribed locally even when a remote
+    /// provider is configured, so their audio never leaves the machine. A
+    /// language chosen for the recording replaces `whisper.language`, with
+    /// "auto" detecting it.
+    pub fn for_recording(
+        settings: &Settings,
+        cache: &mut ModelCache,
+        recording: &Recording,
+    ) -> Result<Self> {
+        let force_local = recording.is_confidential() && !settings.transcription.is_local();
+        if !force_local && recording.language_override.is_none() {
+            return Self::from_cache(settings, cache);
+        }
+
+        let mut settings = settings.clone();
+        if force_local {
+            tracing::info!(
+                "Transcribing confidential recording {} locally",
+                recording.id
+            );
+            settings.transcription.provider = "local".to_string();
+        }
+        if let Some(language) = &recording.language_override {
+            settings.whisper.language = match language.as_str() {
+                "auto" => String::new(),
+                code => code.to_string(),
+            };
+        }
+        Self::from_cache(&settings, cache)
+    }
+
+    /// Language of the transcript, once something has been transcribed
+    pub fn language(&self) -> Option<String> {
+        self.engine.language()
+    }
+
+    /// Stretches without speech in the audio transcribed last, on the
+    /// recording's timeline
+    pub fn silences(&self) -> Vec<Silence> {
+        self.silences
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn set_silences(&self, silences: Vec<Silence>) {
+        *self.silences.lock().unwrap_or_else(|e| e.into_inner()) = silences;
+    }
+
+    fn with_engine(settings: &Settings, engine: Box<dyn TranscriptionEngine>) -> Self {
+        Self {
+            chunk_duration_secs: engine.window_secs(),
+            engine,
+            speaker_profiles: Vec::new(),
+            speaker_match_threshold: settings.whisper.speaker_match_threshold,
+            min_silence_secs: settings.whisper.min_silence_secs,
+            trim_silence: settings.whisper.trim_silence,
+            silence_threshold_db: settings.whisper.silence_threshold_db,
+            silences: Mutex::new(Vec::new()),
+            hallucination_filter: HallucinationFilter::from_settings(settings),
+            text_cleanup: TextCleanup::from_settings(settings),
+            merge_rules: MergeRules::from_settings(settings),
+            resume_from: Vec::new(),
+            save_checkpoint: None,
+        }
+    }
+
+    /// Label segments with enrolled speaker names when their voice matches
+    pub fn with_speaker_profiles(mut self, profiles: Vec<SpeakerProfile>) -> Self {
+        self.speaker_profiles = profiles;
+        self
+    }
+
+    /// Continue from `saved` progress and report new progress to `save`
+    ///
+    /// Saved progress only applies to a stream whose audio is unchanged;
+    /// otherwise that stream is transcribed from the start.
+    pub fn with_checkpoints(
+        mut self,
+        saved: Vec<TranscriptionCheckpoint>,
+        save: CheckpointCallback,
+    ) -> Self {
+        self.resume_from = saved;
+        self.save_checkpoint = Some(save);
+        self
     }
 
     /// Transcribe an audio file
@@ -40,52 +162,339 @@ impl TranscriptionPipeline {
         tracing::info!("Loading audio from: {}", audio_path);
         let samples = load_audio(path)?;
 
-        let sample_rate = 16000; // Whisper expects 16kHz
-        let chunk_samples = (self.chunk_duration_secs * sample_rate as f32) as usize;
+        self.transcribe_loaded(&samples, recording_id, progress_callback)
+            .await
+    }
 
-        let mut all_segments = Vec::new();
-        let mut offset_time = 0.0;
+    /// Transcribe the chunk files of a segmented recording as one recording
+    ///
+    /// Chunks are stitched in order, so timestamps run across chunk boundaries.
+    pub async fn transcribe_chunks(
+        &self,
+        chunk_paths: &[String],
+        recording_id: &str,
+        progress_callback: ProgressCallback,
+    ) -> Result<Vec<TranscriptSegment>> {
+        tracing::info!("Loading {} audio chunks", chunk_paths.len());
+        let samples = load_chunks(chunk_paths)?;
+
+        self.transcribe_loaded(&samples, recording_id, progress_callback)
+            .await
+    }
 
-        // Process in chunks
-        let chunks: Vec<_> = samples.chunks(chunk_samples).collect();
-        let total_chunks = chunks.len();
+    async fn transcribe_loaded(
+        &self,
+        samples: &[f32],
+        recording_id: &str,
+        progress_callback: ProgressCallback,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let (mut merged_segments, silences) = self
+            .transcribe_samples(samples, recording_id, "mixed", &|progress| {
+                progress_callback(progress)
+            })
+            .await?;
+        self.label_speakers(samples, &mut merged_segments);
+        self.set_silences(silences);
 
-        for (i, chunk) in chunks.iter().enumerate() {
-            tracing::debug!("Processing chunk {}/{}", i + 1, total_chunks);
+        tracing::info!("Transcription complete: {} segments", merged_segments.len());
+
+        Ok(merged_segments)
+    }
+
+    /// Transcribe separate system and microphone tracks
+    ///
+    /// Each track is transcribed on its own and its segments are labelled with
+    /// the source ("system" or "microphone") before being interleaved by time.
+    pub async fn transcribe_tracks(
+        &self,
+        system_path: &str,
+        mic_path: &str,
+        recording_id: &str,
+        progress_callback: ProgressCallback,
+    ) -> Result<Vec<TranscriptSegment>> {
+        tracing::info!("Loading system track from: {}", system_path);
+        let system_samples = load_audio(Path::new(system_path))?;
+        tracing::info!("Loading microphone track from: {}", mic_path);
+        let mic_samples = load_audio(Path::new(mic_path))?;
+
+        self.transcribe_track_samples(
+            &system_samples,
+            &mic_samples,
+            recording_id,
+            progress_callback,
+        )
+        .await
+    }
+
+    /// Transcribe already loaded 16kHz mono system and microphone tracks
+    ///
+    /// Both tracks must share the recording's timeline; see [`shift_track`].
+    pub async fn transcribe_track_samples(
+        &self,
+        system_samples: &[f32],
+        mic_samples: &[f32],
+        recording_id: &str,
+        progress_callback: ProgressCallback,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let (mut system_segments, system_silences) = self
+            .transcribe_samples(system_samples, recording_id, "system", &|progress| {
+                progress_callback(progress * 0.5)
+            })
+            .await?;
+        self.label_speakers(system_samples, &mut system_segments);
+
+        let (mut mic_segments, mic_silences) = self
+            .transcribe_samples(mic_samples, recording_id, "microphone", &|progress| {
+                progress_callback(0.5 + progress * 0.5)
+            })
+            .await?;
+        self.label_speakers(mic_samples, &mut mic_segments);
+
+        // The recording is only silent where neither track has speech
+        self.set_silences(common_silences(
+            &system_silences,
+            &mic_silences,
+            self.min_silence_secs,
+        ));
+
+        let segments = interleave_tracks(system_segments, mic_segments);
+
+        tracing::info!("Transcription complete: {} segments", segments.len());
+
+        Ok(segments)
+    }
+
+    fn label_speakers(&self, samples: &[f32], segments: &mut [TranscriptSegment]) {
+        let labelled = identify_speakers(
+            samples,
+            segments,
+            &self.speaker_profiles,
+            self.speaker_match_threshold,
+        );
+        if labelled > 0 {
+            tracing::info!("Labelled {} segments with enrolled speakers", labelled);
+        }
+    }
+
+    /// Saved progress of `stream` that lines up with this run's windows
+    fn resume_point(
+        &self,
+        stream: &str,
+        total_samples: usize,
+        windows: &[Range<usize>],
+    ) -> Option<&TranscriptionCheckpoint> {
+        self.resume_from.iter().find(|checkpoint| {
+            checkpoint.stream == stream
+                && checkpoint.total_samples == total_samples
+                && windows
+                    .iter()
+                    .any(|window| window.end == checkpoint.done_samples)
+        })
+    }
+
+    /// Transcribe 16kHz mono samples in windows of up to the engine's size
+    ///
+    /// Returns the segments and the silences found in the samples. Windows
+    /// are cut at pauses so words aren't split between them. With silence
+    /// trimming enabled, long silent runs are shortened first and segment
+    /// timestamps are mapped back onto the original audio. Windows finished
+    /// by an interrupted run of `stream` are not transcribed again.
+    async fn transcribe_samples(
+        &self,
+        samples: &[f32],
+        recording_id: &str,
+        stream: &str,
+        progress_callback: &(dyn Fn(f32) + Sync),
+    ) -> Result<(Vec<TranscriptSegment>, Vec<Silence>)> {
+        let silences = detect_silences(samples, self.min_silence_secs, self.silence_threshold_db);
+        let (trimmed, silence_map) = if self.trim_silence {
+            let (trimmed, map) =
+                trim_silence(samples, self.min_silence_secs, self.silence_threshold_db);
+            tracing::info!(
+                "Trimmed {:.1}s of silence before transcription",
+                map.removed_secs()
+            );
+            (Some(trimmed), Some(map))
+        } else {
+            (None, None)
+        };
+        let samples = trimmed.as_deref().unwrap_or(samples);
+
+        let sample_rate = 16000; // Whisper expects 16kHz
+        let windows = split_at_pauses(samples, self.chunk_duration_secs);
+
+        let resumed = self.resume_point(stream, samples.len(), &windows);
+        let done_samples = resumed.map_or(0, |checkpoint| checkpoint.done_samples);
+        let mut all_segments = resumed
+            .map(|checkpoint| checkpoint.segments.clone())
+            .unwrap_or_default();
+        if done_samples > 0 {
+            tracing::info!(
+                "Resuming transcription of {} at {:.0}s",
+                recording_id,
+                done_samples as f64 / sample_rate as f64
+            );
+        }
+
+        for (i, window) in windows.iter().enumerate() {
+            if window.end <= done_samples {
+                continue;
+            }
+            tracing::debug!("Processing chunk {}/{}", i + 1, windows.len());
 
             // Report progress
-            let progress = (i as f32 + 0.5) / total_chunks as f32;
+            let progress = (i as f32 + 0.5) / windows.len() as f32;
             progress_callback(progress);
 
             // Transcribe chunk
-            let mut segments = self.transcriber.transcribe(chunk, recording_id)?;
-
-            // Adjust timestamps for chunk offset
-            for segment in &mut segments {
-                segment.start_time += offset_time;
-                segment.end_time += offset_time;
-            }
+            let mut segments = self
+                .engine
+                .transcribe(&samples[window.clone()], recording_id)
+                .await?;
+            place_in_window(&mut segments, window.clone(), sample_rate);
 
             all_segments.extend(segments);
 
-            // Update offset for next chunk
-            offset_time += chunk.len() as f64 / sample_rate as f64;
+            if let Some(save) = &self.save_checkpoint {
+                save(&TranscriptionCheckpoint {
+                    stream: stream.to_string(),
+                    total_samples: samples.len(),
+                    done_samples: window.end,
+                    segments: all_segments.clone(),
+                });
+            }
         }
 
         // Final progress update
         progress_callback(1.0);
 
+        // Segment times still refer to `samples` here
+        if let Some(cleanup) = &self.text_cleanup {
+            all_segments = cleanup.apply(all_segments);
+        }
+
+        if let Some(filter) = &self.hallucination_filter {
+            all_segments = filter.apply(all_segments, samples);
+        }
+
+        if let Some(map) = silence_map {
+            for segment in &mut all_segments {
+                segment.start_time = map.to_original(segment.start_time);
+                segment.end_time = map.to_original(segment.end_time);
+                for word in &mut segment.words {
+                    word.start_time = map.to_original(word.start_time);
+                    word.end_time = map.to_original(word.end_time);
+                }
+            }
+        }
+
         // Merge adjacent segments if they're continuous
-        let merged_segments = merge_segments(all_segments);
+        Ok((merge_segments(all_segments, &self.merge_rules), silences))
+    }
+}
 
-        tracing::info!("Transcription complete: {} segments", merged_segments.len());
+/// Move segments timed relative to `window` onto the timeline of the whole audio
+///
+/// Offsets come from the window's exact sample position rather than a
+/// running sum of chunk durations. Times are clamped to the window, since
+/// Whisper can report an end past the audio it was given.
+fn place_in_window(segments: &mut [TranscriptSegment], window: Range<usize>, sample_rate: u32) {
+    let start = window.start as f64 / sample_rate as f64;
+    let end = window.end as f64 / sample_rate as f64;
+    for segment in segments {
+        segment.start_time = (start + segment.start_time).min(end);
+        segment.end_time = (start + segment.end_time).clamp(segment.start_time, end);
+        for word in &mut segment.words {
+            word.start_time = (start + word.start_time).min(end);
+            word.end_time = (start + word.end_time).clamp(word.start_time, end);
+        }
+    }
+}
 
-        Ok(merged_segments)
+/// Move a 16kHz mono track onto the recording timeline
+///
+/// A positive offset means the track starts that many seconds into the
+/// recording and is padded with leading silence; a negative offset drops the
+/// part of the track from before the recording began.
+pub fn shift_track(samples: &[f32], offset_secs: f64) -> Vec<f32> {
+    let offset = (offset_secs.abs() * 16000.0).round() as usize;
+    if offset_secs >= 0.0 {
+        let mut shifted = vec![0.0; offset];
+        shifted.extend_from_slice(samples);
+        shifted
+    } else {
+        samples.get(offset..).unwrap_or_default().to_vec()
+    }
+}
+
+/// Label per-track segments with their source and order them by start time
+///
+/// Segments already named after an enrolled speaker keep that name.
+fn interleave_tracks(
+    system: Vec<TranscriptSegment>,
+    mic: Vec<TranscriptSegment>,
+) -> Vec<TranscriptSegment> {
+    let mut segments: Vec<_> = system
+        .into_iter()
+        .map(|segment| (segment, "system"))
+        .chain(mic.into_iter().map(|segment| (segment, "microphone")))
+        .map(|(mut segment, speaker)| {
+            segment.speaker.get_or_insert_with(|| speaker.to_string());
+            segment
+        })
+        .collect();
+
+    segments.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    segments
+}
+
+/// When adjacent segments are joined into one
+///
+/// Subtitles want short segments and notes want whole paragraphs, so the
+/// limits come from the `whisper.merge_*` settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeRules {
+    /// Largest gap between segments that are merged (0 = never merge)
+    pub max_gap_secs: f64,
+    /// Longest merged segment (0 = no limit)
+    pub max_duration_secs: f64,
+    /// Keep sentence ends at segment ends
+    pub within_sentences: bool,
+}
+
+impl MergeRules {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            max_gap_secs: settings.whisper.merge_gap_secs,
+            max_duration_secs: settings.whisper.merge_max_secs,
+            within_sentences: settings.whisper.merge_within_sentences,
+        }
+    }
+
+    /// Whether `next` may be appended to `current`
+    fn allows(&self, current: &TranscriptSegment, next: &TranscriptSegment) -> bool {
+        let gap = next.start_time - current.end_time;
+        self.max_gap_secs > 0.0
+            && gap < self.max_gap_secs
+            && current.speaker == next.speaker
+            && (self.max_duration_secs <= 0.0
+                || next.end_time - current.start_time <= self.max_duration_secs)
+            && !(self.within_sentences && ends_sentence(&current.text))
+    }
+}
+
+impl Default for MergeRules {
+    fn default() -> Self {
+        Self {
+            max_gap_secs: 0.5,
+            max_duration_secs: 0.0,
+            within_sentences: false,
+        }
     }
 }
 
-/// Merge adjacent segments with small gaps
-fn merge_segments(segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
+/// Merge adjacent segments of the same speaker as `rules` allow
+fn merge_segments(segments: Vec<TranscriptSegment>, rules: &MergeRules) -> Vec<TranscriptSegment> {
     if segments.is_empty() {
         return segments;
     }
@@ -95,13 +504,17 @@ fn merge_segments(segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
     let mut current = iter.next().unwrap();
 
     for segment in iter {
-        // If segments are close together (within 0.5s) and from same speaker, merge
-        let gap = segment.start_time - current.end_time;
-
-        if gap < 0.5 && current.speaker == segment.speaker {
+        if rules.allows(&current, &segment) {
+            current.confidence = merged_confidence(&current, &segment);
             current.end_time = segment.end_time;
             current.text.push(' ');
             current.text.push_str(&segment.text);
+            current.words.extend(segment.words);
+            current.translated_text =
+                match (current.translated_text.take(), segment.translated_text) {
+                    (Some(first), Some(second)) => Some(format!("{} {}", first, second)),
+                    (first, second) => first.or(second),
+                };
         } else {
             merged.push(current);
             current = segment;
@@ -111,3 +524,136 @@ fn merge_segments(segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
     merged.push(current);
     merged
 }
+
+/// Confidence of two merged segments, weighted by their durations
+fn merged_confidence(a: &TranscriptSegment, b: &TranscriptSegment) -> Option<f64> {
+    match (a.confidence, b.confidence) {
+        (Some(x), Some(y)) => {
+            let (wa, wb) = (a.end_time - a.start_time, b.end_time - b.start_time);
+            if wa + wb > 0.0 {
+                Some((x * wa + y * wb) / (wa + wb))
+            } else {
+                Some((x + y) / 2.0)
+            }
+        }
+        (x, y) => x.or(y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, text: &str) -> TranscriptSegment {
+        TranscriptSegment::new("rec".to_string(), start, start + 1.0, text.to_string())
+    }
+
+    #[test]
+    fn interleaves_tracks_by_start_time_with_source_labels() {
+        let system = vec![segment(0.0, "hello"), segment(4.0, "bye")];
+        let mic = vec![segment(2.0, "hi")];
+
+        let segments = interleave_tracks(system, mic);
+        let texts: Vec<_> = segments.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["hello", "hi", "bye"]);
+        assert_eq!(segments[0].speaker.as_deref(), Some("system"));
+        assert_eq!(segments[1].speaker.as_deref(), Some("microphone"));
+    }
+
+    #[test]
+    fn merged_segments_average_their_confidence_by_duration() {
+        let mut first = segment(0.0, "one");
+        first.confidence = Some(0.9);
+        let mut second = TranscriptSegment::new("rec".to_string(), 1.2, 4.2, "two".to_string());
+        second.confidence = Some(0.5);
+
+        let merged = merge_segments(vec![first, second], &MergeRules::default());
+        assert_eq!(merged.len(), 1);
+        assert!((merged[0].confidence.unwrap() - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn merging_respects_length_and_sentence_limits() {
+        let segments = || {
+            vec![
+                segment(0.0, "we ship"),
+                segment(1.2, "on friday."),
+                segment(2.4, "then"),
+                segment(3.6, "we rest"),
+            ]
+        };
+        let texts = |merged: Vec<TranscriptSegment>| -> Vec<String> {
+            merged.into_iter().map(|s| s.text).collect()
+        };
+
+        let default = merge_segments(segments(), &MergeRules::default());
+        assert_eq!(texts(default), ["we ship on friday. then we rest"]);
+
+        let sentences = MergeRules {
+            within_sentences: true,
+            ..MergeRules::default()
+        };
+        assert_eq!(
+            texts(merge_segments(segments(), &sentences)),
+            ["we ship on friday.", "then we rest"]
+        );
+
+        let short = MergeRules {
+            max_duration_secs: 2.5,
+            ..MergeRules::default()
+        };
+        assert_eq!(
+            texts(merge_segments(segments(), &short)),
+            ["we ship on friday.", "then we rest"]
+        );
+
+        let never = MergeRules {
+            max_gap_secs: 0.0,
+            ..MergeRules::default()
+        };
+        assert_eq!(merge_segments(segments(), &never).len(), 4);
+    }
+
+    #[test]
+    fn shifts_tracks_onto_the_recording_timeline() {
+        let track = vec![1.0; 16000];
+
+        let late = shift_track(&track, 0.5);
+        assert_eq!(late.len(), 24000);
+        assert_eq!(late[7999], 0.0);
+        assert_eq!(late[8000], 1.0);
+
+        assert_eq!(shift_track(&track, -0.25).len(), 12000);
+        assert!(shift_track(&track, -2.0).is_empty());
+    }
+
+    #[test]
+    fn long_recordings_end_at_the_audio_duration() {
+        // Three hours and a bit, in 30-second windows
+        let sample_rate = 16000;
+        let window = 30 * sample_rate as usize;
+        let total = 3 * 3600 * sample_rate as usize + 12_345;
+
+        let mut placed = Vec::new();
+        for start in (0..total).step_by(window) {
+            let end = (start + window).min(total);
+            // Whisper reports the last segment as running to the window length
+            let mut segments = vec![TranscriptSegment::new(
+                "rec".to_string(),
+                0.0,
+                30.0,
+                "text".to_string(),
+            )];
+            place_in_window(&mut segments, start..end, sample_rate);
+            placed.extend(segments);
+        }
+
+        assert_eq!(placed.len(), 361);
+        assert_eq!(placed[360].start_time, 10800.0);
+        assert_eq!(placed[359].end_time, 10800.0);
+        assert_eq!(
+            placed.last().unwrap().end_time,
+            total as f64 / sample_rate as f64
+        );
+    }
+}
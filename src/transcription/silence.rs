@@ -0,0 +1,381 @@
+//! Silence detection, trimming, and window splitting before transcription
+//!
+//! Long idle stretches cost Whisper as much time as speech. Silent runs are
+//! detected with a simple energy threshold, which serves as voice activity
+//! detection: the runs are stored with the recording so viewers can mark
+//! them, and shortened to a brief pause before transcription. A
+//! [`SilenceMap`] records where the kept audio came from so segment
+//! timestamps can be mapped back onto the original recording.
+//!
+//! The same energy measure picks where to split audio into transcription
+//! windows, so a cut falls in a pause instead of through a word.
+
+use std::ops::Range;
+
+use crate::storage::{Silence, TranscriptSegment};
+
+/// Sample rate the trimmer works at (matches `load_audio` output)
+const SAMPLE_RATE: usize = 16000;
+/// Frame length used for the energy measurement (20ms)
+const FRAME_LEN: usize = 320;
+/// Silence kept on each side of a trimmed run so speech isn't clipped
+const PAD_SECS: f64 = 0.25;
+/// How far before a window's nominal end a quieter cut point is looked for
+const SPLIT_SEARCH_SECS: f64 = 5.0;
+/// Frames averaged when comparing cut points, so a stop consonant inside a
+/// word doesn't pass for a pause (100ms)
+const SPLIT_SMOOTHING_FRAMES: usize = 5;
+/// Shortest silence marked between transcript segments
+const MARKED_SILENCE_SECS: f64 = 60.0;
+
+/// A stretch of kept audio: where it sits in the trimmed and original timelines
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct KeptSpan {
+    trimmed_start: f64,
+    original_start: f64,
+}
+
+/// Maps timestamps in trimmed audio back to the original recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct SilenceMap {
+    spans: Vec<KeptSpan>,
+    removed_secs: f64,
+}
+
+impl SilenceMap {
+    /// Original-recording time for a time in the trimmed audio
+    pub fn to_original(&self, trimmed: f64) -> f64 {
+        let index = self
+            .spans
+            .partition_point(|span| span.trimmed_start <= trimmed)
+            .saturating_sub(1);
+        match self.spans.get(index) {
+            Some(span) => span.original_start + (trimmed - span.trimmed_start),
+            None => trimmed,
+        }
+    }
+
+    /// Total seconds of silence removed
+    pub fn removed_secs(&self) -> f64 {
+        self.removed_secs
+    }
+}
+
+/// Sample ranges of silent runs lasting at least `min_silence_secs`
+///
+/// A frame is silent when its RMS level is below `threshold_db` dBFS.
+fn silent_runs(samples: &[f32], min_silence_secs: f64, threshold_db: f32) -> Vec<Range<usize>> {
+    let threshold_ms = 10f32.powf(threshold_db / 20.0).powi(2);
+    let min_frames = ((min_silence_secs * SAMPLE_RATE as f64) as usize / FRAME_LEN).max(1);
+
+    let silent: Vec<bool> = samples
+        .chunks(FRAME_LEN)
+        .map(|frame| frame.iter().map(|s| s * s).sum::<f32>() / (frame.len() as f32) < threshold_ms)
+        .collect();
+
+    let mut runs = Vec::new();
+    let mut frame = 0;
+    while frame < silent.len() {
+        if !silent[frame] {
+            frame += 1;
+            continue;
+        }
+        let run_start = frame;
+        while frame < silent.len() && silent[frame] {
+            frame += 1;
+        }
+        if frame - run_start >= min_frames {
+            runs.push(run_start * FRAME_LEN..(frame * FRAME_LEN).min(samples.len()));
+        }
+    }
+    runs
+}
+
+/// Silent stretches of at least `min_silence_secs` in 16kHz mono samples
+///
+/// A stretch is silent when every 20ms frame in it has an RMS level below
+/// `threshold_db` dBFS.
+pub fn detect_silences(samples: &[f32], min_silence_secs: f64, threshold_db: f32) -> Vec<Silence> {
+    silent_runs(samples, min_silence_secs, threshold_db)
+        .into_iter()
+        .map(|run| Silence {
+            start_time: run.start as f64 / SAMPLE_RATE as f64,
+            end_time: run.end as f64 / SAMPLE_RATE as f64,
+        })
+        .collect()
+}
+
+/// Stretches silent in both `a` and `b` that last at least `min_secs`
+///
+/// Both lists must be sorted by start time, as [`detect_silences`] returns
+/// them.
+pub fn common_silences(a: &[Silence], b: &[Silence], min_secs: f64) -> Vec<Silence> {
+    let mut common = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start_time = a[i].start_time.max(b[j].start_time);
+        let end_time = a[i].end_time.min(b[j].end_time);
+        if end_time - start_time >= min_secs {
+            common.push(Silence {
+                start_time,
+                end_time,
+            });
+        }
+        if a[i].end_time < b[j].end_time {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    common
+}
+
+/// Silences long enough to mark in a transcript, each with the index of the
+/// segment it precedes
+///
+/// A silence goes before the first segment starting after its midpoint, so
+/// segment times that reach a little into it don't move the marker.
+pub fn silence_markers(
+    segments: &[TranscriptSegment],
+    silences: &[Silence],
+) -> Vec<(usize, Silence)> {
+    silences
+        .iter()
+        .filter(|silence| silence.duration() >= MARKED_SILENCE_SECS)
+        .map(|silence| {
+            let middle = (silence.start_time + silence.end_time) / 2.0;
+            let index = segments.partition_point(|segment| segment.start_time < middle);
+            (index, *silence)
+        })
+        .collect()
+}
+
+/// Marker line for a silence, e.g. "—— 12 min silence ——"
+pub fn silence_marker_text(silence: &Silence) -> String {
+    let minutes = (silence.duration() / 60.0).round() as u64;
+    let length = match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{} min", minutes),
+        (hours, 0) => format!("{} h", hours),
+        (hours, minutes) => format!("{} h {} min", hours, minutes),
+    };
+    format!("—— {} silence ——", length)
+}
+
+/// Shorten silent runs longer than `min_silence_secs` in 16kHz mono samples
+///
+/// A frame is silent when its RMS level is below `threshold_db` dBFS. Each
+/// trimmed run keeps a short pause at both ends.
+pub fn trim_silence(
+    samples: &[f32],
+    min_silence_secs: f64,
+    threshold_db: f32,
+) -> (Vec<f32>, SilenceMap) {
+    let pad = (PAD_SECS * SAMPLE_RATE as f64) as usize;
+
+    // Sample ranges to drop: the middle of each long silent run
+    let cuts: Vec<(usize, usize)> = silent_runs(samples, min_silence_secs, threshold_db)
+        .into_iter()
+        .map(|run| (run.start + pad, run.end.saturating_sub(pad)))
+        .filter(|(start, end)| end > start)
+        .collect();
+
+    let mut trimmed = Vec::with_capacity(samples.len());
+    let mut spans = vec![KeptSpan {
+        trimmed_start: 0.0,
+        original_start: 0.0,
+    }];
+    let mut position = 0;
+    let mut removed = 0;
+    for (start, end) in cuts {
+        trimmed.extend_from_slice(&samples[position..start]);
+        removed += end - start;
+        spans.push(KeptSpan {
+            trimmed_start: trimmed.len() as f64 / SAMPLE_RATE as f64,
+            original_start: end as f64 / SAMPLE_RATE as f64,
+        });
+        position = end;
+    }
+    trimmed.extend_from_slice(&samples[position..]);
+
+    let map = SilenceMap {
+        spans,
+        removed_secs: removed as f64 / SAMPLE_RATE as f64,
+    };
+    (trimmed, map)
+}
+
+/// Whether the 16kHz mono samples between `start_secs` and `end_secs` are
+/// silent as a whole: their RMS level is below `threshold_db` dBFS
+pub fn is_silent_span(samples: &[f32], start_secs: f64, end_secs: f64, threshold_db: f32) -> bool {
+    let start = ((start_secs * SAMPLE_RATE as f64) as usize).min(samples.len());
+    let end = ((end_secs * SAMPLE_RATE as f64) as usize).clamp(start, samples.len());
+    let span = &samples[start..end];
+    if span.is_empty() {
+        return false;
+    }
+
+    let threshold_ms = 10f32.powf(threshold_db / 20.0).powi(2);
+    span.iter().map(|s| s * s).sum::<f32>() / (span.len() as f32) < threshold_ms
+}
+
+/// Split 16kHz mono samples into windows of at most `window_secs`
+///
+/// Each window ends at the quietest point in the last few seconds before
+/// its nominal end rather than exactly at it. The returned ranges cover the
+/// samples in order without gaps.
+pub fn split_at_pauses(samples: &[f32], window_secs: f32) -> Vec<Range<usize>> {
+    let window = ((window_secs as f64 * SAMPLE_RATE as f64) as usize).max(FRAME_LEN);
+    // Keep windows from shrinking much below their nominal length
+    let search = ((SPLIT_SEARCH_SECS * SAMPLE_RATE as f64) as usize).min(window / 6);
+
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while samples.len() - start > window {
+        let end = start + window;
+        let cut = quietest_point(&samples[end - search..end]).map_or(end, |at| end - search + at);
+        windows.push(start..cut);
+        start = cut;
+    }
+    if start < samples.len() {
+        windows.push(start..samples.len());
+    }
+    windows
+}
+
+/// Offset of the middle of the quietest stretch of `samples`
+///
+/// Later stretches win ties so windows stay as long as possible.
+fn quietest_point(samples: &[f32]) -> Option<usize> {
+    let energies: Vec<f32> = samples
+        .chunks_exact(FRAME_LEN)
+        .map(|frame| frame.iter().map(|s| s * s).sum::<f32>())
+        .collect();
+    let span = SPLIT_SMOOTHING_FRAMES.min(energies.len());
+    if span == 0 {
+        return None;
+    }
+
+    let (first, _) = energies
+        .windows(span)
+        .map(|stretch| stretch.iter().sum::<f32>())
+        .enumerate()
+        .rev()
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    Some((first * FRAME_LEN + span * FRAME_LEN / 2).min(samples.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(secs: f64) -> Vec<f32> {
+        (0..(secs * SAMPLE_RATE as f64) as usize)
+            .map(|i| 0.3 * (i as f32 * 0.1).sin())
+            .collect()
+    }
+
+    fn silence(secs: f64) -> Vec<f32> {
+        vec![0.0; (secs * SAMPLE_RATE as f64) as usize]
+    }
+
+    #[test]
+    fn trims_long_silence_and_maps_times_back() {
+        let mut samples = tone(2.0);
+        samples.extend(silence(10.0));
+        samples.extend(tone(2.0));
+
+        let (trimmed, map) = trim_silence(&samples, 2.0, -45.0);
+
+        let trimmed_secs = trimmed.len() as f64 / SAMPLE_RATE as f64;
+        assert!((trimmed_secs - 4.5).abs() < 0.01);
+        assert!((map.removed_secs() - 9.5).abs() < 0.01);
+
+        // Speech before the gap is unchanged, speech after it shifts back
+        assert!((map.to_original(1.0) - 1.0).abs() < 1e-9);
+        assert!((map.to_original(3.0) - 12.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn windows_end_in_pauses_near_their_nominal_length() {
+        // Speech with a pause that ends 2 s before the 30 s mark
+        let mut samples = tone(27.5);
+        samples.extend(silence(0.5));
+        samples.extend(tone(40.0));
+
+        let windows = split_at_pauses(&samples, 30.0);
+        assert_eq!(windows.len(), 3);
+        let cut = windows[0].end as f64 / SAMPLE_RATE as f64;
+        assert!((27.5..28.0).contains(&cut), "cut at {}", cut);
+        assert_eq!(windows[1].start, windows[0].end);
+        assert_eq!(windows[2].end, samples.len());
+        assert!(windows.iter().all(|w| w.len() <= 30 * SAMPLE_RATE));
+
+        assert_eq!(split_at_pauses(&samples[..1000], 30.0), vec![0..1000]);
+        assert!(split_at_pauses(&[], 30.0).is_empty());
+    }
+
+    #[test]
+    fn detects_silent_spans() {
+        let mut samples = tone(1.0);
+        samples.extend(silence(2.0));
+
+        assert!(!is_silent_span(&samples, 0.0, 1.0, -45.0));
+        assert!(is_silent_span(&samples, 1.0, 3.0, -45.0));
+        assert!(!is_silent_span(&samples, 0.5, 3.0, -45.0));
+        // Spans outside the audio can't be judged
+        assert!(!is_silent_span(&samples, 5.0, 6.0, -45.0));
+    }
+
+    #[test]
+    fn detects_silences_and_marks_long_ones_between_segments() {
+        let mut samples = tone(2.0);
+        samples.extend(silence(90.0));
+        samples.extend(tone(2.0));
+        samples.extend(silence(3.0));
+        samples.extend(tone(1.0));
+
+        let silences = detect_silences(&samples, 2.0, -45.0);
+        assert_eq!(silences.len(), 2);
+        assert!((silences[0].start_time - 2.0).abs() < 0.03);
+        assert!((silences[0].end_time - 92.0).abs() < 0.03);
+
+        let segments: Vec<TranscriptSegment> = [0.0, 92.1, 97.0]
+            .iter()
+            .map(|&start| {
+                TranscriptSegment::new("rec".to_string(), start, start + 1.0, "Hi".to_string())
+            })
+            .collect();
+        let markers = silence_markers(&segments, &silences);
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].0, 1);
+        assert_eq!(silence_marker_text(&markers[0].1), "—— 2 min silence ——");
+    }
+
+    #[test]
+    fn tracks_are_silent_together_only_where_both_are() {
+        let span = |start_time, end_time| Silence {
+            start_time,
+            end_time,
+        };
+        let system = [span(0.0, 10.0), span(20.0, 100.0)];
+        let mic = [span(5.0, 30.0), span(40.0, 41.0), span(50.0, 120.0)];
+
+        assert_eq!(
+            common_silences(&system, &mic, 2.0),
+            [span(5.0, 10.0), span(20.0, 30.0), span(50.0, 100.0)]
+        );
+    }
+
+    #[test]
+    fn keeps_short_pauses() {
+        let mut samples = tone(1.0);
+        samples.extend(silence(1.0));
+        samples.extend(tone(1.0));
+
+        let (trimmed, map) = trim_silence(&samples, 2.0, -45.0);
+
+        assert_eq!(trimmed.len(), samples.len());
+        assert_eq!(map.removed_secs(), 0.0);
+        assert!((map.to_original(2.5) - 2.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,348 @@
+//! Speaker identification from enrolled voice profiles
+//!
+//! A voice embedding here is the mean and spread of log mel-band energies over
+//! the voiced frames of a clip. It is cheap to compute and needs no model, and
+//! it separates distinct voices well enough to put a known name on a speaker
+//! when the match is clear. Unclear matches keep their existing label.
+
+use std::collections::HashMap;
+
+use crate::audio::fft;
+use crate::storage::{SpeakerProfile, TranscriptSegment};
+
+/// Sample rate embeddings are computed at (matches `load_audio` output)
+const SAMPLE_RATE: usize = 16000;
+/// 25ms analysis window
+const FRAME_LEN: usize = 400;
+/// 10ms hop between frames
+const HOP_LEN: usize = 160;
+const FFT_LEN: usize = 512;
+const MEL_BANDS: usize = 24;
+/// Frames quieter than the loudest frame by this much (dB) are not voiced
+const VOICED_RANGE_DB: f32 = 35.0;
+/// Minimum voiced audio for a usable embedding
+const MIN_VOICED_FRAMES: usize = 50;
+/// Segments shorter than this are too short to identify on their own
+const MIN_SEGMENT_SECS: f64 = 1.5;
+
+/// Fixed-length voice embedding
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoiceEmbedding(pub Vec<f32>);
+
+impl VoiceEmbedding {
+    /// Compute an embedding from 16kHz mono samples
+    ///
+    /// Returns `None` when the clip holds too little voiced audio.
+    pub fn from_samples(samples: &[f32]) -> Option<Self> {
+        if samples.len() < FRAME_LEN {
+            return None;
+        }
+
+        let filterbank = mel_filterbank();
+        let window: Vec<f32> = (0..FRAME_LEN)
+            .map(|i| 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / (FRAME_LEN - 1) as f32).cos())
+            .collect();
+
+        let mut frames = Vec::new();
+        let mut start = 0;
+        while start + FRAME_LEN <= samples.len() {
+            let mut re = vec![0.0f32; FFT_LEN];
+            let mut im = vec![0.0f32; FFT_LEN];
+            for i in 0..FRAME_LEN {
+                re[i] = samples[start + i] * window[i];
+            }
+            fft(&mut re, &mut im);
+
+            let power: Vec<f32> = (0..=FFT_LEN / 2)
+                .map(|k| re[k] * re[k] + im[k] * im[k])
+                .collect();
+            let energy_db = 10.0 * (power.iter().sum::<f32>() + 1e-10).log10();
+            let bands: Vec<f32> = filterbank
+                .iter()
+                .map(|filter| {
+                    let energy: f32 = filter.iter().map(|&(bin, w)| power[bin] * w).sum();
+                    (energy + 1e-10).ln()
+                })
+                .collect();
+
+            frames.push((energy_db, bands));
+            start += HOP_LEN;
+        }
+
+        let loudest = frames
+            .iter()
+            .map(|(db, _)| *db)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let voiced: Vec<&Vec<f32>> = frames
+            .iter()
+            .filter(|(db, _)| *db >= loudest - VOICED_RANGE_DB)
+            .map(|(_, bands)| bands)
+            .collect();
+
+        if voiced.len() < MIN_VOICED_FRAMES {
+            return None;
+        }
+
+        let count = voiced.len() as f32;
+        let mean: Vec<f32> = (0..MEL_BANDS)
+            .map(|b| voiced.iter().map(|f| f[b]).sum::<f32>() / count)
+            .collect();
+        let spread: Vec<f32> = (0..MEL_BANDS)
+            .map(|b| {
+                let var = voiced.iter().map(|f| (f[b] - mean[b]).powi(2)).sum::<f32>() / count;
+                var.sqrt()
+            })
+            .collect();
+
+        // Remove overall loudness so only the spectral shape remains
+        let level = mean.iter().sum::<f32>() / MEL_BANDS as f32;
+        let mut values: Vec<f32> = mean.iter().map(|m| m - level).collect();
+        values.extend(spread);
+        Some(Self(values))
+    }
+
+    /// Cosine similarity in [-1, 1] against a stored embedding
+    pub fn similarity(&self, other: &[f32]) -> f32 {
+        if self.0.len() != other.len() {
+            return 0.0;
+        }
+
+        let dot: f32 = self.0.iter().zip(other).map(|(a, b)| a * b).sum();
+        let norm_a = self.0.iter().map(|a| a * a).sum::<f32>().sqrt();
+        let norm_b = other.iter().map(|b| b * b).sum::<f32>().sqrt();
+        if norm_a == 0.0 || norm_b == 0.0 {
+            return 0.0;
+        }
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Label segments with enrolled speaker names where the voice matches
+///
+/// Segments that already share a speaker label (from diarization) are matched
+/// as a group and renamed together. Unlabelled segments are matched one by
+/// one when they are long enough. Returns the number of segments relabelled.
+pub fn identify_speakers(
+    samples: &[f32],
+    segments: &mut [TranscriptSegment],
+    profiles: &[SpeakerProfile],
+    threshold: f32,
+) -> usize {
+    if profiles.is_empty() {
+        return 0;
+    }
+
+    let clip = |segment: &TranscriptSegment| -> &[f32] {
+        let start = ((segment.start_time.max(0.0)) * SAMPLE_RATE as f64) as usize;
+        let end = ((segment.end_time.max(0.0)) * SAMPLE_RATE as f64) as usize;
+        let end = end.min(samples.len());
+        &samples[start.min(end)..end]
+    };
+
+    let mut relabelled = 0;
+
+    // Diarized speakers: match the pooled audio of each label
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, segment) in segments.iter().enumerate() {
+        if let Some(label) = &segment.speaker {
+            groups.entry(label.clone()).or_default().push(index);
+        }
+    }
+    for indices in groups.values() {
+        let pooled: Vec<f32> = indices
+            .iter()
+            .flat_map(|&i| clip(&segments[i]).iter().copied())
+            .collect();
+        if let Some(name) = best_match(&pooled, profiles, threshold) {
+            for &i in indices {
+                segments[i].speaker = Some(name.to_string());
+                relabelled += 1;
+            }
+        }
+    }
+
+    // Unlabelled segments: match each one that is long enough
+    for segment in segments.iter_mut() {
+        if segment.speaker.is_some() || segment.end_time - segment.start_time < MIN_SEGMENT_SECS {
+            continue;
+        }
+        if let Some(name) = best_match(clip(segment), profiles, threshold) {
+            segment.speaker = Some(name.to_string());
+            relabelled += 1;
+        }
+    }
+
+    relabelled
+}
+
+fn best_match<'a>(
+    samples: &[f32],
+    profiles: &'a [SpeakerProfile],
+    threshold: f32,
+) -> Option<&'a str> {
+    let embedding = VoiceEmbedding::from_samples(samples)?;
+
+    profiles
+        .iter()
+        .map(|profile| (profile, embedding.similarity(&profile.embedding)))
+        .filter(|(_, score)| *score >= threshold)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(profile, _)| profile.name.as_str())
+}
+
+/// Triangular mel filters as (fft bin, weight) lists
+fn mel_filterbank() -> Vec<Vec<(usize, f32)>> {
+    let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+    let mel_to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+
+    let low = hz_to_mel(80.0);
+    let high = hz_to_mel(7600.0);
+    let bin_of = |hz: f32| hz * FFT_LEN as f32 / SAMPLE_RATE as f32;
+    let edges: Vec<f32> = (0..MEL_BANDS + 2)
+        .map(|i| {
+            bin_of(mel_to_hz(
+                low + (high - low) * i as f32 / (MEL_BANDS + 1) as f32,
+            ))
+        })
+        .collect();
+
+    (0..MEL_BANDS)
+        .map(|band| {
+            let (left, center, right) = (edges[band], edges[band + 1], edges[band + 2]);
+            (left.floor() as usize..=right.ceil() as usize)
+                .filter(|&bin| bin <= FFT_LEN / 2)
+                .filter_map(|bin| {
+                    let pos = bin as f32;
+                    let weight = if pos < center {
+                        (pos - left) / (center - left)
+                    } else {
+                        (right - pos) / (right - center)
+                    };
+                    (weight > 0.0).then_some((bin, weight))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Parse a `MM:SS-MM:SS` (or `HH:MM:SS`, or plain seconds) range into seconds
+pub fn parse_time_range(range: &str) -> anyhow::Result<(f64, f64)> {
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| anyhow::anyhow!("Invalid range '{}'. Use e.g. 05:00-06:00", range))?;
+    let start = parse_timestamp(start)?;
+    let end = parse_timestamp(end)?;
+    if end <= start {
+        anyhow::bail!("Invalid range '{}': end must be after start", range);
+    }
+    Ok((start, end))
+}
+
+/// Parse a signed offset such as `2.5s`, `-1.5`, or `01:10` into seconds
+pub fn parse_offset(value: &str) -> anyhow::Result<f64> {
+    let value = value.trim();
+    let (sign, magnitude) = match value.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let magnitude = magnitude.strip_suffix('s').unwrap_or(magnitude);
+    Ok(sign
+        * parse_timestamp(magnitude).map_err(|_| {
+            anyhow::anyhow!("Invalid offset '{}'. Use e.g. 2.5s, -1.5s, or 01:10", value)
+        })?)
+}
+
+fn parse_timestamp(value: &str) -> anyhow::Result<f64> {
+    let mut seconds = 0.0;
+    for part in value.trim().split(':') {
+        let part: f64 = part
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid timestamp '{}'", value.trim()))?;
+        seconds = seconds * 60.0 + part;
+    }
+    Ok(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Harmonic tone with a given fundamental, a rough stand-in for a voice
+    fn voice(fundamental: f32, brightness: f32, secs: f32) -> Vec<f32> {
+        (0..(secs * SAMPLE_RATE as f32) as usize)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                (1..12)
+                    .map(|h| {
+                        let amp = brightness.powi(h);
+                        amp * (std::f32::consts::TAU * fundamental * h as f32 * t).sin()
+                    })
+                    .sum::<f32>()
+                    * 0.1
+            })
+            .collect()
+    }
+
+    fn profile(name: &str, samples: &[f32]) -> SpeakerProfile {
+        SpeakerProfile::new(
+            name.to_string(),
+            VoiceEmbedding::from_samples(samples).unwrap().0,
+        )
+    }
+
+    #[test]
+    fn same_voice_is_more_similar_than_a_different_voice() {
+        let low = VoiceEmbedding::from_samples(&voice(110.0, 0.8, 2.0)).unwrap();
+        let low_again = VoiceEmbedding::from_samples(&voice(112.0, 0.8, 3.0)).unwrap();
+        let high = VoiceEmbedding::from_samples(&voice(240.0, 0.4, 2.0)).unwrap();
+
+        assert!(low.similarity(&low_again.0) > 0.95);
+        assert!(low.similarity(&low_again.0) > low.similarity(&high.0));
+    }
+
+    #[test]
+    fn silence_has_no_embedding() {
+        assert!(VoiceEmbedding::from_samples(&[0.0; 100]).is_none());
+    }
+
+    #[test]
+    fn labels_matching_segments_by_profile_name() {
+        let mut samples = voice(110.0, 0.8, 3.0);
+        samples.extend(voice(240.0, 0.4, 3.0));
+
+        let profiles = vec![
+            profile("Alice", &voice(110.0, 0.8, 2.0)),
+            profile("Bob", &voice(240.0, 0.4, 2.0)),
+        ];
+        let mut segments = vec![
+            TranscriptSegment::new("r".to_string(), 0.0, 3.0, "hi".to_string()),
+            TranscriptSegment::new("r".to_string(), 3.0, 6.0, "hello".to_string()),
+        ];
+        segments[1].speaker = Some("SPEAKER_1".to_string());
+
+        let relabelled = identify_speakers(&samples, &mut segments, &profiles, 0.95);
+        assert_eq!(relabelled, 2);
+        assert_eq!(segments[0].speaker.as_deref(), Some("Alice"));
+        assert_eq!(segments[1].speaker.as_deref(), Some("Bob"));
+    }
+
+    #[test]
+    fn parses_time_ranges() {
+        assert_eq!(parse_time_range("05:00-06:00").unwrap(), (300.0, 360.0));
+        assert_eq!(
+            parse_time_range("1:00:00-1:00:30").unwrap(),
+            (3600.0, 3630.0)
+        );
+        assert_eq!(parse_time_range("10-25.5").unwrap(), (10.0, 25.5));
+        assert!(parse_time_range("06:00-05:00").is_err());
+        assert!(parse_time_range("05:00").is_err());
+    }
+
+    #[test]
+    fn parses_signed_offsets() {
+        assert_eq!(parse_offset("2.5s").unwrap(), 2.5);
+        assert_eq!(parse_offset("-1.5").unwrap(), -1.5);
+        assert_eq!(parse_offset("+01:10").unwrap(), 70.0);
+        assert!(parse_offset("soon").is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! Offline transcription with Vosk
+//!
+//! Vosk's small models need a fraction of the memory and CPU of Whisper and
+//! decode audio as a stream, which suits weak hardware at some cost in
+//! accuracy. Requires libvosk and building with the `vosk` feature.
+
+use serde::Deserialize;
+
+use crate::storage::{TranscriptSegment, TranscriptWord};
+
+/// One utterance as reported by `vosk_recognizer_result`
+#[cfg_attr(not(feature = "vosk"), allow(dead_code))]
+#[derive(Debug, Default, Deserialize)]
+struct VoskResult {
+    #[serde(default)]
+    result: Vec<VoskWord>,
+    #[serde(default)]
+    text: String,
+}
+
+#[cfg_attr(not(feature = "vosk"), allow(dead_code))]
+#[derive(Debug, Deserialize)]
+struct VoskWord {
+    word: String,
+    start: f64,
+    end: f64,
+    #[serde(default)]
+    conf: Option<f64>,
+}
+
+/// Turn one utterance into a segment spanning its words
+///
+/// Returns `None` for utterances without recognized words.
+#[cfg_attr(not(feature = "vosk"), allow(dead_code))]
+fn utterance_segment(
+    json: &str,
+    recording_id: &str,
+    word_timestamps: bool,
+) -> Option<TranscriptSegment> {
+    let utterance: VoskResult = serde_json::from_str(json).ok()?;
+    let text = utterance.text.trim();
+    let (first, last) = (utterance.result.first()?, utterance.result.last()?);
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut segment = TranscriptSegment::new(
+        recording_id.to_string(),
+        first.start,
+        last.end,
+        text.to_string(),
+    );
+    if word_timestamps {
+        segment.words = utterance
+            .result
+            .iter()
+            .map(|word| TranscriptWord {
+                start_time: word.start,
+                end_time: word.end,
+                text: word.word.clone(),
+                confidence: word.conf,
+            })
+            .collect();
+    }
+    Some(segment)
+}
+
+#[cfg(feature = "vosk")]
+pub use engine::VoskTranscriber;
+
+#[cfg(feature = "vosk")]
+mod engine {
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use std::ffi::{c_char, c_float, c_int, c_short, CStr, CString};
+    use std::path::Path;
+
+    use super::utterance_segment;
+    use crate::config::Settings;
+    use crate::storage::TranscriptSegment;
+    use crate::transcription::engine::TranscriptionEngine;
+
+    /// Samples fed to the recognizer per call (0.2 s)
+    const FEED_SAMPLES: usize = 3200;
+
+    #[repr(C)]
+    struct VoskModel {
+        _private: [u8; 0],
+    }
+
+    #[repr(C)]
+    struct VoskRecognizer {
+        _private: [u8; 0],
+    }
+
+    #[link(name = "vosk")]
+    extern "C" {
+        fn vosk_set_log_level(level: c_int);
+        fn vosk_model_new(path: *const c_char) -> *mut VoskModel;
+        fn vosk_model_free(model: *mut VoskModel);
+        fn vosk_recognizer_new(model: *mut VoskModel, sample_rate: c_float) -> *mut VoskRecognizer;
+        fn vosk_recognizer_set_words(recognizer: *mut VoskRecognizer, words: c_int);
+        fn vosk_recognizer_accept_waveform_s(
+            recognizer: *mut VoskRecognizer,
+            data: *const c_short,
+            length: c_int,
+        ) -> c_int;
+        fn vosk_recognizer_result(recognizer: *mut VoskRecognizer) -> *const c_char;
+        fn vosk_recognizer_final_result(recognizer: *mut VoskRecognizer) -> *const c_char;
+        fn vosk_recognizer_free(recognizer: *mut VoskRecognizer);
+    }
+
+    /// Vosk-based transcriber
+    pub struct VoskTranscriber {
+        model: *mut VoskModel,
+        word_timestamps: bool,
+    }
+
+    // The Vosk model is immutable once loaded and may be shared between
+    // recognizers on any thread; each `transcribe` call owns its recognizer.
+    unsafe impl Send for VoskTranscriber {}
+    unsafe impl Sync for VoskTranscriber {}
+
+    impl VoskTranscriber {
+        pub fn new(settings: &Settings) -> Result<Self> {
+            let path = &settings.transcription.vosk_model;
+            if path.as_os_str().is_empty() {
+                anyhow::bail!(
+                    "No Vosk model configured. Download one from https://alphacephei.com/vosk/models, unpack it, and set transcription.vosk_model to its directory."
+                );
+            }
+            if !path.is_dir() {
+                anyhow::bail!("Vosk model directory not found: {}", path.display());
+            }
+
+            Ok(Self {
+                model: load_model(path)?,
+                word_timestamps: settings.whisper.word_timestamps,
+            })
+        }
+
+        /// Parse the recognizer's current JSON result into a segment
+        fn take_result(
+            &self,
+            json: *const c_char,
+            recording_id: &str,
+        ) -> Option<TranscriptSegment> {
+            if json.is_null() {
+                return None;
+            }
+            // SAFETY: Vosk returns a NUL-terminated string owned by the
+            // recognizer, valid until the next call on it
+            let json = unsafe { CStr::from_ptr(json) }.to_string_lossy();
+            utterance_segment(&json, recording_id, self.word_timestamps)
+        }
+    }
+
+    fn load_model(path: &Path) -> Result<*mut VoskModel> {
+        let c_path = CString::new(path.to_string_lossy().as_bytes())?;
+        tracing::info!("Loading Vosk model from {}", path.display());
+        // SAFETY: `c_path` is a valid NUL-terminated string for the call
+        let model = unsafe {
+            vosk_set_log_level(-1);
+            vosk_model_new(c_path.as_ptr())
+        };
+        if model.is_null() {
+            anyhow::bail!("Failed to load Vosk model from {}", path.display());
+        }
+        Ok(model)
+    }
+
+    impl Drop for VoskTranscriber {
+        fn drop(&mut self) {
+            // SAFETY: the model was created by `vosk_model_new` and every
+            // recognizer using it has been freed
+            unsafe { vosk_model_free(self.model) };
+        }
+    }
+
+    #[async_trait]
+    impl TranscriptionEngine for VoskTranscriber {
+        fn name(&self) -> &str {
+            "vosk"
+        }
+
+        /// Vosk decodes incrementally, so memory does not grow with the window
+        fn window_secs(&self) -> f32 {
+            300.0
+        }
+
+        async fn transcribe(
+            &self,
+            samples: &[f32],
+            recording_id: &str,
+        ) -> Result<Vec<TranscriptSegment>> {
+            // SAFETY: the model outlives the recognizer, which is freed below
+            let recognizer = unsafe { vosk_recognizer_new(self.model, 16000.0) };
+            if recognizer.is_null() {
+                anyhow::bail!("Failed to create Vosk recognizer");
+            }
+            // Word timings are needed to time the segments themselves
+            unsafe { vosk_recognizer_set_words(recognizer, 1) };
+
+            let mut segments = Vec::new();
+            for chunk in samples.chunks(FEED_SAMPLES) {
+                let pcm: Vec<i16> = chunk
+                    .iter()
+                    .map(|&sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+                    .collect();
+                // SAFETY: `pcm` holds `pcm.len()` samples for the call
+                let utterance_done = unsafe {
+                    vosk_recognizer_accept_waveform_s(recognizer, pcm.as_ptr(), pcm.len() as c_int)
+                };
+                if utterance_done == 1 {
+                    let json = unsafe { vosk_recognizer_result(recognizer) };
+                    segments.extend(self.take_result(json, recording_id));
+                }
+            }
+            let json = unsafe { vosk_recognizer_final_result(recognizer) };
+            segments.extend(self.take_result(json, recording_id));
+            unsafe { vosk_recognizer_free(recognizer) };
+
+            Ok(segments)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UTTERANCE: &str = r#"{
+        "result": [
+            {"conf": 1.0, "end": 1.02, "start": 0.51, "word": "hello"},
+            {"conf": 0.82, "end": 1.5, "start": 1.05, "word": "world"}
+        ],
+        "text": "hello world"
+    }"#;
+
+    #[test]
+    fn utterance_spans_its_words() {
+        let segment = utterance_segment(UTTERANCE, "rec", true).unwrap();
+        assert_eq!(segment.text, "hello world");
+        assert_eq!(segment.start_time, 0.51);
+        assert_eq!(segment.end_time, 1.5);
+        assert_eq!(segment.words.len(), 2);
+        assert_eq!(segment.words[1].confidence, Some(0.82));
+
+        let without_words = utterance_segment(UTTERANCE, "rec", false).unwrap();
+        assert!(without_words.words.is_empty());
+
+        assert!(utterance_segment(r#"{"text": ""}"#, "rec", true).is_none());
+    }
+}
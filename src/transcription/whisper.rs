@@ -1,66 +1,124 @@
 //! Whisper transcription using whisper-rs
 
 use anyhow::{Context, Result};
-use std::path::Path;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
 
+use crate::audio::decode_ogg_opus;
 use crate::config::Settings;
-use crate::storage::TranscriptSegment;
+use crate::storage::{TranscriptSegment, TranscriptWord};
+use crate::transcription::engine::{attach_translations, LanguageTally, TranscriptionEngine};
+use crate::transcription::error::ModelNotFound;
 
 /// Whisper-based transcriber
 pub struct WhisperTranscriber {
-    ctx: WhisperContext,
+    ctx: Arc<WhisperContext>,
     language: Option<String>,
     translate: bool,
+    /// No-speech probability above which Whisper treats a segment as silence
+    no_speech_threshold: Option<f32>,
+    /// Extract per-word timestamps from the token timings
+    word_timestamps: bool,
+    sampling: SamplingStrategy,
+    temperature: f32,
+    entropy_threshold: f32,
+    /// Languages detected when `language` is unset
+    detected: LanguageTally,
 }
 
 impl WhisperTranscriber {
     /// Create a new transcriber with the specified model
     pub fn new(settings: &Settings) -> Result<Self> {
-        let model_path = settings.model_path();
-
-        if !model_path.exists() {
-            anyhow::bail!(
-                "Whisper model not found at {}. Please download the model first.\n\
-                 Run: minutes model download {}",
-                model_path.display(),
-                settings.whisper.model
-            );
-        }
+        let ctx = load_model(&settings.model_path(), settings)?;
+        Ok(Self::with_context(settings, Arc::new(ctx)))
+    }
 
-        let ctx = WhisperContext::new_with_params(
-            model_path.to_str().unwrap(),
-            WhisperContextParameters::default(),
-        )
-        .context("Failed to load Whisper model")?;
+    /// Create a transcriber that reuses the model held by `cache`
+    pub fn from_cache(settings: &Settings, cache: &mut ModelCache) -> Result<Self> {
+        let ctx = cache.context(settings)?;
+        Ok(Self::with_context(settings, ctx))
+    }
 
+    fn with_context(settings: &Settings, ctx: Arc<WhisperContext>) -> Self {
         let language = if settings.whisper.language.is_empty() {
             None
         } else {
             Some(settings.whisper.language.clone())
         };
 
-        Ok(Self {
+        Self {
             ctx,
             language,
             translate: settings.whisper.translate,
-        })
+            no_speech_threshold: settings
+                .whisper
+                .filter_hallucinations
+                .then_some(settings.whisper.no_speech_threshold),
+            word_timestamps: settings.whisper.word_timestamps,
+            sampling: sampling_strategy(settings),
+            temperature: settings.whisper.temperature,
+            entropy_threshold: settings.whisper.entropy_threshold,
+            detected: LanguageTally::default(),
+        }
+    }
+
+    /// Text tokens of segment `segment` with their timing
+    fn segment_tokens(&self, state: &WhisperState, segment: i32) -> Result<Vec<Token>> {
+        let eot = self.ctx.token_eot();
+        let count = state
+            .full_n_tokens(segment)
+            .context("Failed to get token count")?;
+
+        let mut tokens = Vec::new();
+        for i in 0..count {
+            let data = state
+                .full_get_token_data(segment, i)
+                .context("Failed to get token data")?;
+            // Timestamp and other special tokens follow end-of-text
+            if data.id >= eot {
+                continue;
+            }
+            tokens.push(Token {
+                bytes: state
+                    .full_get_token_bytes(segment, i)
+                    .context("Failed to get token text")?,
+                start: data.t0,
+                end: data.t1,
+                probability: data.p,
+            });
+        }
+        Ok(tokens)
     }
 
-    /// Transcribe audio samples
-    pub fn transcribe(
+    /// Run Whisper over `samples`, transcribing or translating into English
+    ///
+    /// Returns the segments and the language of the audio, configured or
+    /// detected.
+    fn infer(
         &self,
         samples: &[f32],
         recording_id: &str,
-    ) -> Result<Vec<TranscriptSegment>> {
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        translate: bool,
+    ) -> Result<(Vec<TranscriptSegment>, Option<String>)> {
+        let mut params = FullParams::new(self.sampling.clone());
 
         // Configure parameters
         params.set_print_special(false);
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
-        params.set_translate(self.translate);
+        params.set_translate(translate);
+        params.set_token_timestamps(self.word_timestamps && !translate);
+        params.set_temperature(self.temperature);
+        params.set_entropy_thold(self.entropy_threshold);
+        if let Some(threshold) = self.no_speech_threshold {
+            params.set_no_speech_thold(threshold);
+        }
 
         if let Some(ref lang) = self.language {
             params.set_language(Some(lang));
@@ -102,20 +160,358 @@ impl WhisperTranscriber {
                 continue;
             }
 
-            segments.push(TranscriptSegment::new(
-                recording_id.to_string(),
-                start_time,
-                end_time,
-                text,
-            ));
+            let tokens = self.segment_tokens(&state, i)?;
+            let mut segment =
+                TranscriptSegment::new(recording_id.to_string(), start_time, end_time, text);
+            segment.confidence = mean_probability(&tokens);
+            if self.word_timestamps && !translate {
+                segment.words = group_words(tokens);
+            }
+            segments.push(segment);
+        }
+
+        let language = self.language.clone().or_else(|| {
+            state
+                .full_lang_id_from_state()
+                .ok()
+                .and_then(whisper_rs::get_lang_str)
+                .map(str::to_string)
+        });
+        Ok((segments, language))
+    }
+}
+
+#[async_trait]
+impl TranscriptionEngine for WhisperTranscriber {
+    /// Transcribe a window; with `whisper.translate`, also translate it
+    ///
+    /// The translation runs as a second pass over the same audio and is
+    /// stored next to the original text, so neither language is lost.
+    async fn transcribe(
+        &self,
+        samples: &[f32],
+        recording_id: &str,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let (mut segments, language) = self.infer(samples, recording_id, false)?;
+
+        // Windows without speech give no useful detection
+        if segments.is_empty() {
+            return Ok(segments);
+        }
+        if self.language.is_none() {
+            if let Some(language) = &language {
+                self.detected.record(language);
+            }
+        }
+
+        if self.translate && language.as_deref() != Some("en") {
+            let (translated, _) = self.infer(samples, recording_id, true)?;
+            attach_translations(&mut segments, translated);
         }
 
         Ok(segments)
     }
+
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn language(&self) -> Option<String> {
+        self.language
+            .clone()
+            .or_else(|| self.detected.most_common())
+    }
+}
+
+/// Decoding strategy from `whisper.beam_size` and `whisper.best_of`
+fn sampling_strategy(settings: &Settings) -> SamplingStrategy {
+    match settings.whisper.beam_size {
+        0 => SamplingStrategy::Greedy {
+            best_of: settings.whisper.best_of.max(1) as i32,
+        },
+        beam_size => SamplingStrategy::BeamSearch {
+            beam_size: beam_size as i32,
+            // Not used by whisper.cpp
+            patience: -1.0,
+        },
+    }
+}
+
+/// A text token with its timing in centiseconds
+struct Token {
+    bytes: Vec<u8>,
+    start: i64,
+    end: i64,
+    probability: f32,
+}
+
+/// Average probability of a segment's text tokens
+fn mean_probability(tokens: &[Token]) -> Option<f64> {
+    if tokens.is_empty() {
+        return None;
+    }
+    let sum: f64 = tokens
+        .iter()
+        .map(|token| f64::from(token.probability))
+        .sum();
+    Some(sum / tokens.len() as f64)
+}
+
+/// Join tokens into words; a token starting with a space begins a new word
+///
+/// Bytes are joined before decoding, since Whisper can split a multi-byte
+/// character across tokens.
+fn group_words(tokens: Vec<Token>) -> Vec<TranscriptWord> {
+    let mut groups: Vec<Vec<Token>> = Vec::new();
+    for token in tokens {
+        match groups.last_mut() {
+            Some(group) if !token.bytes.starts_with(b" ") => group.push(token),
+            _ => groups.push(vec![token]),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter_map(|group| {
+            let bytes: Vec<u8> = group.iter().flat_map(|token| token.bytes.clone()).collect();
+            let text = String::from_utf8_lossy(&bytes).trim().to_string();
+            let (first, last) = (group.first()?, group.last()?);
+            let probability =
+                group.iter().map(|token| token.probability).sum::<f32>() / group.len() as f32;
+            (!text.is_empty()).then(|| TranscriptWord {
+                start_time: first.start as f64 / 100.0,
+                end_time: last.end.max(first.start) as f64 / 100.0,
+                text,
+                confidence: Some(f64::from(probability)),
+            })
+        })
+        .collect()
+}
+
+/// Whisper model kept loaded between transcriptions
+///
+/// Loading a model takes seconds and, for the larger models, gigabytes of
+/// memory. The daemon keeps one cache for its transcription queue and only
+/// reloads when the configured model path or the model file itself changes.
+#[derive(Default)]
+pub struct ModelCache {
+    loaded: Option<LoadedModel>,
+}
+
+struct LoadedModel {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    ctx: Arc<WhisperContext>,
+}
+
+impl ModelCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Context for the configured model, loading it on first use or after a change
+    pub fn context(&mut self, settings: &Settings) -> Result<Arc<WhisperContext>> {
+        let path = settings.model_path();
+        let modified = std::fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok();
+
+        if let Some(loaded) = &self.loaded {
+            if loaded.path == path && loaded.modified == modified {
+                return Ok(loaded.ctx.clone());
+            }
+            tracing::info!("Whisper model changed, reloading {}", path.display());
+        }
+
+        // Release the old model before loading the new one
+        self.loaded = None;
+        let ctx = Arc::new(load_model(&path, settings)?);
+        self.loaded = Some(LoadedModel {
+            path,
+            modified,
+            ctx: ctx.clone(),
+        });
+        Ok(ctx)
+    }
+
+    /// Whether a model is currently loaded
+    pub fn is_loaded(&self) -> bool {
+        self.loaded.is_some()
+    }
+}
+
+fn load_model(model_path: &Path, settings: &Settings) -> Result<WhisperContext> {
+    if !model_path.exists() {
+        return Err(ModelNotFound {
+            path: model_path.to_path_buf(),
+            model: settings.whisper.model.clone(),
+        }
+        .into());
+    }
+
+    tracing::info!("Loading Whisper model from {}", model_path.display());
+    WhisperContext::new_with_params(
+        model_path.to_str().unwrap(),
+        WhisperContextParameters::default(),
+    )
+    .context("Failed to load Whisper model")
 }
 
-/// Load audio from a WAV file and convert to f32 samples at 16kHz mono
+/// Load audio from a WAV, OGG Opus, or FLAC file and convert to f32 samples at 16kHz mono
 pub fn load_audio(path: &Path) -> Result<Vec<f32>> {
+    let (sample_rate, samples) = load_mono(path)?;
+
+    // Resample to 16kHz if needed
+    let samples = if sample_rate != 16000 {
+        resample(&samples, sample_rate, 16000)
+    } else {
+        samples
+    };
+
+    Ok(samples)
+}
+
+/// Decode an audio file at its own sample rate, keeping its channels
+///
+/// Returns the sample rate, the channel count and the interleaved samples.
+pub fn load_native(path: &Path) -> Result<(u32, usize, Vec<f32>)> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match extension.as_str() {
+        "ogg" | "oga" | "opus" => load_ogg_samples(path),
+        "flac" => load_flac_samples(path),
+        _ => load_wav_samples(path),
+    }
+}
+
+/// Decode an audio file to mono samples at its own sample rate
+fn load_mono(path: &Path) -> Result<(u32, Vec<f32>)> {
+    let (sample_rate, channels, samples) = load_native(path)?;
+
+    // Convert to mono if stereo
+    let samples = if channels > 1 {
+        samples
+            .chunks(channels)
+            .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
+            .collect()
+    } else {
+        samples
+    };
+
+    Ok((sample_rate, samples))
+}
+
+/// Load the chunk files of a segmented recording as one continuous stream
+///
+/// A chunk that cannot be read (for example one cut short by a crash) is
+/// skipped with a warning, so the rest of the recording is still usable.
+/// Each chunk ends at the 16kHz sample matching the source audio read so
+/// far, so per-chunk rounding does not add up over a multi-hour recording.
+pub fn load_chunks(paths: &[String]) -> Result<Vec<f32>> {
+    let mut samples = Vec::new();
+    let mut loaded = 0;
+    // Start of the current run of chunks at one sample rate, and its length
+    let mut run_start = 0;
+    let mut run_rate = None;
+    let mut run_frames = 0u64;
+
+    for path in paths {
+        match load_mono(Path::new(path)) {
+            Ok((sample_rate, chunk)) => {
+                if run_rate != Some(sample_rate) {
+                    run_start = samples.len();
+                    run_rate = Some(sample_rate);
+                    run_frames = 0;
+                }
+                run_frames += chunk.len() as u64;
+                let end = run_start + resampled_len(run_frames, sample_rate, 16000);
+
+                let mut chunk = resample(&chunk, sample_rate, 16000);
+                chunk.resize(end - samples.len(), 0.0);
+                samples.extend(chunk);
+                loaded += 1;
+            }
+            Err(e) => tracing::warn!("Skipping unreadable chunk {}: {:#}", path, e),
+        }
+    }
+
+    if loaded == 0 {
+        anyhow::bail!("None of the {} recording chunks could be read", paths.len());
+    }
+    Ok(samples)
+}
+
+/// Load the chunk files of a segmented recording as one stream at the
+/// sample rate and channel count of the first readable chunk
+///
+/// Unreadable chunks are skipped as in [`load_chunks`]; a chunk captured at
+/// another rate or channel count is converted to match.
+pub fn load_chunks_native(paths: &[String]) -> Result<(u32, usize, Vec<f32>)> {
+    let mut format = None;
+    let mut samples = Vec::new();
+
+    for path in paths {
+        match load_native(Path::new(path)) {
+            Ok((sample_rate, channels, chunk)) => {
+                let (to_rate, to_channels) = *format.get_or_insert((sample_rate, channels));
+                samples.extend(convert(
+                    chunk,
+                    (sample_rate, channels),
+                    (to_rate, to_channels),
+                ));
+            }
+            Err(e) => tracing::warn!("Skipping unreadable chunk {}: {:#}", path, e),
+        }
+    }
+
+    let (sample_rate, channels) = format
+        .with_context(|| format!("None of the {} recording chunks could be read", paths.len()))?;
+    Ok((sample_rate, channels, samples))
+}
+
+/// Interleaved samples converted from one (rate, channels) format to another
+///
+/// Mono is copied to every channel; other channel counts are mixed down first.
+fn convert(samples: Vec<f32>, from: (u32, usize), to: (u32, usize)) -> Vec<f32> {
+    if from == to {
+        return samples;
+    }
+    let ((from_rate, from_channels), (to_rate, to_channels)) = (from, to);
+
+    let planes: Vec<Vec<f32>> = if from_channels == to_channels {
+        (0..from_channels)
+            .map(|channel| {
+                samples
+                    .iter()
+                    .skip(channel)
+                    .step_by(from_channels)
+                    .copied()
+                    .collect()
+            })
+            .collect()
+    } else {
+        let mono: Vec<f32> = samples
+            .chunks(from_channels)
+            .map(|frame| frame.iter().sum::<f32>() / from_channels as f32)
+            .collect();
+        vec![mono; to_channels]
+    };
+    let planes: Vec<Vec<f32>> = planes
+        .iter()
+        .map(|plane| resample(plane, from_rate, to_rate))
+        .collect();
+
+    let frames = planes.first().map_or(0, Vec::len);
+    (0..frames)
+        .flat_map(|frame| planes.iter().map(move |plane| plane[frame]))
+        .collect()
+}
+
+fn load_wav_samples(path: &Path) -> Result<(u32, usize, Vec<f32>)> {
     let reader = hound::WavReader::open(path)
         .with_context(|| format!("Failed to open audio file: {}", path.display()))?;
 
@@ -153,39 +549,42 @@ pub fn load_audio(path: &Path) -> Result<Vec<f32>> {
         ),
     };
 
-    // Convert to mono if stereo
-    let samples = if channels > 1 {
-        samples
-            .chunks(channels)
-            .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
-            .collect()
-    } else {
-        samples
-    };
+    Ok((sample_rate, channels, samples))
+}
 
-    // Resample to 16kHz if needed
-    let samples = if sample_rate != 16000 {
-        resample(&samples, sample_rate, 16000)
-    } else {
-        samples
-    };
+fn load_ogg_samples(path: &Path) -> Result<(u32, usize, Vec<f32>)> {
+    let (sample_rate, channels, samples) = decode_ogg_opus(path)?;
 
-    Ok(samples)
+    tracing::debug!(
+        "Loading audio: {} Hz, {} channels, OGG Opus",
+        sample_rate,
+        channels
+    );
+
+    let samples = samples.into_iter().map(|s| s as f32 / 32768.0).collect();
+    Ok((sample_rate, channels as usize, samples))
 }
 
 /// Simple linear resampling
+///
+/// Source positions are computed with integer arithmetic, so even hours
+/// into a file a sample lands exactly where its timestamp says.
 fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
-    let ratio = from_rate as f64 / to_rate as f64;
-    let new_len = (samples.len() as f64 / ratio) as usize;
+    if from_rate == to_rate {
+        return samples.to_vec();
+    }
+
+    let new_len = resampled_len(samples.len() as u64, from_rate, to_rate);
+    let (from_rate, to_rate) = (from_rate as u64, to_rate as u64);
     let mut result = Vec::with_capacity(new_len);
 
-    for i in 0..new_len {
-        let src_pos = i as f64 * ratio;
-        let src_idx = src_pos as usize;
-        let frac = src_pos - src_idx as f64;
+    for i in 0..new_len as u64 {
+        let src_pos = i * from_rate;
+        let src_idx = (src_pos / to_rate) as usize;
+        let frac = (src_pos % to_rate) as f32 / to_rate as f32;
 
         let sample = if src_idx + 1 < samples.len() {
-            samples[src_idx] * (1.0 - frac as f32) + samples[src_idx + 1] * frac as f32
+            samples[src_idx] * (1.0 - frac) + samples[src_idx + 1] * frac
         } else if src_idx < samples.len() {
             samples[src_idx]
         } else {
@@ -197,3 +596,163 @@ fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
 
     result
 }
+
+/// Number of samples `frames` source samples make at `to_rate`, rounded to nearest
+fn resampled_len(frames: u64, from_rate: u32, to_rate: u32) -> usize {
+    let (from_rate, to_rate) = (from_rate as u64, to_rate as u64);
+    ((frames * to_rate + from_rate / 2) / from_rate) as usize
+}
+
+fn load_flac_samples(path: &Path) -> Result<(u32, usize, Vec<f32>)> {
+    let mut reader = claxon::FlacReader::open(path)
+        .with_context(|| format!("Failed to open FLAC file: {}", path.display()))?;
+
+    let info = reader.streaminfo();
+    tracing::debug!(
+        "Loading audio: {} Hz, {} channels, FLAC {}-bit",
+        info.sample_rate,
+        info.channels,
+        info.bits_per_sample
+    );
+
+    let scale = (1u64 << (info.bits_per_sample - 1)) as f32;
+    let samples = reader
+        .samples()
+        .map(|s| s.map(|s| s as f32 / scale))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to decode FLAC file: {}", path.display()))?;
+
+    Ok((info.sample_rate, info.channels as usize, samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn beam_size_selects_beam_search() {
+        let mut settings = Settings::default();
+        assert!(matches!(
+            sampling_strategy(&settings),
+            SamplingStrategy::Greedy { best_of: 5 }
+        ));
+
+        settings.whisper.beam_size = 4;
+        assert!(matches!(
+            sampling_strategy(&settings),
+            SamplingStrategy::BeamSearch { beam_size: 4, .. }
+        ));
+    }
+
+    #[test]
+    fn model_cache_reports_missing_model_and_stays_empty() {
+        let dir = tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings.whisper.models_dir = dir.path().to_path_buf();
+
+        let mut cache = ModelCache::new();
+        let error = cache.context(&settings).err().unwrap();
+        assert!(error.to_string().contains("Whisper model not found"));
+        assert!(!cache.is_loaded());
+    }
+
+    #[test]
+    fn stitched_chunks_keep_the_duration_of_the_source_audio() {
+        let dir = tempdir().unwrap();
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        // Each 4411-frame chunk is 1600.36 samples at 16kHz
+        let paths: Vec<String> = (0..10)
+            .map(|i| {
+                let path = dir.path().join(format!("rec.{:03}.wav", i + 1));
+                let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+                for _ in 0..4411 {
+                    writer.write_sample(1000i16).unwrap();
+                }
+                writer.finalize().unwrap();
+                path.to_string_lossy().into_owned()
+            })
+            .collect();
+
+        let samples = load_chunks(&paths).unwrap();
+        assert_eq!(samples.len(), resampled_len(44110, 44100, 16000));
+        assert_eq!(samples.len(), 16004);
+    }
+
+    #[test]
+    fn native_chunks_keep_the_first_chunk_format() {
+        let dir = tempdir().unwrap();
+        let write = |name: &str, channels: u16, sample_rate: u32, frames: usize| {
+            let spec = hound::WavSpec {
+                channels,
+                sample_rate,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            };
+            let path = dir.path().join(name);
+            let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+            for frame in 0..frames {
+                for channel in 0..channels {
+                    // Stereo has a silent left channel
+                    let silent = channels == 2 && channel == 0;
+                    let sample = if silent { 0 } else { 16384 + frame as i16 % 2 };
+                    writer.write_sample(sample).unwrap();
+                }
+            }
+            writer.finalize().unwrap();
+            path.to_string_lossy().into_owned()
+        };
+        let paths = [
+            write("rec.001.wav", 2, 48000, 4800),
+            write("rec.002.wav", 1, 16000, 1600),
+        ];
+
+        let (sample_rate, channels, samples) = load_chunks_native(&paths).unwrap();
+        assert_eq!((sample_rate, channels), (48000, 2));
+        assert_eq!(samples.len(), 2 * (4800 + 4800));
+        // The first chunk keeps its channels apart
+        assert_eq!(samples[0], 0.0);
+        assert!(samples[1] > 0.49);
+        // The mono chunk is copied to both channels
+        assert_eq!(samples[2 * 4800 + 100], samples[2 * 4800 + 101]);
+    }
+
+    #[test]
+    fn tokens_are_grouped_into_timed_words() {
+        let token = |text: &[u8], start, end| Token {
+            bytes: text.to_vec(),
+            start,
+            end,
+            probability: 0.5,
+        };
+        // "Grüße" split inside the two-byte "ü"
+        let words = group_words(vec![
+            token(b" Gr\xc3", 10, 30),
+            token(b"\xbc\xc3\x9fe", 30, 55),
+            token(b" all", 60, 80),
+            token(b".", 80, 82),
+        ]);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Grüße");
+        assert_eq!((words[0].start_time, words[0].end_time), (0.1, 0.55));
+        assert_eq!(words[1].text, "all.");
+        assert_eq!((words[1].start_time, words[1].end_time), (0.6, 0.82));
+        assert_eq!(words[1].confidence, Some(0.5));
+    }
+
+    #[test]
+    fn resampled_length_is_exact_for_long_recordings() {
+        let three_hours = 3 * 3600;
+        assert_eq!(
+            resampled_len(three_hours * 44100, 44100, 16000),
+            three_hours as usize * 16000
+        );
+        assert_eq!(resample(&[0.0; 44100], 44100, 16000).len(), 16000);
+    }
+}
@@ -5,19 +5,33 @@ use crossterm::event::KeyCode;
 use ratatui::prelude::*;
 use std::time::{Duration, Instant};
 
+use crate::calendar;
 use crate::config::Settings;
 use crate::daemon::client::DaemonClient;
 use crate::daemon::ipc::{DaemonRequest, RecordingStatus};
-use crate::storage::Database;
-use crate::tui::screens::{BrowserScreen, DashboardScreen, ViewerScreen};
+use crate::storage::{Database, Recording, RecordingFilter, Sensitivity};
+use crate::transcription::download_model;
+use crate::tui::screens::{
+    detect_issues, BrowserScreen, DashboardScreen, DownloadState, SettingsScreen, SetupScreen,
+    ViewerScreen,
+};
+use crate::tui::state::TuiState;
 use crate::tui::widgets::HelpPopup;
 
+/// How often the dashboard re-reads the calendar file
+const CALENDAR_REFRESH: Duration = Duration::from_secs(60);
+
+/// Transcript matches looked up for the browser's search
+const BROWSER_SEARCH_LIMIT: usize = 200;
+
 /// Current screen
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppScreen {
     Dashboard,
     Browser,
     Viewer,
+    Setup,
+    Settings,
 }
 
 /// Main application state
@@ -31,28 +45,51 @@ pub struct App {
     dashboard: DashboardScreen,
     browser: BrowserScreen,
     viewer: ViewerScreen,
+    setup: SetupScreen,
+    settings_screen: SettingsScreen,
 
     // Daemon state
     daemon_status: RecordingStatus,
+    daemon_running: bool,
     last_status_update: Instant,
+
+    // Setup state
+    tui_state: TuiState,
+    setup_checked: bool,
+    pipewire_available: bool,
+
+    // Calendar state
+    last_calendar_update: Option<Instant>,
 }
 
 impl App {
     /// Create a new app instance
     pub fn new(settings: Settings) -> Result<Self> {
         let db = Database::open(&settings)?;
-        let recordings = db.list_recordings(100)?;
+        let recordings = db.list_recordings(&RecordingFilter::default(), 100)?;
+        let mut dashboard = DashboardScreen::new();
+        dashboard.set_stats(db.library_stats()?);
+
+        let tui_state = TuiState::load(&settings);
+        let settings_screen = SettingsScreen::new(&settings);
 
         Ok(Self {
             settings,
             current_screen: AppScreen::Dashboard,
             previous_screen: None,
             show_help: false,
-            dashboard: DashboardScreen::new(),
+            dashboard,
             browser: BrowserScreen::new(recordings),
             viewer: ViewerScreen::new(),
+            setup: SetupScreen::new(),
+            settings_screen,
             daemon_status: RecordingStatus::Idle,
+            daemon_running: false,
             last_status_update: Instant::now(),
+            tui_state,
+            setup_checked: false,
+            pipewire_available: crate::audio::pipewire_available(),
+            last_calendar_update: None,
         })
     }
 
@@ -70,6 +107,12 @@ impl App {
             AppScreen::Viewer => {
                 self.viewer.draw(frame, area, &self.settings);
             }
+            AppScreen::Setup => {
+                self.setup.draw(frame, area);
+            }
+            AppScreen::Settings => {
+                self.settings_screen.draw(frame, area);
+            }
         }
 
         // Draw help popup if active
@@ -95,6 +138,12 @@ impl App {
             AppScreen::Viewer => {
                 self.handle_viewer_key(key)?;
             }
+            AppScreen::Setup => {
+                self.handle_setup_key(key).await;
+            }
+            AppScreen::Settings => {
+                self.handle_settings_key(key);
+            }
         }
 
         Ok(())
@@ -107,9 +156,15 @@ impl App {
                 // Toggle recording
                 self.toggle_recording().await?;
             }
+            KeyCode::Char('m') => {
+                self.record_next_meeting().await?;
+            }
             KeyCode::Char('l') | KeyCode::Tab => {
                 self.switch_screen(AppScreen::Browser);
             }
+            KeyCode::Char('o') => {
+                self.open_setup().await;
+            }
             _ => {}
         }
         Ok(())
@@ -136,14 +191,46 @@ impl App {
                 self.switch_screen(AppScreen::Dashboard);
             }
             _ => {
-                self.browser.handle_key(key);
+                if self.browser.handle_key(key) {
+                    self.search_transcripts()?;
+                }
             }
         }
         Ok(())
     }
 
+    /// Match the browser's search against transcripts as well as titles
+    fn search_transcripts(&mut self) -> Result<()> {
+        // Every word counts as a prefix, so results follow along while typing
+        let query = self
+            .browser
+            .search_query()
+            .split_whitespace()
+            .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let hits = if query.is_empty() {
+            Vec::new()
+        } else {
+            Database::open(&self.settings)?.search_transcripts(
+                &query,
+                &RecordingFilter::default(),
+                BROWSER_SEARCH_LIMIT,
+            )?
+        };
+        self.browser.set_transcript_hits(hits);
+        Ok(())
+    }
+
     /// Handle viewer key input
     fn handle_viewer_key(&mut self, key: KeyCode) -> Result<()> {
+        if self.viewer.is_renaming() {
+            if let Some((label, name)) = self.viewer.handle_rename_key(key) {
+                self.rename_speaker(&label, &name)?;
+            }
+            return Ok(());
+        }
+
         match key {
             KeyCode::Up | KeyCode::Char('k') => {
                 self.viewer.scroll_up();
@@ -163,30 +250,229 @@ impl App {
             KeyCode::End | KeyCode::Char('G') => {
                 self.viewer.scroll_to_bottom();
             }
+            KeyCode::Char('n') => {
+                self.viewer.start_rename();
+            }
+            KeyCode::Char('a') => {
+                self.viewer.toggle_analytics();
+            }
+            KeyCode::Char('t') => {
+                self.accept_suggested_tags()?;
+            }
             _ => {}
         }
         Ok(())
     }
 
+    /// Add the tags suggested for the recording open in the viewer to its tags
+    fn accept_suggested_tags(&mut self) -> Result<()> {
+        let Some(recording_id) = self.viewer.recording_id().map(str::to_string) else {
+            return Ok(());
+        };
+        let Some((tags, suggested)) = self.viewer.tags() else {
+            return Ok(());
+        };
+        if suggested.is_empty() {
+            return Ok(());
+        }
+        let mut tags = tags.to_vec();
+        for tag in suggested {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+
+        let db = Database::open(&self.settings)?;
+        db.set_recording_tags(&recording_id, &tags)?;
+        db.set_suggested_tags(&recording_id, &[])?;
+        self.viewer.set_tags(tags, Vec::new());
+        Ok(())
+    }
+
+    /// Rename a speaker of the recording open in the viewer
+    fn rename_speaker(&mut self, label: &str, name: &str) -> Result<()> {
+        let Some(recording_id) = self.viewer.recording_id().map(str::to_string) else {
+            return Ok(());
+        };
+        let db = Database::open(&self.settings)?;
+        db.rename_speaker(&recording_id, label, name)?;
+        self.viewer
+            .set_segments(db.get_transcript_segments(&recording_id)?);
+        Ok(())
+    }
+
+    /// Handle setup screen key input
+    async fn handle_setup_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('d') if self.setup.offers_fix('d') => {
+                self.start_model_download();
+            }
+            KeyCode::Char('s') if self.setup.offers_fix('s') => {
+                match crate::daemon::start_daemon(&self.settings) {
+                    Ok(()) => self.setup.set_message("Daemon started", false),
+                    Err(e) => self.setup.set_message(format!("{:#}", e), true),
+                }
+                self.update_daemon_status().await;
+                self.check_setup();
+            }
+            KeyCode::Char('c') => {
+                self.settings_screen = SettingsScreen::new(&self.settings);
+                self.switch_screen(AppScreen::Settings);
+            }
+            KeyCode::Char('r') => {
+                self.update_daemon_status().await;
+                self.check_setup();
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle settings screen key input
+    fn handle_settings_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.settings_screen.previous();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.settings_screen.next();
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                self.settings_screen.change_selected();
+            }
+            KeyCode::Char('w') => {
+                self.save_settings();
+            }
+            _ => {}
+        }
+    }
+
+    /// Write the settings screen's values to the config file
+    fn save_settings(&mut self) {
+        let saved = Settings::config_path().and_then(|path| {
+            Settings::update_config(&path, &self.settings_screen.changes())?;
+            Ok(path)
+        });
+
+        match saved {
+            Ok(path) => {
+                self.settings_screen.apply(&mut self.settings);
+                self.settings_screen.set_message(
+                    format!("Saved to {}; restart the daemon to apply", path.display()),
+                    false,
+                );
+                self.check_setup();
+            }
+            Err(e) => self.settings_screen.set_message(format!("{:#}", e), true),
+        }
+    }
+
+    /// Download the configured Whisper model in the background
+    fn start_model_download(&mut self) {
+        if matches!(self.setup.download_state(), DownloadState::Running { .. }) {
+            return;
+        }
+
+        let progress = self.setup.download();
+        if let Ok(mut state) = progress.lock() {
+            *state = DownloadState::Running {
+                received: 0,
+                total: None,
+            };
+        }
+
+        let settings = self.settings.clone();
+        tokio::spawn(async move {
+            let model = settings.whisper.model.clone();
+            let result = download_model(&settings, &model, |received, total| {
+                if let Ok(mut state) = progress.lock() {
+                    *state = DownloadState::Running { received, total };
+                }
+            })
+            .await;
+
+            if let Ok(mut state) = progress.lock() {
+                *state = match result {
+                    Ok(_) => DownloadState::Done,
+                    Err(e) => DownloadState::Failed(format!("{:#}", e)),
+                };
+            }
+        });
+    }
+
+    /// Open the setup checks
+    async fn open_setup(&mut self) {
+        self.update_daemon_status().await;
+        self.check_setup();
+        self.switch_screen(AppScreen::Setup);
+    }
+
+    /// Re-run the setup checks
+    fn check_setup(&mut self) {
+        self.setup.set_issues(detect_issues(
+            &self.settings,
+            self.daemon_running,
+            self.pipewire_available,
+        ));
+        if self.setup.issues().is_empty() {
+            self.complete_setup();
+        }
+    }
+
+    /// Remember that first-launch setup is done so it is not shown again
+    fn complete_setup(&mut self) {
+        if !self.tui_state.setup_completed {
+            self.tui_state.setup_completed = true;
+            if let Err(e) = self.tui_state.save(&self.settings) {
+                tracing::warn!("Failed to save TUI state: {}", e);
+            }
+        }
+    }
+
     /// Toggle recording on/off
     async fn toggle_recording(&mut self) -> Result<()> {
+        let request = match &self.daemon_status {
+            RecordingStatus::Idle => DaemonRequest::StartRecording {
+                title: Recording::default_title(),
+                sensitivity: Sensitivity::Normal,
+                participants: Vec::new(),
+                language: None,
+            },
+            RecordingStatus::Recording { .. } => DaemonRequest::StopRecording,
+            _ => return Ok(()),
+        };
+
+        self.send_to_daemon(request).await;
+        Ok(())
+    }
+
+    /// Start recording the next calendar meeting with its title and attendees
+    async fn record_next_meeting(&mut self) -> Result<()> {
+        if !matches!(self.daemon_status, RecordingStatus::Idle) {
+            return Ok(());
+        }
+        let Some(event) = self.dashboard.next_event() else {
+            return Ok(());
+        };
+
+        let request = DaemonRequest::StartRecording {
+            title: event.title.clone(),
+            sensitivity: Sensitivity::Normal,
+            participants: event.participants.clone(),
+            language: None,
+        };
+        self.send_to_daemon(request).await;
+        Ok(())
+    }
+
+    async fn send_to_daemon(&self, request: DaemonRequest) {
         match DaemonClient::connect(&self.settings).await {
             Ok(mut client) => {
-                let request = match &self.daemon_status {
-                    RecordingStatus::Idle => DaemonRequest::StartRecording {
-                        title: format!("Meeting {}", chrono::Local::now().format("%Y-%m-%d %H:%M")),
-                    },
-                    RecordingStatus::Recording { .. } => DaemonRequest::StopRecording,
-                    _ => return Ok(()),
-                };
-
                 let _ = client.send(request).await;
             }
             Err(_) => {
                 // Daemon not running - could show error in UI
             }
         }
-        Ok(())
     }
 
     /// Open a recording in the viewer
@@ -195,7 +481,10 @@ impl App {
 
         if let Some(recording) = db.get_recording(recording_id)? {
             let segments = db.get_transcript_segments(recording_id)?;
-            self.viewer.set_recording(recording, segments);
+            let silences = db.get_silences(recording_id)?;
+            let summary = db.latest_summary(recording_id)?;
+            self.viewer
+                .set_recording(recording, segments, silences, summary);
             self.switch_screen(AppScreen::Viewer);
         }
 
@@ -210,6 +499,9 @@ impl App {
 
     /// Handle back navigation
     pub fn handle_back(&mut self) {
+        if self.current_screen == AppScreen::Setup {
+            self.complete_setup();
+        }
         if let Some(prev) = self.previous_screen.take() {
             self.current_screen = prev;
         } else if self.current_screen != AppScreen::Dashboard {
@@ -217,6 +509,11 @@ impl App {
         }
     }
 
+    /// Whether keys go to a text field, so q and Esc don't navigate
+    pub fn is_typing(&self) -> bool {
+        self.current_screen == AppScreen::Viewer && self.viewer.is_renaming()
+    }
+
     /// Check if app should quit
     pub fn should_quit(&self) -> bool {
         self.current_screen == AppScreen::Dashboard && !self.show_help
@@ -229,18 +526,52 @@ impl App {
 
     /// Update app state
     pub async fn update(&mut self) -> Result<()> {
+        // Show the setup checks on first launch if anything needs fixing
+        if !self.setup_checked {
+            self.setup_checked = true;
+            if !self.tui_state.setup_completed {
+                self.update_daemon_status().await;
+                self.check_setup();
+                if !self.setup.issues().is_empty() {
+                    self.switch_screen(AppScreen::Setup);
+                }
+            }
+        }
+
+        if let Some(download) = self.setup.take_finished_download() {
+            match download {
+                DownloadState::Failed(e) => self.setup.set_message(e, true),
+                _ => self.setup.set_message("Model downloaded", false),
+            }
+            self.check_setup();
+        }
+
         // Update daemon status periodically
         if self.last_status_update.elapsed() > Duration::from_secs(1) {
             self.update_daemon_status().await;
             self.last_status_update = Instant::now();
         }
 
+        if calendar::is_configured(&self.settings)
+            && self
+                .last_calendar_update
+                .is_none_or(|at| at.elapsed() > CALENDAR_REFRESH)
+        {
+            self.dashboard.set_events(calendar::upcoming_events(
+                &self.settings,
+                chrono::Utc::now(),
+            ));
+            self.last_calendar_update = Some(Instant::now());
+        }
+
         Ok(())
     }
 
     /// Update daemon status
     async fn update_daemon_status(&mut self) {
-        if let Ok(mut client) = DaemonClient::connect(&self.settings).await {
+        let client = DaemonClient::connect(&self.settings).await;
+        self.daemon_running = client.is_ok();
+        if let Ok(mut client) = client {
             if let Ok(crate::daemon::ipc::DaemonResponse::Status(status)) =
                 client.send(DaemonRequest::GetStatus).await
             {
@@ -252,8 +583,9 @@ impl App {
     /// Refresh recordings list
     pub fn refresh_recordings(&mut self) -> Result<()> {
         let db = Database::open(&self.settings)?;
-        let recordings = db.list_recordings(100)?;
+        let recordings = db.list_recordings(&RecordingFilter::default(), 100)?;
         self.browser = BrowserScreen::new(recordings);
+        self.dashboard.set_stats(db.library_stats()?);
         Ok(())
     }
 }
@@ -4,6 +4,7 @@
 
 mod app;
 pub mod screens;
+mod state;
 pub mod widgets;
 
 use anyhow::Result;
@@ -18,6 +19,7 @@ use std::time::Duration;
 
 use crate::config::Settings;
 pub use app::{App, AppScreen};
+pub use state::TuiState;
 
 /// Run the TUI application
 pub async fn run(settings: &Settings) -> Result<()> {
@@ -55,7 +57,9 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
         // Handle events with timeout for async updates
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
+                if key.kind == KeyEventKind::Press && app.is_typing() {
+                    app.handle_key(key.code).await?;
+                } else if key.kind == KeyEventKind::Press {
                     match key.code {
                         KeyCode::Char('q') | KeyCode::Esc => {
                             if app.should_quit() {
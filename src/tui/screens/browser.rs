@@ -5,8 +5,11 @@ use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
 };
+use std::collections::HashMap;
 
-use crate::storage::Recording;
+use crate::storage::{Recording, SearchHit};
+use crate::t;
+use crate::tui::widgets::hint_bar;
 
 /// Browser screen state
 pub struct BrowserScreen {
@@ -15,6 +18,8 @@ pub struct BrowserScreen {
     search_mode: bool,
     search_query: String,
     filtered_indices: Vec<usize>,
+    /// Best transcript match of the search per recording ID
+    transcript_hits: HashMap<String, SearchHit>,
 }
 
 impl BrowserScreen {
@@ -32,6 +37,7 @@ impl BrowserScreen {
             search_mode: false,
             search_query: String::new(),
             filtered_indices,
+            transcript_hits: HashMap::new(),
         }
     }
 
@@ -94,7 +100,7 @@ impl BrowserScreen {
                     crate::storage::RecordingState::Failed => Color::Red,
                 };
 
-                ListItem::new(Line::from(vec![
+                let mut lines = vec![Line::from(vec![
                     Span::styled(state_indicator, Style::default().fg(state_color)),
                     Span::raw(" "),
                     Span::styled(
@@ -102,10 +108,19 @@ impl BrowserScreen {
                         Style::default().fg(Color::White),
                     ),
                     Span::raw(" "),
+                    Span::styled(
+                        truncate(recording.project.as_deref().unwrap_or("-"), 14),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                    Span::raw(" "),
                     Span::styled(date, Style::default().fg(Color::DarkGray)),
                     Span::raw(" "),
                     Span::styled(duration, Style::default().fg(Color::Cyan)),
-                ]))
+                ])];
+                if let Some(hit) = self.transcript_hits.get(&recording.id) {
+                    lines.push(excerpt_line(hit));
+                }
+                ListItem::new(lines)
             })
             .collect();
 
@@ -126,19 +141,13 @@ impl BrowserScreen {
         frame.render_stateful_widget(list, chunks[1], &mut self.state);
 
         // Help bar
-        let help = Paragraph::new(Line::from(vec![
-            Span::styled(" ↑/↓ ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Navigate  "),
-            Span::styled(" Enter ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" View  "),
-            Span::styled(" / ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Search  "),
-            Span::styled(" d ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Dashboard  "),
-            Span::styled(" Esc ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Back"),
-        ]))
-        .alignment(Alignment::Center);
+        let help = hint_bar(&[
+            ("↑/↓", t!("hint-navigate")),
+            ("Enter", t!("hint-view")),
+            ("/", t!("hint-search")),
+            ("d", t!("hint-dashboard")),
+            ("Esc", t!("hint-back")),
+        ]);
         frame.render_widget(help, chunks[2]);
     }
 
@@ -189,25 +198,45 @@ impl BrowserScreen {
         self.search_mode = true;
     }
 
-    pub fn handle_key(&mut self, key: KeyCode) {
+    /// Handle a key while searching, returning whether the query changed
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
         if !self.search_mode {
-            return;
+            return false;
         }
 
         match key {
             KeyCode::Char(c) => {
                 self.search_query.push(c);
                 self.apply_filter();
+                true
             }
             KeyCode::Backspace => {
                 self.search_query.pop();
                 self.apply_filter();
+                true
             }
             KeyCode::Enter | KeyCode::Esc => {
                 self.search_mode = false;
+                false
             }
-            _ => {}
+            _ => false,
+        }
+    }
+
+    pub fn search_query(&self) -> &str {
+        &self.search_query
+    }
+
+    /// Also list the recordings whose transcripts match the search, each
+    /// with an excerpt of its best match
+    pub fn set_transcript_hits(&mut self, hits: Vec<SearchHit>) {
+        self.transcript_hits.clear();
+        for hit in hits {
+            self.transcript_hits
+                .entry(hit.recording.id.clone())
+                .or_insert(hit);
         }
+        self.apply_filter();
     }
 
     fn apply_filter(&mut self) {
@@ -219,7 +248,13 @@ impl BrowserScreen {
                 .recordings
                 .iter()
                 .enumerate()
-                .filter(|(_, r)| r.title.to_lowercase().contains(&query))
+                .filter(|(_, r)| {
+                    r.title.to_lowercase().contains(&query)
+                        || r.project
+                            .as_ref()
+                            .is_some_and(|project| project.to_lowercase().contains(&query))
+                        || self.transcript_hits.contains_key(&r.id)
+                })
                 .map(|(i, _)| i)
                 .collect();
         }
@@ -233,6 +268,22 @@ impl BrowserScreen {
     }
 }
 
+/// Indented excerpt of a transcript match with the matched terms highlighted
+fn excerpt_line(hit: &SearchHit) -> Line<'static> {
+    let mut spans = vec![Span::raw("    ")];
+    for (text, matched) in hit.excerpt_parts() {
+        let style = if matched {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        spans.push(Span::styled(text.to_string(), style));
+    }
+    Line::from(spans)
+}
+
 fn truncate(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         format!("{:<width$}", s, width = max_len)
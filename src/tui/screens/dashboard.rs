@@ -2,14 +2,30 @@
 
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{BarChart, Block, Borders, Paragraph, Wrap},
 };
 
+use crate::calendar::CalendarEvent;
 use crate::daemon::ipc::RecordingStatus;
+use crate::storage::LibraryStats;
+use crate::t;
+use crate::tui::widgets::hint_bar;
+
+/// Upcoming meetings listed on the dashboard
+const UPCOMING_LIMIT: usize = 3;
+/// Attendee names shown before the rest are summarised as "+N"
+const PARTICIPANTS_SHOWN: usize = 3;
 
 /// Dashboard screen state
 pub struct DashboardScreen {
-    // Add any dashboard-specific state here
+    /// Whether a calendar file is configured
+    calendar_enabled: bool,
+    /// Upcoming calendar events, soonest first
+    events: Vec<CalendarEvent>,
+    /// Last calendar read error, shown instead of the events
+    calendar_error: Option<String>,
+    /// Library activity charted next to the info panel
+    stats: Option<LibraryStats>,
 }
 
 impl Default for DashboardScreen {
@@ -20,17 +36,53 @@ impl Default for DashboardScreen {
 
 impl DashboardScreen {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            calendar_enabled: false,
+            events: Vec::new(),
+            calendar_error: None,
+            stats: None,
+        }
+    }
+
+    /// Replace the library statistics after the recordings changed
+    pub fn set_stats(&mut self, stats: LibraryStats) {
+        self.stats = Some(stats);
+    }
+
+    /// Replace the upcoming events after a calendar refresh
+    pub fn set_events(&mut self, events: anyhow::Result<Vec<CalendarEvent>>) {
+        self.calendar_enabled = true;
+        match events {
+            Ok(events) => {
+                self.events = events.into_iter().take(UPCOMING_LIMIT).collect();
+                self.calendar_error = None;
+            }
+            Err(e) => {
+                self.events.clear();
+                self.calendar_error = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    /// The meeting the "record next meeting" key starts
+    pub fn next_event(&self) -> Option<&CalendarEvent> {
+        self.events.first()
     }
 
     pub fn draw(&self, frame: &mut Frame, area: Rect, status: &RecordingStatus) {
+        let upcoming_height = if self.calendar_enabled {
+            self.events.len().max(1) as u16 + 2
+        } else {
+            0
+        };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Title
-                Constraint::Length(7), // Status
-                Constraint::Min(5),    // Info
-                Constraint::Length(3), // Help
+                Constraint::Length(3),               // Title
+                Constraint::Length(7),               // Status
+                Constraint::Length(upcoming_height), // Upcoming meetings
+                Constraint::Min(5),                  // Info
+                Constraint::Length(3),               // Help
             ])
             .split(area);
 
@@ -46,12 +98,12 @@ impl DashboardScreen {
             RecordingStatus::Idle => (
                 vec![
                     Line::from(vec![
-                        Span::raw("Status: "),
-                        Span::styled("Not Recording", Style::default().fg(Color::Gray)),
+                        label(t!("tui-status")),
+                        Span::styled(t!("tui-not-recording"), Style::default().fg(Color::Gray)),
                     ]),
                     Line::from(""),
                     Line::from(Span::styled(
-                        "Press [r] to start recording",
+                        t!("tui-press-to-start"),
                         Style::default().fg(Color::DarkGray),
                     )),
                 ],
@@ -70,45 +122,48 @@ impl DashboardScreen {
                 (
                     vec![
                         Line::from(vec![
-                            Span::raw("Status: "),
-                            Span::styled("● Recording", Style::default().fg(Color::Red).bold()),
+                            label(t!("tui-status")),
+                            Span::styled(
+                                format!("● {}", t!("tui-recording")),
+                                Style::default().fg(Color::Red).bold(),
+                            ),
                         ]),
                         Line::from(vec![
-                            Span::raw("Title: "),
+                            label(t!("tui-title")),
                             Span::styled(title, Style::default().fg(Color::White)),
                         ]),
                         Line::from(vec![
-                            Span::raw("Duration: "),
+                            label(t!("tui-duration")),
                             Span::styled(
                                 format!("{:02}:{:02}", minutes, seconds),
                                 Style::default().fg(Color::Yellow),
                             ),
                         ]),
                         Line::from(vec![
-                            Span::raw("Audio: "),
+                            label(t!("tui-audio")),
                             Span::styled(level_bar, Style::default().fg(Color::Green)),
                         ]),
                         Line::from(""),
                         Line::from(Span::styled(
-                            "Press [r] to stop recording",
+                            t!("tui-press-to-stop"),
                             Style::default().fg(Color::DarkGray),
                         )),
                     ],
                     Style::default(),
                 )
             }
-            RecordingStatus::Transcribing { id, progress } => (
+            RecordingStatus::Transcribing { id, progress, .. } => (
                 vec![
                     Line::from(vec![
-                        Span::raw("Status: "),
-                        Span::styled("Transcribing...", Style::default().fg(Color::Yellow)),
+                        label(t!("tui-status")),
+                        Span::styled(t!("tui-transcribing"), Style::default().fg(Color::Yellow)),
                     ]),
                     Line::from(vec![
-                        Span::raw("Recording: "),
+                        label(t!("tui-recording")),
                         Span::styled(&id[..8], Style::default().fg(Color::White)),
                     ]),
                     Line::from(vec![
-                        Span::raw("Progress: "),
+                        label(t!("tui-progress")),
                         Span::styled(
                             format!("{:.0}%", progress * 100.0),
                             Style::default().fg(Color::Cyan),
@@ -127,6 +182,10 @@ impl DashboardScreen {
         );
         frame.render_widget(status_widget, chunks[1]);
 
+        if self.calendar_enabled {
+            self.draw_upcoming(frame, chunks[2]);
+        }
+
         // Info section
         let info_text = vec![
             Line::from(Span::styled(
@@ -146,6 +205,11 @@ impl DashboardScreen {
                 Span::styled("[l]", Style::default().fg(Color::Cyan)),
                 Span::raw(" to browse recordings"),
             ]),
+            Line::from(vec![
+                Span::raw("• Press "),
+                Span::styled("[o]", Style::default().fg(Color::Cyan)),
+                Span::raw(" to check setup"),
+            ]),
             Line::from(vec![
                 Span::raw("• Press "),
                 Span::styled("[?]", Style::default().fg(Color::Cyan)),
@@ -159,22 +223,151 @@ impl DashboardScreen {
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::DarkGray)),
         );
-        frame.render_widget(info_widget, chunks[2]);
+        match &self.stats {
+            Some(stats) => {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(chunks[3]);
+                frame.render_widget(info_widget, columns[0]);
+                draw_activity(frame, columns[1], stats);
+            }
+            None => frame.render_widget(info_widget, chunks[3]),
+        }
 
         // Help bar
-        let help = Paragraph::new(Line::from(vec![
-            Span::styled(" [r] ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Record  "),
-            Span::styled(" [l] ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" List  "),
-            Span::styled(" [?] ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Help  "),
-            Span::styled(" [q] ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Quit"),
-        ]))
-        .alignment(Alignment::Center);
-        frame.render_widget(help, chunks[3]);
+        let mut hints = vec![("[r]", t!("hint-record"))];
+        if self.next_event().is_some() {
+            hints.push(("[m]", t!("hint-record-meeting")));
+        }
+        hints.extend([
+            ("[l]", t!("hint-list")),
+            ("[?]", t!("hint-help")),
+            ("[q]", t!("hint-quit")),
+        ]);
+        let help = hint_bar(&hints);
+        frame.render_widget(help, chunks[4]);
     }
+
+    fn draw_upcoming(&self, frame: &mut Frame, area: Rect) {
+        let now = chrono::Utc::now();
+        let lines: Vec<Line> = if let Some(error) = &self.calendar_error {
+            vec![Line::from(Span::styled(
+                error.as_str(),
+                Style::default().fg(Color::Red),
+            ))]
+        } else if self.events.is_empty() {
+            vec![Line::from(Span::styled(
+                "No meetings coming up",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            self.events
+                .iter()
+                .enumerate()
+                .map(|(index, event)| event_line(event, index == 0, now))
+                .collect()
+        };
+
+        let widget = Paragraph::new(lines).block(
+            Block::default()
+                .title(" Upcoming Meetings ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Blue)),
+        );
+        frame.render_widget(widget, area);
+    }
+}
+
+/// Bar chart of recordings per week, with the library totals as its title
+fn draw_activity(frame: &mut Frame, area: Rect, stats: &LibraryStats) {
+    let labels: Vec<String> = stats
+        .per_week
+        .iter()
+        .map(|week| week.week_of.format("%m-%d").to_string())
+        .collect();
+    let data: Vec<(&str, u64)> = labels
+        .iter()
+        .zip(&stats.per_week)
+        .map(|(label, week)| (label.as_str(), week.recordings as u64))
+        .collect();
+
+    let chart = BarChart::default()
+        .block(
+            Block::default()
+                .title(format!(
+                    " Recordings per week ({} total, {:.0}h) ",
+                    stats.recordings,
+                    stats.recorded_secs as f64 / 3600.0
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray)),
+        )
+        .data(data.as_slice())
+        .bar_width(5)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Cyan))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+    frame.render_widget(chart, area);
+}
+
+fn event_line(
+    event: &CalendarEvent,
+    is_next: bool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Line<'_> {
+    let start = event.start.with_timezone(&chrono::Local);
+    let when = if event.is_in_progress(now) {
+        "now".to_string()
+    } else {
+        start.format("%H:%M").to_string()
+    };
+
+    let mut spans = vec![
+        Span::styled(format!("{:<6}", when), Style::default().fg(Color::Yellow)),
+        Span::styled(
+            event.title.as_str(),
+            if is_next {
+                Style::default().fg(Color::White).bold()
+            } else {
+                Style::default().fg(Color::White)
+            },
+        ),
+    ];
+
+    if !event.participants.is_empty() {
+        let mut names = event
+            .participants
+            .iter()
+            .take(PARTICIPANTS_SHOWN)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if event.participants.len() > PARTICIPANTS_SHOWN {
+            names.push_str(&format!(
+                " +{}",
+                event.participants.len() - PARTICIPANTS_SHOWN
+            ));
+        }
+        spans.push(Span::styled(
+            format!("  ({})", names),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+
+    if is_next {
+        spans.push(Span::styled(
+            "  [m] record",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    Line::from(spans)
+}
+
+/// Field label such as "Status: "
+fn label(name: String) -> Span<'static> {
+    Span::raw(format!("{}: ", name))
 }
 
 fn create_level_bar(level: f32) -> String {
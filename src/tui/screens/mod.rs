@@ -2,8 +2,12 @@
 
 mod browser;
 mod dashboard;
+mod settings;
+mod setup;
 mod viewer;
 
 pub use browser::BrowserScreen;
 pub use dashboard::DashboardScreen;
+pub use settings::SettingsScreen;
+pub use setup::{detect_issues, DownloadState, SetupIssue, SetupScreen};
 pub use viewer::ViewerScreen;
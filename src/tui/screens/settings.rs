@@ -0,0 +1,241 @@
+//! Settings screen - edit the audio and model settings
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::audio::AudioBackend;
+use crate::config::Settings;
+use crate::t;
+use crate::tui::widgets::hint_bar;
+
+/// Backends offered, in the order Enter cycles through them
+const BACKENDS: [AudioBackend; 6] = [
+    AudioBackend::Auto,
+    AudioBackend::PipeWire,
+    AudioBackend::Cpal,
+    AudioBackend::Jack,
+    AudioBackend::Wasapi,
+    AudioBackend::CoreAudio,
+];
+
+/// Whisper models offered, in the order Enter cycles through them
+const MODELS: [&str; 14] = [
+    "tiny",
+    "tiny.en",
+    "base",
+    "base.en",
+    "base.en-q5_1",
+    "small",
+    "small.en",
+    "small.en-q5_1",
+    "medium",
+    "medium.en",
+    "medium-q5_0",
+    "medium.en-q5_0",
+    "large-v3",
+    "large-v3-q5_0",
+];
+
+/// Number of editable fields
+const FIELD_COUNT: usize = 4;
+
+/// Settings screen state
+pub struct SettingsScreen {
+    backend: AudioBackend,
+    capture_system: bool,
+    capture_microphone: bool,
+    model: String,
+    state: ListState,
+    message: Option<(String, bool)>,
+}
+
+impl SettingsScreen {
+    pub fn new(settings: &Settings) -> Self {
+        let mut screen = Self {
+            backend: settings.audio.backend,
+            capture_system: settings.audio.capture_system,
+            capture_microphone: settings.audio.capture_microphone,
+            model: settings.whisper.model.clone(),
+            state: ListState::default(),
+            message: None,
+        };
+        screen.state.select(Some(0));
+        screen
+    }
+
+    pub fn next(&mut self) {
+        let i = self.state.selected().map_or(0, |i| (i + 1) % FIELD_COUNT);
+        self.state.select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        let i = self
+            .state
+            .selected()
+            .map_or(0, |i| (i + FIELD_COUNT - 1) % FIELD_COUNT);
+        self.state.select(Some(i));
+    }
+
+    /// Change the selected field to its next value
+    pub fn change_selected(&mut self) {
+        match self.state.selected() {
+            Some(0) => self.backend = cycle(&BACKENDS, &self.backend),
+            Some(1) => self.capture_system = !self.capture_system,
+            Some(2) => self.capture_microphone = !self.capture_microphone,
+            Some(3) => self.model = cycle(&MODELS, &self.model.as_str()).to_string(),
+            _ => {}
+        }
+        self.message = None;
+    }
+
+    /// Show a status line; errors are drawn in red
+    pub fn set_message(&mut self, message: impl Into<String>, is_error: bool) {
+        self.message = Some((message.into(), is_error));
+    }
+
+    /// Config file changes for the edited values, as taken by `Settings::update_config`
+    pub fn changes(&self) -> toml::Table {
+        let mut audio = toml::Table::new();
+        audio.insert(
+            "backend".to_string(),
+            toml::Value::String(self.backend.as_str().to_string()),
+        );
+        audio.insert(
+            "capture_system".to_string(),
+            toml::Value::Boolean(self.capture_system),
+        );
+        audio.insert(
+            "capture_microphone".to_string(),
+            toml::Value::Boolean(self.capture_microphone),
+        );
+
+        let mut whisper = toml::Table::new();
+        whisper.insert("model".to_string(), toml::Value::String(self.model.clone()));
+
+        let mut changes = toml::Table::new();
+        changes.insert("audio".to_string(), toml::Value::Table(audio));
+        changes.insert("whisper".to_string(), toml::Value::Table(whisper));
+        changes
+    }
+
+    /// Copy the edited values into `settings`
+    pub fn apply(&self, settings: &mut Settings) {
+        settings.audio.backend = self.backend;
+        settings.audio.capture_system = self.capture_system;
+        settings.audio.capture_microphone = self.capture_microphone;
+        settings.whisper.model = self.model.clone();
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(6),    // Fields
+                Constraint::Length(3), // Status
+                Constraint::Length(3), // Help
+            ])
+            .split(area);
+
+        let fields = [
+            ("Audio backend", self.backend.as_str().to_string()),
+            ("Capture system audio", on_off(self.capture_system)),
+            ("Capture microphone", on_off(self.capture_microphone)),
+            ("Whisper model", self.model.clone()),
+        ];
+        let items: Vec<ListItem> = fields
+            .into_iter()
+            .map(|(label, value)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<24}", label), Style::default().fg(Color::White)),
+                    Span::styled(value, Style::default().fg(Color::Cyan)),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(" Settings ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Blue)),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, chunks[0], &mut self.state);
+
+        let status = match &self.message {
+            Some((message, true)) => Line::from(Span::styled(
+                message.as_str(),
+                Style::default().fg(Color::Red),
+            )),
+            Some((message, false)) => Line::from(Span::styled(
+                message.as_str(),
+                Style::default().fg(Color::Green),
+            )),
+            None => Line::from(Span::styled(
+                "Changes are written to the config file when saved",
+                Style::default().fg(Color::DarkGray),
+            )),
+        };
+        let status = Paragraph::new(status).block(
+            Block::default()
+                .title(" Status ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray)),
+        );
+        frame.render_widget(status, chunks[1]);
+
+        let help = hint_bar(&[
+            ("↑/↓", t!("hint-navigate")),
+            ("Enter", t!("hint-change")),
+            ("w", t!("hint-save")),
+            ("Esc", t!("hint-back")),
+        ]);
+        frame.render_widget(help, chunks[2]);
+    }
+}
+
+/// Value after `current` in `values`, or the first value if it is not listed
+fn cycle<T: Copy + PartialEq>(values: &[T], current: &T) -> T {
+    let next = values
+        .iter()
+        .position(|value| value == current)
+        .map_or(0, |i| (i + 1) % values.len());
+    values[next]
+}
+
+fn on_off(enabled: bool) -> String {
+    if enabled { "on" } else { "off" }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_changes_round_trip_through_the_config_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(&path, "[general]\nlog_level = \"debug\"\n").unwrap();
+
+        let mut screen = SettingsScreen::new(&Settings::default());
+        screen.change_selected();
+        screen.next();
+        screen.change_selected();
+        Settings::update_config(&path, &screen.changes()).unwrap();
+
+        let saved: Settings = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.audio.backend, AudioBackend::PipeWire);
+        assert_eq!(
+            saved.audio.capture_system,
+            !Settings::default().audio.capture_system
+        );
+        assert_eq!(saved.general.log_level, "debug");
+    }
+}
@@ -0,0 +1,322 @@
+//! Setup screen - first-launch checks with one-key fixes
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::audio::AudioBackend;
+use crate::config::Settings;
+use crate::t;
+use crate::tui::widgets::hint_bar;
+
+/// A problem that keeps minutes from recording or transcribing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetupIssue {
+    /// The configured Whisper model has not been downloaded
+    MissingModel { model: String, path: PathBuf },
+    /// The background daemon that records is not running
+    DaemonNotRunning,
+    /// Both system audio and microphone capture are disabled
+    NoAudioSource,
+    /// System audio capture needs PipeWire, which was not found
+    PipeWireUnavailable,
+}
+
+impl SetupIssue {
+    /// Short description of the problem
+    pub fn title(&self) -> String {
+        match self {
+            Self::MissingModel { model, .. } => {
+                format!("Whisper model '{}' is not downloaded", model)
+            }
+            Self::DaemonNotRunning => "Daemon is not running".to_string(),
+            Self::NoAudioSource => "No audio source is enabled".to_string(),
+            Self::PipeWireUnavailable => "PipeWire was not found".to_string(),
+        }
+    }
+
+    /// What the problem means for the user
+    pub fn detail(&self) -> String {
+        match self {
+            Self::MissingModel { path, .. } => format!(
+                "Recordings cannot be transcribed until {} exists",
+                path.display()
+            ),
+            Self::DaemonNotRunning => {
+                "Recordings are started and stopped by the daemon".to_string()
+            }
+            Self::NoAudioSource => {
+                "Enable system audio or microphone capture to record anything".to_string()
+            }
+            Self::PipeWireUnavailable => {
+                "Only the microphone will be recorded; install pw-record or pick another backend"
+                    .to_string()
+            }
+        }
+    }
+
+    /// Key that fixes the problem and what it does
+    pub fn fix(&self) -> (char, &'static str) {
+        match self {
+            Self::MissingModel { .. } => ('d', "Download model"),
+            Self::DaemonNotRunning => ('s', "Start daemon"),
+            Self::NoAudioSource | Self::PipeWireUnavailable => ('c', "Open settings"),
+        }
+    }
+}
+
+/// Check the configuration and environment for setup problems
+pub fn detect_issues(
+    settings: &Settings,
+    daemon_running: bool,
+    pipewire_available: bool,
+) -> Vec<SetupIssue> {
+    let mut issues = Vec::new();
+
+    // Remote providers only need the local model for confidential recordings
+    let path = settings.model_path();
+    if settings.transcription.uses_whisper() && !path.exists() {
+        issues.push(SetupIssue::MissingModel {
+            model: settings.whisper.model.clone(),
+            path,
+        });
+    }
+
+    if !daemon_running {
+        issues.push(SetupIssue::DaemonNotRunning);
+    }
+
+    let audio = &settings.audio;
+    if !audio.capture_system && !audio.capture_microphone {
+        issues.push(SetupIssue::NoAudioSource);
+    } else if cfg!(target_os = "linux")
+        && audio.capture_system
+        && matches!(audio.backend, AudioBackend::Auto | AudioBackend::PipeWire)
+        && !pipewire_available
+    {
+        issues.push(SetupIssue::PipeWireUnavailable);
+    }
+
+    issues
+}
+
+/// Progress of a model download started from the setup screen
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DownloadState {
+    #[default]
+    Idle,
+    Running {
+        received: u64,
+        total: Option<u64>,
+    },
+    Done,
+    Failed(String),
+}
+
+/// Setup screen state
+pub struct SetupScreen {
+    issues: Vec<SetupIssue>,
+    message: Option<(String, bool)>,
+    download: Arc<Mutex<DownloadState>>,
+}
+
+impl SetupScreen {
+    pub fn new() -> Self {
+        Self {
+            issues: Vec::new(),
+            message: None,
+            download: Arc::new(Mutex::new(DownloadState::Idle)),
+        }
+    }
+
+    pub fn issues(&self) -> &[SetupIssue] {
+        &self.issues
+    }
+
+    pub fn set_issues(&mut self, issues: Vec<SetupIssue>) {
+        self.issues = issues;
+    }
+
+    /// Whether one of the current issues is fixed by `key`
+    pub fn offers_fix(&self, key: char) -> bool {
+        self.issues.iter().any(|issue| issue.fix().0 == key)
+    }
+
+    /// Show a status line; errors are drawn in red
+    pub fn set_message(&mut self, message: impl Into<String>, is_error: bool) {
+        self.message = Some((message.into(), is_error));
+    }
+
+    /// Shared download progress, updated by the download task
+    pub fn download(&self) -> Arc<Mutex<DownloadState>> {
+        Arc::clone(&self.download)
+    }
+
+    pub fn download_state(&self) -> DownloadState {
+        self.download
+            .lock()
+            .map(|state| state.clone())
+            .unwrap_or_default()
+    }
+
+    /// Take a finished download's outcome, leaving the state idle
+    pub fn take_finished_download(&mut self) -> Option<DownloadState> {
+        let mut state = self.download.lock().ok()?;
+        match *state {
+            DownloadState::Done | DownloadState::Failed(_) => Some(std::mem::take(&mut *state)),
+            _ => None,
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),    // Checks
+                Constraint::Length(3), // Status
+                Constraint::Length(3), // Help
+            ])
+            .split(area);
+
+        let mut lines = vec![
+            Line::from(Span::styled(
+                "Welcome to minutes",
+                Style::default().fg(Color::White).bold(),
+            )),
+            Line::from(""),
+        ];
+
+        if self.issues.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "✓ Everything is ready to record",
+                Style::default().fg(Color::Green),
+            )));
+        } else {
+            lines.push(Line::from("A few things need attention before recording:"));
+            for issue in &self.issues {
+                let (key, label) = issue.fix();
+                lines.push(Line::from(""));
+                lines.push(Line::from(vec![
+                    Span::styled("✗ ", Style::default().fg(Color::Red)),
+                    Span::styled(issue.title(), Style::default().fg(Color::White).bold()),
+                ]));
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", issue.detail()),
+                    Style::default().fg(Color::Gray),
+                )));
+                lines.push(Line::from(vec![
+                    Span::raw("  Press "),
+                    Span::styled(format!("[{}]", key), Style::default().fg(Color::Cyan)),
+                    Span::raw(format!(" {}", label.to_lowercase())),
+                ]));
+            }
+        }
+
+        let checks = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .title(" Setup ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Blue)),
+        );
+        frame.render_widget(checks, chunks[0]);
+
+        let status = match self.download_state() {
+            DownloadState::Running { received, total } => Line::from(Span::styled(
+                download_progress(received, total),
+                Style::default().fg(Color::Yellow),
+            )),
+            _ => match &self.message {
+                Some((message, true)) => Line::from(Span::styled(
+                    message.as_str(),
+                    Style::default().fg(Color::Red),
+                )),
+                Some((message, false)) => Line::from(Span::styled(
+                    message.as_str(),
+                    Style::default().fg(Color::Green),
+                )),
+                None => Line::from(Span::styled(
+                    "Press a key to fix an issue",
+                    Style::default().fg(Color::DarkGray),
+                )),
+            },
+        };
+        let status = Paragraph::new(status).block(
+            Block::default()
+                .title(" Status ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray)),
+        );
+        frame.render_widget(status, chunks[1]);
+
+        let help = hint_bar(&[
+            ("r", t!("hint-check-again")),
+            ("c", t!("hint-settings")),
+            ("Esc", t!("hint-continue")),
+        ]);
+        frame.render_widget(help, chunks[2]);
+    }
+}
+
+impl Default for SetupScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn download_progress(received: u64, total: Option<u64>) -> String {
+    let mb = |bytes: u64| bytes as f64 / 1_048_576.0;
+    match total {
+        Some(total) if total > 0 => format!(
+            "Downloading model... {:.0}% ({:.1} / {:.1} MB)",
+            received as f64 / total as f64 * 100.0,
+            mb(received),
+            mb(total)
+        ),
+        _ => format!("Downloading model... {:.1} MB", mb(received)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(tmp: &tempfile::TempDir) -> Settings {
+        let mut settings = Settings::default();
+        settings.whisper.models_dir = tmp.path().to_path_buf();
+        settings.audio.backend = AudioBackend::Cpal;
+        settings
+    }
+
+    #[test]
+    fn reports_a_missing_model_and_stopped_daemon() {
+        let tmp = tempfile::tempdir().unwrap();
+        let settings = settings(&tmp);
+
+        let issues = detect_issues(&settings, false, true);
+        assert!(matches!(
+            &issues[..],
+            [SetupIssue::MissingModel { model, .. }, SetupIssue::DaemonNotRunning]
+                if *model == settings.whisper.model
+        ));
+
+        std::fs::write(settings.model_path(), b"model").unwrap();
+        assert!(detect_issues(&settings, true, true).is_empty());
+    }
+
+    #[test]
+    fn reports_when_no_audio_source_is_enabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut settings = settings(&tmp);
+        std::fs::write(settings.model_path(), b"model").unwrap();
+        settings.audio.capture_system = false;
+        settings.audio.capture_microphone = false;
+
+        assert_eq!(
+            detect_issues(&settings, true, true),
+            [SetupIssue::NoAudioSource]
+        );
+    }
+}
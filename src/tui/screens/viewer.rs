@@ -1,19 +1,36 @@
 //! Viewer screen - display transcript for a recording
 
+use crossterm::event::KeyCode;
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
+    widgets::{
+        Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
+    },
 };
 
 use crate::config::Settings;
-use crate::storage::{Recording, TranscriptSegment};
+use crate::storage::{Recording, Silence, Summary, TranscriptSegment, TranscriptStats};
+use crate::t;
+use crate::transcription::{silence_marker_text, silence_markers};
+use crate::tui::widgets::hint_bar;
 
 /// Viewer screen state
 pub struct ViewerScreen {
     recording: Option<Recording>,
     segments: Vec<TranscriptSegment>,
+    silences: Vec<Silence>,
+    summary: Option<Summary>,
     scroll_offset: usize,
     content_height: usize,
+    rename: Option<SpeakerRename>,
+    show_analytics: bool,
+}
+
+/// Dialog naming one of the recording's speakers
+struct SpeakerRename {
+    speakers: Vec<String>,
+    selected: usize,
+    name: String,
 }
 
 impl Default for ViewerScreen {
@@ -27,22 +44,119 @@ impl ViewerScreen {
         Self {
             recording: None,
             segments: Vec::new(),
+            silences: Vec::new(),
+            summary: None,
             scroll_offset: 0,
             content_height: 0,
+            rename: None,
+            show_analytics: false,
         }
     }
 
-    pub fn set_recording(&mut self, recording: Recording, segments: Vec<TranscriptSegment>) {
+    pub fn set_recording(
+        &mut self,
+        recording: Recording,
+        segments: Vec<TranscriptSegment>,
+        silences: Vec<Silence>,
+        summary: Option<Summary>,
+    ) {
         self.recording = Some(recording);
         self.segments = segments;
+        self.silences = silences;
+        self.summary = summary;
         self.scroll_offset = 0;
+        self.rename = None;
+        self.show_analytics = false;
+    }
+
+    /// Replace the transcript of the open recording, keeping the scroll position
+    pub fn set_segments(&mut self, segments: Vec<TranscriptSegment>) {
+        self.segments = segments;
+    }
+
+    pub fn recording_id(&self) -> Option<&str> {
+        self.recording
+            .as_ref()
+            .map(|recording| recording.id.as_str())
+    }
+
+    /// Open the rename dialog, if the transcript has speaker labels
+    pub fn start_rename(&mut self) {
+        let mut speakers: Vec<String> = Vec::new();
+        for speaker in self.segments.iter().filter_map(|s| s.speaker.as_ref()) {
+            if !speakers.contains(speaker) {
+                speakers.push(speaker.clone());
+            }
+        }
+        if !speakers.is_empty() {
+            self.rename = Some(SpeakerRename {
+                speakers,
+                selected: 0,
+                name: String::new(),
+            });
+        }
+    }
+
+    /// Tags and suggested tags of the open recording
+    pub fn tags(&self) -> Option<(&[String], &[String])> {
+        self.recording
+            .as_ref()
+            .map(|recording| (&recording.tags[..], &recording.suggested_tags[..]))
+    }
+
+    /// Replace the tags of the open recording after they were changed
+    pub fn set_tags(&mut self, tags: Vec<String>, suggested: Vec<String>) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.tags = tags;
+            recording.suggested_tags = suggested;
+        }
+    }
+
+    /// Show or hide the talk-time panel
+    pub fn toggle_analytics(&mut self) {
+        self.show_analytics = !self.show_analytics;
+    }
+
+    pub fn is_renaming(&self) -> bool {
+        self.rename.is_some()
+    }
+
+    /// Handle a key in the rename dialog
+    ///
+    /// Returns the `(label, name)` to save when the dialog is confirmed.
+    pub fn handle_rename_key(&mut self, key: KeyCode) -> Option<(String, String)> {
+        let rename = self.rename.as_mut()?;
+        match key {
+            KeyCode::Tab | KeyCode::Down => {
+                rename.selected = (rename.selected + 1) % rename.speakers.len();
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                rename.selected =
+                    (rename.selected + rename.speakers.len() - 1) % rename.speakers.len();
+            }
+            KeyCode::Char(c) => rename.name.push(c),
+            KeyCode::Backspace => {
+                rename.name.pop();
+            }
+            KeyCode::Esc => self.rename = None,
+            KeyCode::Enter => {
+                let name = rename.name.trim().to_string();
+                if !name.is_empty() {
+                    let label = rename.speakers[rename.selected].clone();
+                    self.rename = None;
+                    return Some((label, name));
+                }
+            }
+            _ => {}
+        }
+        None
     }
 
     pub fn draw(&mut self, frame: &mut Frame, area: Rect, settings: &Settings) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(4), // Header
+                Constraint::Length(6), // Header
                 Constraint::Min(5),    // Transcript
                 Constraint::Length(3), // Help
             ])
@@ -73,6 +187,8 @@ impl ViewerScreen {
                         Style::default().fg(Color::DarkGray),
                     ),
                 ]),
+                summary_line(recording, self.summary.as_ref()),
+                tags_line(recording),
             ]
         } else {
             vec![Line::from("No recording selected")]
@@ -88,24 +204,41 @@ impl ViewerScreen {
 
         // Transcript
         let show_timestamps = settings.tui.show_timestamps;
-        let transcript_lines: Vec<Line> = self
-            .segments
-            .iter()
-            .map(|segment| {
-                if show_timestamps {
-                    let timestamp = format_timestamp(segment.start_time);
-                    Line::from(vec![
-                        Span::styled(
-                            format!("[{}] ", timestamp),
-                            Style::default().fg(Color::DarkGray),
-                        ),
-                        Span::raw(&segment.text),
-                    ])
-                } else {
-                    Line::from(segment.text.as_str())
-                }
-            })
-            .collect();
+        let threshold = settings.transcription.low_confidence_threshold;
+        let mut markers = silence_markers(&self.segments, &self.silences)
+            .into_iter()
+            .peekable();
+        let mut transcript_lines: Vec<Line> = Vec::new();
+        for (index, segment) in self.segments.iter().enumerate() {
+            while let Some((_, silence)) = markers.next_if(|(before, _)| *before == index) {
+                transcript_lines.push(silence_line(&silence, show_timestamps));
+            }
+
+            // Segments worth checking against the audio are dimmed
+            let text_style = if segment.is_low_confidence(threshold) {
+                Style::default().fg(Color::DarkGray).italic()
+            } else {
+                Style::default()
+            };
+            let mut spans = Vec::new();
+            if show_timestamps {
+                let timestamp = format_timestamp(segment.start_time);
+                spans.push(Span::styled(
+                    format!("[{}] ", timestamp),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if let Some(speaker) = &segment.speaker {
+                spans.push(Span::styled(
+                    format!("{}: ", speaker),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            spans.push(Span::styled(segment.text.as_str(), text_style));
+            transcript_lines.push(Line::from(spans));
+        }
+        transcript_lines
+            .extend(markers.map(|(_, silence)| silence_line(&silence, show_timestamps)));
 
         self.content_height = transcript_lines.len();
 
@@ -144,21 +277,26 @@ impl ViewerScreen {
         }
 
         // Help bar
-        let help = Paragraph::new(Line::from(vec![
-            Span::styled(" ↑/↓ ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Scroll  "),
-            Span::styled(
-                " PgUp/PgDn ",
-                Style::default().fg(Color::Black).bg(Color::Cyan),
-            ),
-            Span::raw(" Page  "),
-            Span::styled(" g/G ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Top/Bottom  "),
-            Span::styled(" Esc ", Style::default().fg(Color::Black).bg(Color::Cyan)),
-            Span::raw(" Back"),
-        ]))
-        .alignment(Alignment::Center);
+        let help = hint_bar(&[
+            ("↑/↓", t!("hint-scroll")),
+            ("PgUp/PgDn", t!("hint-page")),
+            ("g/G", t!("hint-top-bottom")),
+            ("n", t!("hint-name-speaker")),
+            ("a", t!("hint-talk-time")),
+            ("t", t!("hint-accept-tags")),
+            ("Esc", t!("hint-back")),
+        ]);
         frame.render_widget(help, chunks[2]);
+
+        if self.show_analytics {
+            if let Some(recording) = &self.recording {
+                let stats = TranscriptStats::compute(recording, &self.segments);
+                draw_analytics(frame, area, &stats);
+            }
+        }
+        if let Some(rename) = &self.rename {
+            draw_rename(frame, area, rename);
+        }
     }
 
     pub fn scroll_up(&mut self) {
@@ -188,6 +326,159 @@ impl ViewerScreen {
     }
 }
 
+/// Centered dialog for naming a speaker
+fn draw_rename(frame: &mut Frame, area: Rect, rename: &SpeakerRename) {
+    let width = area.width.min(50);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + area.height.saturating_sub(6) / 2,
+        width,
+        height: area.height.min(6),
+    };
+    frame.render_widget(Clear, popup);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::raw("Speaker: "),
+            Span::styled(
+                &rename.speakers[rename.selected],
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled("  (Tab: next)", Style::default().fg(Color::DarkGray)),
+        ]),
+        Line::from(format!("Name: {}█", rename.name)),
+        Line::from(Span::styled(
+            "Enter: save  Esc: cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let dialog = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Name speaker ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    frame.render_widget(dialog, popup);
+}
+
+/// Centered panel with each speaker's share of the talk time
+fn draw_analytics(frame: &mut Frame, area: Rect, stats: &TranscriptStats) {
+    const BAR_WIDTH: usize = 20;
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{:<16} {:<w$} {:>5} {:>7} {:>7}",
+            "Speaker",
+            "Share",
+            "Time",
+            "Interr.",
+            "Longest",
+            w = BAR_WIDTH + 5
+        ),
+        dim,
+    ))];
+    if stats.speakers.iter().all(|s| s.speaker.is_none()) {
+        lines.push(Line::from(Span::styled(
+            "No speaker labels in this transcript",
+            dim,
+        )));
+    }
+    for speaker in stats.speakers.iter().filter(|s| s.speaker.is_some()) {
+        let share = stats.talk_share(speaker);
+        let filled = (share * BAR_WIDTH as f64).round() as usize;
+        let name: String = speaker
+            .speaker
+            .as_deref()
+            .unwrap_or_default()
+            .chars()
+            .take(16)
+            .collect();
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<16} ", name), Style::default().fg(Color::Yellow)),
+            Span::styled("█".repeat(filled), Style::default().fg(Color::Cyan)),
+            Span::styled("░".repeat(BAR_WIDTH - filled), dim),
+            Span::raw(format!(
+                " {:>3.0}% {:>5} {:>7} {:>7}",
+                share * 100.0,
+                format_timestamp(speaker.speaking_secs),
+                speaker.interruptions,
+                format_timestamp(speaker.longest_monologue_secs)
+            )),
+        ]));
+    }
+
+    let width = area.width.min(72);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, popup);
+    let panel = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Talk time (a: close) ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    frame.render_widget(panel, popup);
+}
+
+/// Where the summary came from, so stale summaries are easy to spot
+fn summary_line<'a>(recording: &Recording, summary: Option<&Summary>) -> Line<'a> {
+    let has_notes = recording
+        .notes
+        .as_deref()
+        .is_some_and(|notes| !notes.trim().is_empty());
+    let detail = match (has_notes, summary) {
+        (false, _) => "not generated".to_string(),
+        (true, Some(summary)) => format!("{}; {}", summary.provenance(), summary.status()),
+        (true, None) => "provenance unknown".to_string(),
+    };
+
+    Line::from(Span::styled(
+        format!("Summary: {}", detail),
+        Style::default().fg(Color::DarkGray),
+    ))
+}
+
+/// Tags of the recording, followed by suggestions waiting to be accepted
+fn tags_line<'a>(recording: &Recording) -> Line<'a> {
+    let tags = if recording.tags.is_empty() {
+        "none".to_string()
+    } else {
+        recording.tags.join(", ")
+    };
+    let mut spans = vec![Span::styled(
+        format!("Tags: {}", tags),
+        Style::default().fg(Color::DarkGray),
+    )];
+    if !recording.suggested_tags.is_empty() {
+        spans.push(Span::raw(" • "));
+        spans.push(Span::styled(
+            format!("suggested: {}", recording.suggested_tags.join(", ")),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Marker line standing in for a long stretch without speech
+fn silence_line<'a>(silence: &Silence, show_timestamps: bool) -> Line<'a> {
+    let style = Style::default().fg(Color::DarkGray);
+    let mut spans = Vec::new();
+    if show_timestamps {
+        spans.push(Span::styled(
+            format!("[{}] ", format_timestamp(silence.start_time)),
+            style,
+        ));
+    }
+    spans.push(Span::styled(silence_marker_text(silence), style.italic()));
+    Line::from(spans)
+}
+
 fn format_timestamp(secs: f64) -> String {
     let total_secs = secs as u64;
     let hours = total_secs / 3600;
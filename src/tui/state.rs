@@ -0,0 +1,34 @@
+//! TUI state persisted between launches
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Settings;
+
+/// State saved to `tui-state.json` in the state directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TuiState {
+    /// Whether the first-launch setup check has been dismissed
+    #[serde(default)]
+    pub setup_completed: bool,
+}
+
+impl TuiState {
+    /// Load the saved state, starting fresh if it is missing or unreadable
+    pub fn load(settings: &Settings) -> Self {
+        std::fs::read_to_string(settings.tui_state_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the state
+    pub fn save(&self, settings: &Settings) -> Result<()> {
+        let path = settings.tui_state_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -39,6 +39,10 @@ impl HelpPopup {
                     Span::styled("r", Style::default().fg(Color::Yellow)),
                     Span::raw("       Start/stop recording"),
                 ]),
+                Line::from(vec![
+                    Span::styled("m", Style::default().fg(Color::Yellow)),
+                    Span::raw("       Record the next calendar meeting"),
+                ]),
                 Line::from(vec![
                     Span::styled("l", Style::default().fg(Color::Yellow)),
                     Span::raw("       List recordings"),
@@ -47,6 +51,10 @@ impl HelpPopup {
                     Span::styled("Tab", Style::default().fg(Color::Yellow)),
                     Span::raw("     Switch to browser"),
                 ]),
+                Line::from(vec![
+                    Span::styled("o", Style::default().fg(Color::Yellow)),
+                    Span::raw("       Check setup"),
+                ]),
                 Line::from(vec![
                     Span::styled("?", Style::default().fg(Color::Yellow)),
                     Span::raw("       Show this help"),
@@ -117,11 +125,73 @@ impl HelpPopup {
                     Span::styled("G", Style::default().fg(Color::Yellow)),
                     Span::raw("       Go to bottom"),
                 ]),
+                Line::from(vec![
+                    Span::styled("n", Style::default().fg(Color::Yellow)),
+                    Span::raw("       Name a speaker"),
+                ]),
+                Line::from(vec![
+                    Span::styled("a", Style::default().fg(Color::Yellow)),
+                    Span::raw("       Show talk time per speaker"),
+                ]),
                 Line::from(vec![
                     Span::styled("Esc", Style::default().fg(Color::Yellow)),
                     Span::raw("     Go back"),
                 ]),
             ],
+            AppScreen::Setup => vec![
+                Line::from(Span::styled(
+                    "Setup Shortcuts",
+                    Style::default().fg(Color::Cyan).bold(),
+                )),
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled("d", Style::default().fg(Color::Yellow)),
+                    Span::raw("       Download the Whisper model"),
+                ]),
+                Line::from(vec![
+                    Span::styled("s", Style::default().fg(Color::Yellow)),
+                    Span::raw("       Start the daemon"),
+                ]),
+                Line::from(vec![
+                    Span::styled("c", Style::default().fg(Color::Yellow)),
+                    Span::raw("       Open settings"),
+                ]),
+                Line::from(vec![
+                    Span::styled("r", Style::default().fg(Color::Yellow)),
+                    Span::raw("       Check again"),
+                ]),
+                Line::from(vec![
+                    Span::styled("Esc", Style::default().fg(Color::Yellow)),
+                    Span::raw("     Continue to the dashboard"),
+                ]),
+            ],
+            AppScreen::Settings => vec![
+                Line::from(Span::styled(
+                    "Settings Shortcuts",
+                    Style::default().fg(Color::Cyan).bold(),
+                )),
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled("↑/k", Style::default().fg(Color::Yellow)),
+                    Span::raw("     Move up"),
+                ]),
+                Line::from(vec![
+                    Span::styled("↓/j", Style::default().fg(Color::Yellow)),
+                    Span::raw("     Move down"),
+                ]),
+                Line::from(vec![
+                    Span::styled("Enter", Style::default().fg(Color::Yellow)),
+                    Span::raw("   Change value"),
+                ]),
+                Line::from(vec![
+                    Span::styled("w", Style::default().fg(Color::Yellow)),
+                    Span::raw("       Save to the config file"),
+                ]),
+                Line::from(vec![
+                    Span::styled("Esc", Style::default().fg(Color::Yellow)),
+                    Span::raw("     Go back without saving"),
+                ]),
+            ],
         };
 
         let mut full_text = help_text;
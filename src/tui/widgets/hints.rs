@@ -0,0 +1,24 @@
+//! Help bar widget
+
+use ratatui::{prelude::*, widgets::Paragraph};
+
+/// Centered bar of key hints, each a highlighted key followed by its label
+pub fn hint_bar(hints: &[(&str, String)]) -> Paragraph<'static> {
+    let last = hints.len().saturating_sub(1);
+    let spans: Vec<Span<'static>> = hints
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (key, label))| {
+            let separator = if i == last { "" } else { "  " };
+            [
+                Span::styled(
+                    format!(" {} ", key),
+                    Style::default().fg(Color::Black).bg(Color::Cyan),
+                ),
+                Span::raw(format!(" {}{}", label, separator)),
+            ]
+        })
+        .collect();
+
+    Paragraph::new(Line::from(spans)).alignment(Alignment::Center)
+}
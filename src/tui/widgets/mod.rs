@@ -1,5 +1,7 @@
 //! TUI widgets
 
 mod help;
+mod hints;
 
 pub use help::HelpPopup;
+pub use hints::hint_bar;
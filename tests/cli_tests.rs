@@ -1,6 +1,6 @@
 mod common;
 
-use common::run_minutes;
+use common::{run_minutes, TestEnv};
 
 #[test]
 fn minutes_help_shows_usage() {
@@ -190,6 +190,65 @@ fn search_empty_mentions_query_and_next_step() {
     assert!(stdout.contains("minutes list"));
 }
 
+#[test]
+fn export_site_writes_index_for_empty_archive() {
+    let site_dir = tempfile::tempdir().expect("create site dir");
+    let site_path = site_dir.path().join("site");
+    let output = run_minutes(&[
+        "export",
+        "site",
+        "--output-dir",
+        site_path.to_str().expect("utf-8 path"),
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        output.status.success(),
+        "export site should succeed\nstdout:\n{}\nstderr:\n{}",
+        stdout,
+        stderr
+    );
+    assert!(stdout.contains("Exported 0 recording(s)"));
+    assert!(site_path.join("index.html").exists());
+    assert!(site_path.join("search-index.json").exists());
+}
+
+#[test]
+fn export_requires_id_without_site() {
+    let output = run_minutes(&["export"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn config_preset_round_trips_without_secrets() {
+    let source = TestEnv::new();
+    source.write_config("[whisper]\nmodel = \"small\"\n\n[llm]\napi_key = \"secret-key\"\n");
+    let preset_dir = tempfile::tempdir().expect("create preset dir");
+    let preset_path = preset_dir.path().join("team.toml");
+    let preset_arg = preset_path.to_str().expect("utf-8 path");
+
+    let output = source.run(&["config", "export-preset", preset_arg]);
+    assert!(
+        output.status.success(),
+        "export-preset should succeed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let preset = std::fs::read_to_string(&preset_path).expect("read preset");
+    assert!(preset.contains("model = \"small\""));
+    assert!(!preset.contains("secret-key"));
+
+    let target = TestEnv::new();
+    let output = target.run(&["config", "import-preset", preset_arg]);
+    assert!(
+        output.status.success(),
+        "import-preset should succeed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let config = std::fs::read_to_string(target.config_path()).expect("read config");
+    assert!(config.contains("model = \"small\""));
+}
+
 #[test]
 fn verbose_flag_enables_info_logs() {
     let output = run_minutes(&["--verbose", "list"]);
@@ -205,3 +264,50 @@ fn verbose_flag_enables_info_logs() {
         "verbose mode should include info diagnostics in stderr"
     );
 }
+
+#[test]
+fn import_adds_a_pending_recording_to_the_library() {
+    let env = TestEnv::new();
+    let data = tempfile::tempdir().expect("create data dir");
+    env.write_config(&format!(
+        "[general]\ndata_dir = {:?}\n",
+        data.path().to_string_lossy()
+    ));
+
+    let source_dir = tempfile::tempdir().expect("create source dir");
+    let source = source_dir.path().join("old-sync.wav");
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&source, spec).expect("create WAV");
+    for _ in 0..16000 * 3 {
+        writer.write_sample(0i16).expect("write sample");
+    }
+    writer.finalize().expect("finalize WAV");
+
+    let output = env.run(&["import", source.to_str().expect("utf-8 path")]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "import should succeed\nstdout:\n{}\nstderr:\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Imported \"old-sync\""), "{}", stdout);
+    assert!(source.exists(), "the original file is kept");
+
+    let db = minutes::storage::Database::open_path(&data.path().join("minutes.db"))
+        .expect("open database");
+    let recordings = db.list_recordings(10).expect("list recordings");
+    assert_eq!(recordings.len(), 1);
+    assert_eq!(
+        recordings[0].state,
+        minutes::storage::RecordingState::Pending
+    );
+    assert_eq!(recordings[0].duration_secs, Some(3));
+    let audio = recordings[0].audio_path.as_deref().expect("audio path");
+    assert!(std::path::Path::new(audio).starts_with(data.path()));
+}
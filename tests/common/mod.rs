@@ -3,6 +3,7 @@ use std::process::{Command, Output};
 
 use tempfile::TempDir;
 
+#[allow(dead_code)]
 pub fn run_minutes(args: &[&str]) -> Output {
     TestEnv::new().run(args)
 }
@@ -0,0 +1,146 @@
+mod common;
+
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::TestEnv;
+use minutes::storage::{Database, Recording, RecordingState};
+use tempfile::TempDir;
+
+/// Stops the daemon when the test ends, even if it fails
+struct Daemon<'a>(&'a TestEnv);
+
+impl<'a> Daemon<'a> {
+    fn start(env: &'a TestEnv) -> Self {
+        let output = env.run(&["daemon", "start", "--backend", "mock"]);
+        assert!(
+            output.status.success(),
+            "daemon start should succeed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+        Self(env)
+    }
+}
+
+impl Drop for Daemon<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.run(&["daemon", "stop"]);
+    }
+}
+
+fn write_config(env: &TestEnv, data: &Path, models: &Path, engine: &str) {
+    env.write_config(&format!(
+        "[general]\ndata_dir = {:?}\n\n[whisper]\nmodels_dir = {:?}\n\n[transcription]\nengine = {:?}\n",
+        data.to_string_lossy(),
+        models.to_string_lossy(),
+        engine,
+    ));
+}
+
+fn run_ok(env: &TestEnv, args: &[&str]) -> String {
+    let output = env.run(args);
+    assert!(
+        output.status.success(),
+        "{:?} should succeed\nstdout:\n{}\nstderr:\n{}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr),
+    );
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Record for `secs` through the daemon and wait until transcription finished
+///
+/// The deadline only guards against a hung daemon; nothing here should come
+/// close to it.
+fn record(env: &TestEnv, data: &Path, title: &str, secs: u64) -> Recording {
+    run_ok(env, &["start", "--title", title]);
+    thread::sleep(Duration::from_secs(secs));
+    run_ok(env, &["stop"]);
+
+    let db = Database::open_path(&data.join("minutes.db")).expect("open database");
+    let deadline = Instant::now() + Duration::from_secs(120);
+    loop {
+        let recording = db
+            .list_recordings(10)
+            .expect("list recordings")
+            .into_iter()
+            .find(|recording| recording.title == title);
+        if let Some(recording) = recording.filter(|recording| {
+            matches!(
+                recording.state,
+                RecordingState::Completed | RecordingState::Failed
+            )
+        }) {
+            return recording;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "recording '{}' was not processed in time",
+            title
+        );
+        thread::sleep(Duration::from_millis(200));
+    }
+}
+
+#[test]
+fn mock_backend_records_through_the_daemon() {
+    let env = TestEnv::new();
+    let data = TempDir::new().unwrap();
+    let models = TempDir::new().unwrap();
+    write_config(&env, data.path(), models.path(), "whisper");
+
+    let _daemon = Daemon::start(&env);
+    let recording = record(&env, data.path(), "Mock check", 2);
+
+    // No model is installed, so transcription fails and the WAV is kept
+    assert_eq!(recording.state, RecordingState::Failed);
+    let audio = recording.audio_path.expect("recording has audio");
+    let mut reader = hound::WavReader::open(&audio).expect("open recorded WAV");
+    let sample_rate = reader.spec().sample_rate;
+    let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+
+    // Capture starts before `start` returns and keeps up until `stop`, so at
+    // least the time slept is written, and it is the 440 Hz test tone
+    assert!(
+        samples.len() >= 2 * sample_rate as usize,
+        "recorded {} frames at {} Hz",
+        samples.len(),
+        sample_rate
+    );
+    for (i, &sample) in samples.iter().take(sample_rate as usize).enumerate() {
+        let t = i as f32 / sample_rate as f32;
+        let tone = (2.0 * std::f32::consts::PI * 440.0 * t).sin() * 0.3 * i16::MAX as f32;
+        assert!(
+            (f32::from(sample) - tone).abs() <= 2.0,
+            "frame {} is {}, expected {:.0}",
+            i,
+            sample,
+            tone
+        );
+    }
+
+    let listing = run_ok(&env, &["list"]);
+    assert!(listing.contains("Mock check"), "list output:\n{}", listing);
+}
+
+#[test]
+fn mock_backend_recording_is_transcribed_and_searchable() {
+    let env = TestEnv::new();
+    let data = TempDir::new().unwrap();
+    let models = TempDir::new().unwrap();
+    write_config(&env, data.path(), models.path(), "mock");
+
+    let _daemon = Daemon::start(&env);
+    let recording = record(&env, data.path(), "Mock speech", 1);
+    assert_eq!(recording.state, RecordingState::Completed);
+
+    let results = run_ok(&env, &["search", "mock transcript"]);
+    assert!(
+        results.contains("Mock speech"),
+        "search output:\n{}",
+        results
+    );
+}
@@ -176,3 +176,111 @@ fn summarize_reports_gemini_error_body_details() {
         stderr
     );
 }
+
+#[test]
+fn summarize_all_missing_skips_recordings_with_summaries() {
+    let env = TestEnv::new();
+    let data_dir = TempDir::new().expect("create data dir");
+    let (endpoint, server) = spawn_fake_gemini_server(
+        "200 OK",
+        r###"{"candidates":[{"content":{"parts":[{"text":"## Summary\n- Batch summary."}]}}],"usageMetadata":{"promptTokenCount":120,"candidatesTokenCount":30}}"###,
+    );
+
+    let pending_id = setup_summary_env(&env, data_dir.path(), &endpoint, "test-key");
+
+    let mut settings = Settings::default();
+    settings.general.data_dir = data_dir.path().to_path_buf();
+    let db = Database::open(&settings).expect("open test database");
+    let mut summarized = Recording::new("Already summarized".to_string());
+    summarized.notes = Some("Existing summary".to_string());
+    db.insert_recording(&summarized)
+        .expect("insert summarized recording");
+    db.insert_segment(&TranscriptSegment::new(
+        summarized.id.clone(),
+        0.0,
+        2.0,
+        "Nothing new.".to_string(),
+    ))
+    .expect("insert test segment");
+
+    // The fake server answers exactly one request, so a second call would fail.
+    let output = env.run(&["summarize", "--all", "--missing", "--delay", "0"]);
+    server.join().expect("join fake server");
+
+    assert!(
+        output.status.success(),
+        "summarize --all --missing should succeed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[1/1] Summary regression"));
+    assert!(stdout.contains("Tokens: 120 input, 30 output"));
+
+    let pending = db.get_recording(&pending_id).unwrap().unwrap();
+    assert_eq!(
+        pending.notes.as_deref(),
+        Some("## Summary\n- Batch summary.")
+    );
+    let summarized = db.get_recording(&summarized.id).unwrap().unwrap();
+    assert_eq!(summarized.notes.as_deref(), Some("Existing summary"));
+}
+
+#[test]
+fn summarize_requires_id_or_all() {
+    let output = run_minutes(&["summarize"]);
+    assert!(!output.status.success());
+
+    let output = run_minutes(&["summarize", "abc", "--all"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn summarize_rejects_unknown_summary_language() {
+    let output = run_minutes(&["summarize", "abc", "--lang", "klingon"]);
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Unknown summary language 'klingon'"),
+        "expected unknown language error, got:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn summarize_refuses_confidential_recording_for_cloud_provider() {
+    let env = TestEnv::new();
+    let data_dir = TempDir::new().expect("create data dir");
+    // Nothing listens here: the request must be refused before any network call
+    let recording_id = setup_summary_env(
+        &env,
+        data_dir.path(),
+        "http://127.0.0.1:9/v1beta",
+        "test-key",
+    );
+
+    let mark = env.run(&["sensitivity", &recording_id[..8], "confidential"]);
+    assert!(
+        mark.status.success(),
+        "sensitivity should succeed\nstderr:\n{}",
+        String::from_utf8_lossy(&mark.stderr)
+    );
+
+    let output = env.run(&["summarize", &recording_id[..8]]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--include-confidential"),
+        "expected confidential refusal, got:\n{}",
+        stderr
+    );
+
+    let export = env.run(&["export", &recording_id[..8]]);
+    assert!(!export.status.success());
+    assert!(String::from_utf8_lossy(&export.stderr).contains("confidential"));
+
+    let export = env.run(&["export", &recording_id[..8], "--include-confidential"]);
+    assert!(export.status.success());
+}